@@ -62,6 +62,7 @@ pub enum Subcommand {
         rustc_args: Vec<String>,
         fail_fast: bool,
         doc_tests: bool,
+        compare_mode: Option<String>,
     },
     Bench {
         paths: Vec<PathBuf>,
@@ -168,6 +169,12 @@ To learn more about a subcommand, run `./x.py <subcommand> -h`");
                     "ARGS",
                 );
                 opts.optflag("", "doc", "run doc tests");
+                opts.optopt(
+                    "",
+                    "compare-mode",
+                    "re-run the UI tests in the given compare mode (e.g. `nll`)",
+                    "MODE",
+                );
             },
             "bench" => { opts.optmulti("", "test-args", "extra arguments", "ARGS"); },
             "clean" => { opts.optflag("", "all", "clean all build artifacts"); },
@@ -325,6 +332,7 @@ Arguments:
                     rustc_args: matches.opt_strs("rustc-args"),
                     fail_fast: !matches.opt_present("no-fail-fast"),
                     doc_tests: matches.opt_present("doc"),
+                    compare_mode: matches.opt_str("compare-mode"),
                 }
             }
             "bench" => {
@@ -423,6 +431,13 @@ impl Subcommand {
             _ => false,
         }
     }
+
+    pub fn compare_mode(&self) -> Option<&str> {
+        match *self {
+            Subcommand::Test { ref compare_mode, .. } => compare_mode.as_ref().map(|s| &s[..]),
+            _ => None,
+        }
+    }
 }
 
 fn split(s: Vec<String>) -> Vec<String> {
@@ -847,6 +847,11 @@ impl Step for Compiletest {
         cmd.arg("--build-base").arg(testdir(build, compiler.host).join(suite));
         cmd.arg("--stage-id").arg(format!("stage{}-{}", compiler.stage, target));
         cmd.arg("--mode").arg(mode);
+        if mode == "ui" {
+            if let Some(compare_mode) = build.config.cmd.compare_mode() {
+                cmd.arg("--compare-mode").arg(compare_mode);
+            }
+        }
         cmd.arg("--target").arg(target);
         cmd.arg("--host").arg(&*compiler.host);
         cmd.arg("--llvm-filecheck").arg(build.llvm_filecheck(build.build));
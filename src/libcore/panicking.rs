@@ -59,6 +59,15 @@ fn panic_bounds_check(file_line_col: &(&'static str, u32, u32),
                            len, index), file_line_col)
 }
 
+#[cold] #[inline(never)]
+#[cfg_attr(not(stage0), lang = "panic_misaligned_pointer_dereference")]
+fn panic_misaligned_pointer_dereference(file_line_col: &(&'static str, u32, u32),
+                                        required: usize, found: usize) -> ! {
+    panic_fmt(format_args!("misaligned pointer dereference: address must be a multiple of {:#x} \
+                            but is {:#x}",
+                           required, found), file_line_col)
+}
+
 #[cold] #[inline(never)]
 pub fn panic_fmt(fmt: fmt::Arguments, file_line_col: &(&'static str, u32, u32)) -> ! {
     #[allow(improper_ctypes)]
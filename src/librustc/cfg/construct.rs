@@ -167,6 +167,12 @@ impl<'a, 'tcx> CFGBuilder<'a, 'tcx> {
                 let post_exit = self.pats_all(post.iter(), vec_exit);
                 self.add_ast_node(pat.hir_id.local_id, &[post_exit])
             }
+
+            PatKind::Or(ref pats) => {
+                // Only one of the alternatives has to match.
+                let exits: Vec<_> = pats.iter().map(|pat| self.pat(&pat, pred)).collect();
+                self.add_ast_node(pat.hir_id.local_id, &exits)
+            }
         }
     }
 
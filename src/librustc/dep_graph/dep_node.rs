@@ -476,6 +476,7 @@ define_dep_nodes!( <'tcx>
     [] MirConst(DefId),
     [] MirValidated(DefId),
     [] MirOptimized(DefId),
+    [] CoverageInfo(DefId),
     [] MirShim { instance_def: InstanceDef<'tcx> },
 
     [] BorrowCheckKrate,
@@ -574,6 +575,7 @@ define_dep_nodes!( <'tcx>
     [] IsProfilerRuntime(CrateNum),
     [] GetPanicStrategy(CrateNum),
     [] IsNoBuiltins(CrateNum),
+    [] IsMirOnlyRlib(CrateNum),
     [] ImplDefaultness(DefId),
     [] ReachableNonGenerics(CrateNum),
     [] NativeLibraries(CrateNum),
@@ -594,7 +596,7 @@ define_dep_nodes!( <'tcx>
     [] ResolveLifetimes(CrateNum),
     [] NamedRegion(DefIndex),
     [] IsLateBound(DefIndex),
-    [] ObjectLifetimeDefaults(DefIndex),
+    [] ObjectLifetimeDefaults(DefId),
 
     [] Visibility(DefId),
     [input] DepKind(CrateNum),
@@ -632,6 +634,9 @@ define_dep_nodes!( <'tcx>
     [] CompileCodegenUnit(InternedString),
     [input] OutputFilenames,
     [anon] NormalizeTy,
+    [anon] NormalizeProjectionTy,
+    [anon] DropckOutlives,
+    [anon] ImpliedOutlivesBounds,
 
     [] SubstituteNormalizeAndTestPredicates { key: (DefId, &'tcx Substs<'tcx>) },
 
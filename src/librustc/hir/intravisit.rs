@@ -682,6 +682,9 @@ pub fn walk_pat<'v, V: Visitor<'v>>(visitor: &mut V, pattern: &'v Pat) {
             walk_list!(visitor, visit_pat, slice_pattern);
             walk_list!(visitor, visit_pat, postpatterns);
         }
+        PatKind::Or(ref alternatives) => {
+            walk_list!(visitor, visit_pat, alternatives);
+        }
     }
 }
 
@@ -728,6 +731,11 @@ pub fn walk_generic_param<'v, V: Visitor<'v>>(visitor: &mut V, param: &'v Generi
             walk_list!(visitor, visit_ty_param_bound, &ty_param.bounds);
             walk_list!(visitor, visit_ty, &ty_param.default);
         }
+        GenericParam::Const(ref const_param) => {
+            visitor.visit_id(const_param.id);
+            visitor.visit_name(const_param.span, const_param.name);
+            visitor.visit_ty(&const_param.ty);
+        }
     }
 }
 
@@ -172,6 +172,11 @@ enum ImplTraitContext {
     /// equivalent to a fresh existential parameter like `abstract type T; fn foo() -> T`.
     Existential,
 
+    /// Treat `impl Trait` in the type of a `let` binding, `const` or `static`
+    /// as an existential type whose concrete type is inferred from the initializer.
+    /// Example: `let x: impl Debug = 5;`.
+    Binding,
+
     /// `impl Trait` is not accepted in this position.
     Disallowed,
 }
@@ -964,12 +969,15 @@ impl<'a> LoweringContext<'a> {
                 use syntax::feature_gate::{emit_feature_err, GateIssue};
                 let span = t.span;
                 match itctx {
-                    ImplTraitContext::Existential => {
-                        let has_feature = self.sess.features_untracked().conservative_impl_trait;
-                        if !t.span.allows_unstable() && !has_feature {
-                            emit_feature_err(&self.sess.parse_sess, "conservative_impl_trait",
-                                             t.span, GateIssue::Language,
-                                             "`impl Trait` in return position is experimental");
+                    ImplTraitContext::Existential | ImplTraitContext::Binding => {
+                        if let ImplTraitContext::Existential = itctx {
+                            let has_feature =
+                                self.sess.features_untracked().conservative_impl_trait;
+                            if !t.span.allows_unstable() && !has_feature {
+                                emit_feature_err(&self.sess.parse_sess, "conservative_impl_trait",
+                                                 t.span, GateIssue::Language,
+                                                 "`impl Trait` in return position is experimental");
+                            }
                         }
                         let def_index = self.resolver.definitions().opt_def_index(t.id).unwrap();
                         let hir_bounds = self.lower_bounds(bounds, itctx);
@@ -989,13 +997,6 @@ impl<'a> LoweringContext<'a> {
                         }, lifetimes)
                     },
                     ImplTraitContext::Universal(def_id) => {
-                        let has_feature = self.sess.features_untracked().universal_impl_trait;
-                        if !t.span.allows_unstable() && !has_feature {
-                            emit_feature_err(&self.sess.parse_sess, "universal_impl_trait",
-                                             t.span, GateIssue::Language,
-                                             "`impl Trait` in argument position is experimental");
-                        }
-
                         let def_node_id = self.next_id().node_id;
 
                         // Add a definition for the in-band TyParam
@@ -1469,12 +1470,23 @@ impl<'a> LoweringContext<'a> {
         }, false)
     }
 
+    /// `impl Trait` is only accepted in the types of bindings with
+    /// `#![feature(impl_trait_in_bindings)]`.
+    fn binding_impl_trait_context(&self) -> ImplTraitContext {
+        if self.sess.features_untracked().impl_trait_in_bindings {
+            ImplTraitContext::Binding
+        } else {
+            ImplTraitContext::Disallowed
+        }
+    }
+
     fn lower_local(&mut self, l: &Local) -> P<hir::Local> {
         let LoweredNodeId { node_id, hir_id } = self.lower_node_id(l.id);
+        let itctx = self.binding_impl_trait_context();
         P(hir::Local {
             id: node_id,
             hir_id,
-            ty: l.ty.as_ref().map(|t| self.lower_ty(t, ImplTraitContext::Disallowed)),
+            ty: l.ty.as_ref().map(|t| self.lower_ty(t, itctx)),
             pat: self.lower_pat(&l.pat),
             init: l.init.as_ref().map(|e| P(self.lower_expr(e))),
             span: l.span,
@@ -1595,6 +1607,15 @@ impl<'a> LoweringContext<'a> {
         }
     }
 
+    fn lower_const_param(&mut self, cp: &ConstParam) -> hir::ConstParam {
+        hir::ConstParam {
+            id: self.lower_node_id(cp.id).node_id,
+            name: self.lower_ident(cp.ident),
+            ty: self.lower_ty(&cp.ty, ImplTraitContext::Disallowed),
+            span: cp.span,
+        }
+    }
+
     fn lower_lifetime(&mut self, l: &Lifetime) -> hir::Lifetime {
         let name = match self.lower_ident(l.ident) {
             x if x == "'_" => hir::LifetimeName::Underscore,
@@ -1656,6 +1677,9 @@ impl<'a> LoweringContext<'a> {
                         add_bounds.get(&ty_param.id).map_or(&[][..], |x| &x)
                     ))
                 }
+                GenericParam::Const(ref const_param) => {
+                    hir::GenericParam::Const(self.lower_const_param(const_param))
+                }
             })
             .collect()
     }
@@ -1916,13 +1940,15 @@ impl<'a> LoweringContext<'a> {
             }
             ItemKind::Static(ref t, m, ref e) => {
                 let value = self.lower_body(None, |this| this.lower_expr(e));
-                hir::ItemStatic(self.lower_ty(t, ImplTraitContext::Disallowed),
+                let itctx = self.binding_impl_trait_context();
+                hir::ItemStatic(self.lower_ty(t, itctx),
                                 self.lower_mutability(m),
                                 value)
             }
             ItemKind::Const(ref t, ref e) => {
                 let value = self.lower_body(None, |this| this.lower_expr(e));
-                hir::ItemConst(self.lower_ty(t, ImplTraitContext::Disallowed), value)
+                let itctx = self.binding_impl_trait_context();
+                hir::ItemConst(self.lower_ty(t, itctx), value)
             }
             ItemKind::Fn(ref decl, unsafety, constness, abi, ref generics, ref body) => {
                 let fn_def_id = self.resolver.definitions().opt_local_def_id(id);
@@ -2549,6 +2575,9 @@ impl<'a> LoweringContext<'a> {
                             after.iter().map(|x| self.lower_pat(x)).collect())
             }
             PatKind::Paren(ref inner) => return self.lower_pat(inner),
+            PatKind::Or(ref pats) => {
+                hir::PatKind::Or(pats.iter().map(|x| self.lower_pat(x)).collect())
+            }
             PatKind::Mac(_) => panic!("Shouldn't exist here"),
         };
 
@@ -3718,7 +3747,7 @@ impl<'a> LoweringContext<'a> {
     fn maybe_lint_bare_trait(&self, span: Span, id: NodeId, is_global: bool) {
         if self.sess.features_untracked().dyn_trait {
             self.sess.buffer_lint_with_diagnostic(
-                builtin::BARE_TRAIT_OBJECT, id, span,
+                builtin::BARE_TRAIT_OBJECTS, id, span,
                 "trait objects without an explicit `dyn` are deprecated",
                 builtin::BuiltinLintDiagnostics::BareTraitObject(span, is_global)
             )
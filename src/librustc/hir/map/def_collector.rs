@@ -209,6 +209,14 @@ impl<'a> visit::Visitor<'a> for DefCollector<'a> {
                     ty_param.span
                 );
             }
+            GenericParam::Const(ref const_param) => {
+                self.create_def(
+                    const_param.id,
+                    DefPathData::ConstParam(const_param.ident.name.as_str()),
+                    REGULAR_SPACE,
+                    const_param.span
+                );
+            }
         }
 
         visit::walk_generic_param(self, param);
@@ -216,6 +216,7 @@ impl DefKey {
             DefPathData::Module(name) |
             DefPathData::MacroDef(name) |
             DefPathData::TypeParam(name) |
+            DefPathData::ConstParam(name) |
             DefPathData::LifetimeDef(name) |
             DefPathData::EnumVariant(name) |
             DefPathData::Field(name) |
@@ -372,6 +373,8 @@ pub enum DefPathData {
     // Subportions of items
     /// A type parameter (generic parameter)
     TypeParam(InternedString),
+    /// A const generic parameter
+    ConstParam(InternedString),
     /// A lifetime definition
     LifetimeDef(InternedString),
     /// A variant of a enum
@@ -643,6 +646,7 @@ impl DefPathData {
             Module(name) |
             MacroDef(name) |
             TypeParam(name) |
+            ConstParam(name) |
             LifetimeDef(name) |
             EnumVariant(name) |
             Field(name) |
@@ -667,6 +671,7 @@ impl DefPathData {
             Module(name) |
             MacroDef(name) |
             TypeParam(name) |
+            ConstParam(name) |
             LifetimeDef(name) |
             EnumVariant(name) |
             Field(name) |
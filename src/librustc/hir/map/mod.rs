@@ -33,7 +33,7 @@ use hir::svh::Svh;
 use util::nodemap::{DefIdMap, FxHashMap};
 
 use arena::TypedArena;
-use std::cell::RefCell;
+use rustc_data_structures::sync::RwLock;
 use std::io;
 use ty::TyCtxt;
 
@@ -70,6 +70,7 @@ pub enum Node<'hir> {
 
     NodeLifetime(&'hir Lifetime),
     NodeTyParam(&'hir TyParam),
+    NodeConstParam(&'hir ConstParam),
     NodeVisibility(&'hir Visibility),
 }
 
@@ -97,6 +98,7 @@ enum MapEntry<'hir> {
     EntryStructCtor(NodeId, DepNodeIndex, &'hir VariantData),
     EntryLifetime(NodeId, DepNodeIndex, &'hir Lifetime),
     EntryTyParam(NodeId, DepNodeIndex, &'hir TyParam),
+    EntryConstParam(NodeId, DepNodeIndex, &'hir ConstParam),
     EntryVisibility(NodeId, DepNodeIndex, &'hir Visibility),
     EntryLocal(NodeId, DepNodeIndex, &'hir Local),
 
@@ -132,6 +134,7 @@ impl<'hir> MapEntry<'hir> {
             EntryStructCtor(id, _, _) => id,
             EntryLifetime(id, _, _) => id,
             EntryTyParam(id, _, _) => id,
+            EntryConstParam(id, _, _) => id,
             EntryVisibility(id, _, _) => id,
             EntryLocal(id, _, _) => id,
 
@@ -159,6 +162,7 @@ impl<'hir> MapEntry<'hir> {
             EntryStructCtor(_, _, n) => NodeStructCtor(n),
             EntryLifetime(_, _, n) => NodeLifetime(n),
             EntryTyParam(_, _, n) => NodeTyParam(n),
+            EntryConstParam(_, _, n) => NodeConstParam(n),
             EntryVisibility(_, _, n) => NodeVisibility(n),
             EntryLocal(_, _, n) => NodeLocal(n),
             EntryMacroDef(_, n) => NodeMacroDef(n),
@@ -264,7 +268,7 @@ pub struct Map<'hir> {
     definitions: &'hir Definitions,
 
     /// Bodies inlined from other crates are cached here.
-    inlined_bodies: RefCell<DefIdMap<&'hir Body>>,
+    inlined_bodies: RwLock<DefIdMap<&'hir Body>>,
 
     /// The reverse mapping of `node_to_hir_id`.
     hir_to_node_id: FxHashMap<HirId, NodeId>,
@@ -296,6 +300,7 @@ impl<'hir> Map<'hir> {
             EntryStructCtor(_, dep_node_index, _) |
             EntryLifetime(_, dep_node_index, _) |
             EntryTyParam(_, dep_node_index, _) |
+            EntryConstParam(_, dep_node_index, _) |
             EntryVisibility(_, dep_node_index, _) |
             EntryExpr(_, dep_node_index, _) |
             EntryLocal(_, dep_node_index, _) |
@@ -450,6 +455,7 @@ impl<'hir> Map<'hir> {
             NodeStructCtor(_) |
             NodeLifetime(_) |
             NodeVisibility(_) |
+            NodeConstParam(_) |
             NodeBlock(_) => None,
             NodeLocal(local) => {
                 Some(Def::Local(local.id))
@@ -943,6 +949,7 @@ impl<'hir> Map<'hir> {
             NodeField(f) => f.name,
             NodeLifetime(lt) => lt.name.name(),
             NodeTyParam(tp) => tp.name,
+            NodeConstParam(cp) => cp.name,
             NodeBinding(&Pat { node: PatKind::Binding(_,_,l,_), .. }) => l.node,
             NodeStructCtor(_) => self.name(self.get_parent(id)),
             _ => bug!("no name for {}", self.node_to_string(id))
@@ -1008,6 +1015,7 @@ impl<'hir> Map<'hir> {
             Some(EntryStructCtor(_, _, _)) => self.expect_item(self.get_parent(id)).span,
             Some(EntryLifetime(_, _, lifetime)) => lifetime.span,
             Some(EntryTyParam(_, _, ty_param)) => ty_param.span,
+            Some(EntryConstParam(_, _, const_param)) => const_param.span,
             Some(EntryVisibility(_, _, &Visibility::Restricted { ref path, .. })) => path.span,
             Some(EntryVisibility(_, _, v)) => bug!("unexpected Visibility {:?}", v),
             Some(EntryLocal(_, _, local)) => local.span,
@@ -1188,7 +1196,7 @@ pub fn map_crate<'hir>(sess: &::session::Session,
         map,
         hir_to_node_id,
         definitions,
-        inlined_bodies: RefCell::new(DefIdMap()),
+        inlined_bodies: RwLock::new(DefIdMap()),
     };
 
     hir_id_validator::check_crate(&map);
@@ -1236,6 +1244,7 @@ impl<'a> print::State<'a> {
             NodeLifetime(a)    => self.print_lifetime(&a),
             NodeVisibility(a)  => self.print_visibility(&a),
             NodeTyParam(_)     => bug!("cannot print TyParam"),
+            NodeConstParam(_)  => bug!("cannot print ConstParam"),
             NodeField(_)       => bug!("cannot print StructField"),
             // these cases do not carry enough information in the
             // hir_map to reconstruct their full structure for pretty
@@ -1356,6 +1365,9 @@ fn node_id_to_string(map: &Map, id: NodeId, include_id: bool) -> String {
         Some(NodeTyParam(ref ty_param)) => {
             format!("typaram {:?}{}", ty_param, id_str)
         }
+        Some(NodeConstParam(ref const_param)) => {
+            format!("const param {:?}{}", const_param, id_str)
+        }
         Some(NodeVisibility(ref vis)) => {
             format!("visibility {:?}{}", vis, id_str)
         }
@@ -416,10 +416,20 @@ pub struct TyParam {
     pub synthetic: Option<SyntheticTyParamKind>,
 }
 
+/// A const generic parameter, e.g. `const N: usize`.
+#[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
+pub struct ConstParam {
+    pub name: Name,
+    pub id: NodeId,
+    pub ty: P<Ty>,
+    pub span: Span,
+}
+
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub enum GenericParam {
     Lifetime(LifetimeDef),
     Type(TyParam),
+    Const(ConstParam),
 }
 
 impl GenericParam {
@@ -436,6 +446,13 @@ impl GenericParam {
             _ => false,
         }
     }
+
+    pub fn is_const_param(&self) -> bool {
+        match *self {
+            GenericParam::Const(_) => true,
+            _ => false,
+        }
+    }
 }
 
 pub trait GenericParamsExt {
@@ -448,6 +465,11 @@ pub trait GenericParamsExt {
         slice::Iter<GenericParam>,
         fn(&GenericParam) -> Option<&TyParam>,
     >;
+
+    fn const_params<'a>(&'a self) -> iter::FilterMap<
+        slice::Iter<GenericParam>,
+        fn(&GenericParam) -> Option<&ConstParam>,
+    >;
 }
 
 impl GenericParamsExt for [GenericParam] {
@@ -470,9 +492,19 @@ impl GenericParamsExt for [GenericParam] {
             _ => None,
         })
     }
+
+    fn const_params<'a>(&'a self) -> iter::FilterMap<
+        slice::Iter<GenericParam>,
+        fn(&GenericParam) -> Option<&ConstParam>,
+    > {
+        self.iter().filter_map(|param| match *param {
+            GenericParam::Const(ref c) => Some(c),
+            _ => None,
+        })
+    }
 }
 
-/// Represents lifetimes and type parameters attached to a declaration
+/// Represents lifetime, type and const parameters attached to a declaration
 /// of a function, enum, trait, etc.
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub struct Generics {
@@ -508,6 +540,10 @@ impl Generics {
     pub fn ty_params<'a>(&'a self) -> impl Iterator<Item = &'a TyParam> {
         self.params.ty_params()
     }
+
+    pub fn const_params<'a>(&'a self) -> impl Iterator<Item = &'a ConstParam> {
+        self.params.const_params()
+    }
 }
 
 pub enum UnsafeGeneric {
@@ -538,6 +574,7 @@ impl Generics {
                         return Some(UnsafeGeneric::Type(t.clone(), "may_dangle"));
                     }
                 }
+                GenericParam::Const(_) => {}
             }
         }
 
@@ -755,6 +792,7 @@ impl Pat {
                 slice.iter().all(|p| p.walk_(it)) &&
                 after.iter().all(|p| p.walk_(it))
             }
+            PatKind::Or(ref pats) => pats.iter().all(|p| p.walk_(it)),
             PatKind::Wild |
             PatKind::Lit(_) |
             PatKind::Range(..) |
@@ -854,6 +892,10 @@ pub enum PatKind {
     /// `[a, b, ..i, y, z]` is represented as:
     ///     `PatKind::Slice(box [a, b], Some(i), box [y, z])`
     Slice(HirVec<P<Pat>>, Option<P<Pat>>, HirVec<P<Pat>>),
+    /// An or-pattern nested inside another pattern, e.g. `Some(0 | 1)`.
+    /// The alternatives bind the same variables, and the bindings of the
+    /// first one have the canonical IDs.
+    Or(HirVec<P<Pat>>),
 }
 
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug, Copy)]
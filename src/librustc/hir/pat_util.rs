@@ -97,6 +97,40 @@ impl hir::Pat {
         });
     }
 
+    /// Call `f` on every "binding" in a pattern, like `each_binding`, but
+    /// only look at the first alternative of nested or-patterns: the other
+    /// alternatives bind the same variables, under non-canonical IDs.
+    pub fn each_binding_or_first<F>(&self, f: &mut F)
+        where F: FnMut(hir::BindingAnnotation, ast::NodeId, Span, &Spanned<ast::Name>),
+    {
+        match self.node {
+            PatKind::Binding(binding_mode, _, ref pth, ref sub) => {
+                f(binding_mode, self.id, self.span, pth);
+                if let Some(ref sub) = *sub {
+                    sub.each_binding_or_first(f);
+                }
+            }
+            PatKind::Or(ref pats) => pats[0].each_binding_or_first(f),
+            PatKind::Struct(_, ref fields, _) => {
+                for field in fields.iter() {
+                    field.node.pat.each_binding_or_first(f);
+                }
+            }
+            PatKind::TupleStruct(_, ref pats, _) | PatKind::Tuple(ref pats, _) => {
+                for pat in pats.iter() {
+                    pat.each_binding_or_first(f);
+                }
+            }
+            PatKind::Box(ref pat) | PatKind::Ref(ref pat, _) => pat.each_binding_or_first(f),
+            PatKind::Slice(ref before, ref slice, ref after) => {
+                for pat in before.iter().chain(slice).chain(after.iter()) {
+                    pat.each_binding_or_first(f);
+                }
+            }
+            PatKind::Wild | PatKind::Lit(_) | PatKind::Range(..) | PatKind::Path(_) => {}
+        }
+    }
+
     /// Checks if the pattern contains any patterns that bind something to
     /// an ident, e.g. `foo`, or `Foo(foo)` or `foo @ Bar(..)`.
     pub fn contains_bindings(&self) -> bool {
@@ -1848,6 +1848,18 @@ impl<'a> State<'a> {
                 self.commasep(Inconsistent, &after[..], |s, p| s.print_pat(&p))?;
                 self.s.word("]")?;
             }
+            PatKind::Or(ref pats) => {
+                let mut first = true;
+                for p in pats.iter() {
+                    if first {
+                        first = false;
+                    } else {
+                        self.s.space()?;
+                        self.word_space("|")?;
+                    }
+                    self.print_pat(&p)?;
+                }
+            }
         }
         self.ann.post(self, NodePat(pat))
     }
@@ -2038,6 +2050,7 @@ impl<'a> State<'a> {
                 match *param {
                     hir::GenericParam::Lifetime(ref ld) => s.print_lifetime_def(ld),
                     hir::GenericParam::Type(ref tp) => s.print_ty_param(tp),
+                    hir::GenericParam::Const(ref cp) => s.print_const_param(cp),
                 }
             })?;
 
@@ -2059,6 +2072,13 @@ impl<'a> State<'a> {
         }
     }
 
+    pub fn print_const_param(&mut self, param: &hir::ConstParam) -> io::Result<()> {
+        self.word_space("const")?;
+        self.print_name(param.name)?;
+        self.word_space(":")?;
+        self.print_type(&param.ty)
+    }
+
     pub fn print_where_clause(&mut self, where_clause: &hir::WhereClause) -> io::Result<()> {
         if where_clause.predicates.is_empty() {
             return Ok(());
@@ -206,9 +206,17 @@ impl_stable_hash_for!(struct hir::TyParam {
     synthetic
 });
 
+impl_stable_hash_for!(struct hir::ConstParam {
+    name,
+    id,
+    ty,
+    span
+});
+
 impl_stable_hash_for!(enum hir::GenericParam {
     Lifetime(lifetime_def),
-    Type(ty_param)
+    Type(ty_param),
+    Const(const_param)
 });
 
 impl_stable_hash_for!(struct hir::Generics {
@@ -447,7 +455,8 @@ impl_stable_hash_for!(enum hir::PatKind {
     Ref(sub, mutability),
     Lit(expr),
     Range(start, end, end_kind),
-    Slice(one, two, three)
+    Slice(one, two, three),
+    Or(pats)
 });
 
 impl_stable_hash_for!(enum hir::BinOp_ {
@@ -17,7 +17,7 @@ use rustc_data_structures::stable_hasher::{HashStable, StableHasher,
                                            StableHasherResult};
 use std::mem;
 
-impl_stable_hash_for!(struct mir::GeneratorLayout<'tcx> { fields });
+impl_stable_hash_for!(struct mir::GeneratorLayout<'tcx> { fields, shared_tys });
 impl_stable_hash_for!(struct mir::SourceInfo { span, scope });
 impl_stable_hash_for!(enum mir::Mutability { Mut, Not });
 impl_stable_hash_for!(enum mir::LocalKind { Var, Temp, Arg, ReturnPointer });
@@ -32,8 +32,31 @@ impl_stable_hash_for!(struct mir::LocalDecl<'tcx> {
 });
 impl_stable_hash_for!(struct mir::UpvarDecl { debug_name, by_ref, mutability });
 impl_stable_hash_for!(struct mir::BasicBlockData<'tcx> { statements, terminator, is_cleanup });
-impl_stable_hash_for!(struct mir::UnsafetyViolation { source_info, description, kind });
-impl_stable_hash_for!(struct mir::UnsafetyCheckResult { violations, unsafe_blocks });
+impl_stable_hash_for!(struct mir::UnsafetyViolation { source_info, details, kind });
+impl_stable_hash_for!(enum mir::UnsafetyViolationDetails {
+    CallToUnsafeFunction,
+    UseOfInlineAssembly,
+    ImmovableGeneratorConstruction,
+    DerefOfRawPointer,
+    AccessToUnionField,
+    AssignToNonCopyUnionField,
+    UseOfMutableStatic,
+    UseOfExternStatic,
+    BorrowOfPackedField
+});
+impl_stable_hash_for!(struct mir::UnsafetyCheckResult {
+    violations,
+    unsafe_blocks,
+    used_operations
+});
+impl_stable_hash_for!(struct mir::CoverageInfo { function_hash, regions });
+impl_stable_hash_for!(struct mir::CodeRegion {
+    file_name,
+    start_line,
+    start_col,
+    end_line,
+    end_col
+});
 
 impl<'a> HashStable<StableHashingContext<'a>>
 for mir::BorrowKind {
@@ -244,6 +267,11 @@ for mir::AssertMessage<'gcx> {
             }
             mir::AssertMessage::GeneratorResumedAfterReturn => (),
             mir::AssertMessage::GeneratorResumedAfterPanic => (),
+            mir::AssertMessage::MisalignedPointerDereference { ref required, ref found } => {
+                required.hash_stable(hcx, hasher);
+                found.hash_stable(hcx, hasher);
+            }
+            mir::AssertMessage::NullPointerDereference => (),
         }
     }
 }
@@ -277,6 +305,9 @@ for mir::StatementKind<'gcx> {
                 op.hash_stable(hcx, hasher);
                 places.hash_stable(hcx, hasher);
             }
+            mir::StatementKind::Coverage { index } => {
+                index.hash_stable(hcx, hasher);
+            }
             mir::StatementKind::Nop => {}
             mir::StatementKind::InlineAsm { ref asm, ref outputs, ref inputs } => {
                 asm.hash_stable(hcx, hasher);
@@ -559,6 +590,12 @@ impl<'a, 'gcx> HashStable<StableHashingContext<'a>> for mir::Literal<'gcx> {
 
 impl_stable_hash_for!(struct mir::Location { block, statement_index });
 
+impl_stable_hash_for!(struct mir::BorrowCheckResult<'tcx> {
+    closure_requirements,
+    used_mut_locals,
+    used_mut_upvars
+});
+
 impl_stable_hash_for!(struct mir::ClosureRegionRequirements<'tcx> {
     num_external_vids,
     outlives_requirements
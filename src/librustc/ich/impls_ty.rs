@@ -18,6 +18,7 @@ use rustc_data_structures::stable_hasher::{HashStable, ToStableHashKey,
 use std::cell::RefCell;
 use std::hash as std_hash;
 use std::mem;
+use infer;
 use middle::region;
 use traits;
 use ty;
@@ -69,6 +70,7 @@ for ty::subst::UnpackedKind<'gcx> {
         match self {
             ty::subst::UnpackedKind::Lifetime(lt) => lt.hash_stable(hcx, hasher),
             ty::subst::UnpackedKind::Type(ty) => ty.hash_stable(hcx, hasher),
+            ty::subst::UnpackedKind::Const(ct) => ct.hash_stable(hcx, hasher),
         }
     }
 }
@@ -111,6 +113,9 @@ for ty::RegionKind {
             ty::ReClosureBound(vid) => {
                 vid.hash_stable(hcx, hasher);
             }
+            ty::ReCanonical(cvar) => {
+                cvar.hash_stable(hcx, hasher);
+            }
             ty::ReLateBound(..) |
             ty::ReVar(..) |
             ty::ReSkolemized(..) => {
@@ -130,6 +135,16 @@ impl<'a> HashStable<StableHashingContext<'a>> for ty::RegionVid {
     }
 }
 
+impl<'a> HashStable<StableHashingContext<'a>> for ty::CanonicalVar {
+    #[inline]
+    fn hash_stable<W: StableHasherResult>(&self,
+                                          hcx: &mut StableHashingContext<'a>,
+                                          hasher: &mut StableHasher<W>) {
+        use rustc_data_structures::indexed_vec::Idx;
+        self.index().hash_stable(hcx, hasher);
+    }
+}
+
 impl<'a, 'gcx> HashStable<StableHashingContext<'a>>
 for ty::adjustment::AutoBorrow<'gcx> {
     fn hash_stable<W: StableHasherResult>(&self,
@@ -264,6 +279,25 @@ for ty::OutlivesPredicate<A, B>
 impl_stable_hash_for!(struct ty::ProjectionPredicate<'tcx> { projection_ty, ty });
 impl_stable_hash_for!(struct ty::ProjectionTy<'tcx> { substs, item_def_id });
 
+impl<'a, 'tcx> HashStable<StableHashingContext<'a>>
+for ::infer::region_constraints::GenericKind<'tcx> {
+    fn hash_stable<W: StableHasherResult>(&self,
+                                          hcx: &mut StableHashingContext<'a>,
+                                          hasher: &mut StableHasher<W>) {
+        use infer::region_constraints::GenericKind;
+
+        mem::discriminant(self).hash_stable(hcx, hasher);
+        match *self {
+            GenericKind::Param(ref param_ty) => {
+                param_ty.hash_stable(hcx, hasher);
+            }
+            GenericKind::Projection(ref projection_ty) => {
+                projection_ty.hash_stable(hcx, hasher);
+            }
+        }
+    }
+}
+
 
 impl<'a, 'gcx> HashStable<StableHashingContext<'a>> for ty::Predicate<'gcx> {
     fn hash_stable<W: StableHasherResult>(&self,
@@ -349,6 +383,9 @@ for ::middle::const_val::ConstVal<'gcx> {
             Value(ref value) => {
                 value.hash_stable(hcx, hasher);
             }
+            Param(param) => {
+                param.hash_stable(hcx, hasher);
+            }
         }
     }
 }
@@ -670,8 +707,10 @@ impl<'a> HashStable<StableHashingContext<'a>> for ty::Generics {
             parent,
             parent_regions,
             parent_types,
+            parent_consts,
             ref regions,
             ref types,
+            ref consts,
 
             // Reverse map to each `TypeParameterDef`'s `index` field, from
             // `def_id.index` (`def_id.krate` is the same as the item's).
@@ -683,8 +722,10 @@ impl<'a> HashStable<StableHashingContext<'a>> for ty::Generics {
         parent.hash_stable(hcx, hasher);
         parent_regions.hash_stable(hcx, hasher);
         parent_types.hash_stable(hcx, hasher);
+        parent_consts.hash_stable(hcx, hasher);
         regions.hash_stable(hcx, hasher);
         types.hash_stable(hcx, hasher);
+        consts.hash_stable(hcx, hasher);
         has_self.hash_stable(hcx, hasher);
         has_late_bound_regions.hash_stable(hcx, hasher);
     }
@@ -709,6 +750,12 @@ for ty::RegionParameterDef {
     }
 }
 
+impl_stable_hash_for!(struct ty::ConstParameterDef {
+    name,
+    def_id,
+    index
+});
+
 impl_stable_hash_for!(struct ty::TypeParameterDef {
     name,
     def_id,
@@ -889,6 +936,9 @@ for ty::TypeVariants<'gcx>
             TyForeign(def_id) => {
                 def_id.hash_stable(hcx, hasher);
             }
+            TyInfer(ty::CanonicalTy(cvar)) => {
+                cvar.hash_stable(hcx, hasher);
+            }
             TyInfer(..) => {
                 bug!("ty::TypeVariants::hash_stable() - Unexpected variant {:?}.", *self)
             }
@@ -901,6 +951,11 @@ impl_stable_hash_for!(struct ty::ParamTy {
     name
 });
 
+impl_stable_hash_for!(struct ty::ParamConst {
+    idx,
+    name
+});
+
 impl_stable_hash_for!(struct ty::TypeAndMut<'tcx> {
     ty,
     mutbl
@@ -1237,3 +1292,102 @@ for ty::UniverseIndex {
         self.depth().hash_stable(hcx, hasher);
     }
 }
+
+impl<'a, 'gcx, V> HashStable<StableHashingContext<'a>>
+for infer::canonical::Canonical<'gcx, V> where V: HashStable<StableHashingContext<'a>> {
+    fn hash_stable<W: StableHasherResult>(&self,
+                                          hcx: &mut StableHashingContext<'a>,
+                                          hasher: &mut StableHasher<W>) {
+        let infer::canonical::Canonical {
+            variables,
+            ref value,
+        } = *self;
+        variables.hash_stable(hcx, hasher);
+        value.hash_stable(hcx, hasher);
+    }
+}
+
+impl<'a, 'gcx> HashStable<StableHashingContext<'a>>
+for infer::canonical::CanonicalVarValues<'gcx> {
+    fn hash_stable<W: StableHasherResult>(&self,
+                                          hcx: &mut StableHashingContext<'a>,
+                                          hasher: &mut StableHasher<W>) {
+        let infer::canonical::CanonicalVarValues {
+            ref var_values,
+        } = *self;
+        var_values.hash_stable(hcx, hasher);
+    }
+}
+
+impl_stable_hash_for!(struct infer::canonical::CanonicalVarInfo {
+    kind
+});
+
+impl_stable_hash_for!(enum infer::canonical::CanonicalVarKind {
+    Ty(k),
+    Region
+});
+
+impl_stable_hash_for!(enum infer::canonical::CanonicalTyVarKind {
+    General,
+    Int,
+    Float
+});
+
+impl<'a, 'gcx, R> HashStable<StableHashingContext<'a>>
+for infer::canonical::QueryResult<'gcx, R> where R: HashStable<StableHashingContext<'a>> {
+    fn hash_stable<W: StableHasherResult>(&self,
+                                          hcx: &mut StableHashingContext<'a>,
+                                          hasher: &mut StableHasher<W>) {
+        let infer::canonical::QueryResult {
+            ref var_values,
+            ref region_constraints,
+            certainty,
+            ref value,
+        } = *self;
+        var_values.hash_stable(hcx, hasher);
+        region_constraints.hash_stable(hcx, hasher);
+        certainty.hash_stable(hcx, hasher);
+        value.hash_stable(hcx, hasher);
+    }
+}
+
+impl_stable_hash_for!(enum infer::canonical::Certainty {
+    Proven,
+    Ambiguous
+});
+
+impl_stable_hash_for!(struct traits::query::NoSolution {});
+
+impl_stable_hash_for!(struct traits::query::normalize::NormalizationResult<'tcx> {
+    normalized_ty
+});
+
+impl_stable_hash_for!(struct traits::query::dropck_outlives::DropckOutlivesResult<'tcx> {
+    kinds
+});
+
+impl<'a, 'gcx> HashStable<StableHashingContext<'a>>
+for infer::outlives::bounds::OutlivesBound<'gcx> {
+    fn hash_stable<W: StableHasherResult>(&self,
+                                          hcx: &mut StableHashingContext<'a>,
+                                          hasher: &mut StableHasher<W>) {
+        use infer::outlives::bounds::OutlivesBound::*;
+
+        mem::discriminant(self).hash_stable(hcx, hasher);
+        match *self {
+            RegionSubRegion(a, b) => {
+                a.hash_stable(hcx, hasher);
+                b.hash_stable(hcx, hasher);
+            }
+            RegionSubParam(a, b) => {
+                a.hash_stable(hcx, hasher);
+                b.hash_stable(hcx, hasher);
+            }
+            RegionSubProjection(a, ref b) => {
+                a.hash_stable(hcx, hasher);
+                b.hash_stable(hcx, hasher);
+            }
+        }
+    }
+}
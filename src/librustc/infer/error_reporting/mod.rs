@@ -70,7 +70,7 @@ use ty::{self, Region, Ty, TyCtxt, TypeFoldable, TypeVariants};
 use ty::error::TypeError;
 use syntax::ast::DUMMY_NODE_ID;
 use syntax_pos::{Pos, Span};
-use errors::{DiagnosticBuilder, DiagnosticStyledString};
+use errors::{Applicability, DiagnosticBuilder, DiagnosticStyledString};
 
 use rustc_data_structures::indexed_vec::Idx;
 
@@ -154,6 +154,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             }
 
             // We shouldn't encounter an error message with ReClosureBound.
+            ty::ReCanonical(..) |
             ty::ReClosureBound(..) => {
                 bug!("encountered unexpected ReClosureBound: {:?}", region,);
             }
@@ -360,6 +361,8 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                     RegionResolutionError::GenericBoundFailure(origin, param_ty, sub) => {
                         self.report_generic_bound_failure(
                             region_scope_tree,
+                            self.in_progress_tables
+                                .and_then(|tables| tables.borrow().local_id_root),
                             origin.span(),
                             Some(origin),
                             param_ty,
@@ -1011,49 +1014,71 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
     pub fn report_generic_bound_failure(
         &self,
         region_scope_tree: &region::ScopeTree,
+        generic_param_scope: Option<DefId>,
         span: Span,
         origin: Option<SubregionOrigin<'tcx>>,
         bound_kind: GenericKind<'tcx>,
         sub: Region<'tcx>,
     ) {
+        self.construct_generic_bound_failure(
+            region_scope_tree,
+            generic_param_scope,
+            span,
+            origin,
+            bound_kind,
+            sub,
+        ).emit();
+    }
+
+    /// Builds the error that `bound_kind` may not outlive `sub`.
+    /// `generic_param_scope` is the item whose generics `bound_kind` refers
+    /// to, if known; it is used to suggest adding the missing bound to the
+    /// parameter.
+    pub fn construct_generic_bound_failure(
+        &self,
+        region_scope_tree: &region::ScopeTree,
+        generic_param_scope: Option<DefId>,
+        span: Span,
+        origin: Option<SubregionOrigin<'tcx>>,
+        bound_kind: GenericKind<'tcx>,
+        sub: Region<'tcx>,
+    ) -> DiagnosticBuilder<'tcx> {
         // Attempt to obtain the span of the parameter so we can
         // suggest adding an explicit lifetime bound to it.
-        let type_param_span = match (self.in_progress_tables, bound_kind) {
-            (Some(ref table), GenericKind::Param(ref param)) => {
-                let table = table.borrow();
-                table.local_id_root.and_then(|did| {
-                    let generics = self.tcx.generics_of(did);
-                    // Account for the case where `did` corresponds to `Self`, which doesn't have
-                    // the expected type argument.
-                    if !param.is_self() {
-                        let type_param = generics.type_param(param, self.tcx);
-                        let hir = &self.tcx.hir;
-                        hir.as_local_node_id(type_param.def_id).map(|id| {
-                            // Get the `hir::TyParam` to verify whether it already has any bounds.
-                            // We do this to avoid suggesting code that ends up as `T: 'a'b`,
-                            // instead we suggest `T: 'a + 'b` in that case.
-                            let has_lifetimes = if let hir_map::NodeTyParam(ref p) = hir.get(id) {
-                                p.bounds.len() > 0
-                            } else {
-                                false
-                            };
-                            let sp = hir.span(id);
-                            // `sp` only covers `T`, change it so that it covers
-                            // `T:` when appropriate
-                            let sp = if has_lifetimes {
-                                sp.to(self.tcx
-                                    .sess
-                                    .codemap()
-                                    .next_point(self.tcx.sess.codemap().next_point(sp)))
+        let type_param_span = match (generic_param_scope, bound_kind) {
+            (Some(did), GenericKind::Param(ref param)) => {
+                let generics = self.tcx.generics_of(did);
+                // Account for the case where `did` corresponds to `Self`, which doesn't have
+                // the expected type argument.
+                if !param.is_self() {
+                    let type_param = generics.type_param(param, self.tcx);
+                    let hir = &self.tcx.hir;
+                    hir.as_local_node_id(type_param.def_id).map(|id| {
+                        // Get the `hir::TyParam` to verify whether it already has any bounds.
+                        // We do this to avoid suggesting code that ends up as `T: 'a'b`,
+                        // instead we suggest `T: 'a + 'b` in that case.
+                        let (has_lifetimes, is_impl_trait) =
+                            if let hir_map::NodeTyParam(ref p) = hir.get(id) {
+                                (p.bounds.len() > 0, p.synthetic.is_some())
                             } else {
-                                sp
+                                (false, false)
                             };
-                            (sp, has_lifetimes)
-                        })
-                    } else {
-                        None
-                    }
-                })
+                        let sp = hir.span(id);
+                        // `sp` only covers `T`, change it so that it covers
+                        // `T:` when appropriate
+                        let sp = if has_lifetimes && !is_impl_trait {
+                            sp.to(self.tcx
+                                .sess
+                                .codemap()
+                                .next_point(self.tcx.sess.codemap().next_point(sp)))
+                        } else {
+                            sp
+                        };
+                        TypeParamSpan { span: sp, has_lifetimes, is_impl_trait }
+                    })
+                } else {
+                    None
+                }
             }
             _ => None,
         };
@@ -1070,35 +1095,68 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
             trait_item_def_id,
         }) = origin
         {
-            self.report_extra_impl_obligation(
+            return self.report_extra_impl_obligation(
                 span,
                 item_name,
                 impl_item_def_id,
                 trait_item_def_id,
                 &format!("`{}: {}`", bound_kind, sub),
-            ).emit();
-            return;
+            );
         }
 
-        fn binding_suggestion<'tcx, S: fmt::Display>(
+        fn binding_suggestion<'a, 'gcx, 'tcx, S: fmt::Display>(
+            infcx: &InferCtxt<'a, 'gcx, 'tcx>,
             err: &mut DiagnosticBuilder<'tcx>,
-            type_param_span: Option<(Span, bool)>,
+            type_param_span: Option<TypeParamSpan>,
             bound_kind: GenericKind<'tcx>,
             sub: S,
         ) {
-            let consider = &format!(
+            match type_param_span {
+                Some(TypeParamSpan { span, is_impl_trait: true, .. }) => {
+                    // The parameter is an `impl Trait` argument, so the bound
+                    // goes on the `impl Trait` itself.
+                    if let Ok(snippet) = infcx.tcx.sess.codemap().span_to_snippet(span) {
+                        err.span_suggestion_with_applicability(
+                            span,
+                            "consider adding an explicit lifetime bound",
+                            format!("{} + {}", snippet, sub),
+                            Applicability::MaybeIncorrect,
+                        );
+                        return;
+                    }
+                }
+                Some(TypeParamSpan { span, has_lifetimes, .. }) => {
+                    let tail = if has_lifetimes { " + " } else { "" };
+                    err.span_suggestion_short_with_applicability(
+                        span,
+                        &format!(
+                            "consider adding an explicit lifetime bound `{}: {}`...",
+                            bound_kind, sub
+                        ),
+                        format!("{}: {}{}", bound_kind, sub, tail),
+                        Applicability::MaybeIncorrect,
+                    );
+                    return;
+                }
+                None => {}
+            }
+            err.help(&format!(
                 "consider adding an explicit lifetime bound `{}: {}`...",
                 bound_kind, sub
-            );
-            if let Some((sp, has_lifetimes)) = type_param_span {
-                let tail = if has_lifetimes { " + " } else { "" };
-                let suggestion = format!("{}: {}{}", bound_kind, sub, tail);
-                err.span_suggestion_short(sp, consider, suggestion);
-            } else {
-                err.help(consider);
-            }
+            ));
         }
 
+        // Suggest the bound using the region's name as written in the
+        // source, however the region itself gets printed.
+        let sub_name = match *sub {
+            ty::ReEarlyBound(ref data) => data.name.to_string(),
+            ty::ReFree(ty::FreeRegion {
+                bound_region: ty::BrNamed(_, name),
+                ..
+            }) => name.to_string(),
+            _ => sub.to_string(),
+        };
+
         let mut err = match *sub {
             ty::ReEarlyBound(_)
             | ty::ReFree(ty::FreeRegion {
@@ -1113,7 +1171,7 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                     "{} may not live long enough",
                     labeled_user_string
                 );
-                binding_suggestion(&mut err, type_param_span, bound_kind, sub);
+                binding_suggestion(self, &mut err, type_param_span, bound_kind, sub_name);
                 err
             }
 
@@ -1126,7 +1184,7 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                     "{} may not live long enough",
                     labeled_user_string
                 );
-                binding_suggestion(&mut err, type_param_span, bound_kind, "'static");
+                binding_suggestion(self, &mut err, type_param_span, bound_kind, "'static");
                 err
             }
 
@@ -1157,7 +1215,7 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
         if let Some(origin) = origin {
             self.note_region_origin(&mut err, &origin);
         }
-        err.emit();
+        err
     }
 
     fn report_sub_sup_conflict(
@@ -1274,6 +1332,16 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
     }
 }
 
+/// The span of a type parameter to which a missing lifetime bound can be
+/// added, as computed by `report_generic_bound_failure`.
+struct TypeParamSpan {
+    span: Span,
+    /// Whether the parameter already has some bounds.
+    has_lifetimes: bool,
+    /// Whether the parameter is an `impl Trait` in argument position.
+    is_impl_trait: bool,
+}
+
 enum FailureCode {
     Error0317(&'static str),
     Error0580(&'static str),
@@ -260,6 +260,8 @@ impl<'cx, 'gcx, 'tcx> LexicalResolver<'cx, 'gcx, 'tcx> {
         match (a, b) {
             (&ty::ReClosureBound(..), _) |
             (_, &ty::ReClosureBound(..)) |
+            (&ty::ReCanonical(..), _) |
+            (_, &ty::ReCanonical(..)) |
             (&ReLateBound(..), _) |
             (_, &ReLateBound(..)) |
             (&ReErased, _) |
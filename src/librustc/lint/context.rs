@@ -27,12 +27,14 @@
 use self::TargetLint::*;
 
 use std::slice;
-use lint::{EarlyLintPassObject, LateLintPassObject};
+use lint::{EarlyLintPassObject, LateLintPassObject, MirLintPassObject};
 use lint::{Level, Lint, LintId, LintPass, LintBuffer};
 use lint::builtin::BuiltinLintDiagnostics;
 use lint::levels::{LintLevelSets, LintLevelsBuilder};
 use middle::privacy::AccessLevels;
+use mir::{BorrowCheckResult, ClearCrossCrate, Location, Mir, SourceInfo};
 use rustc_serialize::{Decoder, Decodable, Encoder, Encodable};
+use rustc_data_structures::sync::{ReadGuard, RwLock};
 use session::{config, early_error, Session};
 use traits::Reveal;
 use ty::{self, TyCtxt, Ty};
@@ -40,13 +42,12 @@ use ty::layout::{LayoutError, LayoutOf, TyLayout};
 use util::nodemap::FxHashMap;
 
 use std::default::Default as StdDefault;
-use std::cell::{Ref, RefCell};
 use syntax::ast;
 use syntax::epoch;
 use syntax_pos::{MultiSpan, Span};
 use errors::DiagnosticBuilder;
 use hir;
-use hir::def_id::LOCAL_CRATE;
+use hir::def_id::{DefId, LOCAL_CRATE};
 use hir::intravisit as hir_visit;
 use syntax::visit as ast_visit;
 
@@ -64,6 +65,7 @@ pub struct LintStore {
     /// of `LintSession::new`.
     early_passes: Option<Vec<EarlyLintPassObject>>,
     late_passes: Option<Vec<LateLintPassObject>>,
+    mir_passes: Option<Vec<MirLintPassObject>>,
 
     /// Lints indexed by name.
     by_name: FxHashMap<String, TargetLint>,
@@ -79,7 +81,7 @@ pub struct LintStore {
 
 pub struct LintSession<'a, PassObject> {
     /// Reference to the store of registered lints.
-    lints: Ref<'a, LintStore>,
+    lints: ReadGuard<'a, LintStore>,
 
     /// Trait objects for each lint pass.
     passes: Option<Vec<PassObject>>,
@@ -142,6 +144,7 @@ impl LintStore {
             lints: vec![],
             early_passes: Some(vec![]),
             late_passes: Some(vec![]),
+            mir_passes: Some(vec![]),
             by_name: FxHashMap(),
             future_incompatible: FxHashMap(),
             lint_groups: FxHashMap(),
@@ -174,7 +177,15 @@ impl LintStore {
         self.late_passes.as_mut().unwrap().push(pass);
     }
 
-    // Helper method for register_early/late_pass
+    pub fn register_mir_pass(&mut self,
+                             sess: Option<&Session>,
+                             from_plugin: bool,
+                             pass: MirLintPassObject) {
+        self.push_pass(sess, from_plugin, &pass);
+        self.mir_passes.as_mut().unwrap().push(pass);
+    }
+
+    // Helper method for register_early/late/mir_pass
     fn push_pass<P: LintPass + ?Sized + 'static>(&mut self,
                                         sess: Option<&Session>,
                                         from_plugin: bool,
@@ -337,7 +348,7 @@ impl<'a, PassObject: LintPassObject> LintSession<'a, PassObject> {
     /// Creates a new `LintSession`, by moving out the `LintStore`'s initial
     /// lint levels and pass objects. These can be restored using the `restore`
     /// method.
-    fn new(store: &'a RefCell<LintStore>) -> LintSession<'a, PassObject> {
+    fn new(store: &'a RwLock<LintStore>) -> LintSession<'a, PassObject> {
         let mut s = store.borrow_mut();
         let passes = PassObject::take_passes(&mut *s);
         drop(s);
@@ -348,7 +359,7 @@ impl<'a, PassObject: LintPassObject> LintSession<'a, PassObject> {
     }
 
     /// Restores the levels back to the original lint store.
-    fn restore(self, store: &RefCell<LintStore>) {
+    fn restore(self, store: &RwLock<LintStore>) {
         drop(self.lints);
         let mut s = store.borrow_mut();
         PassObject::restore_passes(&mut *s, self.passes);
@@ -395,6 +406,31 @@ pub struct EarlyContext<'a> {
     buffered: LintBuffer,
 }
 
+/// Context for lint checking of the MIR of a single body, after borrow
+/// checking.
+pub struct MirContext<'a, 'tcx: 'a> {
+    /// Type context we're checking in.
+    pub tcx: TyCtxt<'a, 'tcx, 'tcx>,
+
+    /// The body being checked.
+    pub def_id: DefId,
+
+    /// MIR of the body, as seen by borrowck.
+    pub mir: &'a Mir<'tcx>,
+
+    /// What MIR borrowck found out about the body. This is trivially empty
+    /// when MIR borrowck is not in use.
+    pub borrowck_result: BorrowCheckResult<'tcx>,
+
+    /// Parameter environment of the body.
+    pub param_env: ty::ParamEnv<'tcx>,
+
+    /// The store of registered lints and the lint levels.
+    lint_sess: LintSession<'tcx, MirLintPassObject>,
+
+    last_lint_root: ast::NodeId,
+}
+
 /// Convenience macro for calling a `LintPass` method on every pass in the context.
 macro_rules! run_lints { ($cx:expr, $f:ident, $ps:ident, $($args:expr),*) => ({
     // Move the vector of passes out of `$cx` so that we can
@@ -431,6 +467,16 @@ impl LintPassObject for LateLintPassObject {
     }
 }
 
+impl LintPassObject for MirLintPassObject {
+    fn take_passes(store: &mut LintStore) -> Option<Vec<Self>> {
+        store.mir_passes.take()
+    }
+
+    fn restore_passes(store: &mut LintStore, passes: Option<Vec<Self>>) {
+        store.mir_passes = passes;
+    }
+}
+
 
 pub trait LintContext<'tcx>: Sized {
     type PassObject: LintPassObject;
@@ -647,6 +693,59 @@ impl<'a> LintContext<'a> for EarlyContext<'a> {
     }
 }
 
+impl<'a, 'tcx> LintContext<'tcx> for MirContext<'a, 'tcx> {
+    type PassObject = MirLintPassObject;
+
+    /// Get the overall compiler `Session` object.
+    fn sess(&self) -> &Session {
+        &self.tcx.sess
+    }
+
+    fn lints(&self) -> &LintStore {
+        &*self.lint_sess.lints
+    }
+
+    fn lint_sess(&self) -> &LintSession<'tcx, Self::PassObject> {
+        &self.lint_sess
+    }
+
+    fn lint_sess_mut(&mut self) -> &mut LintSession<'tcx, Self::PassObject> {
+        &mut self.lint_sess
+    }
+
+    // MIR carries no attributes of its own: the lint levels in effect for a
+    // statement are those of the HIR node its scope was lowered from.
+    fn enter_attrs(&mut self, _: &'tcx [ast::Attribute]) { }
+
+    fn exit_attrs(&mut self, _: &'tcx [ast::Attribute]) { }
+
+    fn lookup<S: Into<MultiSpan>>(&self,
+                                  lint: &'static Lint,
+                                  span: Option<S>,
+                                  msg: &str)
+                                  -> DiagnosticBuilder {
+        let id = self.last_lint_root;
+        match span {
+            Some(s) => self.tcx.struct_span_lint_node(lint, id, s, msg),
+            None => self.tcx.struct_lint_node(lint, id, msg),
+        }
+    }
+
+    fn with_lint_attrs<F>(&mut self,
+                          id: ast::NodeId,
+                          attrs: &'tcx [ast::Attribute],
+                          f: F)
+        where F: FnOnce(&mut Self)
+    {
+        let prev = self.last_lint_root;
+        self.last_lint_root = id;
+        self.enter_attrs(attrs);
+        f(self);
+        self.exit_attrs(attrs);
+        self.last_lint_root = prev;
+    }
+}
+
 impl<'a, 'tcx> LateContext<'a, 'tcx> {
     fn with_param_env<F>(&mut self, id: ast::NodeId, f: F)
         where F: FnOnce(&mut Self),
@@ -666,6 +765,52 @@ impl<'a, 'tcx> LayoutOf<Ty<'tcx>> for &'a LateContext<'a, 'tcx> {
     }
 }
 
+impl<'a, 'tcx> MirContext<'a, 'tcx> {
+    /// Run `f` with the lint levels in effect at `source_info`.
+    fn with_source_info<F>(&mut self, source_info: SourceInfo, f: F)
+        where F: FnOnce(&mut Self)
+    {
+        let lint_root = match self.mir.visibility_scope_info {
+            ClearCrossCrate::Set(ref data) => data[source_info.scope].lint_root,
+            ClearCrossCrate::Clear => self.last_lint_root,
+        };
+        let prev = self.last_lint_root;
+        self.last_lint_root = lint_root;
+        f(self);
+        self.last_lint_root = prev;
+    }
+
+    fn check_body(&mut self) {
+        let mir = self.mir;
+        run_lints!(self, check_mir, mir_passes, mir);
+
+        for (block, data) in mir.basic_blocks().iter_enumerated() {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let location = Location { block, statement_index };
+                self.with_source_info(statement.source_info, |cx| {
+                    run_lints!(cx, check_statement, mir_passes, statement, location);
+                });
+            }
+
+            let terminator = data.terminator();
+            let location = Location { block, statement_index: data.statements.len() };
+            self.with_source_info(terminator.source_info, |cx| {
+                run_lints!(cx, check_terminator, mir_passes, terminator, location);
+            });
+        }
+
+        run_lints!(self, check_mir_post, mir_passes, mir);
+    }
+}
+
+impl<'a, 'tcx> LayoutOf<Ty<'tcx>> for &'a MirContext<'a, 'tcx> {
+    type TyLayout = Result<TyLayout<'tcx>, LayoutError<'tcx>>;
+
+    fn layout_of(self, ty: Ty<'tcx>) -> Self::TyLayout {
+        self.tcx.layout_of(self.param_env.and(ty))
+    }
+}
+
 impl<'a, 'tcx> hir_visit::Visitor<'tcx> for LateContext<'a, 'tcx> {
     /// Because lints are scoped lexically, we want to walk nested
     /// items in the context of the outer item, so enable
@@ -1077,6 +1222,45 @@ pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
     cx.lint_sess.restore(&tcx.sess.lint_store);
 }
 
+/// Perform lint checking on the MIR of every body in the crate.
+///
+/// This must run after MIR borrowck, whose results are handed to the passes.
+pub fn check_mir_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    let mut lint_sess = LintSession::new(&tcx.sess.lint_store);
+
+    // Don't walk the MIR of every body if no pass has anything to check.
+    let enabled = lint_sess.passes.as_ref().map_or(false, |passes| {
+        passes.iter().any(|pass| pass.is_enabled(tcx.sess))
+    });
+    if enabled {
+        for def_id in tcx.body_owners() {
+            // Lint the MIR borrowck saw, without forcing optimizations. Bodies
+            // already optimized (e.g. consts evaluated during type checking)
+            // no longer have it, and their optimized MIR exists by now.
+            let validated = tcx.mir_validated(def_id);
+            let validated = validated.try_borrow();
+            let mir = match validated {
+                Some(ref mir) => &**mir,
+                None => tcx.optimized_mir(def_id),
+            };
+            let mut cx = MirContext {
+                tcx,
+                def_id,
+                mir,
+                borrowck_result: tcx.mir_borrowck(def_id),
+                param_env: tcx.param_env(def_id),
+                lint_sess,
+                last_lint_root: tcx.hir.as_local_node_id(def_id).unwrap(),
+            };
+            cx.check_body();
+            lint_sess = cx.lint_sess;
+        }
+    }
+
+    // Put the lint store passes back in the session.
+    lint_sess.restore(&tcx.sess.lint_store);
+}
+
 pub fn check_ast_crate(sess: &Session, krate: &ast::Crate) {
     let mut cx = EarlyContext::new(sess, krate);
 
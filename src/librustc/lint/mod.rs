@@ -38,6 +38,7 @@ use hir::def_id::{CrateNum, LOCAL_CRATE};
 use hir::intravisit::{self, FnKind};
 use hir;
 use lint::builtin::BuiltinLintDiagnostics;
+use mir;
 use session::{Session, DiagnosticMessageId};
 use std::hash;
 use syntax::ast;
@@ -50,8 +51,8 @@ use ty::TyCtxt;
 use ty::maps::Providers;
 use util::nodemap::NodeMap;
 
-pub use lint::context::{LateContext, EarlyContext, LintContext, LintStore,
-                        check_crate, check_ast_crate,
+pub use lint::context::{LateContext, EarlyContext, MirContext, LintContext, LintStore,
+                        check_crate, check_ast_crate, check_mir_crate,
                         FutureIncompatibleInfo, BufferedEarlyLint};
 
 /// Specification of a single lint.
@@ -77,8 +78,9 @@ pub struct Lint {
     /// e.g. "imports that are never used"
     pub desc: &'static str,
 
-    /// Deny lint after this epoch
-    pub epoch_deny: Option<Epoch>,
+    /// Starting at the given epoch, default to the given lint level. If this is
+    /// `None`, then use `default_level`.
+    pub epoch_lint_opts: Option<(Epoch, Level)>,
 }
 
 impl Lint {
@@ -88,9 +90,9 @@ impl Lint {
     }
 
     pub fn default_level(&self, session: &Session) -> Level {
-        if let Some(epoch_deny) = self.epoch_deny {
-            if session.epoch() >= epoch_deny {
-                return Level::Deny
+        if let Some((epoch, level)) = self.epoch_lint_opts {
+            if session.epoch() >= epoch {
+                return level
             }
         }
         self.default_level
@@ -100,12 +102,13 @@ impl Lint {
 /// Declare a static item of type `&'static Lint`.
 #[macro_export]
 macro_rules! declare_lint {
-    ($vis: vis $NAME: ident, $Level: ident, $desc: expr, $epoch: expr) => (
+    ($vis: vis $NAME: ident, $Level: ident, $desc: expr,
+     $lint_epoch: expr => $epoch_level: ident) => (
         $vis static $NAME: &$crate::lint::Lint = &$crate::lint::Lint {
             name: stringify!($NAME),
             default_level: $crate::lint::$Level,
             desc: $desc,
-            epoch_deny: Some($epoch)
+            epoch_lint_opts: Some(($lint_epoch, $crate::lint::Level::$epoch_level)),
         };
     );
     ($vis: vis $NAME: ident, $Level: ident, $desc: expr) => (
@@ -113,7 +116,7 @@ macro_rules! declare_lint {
             name: stringify!($NAME),
             default_level: $crate::lint::$Level,
             desc: $desc,
-            epoch_deny: None,
+            epoch_lint_opts: None,
         };
     );
 }
@@ -235,6 +238,30 @@ pub trait LateLintPass<'a, 'tcx>: LintPass {
     fn exit_lint_attrs(&mut self, _: &LateContext<'a, 'tcx>, _: &'tcx [ast::Attribute]) { }
 }
 
+/// Trait for types providing lint checks over the MIR of a body.
+///
+/// These passes run once the whole crate has been borrow checked, on the
+/// MIR that borrowck saw, so the results of MIR borrowck are available
+/// through the `MirContext`. `check_mir` is called first, then
+/// `check_statement` and `check_terminator` for each basic block in order,
+/// and finally `check_mir_post`.
+///
+/// The MIR of the crate is only walked if at least one registered pass
+/// returns `true` from `is_enabled`.
+pub trait MirLintPass<'a, 'tcx>: LintPass {
+    fn is_enabled(&self, _: &Session) -> bool { true }
+    fn check_mir(&mut self, _: &MirContext<'a, 'tcx>, _: &'a mir::Mir<'tcx>) { }
+    fn check_mir_post(&mut self, _: &MirContext<'a, 'tcx>, _: &'a mir::Mir<'tcx>) { }
+    fn check_statement(&mut self,
+                       _: &MirContext<'a, 'tcx>,
+                       _: &'a mir::Statement<'tcx>,
+                       _: mir::Location) { }
+    fn check_terminator(&mut self,
+                        _: &MirContext<'a, 'tcx>,
+                        _: &'a mir::Terminator<'tcx>,
+                        _: mir::Location) { }
+}
+
 pub trait EarlyLintPass: LintPass {
     fn check_ident(&mut self, _: &EarlyContext, _: Span, _: ast::Ident) { }
     fn check_crate(&mut self, _: &EarlyContext, _: &ast::Crate) { }
@@ -289,6 +316,7 @@ pub trait EarlyLintPass: LintPass {
 /// A lint pass boxed up as a trait object.
 pub type EarlyLintPassObject = Box<dyn EarlyLintPass + 'static>;
 pub type LateLintPassObject = Box<dyn for<'a, 'tcx> LateLintPass<'a, 'tcx> + 'static>;
+pub type MirLintPassObject = Box<dyn for<'a, 'tcx> MirLintPass<'a, 'tcx> + 'static>;
 
 /// Identifies a lint known to the compiler.
 #[derive(Clone, Copy, Debug)]
@@ -27,6 +27,7 @@ pub type EvalResult<'tcx> = Result<&'tcx ty::Const<'tcx>, ConstEvalErr<'tcx>>;
 pub enum ConstVal<'tcx> {
     Unevaluated(DefId, &'tcx Substs<'tcx>),
     Value(Value),
+    Param(ty::ParamConst),
 }
 
 impl<'tcx> ConstVal<'tcx> {
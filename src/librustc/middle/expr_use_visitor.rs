@@ -612,7 +612,7 @@ impl<'a, 'gcx, 'tcx> ExprUseVisitor<'a, 'gcx, 'tcx> {
         match local.init {
             None => {
                 let delegate = &mut self.delegate;
-                local.pat.each_binding(|_, id, span, _| {
+                local.pat.each_binding_or_first(&mut |_, id, span, _| {
                     delegate.decl_without_init(id, span);
                 })
             }
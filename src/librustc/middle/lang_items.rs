@@ -285,7 +285,9 @@ language_item_table! {
 
     // A number of panic-related lang items. The `panic` item corresponds to
     // divide-by-zero and various panic cases with `match`. The
-    // `panic_bounds_check` item is for indexing arrays.
+    // `panic_bounds_check` item is for indexing arrays, and the
+    // `panic_misaligned_pointer_dereference` item is for the checks inserted
+    // by `-Z mir-emit-alignment-checks`.
     //
     // The `begin_unwind` lang item has a predefined symbol name and is sort of
     // a "weak lang item" in the sense that a crate is not required to have it
@@ -294,6 +296,8 @@ language_item_table! {
     // lang item, but do not have it defined.
     PanicFnLangItem,                 "panic",                   panic_fn;
     PanicBoundsCheckFnLangItem,      "panic_bounds_check",      panic_bounds_check_fn;
+    PanicMisalignedPointerDereferenceFnLangItem, "panic_misaligned_pointer_dereference",
+                                     panic_misaligned_pointer_dereference_fn;
     PanicFmtLangItem,                "panic_fmt",               panic_fmt;
 
     ExchangeMallocFnLangItem,        "exchange_malloc",         exchange_malloc_fn;
@@ -312,6 +316,8 @@ language_item_table! {
 
     NonZeroItem,                     "non_zero",                non_zero;
 
+    OptionLangItem,                  "option",                  option_type;
+
     DebugTraitLangItem,              "debug_trait",             debug_trait;
 
     // A lang item for each of the 128-bit operators we can optionally lower.
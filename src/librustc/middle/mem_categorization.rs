@@ -1338,6 +1338,13 @@ impl<'a, 'gcx, 'tcx> MemCategorizationContext<'a, 'gcx, 'tcx> {
             }
           }
 
+          PatKind::Or(ref pats) => {
+            // Each alternative looks at the same value.
+            for pat in pats {
+                self.cat_pattern_(cmt.clone(), &pat, op)?;
+            }
+          }
+
           PatKind::Path(_) | PatKind::Binding(.., None) |
           PatKind::Lit(..) | PatKind::Range(..) | PatKind::Wild => {
             // always ok
@@ -14,20 +14,26 @@
 // on how deeply they recurse to prevent stack overflow. Users can override
 // this via an attribute on the crate like `#![recursion_limit="22"]`. This pass
 // just peeks and looks for that attribute.
+//
+// The number of steps the const evaluator may take to evaluate a single
+// constant is limited in the same way, by `#![const_eval_limit="N"]`, so that
+// constants which loop forever produce an error instead of hanging.
 
 use session::Session;
 use syntax::ast;
 
-use std::cell::Cell;
+use rustc_data_structures::sync::LockCell;
 
 pub fn update_limits(sess: &Session, krate: &ast::Crate) {
     update_limit(sess, krate, &sess.recursion_limit, "recursion_limit",
                  "recursion limit");
     update_limit(sess, krate, &sess.type_length_limit, "type_length_limit",
                  "type length limit");
+    update_limit(sess, krate, &sess.const_eval_step_limit, "const_eval_limit",
+                 "const eval limit");
 }
 
-fn update_limit(sess: &Session, krate: &ast::Crate, limit: &Cell<usize>,
+fn update_limit(sess: &Session, krate: &ast::Crate, limit: &LockCell<usize>,
                 name: &str, description: &str) {
     for attr in &krate.attrs {
         if !attr.check_name(name) {
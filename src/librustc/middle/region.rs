@@ -1216,7 +1216,8 @@ fn resolve_local<'a, 'tcx>(visitor: &mut RegionResolutionVisitor<'a, 'tcx>,
             }
 
             PatKind::TupleStruct(_, ref subpats, _) |
-            PatKind::Tuple(ref subpats, _) => {
+            PatKind::Tuple(ref subpats, _) |
+            PatKind::Or(ref subpats) => {
                 subpats.iter().any(|p| is_binding_pat(&p))
             }
 
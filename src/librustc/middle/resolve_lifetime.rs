@@ -23,12 +23,16 @@ use ty::{self, TyCtxt};
 
 use std::cell::Cell;
 use std::mem::replace;
+use std::ops::Range;
 use rustc_data_structures::sync::Lrc;
 use syntax::ast;
 use syntax::attr;
 use syntax::ptr::P;
-use syntax_pos::Span;
-use errors::DiagnosticBuilder;
+use syntax_pos::{BytePos, Span, DUMMY_SP};
+use syntax::codemap::CodeMap;
+use syntax::symbol::keywords;
+use syntax::util::lev_distance::find_best_match_for_name;
+use errors::{Applicability, DiagnosticBuilder};
 use util::nodemap::{DefIdMap, FxHashMap, FxHashSet, NodeMap, NodeSet};
 use std::slice;
 use rustc::lint;
@@ -204,24 +208,17 @@ struct NamedRegionMap {
     // be late-bound if (a) it does NOT appear in a where-clause and
     // (b) it DOES appear in the arguments.
     pub late_bound: NodeSet,
-
-    // For each type and trait definition, maps type parameters
-    // to the trait object lifetime defaults computed from them.
-    pub object_lifetime_defaults: NodeMap<Vec<ObjectLifetimeDefault>>,
 }
 
 /// See `NamedRegionMap`.
 pub struct ResolveLifetimes {
     defs: FxHashMap<LocalDefId, Lrc<FxHashMap<ItemLocalId, Region>>>,
     late_bound: FxHashMap<LocalDefId, Lrc<FxHashSet<ItemLocalId>>>,
-    object_lifetime_defaults:
-        FxHashMap<LocalDefId, Lrc<FxHashMap<ItemLocalId, Lrc<Vec<ObjectLifetimeDefault>>>>>,
 }
 
 impl_stable_hash_for!(struct ::middle::resolve_lifetime::ResolveLifetimes {
     defs,
-    late_bound,
-    object_lifetime_defaults
+    late_bound
 });
 
 struct LifetimeContext<'a, 'tcx: 'a> {
@@ -251,9 +248,6 @@ struct LifetimeContext<'a, 'tcx: 'a> {
     // List of labels in the function/method currently under analysis.
     labels_in_fn: Vec<(ast::Name, Span)>,
 
-    // Cache for cross-crate per-definition object lifetime defaults.
-    xcrate_object_lifetime_defaults: DefIdMap<Vec<ObjectLifetimeDefault>>,
-
     lifetime_uses: DefIdMap<LifetimeUseSet<'tcx>>,
 }
 
@@ -355,13 +349,7 @@ pub fn provide(providers: &mut ty::maps::Providers) {
                 .cloned()
         },
 
-        object_lifetime_defaults_map: |tcx, id| {
-            let id = LocalDefId::from_def_id(DefId::local(id)); // (*)
-            tcx.resolve_lifetimes(LOCAL_CRATE)
-                .object_lifetime_defaults
-                .get(&id)
-                .cloned()
-        },
+        object_lifetime_defaults,
 
         ..*providers
     };
@@ -396,21 +384,10 @@ fn resolve_lifetimes<'tcx>(
             .or_insert_with(|| Lrc::new(FxHashSet()));
         Lrc::get_mut(map).unwrap().insert(hir_id.local_id);
     }
-    let mut object_lifetime_defaults = FxHashMap();
-    for (k, v) in named_region_map.object_lifetime_defaults {
-        let hir_id = tcx.hir.node_to_hir_id(k);
-        let map = object_lifetime_defaults
-            .entry(hir_id.owner_local_def_id())
-            .or_insert_with(|| Lrc::new(FxHashMap()));
-        Lrc::get_mut(map)
-            .unwrap()
-            .insert(hir_id.local_id, Lrc::new(v));
-    }
 
     Lrc::new(ResolveLifetimes {
         defs,
         late_bound,
-        object_lifetime_defaults,
     })
 }
 
@@ -419,8 +396,8 @@ fn krate<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>) -> NamedRegionMap {
     let mut map = NamedRegionMap {
         defs: NodeMap(),
         late_bound: NodeSet(),
-        object_lifetime_defaults: compute_object_lifetime_defaults(tcx),
     };
+    report_object_lifetime_defaults(tcx);
     {
         let mut visitor = LifetimeContext {
             tcx,
@@ -429,13 +406,16 @@ fn krate<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>) -> NamedRegionMap {
             trait_ref_hack: false,
             is_in_fn_syntax: false,
             labels_in_fn: vec![],
-            xcrate_object_lifetime_defaults: DefIdMap(),
             lifetime_uses: DefIdMap(),
         };
         for (_, item) in &krate.items {
             visitor.visit_item(item);
         }
     }
+    {
+        let mut visitor = LifetimeLintVisitor { tcx, map: &map };
+        intravisit::walk_crate(&mut visitor, krate);
+    }
     map
 }
 
@@ -1107,47 +1087,67 @@ fn extract_labels(ctxt: &mut LifetimeContext<'_, '_>, body: &hir::Body) {
     }
 }
 
-fn compute_object_lifetime_defaults(
-    tcx: TyCtxt<'_, '_, '_>,
-) -> NodeMap<Vec<ObjectLifetimeDefault>> {
-    let mut map = NodeMap();
+/// Computes the trait object lifetime defaults of the type parameters of
+/// the local type or trait `def_id`, in declaration order and without
+/// `Self`. Lowering applies them to the trait objects passed as arguments
+/// to those parameters, e.g. the `Box<dyn Trait>` in `Ref<'a, dyn Trait>`.
+fn object_lifetime_defaults<'tcx>(
+    tcx: TyCtxt<'_, 'tcx, 'tcx>,
+    def_id: DefId,
+) -> Lrc<Vec<ObjectLifetimeDefault>> {
+    let node_id = tcx.hir.as_local_node_id(def_id).unwrap();
+    let defaults = match tcx.hir.get(node_id) {
+        hir::map::NodeItem(item) => match item.node {
+            hir::ItemStruct(_, ref generics)
+            | hir::ItemUnion(_, ref generics)
+            | hir::ItemEnum(_, ref generics)
+            | hir::ItemTy(_, ref generics)
+            | hir::ItemTrait(_, _, ref generics, ..) => {
+                object_lifetime_defaults_for_item(tcx, generics)
+            }
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+    Lrc::new(defaults)
+}
+
+/// Debugging aid: reports the trait object lifetime defaults of the items
+/// with a `#[rustc_object_lifetime_default]` attribute as errors.
+fn report_object_lifetime_defaults(tcx: TyCtxt<'_, '_, '_>) {
     for item in tcx.hir.krate().items.values() {
+        if !attr::contains_name(&item.attrs, "rustc_object_lifetime_default") {
+            continue;
+        }
         match item.node {
             hir::ItemStruct(_, ref generics)
             | hir::ItemUnion(_, ref generics)
             | hir::ItemEnum(_, ref generics)
             | hir::ItemTy(_, ref generics)
             | hir::ItemTrait(_, _, ref generics, ..) => {
-                let result = object_lifetime_defaults_for_item(tcx, generics);
-
-                // Debugging aid.
-                if attr::contains_name(&item.attrs, "rustc_object_lifetime_default") {
-                    let object_lifetime_default_reprs: String = result
-                        .iter()
-                        .map(|set| match *set {
-                            Set1::Empty => "BaseDefault".to_string(),
-                            Set1::One(Region::Static) => "'static".to_string(),
-                            Set1::One(Region::EarlyBound(i, _, _)) => generics.lifetimes()
-                                .nth(i as usize)
-                                .unwrap()
-                                .lifetime
-                                .name
-                                .name()
-                                .to_string(),
-                            Set1::One(_) => bug!(),
-                            Set1::Many => "Ambiguous".to_string(),
-                        })
-                        .collect::<Vec<String>>()
-                        .join(",");
-                    tcx.sess.span_err(item.span, &object_lifetime_default_reprs);
-                }
-
-                map.insert(item.id, result);
+                let result = tcx.object_lifetime_defaults(tcx.hir.local_def_id(item.id));
+                let object_lifetime_default_reprs: String = result
+                    .iter()
+                    .map(|set| match *set {
+                        Set1::Empty => "BaseDefault".to_string(),
+                        Set1::One(Region::Static) => "'static".to_string(),
+                        Set1::One(Region::EarlyBound(i, _, _)) => generics.lifetimes()
+                            .nth(i as usize)
+                            .unwrap()
+                            .lifetime
+                            .name
+                            .name()
+                            .to_string(),
+                        Set1::One(_) => bug!(),
+                        Set1::Many => "Ambiguous".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(",");
+                tcx.sess.span_err(item.span, &object_lifetime_default_reprs);
             }
             _ => {}
         }
     }
-    map
 }
 
 /// Scan the bounds and where-clauses on parameters to extract bounds
@@ -1218,6 +1218,41 @@ fn object_lifetime_defaults_for_item(
         .collect()
 }
 
+/// Returns the spans of the trait object types in `ty` whose lifetime
+/// bound was elided and defaulted to `'static`, e.g. `Box<dyn Trait>`.
+/// Used to explain where a `'static` requirement comes from in region errors.
+pub fn static_object_lifetime_defaults<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    ty: &'tcx hir::Ty,
+) -> Vec<Span> {
+    struct StaticObjectLifetimeVisitor<'a, 'tcx: 'a> {
+        tcx: TyCtxt<'a, 'tcx, 'tcx>,
+        spans: Vec<Span>,
+    }
+
+    impl<'a, 'tcx> Visitor<'tcx> for StaticObjectLifetimeVisitor<'a, 'tcx> {
+        fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+            NestedVisitorMap::None
+        }
+
+        fn visit_ty(&mut self, ty: &'tcx hir::Ty) {
+            if let hir::TyTraitObject(_, ref lifetime) = ty.node {
+                if lifetime.is_elided() {
+                    let hir_id = self.tcx.hir.node_to_hir_id(lifetime.id);
+                    if let Some(Region::Static) = self.tcx.named_region(hir_id) {
+                        self.spans.push(ty.span);
+                    }
+                }
+            }
+            intravisit::walk_ty(self, ty);
+        }
+    }
+
+    let mut visitor = StaticObjectLifetimeVisitor { tcx, spans: vec![] };
+    visitor.visit_ty(ty);
+    visitor.spans
+}
+
 impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
     // FIXME(#37666) this works around a limitation in the region inferencer
     fn hack<F>(&mut self, f: F)
@@ -1235,8 +1270,6 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             tcx, ref mut map, ..
         } = *self;
         let labels_in_fn = replace(&mut self.labels_in_fn, vec![]);
-        let xcrate_object_lifetime_defaults =
-            replace(&mut self.xcrate_object_lifetime_defaults, DefIdMap());
         let mut this = LifetimeContext {
             tcx,
             map: *map,
@@ -1244,14 +1277,12 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             trait_ref_hack: self.trait_ref_hack,
             is_in_fn_syntax: self.is_in_fn_syntax,
             labels_in_fn,
-            xcrate_object_lifetime_defaults,
             lifetime_uses: DefIdMap(),
         };
         debug!("entering scope {:?}", this.scope);
         f(self.scope, &mut this);
         debug!("exiting scope {:?}", this.scope);
         self.labels_in_fn = this.labels_in_fn;
-        self.xcrate_object_lifetime_defaults = this.xcrate_object_lifetime_defaults;
 
         for (def_id, lifetimeuseset) in &this.lifetime_uses {
             match lifetimeuseset {
@@ -1321,7 +1352,10 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             match parent.node {
                 hir::ItemTrait(_, _, ref generics, ..)
                 | hir::ItemImpl(_, _, _, ref generics, ..) => {
-                    index += generics.params.len() as u32;
+                    // Const parameters don't occupy a slot in `Substs` yet.
+                    index += generics.params.iter()
+                        .filter(|param| !param.is_const_param())
+                        .count() as u32;
                 }
                 _ => {}
             }
@@ -1477,14 +1511,73 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
 
             self.insert_lifetime(lifetime_ref, def);
         } else {
-            struct_span_err!(
+            let mut err = struct_span_err!(
                 self.tcx.sess,
                 lifetime_ref.span,
                 E0261,
                 "use of undeclared lifetime name `{}`",
                 lifetime_ref.name.name()
-            ).span_label(lifetime_ref.span, "undeclared lifetime")
-                .emit();
+            );
+            err.span_label(lifetime_ref.span, "undeclared lifetime");
+            self.suggest_lifetime_name(&mut err, lifetime_ref);
+            err.emit();
+        }
+    }
+
+    /// Suggests a similarly named lifetime that is in scope at `lifetime_ref`
+    /// (or `'static`), and points at the generics of the enclosing item, where
+    /// the lifetime could be declared.
+    fn suggest_lifetime_name(&self, err: &mut DiagnosticBuilder, lifetime_ref: &hir::Lifetime) {
+        let mut candidates = vec![keywords::StaticLifetime.name()];
+        let mut scope = self.scope;
+        loop {
+            match *scope {
+                Scope::Root => break,
+
+                Scope::Binder { ref lifetimes, s, .. } => {
+                    candidates.extend(lifetimes.keys().filter_map(|name| match *name {
+                        hir::LifetimeName::Name(name) => Some(name),
+                        _ => None,
+                    }));
+                    scope = s;
+                }
+
+                Scope::Body { s, .. }
+                | Scope::Elision { s, .. }
+                | Scope::ObjectLifetimeDefault { s, .. } => scope = s,
+            }
+        }
+
+        let name = lifetime_ref.name.name();
+        let lookup = name.as_str();
+        // Lifetime names are short, so only allow one edit per three characters
+        // of the name proper; otherwise every `'a` would be a typo of `'b`.
+        let max_dist = (lookup.len() - 1) / 3;
+        let similar = find_best_match_for_name(candidates.iter(), &lookup, Some(max_dist));
+        if let Some(similar) = similar {
+            err.span_suggestion_with_applicability(
+                lifetime_ref.span,
+                "a lifetime with a similar name exists",
+                similar.to_string(),
+                Applicability::MaybeIncorrect,
+            );
+        }
+
+        let parent = self.tcx.hir.get_parent(lifetime_ref.id);
+        let generics = match self.tcx.hir.find(parent) {
+            Some(hir::map::NodeItem(item)) => item.node.generics(),
+            Some(hir::map::NodeTraitItem(item)) => Some(&item.generics),
+            Some(hir::map::NodeImplItem(item)) => Some(&item.generics),
+            _ => None,
+        };
+        if let Some(generics) = generics {
+            if generics.span != DUMMY_SP {
+                err.span_note(
+                    generics.span,
+                    &format!("the lifetime `{}` could be declared in these generic parameters",
+                             name),
+                );
+            }
         }
     }
 
@@ -1552,20 +1645,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             };
 
             let map = &self.map;
-            let unsubst = if let Some(id) = self.tcx.hir.as_local_node_id(def_id) {
-                &map.object_lifetime_defaults[&id]
-            } else {
-                let tcx = self.tcx;
-                self.xcrate_object_lifetime_defaults
-                    .entry(def_id)
-                    .or_insert_with(|| {
-                        tcx.generics_of(def_id)
-                            .types
-                            .iter()
-                            .map(|def| def.object_lifetime_default)
-                            .collect()
-                    })
-            };
+            let unsubst = self.tcx.object_lifetime_defaults(def_id);
             unsubst
                 .iter()
                 .map(|set| match *set {
@@ -1933,6 +2013,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         if let Some(params) = error {
             if lifetime_refs.len() == 1 {
                 self.report_elision_failure(&mut err, params);
+                self.suggest_named_lifetime(&mut err, &lifetime_refs[0], params);
             }
         }
         err.emit();
@@ -2027,6 +2108,110 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         }
     }
 
+    /// Suggests the explicit form of a function signature whose return type
+    /// borrows from one of several elided argument lifetimes, by tying all
+    /// of the elided references in the signature to a new lifetime parameter.
+    fn suggest_named_lifetime(
+        &self,
+        db: &mut DiagnosticBuilder,
+        lifetime_ref: &hir::Lifetime,
+        params: &[ElisionFailureInfo],
+    ) {
+        let parent = self.tcx.hir.get_parent(lifetime_ref.id);
+        let (name, span, decl, generics) = match self.tcx.hir.find(parent) {
+            Some(hir::map::NodeItem(&hir::Item {
+                name,
+                span,
+                node: hir::ItemFn(ref decl, _, _, _, ref generics, _),
+                ..
+            })) => (name, span, &**decl, generics),
+            Some(hir::map::NodeTraitItem(&hir::TraitItem {
+                name,
+                span,
+                ref generics,
+                node: hir::TraitItemKind::Method(ref sig, _),
+                ..
+            }))
+            | Some(hir::map::NodeImplItem(&hir::ImplItem {
+                name,
+                span,
+                ref generics,
+                node: hir::ImplItemKind::Method(ref sig, _),
+                ..
+            })) => (name, span, &*sig.decl, generics),
+            _ => return,
+        };
+
+        // The missing lifetime must be in the return type of this function,
+        // not in some `fn` type nested in it.
+        let output = match decl.output {
+            hir::Return(ref ty) if ty.span.contains(lifetime_ref.span) => ty,
+            _ => return,
+        };
+
+        // Only suggest anything if every lifetime we need to name is that of a
+        // plain reference, which we can name by inserting after the `&`.
+        let mut references = vec![];
+        for info in params.iter().filter(|info| info.lifetime_count > 0) {
+            match elided_reference_spans(&decl.inputs[info.index]) {
+                Some(ref spans) if spans.len() == info.lifetime_count => {
+                    references.extend(spans);
+                }
+                _ => return,
+            }
+        }
+        if references.is_empty() {
+            return;
+        }
+        match elided_reference_spans(output) {
+            Some(spans) => references.extend(spans),
+            None => return,
+        }
+
+        let is_declared = |lifetime_name: &str| {
+            generics.lifetimes().any(|def| def.lifetime.name.name() == lifetime_name)
+        };
+        let lifetime_name = match "abcdefghijklmnopqrstuvwxyz"
+            .chars()
+            .map(|c| format!("'{}", c))
+            .find(|lifetime_name| !is_declared(lifetime_name))
+        {
+            Some(lifetime_name) => lifetime_name,
+            None => return,
+        };
+
+        let codemap = self.tcx.sess.codemap();
+        let sig_span = match fn_signature_span(codemap, span, name, decl) {
+            Some(sig_span) => sig_span,
+            None => return,
+        };
+        let snippet = match codemap.span_to_snippet(sig_span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        let offset = |span: Span| (span.lo().0 - sig_span.lo().0) as usize;
+
+        let mut edits = vec![];
+        if generics.span != DUMMY_SP {
+            let position = offset(generics.span) + 1;
+            edits.push((position..position, format!("{}, ", lifetime_name)));
+        } else {
+            let fn_end = "fn ".len() + name.as_str().len();
+            edits.push((fn_end..fn_end, format!("<{}>", lifetime_name)));
+        }
+        for span in references {
+            let position = offset(span) + 1;
+            edits.push((position..position, format!("{} ", lifetime_name)));
+        }
+
+        db.span_suggestion_with_applicability(
+            sig_span,
+            "consider introducing a named lifetime parameter",
+            apply_edits(&snippet, edits),
+            Applicability::MaybeIncorrect,
+        );
+    }
+
     fn resolve_object_lifetime_default(&mut self, lifetime_ref: &'tcx hir::Lifetime) {
         let mut late_depth = 0;
         let mut scope = self.scope;
@@ -2221,6 +2406,462 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
 
 ///////////////////////////////////////////////////////////////////////////
 
+/// Returns the span of the signature of the function `name` declared by the
+/// item spanning `item_span`, from the `fn` keyword to the end of the return
+/// type, or to the end of the arguments if there is no return type.
+fn fn_signature_span(
+    codemap: &CodeMap,
+    item_span: Span,
+    name: ast::Name,
+    decl: &hir::FnDecl,
+) -> Option<Span> {
+    let snippet = codemap.span_to_snippet(item_span).ok()?;
+    let fn_start = snippet.find(&format!("fn {}", name))?;
+    let lo = item_span.lo() + BytePos(fn_start as u32);
+    let hi = match decl.output {
+        hir::Return(ref ty) => ty.span.hi(),
+        hir::DefaultReturn(_) => {
+            let after_inputs = match decl.inputs.last() {
+                Some(input) => item_span.with_lo(input.span.hi()),
+                None => item_span.with_lo(lo),
+            };
+            let through_paren = codemap.span_through_char(after_inputs, ')');
+            if through_paren == after_inputs {
+                return None;
+            }
+            through_paren.hi()
+        }
+    };
+    Some(item_span.with_lo(lo).with_hi(hi))
+}
+
+/// Rewrites `snippet` by replacing each of the given (non-overlapping) byte
+/// ranges of it with the accompanying text.
+fn apply_edits(snippet: &str, mut edits: Vec<(Range<usize>, String)>) -> String {
+    edits.sort_by_key(|&(ref range, _)| range.start);
+
+    let mut result = String::new();
+    let mut last = 0;
+    for (range, replacement) in edits {
+        result.push_str(&snippet[last..range.start]);
+        result.push_str(&replacement);
+        last = range.end;
+    }
+    result.push_str(&snippet[last..]);
+    result
+}
+
+/// Returns the byte range of `snippet`, the source text of `generics`, to
+/// delete in order to remove its `index`th parameter along with the comma
+/// separating it from its neighbours, or all of `snippet` if it is the only
+/// parameter.
+fn generic_param_removal_range(
+    snippet: &str,
+    generics: &hir::Generics,
+    index: usize,
+) -> Option<Range<usize>> {
+    let param_start = |param: &hir::GenericParam| {
+        let span = match *param {
+            hir::GenericParam::Lifetime(ref def) => def.lifetime.span,
+            hir::GenericParam::Type(ref ty_param) => ty_param.span,
+            hir::GenericParam::Const(ref const_param) => const_param.span,
+        };
+        (span.lo().0 - generics.span.lo().0) as usize
+    };
+
+    let params = &generics.params;
+    if params.len() == 1 {
+        Some(0..snippet.len())
+    } else if index + 1 < params.len() {
+        Some(param_start(&params[index])..param_start(&params[index + 1]))
+    } else {
+        let start = param_start(&params[index]);
+        let comma = snippet[..start].rfind(',')?;
+        let end = snippet.rfind('>')?;
+        Some(comma..end)
+    }
+}
+
+/// Collects the uses of lifetime parameters in (some part of) an item,
+/// together with the definitions they were resolved to.
+struct LifetimeUseCollector<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    map: &'a NamedRegionMap,
+    /// The definition each use resolves to, and whether the use is the
+    /// lifetime of a reference type.
+    uses: Vec<(DefId, &'tcx hir::Lifetime, bool)>,
+    /// The number of lifetimes seen, named or not, that elision looks at.
+    elision_inputs: usize,
+    /// Whether there were any types in which lifetimes are elided by other
+    /// rules than those of signatures (e.g. `fn` types or trait objects).
+    other_elision: bool,
+}
+
+impl<'a, 'tcx> LifetimeUseCollector<'a, 'tcx> {
+    fn new(tcx: TyCtxt<'a, 'tcx, 'tcx>, map: &'a NamedRegionMap) -> Self {
+        LifetimeUseCollector {
+            tcx,
+            map,
+            uses: vec![],
+            elision_inputs: 0,
+            other_elision: false,
+        }
+    }
+
+    fn record(&mut self, lifetime: &'tcx hir::Lifetime, is_reference: bool) {
+        self.elision_inputs += 1;
+        let def_id = match self.map.defs.get(&lifetime.id) {
+            Some(&Region::EarlyBound(_, def_id, _))
+            | Some(&Region::LateBound(_, def_id, _))
+            | Some(&Region::Free(_, def_id)) => def_id,
+            _ => return,
+        };
+        self.uses.push((def_id, lifetime, is_reference));
+    }
+
+    fn uses_of(&self, def_id: DefId) -> Vec<(&'tcx hir::Lifetime, bool)> {
+        self.uses
+            .iter()
+            .filter(|&&(use_def_id, _, _)| use_def_id == def_id)
+            .map(|&(_, lifetime, is_reference)| (lifetime, is_reference))
+            .collect()
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for LifetimeUseCollector<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+        NestedVisitorMap::OnlyBodies(&self.tcx.hir)
+    }
+
+    fn visit_ty(&mut self, ty: &'tcx hir::Ty) {
+        match ty.node {
+            hir::TyRptr(ref lifetime, ref mt) => {
+                self.record(lifetime, true);
+                self.visit_ty(&mt.ty);
+                return;
+            }
+            hir::TyBareFn(..) | hir::TyTraitObject(..) | hir::TyImplTraitExistential(..) => {
+                self.other_elision = true;
+            }
+            _ => {}
+        }
+        intravisit::walk_ty(self, ty);
+    }
+
+    fn visit_path_parameters(&mut self, span: Span, parameters: &'tcx hir::PathParameters) {
+        if parameters.parenthesized {
+            self.other_elision = true;
+        }
+        intravisit::walk_path_parameters(self, span, parameters);
+    }
+
+    fn visit_lifetime(&mut self, lifetime: &'tcx hir::Lifetime) {
+        self.record(lifetime, false);
+    }
+}
+
+/// Runs the lints about lifetime parameters that need the results of
+/// lifetime resolution.
+struct LifetimeLintVisitor<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    map: &'a NamedRegionMap,
+}
+
+impl<'a, 'tcx> LifetimeLintVisitor<'a, 'tcx> {
+    /// Both lints are allowed by default, so most of the time there is no
+    /// need to collect the uses of any lifetime.
+    fn is_enabled(&self, lint: &'static lint::Lint, id: ast::NodeId) -> bool {
+        self.tcx.lint_level_at_node(lint, id).0 != lint::Allow
+    }
+
+    /// Lints the lifetime parameters of a function that could be left out of
+    /// its signature, because they are either used in only one argument, or
+    /// are exactly the lifetime that elision would pick for the return type.
+    fn check_redundant_lifetimes(
+        &self,
+        span: Span,
+        name: ast::Name,
+        generics: &'tcx hir::Generics,
+        decl: &'tcx hir::FnDecl,
+        all_uses: &LifetimeUseCollector<'a, 'tcx>,
+    ) {
+        let mut inputs = LifetimeUseCollector::new(self.tcx, self.map);
+        for input in &decl.inputs {
+            inputs.visit_ty(input);
+        }
+        let mut output = LifetimeUseCollector::new(self.tcx, self.map);
+        if let hir::Return(ref ty) = decl.output {
+            output.visit_ty(ty);
+        }
+        if inputs.other_elision || output.other_elision {
+            return;
+        }
+
+        for (index, param) in generics.params.iter().enumerate() {
+            let def = match *param {
+                hir::GenericParam::Lifetime(ref def) => def,
+                _ => continue,
+            };
+            if !self.map.late_bound.contains(&def.lifetime.id) {
+                continue;
+            }
+
+            let def_id = self.tcx.hir.local_def_id(def.lifetime.id);
+            let input_uses = inputs.uses_of(def_id);
+            let output_uses = output.uses_of(def_id);
+
+            // The lifetime must only be used as the lifetime of references in
+            // the signature, and in exactly one of the arguments.
+            if all_uses.uses_of(def_id).len() != input_uses.len() + output_uses.len()
+                || input_uses.len() != 1
+                || !input_uses
+                    .iter()
+                    .chain(&output_uses)
+                    .all(|&(_, is_reference)| is_reference)
+            {
+                continue;
+            }
+
+            // If the return type uses it, elision has to pick it too: either
+            // as the only lifetime of the arguments, or as that of `&self`.
+            let input_use = input_uses[0].0;
+            let is_self_lifetime = decl.has_implicit_self && match decl.inputs[0].node {
+                hir::TyRptr(ref lifetime, _) => lifetime.id == input_use.id,
+                _ => false,
+            };
+            if !output_uses.is_empty() && inputs.elision_inputs != 1 && !is_self_lifetime {
+                continue;
+            }
+
+            let codemap = self.tcx.sess.codemap();
+            let sig_span = match fn_signature_span(codemap, span, name, decl) {
+                Some(sig_span) => sig_span,
+                None => continue,
+            };
+            let snippet = match codemap.span_to_snippet(sig_span) {
+                Ok(snippet) => snippet,
+                Err(_) => continue,
+            };
+            let offset = |span: Span| (span.lo().0 - sig_span.lo().0) as usize;
+            let mut edits = vec![];
+            for &(lifetime, _) in input_uses.iter().chain(&output_uses) {
+                let start = offset(lifetime.span);
+                let mut end = start + (lifetime.span.hi().0 - lifetime.span.lo().0) as usize;
+                end += snippet[end..].len() - snippet[end..].trim_left().len();
+                edits.push((start..end, String::new()));
+            }
+            if !def.in_band {
+                let start = offset(generics.span);
+                let end = start + (generics.span.hi().0 - generics.span.lo().0) as usize;
+                match generic_param_removal_range(&snippet[start..end], generics, index) {
+                    Some(range) => {
+                        edits.push((start + range.start..start + range.end, String::new()));
+                    }
+                    None => continue,
+                }
+            }
+
+            self.tcx
+                .struct_span_lint_node(
+                    lint::builtin::REDUNDANT_LIFETIME,
+                    def.lifetime.id,
+                    def.lifetime.span,
+                    &format!("lifetime parameter `{}` can be elided", def.lifetime.name.name()),
+                )
+                .span_suggestion_with_applicability(
+                    sig_span,
+                    "elide the lifetime",
+                    apply_edits(&snippet, edits),
+                    Applicability::MachineApplicable,
+                )
+                .emit();
+        }
+    }
+
+    /// Lints the lifetime parameters in `generics` that are not used anywhere
+    /// in `all_uses`, suggesting to remove them.
+    fn check_unused_lifetimes(
+        &self,
+        generics: &'tcx hir::Generics,
+        all_uses: &LifetimeUseCollector<'a, 'tcx>,
+    ) {
+        for (index, param) in generics.params.iter().enumerate() {
+            let def = match *param {
+                hir::GenericParam::Lifetime(ref def) => def,
+                _ => continue,
+            };
+            // In-band lifetimes are declared by their uses.
+            if def.in_band {
+                continue;
+            }
+            let def_id = self.tcx.hir.local_def_id(def.lifetime.id);
+            if !all_uses.uses_of(def_id).is_empty() {
+                continue;
+            }
+
+            let mut err = self.tcx.struct_span_lint_node(
+                lint::builtin::UNUSED_LIFETIMES,
+                def.lifetime.id,
+                def.lifetime.span,
+                &format!("lifetime parameter `{}` never used", def.lifetime.name.name()),
+            );
+            let snippet = self.tcx.sess.codemap().span_to_snippet(generics.span);
+            if let Ok(snippet) = snippet {
+                if let Some(range) = generic_param_removal_range(&snippet, generics, index) {
+                    let lo = generics.span.lo();
+                    err.span_suggestion_with_applicability(
+                        generics.span
+                            .with_lo(lo + BytePos(range.start as u32))
+                            .with_hi(lo + BytePos(range.end as u32)),
+                        "remove the unused lifetime parameter",
+                        String::new(),
+                        Applicability::MachineApplicable,
+                    );
+                }
+            }
+            err.emit();
+        }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for LifetimeLintVisitor<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+        NestedVisitorMap::All(&self.tcx.hir)
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item) {
+        // The unused lifetime parameters of structs, enums and unions are
+        // already errors (E0392), so only those of functions and impls are
+        // linted.
+        let redundant = self.is_enabled(lint::builtin::REDUNDANT_LIFETIME, item.id);
+        let unused = self.is_enabled(lint::builtin::UNUSED_LIFETIMES, item.id);
+        match item.node {
+            hir::ItemFn(ref decl, _, _, _, ref generics, _) if redundant || unused => {
+                let mut all_uses = LifetimeUseCollector::new(self.tcx, self.map);
+                intravisit::walk_item(&mut all_uses, item);
+                if redundant {
+                    self.check_redundant_lifetimes(item.span, item.name, generics, decl,
+                                                   &all_uses);
+                }
+                if unused {
+                    self.check_unused_lifetimes(generics, &all_uses);
+                }
+            }
+            hir::ItemImpl(_, _, _, ref generics, _, _, ref impl_item_refs) if unused => {
+                // The lifetimes of an impl can also be used by its items.
+                let mut all_uses = LifetimeUseCollector::new(self.tcx, self.map);
+                intravisit::walk_item(&mut all_uses, item);
+                for impl_item_ref in impl_item_refs {
+                    let impl_item = self.tcx.hir.impl_item(impl_item_ref.id);
+                    intravisit::walk_impl_item(&mut all_uses, impl_item);
+                }
+                self.check_unused_lifetimes(generics, &all_uses);
+            }
+            _ => {}
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    fn visit_trait_item(&mut self, trait_item: &'tcx hir::TraitItem) {
+        let redundant = self.is_enabled(lint::builtin::REDUNDANT_LIFETIME, trait_item.id);
+        if let hir::TraitItemKind::Method(ref sig, _) = trait_item.node {
+            if redundant {
+                let mut all_uses = LifetimeUseCollector::new(self.tcx, self.map);
+                intravisit::walk_trait_item(&mut all_uses, trait_item);
+                self.check_redundant_lifetimes(
+                    trait_item.span,
+                    trait_item.name,
+                    &trait_item.generics,
+                    &sig.decl,
+                    &all_uses,
+                );
+            }
+        }
+        intravisit::walk_trait_item(self, trait_item);
+    }
+
+    fn visit_impl_item(&mut self, impl_item: &'tcx hir::ImplItem) {
+        let redundant = self.is_enabled(lint::builtin::REDUNDANT_LIFETIME, impl_item.id);
+        // The lifetime parameters of a method implementing a trait have to
+        // match those of the trait's method, used or not.
+        let parent = self.tcx.hir.get_parent(impl_item.id);
+        let is_inherent = match self.tcx.hir.expect_item(parent).node {
+            hir::ItemImpl(_, _, _, _, None, _, _) => true,
+            _ => false,
+        };
+        let unused = is_inherent &&
+            self.is_enabled(lint::builtin::UNUSED_LIFETIMES, impl_item.id);
+        if let hir::ImplItemKind::Method(ref sig, _) = impl_item.node {
+            if redundant || unused {
+                let mut all_uses = LifetimeUseCollector::new(self.tcx, self.map);
+                intravisit::walk_impl_item(&mut all_uses, impl_item);
+                if redundant {
+                    self.check_redundant_lifetimes(
+                        impl_item.span,
+                        impl_item.name,
+                        &impl_item.generics,
+                        &sig.decl,
+                        &all_uses,
+                    );
+                }
+                if unused {
+                    self.check_unused_lifetimes(&impl_item.generics, &all_uses);
+                }
+            }
+        }
+        intravisit::walk_impl_item(self, impl_item);
+    }
+}
+
+/// Returns the spans of the references in `ty` whose lifetimes were left
+/// out, or `None` if `ty` has elided lifetimes that are not those of a plain
+/// reference (e.g. in a path, or written as `'_`). Lifetimes in `fn` types
+/// are elided separately and are not considered.
+fn elided_reference_spans(ty: &hir::Ty) -> Option<Vec<Span>> {
+    struct ElidedReferences {
+        spans: Vec<Span>,
+        other_elided: bool,
+    }
+
+    impl<'v> Visitor<'v> for ElidedReferences {
+        fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'v> {
+            NestedVisitorMap::None
+        }
+
+        fn visit_ty(&mut self, ty: &'v hir::Ty) {
+            match ty.node {
+                hir::TyBareFn(..) => return,
+                hir::TyRptr(ref lifetime, ref mt)
+                    if lifetime.name == hir::LifetimeName::Implicit =>
+                {
+                    self.spans.push(ty.span);
+                    self.visit_ty(&mt.ty);
+                    return;
+                }
+                _ => {}
+            }
+            intravisit::walk_ty(self, ty);
+        }
+
+        fn visit_lifetime(&mut self, lifetime: &'v hir::Lifetime) {
+            if lifetime.is_elided() {
+                self.other_elided = true;
+            }
+        }
+    }
+
+    let mut visitor = ElidedReferences {
+        spans: vec![],
+        other_elided: false,
+    };
+    visitor.visit_ty(ty);
+    if visitor.other_elided {
+        None
+    } else {
+        Some(visitor.spans)
+    }
+}
+
 /// Detects late-bound lifetimes and inserts them into
 /// `map.late_bound`.
 ///
@@ -2282,6 +2923,7 @@ fn insert_late_bound_lifetimes(
                     &ty_param.bounds
                 );
             }
+            hir::GenericParam::Const(_) => {}
         }
     }
 
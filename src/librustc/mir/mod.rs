@@ -40,7 +40,6 @@ use std::{iter, mem, u32};
 use std::ops::{Index, IndexMut};
 use std::vec::IntoIter;
 use syntax::ast::{self, Name};
-use syntax::symbol::InternedString;
 use syntax_pos::{Span, DUMMY_SP};
 
 mod cache;
@@ -690,7 +689,7 @@ pub struct Terminator<'tcx> {
     pub kind: TerminatorKind<'tcx>
 }
 
-#[derive(Clone, RustcEncodable, RustcDecodable)]
+#[derive(Clone, PartialEq, RustcEncodable, RustcDecodable)]
 pub enum TerminatorKind<'tcx> {
     /// block should have one successor in the graph; we jump there
     Goto {
@@ -1130,6 +1129,15 @@ impl<'tcx> TerminatorKind<'tcx> {
                     AssertMessage::GeneratorResumedAfterPanic => {
                         write!(fmt, "{:?}", "generator resumed after panicking")?;
                     }
+                    AssertMessage::MisalignedPointerDereference { ref required, ref found } => {
+                        write!(fmt, "{:?}, {:?}, {:?}",
+                               "misaligned pointer dereference: address must be a multiple \
+                                of {} but is {}",
+                               required, found)?;
+                    }
+                    AssertMessage::NullPointerDereference => {
+                        write!(fmt, "{:?}", "null pointer dereference")?;
+                    }
                 }
 
                 write!(fmt, ")")
@@ -1187,7 +1195,7 @@ impl<'tcx> TerminatorKind<'tcx> {
     }
 }
 
-#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+#[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
 pub enum AssertMessage<'tcx> {
     BoundsCheck {
         len: Operand<'tcx>,
@@ -1196,6 +1204,15 @@ pub enum AssertMessage<'tcx> {
     Math(ConstMathErr),
     GeneratorResumedAfterReturn,
     GeneratorResumedAfterPanic,
+    /// The address of a dereferenced raw pointer is not a multiple of the
+    /// alignment of its pointee. Only checked with `-Z mir-emit-alignment-checks`.
+    MisalignedPointerDereference {
+        required: Operand<'tcx>,
+        found: Operand<'tcx>,
+    },
+    /// A dereferenced raw pointer is null. Only checked with
+    /// `-Z mir-emit-alignment-checks`.
+    NullPointerDereference,
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -1223,7 +1240,7 @@ impl<'tcx> Statement<'tcx> {
     }
 }
 
-#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+#[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
 pub enum StatementKind<'tcx> {
     /// Write the RHS Rvalue to the LHS Place.
     Assign(Place<'tcx>, Rvalue<'tcx>),
@@ -1253,6 +1270,12 @@ pub enum StatementKind<'tcx> {
     /// (The starting point(s) arise implicitly from borrows.)
     EndRegion(region::Scope),
 
+    /// Increment the coverage counter `index` of the current function, which
+    /// records that the basic block containing this statement was reached.
+    /// The span of this statement is the code region counted by the counter.
+    /// These statements are only inserted with `-Z instrument-coverage`.
+    Coverage { index: u32 },
+
     /// No-op. Useful for deleting instructions without affecting statement indices.
     Nop,
 }
@@ -1286,7 +1309,7 @@ impl Debug for ValidationOp {
 }
 
 // This is generic so that it can be reused by miri
-#[derive(Clone, RustcEncodable, RustcDecodable)]
+#[derive(Clone, PartialEq, RustcEncodable, RustcDecodable)]
 pub struct ValidationOperand<'tcx, T> {
     pub place: T,
     pub ty: Ty<'tcx>,
@@ -1324,6 +1347,7 @@ impl<'tcx> Debug for Statement<'tcx> {
             InlineAsm { ref asm, ref outputs, ref inputs } => {
                 write!(fmt, "asm!({:?} : {:?} : {:?})", asm, outputs, inputs)
             },
+            Coverage { index } => write!(fmt, "Coverage(counter {})", index),
             Nop => write!(fmt, "nop"),
         }
     }
@@ -1557,7 +1581,7 @@ impl<'tcx> Operand<'tcx> {
 ///////////////////////////////////////////////////////////////////////////
 /// Rvalues
 
-#[derive(Clone, RustcEncodable, RustcDecodable)]
+#[derive(Clone, PartialEq, RustcEncodable, RustcDecodable)]
 pub enum Rvalue<'tcx> {
     /// x (either a move or copy, depending on type of x)
     Use(Operand<'tcx>),
@@ -1874,6 +1898,7 @@ fn fmt_const_val<W: Write>(fmt: &mut W, const_val: &ty::Const) -> fmt::Result {
     match const_val.val {
         Unevaluated(..) => write!(fmt, "{:?}", const_val),
         Value(val) => print_miri_value(val, const_val.ty, fmt),
+        Param(param) => write!(fmt, "{}", param),
     }
 }
 
@@ -1988,10 +2013,86 @@ pub enum UnsafetyViolationKind {
     BorrowPacked(ast::NodeId),
 }
 
+/// The operations which are only allowed in unsafe code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash,
+         RustcEncodable, RustcDecodable)]
+pub enum UnsafetyViolationDetails {
+    CallToUnsafeFunction,
+    UseOfInlineAssembly,
+    ImmovableGeneratorConstruction,
+    DerefOfRawPointer,
+    AccessToUnionField,
+    AssignToNonCopyUnionField,
+    UseOfMutableStatic,
+    UseOfExternStatic,
+    BorrowOfPackedField,
+}
+
+impl UnsafetyViolationDetails {
+    /// A short description of the operation, used as the subject of the
+    /// "requires unsafe" error.
+    pub fn description(&self) -> &'static str {
+        use self::UnsafetyViolationDetails::*;
+        match *self {
+            CallToUnsafeFunction => "call to unsafe function",
+            UseOfInlineAssembly => "use of inline assembly",
+            ImmovableGeneratorConstruction => "construction of immovable generator",
+            DerefOfRawPointer => "dereference of raw pointer",
+            AccessToUnionField => "access to union field",
+            AssignToNonCopyUnionField => "assignment to non-`Copy` union field",
+            UseOfMutableStatic => "use of mutable static",
+            UseOfExternStatic => "use of extern static",
+            BorrowOfPackedField => "borrow of packed field",
+        }
+    }
+
+    /// Why the operation is unsafe, shown as a note on the error.
+    pub fn explanation(&self) -> &'static str {
+        use self::UnsafetyViolationDetails::*;
+        match *self {
+            CallToUnsafeFunction => {
+                "consult the function's documentation for information on how to avoid \
+                 undefined behavior"
+            }
+            UseOfInlineAssembly => {
+                "inline assembly is entirely unchecked and can cause undefined behavior"
+            }
+            ImmovableGeneratorConstruction => {
+                "an immovable generator may hold references into its own state, so moving it \
+                 after it has been resumed is undefined behavior"
+            }
+            DerefOfRawPointer => {
+                "raw pointers may be NULL, dangling or unaligned; they can violate aliasing \
+                 rules and cause data races: all of these are undefined behavior"
+            }
+            AccessToUnionField => {
+                "the field may not be properly initialized: using uninitialized data will \
+                 cause undefined behavior"
+            }
+            AssignToNonCopyUnionField => {
+                "the previous content of the field will be dropped, which causes undefined \
+                 behavior if the field was not properly initialized"
+            }
+            UseOfMutableStatic => {
+                "mutable statics can be mutated by multiple threads: aliasing violations or \
+                 data races will cause undefined behavior"
+            }
+            UseOfExternStatic => {
+                "extern statics are not controlled by the Rust type system: invalid data, \
+                 aliasing violations or data races will cause undefined behavior"
+            }
+            BorrowOfPackedField => {
+                "fields of packed structs might be misaligned: dereferencing a misaligned \
+                 pointer or even just creating a misaligned reference is undefined behavior"
+            }
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
 pub struct UnsafetyViolation {
     pub source_info: SourceInfo,
-    pub description: InternedString,
+    pub details: UnsafetyViolationDetails,
     pub kind: UnsafetyViolationKind,
 }
 
@@ -2002,12 +2103,76 @@ pub struct UnsafetyCheckResult {
     /// unsafe blocks in this function, along with whether they are used. This is
     /// used for the "unused_unsafe" lint.
     pub unsafe_blocks: Lrc<[(ast::NodeId, bool)]>,
+    /// Every kind of unsafe operation performed by this function, including
+    /// its closures, whether or not it happens within unsafe code. Sorted and
+    /// without duplicates.
+    pub used_operations: Lrc<[UnsafetyViolationDetails]>,
+}
+
+/// The coverage counters of a function instrumented with
+/// `-Z instrument-coverage`, along with the source code counted by each of
+/// them. This is emitted into the crate metadata, so that coverage reports
+/// can map the counters collected at runtime back to the source code.
+#[derive(Clone, Debug, Default, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct CoverageInfo {
+    /// Identifies this version of the function to the profiler runtime.
+    pub function_hash: u64,
+    /// The region counted by each counter, indexed by counter.
+    pub regions: Vec<CodeRegion>,
+}
+
+impl CoverageInfo {
+    pub fn num_counters(&self) -> u32 {
+        self.regions.len() as u32
+    }
+}
+
+/// A region of source code, with 1-based lines and columns. The end is
+/// exclusive.
+#[derive(Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct CodeRegion {
+    pub file_name: String,
+    pub start_line: u32,
+    pub start_col: u32,
+    pub end_line: u32,
+    pub end_col: u32,
+}
+
+impl fmt::Display for CodeRegion {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "{}:{}:{} - {}:{}",
+               self.file_name, self.start_line, self.start_col, self.end_line, self.end_col)
+    }
 }
 
 /// The layout of generator state
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct GeneratorLayout<'tcx> {
     pub fields: Vec<LocalDecl<'tcx>>,
+
+    /// For each field, the types of the other saved locals stored in it,
+    /// where they differ from the type of the field. The field is large
+    /// and aligned enough for all of them.
+    pub shared_tys: Vec<Vec<Ty<'tcx>>>,
+}
+
+/// The result of the MIR borrow checker for a body. Its errors are reported
+/// as a side effect of the `mir_borrowck` query, and replayed from the
+/// incremental cache along with it when the body didn't change.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct BorrowCheckResult<'gcx> {
+    /// The requirements on the free regions of the creator, if the body is
+    /// a closure.
+    pub closure_requirements: Option<ClosureRegionRequirements<'gcx>>,
+
+    /// The user variables which are reassigned, mutated through a
+    /// projection or mutably borrowed, sorted.
+    pub used_mut_locals: Vec<Local>,
+
+    /// The by-value upvars which are reassigned, mutated through a
+    /// projection or mutably borrowed, if the body is a closure, sorted.
+    /// The creator uses them to mark the captured variables as used `mut`.
+    pub used_mut_upvars: Vec<Field>,
 }
 
 /// After we borrow check a closure, we are left with various
@@ -2142,11 +2307,12 @@ impl<'tcx> TypeFoldable<'tcx> for GeneratorLayout<'tcx> {
     fn super_fold_with<'gcx: 'tcx, F: TypeFolder<'gcx, 'tcx>>(&self, folder: &mut F) -> Self {
         GeneratorLayout {
             fields: self.fields.fold_with(folder),
+            shared_tys: self.shared_tys.fold_with(folder),
         }
     }
 
     fn super_visit_with<V: TypeVisitor<'tcx>>(&self, visitor: &mut V) -> bool {
-        self.fields.visit_with(visitor)
+        self.fields.visit_with(visitor) || self.shared_tys.visit_with(visitor)
     }
 }
 
@@ -2220,6 +2386,8 @@ impl<'tcx> TypeFoldable<'tcx> for Statement<'tcx> {
                 Validate(op.clone(),
                          places.iter().map(|operand| operand.fold_with(folder)).collect()),
 
+            Coverage { index } => Coverage { index },
+
             Nop => Nop,
         };
         Statement {
@@ -2248,6 +2416,7 @@ impl<'tcx> TypeFoldable<'tcx> for Statement<'tcx> {
             Validate(ref _op, ref places) =>
                 places.iter().any(|ty_and_place| ty_and_place.visit_with(visitor)),
 
+            Coverage { .. } |
             Nop => false,
         }
     }
@@ -2294,13 +2463,20 @@ impl<'tcx> TypeFoldable<'tcx> for Terminator<'tcx> {
                 }
             },
             Assert { ref cond, expected, ref msg, target, cleanup } => {
-                let msg = if let AssertMessage::BoundsCheck { ref len, ref index } = *msg {
-                    AssertMessage::BoundsCheck {
-                        len: len.fold_with(folder),
-                        index: index.fold_with(folder),
+                let msg = match *msg {
+                    AssertMessage::BoundsCheck { ref len, ref index } => {
+                        AssertMessage::BoundsCheck {
+                            len: len.fold_with(folder),
+                            index: index.fold_with(folder),
+                        }
                     }
-                } else {
-                    msg.clone()
+                    AssertMessage::MisalignedPointerDereference { ref required, ref found } => {
+                        AssertMessage::MisalignedPointerDereference {
+                            required: required.fold_with(folder),
+                            found: found.fold_with(folder),
+                        }
+                    }
+                    _ => msg.clone(),
                 };
                 Assert {
                     cond: cond.fold_with(folder),
@@ -2344,10 +2520,14 @@ impl<'tcx> TypeFoldable<'tcx> for Terminator<'tcx> {
             },
             Assert { ref cond, ref msg, .. } => {
                 if cond.visit_with(visitor) {
-                    if let AssertMessage::BoundsCheck { ref len, ref index } = *msg {
-                        len.visit_with(visitor) || index.visit_with(visitor)
-                    } else {
-                        false
+                    match *msg {
+                        AssertMessage::BoundsCheck { ref len, ref index } => {
+                            len.visit_with(visitor) || index.visit_with(visitor)
+                        }
+                        AssertMessage::MisalignedPointerDereference { ref required, ref found } => {
+                            required.visit_with(visitor) || found.visit_with(visitor)
+                        }
+                        _ => false,
                     }
                 } else {
                     false
@@ -2533,3 +2713,34 @@ impl<'tcx> TypeFoldable<'tcx> for Literal<'tcx> {
         }
     }
 }
+
+#[test]
+fn closure_region_requirements_encode_decode() {
+    use rustc_serialize::{opaque, Decodable, Encodable};
+    use std::io::Cursor;
+    use syntax_pos::{BytePos, NO_EXPANSION};
+
+    let requirements: ClosureRegionRequirements = ClosureRegionRequirements {
+        num_external_vids: 3,
+        outlives_requirements: vec![
+            ClosureOutlivesRequirement {
+                subject: ClosureOutlivesSubject::Region(ty::RegionVid::new(2)),
+                outlived_free_region: ty::RegionVid::new(1),
+                blame_span: Span::new(BytePos(4), BytePos(12), NO_EXPANSION),
+            },
+            ClosureOutlivesRequirement {
+                subject: ClosureOutlivesSubject::Region(ty::RegionVid::new(1)),
+                outlived_free_region: ty::RegionVid::new(0),
+                blame_span: DUMMY_SP,
+            },
+        ],
+    };
+
+    let mut cursor = Cursor::new(Vec::new());
+    requirements.encode(&mut opaque::Encoder::new(&mut cursor)).unwrap();
+    let data = cursor.into_inner();
+    let decoded: ClosureRegionRequirements =
+        Decodable::decode(&mut opaque::Decoder::new(&data, 0)).unwrap();
+
+    assert_eq!(format!("{:?}", decoded), format!("{:?}", requirements));
+}
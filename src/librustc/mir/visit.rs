@@ -376,6 +376,7 @@ macro_rules! make_mir_visitor {
                             self.visit_operand(input, location);
                         }
                     }
+                    StatementKind::Coverage { .. } => {}
                     StatementKind::Nop => {}
                 }
             }
@@ -511,6 +512,14 @@ macro_rules! make_mir_visitor {
                     AssertMessage::Math(_) => {},
                     AssertMessage::GeneratorResumedAfterReturn => {},
                     AssertMessage::GeneratorResumedAfterPanic => {},
+                    AssertMessage::MisalignedPointerDereference {
+                        ref $($mutability)* required,
+                        ref $($mutability)* found
+                    } => {
+                        self.visit_operand(required, location);
+                        self.visit_operand(found, location);
+                    }
+                    AssertMessage::NullPointerDereference => {},
                 }
             }
 
@@ -0,0 +1,129 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+//! Bookkeeping for `-Z borrowck=compare`, which runs both the AST and the MIR
+//! borrow checker and, with `-Z borrowck-compare-report`, reports the errors
+//! which only one of them found.
+
+use errors::{Diagnostic, DiagnosticId};
+use syntax::codemap::CodeMap;
+use syntax_pos::Span;
+
+use serialize::json;
+
+/// Which borrow checker reported an error.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BorrowckKind {
+    Ast,
+    Mir,
+}
+
+#[derive(Clone, Debug)]
+struct BorrowckError {
+    code: Option<String>,
+    span: Option<Span>,
+    message: String,
+}
+
+impl BorrowckError {
+    /// Two errors are considered the same if they have the same code and the
+    /// same primary span; their messages are worded differently by each
+    /// borrow checker.
+    fn matches(&self, other: &BorrowckError) -> bool {
+        self.code == other.code && self.span == other.span
+    }
+}
+
+#[derive(RustcEncodable)]
+struct ReportedError {
+    code: Option<String>,
+    location: String,
+    message: String,
+}
+
+#[derive(RustcEncodable)]
+struct Report {
+    krate: String,
+    common: usize,
+    ast_only: Vec<ReportedError>,
+    mir_only: Vec<ReportedError>,
+}
+
+pub struct BorrowckComparison {
+    ast_errors: Vec<BorrowckError>,
+    mir_errors: Vec<BorrowckError>,
+}
+
+impl BorrowckComparison {
+    pub fn new() -> Self {
+        BorrowckComparison { ast_errors: vec![], mir_errors: vec![] }
+    }
+
+    pub fn record(&mut self, kind: BorrowckKind, diag: &Diagnostic) {
+        let error = BorrowckError {
+            code: match diag.code {
+                Some(DiagnosticId::Error(ref code)) |
+                Some(DiagnosticId::Lint(ref code)) => Some(code.clone()),
+                None => None,
+            },
+            span: diag.span.primary_span(),
+            message: diag.message(),
+        };
+        match kind {
+            BorrowckKind::Ast => self.ast_errors.push(error),
+            BorrowckKind::Mir => self.mir_errors.push(error),
+        }
+    }
+
+    /// Prints the errors found by only one of the borrow checkers, either as
+    /// plain text or, if `json` is set, as a single JSON object.
+    pub fn print_report(&self, codemap: &CodeMap, krate: &str, json: bool) {
+        let unique = |errors: &[BorrowckError], others: &[BorrowckError]| {
+            let mut unique: Vec<_> = errors.iter()
+                .filter(|error| !others.iter().any(|other| error.matches(other)))
+                .map(|error| ReportedError {
+                    code: error.code.clone(),
+                    location: error.span.map_or(String::from("<unknown>"),
+                                                |span| codemap.span_to_string(span)),
+                    message: error.message.clone(),
+                })
+                .collect();
+            unique.sort_by(|a, b| a.location.cmp(&b.location));
+            unique
+        };
+        let ast_only = unique(&self.ast_errors, &self.mir_errors);
+        let mir_only = unique(&self.mir_errors, &self.ast_errors);
+        let report = Report {
+            krate: krate.to_string(),
+            common: self.ast_errors.len() - ast_only.len(),
+            ast_only,
+            mir_only,
+        };
+
+        if json {
+            println!("{}", json::as_json(&report));
+            return;
+        }
+
+        println!("borrowck-compare: crate `{}`: {} common, {} AST-only, {} MIR-only",
+                 report.krate, report.common, report.ast_only.len(), report.mir_only.len());
+        for &(kind, ref errors) in &[("AST-only", &report.ast_only),
+                                     ("MIR-only", &report.mir_only)] {
+            for error in errors.iter() {
+                println!("borrowck-compare: {} {}: {}: {}",
+                         kind,
+                         error.code.as_ref().map_or("-", |code| &code[..]),
+                         error.location,
+                         error.message);
+            }
+        }
+    }
+}
@@ -93,6 +93,26 @@ pub enum Lto {
     Fat,
 }
 
+#[derive(Clone, PartialEq, Hash)]
+pub enum LinkerPluginLto {
+    /// Emit LLVM bitcode in object files and let the linker plugin, at the
+    /// given path, perform LTO.
+    LinkerPlugin(PathBuf),
+    /// Same, with the linker plugin found by the linker itself.
+    LinkerPluginAuto,
+    Disabled,
+}
+
+impl LinkerPluginLto {
+    pub fn enabled(&self) -> bool {
+        match *self {
+            LinkerPluginLto::LinkerPlugin(_) |
+            LinkerPluginLto::LinkerPluginAuto => true,
+            LinkerPluginLto::Disabled => false,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Hash)]
 pub enum DebugInfoLevel {
     NoDebugInfo,
@@ -106,6 +126,7 @@ pub enum OutputType {
     Assembly,
     LlvmAssembly,
     Mir,
+    MirJson,
     Metadata,
     Object,
     Exe,
@@ -118,6 +139,7 @@ impl_stable_hash_for!(enum self::OutputType {
     Assembly,
     LlvmAssembly,
     Mir,
+    MirJson,
     Metadata,
     Object,
     Exe,
@@ -140,6 +162,7 @@ impl OutputType {
             | OutputType::Assembly
             | OutputType::LlvmAssembly
             | OutputType::Mir
+            | OutputType::MirJson
             | OutputType::Object
             | OutputType::Metadata => false,
         }
@@ -151,6 +174,7 @@ impl OutputType {
             OutputType::Assembly => "asm",
             OutputType::LlvmAssembly => "llvm-ir",
             OutputType::Mir => "mir",
+            OutputType::MirJson => "mir-json",
             OutputType::Object => "obj",
             OutputType::Metadata => "metadata",
             OutputType::Exe => "link",
@@ -163,6 +187,7 @@ impl OutputType {
             "asm" => OutputType::Assembly,
             "llvm-ir" => OutputType::LlvmAssembly,
             "mir" => OutputType::Mir,
+            "mir-json" => OutputType::MirJson,
             "llvm-bc" => OutputType::Bitcode,
             "obj" => OutputType::Object,
             "metadata" => OutputType::Metadata,
@@ -174,11 +199,12 @@ impl OutputType {
 
     fn shorthands_display() -> String {
         format!(
-            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
+            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
             OutputType::Bitcode.shorthand(),
             OutputType::Assembly.shorthand(),
             OutputType::LlvmAssembly.shorthand(),
             OutputType::Mir.shorthand(),
+            OutputType::MirJson.shorthand(),
             OutputType::Object.shorthand(),
             OutputType::Metadata.shorthand(),
             OutputType::Exe.shorthand(),
@@ -192,6 +218,7 @@ impl OutputType {
             OutputType::Assembly => "s",
             OutputType::LlvmAssembly => "ll",
             OutputType::Mir => "mir",
+            OutputType::MirJson => "mir.json",
             OutputType::Object => "o",
             OutputType::Metadata => "rmeta",
             OutputType::DepInfo => "d",
@@ -253,6 +280,7 @@ impl OutputTypes {
             | OutputType::Assembly
             | OutputType::LlvmAssembly
             | OutputType::Mir
+            | OutputType::MirJson
             | OutputType::Object
             | OutputType::Exe => true,
             OutputType::Metadata | OutputType::DepInfo => false,
@@ -751,12 +779,16 @@ macro_rules! options {
         pub const parse_opt_string: Option<&'static str> = Some("a string");
         pub const parse_opt_pathbuf: Option<&'static str> = Some("a path");
         pub const parse_list: Option<&'static str> = Some("a space-separated list of strings");
+        pub const parse_list_with_polarity: Option<&'static str> =
+            Some("a comma-separated list of strings, with elements beginning with + or -");
         pub const parse_opt_list: Option<&'static str> = Some("a space-separated list of strings");
         pub const parse_uint: Option<&'static str> = Some("a number");
         pub const parse_passes: Option<&'static str> =
             Some("a space-separated list of passes, or `all`");
         pub const parse_opt_uint: Option<&'static str> =
             Some("a number");
+        pub const parse_treat_err_as_bug: Option<&'static str> =
+            Some("either no value or a number bigger than 0");
         pub const parse_panic_strategy: Option<&'static str> =
             Some("either `panic` or `abort`");
         pub const parse_relro_level: Option<&'static str> =
@@ -771,13 +803,17 @@ macro_rules! options {
             Some("`string` or `string=string`");
         pub const parse_lto: Option<&'static str> =
             Some("one of `thin`, `fat`, or omitted");
+        pub const parse_linker_plugin_lto: Option<&'static str> =
+            Some("either a boolean (`yes`, `no`, `on`, `off`, etc), \
+                  or the path to the linker plugin");
         pub const parse_epoch: Option<&'static str> =
             Some("one of: `2015`, `2018`");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
-        use super::{$struct_name, Passes, SomePasses, AllPasses, Sanitizer, Lto};
+        use super::{$struct_name, Passes, SomePasses, AllPasses, Sanitizer, Lto,
+                    LinkerPluginLto};
         use rustc_back::{LinkerFlavor, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
         use syntax::epoch::Epoch;
@@ -862,6 +898,29 @@ macro_rules! options {
             }
         }
 
+        fn parse_list_with_polarity(slot: &mut Vec<(String, bool)>, v: Option<&str>) -> bool {
+            match v {
+                Some(s) => {
+                    for s in s.split(",") {
+                        let enabled = match s.chars().next() {
+                            Some('+') => true,
+                            Some('-') => false,
+                            _ => return false,
+                        };
+                        let name = &s[1..];
+                        if name.is_empty() {
+                            return false;
+                        }
+                        // Later occurrences override earlier ones.
+                        slot.retain(|&(ref n, _)| n != name);
+                        slot.push((name.to_string(), enabled));
+                    }
+                    true
+                },
+                None => false,
+            }
+        }
+
         fn parse_opt_list(slot: &mut Option<Vec<String>>, v: Option<&str>)
                       -> bool {
             match v {
@@ -888,6 +947,16 @@ macro_rules! options {
             }
         }
 
+        fn parse_treat_err_as_bug(slot: &mut Option<usize>, v: Option<&str>) -> bool {
+            match v {
+                Some(s) => match s.parse() {
+                    Ok(0) | Err(_) => { *slot = None; false }
+                    Ok(n) => { *slot = Some(n); true }
+                },
+                None => { *slot = Some(1); true }
+            }
+        }
+
         fn parse_passes(slot: &mut Passes, v: Option<&str>) -> bool {
             match v {
                 Some("all") => {
@@ -983,6 +1052,26 @@ macro_rules! options {
             true
         }
 
+        fn parse_linker_plugin_lto(slot: &mut LinkerPluginLto, v: Option<&str>) -> bool {
+            if v.is_some() {
+                let mut bool_arg = None;
+                if parse_opt_bool(&mut bool_arg, v) {
+                    *slot = if bool_arg.unwrap() {
+                        LinkerPluginLto::LinkerPluginAuto
+                    } else {
+                        LinkerPluginLto::Disabled
+                    };
+                    return true
+                }
+            }
+
+            *slot = match v {
+                None => LinkerPluginLto::LinkerPluginAuto,
+                Some(path) => LinkerPluginLto::LinkerPlugin(PathBuf::from(path)),
+            };
+            true
+        }
+
         fn parse_epoch(slot: &mut Epoch, v: Option<&str>) -> bool {
             match v {
                 Some(s) => {
@@ -1015,6 +1104,10 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
         "don't let linker strip dead code (turning it on can be used for code coverage)"),
     lto: Lto = (Lto::No, parse_lto, [TRACKED],
         "perform LLVM link-time optimizations"),
+    linker_plugin_lto: LinkerPluginLto = (LinkerPluginLto::Disabled,
+        parse_linker_plugin_lto, [TRACKED],
+        "emit LLVM bitcode in object files and let the linker plugin perform LTO, \
+         e.g. to inline across languages"),
     target_cpu: Option<String> = (None, parse_opt_string, [TRACKED],
         "select target processor (rustc --print target-cpus for details)"),
     target_feature: String = ("".to_string(), parse_string, [TRACKED],
@@ -1089,6 +1182,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "emit EndRegion as part of MIR; enable transforms that solely process EndRegion"),
     borrowck: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "select which borrowck is used (`ast`, `mir`, or `compare`)"),
+    borrowck_compare_report: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "with `-Z borrowck=compare`, print the errors found by only one borrow checker \
+         (`text` or `json`)"),
+    borrowck_tool_errors: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "with `-Z borrowck=compare`, select the borrowck whose errors are kept when both report \
+         the same problem in the JSON and save-analysis output (`ast` or `mir`, default: `ast`)"),
     two_phase_borrows: bool = (false, parse_bool, [UNTRACKED],
         "use two-phase reserved/active distinction for `&mut` borrows in MIR borrowck"),
     two_phase_beyond_autoref: bool = (false, parse_bool, [UNTRACKED],
@@ -1152,10 +1251,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "parse only; do not compile, assemble, or link"),
     no_trans: bool = (false, parse_bool, [TRACKED],
           "run all passes except translation; no output"),
-    treat_err_as_bug: bool = (false, parse_bool, [TRACKED],
-          "treat all errors that occur as bugs"),
+    treat_err_as_bug: Option<usize> = (None, parse_treat_err_as_bug, [TRACKED],
+          "treat the `n`th error that occurs as a bug (the first one if no `n` is given)"),
     external_macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
           "show macro backtraces even for non-local macros"),
+    deduplicate_diagnostics: Option<bool> = (None, parse_opt_bool, [UNTRACKED],
+          "collapse long runs of errors of the same kind into a note (default: no)"),
     teach: bool = (false, parse_bool, [TRACKED],
           "show extended diagnostic help"),
     continue_parse_after_error: bool = (false, parse_bool, [TRACKED],
@@ -1203,32 +1304,67 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
           "print layout information for each type encountered"),
     print_trans_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
-          "print the result of the translation item collection pass"),
+          "print the result of the translation item collection pass \
+           (lazy|eager|verbose)"),
+    mono_instance_limit: Option<usize> = (None, parse_opt_uint, [TRACKED],
+          "warn about generic functions with more instances than this"),
+    move_size_limit: Option<usize> = (None, parse_opt_uint, [TRACKED],
+          "warn about moves of values larger than this many bytes"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
           "set the MIR optimization level (0-3, default: 1)"),
+    mir_enable_passes: Vec<(String, bool)> = (Vec::new(), parse_list_with_polarity, [TRACKED],
+          "use like `-Zmir-enable-passes=+Inline,-ConstProp`. Forces the specified passes to be \
+           enabled, overriding all other checks. Passes that are not specified are enabled or \
+           disabled by other flags as usual."),
     mutable_noalias: bool = (false, parse_bool, [UNTRACKED],
           "emit noalias metadata for mutable references"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
-          "dump MIR state at various points in translation"),
+          "dump MIR state at various points in translation; `&`/`|`-separated filters \
+           match pass names or item paths, `path:TEXT` only item paths containing `TEXT`, \
+           and `item=PATH` the item with exactly that path and its closures"),
     dump_mir_dir: String = (String::from("mir_dump"), parse_string, [UNTRACKED],
           "the directory the MIR is dumped into"),
     dump_mir_graphviz: bool = (false, parse_bool, [UNTRACKED],
           "in addition to `.mir` files, create graphviz `.dot` files"),
+    dump_mir_spanview: bool = (false, parse_bool, [UNTRACKED],
+          "in addition to `.mir` files, create `.html` files highlighting the source code \
+           covered by each MIR statement"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
           "if set, exclude the pass number when dumping MIR (used in tests)"),
+    validate_mir: bool = (false, parse_bool, [UNTRACKED],
+          "validate the structural invariants of MIR after each MIR pass"),
     mir_emit_validate: usize = (0, parse_uint, [TRACKED],
           "emit Validate MIR statements, interpreted e.g. by miri (0: do not emit; 1: if function \
            contains unsafe block, only validate arguments; 2: always emit full validation)"),
+    mir_emit_alignment_checks: bool = (false, parse_bool, [TRACKED],
+          "check that raw pointers are non-null and aligned before they are dereferenced, \
+           panicking otherwise"),
     perf_stats: bool = (false, parse_bool, [UNTRACKED],
           "print some performance-related statistics"),
+    self_profile: bool = (false, parse_bool, [UNTRACKED],
+          "record query executions, cache hits and pass timings, and write them out \
+           along with a summary"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
           "print some statistics about AST and HIR"),
     mir_stats: bool = (false, parse_bool, [UNTRACKED],
           "print some statistics about MIR"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
           "encode MIR of all functions into the crate metadata"),
+    mir_only_rlibs: bool = (false, parse_bool, [TRACKED],
+          "build rlibs with the MIR of all functions instead of their code, which \
+           downstream crates generate from the MIR (only when rlib is the only crate type)"),
     miri: bool = (false, parse_bool, [TRACKED],
           "check the miri const evaluator against the old ctfe"),
+    unleash_the_miri: bool = (false, parse_bool, [TRACKED],
+          "allow the const evaluator to do anything it can, like calling non-const fns, \
+           emitting a warning for each restriction of constants which isn't enforced"),
+    interpret_main: bool = (false, parse_bool, [UNTRACKED],
+          "interpret the MIR of the whole program, starting at `main`, after analysis, \
+           and report undefined behavior and unsupported operations as errors"),
+    interpret_main_step_limit: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+          "the number of MIR statements `-Z interpret-main` may execute (default: 1000000)"),
+    interpret_main_stack_limit: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+          "the number of stack frames `-Z interpret-main` may push (default: 100)"),
     osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
           "pass `-install_name @rpath/...` to the macOS linker"),
     sanitizer: Option<Sanitizer> = (None, parse_sanitizer, [TRACKED],
@@ -1247,10 +1383,15 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "extra arguments to prepend to the linker invocation (space separated)"),
     profile: bool = (false, parse_bool, [TRACKED],
                      "insert profiling code"),
+    instrument_coverage: bool = (false, parse_bool, [TRACKED],
+        "instrument the MIR of every function with a coverage counter per basic block, \
+         and link the profiler runtime to collect them"),
     relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED],
         "choose which RELRO level to use"),
     nll: bool = (false, parse_bool, [UNTRACKED],
                  "run the non-lexical lifetimes MIR pass"),
+    nll_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print a summary of what the non-lexical lifetimes borrow checker found in the crate"),
     trans_time_graph: bool = (false, parse_bool, [UNTRACKED],
         "generate a graphical HTML report of time spent in trans and LLVM"),
     thinlto: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1271,8 +1412,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     dep_info_omit_d_target: bool = (false, parse_bool, [TRACKED],
         "in dep-info output, omit targets for tracking dependencies of the dep-info files \
          themselves"),
-    approximate_suggestions: bool = (false, parse_bool, [UNTRACKED],
+    suggestion_applicability: bool = (false, parse_bool, [UNTRACKED],
         "include machine-applicability of suggestions in JSON output"),
+    approximate_suggestions: bool = (false, parse_bool, [UNTRACKED],
+        "alias for `-Z suggestion-applicability`"),
     unpretty: Option<String> = (None, parse_unpretty, [UNTRACKED],
         "Present the input source, unstable (and less-pretty) variants;
         valid types are any of the types for `--pretty`, as well as:
@@ -1288,6 +1431,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "run `dsymutil` and delete intermediate object files"),
     ui_testing: bool = (false, parse_bool, [UNTRACKED],
           "format compiler diagnostics in a way that's better suitable for UI testing"),
+    chalk: bool = (false, parse_bool, [TRACKED],
+          "solve trait obligations with the experimental Chalk-style solver first, \
+           falling back to the regular trait selection for goals it doesn't support"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -1782,6 +1928,12 @@ pub fn build_session_options_and_crate_config(
                         ),
                     ),
                 };
+                if output_type == OutputType::MirJson && !debugging_opts.unstable_options {
+                    early_error(
+                        error_format,
+                        "--emit=mir-json is unstable, pass -Z unstable-options to use it",
+                    );
+                }
                 let path = parts.next().map(PathBuf::from);
                 output_types.insert(output_type, path);
             }
@@ -1870,6 +2022,24 @@ pub fn build_session_options_and_crate_config(
         );
     }
 
+    // The dependency graph records the reads of the current task in a single
+    // stack, which queries running on several threads would interleave.
+    let builds_dep_graph = incremental.is_some() || debugging_opts.dump_dep_graph ||
+        debugging_opts.query_dep_graph;
+    if debugging_opts.query_threads.map_or(false, |n| n > 1) && builds_dep_graph {
+        early_error(
+            error_format,
+            "can't execute queries on several threads while building the dependency graph",
+        );
+    }
+
+    if cg.lto != Lto::No && cg.linker_plugin_lto.enabled() {
+        early_error(
+            error_format,
+            "options `-C lto` and `-C linker-plugin-lto` are incompatible",
+        );
+    }
+
     let mut prints = Vec::<PrintRequest>::new();
     if cg.target_cpu.as_ref().map_or(false, |s| s == "help") {
         prints.push(PrintRequest::TargetCPUs);
@@ -2050,6 +2220,11 @@ pub fn build_session_options_and_crate_config(
         Some(m) => early_error(error_format, &format!("unknown borrowck mode `{}`", m)),
     };
 
+    match debugging_opts.borrowck_tool_errors.as_ref().map(|s| &s[..]) {
+        None | Some("ast") | Some("mir") => {}
+        Some(m) => early_error(error_format, &format!("unknown borrowck for tool errors `{}`", m)),
+    }
+
     if !cg.remark.is_empty() && debuginfo == NoDebugInfo {
         early_warn(
             error_format,
@@ -2257,7 +2432,7 @@ mod dep_tracking {
     use std::path::PathBuf;
     use std::collections::hash_map::DefaultHasher;
     use super::{CrateType, DebugInfoLevel, Epoch, ErrorOutputType, Lto, OptLevel, OutputTypes,
-                Passes, Sanitizer};
+                Passes, Sanitizer, LinkerPluginLto};
     use syntax::feature_gate::UnstableFeatures;
     use rustc_back::{PanicStrategy, RelroLevel};
 
@@ -2312,6 +2487,7 @@ mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(Passes);
     impl_dep_tracking_hash_via_hash!(OptLevel);
     impl_dep_tracking_hash_via_hash!(Lto);
+    impl_dep_tracking_hash_via_hash!(LinkerPluginLto);
     impl_dep_tracking_hash_via_hash!(DebugInfoLevel);
     impl_dep_tracking_hash_via_hash!(UnstableFeatures);
     impl_dep_tracking_hash_via_hash!(OutputTypes);
@@ -2330,6 +2506,7 @@ mod dep_tracking {
         Option<cstore::NativeLibraryKind>
     ));
     impl_dep_tracking_hash_for_sortable_vec_of!((String, u64));
+    impl_dep_tracking_hash_for_sortable_vec_of!((String, bool));
 
     impl<T1, T2> DepTrackingHash for (T1, T2)
     where
@@ -2383,7 +2560,7 @@ mod tests {
     use lint;
     use middle::cstore;
     use session::config::{build_configuration, build_session_options_and_crate_config};
-    use session::config::Lto;
+    use session::config::{Lto, LinkerPluginLto};
     use session::build_session;
     use std::collections::{BTreeMap, BTreeSet};
     use std::iter::FromIterator;
@@ -2812,6 +2989,10 @@ mod tests {
         opts.cg.lto = Lto::Fat;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+        opts = reference.clone();
+        opts.cg.linker_plugin_lto = LinkerPluginLto::LinkerPluginAuto;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
         opts = reference.clone();
         opts.cg.target_cpu = Some(String::from("abc"));
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
@@ -2907,6 +3088,12 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.time_passes = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.self_profile = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.interpret_main = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.interpret_main_step_limit = Some(10);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.count_llvm_insns = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.time_llvm_passes = true;
@@ -2967,6 +3154,10 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_mir_spanview = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.validate_mir = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
@@ -2990,7 +3181,7 @@ mod tests {
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
         opts = reference.clone();
-        opts.debugging_opts.treat_err_as_bug = true;
+        opts.debugging_opts.treat_err_as_bug = Some(1);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
         opts = reference.clone();
@@ -3017,8 +3208,40 @@ mod tests {
         opts.debugging_opts.mir_opt_level = 3;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+        opts = reference.clone();
+        opts.debugging_opts.mir_enable_passes = vec![(String::from("Inline"), true)];
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
         opts = reference.clone();
         opts.debugging_opts.relro_level = Some(RelroLevel::Full);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.instrument_coverage = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.mir_emit_alignment_checks = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.mir_only_rlibs = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.mono_instance_limit = Some(10);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.move_size_limit = Some(1000);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.unleash_the_miri = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.chalk = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
     }
 }
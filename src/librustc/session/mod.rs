@@ -10,8 +10,9 @@
 
 pub use self::code_stats::{CodeStats, DataTypeKind, FieldInfo};
 pub use self::code_stats::{SizeKind, TypeSizeInfo, VariantInfo};
+pub use self::borrowck_comparison::{BorrowckComparison, BorrowckKind};
 
-use hir::def_id::CrateNum;
+use hir::def_id::{CrateNum, DefId};
 use ich::Fingerprint;
 
 use ich;
@@ -19,14 +20,16 @@ use lint;
 use lint::builtin::BuiltinLintDiagnostics;
 use middle::allocator::AllocatorKind;
 use middle::dependency_format;
+use mir::Promoted;
 use session::search_paths::PathKind;
 use session::config::{DebugInfoLevel, OutputType};
 use ty::tls;
 use util::nodemap::{FxHashMap, FxHashSet};
 use util::common::{duration_to_secs_str, ErrorReported};
 use util::common::ProfileQueriesMsg;
+use util::profiling::{self, SelfProfiler};
 
-use rustc_data_structures::sync::{Lrc, Lock};
+use rustc_data_structures::sync::{Lrc, Lock, LockCell, OneThread, Once, RwLock};
 
 use syntax::ast::NodeId;
 use errors::{self, DiagnosticBuilder, DiagnosticId};
@@ -46,16 +49,19 @@ use rustc_back::target::Target;
 use rustc_data_structures::flock;
 use jobserver::Client;
 
-use std::cell::{self, Cell, RefCell};
+use std::cell::{self, RefCell};
 use std::collections::HashMap;
 use std::env;
 use std::fmt;
+use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::process;
 use std::sync::{Once, ONCE_INIT};
 use std::time::Duration;
 use std::sync::mpsc;
 
+mod borrowck_comparison;
 mod code_stats;
 pub mod config;
 pub mod filesearch;
@@ -69,10 +75,10 @@ pub struct Session {
     pub opts: config::Options,
     pub parse_sess: ParseSess,
     /// For a library crate, this is always none
-    pub entry_fn: RefCell<Option<(NodeId, Span)>>,
-    pub entry_type: Cell<Option<config::EntryFnType>>,
-    pub plugin_registrar_fn: Cell<Option<ast::NodeId>>,
-    pub derive_registrar_fn: Cell<Option<ast::NodeId>>,
+    pub entry_fn: Lock<Option<(NodeId, Span)>>,
+    pub entry_type: LockCell<Option<config::EntryFnType>>,
+    pub plugin_registrar_fn: LockCell<Option<ast::NodeId>>,
+    pub derive_registrar_fn: LockCell<Option<ast::NodeId>>,
     pub default_sysroot: Option<PathBuf>,
     /// The name of the root source file of the crate, in the local file system.
     /// `None` means that there is no source file.
@@ -80,50 +86,51 @@ pub struct Session {
     /// The directory the compiler has been executed in plus a flag indicating
     /// if the value stored here has been affected by path remapping.
     pub working_dir: (PathBuf, bool),
-    pub lint_store: RefCell<lint::LintStore>,
-    pub buffered_lints: RefCell<Option<lint::LintBuffer>>,
+    pub lint_store: RwLock<lint::LintStore>,
+    pub buffered_lints: Lock<Option<lint::LintBuffer>>,
     /// Set of (DiagnosticId, Option<Span>, message) tuples tracking
     /// (sub)diagnostics that have been set once, but should not be set again,
     /// in order to avoid redundantly verbose output (Issue #24690, #44953).
-    pub one_time_diagnostics: RefCell<FxHashSet<(DiagnosticMessageId, Option<Span>, String)>>,
-    pub plugin_llvm_passes: RefCell<Vec<String>>,
-    pub plugin_attributes: RefCell<Vec<(String, AttributeType)>>,
-    pub crate_types: RefCell<Vec<config::CrateType>>,
-    pub dependency_formats: RefCell<dependency_format::Dependencies>,
+    pub one_time_diagnostics: Lock<FxHashSet<(DiagnosticMessageId, Option<Span>, String)>>,
+    pub plugin_llvm_passes: Lock<Vec<String>>,
+    pub plugin_attributes: Lock<Vec<(String, AttributeType)>>,
+    pub crate_types: RwLock<Vec<config::CrateType>>,
+    pub dependency_formats: RwLock<dependency_format::Dependencies>,
     /// The crate_disambiguator is constructed out of all the `-C metadata`
     /// arguments passed to the compiler. Its value together with the crate-name
     /// forms a unique global identifier for the crate. It is used to allow
     /// multiple crates with the same name to coexist. See the
     /// trans::back::symbol_names module for more information.
-    pub crate_disambiguator: RefCell<Option<CrateDisambiguator>>,
+    pub crate_disambiguator: Lock<Option<CrateDisambiguator>>,
 
-    features: RefCell<Option<feature_gate::Features>>,
+    features: Once<feature_gate::Features>,
 
     /// The maximum recursion limit for potentially infinitely recursive
     /// operations such as auto-dereference and monomorphization.
-    pub recursion_limit: Cell<usize>,
+    pub recursion_limit: LockCell<usize>,
 
     /// The maximum length of types during monomorphization.
-    pub type_length_limit: Cell<usize>,
+    pub type_length_limit: LockCell<usize>,
 
     /// The maximum number of stackframes allowed in const eval
-    pub const_eval_stack_frame_limit: Cell<usize>,
+    pub const_eval_stack_frame_limit: LockCell<usize>,
     /// The maximum number miri steps per constant
-    pub const_eval_step_limit: Cell<usize>,
+    pub const_eval_step_limit: LockCell<usize>,
 
     /// The metadata::creader module may inject an allocator/panic_runtime
     /// dependency if it didn't already find one, and this tracks what was
     /// injected.
-    pub injected_allocator: Cell<Option<CrateNum>>,
-    pub allocator_kind: Cell<Option<AllocatorKind>>,
-    pub injected_panic_runtime: Cell<Option<CrateNum>>,
+    pub injected_allocator: LockCell<Option<CrateNum>>,
+    pub allocator_kind: LockCell<Option<AllocatorKind>>,
+    pub injected_panic_runtime: LockCell<Option<CrateNum>>,
 
     /// Map from imported macro spans (which consist of
     /// the localized span for the macro body) to the
     /// macro name and definition span in the source crate.
-    pub imported_macro_spans: RefCell<HashMap<Span, (String, Span)>>,
+    pub imported_macro_spans: Lock<HashMap<Span, (String, Span)>>,
 
-    incr_comp_session: RefCell<IncrCompSession>,
+    /// Only ever used on the main thread.
+    incr_comp_session: OneThread<RefCell<IncrCompSession>>,
 
     /// A cache of attributes ignored by StableHashingContext
     pub ignored_attr_names: FxHashSet<Symbol>,
@@ -134,45 +141,156 @@ pub struct Session {
     /// Some measurements that are being gathered during compilation.
     pub perf_stats: PerfStats,
 
+    /// The time spent in each phase of the MIR borrow checker.
+    pub mir_borrowck_times: MirBorrowckTimes,
+
+    /// What the NLL borrow checker found, for `-Z nll-stats`.
+    pub nll_stats: NllStats,
+
+    /// The self-profiler, if `-Z self-profile` is enabled.
+    pub self_profiling: Option<Lock<SelfProfiler>>,
+
     /// Data about code being compiled, gathered during compilation.
-    pub code_stats: RefCell<CodeStats>,
+    pub code_stats: Lock<CodeStats>,
+
+    /// The errors reported by each borrow checker under `-Z borrowck=compare`.
+    pub borrowck_comparison: Lock<BorrowckComparison>,
 
-    next_node_id: Cell<ast::NodeId>,
+    /// How many times each MIR pass has been run on each MIR body so far,
+    /// keyed by the name of the pass. Only kept under `-Z dump-mir`, for its
+    /// `name#N` filters.
+    pub mir_pass_runs: Lock<FxHashMap<String, FxHashMap<(DefId, Option<Promoted>), usize>>>,
+
+    /// The long error descriptions, kept around under `-Z teach` for
+    /// `teach_explanation`.
+    teach_registry: Option<errors::registry::Registry>,
+
+    next_node_id: LockCell<ast::NodeId>,
 
     /// If -zfuel=crate=n is specified, Some(crate).
     optimization_fuel_crate: Option<String>,
     /// If -zfuel=crate=n is specified, initially set to n. Otherwise 0.
-    optimization_fuel_limit: Cell<u64>,
+    optimization_fuel_limit: LockCell<u64>,
     /// We're rejecting all further optimizations.
-    out_of_fuel: Cell<bool>,
+    out_of_fuel: LockCell<bool>,
 
     // The next two are public because the driver needs to read them.
     /// If -zprint-fuel=crate, Some(crate).
     pub print_fuel_crate: Option<String>,
     /// Always set to zero and incremented so that we can print fuel expended by a crate.
-    pub print_fuel: Cell<u64>,
+    pub print_fuel: LockCell<u64>,
 
     /// Loaded up early on in the initialization of this `Session` to avoid
     /// false positives about a job server in our environment.
     pub jobserver_from_env: Option<Client>,
 
     /// Metadata about the allocators for the current crate being compiled
-    pub has_global_allocator: Cell<bool>,
+    pub has_global_allocator: LockCell<bool>,
 }
 
 pub struct PerfStats {
     /// The accumulated time needed for computing the SVH of the crate
-    pub svh_time: Cell<Duration>,
+    pub svh_time: Lock<Duration>,
     /// The accumulated time spent on computing incr. comp. hashes
-    pub incr_comp_hashes_time: Cell<Duration>,
+    pub incr_comp_hashes_time: Lock<Duration>,
     /// The number of incr. comp. hash computations performed
-    pub incr_comp_hashes_count: Cell<u64>,
+    pub incr_comp_hashes_count: LockCell<u64>,
     /// The number of bytes hashed when computing ICH values
-    pub incr_comp_bytes_hashed: Cell<u64>,
+    pub incr_comp_bytes_hashed: LockCell<u64>,
     /// The accumulated time spent on computing symbol hashes
-    pub symbol_hash_time: Cell<Duration>,
+    pub symbol_hash_time: Lock<Duration>,
     /// The accumulated time spent decoding def path tables from metadata
-    pub decode_def_path_tables_time: Cell<Duration>,
+    pub decode_def_path_tables_time: Lock<Duration>,
+}
+
+/// The time spent in each phase of the MIR borrow checker, accumulated over
+/// all the bodies of the crate, and reported by `-Z time-passes`.
+pub struct MirBorrowckTimes {
+    /// Replacing the regions of the MIR with inference variables
+    pub renumber: Lock<Duration>,
+    /// Computing the liveness of locals
+    pub liveness: Lock<Duration>,
+    /// Type checking the MIR, which generates the region constraints
+    pub type_check: Lock<Duration>,
+    /// Propagating the constraints between region variables
+    pub constraint_propagation: Lock<Duration>,
+    /// Checking the `T: 'a` requirements
+    pub type_tests: Lock<Duration>,
+    /// Checking the relations between the universal regions
+    pub universal_regions: Lock<Duration>,
+    /// Emitting the errors found by the borrow checker
+    pub error_reporting: Lock<Duration>,
+    /// The number of bodies the borrow checker actually ran on, as
+    /// opposed to reusing the result from the incremental cache
+    pub bodies_checked: LockCell<u64>,
+}
+
+impl MirBorrowckTimes {
+    fn new() -> MirBorrowckTimes {
+        MirBorrowckTimes {
+            renumber: Lock::new(Duration::from_secs(0)),
+            liveness: Lock::new(Duration::from_secs(0)),
+            type_check: Lock::new(Duration::from_secs(0)),
+            constraint_propagation: Lock::new(Duration::from_secs(0)),
+            type_tests: Lock::new(Duration::from_secs(0)),
+            universal_regions: Lock::new(Duration::from_secs(0)),
+            error_reporting: Lock::new(Duration::from_secs(0)),
+            bodies_checked: LockCell::new(0),
+        }
+    }
+
+    /// The phases, in order, along with their names.
+    pub fn phases(&self) -> Vec<(&'static str, Duration)> {
+        vec![
+            ("MIR renumbering", *self.renumber.lock()),
+            ("NLL liveness", *self.liveness.lock()),
+            ("NLL type check", *self.type_check.lock()),
+            ("NLL constraint propagation", *self.constraint_propagation.lock()),
+            ("NLL type tests", *self.type_tests.lock()),
+            ("NLL universal region checks", *self.universal_regions.lock()),
+            ("MIR borrowck error reporting", *self.error_reporting.lock()),
+        ]
+    }
+}
+
+/// Counts of what the NLL borrow checker found, accumulated over all the
+/// bodies of the crate, and reported by `-Z nll-stats`.
+pub struct NllStats {
+    /// The number of bodies checked with NLL
+    pub bodies_checked: LockCell<u64>,
+    /// The number of bodies for which errors were reported
+    pub bodies_with_errors: LockCell<u64>,
+    /// The number of requirements that closures propagated to their creators
+    pub closure_requirements: LockCell<u64>,
+    /// The number of type tests propagated to the creator of a closure
+    pub type_tests_promoted: LockCell<u64>,
+    /// The number of type tests that failed and were reported as errors
+    pub type_tests_reported: LockCell<u64>,
+}
+
+impl NllStats {
+    fn new() -> NllStats {
+        NllStats {
+            bodies_checked: LockCell::new(0),
+            bodies_with_errors: LockCell::new(0),
+            closure_requirements: LockCell::new(0),
+            type_tests_promoted: LockCell::new(0),
+            type_tests_reported: LockCell::new(0),
+        }
+    }
+
+    /// Adds `n` to one of the counters.
+    pub fn add(counter: &LockCell<u64>, n: u64) {
+        counter.set(counter.get() + n);
+    }
+
+    pub fn print(&self) {
+        println!("NLL bodies checked:              {}", self.bodies_checked.get());
+        println!("NLL bodies with errors:          {}", self.bodies_with_errors.get());
+        println!("NLL closure requirements:        {}", self.closure_requirements.get());
+        println!("NLL type tests promoted:         {}", self.type_tests_promoted.get());
+        println!("NLL type tests reported:         {}", self.type_tests_reported.get());
+    }
 }
 
 /// Enum to support dispatch of one-time diagnostics (in Session.diag_once)
@@ -513,6 +631,19 @@ impl Session {
     pub fn meta_stats(&self) -> bool {
         self.opts.debugging_opts.meta_stats
     }
+    /// Whether the MIR of every function should be encoded in the crate
+    /// metadata, and not only the MIR needed to instantiate generic and
+    /// inline functions in other crates.
+    pub fn always_encode_mir(&self) -> bool {
+        self.opts.debugging_opts.always_encode_mir || self.mir_only_rlib()
+    }
+    /// Whether this crate is built as an rlib without code for its functions,
+    /// with `-Z mir-only-rlibs`. Like `#[inline]` functions, they are then
+    /// translated from their MIR by each crate using them.
+    pub fn mir_only_rlib(&self) -> bool {
+        self.opts.debugging_opts.mir_only_rlibs &&
+            *self.crate_types.borrow() == [config::CrateTypeRlib]
+    }
     pub fn asm_comments(&self) -> bool {
         self.opts.debugging_opts.asm_comments
     }
@@ -530,18 +661,15 @@ impl Session {
     /// DO NOT USE THIS METHOD if there is a TyCtxt available, as it circumvents
     /// dependency tracking. Use tcx.features() instead.
     #[inline]
-    pub fn features_untracked(&self) -> cell::Ref<feature_gate::Features> {
-        let features = self.features.borrow();
-
-        if features.is_none() {
-            bug!("Access to Session::features before it is initialized");
+    pub fn features_untracked(&self) -> &feature_gate::Features {
+        match self.features.try_get() {
+            Some(features) => features,
+            None => bug!("Access to Session::features before it is initialized"),
         }
-
-        cell::Ref::map(features, |r| r.as_ref().unwrap())
     }
 
     pub fn init_features(&self, features: feature_gate::Features) {
-        *(self.features.borrow_mut()) = Some(features);
+        self.features.set(features);
     }
 
     /// Calculates the flavor of LTO to use for this compilation.
@@ -551,6 +679,11 @@ impl Session {
             return config::Lto::Fat;
         }
 
+        // LTO is left to the linker plugin, which gets bitcode in the objects.
+        if self.opts.cg.linker_plugin_lto.enabled() {
+            return config::Lto::No;
+        }
+
         // If the user specified something, return that. If they only said `-C
         // lto` and we've for whatever reason forced off ThinLTO via the CLI,
         // then ensure we can't use a ThinLTO.
@@ -824,14 +957,42 @@ impl Session {
         }
     }
 
+    /// Calls `f` with the self-profiler, if `-Z self-profile` is enabled.
+    #[inline]
+    pub fn profiler<F: FnOnce(&mut SelfProfiler)>(&self, f: F) {
+        if let Some(ref profiler) = self.self_profiling {
+            f(&mut profiler.borrow_mut())
+        }
+    }
+
+    /// Writes the events recorded by the self-profiler to
+    /// `<file_stem>-<pid>.events`, and their summary to
+    /// `<file_stem>-<pid>.summary.md`.
+    pub fn save_self_profile(&self, file_stem: &str) {
+        let profiler = match self.self_profiling {
+            Some(ref profiler) => profiler.borrow(),
+            None => return,
+        };
+        let stem = format!("{}-{}", file_stem, process::id());
+        let events = profiler.events();
+
+        let result = File::create(format!("{}.events", stem))
+            .and_then(|mut file| profiling::write_events(&mut file, events))
+            .and_then(|_| File::create(format!("{}.summary.md", stem)))
+            .and_then(|mut file| profiling::summarize(events).write_markdown(&mut file));
+        if let Err(e) = result {
+            self.err(&format!("could not write the self-profile of `{}`: {}", file_stem, e));
+        }
+    }
+
     pub fn print_perf_stats(&self) {
         println!(
             "Total time spent computing SVHs:               {}",
-            duration_to_secs_str(self.perf_stats.svh_time.get())
+            duration_to_secs_str(*self.perf_stats.svh_time.lock())
         );
         println!(
             "Total time spent computing incr. comp. hashes: {}",
-            duration_to_secs_str(self.perf_stats.incr_comp_hashes_time.get())
+            duration_to_secs_str(*self.perf_stats.incr_comp_hashes_time.lock())
         );
         println!(
             "Total number of incr. comp. hashes computed:   {}",
@@ -852,11 +1013,11 @@ impl Session {
         }
         println!(
             "Total time spent computing symbol hashes:      {}",
-            duration_to_secs_str(self.perf_stats.symbol_hash_time.get())
+            duration_to_secs_str(*self.perf_stats.symbol_hash_time.lock())
         );
         println!(
             "Total time spent decoding DefPath tables:      {}",
-            duration_to_secs_str(self.perf_stats.decode_def_path_tables_time.get())
+            duration_to_secs_str(*self.perf_stats.decode_def_path_tables_time.lock())
         );
     }
 
@@ -959,6 +1120,30 @@ impl Session {
         self.opts.debugging_opts.teach && !self.parse_sess.span_diagnostic.code_emitted(code)
     }
 
+    /// Under `-Z teach`, adds the introduction of the `--explain` text for the
+    /// error code of `diag` to it as a note, the first time the code is reported.
+    pub fn teach_explanation(&self, diag: &mut DiagnosticBuilder) {
+        let code = match diag.get_code() {
+            Some(code) => code,
+            None => return,
+        };
+        if !self.teach(&code) {
+            return;
+        }
+        let description = match (&code, &self.teach_registry) {
+            (&DiagnosticId::Error(ref code), &Some(ref registry)) => {
+                registry.find_description(code)
+            }
+            _ => None,
+        };
+        if let Some(description) = description {
+            let summary = explanation_summary(description);
+            if !summary.is_empty() {
+                diag.note(&summary);
+            }
+        }
+    }
+
     /// Are we allowed to use features from the Rust 2018 epoch?
     pub fn rust_2018(&self) -> bool {
         self.opts.debugging_opts.epoch >= Epoch::Epoch2018
@@ -1010,6 +1195,29 @@ pub fn build_session_with_codemap(
 
     let external_macro_backtrace = sopts.debugging_opts.external_macro_backtrace;
 
+    let teach_registry = if sopts.debugging_opts.teach {
+        Some(registry.clone())
+    } else {
+        None
+    };
+
+    let deduplicate_diagnostics = sopts.debugging_opts.deduplicate_diagnostics.unwrap_or(false);
+
+    // With `-Z borrowck=compare`, both borrow checkers report most errors.
+    // Tools reading the JSON output or the save-analysis data only get them
+    // once, from the borrow checker selected by `-Z borrowck-tool-errors`.
+    let for_tools = match sopts.error_format {
+        config::ErrorOutputType::Json(_) => true,
+        _ => sopts.debugging_opts.save_analysis,
+    };
+    let authoritative_origin = if sopts.borrowck_mode == config::BorrowckMode::Compare &&
+                                  for_tools {
+        Some(sopts.debugging_opts.borrowck_tool_errors.clone()
+             .unwrap_or_else(|| "ast".to_string()))
+    } else {
+        None
+    };
+
     let emitter: Box<dyn Emitter> =
         match (sopts.error_format, emitter_dest) {
             (config::ErrorOutputType::HumanReadable(color_config), None) => Box::new(
@@ -1029,7 +1237,8 @@ pub fn build_session_with_codemap(
                     Some(registry),
                     codemap.clone(),
                     pretty,
-                    sopts.debugging_opts.approximate_suggestions,
+                    sopts.debugging_opts.suggestion_applicability ||
+                        sopts.debugging_opts.approximate_suggestions,
                 ).ui_testing(sopts.debugging_opts.ui_testing),
             ),
             (config::ErrorOutputType::Json(pretty), Some(dst)) => Box::new(
@@ -1038,7 +1247,8 @@ pub fn build_session_with_codemap(
                     Some(registry),
                     codemap.clone(),
                     pretty,
-                    sopts.debugging_opts.approximate_suggestions,
+                    sopts.debugging_opts.suggestion_applicability ||
+                        sopts.debugging_opts.approximate_suggestions,
                 ).ui_testing(sopts.debugging_opts.ui_testing),
             ),
             (config::ErrorOutputType::Short(color_config), None) => Box::new(
@@ -1055,11 +1265,15 @@ pub fn build_session_with_codemap(
             can_emit_warnings,
             treat_err_as_bug,
             external_macro_backtrace,
+            deduplicate_diagnostics,
+            authoritative_origin,
             ..Default::default()
         },
     );
 
-    build_session_(sopts, local_crate_source_file, diagnostic_handler, codemap)
+    let mut sess = build_session_(sopts, local_crate_source_file, diagnostic_handler, codemap);
+    sess.teach_registry = teach_registry;
+    sess
 }
 
 pub fn build_session_(
@@ -1078,7 +1292,8 @@ pub fn build_session_(
     };
     let target_cfg = config::build_target_config(&sopts, &span_diagnostic);
 
-    let p_s = parse::ParseSess::with_span_handler(span_diagnostic, codemap);
+    let mut p_s = parse::ParseSess::with_span_handler(span_diagnostic, codemap);
+    p_s.epoch = sopts.debugging_opts.epoch;
     let default_sysroot = match sopts.maybe_sysroot {
         Some(_) => None,
         None => Some(filesearch::get_or_default_sysroot()),
@@ -1089,11 +1304,17 @@ pub fn build_session_(
     let local_crate_source_file =
         local_crate_source_file.map(|path| file_path_mapping.map_prefix(path).0);
 
+    let self_profiling = if sopts.debugging_opts.self_profile {
+        Some(Lock::new(SelfProfiler::new()))
+    } else {
+        None
+    };
+
     let optimization_fuel_crate = sopts.debugging_opts.fuel.as_ref().map(|i| i.0.clone());
     let optimization_fuel_limit =
-        Cell::new(sopts.debugging_opts.fuel.as_ref().map(|i| i.1).unwrap_or(0));
+        LockCell::new(sopts.debugging_opts.fuel.as_ref().map(|i| i.1).unwrap_or(0));
     let print_fuel_crate = sopts.debugging_opts.print_fuel.clone();
-    let print_fuel = Cell::new(0);
+    let print_fuel = LockCell::new(0);
 
     let working_dir = match env::current_dir() {
         Ok(dir) => dir,
@@ -1109,48 +1330,54 @@ pub fn build_session_(
         opts: sopts,
         parse_sess: p_s,
         // For a library crate, this is always none
-        entry_fn: RefCell::new(None),
-        entry_type: Cell::new(None),
-        plugin_registrar_fn: Cell::new(None),
-        derive_registrar_fn: Cell::new(None),
+        entry_fn: Lock::new(None),
+        entry_type: LockCell::new(None),
+        plugin_registrar_fn: LockCell::new(None),
+        derive_registrar_fn: LockCell::new(None),
         default_sysroot,
         local_crate_source_file,
         working_dir,
-        lint_store: RefCell::new(lint::LintStore::new()),
-        buffered_lints: RefCell::new(Some(lint::LintBuffer::new())),
-        one_time_diagnostics: RefCell::new(FxHashSet()),
-        plugin_llvm_passes: RefCell::new(Vec::new()),
-        plugin_attributes: RefCell::new(Vec::new()),
-        crate_types: RefCell::new(Vec::new()),
-        dependency_formats: RefCell::new(FxHashMap()),
-        crate_disambiguator: RefCell::new(None),
-        features: RefCell::new(None),
-        recursion_limit: Cell::new(64),
-        type_length_limit: Cell::new(1048576),
-        const_eval_stack_frame_limit: Cell::new(100),
-        const_eval_step_limit: Cell::new(1_000_000),
-        next_node_id: Cell::new(NodeId::new(1)),
-        injected_allocator: Cell::new(None),
-        allocator_kind: Cell::new(None),
-        injected_panic_runtime: Cell::new(None),
-        imported_macro_spans: RefCell::new(HashMap::new()),
-        incr_comp_session: RefCell::new(IncrCompSession::NotInitialized),
+        lint_store: RwLock::new(lint::LintStore::new()),
+        buffered_lints: Lock::new(Some(lint::LintBuffer::new())),
+        one_time_diagnostics: Lock::new(FxHashSet()),
+        plugin_llvm_passes: Lock::new(Vec::new()),
+        plugin_attributes: Lock::new(Vec::new()),
+        crate_types: RwLock::new(Vec::new()),
+        dependency_formats: RwLock::new(FxHashMap()),
+        crate_disambiguator: Lock::new(None),
+        features: Once::new(),
+        recursion_limit: LockCell::new(64),
+        type_length_limit: LockCell::new(1048576),
+        const_eval_stack_frame_limit: LockCell::new(100),
+        const_eval_step_limit: LockCell::new(1_000_000),
+        next_node_id: LockCell::new(NodeId::new(1)),
+        injected_allocator: LockCell::new(None),
+        allocator_kind: LockCell::new(None),
+        injected_panic_runtime: LockCell::new(None),
+        imported_macro_spans: Lock::new(HashMap::new()),
+        incr_comp_session: OneThread::new(RefCell::new(IncrCompSession::NotInitialized)),
         ignored_attr_names: ich::compute_ignored_attr_names(),
         profile_channel: Lock::new(None),
         perf_stats: PerfStats {
-            svh_time: Cell::new(Duration::from_secs(0)),
-            incr_comp_hashes_time: Cell::new(Duration::from_secs(0)),
-            incr_comp_hashes_count: Cell::new(0),
-            incr_comp_bytes_hashed: Cell::new(0),
-            symbol_hash_time: Cell::new(Duration::from_secs(0)),
-            decode_def_path_tables_time: Cell::new(Duration::from_secs(0)),
+            svh_time: Lock::new(Duration::from_secs(0)),
+            incr_comp_hashes_time: Lock::new(Duration::from_secs(0)),
+            incr_comp_hashes_count: LockCell::new(0),
+            incr_comp_bytes_hashed: LockCell::new(0),
+            symbol_hash_time: Lock::new(Duration::from_secs(0)),
+            decode_def_path_tables_time: Lock::new(Duration::from_secs(0)),
         },
-        code_stats: RefCell::new(CodeStats::new()),
+        mir_borrowck_times: MirBorrowckTimes::new(),
+        nll_stats: NllStats::new(),
+        self_profiling,
+        code_stats: Lock::new(CodeStats::new()),
+        borrowck_comparison: Lock::new(BorrowckComparison::new()),
+        mir_pass_runs: Lock::new(FxHashMap()),
+        teach_registry: None,
         optimization_fuel_crate,
         optimization_fuel_limit,
         print_fuel_crate,
         print_fuel,
-        out_of_fuel: Cell::new(false),
+        out_of_fuel: LockCell::new(false),
         // Note that this is unsafe because it may misinterpret file descriptors
         // on Unix as jobserver file descriptors. We hopefully execute this near
         // the beginning of the process though to ensure we don't get false
@@ -1168,7 +1395,7 @@ pub fn build_session_(
             });
             (*GLOBAL_JOBSERVER).clone()
         },
-        has_global_allocator: Cell::new(false),
+        has_global_allocator: LockCell::new(false),
     };
 
     sess
@@ -1216,6 +1443,23 @@ pub enum IncrCompSession {
     InvalidBecauseOfErrors { session_directory: PathBuf },
 }
 
+/// Cuts an `--explain` text down to the prose in front of its first code
+/// example, with each paragraph on a single line.
+fn explanation_summary(description: &str) -> String {
+    let mut paragraphs = description.trim()
+        .split("\n\n")
+        .take_while(|paragraph| !paragraph.trim_left().starts_with("```"))
+        .map(|paragraph| {
+            paragraph.lines().map(str::trim).collect::<Vec<_>>().join(" ")
+        })
+        .collect::<Vec<_>>();
+    // Drop lines like "Erroneous code example:" that introduce the example.
+    while paragraphs.last().map_or(false, |paragraph| paragraph.ends_with(':')) {
+        paragraphs.pop();
+    }
+    paragraphs.join("\n")
+}
+
 pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
     let emitter: Box<dyn Emitter> = match output {
         config::ErrorOutputType::HumanReadable(color_config) => {
@@ -1226,7 +1470,7 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
     };
-    let handler = errors::Handler::with_emitter(true, false, emitter);
+    let handler = errors::Handler::with_emitter(true, None, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Fatal);
     errors::FatalError.raise();
 }
@@ -1241,7 +1485,7 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
     };
-    let handler = errors::Handler::with_emitter(true, false, emitter);
+    let handler = errors::Handler::with_emitter(true, None, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Warning);
 }
 
@@ -1292,6 +1536,9 @@ fn opt_span_bug_fmt<S: Into<MultiSpan>>(
 ) -> ! {
     tls::with_opt(move |tcx| {
         let msg = format!("{}:{}: {}", file, line, args);
+        if let Some(tcx) = tcx {
+            tcx.try_print_query_stack();
+        }
         match (tcx, span) {
             (Some(tcx), Some(span)) => tcx.sess.diagnostic().span_bug(span, &msg),
             (Some(tcx), None) => tcx.sess.diagnostic().bug(&msg),
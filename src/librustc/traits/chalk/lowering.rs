@@ -0,0 +1,127 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lowering of rustc predicates, impls and where-clauses into the
+//! program clauses understood by the solver in the parent module.
+//!
+//! Only the subset of the trait system that has a direct logical
+//! reading is lowered here. Everything else is reported as
+//! unsupported, so that the caller can fall back to the regular
+//! selection machinery.
+
+use hir::def_id::DefId;
+use ty::{self, TyCtxt};
+use ty::subst::{Subst, Substs};
+
+/// A goal about the "domain" of the program, i.e. a fact that holds or
+/// doesn't hold independently of how it was proven.
+#[derive(Clone, Debug)]
+pub enum DomainGoal<'tcx> {
+    /// `T: Trait<..>`
+    Implemented(ty::TraitRef<'tcx>),
+
+    /// `T: 'a` or `'a: 'b`; region constraints are left to regionck,
+    /// so the solver treats these as trivially provable.
+    Outlives,
+}
+
+/// Where a program clause comes from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClauseSource {
+    /// A where-clause in scope, from the `ParamEnv`.
+    WhereClause,
+
+    /// An impl; its generics must be instantiated before use.
+    Impl(DefId),
+}
+
+/// A clause of the form `consequence :- conditions`.
+#[derive(Clone, Debug)]
+pub struct ProgramClause<'tcx> {
+    pub source: ClauseSource,
+    pub consequence: ty::TraitRef<'tcx>,
+    pub conditions: Vec<ty::Predicate<'tcx>>,
+}
+
+impl<'tcx> ProgramClause<'tcx> {
+    /// Substitutes `substs` for the generic parameters of the clause.
+    pub fn instantiate<'a, 'gcx>(&self,
+                                 tcx: TyCtxt<'a, 'gcx, 'tcx>,
+                                 substs: &Substs<'tcx>)
+                                 -> ProgramClause<'tcx>
+    {
+        ProgramClause {
+            source: self.source,
+            consequence: self.consequence.subst(tcx, substs),
+            conditions: self.conditions.subst(tcx, substs),
+        }
+    }
+}
+
+/// Lowers `predicate` into a domain goal, or returns `None` if the
+/// predicate is not supported by the solver.
+pub fn lower_predicate<'tcx>(predicate: &ty::Predicate<'tcx>) -> Option<DomainGoal<'tcx>> {
+    match *predicate {
+        ty::Predicate::Trait(ref data) => {
+            data.no_late_bound_regions().map(|data| DomainGoal::Implemented(data.trait_ref))
+        }
+        ty::Predicate::RegionOutlives(..) |
+        ty::Predicate::TypeOutlives(..) => Some(DomainGoal::Outlives),
+        ty::Predicate::Projection(..) |
+        ty::Predicate::WellFormed(..) |
+        ty::Predicate::ObjectSafe(..) |
+        ty::Predicate::ClosureKind(..) |
+        ty::Predicate::Subtype(..) |
+        ty::Predicate::ConstEvaluatable(..) => None,
+    }
+}
+
+/// Lowers the impl `impl_def_id` into the clause
+/// `TraitRef :- WhereClauses`, expressed in terms of the impl's own
+/// generic parameters.
+pub fn program_clause_for_impl<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>,
+                                                impl_def_id: DefId)
+                                                -> ProgramClause<'tcx>
+{
+    let trait_ref = tcx.impl_trait_ref(impl_def_id)
+        .expect("program_clause_for_impl called on an inherent impl");
+    let predicates = tcx.predicates_of(impl_def_id);
+    assert_eq!(predicates.parent, None);
+
+    ProgramClause {
+        source: ClauseSource::Impl(impl_def_id),
+        consequence: trait_ref,
+        conditions: predicates.predicates.clone(),
+    }
+}
+
+/// Lowers the where-clauses in `param_env` that concern `trait_def_id`
+/// into facts. Returns `None` if one of them is higher-ranked, as those
+/// cannot be expressed without universal quantification in clauses.
+pub fn program_clauses_for_env<'tcx>(param_env: ty::ParamEnv<'tcx>,
+                                     trait_def_id: DefId)
+                                     -> Option<Vec<ProgramClause<'tcx>>>
+{
+    let mut clauses = vec![];
+    for predicate in param_env.caller_bounds.iter() {
+        if let ty::Predicate::Trait(ref data) = *predicate {
+            if data.def_id() != trait_def_id {
+                continue;
+            }
+            let data = data.no_late_bound_regions()?;
+            clauses.push(ProgramClause {
+                source: ClauseSource::WhereClause,
+                consequence: data.trait_ref,
+                conditions: vec![],
+            });
+        }
+    }
+    Some(clauses)
+}
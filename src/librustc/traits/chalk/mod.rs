@@ -0,0 +1,422 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An experimental, Chalk-style trait solver, enabled with `-Z chalk`.
+//!
+//! Trait obligations are lowered into program clauses (see the
+//! `lowering` module) and proven by an SLG-style solver: every goal
+//! gets a table holding its answer. A goal that is reached again while
+//! its table is still being filled consumes the answer found so far,
+//! and the goals of such a cycle are re-evaluated until their tables
+//! stop changing, at which point the tables are complete.
+//!
+//! Cycles are inductive, i.e. a goal cannot be used to prove itself.
+//! A goal that can only fail through a cycle is not reported as an
+//! error though: its answer is ambiguous, and the regular selection
+//! gets to decide (usually by reporting an overflow).
+//!
+//! The solver only understands a subset of the trait system: impls and
+//! where-clauses without higher-ranked regions or projections. For any
+//! other goal it answers `Unsupported`, and the fulfillment context
+//! falls back to the regular trait selection.
+
+use infer::{InferCtxt, InferOk};
+use rustc_data_structures::fx::FxHashMap;
+use std::cmp;
+use ty::{self, TyCtxt, TypeFoldable};
+use ty::fast_reject;
+
+use super::{ImplDerivedObligation, Obligation, ObligationCause};
+use super::{PredicateObligation, TraitObligation};
+use super::project::{self, Normalized};
+use super::select::SelectionContext;
+
+use self::lowering::{ClauseSource, DomainGoal, ProgramClause};
+
+mod lowering;
+
+/// The outcome of solving a trait obligation.
+#[derive(Debug)]
+pub enum Answer<'tcx> {
+    /// The obligation holds, provided that the nested obligations hold.
+    Proven(Vec<PredicateObligation<'tcx>>),
+
+    /// The obligation cannot hold.
+    NoSolution,
+
+    /// The solver does not support this obligation, or could not
+    /// pick a unique clause for it; use the regular selection instead.
+    Unsupported,
+}
+
+/// The result of evaluating a goal, ordered from "worst" to "best".
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Evaluation {
+    NoSolution,
+    Maybe,
+    Proven,
+}
+
+/// The answer to a goal, as known so far.
+#[derive(Copy, Clone, Debug)]
+struct Table {
+    answer: Evaluation,
+
+    /// Whether the goal was reached again while it was being solved.
+    cyclic: bool,
+
+    /// Whether `answer` is final. Tables of goals that are part of a
+    /// cycle only become complete once the whole cycle is solved.
+    complete: bool,
+}
+
+/// A goal being solved.
+struct StackEntry<'tcx> {
+    key: ty::TraitRef<'tcx>,
+
+    /// The lowest stack depth of the goals this goal has consumed
+    /// incomplete answers of; if this is the depth of the goal itself,
+    /// it leads its cycle and can complete the tables of that cycle.
+    link: usize,
+}
+
+struct Solver<'a, 'b: 'a, 'gcx: 'tcx, 'tcx: 'b> {
+    selcx: &'a mut SelectionContext<'b, 'gcx, 'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    cause: ObligationCause<'tcx>,
+
+    /// The tables of the goals (with regions erased). Only the tables of
+    /// ground goals are kept once complete, as inference variables in
+    /// the other goals may come from a probe that has been rolled back.
+    tables: FxHashMap<ty::TraitRef<'tcx>, Table>,
+
+    /// Goals currently being solved, used to detect cycles.
+    stack: Vec<StackEntry<'tcx>>,
+}
+
+/// Tries to solve `obligation` with the Chalk-style solver.
+pub fn solve_obligation<'a, 'gcx, 'tcx>(selcx: &mut SelectionContext<'a, 'gcx, 'tcx>,
+                                        obligation: &TraitObligation<'tcx>)
+                                        -> Answer<'tcx>
+{
+    debug!("chalk::solve_obligation({:?})", obligation);
+
+    let goal = match obligation.predicate.no_late_bound_regions() {
+        Some(predicate) => selcx.infcx().resolve_type_vars_if_possible(&predicate.trait_ref),
+        None => return Answer::Unsupported,
+    };
+
+    let mut solver = Solver {
+        selcx,
+        param_env: obligation.param_env,
+        cause: obligation.cause.clone(),
+        tables: FxHashMap(),
+        stack: vec![],
+    };
+
+    if !solver.is_supported(goal) {
+        return Answer::Unsupported;
+    }
+    let clauses = match solver.clauses_for(goal) {
+        Some(clauses) => clauses,
+        None => return Answer::Unsupported,
+    };
+
+    // Complete the table of the goal first, so that the clauses below
+    // see its final answer if they lead back to it.
+    let depth = obligation.recursion_depth;
+    let key = solver.tcx().erase_regions(&goal);
+    let evaluation = solver.evaluate_goal(goal, depth);
+    let cyclic = solver.tables.get(&key).map_or(false, |table| table.cyclic);
+    debug!("chalk::solve_obligation: evaluation = {:?}, cyclic = {}", evaluation, cyclic);
+    match evaluation {
+        Evaluation::NoSolution => return Answer::NoSolution,
+        Evaluation::Maybe if cyclic => return Answer::Unsupported,
+        Evaluation::Maybe | Evaluation::Proven => {}
+    }
+
+    let mut applicable: Vec<_> = clauses.into_iter().filter(|clause| {
+        solver.evaluate_clause(goal, clause, depth) != Evaluation::NoSolution
+    }).collect();
+
+    debug!("chalk::solve_obligation: applicable clauses = {:?}", applicable);
+
+    match applicable.len() {
+        0 => Answer::NoSolution,
+        1 => solver.confirm(obligation, goal, applicable.pop().unwrap()),
+        _ => Answer::Unsupported,
+    }
+}
+
+impl<'a, 'b, 'gcx, 'tcx> Solver<'a, 'b, 'gcx, 'tcx> {
+    fn infcx(&self) -> &'b InferCtxt<'b, 'gcx, 'tcx> {
+        self.selcx.infcx()
+    }
+
+    fn tcx(&self) -> TyCtxt<'b, 'gcx, 'tcx> {
+        self.selcx.tcx()
+    }
+
+    /// Whether `goal` can be lowered at all. Builtin and auto traits
+    /// are proven by rules that are not expressed as clauses yet.
+    fn is_supported(&self, goal: ty::TraitRef<'tcx>) -> bool {
+        let tcx = self.tcx();
+        let lang_items = tcx.lang_items();
+        let def_id = Some(goal.def_id);
+        if def_id == lang_items.sized_trait() ||
+            def_id == lang_items.copy_trait() ||
+            def_id == lang_items.clone_trait() ||
+            def_id == lang_items.unsize_trait() ||
+            def_id == lang_items.gen_trait() ||
+            lang_items.fn_trait_kind(goal.def_id).is_some() ||
+            tcx.trait_is_auto(goal.def_id)
+        {
+            return false;
+        }
+
+        if goal.references_error() || goal.has_projections() {
+            return false;
+        }
+
+        let self_ty = goal.self_ty();
+        match self_ty.sty {
+            ty::TyInfer(ty::TyVar(_)) |
+            ty::TyDynamic(..) |
+            ty::TyClosure(..) |
+            ty::TyGenerator(..) |
+            ty::TyProjection(..) |
+            ty::TyAnon(..) => false,
+            _ => !self_ty.is_defaulted_unit(),
+        }
+    }
+
+    /// Collects the clauses whose consequence may unify with `goal`, or
+    /// `None` if one of the candidate clauses cannot be lowered.
+    fn clauses_for(&self, goal: ty::TraitRef<'tcx>) -> Option<Vec<ProgramClause<'tcx>>> {
+        let tcx = self.tcx();
+        let mut clauses = lowering::program_clauses_for_env(self.param_env, goal.def_id)?;
+        let mut supported = true;
+        tcx.for_each_relevant_impl(goal.def_id, goal.self_ty(), |impl_def_id| {
+            let clause = lowering::program_clause_for_impl(tcx, impl_def_id);
+            if clause.consequence.has_projections() {
+                supported = false;
+            }
+            clauses.push(clause);
+        });
+        if !supported {
+            return None;
+        }
+
+        clauses.retain(|clause| !fast_reject_trait_refs(tcx, goal, clause.consequence));
+        Some(clauses)
+    }
+
+    /// Replaces the generic parameters of an impl clause with fresh
+    /// inference variables.
+    fn instantiate_clause(&self, clause: &ProgramClause<'tcx>) -> ProgramClause<'tcx> {
+        match clause.source {
+            ClauseSource::Impl(impl_def_id) => {
+                let substs = self.infcx().fresh_substs_for_item(self.param_env.universe,
+                                                                self.cause.span,
+                                                                impl_def_id);
+                clause.instantiate(self.tcx(), substs)
+            }
+            ClauseSource::WhereClause => clause.clone(),
+        }
+    }
+
+    /// Evaluates whether `goal` holds, considering every clause that
+    /// might apply.
+    fn evaluate_goal(&mut self, goal: ty::TraitRef<'tcx>, depth: usize) -> Evaluation {
+        let goal = self.infcx().resolve_type_vars_if_possible(&goal);
+        debug!("chalk::evaluate_goal({:?}, depth={})", goal, depth);
+
+        if depth > self.tcx().sess.recursion_limit.get() || !self.is_supported(goal) {
+            return Evaluation::Maybe;
+        }
+
+        let key = self.tcx().erase_regions(&goal);
+        if let Some(&Table { answer, complete: true, .. }) = self.tables.get(&key) {
+            return answer;
+        }
+        if let Some(index) = self.stack.iter().position(|entry| entry.key == key) {
+            // Consume the answer found so far; the goal is solved again if
+            // its answer changes, so the cycle is solved once it stops changing.
+            debug!("chalk::evaluate_goal: cycle on {:?}", key);
+            let top = self.stack.last_mut().unwrap();
+            top.link = cmp::min(top.link, index);
+            let table = self.tables.get_mut(&key).unwrap();
+            table.cyclic = true;
+            return table.answer;
+        }
+
+        let clauses = match self.clauses_for(goal) {
+            Some(clauses) => clauses,
+            None => return Evaluation::Maybe,
+        };
+
+        // Cycles are inductive: until a clause without the goal proves
+        // it, the goal has no answer.
+        let index = self.stack.len();
+        self.tables.insert(key, Table {
+            answer: Evaluation::NoSolution,
+            cyclic: false,
+            complete: false,
+        });
+        self.stack.push(StackEntry { key, link: index });
+        loop {
+            let mut result = Evaluation::NoSolution;
+            for clause in &clauses {
+                result = result.max(self.evaluate_clause(goal, clause, depth));
+                if result == Evaluation::Proven {
+                    break;
+                }
+            }
+
+            let table = self.tables.get_mut(&key).unwrap();
+            let changed = result != table.answer;
+            table.answer = result;
+            if !changed || !table.cyclic {
+                break;
+            }
+        }
+        let entry = self.stack.pop().unwrap();
+
+        let mut table = self.tables.remove(&key).unwrap();
+        if entry.link == index {
+            // Nothing this goal depends on is still being solved.
+            table.complete = true;
+            if table.cyclic && table.answer == Evaluation::NoSolution {
+                // Leave goals that fail only through a cycle to the regular
+                // selection, rather than reporting them as unimplemented.
+                table.answer = Evaluation::Maybe;
+            }
+            if !key.needs_infer() || index == 0 {
+                self.tables.insert(key, table);
+            }
+        } else if let Some(parent) = self.stack.last_mut() {
+            // The answer depends on a goal lower in the stack, which will
+            // solve this goal again until its own answer is final.
+            parent.link = cmp::min(parent.link, entry.link);
+        }
+
+        debug!("chalk::evaluate_goal({:?}) = {:?}", goal, table.answer);
+        table.answer
+    }
+
+    /// Evaluates whether `clause` can be used to prove `goal`, without
+    /// affecting the inference context.
+    fn evaluate_clause(&mut self,
+                       goal: ty::TraitRef<'tcx>,
+                       clause: &ProgramClause<'tcx>,
+                       depth: usize)
+                       -> Evaluation
+    {
+        let infcx = self.infcx();
+        infcx.probe(|_| {
+            let clause = self.instantiate_clause(clause);
+            let mut result = match infcx.at(&self.cause, self.param_env)
+                                        .eq(goal, clause.consequence) {
+                Ok(InferOk { obligations, .. }) => {
+                    if obligations.is_empty() {
+                        Evaluation::Proven
+                    } else {
+                        Evaluation::Maybe
+                    }
+                }
+                Err(_) => return Evaluation::NoSolution,
+            };
+
+            let Normalized { value: conditions, obligations } =
+                project::normalize_with_depth(self.selcx,
+                                              self.param_env,
+                                              self.cause.clone(),
+                                              depth + 1,
+                                              &clause.conditions);
+            if !obligations.is_empty() {
+                result = Evaluation::Maybe;
+            }
+
+            for condition in &conditions {
+                let evaluation = match lowering::lower_predicate(condition) {
+                    Some(DomainGoal::Implemented(trait_ref)) => {
+                        self.evaluate_goal(trait_ref, depth + 1)
+                    }
+                    Some(DomainGoal::Outlives) => Evaluation::Proven,
+                    None => Evaluation::Maybe,
+                };
+                result = result.min(evaluation);
+                if result == Evaluation::NoSolution {
+                    break;
+                }
+            }
+            result
+        })
+    }
+
+    /// Commits to `clause` as the way to prove `goal`, returning the
+    /// obligations that remain to be proven.
+    fn confirm(&mut self,
+               obligation: &TraitObligation<'tcx>,
+               goal: ty::TraitRef<'tcx>,
+               clause: ProgramClause<'tcx>)
+               -> Answer<'tcx>
+    {
+        let clause = self.instantiate_clause(&clause);
+        let mut nested = match self.infcx().at(&self.cause, self.param_env)
+                                           .eq(goal, clause.consequence) {
+            Ok(InferOk { obligations, .. }) => obligations,
+            Err(err) => {
+                // The clause unified with the goal during evaluation, so
+                // this should not happen; let the regular selection decide.
+                debug!("chalk::confirm: failed to unify {:?} with {:?}: {:?}",
+                       goal, clause, err);
+                return Answer::Unsupported;
+            }
+        };
+
+        let cause = obligation.derived_cause(ImplDerivedObligation);
+        let depth = obligation.recursion_depth + 1;
+        for condition in &clause.conditions {
+            let Normalized { value: predicate, obligations } =
+                project::normalize_with_depth(self.selcx,
+                                              self.param_env,
+                                              cause.clone(),
+                                              depth,
+                                              condition);
+            nested.extend(obligations);
+            nested.push(Obligation {
+                cause: cause.clone(),
+                recursion_depth: depth,
+                param_env: self.param_env,
+                predicate,
+            });
+        }
+
+        debug!("chalk::confirm({:?}) = {:?}", goal, nested);
+        Answer::Proven(nested)
+    }
+}
+
+/// Whether `goal` can be quickly shown not to unify with `consequence`.
+fn fast_reject_trait_refs<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>,
+                                          goal: ty::TraitRef<'tcx>,
+                                          consequence: ty::TraitRef<'tcx>)
+                                          -> bool
+{
+    goal.input_types().zip(consequence.input_types()).any(|(goal_ty, clause_ty)| {
+        let simplified_goal_ty = fast_reject::simplify_type(tcx, goal_ty, true);
+        let simplified_clause_ty = fast_reject::simplify_type(tcx, clause_ty, false);
+
+        simplified_goal_ty.is_some() &&
+            simplified_clause_ty.is_some() &&
+            simplified_goal_ty != simplified_clause_ty
+    })
+}
@@ -25,9 +25,10 @@ use super::{
     SelectionContext,
     SelectionError,
     ObjectSafetyViolation,
+    type_known_to_meet_bound,
 };
 
-use errors::DiagnosticBuilder;
+use errors::{Applicability, DiagnosticBuilder};
 use hir;
 use hir::def_id::DefId;
 use infer::{self, InferCtxt};
@@ -343,7 +344,8 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
         let mut flags = vec![];
         let direct = match obligation.cause.code {
             ObligationCauseCode::BuiltinDerivedObligation(..) |
-            ObligationCauseCode::ImplDerivedObligation(..) => false,
+            ObligationCauseCode::ImplDerivedObligation(..) |
+            ObligationCauseCode::TraitAliasDerivedObligation(..) => false,
             _ => true
         };
         if direct {
@@ -683,6 +685,12 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                                     err.span_label(*span, format!(
                                         "closure is `FnOnce` because it moves the \
                                          variable `{}` out of its environment", name));
+                                    self.suggest_cloning_captured_value(&mut err,
+                                                                        &tables,
+                                                                        node_id,
+                                                                        *span,
+                                                                        *name,
+                                                                        obligation.param_env);
                                 },
                                 (ty::ClosureKind::FnMut, Some((span, name))) => {
                                     err.span_label(*span, format!(
@@ -806,6 +814,53 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
         }
     }
 
+    /// When a closure is `FnOnce` only because it moves a captured variable out
+    /// of its environment, suggest moving out a clone of it instead, provided
+    /// the variable's type is `Clone`.
+    fn suggest_cloning_captured_value(&self,
+                                      err: &mut DiagnosticBuilder,
+                                      tables: &ty::TypeckTables<'tcx>,
+                                      closure_id: ast::NodeId,
+                                      span: Span,
+                                      name: ast::Name,
+                                      param_env: ty::ParamEnv<'tcx>) {
+        // Only suggest when the move is a plain use of the variable, not of
+        // some place derived from it.
+        match self.tcx.sess.codemap().span_to_snippet(span) {
+            Ok(ref snippet) if *snippet == *name.as_str() => {}
+            _ => return,
+        }
+        let clone_trait = match self.tcx.lang_items().clone_trait() {
+            Some(def_id) => def_id,
+            None => return,
+        };
+        let var_id = self.tcx.with_freevars(closure_id, |freevars| {
+            freevars.iter()
+                    .map(|freevar| freevar.var_id())
+                    .find(|&var_id| self.tcx.hir.name(var_id) == name)
+        });
+        let var_ty = match var_id {
+            Some(var_id) => {
+                let var_hir_id = self.tcx.hir.node_to_hir_id(var_id);
+                match tables.node_id_to_type_opt(var_hir_id) {
+                    Some(ty) => self.resolve_type_vars_if_possible(&ty),
+                    None => return,
+                }
+            }
+            None => return,
+        };
+        if var_ty.needs_infer() ||
+           !type_known_to_meet_bound(self, param_env, var_ty, clone_trait, span) {
+            return;
+        }
+        err.span_suggestion_with_applicability(
+            span,
+            "consider cloning the captured value",
+            format!("{}.clone()", name),
+            Applicability::MaybeIncorrect,
+        );
+    }
+
     /// Given some node representing a fn-like thing in the HIR map,
     /// returns a span and `ArgKind` information that describes the
     /// arguments it expects. This can be supplied to
@@ -1369,6 +1424,18 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                                             &data.parent_code,
                                             obligated_types);
             }
+            ObligationCauseCode::TraitAliasDerivedObligation(ref data) => {
+                let parent_trait_ref = self.resolve_type_vars_if_possible(&data.parent_trait_ref);
+                err.note(
+                    &format!("required because of the bounds of the trait alias `{}` for `{}`",
+                             parent_trait_ref,
+                             parent_trait_ref.0.self_ty()));
+                let parent_predicate = parent_trait_ref.to_predicate();
+                self.note_obligation_cause_code(err,
+                                            &parent_predicate,
+                                            &data.parent_code,
+                                            obligated_types);
+            }
             ObligationCauseCode::CompareImplMethodObligation { .. } => {
                 err.note(
                     &format!("the requirement `{}` appears on the impl method \
@@ -19,6 +19,7 @@ use hir::def_id::DefId;
 use middle::const_val::{ConstEvalErr, ErrKind};
 
 use super::CodeAmbiguity;
+use super::chalk;
 use super::CodeProjectionError;
 use super::CodeSelectionError;
 use super::{FulfillmentError, FulfillmentErrorCode};
@@ -327,6 +328,22 @@ fn process_predicate<'a, 'gcx, 'tcx>(
         ty::Predicate::Trait(ref data) => {
             let trait_obligation = obligation.with(data.clone());
 
+            if selcx.tcx().sess.opts.debugging_opts.chalk {
+                match chalk::solve_obligation(selcx, &trait_obligation) {
+                    chalk::Answer::Proven(nested) => {
+                        debug!("solving trait `{:?}` with chalk at depth {} yielded Proven",
+                               data, obligation.recursion_depth);
+                        return Ok(Some(nested));
+                    }
+                    chalk::Answer::NoSolution => {
+                        debug!("solving trait `{:?}` with chalk at depth {} yielded NoSolution",
+                               data, obligation.recursion_depth);
+                        return Err(CodeSelectionError(Unimplemented));
+                    }
+                    chalk::Answer::Unsupported => {}
+                }
+            }
+
             if data.is_global() {
                 // no type variables present, can use evaluation for better caching.
                 // FIXME: consider caching errors too.
@@ -510,6 +527,17 @@ fn process_predicate<'a, 'gcx, 'tcx>(
         }
 
         ty::Predicate::ConstEvaluatable(def_id, substs) => {
+            if substs.needs_subst() {
+                // Lazily normalized constants can only be evaluated once their
+                // generic parameters are known; until then, they are only known
+                // to be evaluatable if the where-clauses in scope say so.
+                let predicate = selcx.infcx().resolve_type_vars_if_possible(&obligation.predicate);
+                return if obligation.param_env.caller_bounds.contains(&predicate) {
+                    Ok(Some(vec![]))
+                } else {
+                    Ok(None)
+                };
+            }
             match selcx.tcx().lift_to_global(&obligation.param_env) {
                 None => {
                     Ok(None)
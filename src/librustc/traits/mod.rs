@@ -51,6 +51,7 @@ pub use self::util::supertrait_def_ids;
 pub use self::util::SupertraitDefIds;
 pub use self::util::transitive_bounds;
 
+mod chalk;
 mod coherence;
 pub mod error_reporting;
 mod fulfill;
@@ -63,6 +64,8 @@ mod structural_impls;
 pub mod trans;
 mod util;
 
+pub mod query;
+
 // Whether to enable bug compatibility with issue #43355
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum IntercrateMode {
@@ -183,6 +186,9 @@ pub enum ObligationCauseCode<'tcx> {
 
     ImplDerivedObligation(DerivedObligationCause<'tcx>),
 
+    /// bound of a trait alias, required by a use of the alias
+    TraitAliasDerivedObligation(DerivedObligationCause<'tcx>),
+
     /// error derived when matching traits/impls; see ObligationCause for more details
     CompareImplMethodObligation {
         item_name: ast::Name,
@@ -912,6 +918,9 @@ pub fn provide(providers: &mut ty::maps::Providers) {
         trans_fulfill_obligation: trans::trans_fulfill_obligation,
         vtable_methods,
         substitute_normalize_and_test_predicates,
+        normalize_projection_ty: query::normalize::normalize_projection_ty,
+        dropck_outlives: query::dropck_outlives::dropck_outlives,
+        implied_outlives_bounds: query::outlives_bounds::implied_outlives_bounds,
         ..*providers
     };
 }
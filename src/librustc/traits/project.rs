@@ -402,9 +402,17 @@ impl<'a, 'b, 'gcx, 'tcx> TypeFolder<'gcx, 'tcx> for AssociatedTypeNormalizer<'a,
     fn fold_const(&mut self, constant: &'tcx ty::Const<'tcx>) -> &'tcx ty::Const<'tcx> {
         if let ConstVal::Unevaluated(def_id, substs) = constant.val {
             let tcx = self.selcx.tcx().global_tcx();
+            let identity_substs = Substs::identity_for_item(tcx, def_id);
+            if substs.needs_subst() || (substs.needs_infer() && identity_substs.needs_subst()) {
+                // Constants that depend on generic parameters (only possible
+                // with `#![feature(lazy_normalization_consts)]`) are left
+                // unevaluated until those parameters are known. Evaluating
+                // them here would type-check their body in the middle of
+                // computing the `ParamEnv` it is checked under.
+                return constant;
+            }
             if let Some(param_env) = self.tcx().lift_to_global(&self.param_env) {
                 if substs.needs_infer() {
-                    let identity_substs = Substs::identity_for_item(tcx, def_id);
                     let instance = ty::Instance::resolve(tcx, param_env, def_id, identity_substs);
                     if let Some(instance) = instance {
                         let cid = GlobalId {
@@ -943,7 +951,7 @@ fn assemble_candidates_from_trait_def<'cx, 'gcx, 'tcx>(
     // Check whether the self-type is itself a projection.
     let (def_id, substs) = match obligation_trait_ref.self_ty().sty {
         ty::TyProjection(ref data) => {
-            (data.trait_ref(tcx).def_id, data.substs)
+            (data.bounds_def_id(tcx), data.substs)
         }
         ty::TyAnon(def_id, substs) => (def_id, substs),
         ty::TyInfer(ty::TyVar(_)) => {
@@ -1475,6 +1483,10 @@ fn confirm_impl_candidate<'cx, 'gcx, 'tcx>(
         tcx.type_of(assoc_ty.item.def_id)
     };
     let substs = translate_substs(selcx.infcx(), param_env, impl_def_id, substs, assoc_ty.node);
+    // For a generic associated type, the projection also provides the
+    // arguments for the associated type's own parameters.
+    let trait_def_id = tcx.associated_item(obligation.predicate.item_def_id).container.id();
+    let substs = obligation.predicate.substs.rebase_onto(tcx, trait_def_id, substs);
     Progress {
         ty: ty.subst(tcx, substs),
         obligations: nested,
@@ -0,0 +1,213 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+//! Code for the `dropck_outlives` query, which computes the set of
+//! types and regions that must outlive the point where a value of
+//! some type is dropped.
+
+use infer::at::At;
+use infer::canonical::{Canonical, QueryResult};
+use infer::InferOk;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::sync::Lrc;
+use syntax_pos::DUMMY_SP;
+use traits::{FulfillmentContext, Normalized, ObligationCause};
+use ty::{self, ParamEnvAnd, Ty, TyCtxt};
+use ty::subst::Kind;
+use util::common::ErrorReported;
+
+use super::{CanonicalTyGoal, NoSolution};
+
+impl<'cx, 'gcx, 'tcx> At<'cx, 'gcx, 'tcx> {
+    /// Given a type `ty` of some value being dropped, computes a set
+    /// of "kinds" (types, regions) that must be outlive the execution
+    /// of the destructor. These basically correspond to data that the
+    /// destructor might access. This is used during regionck to
+    /// impose "outlives" constraints on any lifetimes referenced
+    /// within.
+    ///
+    /// The rules here are given by the "dropck" RFCs, notably [#1238]
+    /// and [#1327]. This is a fixed-point computation, where we
+    /// explore all the data that will be dropped (transitively) when
+    /// a value of type `ty` is dropped. For each type T that will be
+    /// dropped and which has a destructor, we must assume that all
+    /// the types/regions of T are live during the destructor, unless
+    /// they are marked with a special attribute (`#[may_dangle]`).
+    ///
+    /// [#1238]: https://github.com/rust-lang/rfcs/blob/master/text/1238-nonparametric-dropck.md
+    /// [#1327]: https://github.com/rust-lang/rfcs/blob/master/text/1327-dropck-param-eyepatch.md
+    pub fn dropck_outlives(&self, ty: Ty<'tcx>) -> InferOk<'tcx, Vec<Kind<'tcx>>> {
+        debug!(
+            "dropck_outlives(ty={:?}, param_env={:?})",
+            ty,
+            self.param_env,
+        );
+
+        let tcx = self.infcx.tcx;
+        let gcx = tcx.global_tcx();
+        let (c_ty, orig_values) = self.infcx.canonicalize_query(&self.param_env.and(ty));
+        let span = self.cause.span;
+        match &gcx.dropck_outlives(c_ty) {
+            Ok(result) if result.is_proven() => {
+                match self.infcx.instantiate_query_result(
+                    self.cause,
+                    self.param_env,
+                    &orig_values,
+                    result,
+                ) {
+                    Ok(InferOk {
+                        value: DropckOutlivesResult { kinds },
+                        obligations,
+                    }) => {
+                        return InferOk {
+                            value: kinds,
+                            obligations,
+                        };
+                    }
+
+                    Err(_) => { /* fallthrough to error-handling code below */ }
+                }
+            }
+
+            _ => { /* fallthrough to error-handling code below */ }
+        }
+
+        // Errors and ambiguity in dropck occur in two cases:
+        // - unresolved inference variables at the end of typeck
+        // - non well-formed types where projections cannot be resolved
+        // Either of these should have created an error before.
+        tcx.sess
+            .delay_span_bug(span, "dtorck encountered internal error");
+        InferOk {
+            value: vec![],
+            obligations: vec![],
+        }
+    }
+}
+
+/// Result from the `dropck_outlives` query.
+#[derive(Clone, Debug)]
+pub struct DropckOutlivesResult<'tcx> {
+    /// Types and regions that must outlive the point where the value
+    /// is dropped.
+    pub kinds: Vec<Kind<'tcx>>,
+}
+
+pub(crate) fn dropck_outlives<'tcx>(
+    tcx: TyCtxt<'_, 'tcx, 'tcx>,
+    goal: CanonicalTyGoal<'tcx>,
+) -> Result<Lrc<Canonical<'tcx, QueryResult<'tcx, DropckOutlivesResult<'tcx>>>>, NoSolution> {
+    debug!("dropck_outlives(goal={:#?})", goal);
+
+    tcx.infer_ctxt().enter(|ref infcx| {
+        let tcx = infcx.tcx;
+        let (
+            ParamEnvAnd {
+                param_env,
+                value: for_ty,
+            },
+            canonical_inference_vars,
+        ) = infcx.instantiate_canonical_with_fresh_inference_vars(DUMMY_SP, &goal);
+
+        let mut result = DropckOutlivesResult { kinds: vec![] };
+
+        // A stack of types left to process. Each round, we pop
+        // something from the stack and invoke
+        // `dtorck_constraint_for_ty`. This may produce new types that
+        // have to be pushed on the stack. This continues until we have
+        // explored all the reachable types from `for_ty`.
+        //
+        // Example: Imagine that we have the following code:
+        //
+        // ```rust
+        // struct A {
+        //     value: B,
+        //     children: Vec<A>,
+        // }
+        //
+        // struct B {
+        //     value: u32
+        // }
+        //
+        // fn f() {
+        //   let a: A = ...;
+        //   ..
+        // } // here, `a` is dropped
+        // ```
+        //
+        // at the point where `a` is dropped, we need to figure out
+        // which types inside of `a` contain region data that may be
+        // accessed by any destructors in `a`. We begin by pushing `A`
+        // onto the stack, as that is the type of `a`. We will then
+        // invoke `dtorck_constraint_for_ty` which will expand `A`
+        // into the types of its fields `(B, Vec<A>)`. These will get
+        // pushed onto the stack. Eventually, expanding `Vec<A>` will
+        // lead to us trying to push `A` a second time -- to prevent
+        // infinite recusion, we notice that `A` was already pushed
+        // once and stop.
+        let mut ty_stack = vec![(for_ty, 0)];
+
+        // Set used to detect infinite recursion.
+        let mut ty_set = FxHashSet();
+
+        let fulfill_cx = &mut FulfillmentContext::new();
+
+        let cause = ObligationCause::dummy();
+        while let Some((ty, depth)) = ty_stack.pop() {
+            let ty::DtorckConstraint {
+                dtorck_types,
+                outlives,
+            } = match tcx.dtorck_constraint_for_ty(DUMMY_SP, for_ty, depth, ty) {
+                Ok(constraint) => constraint,
+                Err(ErrorReported) => return Err(NoSolution),
+            };
+
+            // "outlives" represent types/regions that may be touched
+            // by a destructor.
+            result.kinds.extend(outlives);
+
+            // dtorck types are "types that will get dropped but which
+            // do not themselves define a destructor", more or less. We have
+            // to push them onto the stack to be expanded.
+            for ty in dtorck_types {
+                let Normalized {
+                    value: ty,
+                    obligations,
+                } = infcx.at(&cause, param_env).normalize(&ty)?;
+                fulfill_cx.register_predicate_obligations(infcx, obligations);
+
+                debug!("dropck_outlives: ty from dtorck_types = {:?}", ty);
+
+                match ty.sty {
+                    // All parameters live for the duration of the
+                    // function.
+                    ty::TyParam(..) => {}
+
+                    // A projection that we couldn't resolve - it
+                    // might have a destructor.
+                    ty::TyProjection(..) | ty::TyAnon(..) => {
+                        result.kinds.push(Kind::from(ty));
+                    }
+
+                    _ => {
+                        if ty_set.insert(ty) {
+                            ty_stack.push((ty, depth + 1));
+                        }
+                    }
+                }
+            }
+        }
+
+        debug!("dropck_outlives: result = {:#?}", result);
+
+        infcx.make_canonicalized_query_result(canonical_inference_vars, result, fulfill_cx)
+    })
+}
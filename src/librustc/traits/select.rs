@@ -22,6 +22,7 @@ use super::project;
 use super::project::{normalize_with_depth, Normalized, ProjectionCacheKey};
 use super::{PredicateObligation, TraitObligation, ObligationCause};
 use super::{ObligationCauseCode, BuiltinDerivedObligation, ImplDerivedObligation};
+use super::TraitAliasDerivedObligation;
 use super::{SelectionError, Unimplemented, OutputTypeParameterMismatch};
 use super::{ObjectCastObligation, Obligation};
 use super::TraitNotObjectSafe;
@@ -45,8 +46,8 @@ use middle::lang_items;
 use mir::interpret::{GlobalId};
 
 use rustc_data_structures::bitvec::BitVector;
+use rustc_data_structures::sync::Lock;
 use std::iter;
-use std::cell::RefCell;
 use std::cmp;
 use std::fmt;
 use std::mem;
@@ -144,7 +145,7 @@ struct TraitObligationStack<'prev, 'tcx: 'prev> {
 
 #[derive(Clone)]
 pub struct SelectionCache<'tcx> {
-    hashmap: RefCell<FxHashMap<ty::TraitRef<'tcx>,
+    hashmap: Lock<FxHashMap<ty::TraitRef<'tcx>,
                                WithDepNode<SelectionResult<'tcx, SelectionCandidate<'tcx>>>>>,
 }
 
@@ -227,6 +228,10 @@ enum SelectionCandidate<'tcx> {
     ImplCandidate(DefId),
     AutoImplCandidate(DefId),
 
+    /// A use of a trait alias, which holds if the bounds the alias
+    /// stands for hold.
+    TraitAliasCandidate(DefId),
+
     /// This is a trait matching with a projected type as `Self`, and
     /// we found an applicable bound in the trait definition.
     ProjectionCandidate,
@@ -261,6 +266,7 @@ impl<'a, 'tcx> ty::Lift<'tcx> for SelectionCandidate<'a> {
             }
             ImplCandidate(def_id) => ImplCandidate(def_id),
             AutoImplCandidate(def_id) => AutoImplCandidate(def_id),
+            TraitAliasCandidate(def_id) => TraitAliasCandidate(def_id),
             ProjectionCandidate => ProjectionCandidate,
             FnPointerCandidate => FnPointerCandidate,
             ObjectCandidate => ObjectCandidate,
@@ -409,7 +415,7 @@ impl EvaluationResult {
 
 #[derive(Clone)]
 pub struct EvaluationCache<'tcx> {
-    hashmap: RefCell<FxHashMap<ty::PolyTraitRef<'tcx>, WithDepNode<EvaluationResult>>>
+    hashmap: Lock<FxHashMap<ty::PolyTraitRef<'tcx>, WithDepNode<EvaluationResult>>>
 }
 
 impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
@@ -733,6 +739,15 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
             }
 
             ty::Predicate::ConstEvaluatable(def_id, substs) => {
+                if substs.needs_subst() {
+                    // See the corresponding case in `FulfillmentContext`.
+                    let predicate = self.infcx.resolve_type_vars_if_possible(&obligation.predicate);
+                    return if obligation.param_env.caller_bounds.contains(&predicate) {
+                        EvaluatedToOk
+                    } else {
+                        EvaluatedToAmbig
+                    };
+                }
                 let tcx = self.tcx();
                 match tcx.lift_to_global(&(obligation.param_env, substs)) {
                     Some((param_env, substs)) => {
@@ -1372,7 +1387,10 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
 
         let def_id = obligation.predicate.def_id();
         let lang_items = self.tcx().lang_items();
-        if lang_items.copy_trait() == Some(def_id) {
+        if self.tcx().is_trait_alias(def_id) {
+            // A trait alias has no impls; it is proven by its bounds.
+            candidates.vec.push(TraitAliasCandidate(def_id));
+        } else if lang_items.copy_trait() == Some(def_id) {
             debug!("obligation self ty is {:?}",
                    obligation.predicate.0.self_ty());
 
@@ -1462,7 +1480,7 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
 
         let (def_id, substs) = match skol_trait_predicate.trait_ref.self_ty().sty {
             ty::TyProjection(ref data) =>
-                (data.trait_ref(self.tcx()).def_id, data.substs),
+                (data.bounds_def_id(self.tcx()), data.substs),
             ty::TyAnon(def_id, substs) => (def_id, substs),
             _ => {
                 span_bug!(
@@ -1977,6 +1995,7 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
                          when there are other valid candidates");
                 }
                 ImplCandidate(..) |
+                TraitAliasCandidate(..) |
                 ClosureCandidate |
                 GeneratorCandidate |
                 FnPointerCandidate |
@@ -2085,7 +2104,8 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
 
             ty::TyInfer(ty::FreshTy(_))
             | ty::TyInfer(ty::FreshIntTy(_))
-            | ty::TyInfer(ty::FreshFloatTy(_)) => {
+            | ty::TyInfer(ty::FreshFloatTy(_))
+            | ty::TyInfer(ty::CanonicalTy(_)) => {
                 bug!("asked to assemble builtin bounds of unexpected type: {:?}",
                      self_ty);
             }
@@ -2156,7 +2176,8 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
 
             ty::TyInfer(ty::FreshTy(_))
             | ty::TyInfer(ty::FreshIntTy(_))
-            | ty::TyInfer(ty::FreshFloatTy(_)) => {
+            | ty::TyInfer(ty::FreshFloatTy(_))
+            | ty::TyInfer(ty::CanonicalTy(_)) => {
                 bug!("asked to assemble builtin bounds of unexpected type: {:?}",
                      self_ty);
             }
@@ -2198,7 +2219,8 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
             ty::TyInfer(ty::TyVar(_)) |
             ty::TyInfer(ty::FreshTy(_)) |
             ty::TyInfer(ty::FreshIntTy(_)) |
-            ty::TyInfer(ty::FreshFloatTy(_)) => {
+            ty::TyInfer(ty::FreshFloatTy(_)) |
+            ty::TyInfer(ty::CanonicalTy(_)) => {
                 bug!("asked to assemble constituent types of unexpected type: {:?}",
                      t);
             }
@@ -2338,6 +2360,11 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
                 Ok(VtableImpl(self.confirm_impl_candidate(obligation, impl_def_id)))
             }
 
+            TraitAliasCandidate(alias_def_id) => {
+                let data = self.confirm_trait_alias_candidate(obligation, alias_def_id);
+                Ok(VtableBuiltin(data))
+            }
+
             ClosureCandidate => {
                 let vtable_closure = self.confirm_closure_candidate(obligation)?;
                 Ok(VtableClosure(vtable_closure))
@@ -2547,6 +2574,37 @@ impl<'cx, 'gcx, 'tcx> SelectionContext<'cx, 'gcx, 'tcx> {
         })
     }
 
+    /// A trait alias holds when each of its bounds holds, so using one just
+    /// means proving the alias's predicates with `Self` and its parameters
+    /// substituted.
+    fn confirm_trait_alias_candidate(&mut self,
+                                     obligation: &TraitObligation<'tcx>,
+                                     alias_def_id: DefId)
+                                     -> VtableBuiltinData<PredicateObligation<'tcx>>
+    {
+        debug!("confirm_trait_alias_candidate({:?}, {:?})",
+               obligation,
+               alias_def_id);
+
+        self.in_snapshot(|this, snapshot| {
+            let poly_trait_ref = obligation.predicate.to_poly_trait_ref();
+            let (trait_ref, skol_map) =
+                this.infcx().skolemize_late_bound_regions(&poly_trait_ref, snapshot);
+            let cause = obligation.derived_cause(TraitAliasDerivedObligation);
+            let nested = this.impl_or_trait_obligations(cause,
+                                                        obligation.recursion_depth + 1,
+                                                        obligation.param_env,
+                                                        alias_def_id,
+                                                        &trait_ref.substs,
+                                                        skol_map,
+                                                        snapshot);
+
+            debug!("confirm_trait_alias_candidate: nested={:?}", nested);
+
+            VtableBuiltinData { nested }
+        })
+    }
+
     fn vtable_impl(&mut self,
                    impl_def_id: DefId,
                    mut substs: Normalized<'tcx, &'tcx Substs<'tcx>>,
@@ -3343,7 +3401,7 @@ impl<'tcx> TraitObligation<'tcx> {
 impl<'tcx> SelectionCache<'tcx> {
     pub fn new() -> SelectionCache<'tcx> {
         SelectionCache {
-            hashmap: RefCell::new(FxHashMap())
+            hashmap: Lock::new(FxHashMap())
         }
     }
 
@@ -3355,7 +3413,7 @@ impl<'tcx> SelectionCache<'tcx> {
 impl<'tcx> EvaluationCache<'tcx> {
     pub fn new() -> EvaluationCache<'tcx> {
         EvaluationCache {
-            hashmap: RefCell::new(FxHashMap())
+            hashmap: Lock::new(FxHashMap())
         }
     }
 
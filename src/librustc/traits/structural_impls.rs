@@ -220,6 +220,9 @@ impl<'a, 'tcx> Lift<'tcx> for traits::ObligationCauseCode<'a> {
             super::ImplDerivedObligation(ref cause) => {
                 tcx.lift(cause).map(super::ImplDerivedObligation)
             }
+            super::TraitAliasDerivedObligation(ref cause) => {
+                tcx.lift(cause).map(super::TraitAliasDerivedObligation)
+            }
             super::CompareImplMethodObligation { item_name,
                                                  impl_item_def_id,
                                                  trait_item_def_id } => {
@@ -550,6 +553,9 @@ impl<'tcx> TypeFoldable<'tcx> for traits::ObligationCauseCode<'tcx> {
             super::ImplDerivedObligation(ref cause) => {
                 super::ImplDerivedObligation(cause.fold_with(folder))
             }
+            super::TraitAliasDerivedObligation(ref cause) => {
+                super::TraitAliasDerivedObligation(cause.fold_with(folder))
+            }
         }
     }
 
@@ -587,7 +593,8 @@ impl<'tcx> TypeFoldable<'tcx> for traits::ObligationCauseCode<'tcx> {
             super::ObjectTypeBound(ty, r) => ty.visit_with(visitor) || r.visit_with(visitor),
             super::ObjectCastObligation(ty) => ty.visit_with(visitor),
             super::BuiltinDerivedObligation(ref cause) => cause.visit_with(visitor),
-            super::ImplDerivedObligation(ref cause) => cause.visit_with(visitor)
+            super::ImplDerivedObligation(ref cause) => cause.visit_with(visitor),
+            super::TraitAliasDerivedObligation(ref cause) => cause.visit_with(visitor)
         }
     }
 }
@@ -113,6 +113,7 @@ impl FlagComputation {
                     ty::FreshTy(_) |
                     ty::FreshIntTy(_) |
                     ty::FreshFloatTy(_) => {}
+                    ty::CanonicalTy(_) => self.add_flags(TypeFlags::HAS_CANONICAL_VARS),
                     _ => self.add_flags(TypeFlags::KEEP_IN_LOCAL_TCX)
                 }
             }
@@ -223,6 +224,9 @@ impl FlagComputation {
                 self.add_flags(TypeFlags::HAS_PROJECTION);
                 self.add_substs(substs);
             }
+            ConstVal::Param(_) => {
+                self.add_flags(TypeFlags::HAS_PARAMS);
+            }
         }
     }
 
@@ -611,12 +611,20 @@ impl<'tcx> TypeVisitor<'tcx> for HasTypeFlagsVisitor {
     }
 
     fn visit_const(&mut self, c: &'tcx ty::Const<'tcx>) -> bool {
-        if let ConstVal::Unevaluated(..) = c.val {
-            let projection_flags = TypeFlags::HAS_NORMALIZABLE_PROJECTION |
-                TypeFlags::HAS_PROJECTION;
-            if projection_flags.intersects(self.flags) {
-                return true;
+        match c.val {
+            ConstVal::Unevaluated(..) => {
+                let projection_flags = TypeFlags::HAS_NORMALIZABLE_PROJECTION |
+                    TypeFlags::HAS_PROJECTION;
+                if projection_flags.intersects(self.flags) {
+                    return true;
+                }
+            }
+            ConstVal::Param(_) => {
+                if TypeFlags::HAS_PARAMS.intersects(self.flags) {
+                    return true;
+                }
             }
+            ConstVal::Value(_) => {}
         }
         c.super_visit_with(self)
     }
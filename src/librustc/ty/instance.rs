@@ -106,7 +106,12 @@ impl<'tcx> InstanceDef<'tcx> {
             return true
         }
         let trans_fn_attrs = tcx.trans_fn_attrs(self.def_id());
-        trans_fn_attrs.requests_inline() || tcx.is_const_fn(self.def_id())
+        if trans_fn_attrs.requests_inline() || tcx.is_const_fn(self.def_id()) {
+            return true
+        }
+        // There is no code for the functions of MIR-only rlibs, except for
+        // those whose symbol names are fixed.
+        tcx.is_mir_only_rlib(self.def_id().krate) && !trans_fn_attrs.contains_extern_indicator()
     }
 }
 
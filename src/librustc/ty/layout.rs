@@ -1306,9 +1306,33 @@ impl<'a, 'tcx> LayoutCx<'tcx, TyCtxt<'a, 'tcx, 'tcx>> {
             // Tuples, generators and closures.
             ty::TyGenerator(def_id, ref substs, _) => {
                 let tys = substs.field_tys(def_id, tcx);
-                univariant(&tys.map(|ty| self.layout_of(ty)).collect::<Result<Vec<_>, _>>()?,
-                    &ReprOptions::default(),
-                    StructKind::AlwaysSized)?
+                let mut fields = tys.map(|ty| self.layout_of(ty)).collect::<Result<Vec<_>, _>>()?;
+
+                // Saved locals of different types may share a field, which
+                // then has to be large and aligned enough for each of them.
+                let prefix_len = substs.pre_transforms_tys(def_id, tcx).count();
+                let shared_tys = substs.state_shared_tys(def_id, tcx);
+                for (field, shared_tys) in fields[prefix_len..].iter_mut().zip(shared_tys) {
+                    if shared_tys.is_empty() {
+                        continue;
+                    }
+                    let mut align = field.align;
+                    let mut size = field.size;
+                    for ty in shared_tys {
+                        let shared = self.layout_of(ty)?;
+                        align = align.max(shared.align);
+                        size = cmp::max(size, shared.size);
+                    }
+                    field.details = tcx.intern_layout(LayoutDetails {
+                        variants: Variants::Single { index: 0 },
+                        fields: FieldPlacement::Union(0),
+                        abi: Abi::Aggregate { sized: true },
+                        align,
+                        size: size.abi_align(align)
+                    });
+                }
+
+                univariant(&fields, &ReprOptions::default(), StructKind::AlwaysSized)?
             }
 
             ty::TyClosure(def_id, ref substs) => {
@@ -0,0 +1,289 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Query jobs represent the queries currently being executed. Each job knows
+//! the query which started it, so that query cycles can be found by walking
+//! up the jobs, and with `cfg(parallel_queries)`, threads which need the
+//! result of a query being executed by another thread wait on its job.
+//!
+//! If all the threads of a `ThreadPool` end up waiting on each other, which
+//! is only possible if the queries they wait for form a cycle,
+//! `handle_deadlock` finds that cycle and resumes one of the waiting threads
+//! with a cycle error.
+
+use rustc_data_structures::sync::Lrc;
+use std::ptr;
+use syntax_pos::Span;
+use ty::context::TyCtxt;
+use ty::tls;
+use ty::maps::Query;
+use ty::maps::plumbing::CycleError;
+
+#[cfg(parallel_queries)]
+use rustc_data_structures::fx::FxHashSet;
+#[cfg(parallel_queries)]
+use rustc_data_structures::sync::Lock;
+#[cfg(parallel_queries)]
+use std::sync::{Condvar, Mutex};
+#[cfg(parallel_queries)]
+use util::thread_pool;
+
+/// Represents a span and a query key.
+#[derive(Clone, Debug)]
+pub struct QueryInfo<'tcx> {
+    /// The span for a reason this query was required
+    pub span: Span,
+    pub query: Query<'tcx>,
+}
+
+/// A result of the query being executed, or the fact that it panicked.
+pub(super) enum QueryResult<'tcx> {
+    Started(Lrc<QueryJob<'tcx>>),
+    Poisoned,
+}
+
+/// A query which is being executed.
+pub struct QueryJob<'tcx> {
+    pub info: QueryInfo<'tcx>,
+
+    /// The job of the query which started this one, if any.
+    pub parent: Option<Lrc<QueryJob<'tcx>>>,
+
+    /// The threads waiting for this query to complete.
+    #[cfg(parallel_queries)]
+    latch: QueryLatch<'tcx>,
+}
+
+impl<'tcx> QueryJob<'tcx> {
+    pub fn new(info: QueryInfo<'tcx>, parent: Option<Lrc<QueryJob<'tcx>>>) -> Self {
+        QueryJob {
+            info,
+            parent,
+            #[cfg(parallel_queries)]
+            latch: QueryLatch::new(),
+        }
+    }
+
+    /// Waits for this query to complete, which it has when this returns
+    /// `Ok`. Returns a cycle error if completing it requires the result of
+    /// the query calling `await`.
+    ///
+    /// Without `cfg(parallel_queries)`, a query which has been started but
+    /// isn't complete can only be one of the callers of the current query, so
+    /// this always returns an error.
+    pub(super) fn await<'a, 'lcx>(&self,
+                                  tcx: TyCtxt<'a, 'tcx, 'lcx>,
+                                  span: Span)
+                                  -> Result<(), CycleError<'tcx>> {
+        #[cfg(parallel_queries)]
+        {
+            if !self.is_started_by_current_thread(tcx) {
+                return self.latch.await(tls::current_query(tcx), span);
+            }
+        }
+
+        Err(self.find_cycle_in_stack(tcx, span))
+    }
+
+    /// Returns the cycle between this query and the query being executed by
+    /// the current thread, which is one of its callers.
+    fn find_cycle_in_stack<'a, 'lcx>(&self, tcx: TyCtxt<'a, 'tcx, 'lcx>, span: Span)
+                                     -> CycleError<'tcx> {
+        let mut cycle = Vec::new();
+        let mut current_job = tls::current_query(tcx);
+
+        while let Some(job) = current_job {
+            cycle.insert(0, job.info.clone());
+
+            if ptr::eq(&*job, self) {
+                return CycleError { span, cycle };
+            }
+
+            current_job = job.parent.clone();
+        }
+
+        bug!("no cycle found for an incomplete query")
+    }
+
+    #[cfg(parallel_queries)]
+    fn is_started_by_current_thread<'a, 'lcx>(&self, tcx: TyCtxt<'a, 'tcx, 'lcx>) -> bool {
+        let mut current_job = tls::current_query(tcx);
+        while let Some(job) = current_job {
+            if ptr::eq(&*job, self) {
+                return true;
+            }
+            current_job = job.parent.clone();
+        }
+        false
+    }
+
+    /// Wakes up the threads waiting for this query, once its result has been
+    /// stored, or it panicked.
+    pub(super) fn signal_complete(&self) {
+        #[cfg(parallel_queries)]
+        self.latch.set();
+    }
+}
+
+/// A thread waiting for a query to complete.
+#[cfg(parallel_queries)]
+struct QueryWaiter<'tcx> {
+    /// The query being executed by the waiting thread, if any.
+    query: Option<Lrc<QueryJob<'tcx>>>,
+    span: Span,
+    condvar: Condvar,
+    /// Set by `handle_deadlock` to resume the thread with an error.
+    cycle: Lock<Option<CycleError<'tcx>>>,
+}
+
+#[cfg(parallel_queries)]
+struct QueryLatchInfo<'tcx> {
+    complete: bool,
+    waiters: Vec<Lrc<QueryWaiter<'tcx>>>,
+}
+
+#[cfg(parallel_queries)]
+struct QueryLatch<'tcx> {
+    info: Mutex<QueryLatchInfo<'tcx>>,
+}
+
+#[cfg(parallel_queries)]
+impl<'tcx> QueryLatch<'tcx> {
+    fn new() -> Self {
+        QueryLatch {
+            info: Mutex::new(QueryLatchInfo {
+                complete: false,
+                waiters: Vec::new(),
+            }),
+        }
+    }
+
+    fn await(&self, query: Option<Lrc<QueryJob<'tcx>>>, span: Span)
+             -> Result<(), CycleError<'tcx>> {
+        let waiter = Lrc::new(QueryWaiter {
+            query,
+            span,
+            condvar: Condvar::new(),
+            cycle: Lock::new(None),
+        });
+
+        {
+            let mut info = self.info.lock().unwrap();
+            if info.complete {
+                return Ok(());
+            }
+            info.waiters.push(waiter.clone());
+        }
+
+        // This may call `handle_deadlock`, which needs to lock the latches,
+        // so it has to be done while `info` isn't locked. Then `set` and
+        // `handle_deadlock` can both have been called before the thread
+        // starts waiting, which is why the state of the latch and of the
+        // waiter are checked before waiting.
+        thread_pool::mark_blocked();
+
+        let mut info = self.info.lock().unwrap();
+        while !info.complete && waiter.cycle.lock().is_none() {
+            info = waiter.condvar.wait(info).unwrap();
+        }
+        drop(info);
+
+        thread_pool::mark_unblocked();
+
+        match waiter.cycle.lock().take() {
+            None => Ok(()),
+            Some(cycle) => Err(cycle),
+        }
+    }
+
+    fn set(&self) {
+        let mut info = self.info.lock().unwrap();
+        debug_assert!(!info.complete);
+        info.complete = true;
+        for waiter in info.waiters.drain(..) {
+            waiter.condvar.notify_one();
+        }
+    }
+
+    fn waiters(&self) -> Vec<Lrc<QueryWaiter<'tcx>>> {
+        self.info.lock().unwrap().waiters.clone()
+    }
+
+    /// Removes `waiter` from the latch and wakes it up with a cycle error.
+    fn resume_with_cycle(&self, waiter: &Lrc<QueryWaiter<'tcx>>, cycle: CycleError<'tcx>) {
+        let mut info = self.info.lock().unwrap();
+        info.waiters.retain(|w| !Lrc::ptr_eq(w, waiter));
+        *waiter.cycle.lock() = Some(cycle);
+        waiter.condvar.notify_one();
+    }
+}
+
+/// Called by a `ThreadPool` when all its threads are waiting for queries to
+/// complete. The queries they're waiting for then form a cycle: this finds
+/// it, and makes one of the threads report it as a cycle error.
+#[cfg(parallel_queries)]
+pub fn handle_deadlock<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    // Each waiting thread, along with the query it's waiting for.
+    let mut waiters = Vec::new();
+    for job in tcx.maps.collect_active_jobs() {
+        for waiter in job.latch.waiters() {
+            waiters.push((waiter, job.clone()));
+        }
+    }
+
+    // The thread executing the query waited for by `waiters[i]` is itself
+    // waiting, and we follow these edges until we get back to a thread we
+    // have already seen.
+    let waiting_for = |i: usize| -> usize {
+        let target = &waiters[i].1;
+        waiters.iter().position(|&(ref waiter, _)| {
+            let mut current_job = waiter.query.clone();
+            while let Some(job) = current_job {
+                if Lrc::ptr_eq(&job, target) {
+                    return true;
+                }
+                current_job = job.parent.clone();
+            }
+            false
+        }).unwrap_or_else(|| bug!("deadlock detected, but a query isn't waited on"))
+    };
+
+    let mut seen = FxHashSet();
+    let mut path = vec![];
+    let mut current = 0;
+    while seen.insert(current) {
+        path.push(current);
+        current = waiting_for(current);
+    }
+    let start = path.iter().position(|&i| i == current).unwrap();
+    let cycle_waiters = &path[start..];
+
+    // The cycle goes from each query waited for to the thread waiting for
+    // the next query, and then from that query on.
+    let mut cycle = vec![];
+    for (n, &i) in cycle_waiters.iter().enumerate() {
+        let target = &waiters[i].1;
+        let next = cycle_waiters[(n + 1) % cycle_waiters.len()];
+        let mut infos = vec![];
+        let mut current_job = waiters[next].0.query.clone();
+        while let Some(job) = current_job {
+            infos.push(job.info.clone());
+            if Lrc::ptr_eq(&job, target) {
+                break;
+            }
+            current_job = job.parent.clone();
+        }
+        cycle.extend(infos.into_iter().rev());
+    }
+
+    let (ref waiter, ref job) = waiters[cycle_waiters[0]];
+    let cycle = CycleError { span: waiter.span, cycle };
+    job.latch.resume_with_cycle(waiter, cycle);
+}
@@ -14,6 +14,8 @@ use hir::def_id::{CrateNum, DefId, DefIndex};
 use hir::def::{Def, Export};
 use hir::{self, TraitCandidate, ItemLocalId, TransFnAttrs};
 use hir::svh::Svh;
+use infer::canonical::{Canonical, QueryResult};
+use infer::outlives::bounds::OutlivesBound;
 use lint;
 use middle::borrowck::BorrowCheckResult;
 use middle::cstore::{ExternCrate, LinkagePreference, NativeLibrary,
@@ -33,6 +35,9 @@ use mir::interpret::{GlobalId};
 use session::{CompileResult, CrateDisambiguator};
 use session::config::OutputFilenames;
 use traits::Vtable;
+use traits::query::{CanonicalProjectionGoal, CanonicalTyGoal, NoSolution};
+use traits::query::dropck_outlives::DropckOutlivesResult;
+use traits::query::normalize::NormalizationResult;
 use traits::specialization_graph;
 use ty::{self, CrateInherentImpls, Ty, TyCtxt};
 use ty::steal::Steal;
@@ -59,7 +64,12 @@ use syntax::symbol::Symbol;
 #[macro_use]
 mod plumbing;
 use self::plumbing::*;
-pub use self::plumbing::force_from_dep_node;
+pub use self::plumbing::{force_from_dep_node, poisoned_query_count};
+
+mod job;
+pub use self::job::{QueryJob, QueryInfo};
+#[cfg(parallel_queries)]
+pub use self::job::handle_deadlock;
 
 mod keys;
 pub use self::keys::Key;
@@ -170,6 +180,10 @@ define_maps! { <'tcx>
     /// for trans. This is also the only query that can fetch non-local MIR, at present.
     [] fn optimized_mir: MirOptimized(DefId) -> &'tcx mir::Mir<'tcx>,
 
+    /// The coverage counters inserted into the optimized MIR of a function
+    /// by `-Z instrument-coverage`, and the source code they count.
+    [] fn coverage_info: CoverageInfo(DefId) -> Lrc<mir::CoverageInfo>,
+
     /// The result of unsafety-checking this def-id.
     [] fn unsafety_check_result: UnsafetyCheckResult(DefId) -> mir::UnsafetyCheckResult,
 
@@ -195,9 +209,10 @@ define_maps! { <'tcx>
 
     [] fn borrowck: BorrowCheck(DefId) -> Lrc<BorrowCheckResult>,
 
-    /// Borrow checks the function body. If this is a closure, returns
-    /// additional requirements that the closure's creator must verify.
-    [] fn mir_borrowck: MirBorrowCheck(DefId) -> Option<mir::ClosureRegionRequirements<'tcx>>,
+    /// Borrow checks the function body. If this is a closure, the result
+    /// includes additional requirements that the closure's creator must
+    /// verify.
+    [] fn mir_borrowck: MirBorrowCheck(DefId) -> mir::BorrowCheckResult<'tcx>,
 
     /// Gets a complete map from all types to their inherent impls.
     /// Not meant to be used directly outside of coherence.
@@ -281,6 +296,7 @@ define_maps! { <'tcx>
     [fatal_cycle] fn is_profiler_runtime: IsProfilerRuntime(CrateNum) -> bool,
     [fatal_cycle] fn panic_strategy: GetPanicStrategy(CrateNum) -> PanicStrategy,
     [fatal_cycle] fn is_no_builtins: IsNoBuiltins(CrateNum) -> bool,
+    [fatal_cycle] fn is_mir_only_rlib: IsMirOnlyRlib(CrateNum) -> bool,
 
     [] fn extern_crate: ExternCrate(DefId) -> Lrc<Option<ExternCrate>>,
 
@@ -332,8 +348,8 @@ define_maps! { <'tcx>
         Option<Lrc<FxHashMap<ItemLocalId, Region>>>,
     [] fn is_late_bound_map: IsLateBound(DefIndex) ->
         Option<Lrc<FxHashSet<ItemLocalId>>>,
-    [] fn object_lifetime_defaults_map: ObjectLifetimeDefaults(DefIndex)
-        -> Option<Lrc<FxHashMap<ItemLocalId, Lrc<Vec<ObjectLifetimeDefault>>>>>,
+    [] fn object_lifetime_defaults: ObjectLifetimeDefaults(DefId)
+        -> Lrc<Vec<ObjectLifetimeDefault>>,
 
     [] fn visibility: Visibility(DefId) -> ty::Visibility,
     [] fn dep_kind: DepKind(CrateNum) -> DepKind,
@@ -380,6 +396,30 @@ define_maps! { <'tcx>
     [] fn erase_regions_ty: erase_regions_ty(Ty<'tcx>) -> Ty<'tcx>,
     [] fn fully_normalize_monormophic_ty: normalize_ty_node(Ty<'tcx>) -> Ty<'tcx>,
 
+    /// Do not call this query directly: invoke `normalize` instead.
+    [] fn normalize_projection_ty: normalize_projection_ty_node(
+        CanonicalProjectionGoal<'tcx>
+    ) -> Result<
+        Lrc<Canonical<'tcx, QueryResult<'tcx, NormalizationResult<'tcx>>>>,
+        NoSolution,
+    >,
+
+    /// Do not call this query directly: invoke `infcx.at().dropck_outlives()` instead.
+    [] fn dropck_outlives: dropck_outlives_node(
+        CanonicalTyGoal<'tcx>
+    ) -> Result<
+        Lrc<Canonical<'tcx, QueryResult<'tcx, DropckOutlivesResult<'tcx>>>>,
+        NoSolution,
+    >,
+
+    /// Do not call this query directly: invoke `infcx.implied_outlives_bounds()` instead.
+    [] fn implied_outlives_bounds: implied_outlives_bounds_node(
+        CanonicalTyGoal<'tcx>
+    ) -> Result<
+        Lrc<Canonical<'tcx, QueryResult<'tcx, Vec<OutlivesBound<'tcx>>>>>,
+        NoSolution,
+    >,
+
     [] fn substitute_normalize_and_test_predicates:
         substitute_normalize_and_test_predicates_node((DefId, &'tcx Substs<'tcx>)) -> bool,
 
@@ -541,6 +581,18 @@ fn normalize_ty_node<'tcx>(_: Ty<'tcx>) -> DepConstructor<'tcx> {
     DepConstructor::NormalizeTy
 }
 
+fn normalize_projection_ty_node<'tcx>(_: CanonicalProjectionGoal<'tcx>) -> DepConstructor<'tcx> {
+    DepConstructor::NormalizeProjectionTy
+}
+
+fn dropck_outlives_node<'tcx>(_: CanonicalTyGoal<'tcx>) -> DepConstructor<'tcx> {
+    DepConstructor::DropckOutlives
+}
+
+fn implied_outlives_bounds_node<'tcx>(_: CanonicalTyGoal<'tcx>) -> DepConstructor<'tcx> {
+    DepConstructor::ImpliedOutlivesBounds
+}
+
 fn substitute_normalize_and_test_predicates_node<'tcx>(key: (DefId, &'tcx Substs<'tcx>))
                                             -> DepConstructor<'tcx> {
     DepConstructor::SubstituteNormalizeAndTestPredicates { key }
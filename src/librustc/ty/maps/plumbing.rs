@@ -14,20 +14,28 @@
 
 use dep_graph::{DepNodeIndex, DepNode, DepKind, DepNodeColor};
 use errors::DiagnosticBuilder;
+use errors::FatalError;
 use ty::{TyCtxt};
 use ty::maps::Query; // NB: actually generated by the macros in this file
 use ty::maps::config::QueryDescription;
+use ty::maps::job::{QueryResult, QueryInfo, QueryJob};
 use ty::item_path;
+use ty::tls;
 
 use rustc_data_structures::fx::{FxHashMap};
-use std::cell::{Ref, RefMut};
+use rustc_data_structures::sync::{Lrc, Lock, LockGuard};
+use std::cell::Cell;
+use std::collections::hash_map::Entry;
 use std::marker::PhantomData;
 use std::mem;
+use std::ptr;
 use syntax_pos::Span;
 
 pub(super) struct QueryMap<'tcx, D: QueryDescription<'tcx>> {
     phantom: PhantomData<(D, &'tcx ())>,
     pub(super) map: FxHashMap<D::Key, QueryValue<D::Value>>,
+    /// The queries being executed, or which panicked.
+    pub(super) active: FxHashMap<D::Key, QueryResult<'tcx>>,
 }
 
 pub(super) struct QueryValue<T> {
@@ -51,29 +59,146 @@ impl<'tcx, M: QueryDescription<'tcx>> QueryMap<'tcx, M> {
         QueryMap {
             phantom: PhantomData,
             map: FxHashMap(),
+            active: FxHashMap(),
         }
     }
 }
 
 pub(super) trait GetCacheInternal<'tcx>: QueryDescription<'tcx> + Sized {
+    fn query_map<'a, 'lcx>(tcx: TyCtxt<'a, 'tcx, 'lcx>) -> &'a Lock<QueryMap<'tcx, Self>>;
+
+    fn to_query(key: Self::Key) -> Query<'tcx>;
+
     fn get_cache_internal<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>)
-                              -> Ref<'a, QueryMap<'tcx, Self>>;
+                              -> LockGuard<'a, QueryMap<'tcx, Self>> {
+        Self::query_map(tcx).borrow()
+    }
+}
+
+/// A type representing the responsibility to execute the job in the `job`
+/// field. This will poison the relevant query if dropped.
+pub(super) struct JobOwner<'a, 'tcx: 'a, Q: QueryDescription<'tcx> + 'a> {
+    map: &'a Lock<QueryMap<'tcx, Q>>,
+    key: Q::Key,
+    job: Lrc<QueryJob<'tcx>>,
 }
 
-pub(super) struct CycleError<'a, 'tcx: 'a> {
-    span: Span,
-    cycle: RefMut<'a, [(Span, Query<'tcx>)]>,
+/// The result of `JobOwner::try_get`.
+pub(super) enum TryGetJob<'a, 'tcx: 'a, D: QueryDescription<'tcx> + 'a> {
+    /// The query is not yet started. Contains a guard to the map eventually
+    /// used to start it.
+    NotYetStarted(JobOwner<'a, 'tcx, D>),
+
+    /// The query was already completed.
+    /// Returns the result of the query and its dep node index
+    /// if it succeeded or a cycle error if it failed.
+    JobCompleted(Result<(D::Value, DepNodeIndex), CycleError<'tcx>>),
+}
+
+impl<'a, 'tcx, Q: GetCacheInternal<'tcx>> JobOwner<'a, 'tcx, Q> {
+    /// Either gets a `JobOwner` corresponding to the query, allowing us to
+    /// start executing it, or returns the result of the query. If the query
+    /// is being executed by another thread, this waits for it to complete.
+    /// If the query panicked, this raises a fatal error.
+    pub(super) fn try_get<'lcx>(tcx: TyCtxt<'a, 'tcx, 'lcx>, span: Span, key: &Q::Key)
+                                -> TryGetJob<'a, 'tcx, Q> {
+        let map = Q::query_map(tcx);
+        loop {
+            let mut lock = map.borrow_mut();
+            if let Some(value) = lock.map.get(key) {
+                return TryGetJob::JobCompleted(Ok((value.value.clone(), value.index)));
+            }
+
+            let job = match lock.active.entry((*key).clone()) {
+                Entry::Occupied(entry) => {
+                    match *entry.get() {
+                        QueryResult::Started(ref job) => job.clone(),
+                        QueryResult::Poisoned => FatalError.raise(),
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    // No job entry for this query, so we start a new one.
+                    let info = QueryInfo {
+                        span,
+                        query: Q::to_query(key.clone()),
+                    };
+                    let job = Lrc::new(QueryJob::new(info, tls::current_query(tcx)));
+                    entry.insert(QueryResult::Started(job.clone()));
+                    return TryGetJob::NotYetStarted(JobOwner {
+                        map,
+                        key: (*key).clone(),
+                        job,
+                    });
+                }
+            };
+            mem::drop(lock);
+
+            if let Err(cycle) = job.await(tcx, span) {
+                return TryGetJob::JobCompleted(Err(cycle));
+            }
+        }
+    }
+
+    /// Completes the query by storing its result in the map, and wakes up
+    /// the threads waiting for it.
+    pub(super) fn complete(self, result: &Q::Value, dep_node_index: DepNodeIndex) {
+        // We can move out of `self` here because we `mem::forget` it below.
+        let key = unsafe { ptr::read(&self.key) };
+        let job = unsafe { ptr::read(&self.job) };
+        let map = self.map;
+        mem::forget(self);
+
+        let value = QueryValue::new(result.clone(), dep_node_index);
+        {
+            let mut lock = map.borrow_mut();
+            lock.active.remove(&key);
+            lock.map.insert(key, value);
+        }
+
+        job.signal_complete();
+    }
+
+    /// Executes `compute` as the job of this query, so that the queries it
+    /// calls know which query started them.
+    pub(super) fn start<F, R>(&self, compute: F) -> R
+        where F: FnOnce() -> R
+    {
+        tls::enter_query(&self.job, compute)
+    }
+}
+
+thread_local!(static POISONED_JOBS: Cell<usize> = Cell::new(0));
+
+/// The number of queries that the current thread poisoned by unwinding out
+/// of them. Asking for such a query again raises a fatal error, so code
+/// that recovers from a panic must check that the count didn't change, i.e.
+/// that the panic didn't come from a nested query.
+pub fn poisoned_query_count() -> usize {
+    POISONED_JOBS.with(|count| count.get())
+}
+
+impl<'a, 'tcx, Q: QueryDescription<'tcx>> Drop for JobOwner<'a, 'tcx, Q> {
+    fn drop(&mut self) {
+        // Poison the query so jobs waiting on it panic.
+        self.map.borrow_mut().active.insert(self.key.clone(), QueryResult::Poisoned);
+        POISONED_JOBS.with(|count| count.set(count.get() + 1));
+        // Also signal the completion of the job, so waiters
+        // will continue execution.
+        self.job.signal_complete();
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct CycleError<'tcx> {
+    /// The span of the query which completed the cycle.
+    pub(super) span: Span,
+    pub(super) cycle: Vec<QueryInfo<'tcx>>,
 }
 
 impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
-    pub(super) fn report_cycle(self, CycleError { span, cycle }: CycleError)
+    pub(super) fn report_cycle(self, CycleError { span, cycle: stack }: CycleError<'gcx>)
         -> DiagnosticBuilder<'a>
     {
-        // Subtle: release the refcell lock before invoking `describe()`
-        // below by dropping `cycle`.
-        let stack = cycle.to_vec();
-        mem::drop(cycle);
-
         assert!(!stack.is_empty());
 
         // Disable naming impls with types in this path, since that
@@ -81,48 +206,64 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         // (And cycle errors around impls tend to occur during the
         // collect/coherence phases anyhow.)
         item_path::with_forced_impl_filename_line(|| {
+            let span = stack[0].query.default_span(self, span);
             let span = self.sess.codemap().def_span(span);
             let mut err =
                 struct_span_err!(self.sess, span, E0391,
-                                 "cyclic dependency detected");
+                                 "cycle detected when {}", stack[0].query.describe(self));
             err.span_label(span, "cyclic reference");
 
-            err.span_note(self.sess.codemap().def_span(stack[0].0),
-                          &format!("the cycle begins when {}...", stack[0].1.describe(self)));
+            let first_span = stack[0].query.default_span(self, stack[0].span);
+            err.span_note(self.sess.codemap().def_span(first_span),
+                          &format!("the cycle begins when {}...", stack[0].query.describe(self)));
 
-            for &(span, ref query) in &stack[1..] {
+            for &QueryInfo { span, ref query } in &stack[1..] {
+                let span = query.default_span(self, span);
                 err.span_note(self.sess.codemap().def_span(span),
                               &format!("...which then requires {}...", query.describe(self)));
             }
 
             err.note(&format!("...which then again requires {}, completing the cycle.",
-                              stack[0].1.describe(self)));
+                              stack[0].query.describe(self)));
 
             return err
         })
     }
 
-    pub(super) fn cycle_check<F, R>(self, span: Span, query: Query<'gcx>, compute: F)
-                                    -> Result<R, CycleError<'a, 'gcx>>
-        where F: FnOnce() -> R
-    {
-        {
-            let mut stack = self.maps.query_stack.borrow_mut();
-            if let Some((i, _)) = stack.iter().enumerate().rev()
-                                       .find(|&(_, &(_, ref q))| *q == query) {
-                return Err(CycleError {
-                    span,
-                    cycle: RefMut::map(stack, |stack| &mut stack[i..])
-                });
-            }
-            stack.push((span, query));
+    /// Prints the queries being executed by the current thread, innermost
+    /// first, so that an ICE says what the compiler was doing when it happened.
+    pub fn try_print_query_stack(self) {
+        thread_local! {
+            static PRINTING: Cell<bool> = Cell::new(false)
         }
 
-        let result = compute();
+        // Describing a query may itself ICE, don't print the stack again then.
+        if PRINTING.with(|printing| printing.replace(true)) {
+            return;
+        }
+
+        item_path::with_forced_impl_filename_line(|| {
+            let handler = self.sess.diagnostic();
+            let mut current_job = tls::current_query(self);
+            let mut i = 0;
 
-        self.maps.query_stack.borrow_mut().pop();
+            if current_job.is_some() {
+                handler.note_without_error("query stack during panic:");
+            }
+            while let Some(job) = current_job {
+                let QueryInfo { span, ref query } = job.info;
+                let span = self.sess.codemap().def_span(query.default_span(self, span));
+                handler.span_note_without_error(span,
+                                                &format!("#{} [{}] {}",
+                                                         i,
+                                                         query.name(),
+                                                         query.describe(self)));
+                current_job = job.parent.clone();
+                i += 1;
+            }
+        });
 
-        Ok(result)
+        PRINTING.with(|printing| printing.set(false));
     }
 
     /// Try to read a node index for the node dep_node.
@@ -202,7 +343,8 @@ macro_rules! define_maps {
        [$($modifiers:tt)*] fn $name:ident: $node:ident($K:ty) -> $V:ty,)*) => {
 
         use dep_graph::DepNodeIndex;
-        use std::cell::RefCell;
+        use rustc_data_structures::sync::Lock;
+        use ty::maps::job::{QueryJob, QueryResult};
 
         define_map_struct! {
             tcx: $tcx,
@@ -214,10 +356,22 @@ macro_rules! define_maps {
                        -> Self {
                 Maps {
                     providers,
-                    query_stack: RefCell::new(vec![]),
-                    $($name: RefCell::new(QueryMap::new())),*
+                    $($name: Lock::new(QueryMap::new())),*
                 }
             }
+
+            /// The jobs of all the queries being executed.
+            pub fn collect_active_jobs(&self) -> Vec<Lrc<QueryJob<$tcx>>> {
+                let mut jobs = Vec::new();
+
+                $(for v in self.$name.borrow().active.values() {
+                    if let QueryResult::Started(ref job) = *v {
+                        jobs.push(job.clone());
+                    }
+                })*
+
+                jobs
+            }
         }
 
         #[allow(bad_style)]
@@ -234,17 +388,38 @@ macro_rules! define_maps {
 
         impl<$tcx> Query<$tcx> {
             pub fn describe(&self, tcx: TyCtxt) -> String {
-                let (r, name) = match *self {
-                    $(Query::$name(key) => {
-                        (queries::$name::describe(tcx, key), stringify!($name))
-                    })*
+                let r = match *self {
+                    $(Query::$name(key) => queries::$name::describe(tcx, key)),*
                 };
                 if tcx.sess.verbose() {
-                    format!("{} [{}]", r, name)
+                    format!("{} [{}]", r, self.name())
                 } else {
                     r
                 }
             }
+
+            /// The name of the query, as used in its provider.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    $(Query::$name(_) => stringify!($name)),*
+                }
+            }
+
+            // FIXME(eddyb) Get more valid Span's on queries.
+            pub fn default_span(&self, tcx: TyCtxt, span: Span) -> Span {
+                if span != DUMMY_SP {
+                    return span;
+                }
+                // The def_span query is used to calculate default_span,
+                // so exit to avoid infinite recursion
+                match *self {
+                    Query::def_span(..) => return span,
+                    _ => ()
+                }
+                match *self {
+                    $(Query::$name(key) => key.default_span(tcx),)*
+                }
+            }
         }
 
         pub mod queries {
@@ -262,9 +437,13 @@ macro_rules! define_maps {
         }
 
         impl<$tcx> GetCacheInternal<$tcx> for queries::$name<$tcx> {
-            fn get_cache_internal<'a>(tcx: TyCtxt<'a, $tcx, $tcx>)
-                                      -> ::std::cell::Ref<'a, QueryMap<$tcx, Self>> {
-                tcx.maps.$name.borrow()
+            fn query_map<'a, 'lcx>(tcx: TyCtxt<'a, $tcx, 'lcx>)
+                                   -> &'a Lock<QueryMap<$tcx, Self>> {
+                &tcx.maps.$name
+            }
+
+            fn to_query(key: $K) -> Query<$tcx> {
+                Query::$name(key)
             }
         }
 
@@ -278,9 +457,9 @@ macro_rules! define_maps {
             }
 
             fn try_get_with(tcx: TyCtxt<'a, $tcx, 'lcx>,
-                            mut span: Span,
+                            span: Span,
                             key: $K)
-                            -> Result<$V, CycleError<'a, $tcx>>
+                            -> Result<$V, CycleError<$tcx>>
             {
                 debug!("ty::queries::{}::try_get_with(key={:?}, span={:?})",
                        stringify!($name),
@@ -294,25 +473,23 @@ macro_rules! define_maps {
                     )
                 );
 
-                if let Some(value) = tcx.maps.$name.borrow().map.get(&key) {
-                    profq_msg!(tcx, ProfileQueriesMsg::CacheHit);
-                    tcx.dep_graph.read_index(value.index);
-                    return Ok((&value.value).clone());
-                }
-
-                // FIXME(eddyb) Get more valid Span's on queries.
-                // def_span guard is necessary to prevent a recursive loop,
-                // default_span calls def_span query internally.
-                if span == DUMMY_SP && stringify!($name) != "def_span" {
-                    span = key.default_span(tcx)
-                }
+                let job = match JobOwner::try_get(tcx, span, &key) {
+                    TryGetJob::NotYetStarted(job) => job,
+                    TryGetJob::JobCompleted(result) => {
+                        return result.map(|(value, dep_node_index)| {
+                            profq_msg!(tcx, ProfileQueriesMsg::CacheHit);
+                            tcx.sess.profiler(|p| p.record_query_hit(stringify!($name)));
+                            tcx.dep_graph.read_index(dep_node_index);
+                            value
+                        })
+                    }
+                };
 
                 // Fast path for when incr. comp. is off. `to_dep_node` is
                 // expensive for some DepKinds.
                 if !tcx.dep_graph.is_fully_enabled() {
                     let null_dep_node = DepNode::new_no_params(::dep_graph::DepKind::Null);
-                    return Self::force(tcx, key, span, null_dep_node)
-                                .map(|(v, _)| v);
+                    return Self::force_with_job(tcx, key, job, null_dep_node).map(|(v, _)| v);
                 }
 
                 let dep_node = Self::to_dep_node(tcx, &key);
@@ -320,13 +497,13 @@ macro_rules! define_maps {
                 if dep_node.kind.is_anon() {
                     profq_msg!(tcx, ProfileQueriesMsg::ProviderBegin);
 
-                    let res = tcx.cycle_check(span, Query::$name(key), || {
+                    let res = job.start(|| {
                         tcx.sess.diagnostic().track_diagnostics(|| {
                             tcx.dep_graph.with_anon_task(dep_node.kind, || {
                                 Self::compute_result(tcx.global_tcx(), key)
                             })
                         })
-                    })?;
+                    });
 
                     profq_msg!(tcx, ProfileQueriesMsg::ProviderEnd);
                     let ((result, dep_node_index), diagnostics) = res;
@@ -336,15 +513,9 @@ macro_rules! define_maps {
                     tcx.on_disk_query_result_cache
                        .store_diagnostics_for_anon_node(dep_node_index, diagnostics);
 
-                    let value = QueryValue::new(result, dep_node_index);
+                    job.complete(&result, dep_node_index);
 
-                    return Ok((&tcx.maps
-                                    .$name
-                                    .borrow_mut()
-                                    .map
-                                    .entry(key)
-                                    .or_insert(value)
-                                    .value).clone());
+                    return Ok(result);
                 }
 
                 if !dep_node.kind.is_input() {
@@ -352,13 +523,13 @@ macro_rules! define_maps {
                         profq_msg!(tcx, ProfileQueriesMsg::CacheHit);
                         return Self::load_from_disk_and_cache_in_memory(tcx,
                                                                         key,
-                                                                        span,
+                                                                        job,
                                                                         dep_node_index,
                                                                         &dep_node)
                     }
                 }
 
-                match Self::force(tcx, key, span, dep_node) {
+                match Self::force_with_job(tcx, key, job, dep_node) {
                     Ok((result, dep_node_index)) => {
                         tcx.dep_graph.read_index(dep_node_index);
                         Ok(result)
@@ -393,15 +564,18 @@ macro_rules! define_maps {
 
             fn compute_result(tcx: TyCtxt<'a, $tcx, 'lcx>, key: $K) -> $V {
                 let provider = tcx.maps.providers[key.map_crate()].$name;
-                provider(tcx.global_tcx(), key)
+                tcx.sess.profiler(|p| p.start_query(stringify!($name)));
+                let result = provider(tcx.global_tcx(), key);
+                tcx.sess.profiler(|p| p.end_query(stringify!($name)));
+                result
             }
 
             fn load_from_disk_and_cache_in_memory(tcx: TyCtxt<'a, $tcx, 'lcx>,
                                                   key: $K,
-                                                  span: Span,
+                                                  job: JobOwner<'a, $tcx, Self>,
                                                   dep_node_index: DepNodeIndex,
                                                   dep_node: &DepNode)
-                                                  -> Result<$V, CycleError<'a, $tcx>>
+                                                  -> Result<$V, CycleError<$tcx>>
             {
                 debug_assert!(tcx.dep_graph.is_green(dep_node));
 
@@ -426,11 +600,14 @@ macro_rules! define_maps {
                 };
 
                 let result = if let Some(result) = result {
+                    // Only count a hit if the provider doesn't run, since
+                    // running it records an execution of the query.
+                    tcx.sess.profiler(|p| p.record_query_hit(stringify!($name)));
                     result
                 } else {
                     // We could not load a result from the on-disk cache, so
                     // recompute.
-                    let (result, _ ) = tcx.cycle_check(span, Query::$name(key), || {
+                    let (result, _) = job.start(|| {
                         // The diagnostics for this query have already been
                         // promoted to the current session during
                         // try_mark_green(), so we can ignore them here.
@@ -441,7 +618,7 @@ macro_rules! define_maps {
                                 Self::compute_result(tcx, key)
                             })
                         })
-                    })?;
+                    });
                     result
                 };
 
@@ -475,26 +652,33 @@ macro_rules! define_maps {
                     tcx.dep_graph.mark_loaded_from_cache(dep_node_index, true);
                 }
 
-                let value = QueryValue::new(result, dep_node_index);
+                job.complete(&result, dep_node_index);
 
-                Ok((&tcx.maps
-                         .$name
-                         .borrow_mut()
-                         .map
-                         .entry(key)
-                         .or_insert(value)
-                         .value).clone())
+                Ok(result)
             }
 
             fn force(tcx: TyCtxt<'a, $tcx, 'lcx>,
                      key: $K,
                      span: Span,
                      dep_node: DepNode)
-                     -> Result<($V, DepNodeIndex), CycleError<'a, $tcx>> {
+                     -> Result<($V, DepNodeIndex), CycleError<$tcx>> {
+                match JobOwner::try_get(tcx, span, &key) {
+                    TryGetJob::NotYetStarted(job) => {
+                        Self::force_with_job(tcx, key, job, dep_node)
+                    }
+                    TryGetJob::JobCompleted(result) => result,
+                }
+            }
+
+            fn force_with_job(tcx: TyCtxt<'a, $tcx, 'lcx>,
+                              key: $K,
+                              job: JobOwner<'a, $tcx, Self>,
+                              dep_node: DepNode)
+                              -> Result<($V, DepNodeIndex), CycleError<$tcx>> {
                 debug_assert!(!tcx.dep_graph.dep_node_exists(&dep_node));
 
                 profq_msg!(tcx, ProfileQueriesMsg::ProviderBegin);
-                let res = tcx.cycle_check(span, Query::$name(key), || {
+                let res = job.start(|| {
                     tcx.sess.diagnostic().track_diagnostics(|| {
                         if dep_node.kind.is_eval_always() {
                             tcx.dep_graph.with_eval_always_task(dep_node,
@@ -508,7 +692,7 @@ macro_rules! define_maps {
                                                     Self::compute_result)
                         }
                     })
-                })?;
+                });
                 profq_msg!(tcx, ProfileQueriesMsg::ProviderEnd);
 
                 let ((result, dep_node_index), diagnostics) = res;
@@ -522,16 +706,9 @@ macro_rules! define_maps {
                        .store_diagnostics(dep_node_index, diagnostics);
                 }
 
-                let value = QueryValue::new(result, dep_node_index);
+                job.complete(&result, dep_node_index);
 
-                Ok(((&tcx.maps
-                         .$name
-                         .borrow_mut()
-                         .map
-                         .entry(key)
-                         .or_insert(value)
-                         .value).clone(),
-                   dep_node_index))
+                Ok((result, dep_node_index))
             }
 
             pub fn try_get(tcx: TyCtxt<'a, $tcx, 'lcx>, span: Span, key: $K)
@@ -599,8 +776,7 @@ macro_rules! define_map_struct {
      input: ($(([$($modifiers:tt)*] [$($attr:tt)*] [$name:ident]))*)) => {
         pub struct Maps<$tcx> {
             providers: IndexVec<CrateNum, Providers<$tcx>>,
-            query_stack: RefCell<Vec<(Span, Query<$tcx>)>>,
-            $($(#[$attr])*  $name: RefCell<QueryMap<$tcx, queries::$name<$tcx>>>,)*
+            $($(#[$attr])*  $name: Lock<QueryMap<$tcx, queries::$name<$tcx>>>,)*
         }
     };
 }
@@ -773,6 +949,9 @@ pub fn force_from_dep_node<'a, 'gcx, 'lcx>(tcx: TyCtxt<'a, 'gcx, 'lcx>,
         DepKind::VtableMethods |
         DepKind::EraseRegionsTy |
         DepKind::NormalizeTy |
+        DepKind::NormalizeProjectionTy |
+        DepKind::DropckOutlives |
+        DepKind::ImpliedOutlivesBounds |
         DepKind::SubstituteNormalizeAndTestPredicates |
         DepKind::InstanceDefSizeEstimate |
 
@@ -799,6 +978,7 @@ pub fn force_from_dep_node<'a, 'gcx, 'lcx>(tcx: TyCtxt<'a, 'gcx, 'lcx>,
         DepKind::MirConst => { force!(mir_const, def_id!()); }
         DepKind::MirValidated => { force!(mir_validated, def_id!()); }
         DepKind::MirOptimized => { force!(optimized_mir, def_id!()); }
+        DepKind::CoverageInfo => { force!(coverage_info, def_id!()); }
 
         DepKind::BorrowCheck => { force!(borrowck, def_id!()); }
         DepKind::MirBorrowCheck => { force!(mir_borrowck, def_id!()); }
@@ -868,6 +1048,7 @@ pub fn force_from_dep_node<'a, 'gcx, 'lcx>(tcx: TyCtxt<'a, 'gcx, 'lcx>,
         DepKind::IsProfilerRuntime => { force!(is_profiler_runtime, krate!()); }
         DepKind::GetPanicStrategy => { force!(panic_strategy, krate!()); }
         DepKind::IsNoBuiltins => { force!(is_no_builtins, krate!()); }
+        DepKind::IsMirOnlyRlib => { force!(is_mir_only_rlib, krate!()); }
         DepKind::ImplDefaultness => { force!(impl_defaultness, def_id!()); }
         DepKind::ReachableNonGenerics => { force!(reachable_non_generics, krate!()); }
         DepKind::NativeLibraries => { force!(native_libraries, krate!()); }
@@ -893,9 +1074,7 @@ pub fn force_from_dep_node<'a, 'gcx, 'lcx>(tcx: TyCtxt<'a, 'gcx, 'lcx>,
         DepKind::ResolveLifetimes => { force!(resolve_lifetimes, krate!()); }
         DepKind::NamedRegion => { force!(named_region_map, def_id!().index); }
         DepKind::IsLateBound => { force!(is_late_bound_map, def_id!().index); }
-        DepKind::ObjectLifetimeDefaults => {
-            force!(object_lifetime_defaults_map, def_id!().index);
-        }
+        DepKind::ObjectLifetimeDefaults => { force!(object_lifetime_defaults, def_id!()); }
 
         DepKind::Visibility => { force!(visibility, def_id!()); }
         DepKind::DepKind => { force!(dep_kind, krate!()); }
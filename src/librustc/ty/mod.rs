@@ -36,6 +36,7 @@ use ty::util::{IntTypeExt, Discr};
 use ty::walk::TypeWalker;
 use util::common::ErrorReported;
 use util::nodemap::{NodeSet, DefIdMap, FxHashMap, FxHashSet};
+use util::thread_pool::ThreadPool;
 
 use serialize::{self, Encodable, Encoder};
 use std::cell::RefCell;
@@ -44,7 +45,7 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::iter::FromIterator;
 use std::ops::Deref;
-use rustc_data_structures::sync::Lrc;
+use rustc_data_structures::sync::{self, Lrc};
 use std::slice;
 use std::vec::IntoIter;
 use std::mem;
@@ -62,14 +63,14 @@ use hir;
 
 pub use self::sty::{Binder, DebruijnIndex};
 pub use self::sty::{FnSig, GenSig, PolyFnSig, PolyGenSig};
-pub use self::sty::{InferTy, ParamTy, ProjectionTy, ExistentialPredicate};
+pub use self::sty::{InferTy, ParamTy, ParamConst, ProjectionTy, ExistentialPredicate};
 pub use self::sty::{ClosureSubsts, GeneratorInterior, TypeAndMut};
 pub use self::sty::{TraitRef, TypeVariants, PolyTraitRef};
 pub use self::sty::{ExistentialTraitRef, PolyExistentialTraitRef};
 pub use self::sty::{ExistentialProjection, PolyExistentialProjection, Const};
 pub use self::sty::{BoundRegion, EarlyBoundRegion, FreeRegion, Region};
 pub use self::sty::RegionKind;
-pub use self::sty::{TyVid, IntVid, FloatVid, RegionVid};
+pub use self::sty::{TyVid, IntVid, FloatVid, RegionVid, CanonicalVar};
 pub use self::sty::BoundRegion::*;
 pub use self::sty::InferTy::*;
 pub use self::sty::RegionKind::*;
@@ -452,6 +453,10 @@ bitflags! {
         // Currently we can't normalize projections w/ bound regions.
         const HAS_NORMALIZABLE_PROJECTION = 1 << 12;
 
+        // Set if this includes a "canonical" type or region var --
+        // ought to be true only for the results of canonicalization.
+        const HAS_CANONICAL_VARS = 1 << 13;
+
         const NEEDS_SUBST        = TypeFlags::HAS_PARAMS.bits |
                                    TypeFlags::HAS_SELF.bits |
                                    TypeFlags::HAS_RE_EARLY_BOUND.bits;
@@ -470,7 +475,8 @@ bitflags! {
                                   TypeFlags::HAS_PROJECTION.bits |
                                   TypeFlags::HAS_TY_CLOSURE.bits |
                                   TypeFlags::HAS_LOCAL_NAMES.bits |
-                                  TypeFlags::KEEP_IN_LOCAL_TCX.bits;
+                                  TypeFlags::KEEP_IN_LOCAL_TCX.bits |
+                                  TypeFlags::HAS_CANONICAL_VARS.bits;
     }
 }
 
@@ -736,6 +742,14 @@ impl RegionParameterDef {
     }
 }
 
+/// A const generic parameter, e.g. `const N: usize`.
+#[derive(Copy, Clone, RustcEncodable, RustcDecodable)]
+pub struct ConstParameterDef {
+    pub name: Name,
+    pub def_id: DefId,
+    pub index: u32,
+}
+
 impl ty::EarlyBoundRegion {
     pub fn to_bound_region(&self) -> ty::BoundRegion {
         ty::BoundRegion::BrNamed(self.def_id, self.name)
@@ -747,17 +761,20 @@ impl ty::EarlyBoundRegion {
 ///
 /// Note that in the presence of a `Self` parameter, the ordering here
 /// is different from the ordering in a Substs. Substs are ordered as
-///     Self, *Regions, *Other Type Params, (...child generics)
+///     Self, *Regions, *Other Type Params, *Consts, (...child generics)
 /// while this struct is ordered as
 ///     regions = Regions
 ///     types = [Self, *Other Type Params]
+///     consts = Consts
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct Generics {
     pub parent: Option<DefId>,
     pub parent_regions: u32,
     pub parent_types: u32,
+    pub parent_consts: u32,
     pub regions: Vec<RegionParameterDef>,
     pub types: Vec<TypeParameterDef>,
+    pub consts: Vec<ConstParameterDef>,
 
     /// Reverse map to each `TypeParameterDef`'s `index` field
     pub type_param_to_index: FxHashMap<DefId, u32>,
@@ -768,11 +785,11 @@ pub struct Generics {
 
 impl<'a, 'gcx, 'tcx> Generics {
     pub fn parent_count(&self) -> usize {
-        self.parent_regions as usize + self.parent_types as usize
+        self.parent_regions as usize + self.parent_types as usize + self.parent_consts as usize
     }
 
     pub fn own_count(&self) -> usize {
-        self.regions.len() + self.types.len()
+        self.regions.len() + self.types.len() + self.consts.len()
     }
 
     pub fn count(&self) -> usize {
@@ -1103,7 +1120,7 @@ pub struct ProjectionPredicate<'tcx> {
 pub type PolyProjectionPredicate<'tcx> = Binder<ProjectionPredicate<'tcx>>;
 
 impl<'tcx> PolyProjectionPredicate<'tcx> {
-    pub fn to_poly_trait_ref(&self, tcx: TyCtxt) -> PolyTraitRef<'tcx> {
+    pub fn to_poly_trait_ref(&self, tcx: TyCtxt<'_, '_, 'tcx>) -> PolyTraitRef<'tcx> {
         // Note: unlike with TraitRef::to_poly_trait_ref(),
         // self.0.trait_ref is permitted to have escaping regions.
         // This is because here `self` has a `Binder` and so does our
@@ -2242,6 +2259,23 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                 .map(move |&body_id| self.hir.body_owner_def_id(body_id))
     }
 
+    /// Calls `f` on the def-id of every body-owner in this crate. With
+    /// `cfg(parallel_queries)` and `-Z query-threads`, the calls are spread
+    /// over a `ThreadPool`.
+    pub fn par_body_owners<F: Fn(DefId) + sync::Sync>(self, f: F) {
+        let tcx = self.global_tcx();
+        let body_owners: Vec<_> = self.body_owners().collect();
+        #[cfg(parallel_queries)]
+        let deadlock_handler = || maps::handle_deadlock(tcx);
+        #[cfg(not(parallel_queries))]
+        let deadlock_handler = || {};
+        ThreadPool::new(self.sess).for_each(
+            body_owners,
+            deadlock_handler,
+            |def_id| tls::enter_global_context(tcx, |_| f(def_id)),
+        );
+    }
+
     pub fn expr_span(self, id: NodeId) -> Span {
         match self.hir.find(id) {
             Some(hir_map::NodeExpr(e)) => {
@@ -2465,6 +2499,11 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         self.trait_def(trait_def_id).has_auto_impl
     }
 
+    /// Returns true if `def_id` refers to a trait alias (`trait Foo = Bar + Baz;`).
+    pub fn is_trait_alias(self, def_id: DefId) -> bool {
+        self.describe_def(def_id) == Some(Def::TraitAlias(def_id))
+    }
+
     pub fn generator_layout(self, def_id: DefId) -> &'tcx GeneratorLayout<'tcx> {
         self.optimized_mir(def_id).generator_layout.as_ref().unwrap()
     }
@@ -150,7 +150,18 @@ pub fn relate_substs<'a, 'gcx, 'tcx, R>(relation: &mut R,
             (UnpackedKind::Type(a_ty), UnpackedKind::Type(b_ty)) => {
                 Ok(relation.relate_with_variance(variance, &a_ty, &b_ty)?.into())
             }
-            (UnpackedKind::Lifetime(_), _) | (UnpackedKind::Type(_), _) => bug!()
+            (UnpackedKind::Const(a_ct), UnpackedKind::Const(b_ct)) => {
+                // There are no inference variables for constants yet,
+                // so related const arguments have to be the same.
+                if a_ct == b_ct {
+                    Ok(a_ct.into())
+                } else {
+                    Err(TypeError::Mismatch)
+                }
+            }
+            (UnpackedKind::Lifetime(_), _) |
+            (UnpackedKind::Type(_), _) |
+            (UnpackedKind::Const(_), _) => bug!()
         }
     });
 
@@ -481,6 +492,30 @@ pub fn super_relate_tys<'a, 'gcx, 'tcx, R>(relation: &mut R,
             let t = relation.relate(&a_t, &b_t)?;
             assert_eq!(sz_a.ty, tcx.types.usize);
             assert_eq!(sz_b.ty, tcx.types.usize);
+
+            // Lengths that still depend on generic parameters are left
+            // unevaluated, so they can only be compared structurally.
+            let is_generic = |x: &'tcx ty::Const<'tcx>| {
+                match x.val {
+                    ConstVal::Unevaluated(_, substs) => substs.needs_subst(),
+                    _ => false,
+                }
+            };
+            if is_generic(sz_a) || is_generic(sz_b) {
+                return match (sz_a.val, sz_b.val) {
+                    (ConstVal::Unevaluated(a_def_id, a_substs),
+                     ConstVal::Unevaluated(b_def_id, b_substs)) if a_def_id == b_def_id => {
+                        let substs = relation.relate(&a_substs, &b_substs)?;
+                        let sz = tcx.mk_const(ty::Const {
+                            val: ConstVal::Unevaluated(a_def_id, substs),
+                            ty: tcx.types.usize,
+                        });
+                        Ok(tcx.mk_ty(ty::TyArray(t, sz)))
+                    }
+                    _ => Err(TypeError::Sorts(expected_found(relation, &a, &b))),
+                };
+            }
+
             let to_u64 = |x: &'tcx ty::Const<'tcx>| -> Result<u64, ErrorReported> {
                 match x.val {
                     ConstVal::Value(Value::ByVal(prim)) => Ok(prim.to_u64().unwrap()),
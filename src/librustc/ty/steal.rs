@@ -49,6 +49,17 @@ impl<T> Steal<T> {
         })
     }
 
+    /// Like `borrow`, but returns `None` instead of panicking if the value
+    /// has already been stolen.
+    pub fn try_borrow(&self) -> Option<Ref<T>> {
+        let value = self.value.borrow();
+        if value.is_some() {
+            Some(Ref::map(value, |opt| opt.as_ref().unwrap()))
+        } else {
+            None
+        }
+    }
+
     pub fn steal(&self) -> T {
         let value_ref = &mut *self.value.borrow_mut();
         let value = mem::replace(value_ref, None);
@@ -60,6 +60,9 @@ CopyImpls! {
     ::mir::Promoted,
     ::traits::Reveal,
     ::syntax_pos::Span,
+    ::infer::canonical::Certainty,
+    ::ty::ParamTy,
+    ::ty::ParamConst,
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -860,6 +863,81 @@ impl<'tcx> TypeFoldable<'tcx> for ty::ParamEnv<'tcx> {
     }
 }
 
+BraceStructTypeFoldableImpl! {
+    impl<'tcx, T> TypeFoldable<'tcx> for ty::ParamEnvAnd<'tcx, T> {
+        param_env, value
+    } where T: TypeFoldable<'tcx>
+}
+
+BraceStructTypeFoldableImpl! {
+    impl<'tcx> TypeFoldable<'tcx> for ::infer::canonical::CanonicalVarValues<'tcx> {
+        var_values,
+    }
+}
+
+BraceStructLiftImpl! {
+    impl<'a, 'tcx> Lift<'tcx> for ::infer::canonical::CanonicalVarValues<'a> {
+        type Lifted = ::infer::canonical::CanonicalVarValues<'tcx>;
+        var_values,
+    }
+}
+
+BraceStructTypeFoldableImpl! {
+    impl<'tcx, R> TypeFoldable<'tcx> for ::infer::canonical::QueryResult<'tcx, R> {
+        var_values, region_constraints, certainty, value
+    } where R: TypeFoldable<'tcx>
+}
+
+BraceStructLiftImpl! {
+    impl<'a, 'tcx, R> Lift<'tcx> for ::infer::canonical::QueryResult<'a, R> {
+        type Lifted = ::infer::canonical::QueryResult<'tcx, R::Lifted>;
+        var_values, region_constraints, certainty, value
+    } where R: Lift<'tcx>
+}
+
+BraceStructTypeFoldableImpl! {
+    impl<'tcx> TypeFoldable<'tcx> for ::traits::query::normalize::NormalizationResult<'tcx> {
+        normalized_ty
+    }
+}
+
+BraceStructLiftImpl! {
+    impl<'a, 'tcx> Lift<'tcx> for ::traits::query::normalize::NormalizationResult<'a> {
+        type Lifted = ::traits::query::normalize::NormalizationResult<'tcx>;
+        normalized_ty
+    }
+}
+
+BraceStructTypeFoldableImpl! {
+    impl<'tcx> TypeFoldable<'tcx> for ::traits::query::dropck_outlives::DropckOutlivesResult<'tcx> {
+        kinds
+    }
+}
+
+BraceStructLiftImpl! {
+    impl<'a, 'tcx> Lift<'tcx> for ::traits::query::dropck_outlives::DropckOutlivesResult<'a> {
+        type Lifted = ::traits::query::dropck_outlives::DropckOutlivesResult<'tcx>;
+        kinds
+    }
+}
+
+EnumTypeFoldableImpl! {
+    impl<'tcx> TypeFoldable<'tcx> for ::infer::outlives::bounds::OutlivesBound<'tcx> {
+        (::infer::outlives::bounds::OutlivesBound::RegionSubRegion)(a, b),
+        (::infer::outlives::bounds::OutlivesBound::RegionSubParam)(a, b),
+        (::infer::outlives::bounds::OutlivesBound::RegionSubProjection)(a, b),
+    }
+}
+
+EnumLiftImpl! {
+    impl<'a, 'tcx> Lift<'tcx> for ::infer::outlives::bounds::OutlivesBound<'a> {
+        type Lifted = ::infer::outlives::bounds::OutlivesBound<'tcx>;
+        (::infer::outlives::bounds::OutlivesBound::RegionSubRegion)(a, b),
+        (::infer::outlives::bounds::OutlivesBound::RegionSubParam)(a, b),
+        (::infer::outlives::bounds::OutlivesBound::RegionSubProjection)(a, b),
+    }
+}
+
 impl<'tcx> TypeFoldable<'tcx> for ty::UniverseIndex {
     fn super_fold_with<'gcx: 'tcx, F: TypeFolder<'gcx, 'tcx>>(&self, _folder: &mut F) -> Self {
         *self
@@ -1448,12 +1526,13 @@ impl<'tcx> TypeFoldable<'tcx> for ConstVal<'tcx> {
             ConstVal::Unevaluated(def_id, substs) => {
                 ConstVal::Unevaluated(def_id, substs.fold_with(folder))
             }
+            ConstVal::Param(param) => ConstVal::Param(param),
         }
     }
 
     fn super_visit_with<V: TypeVisitor<'tcx>>(&self, visitor: &mut V) -> bool {
         match *self {
-            ConstVal::Value(_) => false,
+            ConstVal::Value(_) | ConstVal::Param(_) => false,
             ConstVal::Unevaluated(_, substs) => substs.visit_with(visitor),
         }
     }
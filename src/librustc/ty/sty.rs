@@ -395,6 +395,15 @@ impl<'a, 'gcx, 'tcx> ClosureSubsts<'tcx> {
         state.map(move |d| d.ty.subst(tcx, self.substs))
     }
 
+    /// This returns, for each of the `state_tys`, the types of the other MIR locals
+    /// which are stored in the same field of the generator, where they differ from it.
+    pub fn state_shared_tys(self, def_id: DefId, tcx: TyCtxt<'a, 'gcx, 'tcx>) ->
+        impl Iterator<Item=Vec<Ty<'tcx>>> + 'a
+    {
+        let shared = tcx.generator_layout(def_id).shared_tys.iter();
+        shared.map(move |tys| tys.iter().map(|ty| ty.subst(tcx, self.substs)).collect())
+    }
+
     /// This is the types of the fields of a generate which
     /// is available before the generator transformation.
     /// It includes the upvars and the state discriminant which is u32.
@@ -782,17 +791,40 @@ impl<'a, 'tcx> ProjectionTy<'tcx> {
     /// Extracts the underlying trait reference from this projection.
     /// For example, if this is a projection of `<T as Iterator>::Item`,
     /// then this function would return a `T: Iterator` trait reference.
-    pub fn trait_ref(&self, tcx: TyCtxt) -> ty::TraitRef<'tcx> {
+    ///
+    /// The substitutions of a generic associated type also contain the
+    /// associated type's own parameters, which are not part of the trait
+    /// reference.
+    pub fn trait_ref(&self, tcx: TyCtxt<'_, '_, 'tcx>) -> ty::TraitRef<'tcx> {
         let def_id = tcx.associated_item(self.item_def_id).container.id();
+        let trait_generics = tcx.generics_of(def_id);
+        let substs = if self.substs.len() == trait_generics.count() {
+            self.substs
+        } else {
+            self.substs.truncate_to(tcx, trait_generics)
+        };
         ty::TraitRef {
             def_id,
-            substs: self.substs,
+            substs,
         }
     }
 
     pub fn self_ty(&self) -> Ty<'tcx> {
         self.substs.type_at(0)
     }
+
+    /// Returns the item whose predicates, instantiated with `self.substs`, bound
+    /// this projection: the trait, or for a generic associated type the associated
+    /// type itself, as its bounds may refer to its own parameters. (The predicates
+    /// of an associated type include those of its trait.)
+    pub fn bounds_def_id(&self, tcx: TyCtxt) -> DefId {
+        let trait_def_id = tcx.associated_item(self.item_def_id).container.id();
+        if self.substs.len() == tcx.generics_of(trait_def_id).count() {
+            trait_def_id
+        } else {
+            self.item_def_id
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
@@ -895,6 +927,24 @@ impl<'a, 'gcx, 'tcx> ParamTy {
     }
 }
 
+/// A const generic parameter, e.g. `N` in `struct Foo<const N: usize>`,
+/// as used in the value of a `ty::Const`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub struct ParamConst {
+    pub idx: u32,
+    pub name: Name,
+}
+
+impl<'a, 'gcx, 'tcx> ParamConst {
+    pub fn new(index: u32, name: Name) -> ParamConst {
+        ParamConst { idx: index, name: name }
+    }
+
+    pub fn for_def(def: &ty::ConstParameterDef) -> ParamConst {
+        ParamConst::new(def.index, def.name)
+    }
+}
+
 /// A [De Bruijn index][dbi] is a standard means of representing
 /// regions (and perhaps later types) in a higher-ranked setting. In
 /// particular, imagine a type like this:
@@ -1047,6 +1097,10 @@ pub enum RegionKind {
     /// `ClosureRegionRequirements` that are produced by MIR borrowck.
     /// See `ClosureRegionRequirements` for more details.
     ReClosureBound(RegionVid),
+
+    /// Canonicalized region, used only when preparing a trait query.
+    /// See `infer::canonical` for more details.
+    ReCanonical(CanonicalVar),
 }
 
 impl<'tcx> serialize::UseSpecializedDecodable for Region<'tcx> {}
@@ -1091,8 +1145,14 @@ pub enum InferTy {
     FreshTy(u32),
     FreshIntTy(u32),
     FreshFloatTy(u32),
+
+    /// Canonicalized type variable, used only when preparing a trait query.
+    /// See `infer::canonical` for more details.
+    CanonicalTy(CanonicalVar),
 }
 
+newtype_index!(CanonicalVar);
+
 /// A `ProjectionPredicate` for an `ExistentialTraitRef`.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub struct ExistentialProjection<'tcx> {
@@ -1216,6 +1276,10 @@ impl RegionKind {
             ty::ReClosureBound(..) => {
                 flags = flags | TypeFlags::HAS_FREE_REGIONS;
             }
+            ty::ReCanonical(..) => {
+                flags = flags | TypeFlags::HAS_FREE_REGIONS;
+                flags = flags | TypeFlags::HAS_CANONICAL_VARS;
+            }
         }
 
         match *self {
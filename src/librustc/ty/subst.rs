@@ -11,7 +11,8 @@
 // Type substitutions.
 
 use hir::def_id::DefId;
-use ty::{self, Slice, Region, Ty, TyCtxt};
+use middle::const_val::ConstVal;
+use ty::{self, Lift, Slice, Region, Ty, TyCtxt};
 use ty::fold::{TypeFoldable, TypeFolder, TypeVisitor};
 
 use serialize::{self, Encodable, Encoder, Decodable, Decoder};
@@ -26,23 +27,26 @@ use std::marker::PhantomData;
 use std::mem;
 
 /// An entity in the Rust typesystem, which can be one of
-/// several kinds (only types and lifetimes for now).
+/// several kinds (types, lifetimes and constants).
 /// To reduce memory usage, a `Kind` is a interned pointer,
 /// with the lowest 2 bits being reserved for a tag to
-/// indicate the type (`Ty` or `Region`) it points to.
+/// indicate the type (`Ty`, `Region` or `Const`) it points to.
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Kind<'tcx> {
     ptr: NonZero<usize>,
-    marker: PhantomData<(Ty<'tcx>, ty::Region<'tcx>)>
+    marker: PhantomData<(Ty<'tcx>, ty::Region<'tcx>, &'tcx ty::Const<'tcx>)>
 }
 
 const TAG_MASK: usize = 0b11;
 const TYPE_TAG: usize = 0b00;
 const REGION_TAG: usize = 0b01;
+const CONST_TAG: usize = 0b10;
 
+#[derive(Debug)]
 pub enum UnpackedKind<'tcx> {
     Lifetime(ty::Region<'tcx>),
     Type(Ty<'tcx>),
+    Const(&'tcx ty::Const<'tcx>),
 }
 
 impl<'tcx> UnpackedKind<'tcx> {
@@ -58,6 +62,11 @@ impl<'tcx> UnpackedKind<'tcx> {
                 assert_eq!(mem::align_of_val(ty) & TAG_MASK, 0);
                 (TYPE_TAG, ty as *const _ as usize)
             }
+            UnpackedKind::Const(ct) => {
+                // Ensure we can use the tag bits.
+                assert_eq!(mem::align_of_val(ct) & TAG_MASK, 0);
+                (CONST_TAG, ct as *const _ as usize)
+            }
         };
 
         Kind {
@@ -81,6 +90,12 @@ impl<'tcx> From<Ty<'tcx>> for Kind<'tcx> {
     }
 }
 
+impl<'tcx> From<&'tcx ty::Const<'tcx>> for Kind<'tcx> {
+    fn from(ct: &'tcx ty::Const<'tcx>) -> Kind<'tcx> {
+        UnpackedKind::Const(ct).pack()
+    }
+}
+
 impl<'tcx> Kind<'tcx> {
     #[inline]
     pub fn unpack(self) -> UnpackedKind<'tcx> {
@@ -89,6 +104,7 @@ impl<'tcx> Kind<'tcx> {
             match ptr & TAG_MASK {
                 REGION_TAG => UnpackedKind::Lifetime(&*((ptr & !TAG_MASK) as *const _)),
                 TYPE_TAG => UnpackedKind::Type(&*((ptr & !TAG_MASK) as *const _)),
+                CONST_TAG => UnpackedKind::Const(&*((ptr & !TAG_MASK) as *const _)),
                 _ => intrinsics::unreachable()
             }
         }
@@ -100,6 +116,7 @@ impl<'tcx> fmt::Debug for Kind<'tcx> {
         match self.unpack() {
             UnpackedKind::Lifetime(lt) => write!(f, "{:?}", lt),
             UnpackedKind::Type(ty) => write!(f, "{:?}", ty),
+            UnpackedKind::Const(ct) => write!(f, "{:?}", ct),
         }
     }
 }
@@ -109,6 +126,22 @@ impl<'tcx> fmt::Display for Kind<'tcx> {
         match self.unpack() {
             UnpackedKind::Lifetime(lt) => write!(f, "{}", lt),
             UnpackedKind::Type(ty) => write!(f, "{}", ty),
+            UnpackedKind::Const(ct) => match ct.val {
+                ConstVal::Param(param) => write!(f, "{}", param),
+                _ => write!(f, "{:?}", ct),
+            },
+        }
+    }
+}
+
+impl<'a, 'tcx> Lift<'tcx> for Kind<'a> {
+    type Lifted = Kind<'tcx>;
+
+    fn lift_to_tcx<'cx, 'gcx>(&self, tcx: TyCtxt<'cx, 'gcx, 'tcx>) -> Option<Self::Lifted> {
+        match self.unpack() {
+            UnpackedKind::Lifetime(a) => a.lift_to_tcx(tcx).map(|a| a.into()),
+            UnpackedKind::Type(a) => a.lift_to_tcx(tcx).map(|a| a.into()),
+            UnpackedKind::Const(a) => a.lift_to_tcx(tcx).map(|a| a.into()),
         }
     }
 }
@@ -118,6 +151,7 @@ impl<'tcx> TypeFoldable<'tcx> for Kind<'tcx> {
         match self.unpack() {
             UnpackedKind::Lifetime(lt) => lt.fold_with(folder).into(),
             UnpackedKind::Type(ty) => ty.fold_with(folder).into(),
+            UnpackedKind::Const(ct) => ct.fold_with(folder).into(),
         }
     }
 
@@ -125,6 +159,7 @@ impl<'tcx> TypeFoldable<'tcx> for Kind<'tcx> {
         match self.unpack() {
             UnpackedKind::Lifetime(lt) => lt.visit_with(visitor),
             UnpackedKind::Type(ty) => ty.visit_with(visitor),
+            UnpackedKind::Const(ct) => ct.visit_with(visitor),
         }
     }
 }
@@ -143,6 +178,11 @@ impl<'tcx> Encodable for Kind<'tcx> {
                         e.emit_enum_variant_arg(0, |e| ty.encode(e))
                     })
                 }
+                UnpackedKind::Const(ct) => {
+                    e.emit_enum_variant("Const", CONST_TAG, 1, |e| {
+                        e.emit_enum_variant_arg(0, |e| ct.encode(e))
+                    })
+                }
             }
         })
     }
@@ -151,10 +191,11 @@ impl<'tcx> Encodable for Kind<'tcx> {
 impl<'tcx> Decodable for Kind<'tcx> {
     fn decode<D: Decoder>(d: &mut D) -> Result<Kind<'tcx>, D::Error> {
         d.read_enum("Kind", |d| {
-            d.read_enum_variant(&["Ty", "Region"], |d, tag| {
+            d.read_enum_variant(&["Ty", "Region", "Const"], |d, tag| {
                 match tag {
                     TYPE_TAG => Ty::decode(d).map(Kind::from),
                     REGION_TAG => Region::decode(d).map(Kind::from),
+                    CONST_TAG => <&'tcx ty::Const<'tcx>>::decode(d).map(Kind::from),
                     _ => Err(d.error("invalid Kind tag"))
                 }
             })
@@ -162,7 +203,7 @@ impl<'tcx> Decodable for Kind<'tcx> {
     }
 }
 
-/// A substitution mapping type/region parameters to new values.
+/// A substitution mapping type/region/const parameters to new values.
 pub type Substs<'tcx> = Slice<Kind<'tcx>>;
 
 impl<'a, 'gcx, 'tcx> Substs<'tcx> {
@@ -204,7 +245,7 @@ impl<'a, 'gcx, 'tcx> Substs<'tcx> {
         let defs = tcx.generics_of(def_id);
         let mut result = Vec::with_capacity(defs.count());
         result.extend(self[..].iter().cloned());
-        Substs::fill_single(&mut result, defs, &mut mk_region, &mut mk_type);
+        Substs::fill_single(&mut result, tcx, defs, &mut mk_region, &mut mk_type);
         tcx.intern_substs(&result)
     }
 
@@ -220,10 +261,11 @@ impl<'a, 'gcx, 'tcx> Substs<'tcx> {
             let parent_defs = tcx.generics_of(def_id);
             Substs::fill_item(substs, tcx, parent_defs, mk_region, mk_type);
         }
-        Substs::fill_single(substs, defs, mk_region, mk_type)
+        Substs::fill_single(substs, tcx, defs, mk_region, mk_type)
     }
 
     fn fill_single<FR, FT>(substs: &mut Vec<Kind<'tcx>>,
+                           tcx: TyCtxt<'a, 'gcx, 'tcx>,
                            defs: &ty::Generics,
                            mk_region: &mut FR,
                            mk_type: &mut FT)
@@ -249,6 +291,17 @@ impl<'a, 'gcx, 'tcx> Substs<'tcx> {
             assert_eq!(def.index as usize, substs.len());
             substs.push(Kind::from(ty));
         }
+
+        // There is no syntax for const arguments yet, so const
+        // parameters are always substituted by themselves.
+        for def in &defs.consts {
+            let ct = tcx.mk_const(ty::Const {
+                val: ConstVal::Param(ty::ParamConst::for_def(def)),
+                ty: tcx.type_of(def.def_id),
+            });
+            assert_eq!(def.index as usize, substs.len());
+            substs.push(Kind::from(ct));
+        }
     }
 
     pub fn is_noop(&self) -> bool {
@@ -277,6 +330,17 @@ impl<'a, 'gcx, 'tcx> Substs<'tcx> {
         })
     }
 
+    #[inline]
+    pub fn consts(&'a self) -> impl DoubleEndedIterator<Item=&'tcx ty::Const<'tcx>> + 'a {
+        self.iter().filter_map(|k| {
+            if let UnpackedKind::Const(ct) = k.unpack() {
+                Some(ct)
+            } else {
+                None
+            }
+        })
+    }
+
     #[inline]
     pub fn type_at(&self, i: usize) -> Ty<'tcx> {
         if let UnpackedKind::Type(ty) = self[i].unpack() {
@@ -295,6 +359,15 @@ impl<'a, 'gcx, 'tcx> Substs<'tcx> {
         }
     }
 
+    #[inline]
+    pub fn const_at(&self, i: usize) -> &'tcx ty::Const<'tcx> {
+        if let UnpackedKind::Const(ct) = self[i].unpack() {
+            ct
+        } else {
+            bug!("expected const for param #{} in {:?}", i, self);
+        }
+    }
+
     #[inline]
     pub fn type_for_def(&self, ty_param_def: &ty::TypeParameterDef) -> Ty<'tcx> {
         self.type_at(ty_param_def.index as usize)
@@ -468,6 +541,17 @@ impl<'a, 'gcx, 'tcx> TypeFolder<'gcx, 'tcx> for SubstFolder<'a, 'gcx, 'tcx> {
 
         return t1;
     }
+
+    fn fold_const(&mut self, c: &'tcx ty::Const<'tcx>) -> &'tcx ty::Const<'tcx> {
+        if !c.needs_subst() {
+            return c;
+        }
+
+        match c.val {
+            ConstVal::Param(p) => self.const_for_param(p, c),
+            _ => c.super_fold_with(self),
+        }
+    }
 }
 
 impl<'a, 'gcx, 'tcx> SubstFolder<'a, 'gcx, 'tcx> {
@@ -493,6 +577,28 @@ impl<'a, 'gcx, 'tcx> SubstFolder<'a, 'gcx, 'tcx> {
         self.shift_regions_through_binders(ty)
     }
 
+    fn const_for_param(&self,
+                       p: ty::ParamConst,
+                       source_ct: &'tcx ty::Const<'tcx>)
+                       -> &'tcx ty::Const<'tcx> {
+        // Look up the const in the substitutions. It really should be in there.
+        match self.substs.get(p.idx as usize).map(|k| k.unpack()) {
+            Some(UnpackedKind::Const(ct)) => ct,
+            _ => {
+                let span = self.span.unwrap_or(DUMMY_SP);
+                span_bug!(
+                    span,
+                    "Const parameter `{:?}` ({:?}/{}) out of range \
+                         when substituting (root type={:?}) substs={:?}",
+                    p,
+                    source_ct,
+                    p.idx,
+                    self.root_ty,
+                    self.substs);
+            }
+        }
+    }
+
     /// It is sometimes necessary to adjust the debruijn indices during substitution. This occurs
     /// when we are substituting a type with escaping regions into a context where we have passed
     /// through region binders. That's quite a mouthful. Let's see an example:
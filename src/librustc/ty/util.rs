@@ -555,6 +555,8 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                         // as an error.
                         false
                     }
+                    // Constants don't have any lifetimes the destructor could access.
+                    UnpackedKind::Const(_) => false,
                 }
             }).map(|(&item_param, _)| item_param).collect();
         debug!("destructor_constraint({:?}) = {:?}", def.did, result);
@@ -867,6 +869,7 @@ impl<'a, 'gcx, 'tcx, W> TypeVisitor<'tcx> for TypeIdHasher<'a, 'gcx, 'tcx, W>
             }
 
             ty::ReClosureBound(..) |
+            ty::ReCanonical(..) |
             ty::ReLateBound(..) |
             ty::ReFree(..) |
             ty::ReScope(..) |
@@ -21,6 +21,7 @@ use std::path::Path;
 use std::time::{Duration, Instant};
 
 use std::sync::mpsc::{Sender};
+use std::thread;
 use syntax_pos::{SpanData};
 use ty::maps::{QueryMsg};
 use dep_graph::{DepNode};
@@ -28,9 +29,15 @@ use proc_macro;
 use lazy_static;
 use session::Session;
 
+use rustc_data_structures::sync::Lock;
+
 // The name of the associated type for `Fn` return types
 pub const FN_OUTPUT_NAME: &'static str = "Output";
 
+// The stack size of the threads running the compiler. Temporarily set to
+// 16MB to deal with nom-using crates failing.
+pub const STACK_SIZE: usize = 16 * 1024 * 1024; // 16MB
+
 // Useful type to use with `Result<>` indicate that an error has already
 // been reported to the user, so no need to continue checking.
 #[derive(Clone, Copy, Debug, RustcEncodable, RustcDecodable)]
@@ -38,6 +45,21 @@ pub struct ErrorReported;
 
 thread_local!(static TIME_DEPTH: Cell<usize> = Cell::new(0));
 
+// Descriptions of what the compiler is currently doing, innermost
+// last; see `IceContext`.
+thread_local!(static ICE_CONTEXT: RefCell<Vec<String>> = RefCell::new(Vec::new()));
+
+// While positive, the panic hook holds back what it would print in
+// `HELD_PANIC`; see `catch_panic_quietly`.
+thread_local!(static QUIET_PANICS: Cell<usize> = Cell::new(0));
+
+// The message of the last panic held back by the panic hook, and the
+// descriptions of the `IceContext`s it happened in.
+thread_local!(static HELD_PANIC: RefCell<Option<(String, Vec<String>)>> = RefCell::new(None));
+
+pub const BUG_REPORT_URL: &'static str = "https://github.com/rust-lang/rust/blob/master/\
+                                          CONTRIBUTING.md#bug-reports";
+
 lazy_static! {
     static ref DEFAULT_HOOK: Box<dyn Fn(&panic::PanicInfo) + Sync + Send + 'static> = {
         let hook = panic::take_hook();
@@ -48,7 +70,101 @@ lazy_static! {
 
 fn panic_hook(info: &panic::PanicInfo) {
     if !proc_macro::__internal::in_sess() {
-        (*DEFAULT_HOOK)(info)
+        if QUIET_PANICS.with(|quiet| quiet.get()) > 0 {
+            hold_panic(info);
+        } else {
+            (*DEFAULT_HOOK)(info);
+            print_ice_context();
+        }
+    }
+}
+
+fn hold_panic(info: &panic::PanicInfo) {
+    let payload = info.payload();
+    let message = match payload.downcast_ref::<&str>() {
+        Some(message) => *message,
+        None => match payload.downcast_ref::<String>() {
+            Some(message) => &message[..],
+            None => "Box<Any>",
+        },
+    };
+    let message = match info.location() {
+        Some(location) => format!("panicked at '{}', {}:{}:{}",
+                                  message, location.file(), location.line(), location.column()),
+        None => format!("panicked at '{}'", message),
+    };
+    // The contexts are gone by the time the panic is caught, so they
+    // have to be recorded now.
+    let context = ICE_CONTEXT.with(|context| {
+        context.try_borrow().map(|context| context.to_vec()).unwrap_or_default()
+    });
+    HELD_PANIC.with(|held| *held.borrow_mut() = Some((message, context)));
+}
+
+/// Runs `f`, catching any panic in it. The panic hook doesn't print
+/// anything for such a panic, as the caller may recover from it and
+/// report it as an error instead: see `take_held_panic`. A caller that
+/// doesn't recover should call `print_held_panic` before resuming the
+/// panic, so that the report says what went wrong.
+pub fn catch_panic_quietly<R, F: FnOnce() -> R>(f: F) -> thread::Result<R> {
+    QUIET_PANICS.with(|quiet| quiet.set(quiet.get() + 1));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+    QUIET_PANICS.with(|quiet| quiet.set(quiet.get() - 1));
+    result
+}
+
+/// Takes the message of the last panic caught by `catch_panic_quietly`,
+/// e.g. to add it to the error reported for it.
+pub fn take_held_panic() -> Option<String> {
+    HELD_PANIC.with(|held| held.borrow_mut().take()).map(|(message, _)| message)
+}
+
+/// Prints what the panic hook held back for the last panic caught by
+/// `catch_panic_quietly`, as it would have printed it.
+pub fn print_held_panic() {
+    if let Some((message, context)) = HELD_PANIC.with(|held| held.borrow_mut().take()) {
+        eprintln!("thread '{}' {}", thread::current().name().unwrap_or("<unnamed>"), message);
+        for description in &context {
+            eprintln!("note: while {}", description);
+        }
+    }
+}
+
+fn print_ice_context() {
+    ICE_CONTEXT.with(|context| {
+        // The panic may have happened while the stack was being updated.
+        if let Ok(context) = context.try_borrow() {
+            for description in context.iter() {
+                eprintln!("note: while {}", description);
+            }
+        }
+    });
+}
+
+/// Describes what the compiler is doing on the current thread, so
+/// that it can be included in ICE reports: while an `IceContext` is
+/// alive, the panic hook prints its description after those of the
+/// contexts that enclose it.
+///
+/// ```ignore
+/// let _ice_context = IceContext::enter(format!("borrow checking `{}`", path));
+/// ```
+pub struct IceContext {
+    _private: (),
+}
+
+impl IceContext {
+    pub fn enter(description: String) -> IceContext {
+        ICE_CONTEXT.with(|context| context.borrow_mut().push(description));
+        IceContext { _private: () }
+    }
+}
+
+impl Drop for IceContext {
+    fn drop(&mut self) {
+        ICE_CONTEXT.with(|context| {
+            context.borrow_mut().pop();
+        });
     }
 }
 
@@ -130,7 +246,10 @@ pub fn set_time_depth(depth: usize) {
 pub fn time<T, F>(sess: &Session, what: &str, f: F) -> T where
     F: FnOnce() -> T,
 {
-    time_ext(sess.time_passes(), Some(sess), what, f)
+    sess.profiler(|p| p.start_activity(what));
+    let rv = time_ext(sess.time_passes(), Some(sess), what, f);
+    sess.profiler(|p| p.end_activity(what));
+    rv
 }
 
 pub fn time_ext<T, F>(do_it: bool, sess: Option<&Session>, what: &str, f: F) -> T where
@@ -228,13 +347,14 @@ pub fn to_readable_str(mut val: usize) -> String {
     groups.join("_")
 }
 
-pub fn record_time<T, F>(accu: &Cell<Duration>, f: F) -> T where
+pub fn record_time<T, F>(accu: &Lock<Duration>, f: F) -> T where
     F: FnOnce() -> T,
 {
     let start = Instant::now();
     let rv = f();
     let duration = start.elapsed();
-    accu.set(duration + accu.get());
+    let mut accu = accu.lock();
+    *accu = *accu + duration;
     rv
 }
 
@@ -591,6 +591,15 @@ impl fmt::Debug for ty::RegionParameterDef {
     }
 }
 
+impl fmt::Debug for ty::ConstParameterDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ConstParameterDef({}, {:?}, {})",
+               self.name,
+               self.def_id,
+               self.index)
+    }
+}
+
 impl fmt::Debug for ty::TraitDef {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         ty::tls::with(|tcx| {
@@ -752,6 +761,7 @@ define_print! {
 
                 // The user should never encounter these in unsubstituted form.
                 ty::ReClosureBound(vid) => write!(f, "{:?}", vid),
+                ty::ReCanonical(_) => write!(f, "'_"),
             }
         }
         debug {
@@ -791,7 +801,9 @@ define_print! {
 
                 ty::ReEmpty => write!(f, "ReEmpty"),
 
-                ty::ReErased => write!(f, "ReErased")
+                ty::ReErased => write!(f, "ReErased"),
+
+                ty::ReCanonical(c) => write!(f, "'?{}", c.index())
             }
         }
     }
@@ -890,7 +902,8 @@ define_print! {
                     ty::FloatVar(_) => write!(f, "{}", "{float}"),
                     ty::FreshTy(v) => write!(f, "FreshTy({})", v),
                     ty::FreshIntTy(v) => write!(f, "FreshIntTy({})", v),
-                    ty::FreshFloatTy(v) => write!(f, "FreshFloatTy({})", v)
+                    ty::FreshFloatTy(v) => write!(f, "FreshFloatTy({})", v),
+                    ty::CanonicalTy(_) => write!(f, "_")
                 }
             }
         }
@@ -901,7 +914,8 @@ define_print! {
                 ty::FloatVar(ref v) => write!(f, "{:?}", v),
                 ty::FreshTy(v) => write!(f, "FreshTy({:?})", v),
                 ty::FreshIntTy(v) => write!(f, "FreshIntTy({:?})", v),
-                ty::FreshFloatTy(v) => write!(f, "FreshFloatTy({:?})", v)
+                ty::FreshFloatTy(v) => write!(f, "FreshFloatTy({:?})", v),
+                ty::CanonicalTy(v) => write!(f, "?{:?}", v.index())
             }
         }
     }
@@ -1171,6 +1185,9 @@ define_print! {
                         ConstVal::Unevaluated(_def_id, substs) => {
                             write!(f, "<unevaluated{:?}>", &substs[..])?;
                         }
+                        ConstVal::Param(param) => {
+                            write!(f, "{}", param)?;
+                        }
                         _ => {
                             write!(f, "{:?}", sz)?;
                         }
@@ -1207,6 +1224,17 @@ define_print! {
     }
 }
 
+define_print! {
+    () ty::ParamConst, (self, f, cx) {
+        display {
+            write!(f, "{}", self.name)
+        }
+        debug {
+            write!(f, "{}/#{}", self.name, self.idx)
+        }
+    }
+}
+
 define_print! {
     ('tcx, T: Print + fmt::Debug, U: Print + fmt::Debug) ty::OutlivesPredicate<T, U>,
     (self, f, cx) {
@@ -1258,10 +1286,13 @@ define_print! {
             // FIXME(tschottdorf): use something like
             //   parameterized(f, self.substs, self.item_def_id, &[])
             // (which currently ICEs).
-            let (trait_ref, item_name) = ty::tls::with(|tcx|
-                (self.trait_ref(tcx), tcx.associated_item(self.item_def_id).name)
-            );
-            print!(f, cx, print_debug(trait_ref), write("::{}", item_name))
+            ty::tls::with(|tcx| {
+                let trait_ref = tcx.lift(self)
+                                   .expect("could not lift ProjectionTy for printing")
+                                   .trait_ref(tcx);
+                let item_name = tcx.associated_item(self.item_def_id).name;
+                print!(f, cx, print_debug(trait_ref), write("::{}", item_name))
+            })
         }
     }
 }
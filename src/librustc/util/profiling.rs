@@ -0,0 +1,347 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The self-profiler of `-Z self-profile`.
+//!
+//! While the compiler runs, the profiler records an event whenever a query
+//! provider starts or finishes executing, whenever a query result is found
+//! in the in-memory or incremental cache, and whenever a pass timed by
+//! `util::common::time` starts or finishes. Each event records the thread
+//! it happened on, as the index of that thread among the threads seen by
+//! the profiler. The events are written to a binary stream for external
+//! tools, and `summarize` turns them into the time spent in each query and
+//! pass, which is what the driver writes next to the stream.
+//!
+//! The binary format is the `MAGIC` header, followed by the events, each of
+//! which is a tag byte, the thread index as a little-endian `u32`, the
+//! length of the name as a little-endian `u32`, the name itself in UTF-8,
+//! and the time of the event in nanoseconds since the profiler was created,
+//! as a little-endian `u64`.
+
+use rustc_data_structures::fx::FxHashMap;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+const MAGIC: &'static [u8] = b"RSPROF\x00\x02";
+
+const TAG_QUERY_START: u8 = 0;
+const TAG_QUERY_END: u8 = 1;
+const TAG_QUERY_CACHE_HIT: u8 = 2;
+const TAG_ACTIVITY_START: u8 = 3;
+const TAG_ACTIVITY_END: u8 = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProfilerEventKind {
+    /// The provider of a query started executing.
+    QueryStart,
+    /// The provider of a query finished executing.
+    QueryEnd,
+    /// The result of a query was found in the in-memory cache, or loaded
+    /// from the incremental cache.
+    QueryCacheHit,
+    /// A pass of the compiler started.
+    ActivityStart,
+    /// A pass of the compiler finished.
+    ActivityEnd,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfilerEvent {
+    pub kind: ProfilerEventKind,
+    /// The name of the query or pass.
+    pub name: Cow<'static, str>,
+    /// The index of the thread the event happened on.
+    pub thread: u32,
+    /// The time of the event, in nanoseconds since the profiler was created.
+    pub time: u64,
+}
+
+impl ProfilerEventKind {
+    fn tag(&self) -> u8 {
+        match *self {
+            ProfilerEventKind::QueryStart => TAG_QUERY_START,
+            ProfilerEventKind::QueryEnd => TAG_QUERY_END,
+            ProfilerEventKind::QueryCacheHit => TAG_QUERY_CACHE_HIT,
+            ProfilerEventKind::ActivityStart => TAG_ACTIVITY_START,
+            ProfilerEventKind::ActivityEnd => TAG_ACTIVITY_END,
+        }
+    }
+}
+
+pub struct SelfProfiler {
+    start: Instant,
+    events: Vec<ProfilerEvent>,
+    /// The index of each thread that recorded an event.
+    threads: FxHashMap<ThreadId, u32>,
+}
+
+impl SelfProfiler {
+    pub fn new() -> SelfProfiler {
+        SelfProfiler {
+            start: Instant::now(),
+            events: vec![],
+            threads: FxHashMap(),
+        }
+    }
+
+    fn record(&mut self, kind: ProfilerEventKind, name: Cow<'static, str>) {
+        let elapsed = self.start.elapsed();
+        let time = elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64;
+        let next_thread = self.threads.len() as u32;
+        let thread = *self.threads.entry(thread::current().id()).or_insert(next_thread);
+        self.events.push(ProfilerEvent { kind, name, thread, time });
+    }
+
+    pub fn start_query(&mut self, query_name: &'static str) {
+        self.record(ProfilerEventKind::QueryStart, Cow::Borrowed(query_name));
+    }
+
+    pub fn end_query(&mut self, query_name: &'static str) {
+        self.record(ProfilerEventKind::QueryEnd, Cow::Borrowed(query_name));
+    }
+
+    pub fn record_query_hit(&mut self, query_name: &'static str) {
+        self.record(ProfilerEventKind::QueryCacheHit, Cow::Borrowed(query_name));
+    }
+
+    pub fn start_activity(&mut self, activity: &str) {
+        self.record(ProfilerEventKind::ActivityStart, Cow::Owned(activity.to_string()));
+    }
+
+    pub fn end_activity(&mut self, activity: &str) {
+        self.record(ProfilerEventKind::ActivityEnd, Cow::Owned(activity.to_string()));
+    }
+
+    pub fn events(&self) -> &[ProfilerEvent] {
+        &self.events
+    }
+}
+
+/// Writes `events` as a binary event stream.
+pub fn write_events<W: Write>(w: &mut W, events: &[ProfilerEvent]) -> io::Result<()> {
+    w.write_all(MAGIC)?;
+    for event in events {
+        let name = event.name.as_bytes();
+        w.write_all(&[event.kind.tag()])?;
+        write_le(w, event.thread as u64, 4)?;
+        write_le(w, name.len() as u64, 4)?;
+        w.write_all(name)?;
+        write_le(w, event.time, 8)?;
+    }
+    Ok(())
+}
+
+fn write_le<W: Write>(w: &mut W, value: u64, bytes: usize) -> io::Result<()> {
+    let mut buf = [0u8; 8];
+    for (i, byte) in buf.iter_mut().enumerate() {
+        *byte = (value >> (8 * i)) as u8;
+    }
+    w.write_all(&buf[..bytes])
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct QuerySummary {
+    pub query_name: String,
+    /// How many times the provider was executed.
+    pub executions: u64,
+    /// How many times the result was found in a cache.
+    pub cache_hits: u64,
+    /// The time spent in the provider, excluding the nested queries it
+    /// executed.
+    pub self_time: Duration,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Summary {
+    /// The queries, from the one with the largest self time.
+    pub queries: Vec<QuerySummary>,
+    /// The total time of each pass, in the order they first ran.
+    pub activities: Vec<(String, Duration)>,
+}
+
+/// Computes the time spent in each query and pass from the events.
+pub fn summarize(events: &[ProfilerEvent]) -> Summary {
+    struct Frame<'a> {
+        name: &'a str,
+        is_query: bool,
+        start: u64,
+        /// The time spent in the nested queries and passes.
+        children: u64,
+    }
+
+    let mut queries: FxHashMap<&str, QuerySummary> = FxHashMap();
+    let mut activities: Vec<(String, Duration)> = vec![];
+    // Queries and passes only nest within a thread.
+    let mut stacks: FxHashMap<u32, Vec<Frame>> = FxHashMap();
+
+    for event in events {
+        let stack = stacks.entry(event.thread).or_insert(vec![]);
+        match event.kind {
+            ProfilerEventKind::QueryStart | ProfilerEventKind::ActivityStart => {
+                stack.push(Frame {
+                    name: &event.name,
+                    is_query: event.kind == ProfilerEventKind::QueryStart,
+                    start: event.time,
+                    children: 0,
+                });
+            }
+            ProfilerEventKind::QueryEnd | ProfilerEventKind::ActivityEnd => {
+                // Unwind any frame left open, e.g. by a query cycle.
+                let index = match stack.iter().rposition(|frame| frame.name == event.name) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let frame = stack.drain(index..).next().unwrap();
+                let elapsed = event.time.saturating_sub(frame.start);
+                if let Some(parent) = stack.last_mut() {
+                    parent.children += elapsed;
+                }
+
+                if frame.is_query {
+                    let summary = query_summary(&mut queries, frame.name);
+                    summary.executions += 1;
+                    summary.self_time += nanos(elapsed.saturating_sub(frame.children));
+                } else {
+                    match activities.iter().position(|&(ref name, _)| name == frame.name) {
+                        Some(index) => activities[index].1 += nanos(elapsed),
+                        None => activities.push((frame.name.to_string(), nanos(elapsed))),
+                    }
+                }
+            }
+            ProfilerEventKind::QueryCacheHit => {
+                query_summary(&mut queries, &event.name).cache_hits += 1;
+            }
+        }
+    }
+
+    let mut queries: Vec<_> = queries.into_iter().map(|(_, summary)| summary).collect();
+    queries.sort_by(|a, b| {
+        b.self_time.cmp(&a.self_time).then_with(|| a.query_name.cmp(&b.query_name))
+    });
+    Summary { queries, activities }
+}
+
+fn query_summary<'a, 'b>(queries: &'b mut FxHashMap<&'a str, QuerySummary>,
+                         query_name: &'a str)
+                         -> &'b mut QuerySummary {
+    queries.entry(query_name).or_insert_with(|| QuerySummary {
+        query_name: query_name.to_string(),
+        ..QuerySummary::default()
+    })
+}
+
+fn nanos(nanos: u64) -> Duration {
+    Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)
+}
+
+impl Summary {
+    /// Writes the summary as Markdown tables.
+    pub fn write_markdown<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "| Pass | Time (ms) |")?;
+        writeln!(w, "| ---- | --------- |")?;
+        for &(ref activity, duration) in &self.activities {
+            writeln!(w, "| {} | {} |", activity, millis(duration))?;
+        }
+        writeln!(w, "")?;
+
+        writeln!(w, "| Query | Self time (ms) | Executions | Cache hits | Hit rate |")?;
+        writeln!(w, "| ----- | -------------- | ---------- | ---------- | -------- |")?;
+        for query in &self.queries {
+            let total = query.executions + query.cache_hits;
+            let hit_rate = if total == 0 { 0.0 } else { query.cache_hits as f64 / total as f64 };
+            writeln!(w, "| {} | {} | {} | {} | {:.1}% |",
+                     query.query_name,
+                     millis(query.self_time),
+                     query.executions,
+                     query.cache_hits,
+                     hit_rate * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    let millis = duration.as_secs() as f64 * 1000.0 + duration.subsec_nanos() as f64 / 1_000_000.0;
+    format!("{:.3}", millis)
+}
+
+#[cfg(test)]
+fn event(kind: ProfilerEventKind, name: &'static str, thread: u32, time: u64) -> ProfilerEvent {
+    ProfilerEvent { kind, name: Cow::Borrowed(name), thread, time }
+}
+
+#[test]
+fn test_write_events() {
+    let events = vec![event(ProfilerEventKind::QueryEnd, "ab", 1, 0x0102)];
+    let mut data = vec![];
+    write_events(&mut data, &events).unwrap();
+
+    let mut expected = MAGIC.to_vec();
+    expected.extend_from_slice(&[TAG_QUERY_END, 1, 0, 0, 0, 2, 0, 0, 0, b'a', b'b']);
+    expected.extend_from_slice(&[0x02, 0x01, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(data, expected);
+}
+
+#[test]
+fn test_summarize_nested() {
+    use self::ProfilerEventKind::*;
+
+    let summary = summarize(&[
+        event(ActivityStart, "typeck", 0, 0),
+        event(QueryStart, "outer", 0, 10),
+        event(QueryStart, "inner", 0, 20),
+        event(QueryCacheHit, "inner", 0, 25),
+        event(QueryEnd, "inner", 0, 50),
+        event(QueryEnd, "outer", 0, 100),
+        event(ActivityEnd, "typeck", 0, 200),
+    ]);
+
+    assert_eq!(summary.activities, vec![("typeck".to_string(), nanos(200))]);
+    let outer = summary.queries.iter().find(|q| q.query_name == "outer").unwrap();
+    assert_eq!((outer.executions, outer.cache_hits, outer.self_time), (1, 0, nanos(60)));
+    let inner = summary.queries.iter().find(|q| q.query_name == "inner").unwrap();
+    assert_eq!((inner.executions, inner.cache_hits, inner.self_time), (1, 1, nanos(30)));
+    assert_eq!(summary.queries[0].query_name, "outer");
+}
+
+#[test]
+fn test_summarize_threads() {
+    use self::ProfilerEventKind::*;
+
+    // The queries of two threads overlap, but don't nest in each other.
+    let summary = summarize(&[
+        event(QueryStart, "a", 0, 0),
+        event(QueryStart, "b", 1, 10),
+        event(QueryEnd, "a", 0, 40),
+        event(QueryEnd, "b", 1, 60),
+    ]);
+
+    let a = summary.queries.iter().find(|q| q.query_name == "a").unwrap();
+    assert_eq!((a.executions, a.self_time), (1, nanos(40)));
+    let b = summary.queries.iter().find(|q| q.query_name == "b").unwrap();
+    assert_eq!((b.executions, b.self_time), (1, nanos(50)));
+}
+
+#[test]
+fn test_profiler_threads() {
+    use std::sync::{Arc, Mutex};
+
+    let profiler = Arc::new(Mutex::new(SelfProfiler::new()));
+    profiler.lock().unwrap().start_query("a");
+    let other = profiler.clone();
+    thread::spawn(move || other.lock().unwrap().start_query("b")).join().unwrap();
+    profiler.lock().unwrap().end_query("a");
+
+    let profiler = profiler.lock().unwrap();
+    let threads: Vec<_> = profiler.events().iter().map(|e| e.thread).collect();
+    assert_eq!(threads, vec![0, 1, 0]);
+}
@@ -0,0 +1,312 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A thread pool used to execute independent queries in parallel, e.g. type
+//! checking the bodies of a crate. Without `cfg(parallel_queries)`, or with
+//! a single query thread, the work is simply done on the current thread.
+//!
+//! The pool respects the jobserver: besides the thread it's used from, it
+//! only runs work on threads which acquired a jobserver token, so rustc
+//! doesn't use more cores than it was given, e.g. by cargo.
+//!
+//! With several threads, a thread may have to wait for a query being
+//! executed by another thread. If all the threads of the pool end up
+//! waiting, they wait on each other, which means the queries form a cycle,
+//! and the deadlock handler of the pool is called to break it.
+//!
+//! Note that the compiler does not build with `cfg(parallel_queries)` yet.
+//! The `Session`, the `ParseSess`, the dependency graph, the interners and
+//! the caches of the `GlobalCtxt` use the thread safe types of
+//! `rustc_data_structures::sync` and `SyncDroplessArena`, but the typed
+//! arenas, the crate store and the caches of the MIR still use `Cell` and
+//! `RefCell`, so the `GlobalCtxt` is not `Sync`.
+
+use rustc_data_structures::sync::Sync;
+use session::Session;
+
+#[cfg(parallel_queries)]
+use jobserver::Client;
+#[cfg(parallel_queries)]
+use std::cell::Cell;
+#[cfg(parallel_queries)]
+use std::env;
+#[cfg(parallel_queries)]
+use std::mem;
+#[cfg(parallel_queries)]
+use std::panic;
+#[cfg(parallel_queries)]
+use std::sync::Mutex;
+#[cfg(parallel_queries)]
+use std::sync::mpsc;
+#[cfg(parallel_queries)]
+use std::thread;
+#[cfg(parallel_queries)]
+use util::common::STACK_SIZE;
+
+pub struct ThreadPool {
+    /// The maximum number of threads used, including the calling thread.
+    threads: usize,
+    #[cfg(parallel_queries)]
+    jobserver: Client,
+}
+
+impl ThreadPool {
+    #[cfg(not(parallel_queries))]
+    pub fn new(sess: &Session) -> ThreadPool {
+        ThreadPool { threads: sess.query_threads() }
+    }
+
+    #[cfg(parallel_queries)]
+    pub fn new(sess: &Session) -> ThreadPool {
+        let threads = sess.query_threads();
+        let jobserver = sess.jobserver_from_env.clone().unwrap_or_else(|| {
+            // The calling thread doesn't need a token.
+            Client::new(threads - 1).expect("failed to create jobserver")
+        });
+        ThreadPool::with_jobserver(threads, jobserver)
+    }
+
+    /// Creates a pool of at most `threads` threads, including the calling
+    /// thread, which takes tokens for the other threads from `jobserver`.
+    #[cfg(parallel_queries)]
+    pub fn with_jobserver(threads: usize, jobserver: Client) -> ThreadPool {
+        ThreadPool { threads, jobserver }
+    }
+
+    /// Calls `f` on each of `items`. `deadlock_handler` is called when all
+    /// the threads are waiting for queries being executed by each other.
+    #[cfg(not(parallel_queries))]
+    pub fn for_each<T, F, D>(&self, items: Vec<T>, _deadlock_handler: D, f: F)
+        where F: Fn(T) + Sync,
+              D: Fn() + Sync,
+    {
+        for item in items {
+            f(item);
+        }
+    }
+
+    /// Calls `f` on each of `items`. `deadlock_handler` is called when all
+    /// the threads are waiting for queries being executed by each other.
+    #[cfg(parallel_queries)]
+    pub fn for_each<T, F, D>(&self, items: Vec<T>, deadlock_handler: D, f: F)
+        where T: Send,
+              F: Fn(T) + Sync,
+              D: Fn() + Sync,
+    {
+        if self.threads <= 1 {
+            for item in items {
+                f(item);
+            }
+            return;
+        }
+
+        let items = Mutex::new(items.into_iter());
+        let next_item = || items.lock().unwrap().next();
+        let registry = Registry {
+            state: Mutex::new(RegistryState {
+                running: 1,
+                blocked: 0,
+            }),
+            deadlock_handler: &deadlock_handler,
+        };
+
+        // The other threads are only started once they get a token.
+        let (token_sender, tokens) = mpsc::channel();
+        let helper = self.jobserver.clone().into_helper_thread(move |token| {
+            drop(token_sender.send(token));
+        }).expect("failed to spawn jobserver helper thread");
+        for _ in 1..self.threads {
+            helper.request_token();
+        }
+
+        let work = || {
+            registry.enter(|| {
+                while let Some(item) = next_item() {
+                    f(item);
+                }
+            });
+            registry.exit();
+        };
+        let work: &(Fn() + Sync) = &work;
+        // The threads borrow from this stack frame, which is fine because
+        // they are always joined before returning, by the destructor of
+        // `workers` if there's a panic.
+        let work: &'static (Fn() + Sync) = unsafe { mem::transmute(work) };
+        let mut workers = Workers(Vec::new());
+
+        registry.enter(|| {
+            loop {
+                while let Ok(token) = tokens.try_recv() {
+                    // There's nothing to do if we failed to get a token.
+                    if let Ok(token) = token {
+                        registry.add_thread();
+                        // The queries need as much stack as on the main thread.
+                        let mut builder = thread::Builder::new().name("rustc-query".to_string());
+                        if env::var_os("RUST_MIN_STACK").is_none() {
+                            builder = builder.stack_size(STACK_SIZE);
+                        }
+                        let worker = builder.spawn(move || {
+                            let _token = token;
+                            work();
+                        }).expect("failed to spawn query thread");
+                        workers.0.push(worker);
+                    }
+                }
+                match next_item() {
+                    Some(item) => f(item),
+                    None => break,
+                }
+            }
+        });
+        registry.exit();
+
+        // Stop asking for tokens, and release the ones we already got.
+        drop(helper);
+        workers.join();
+    }
+}
+
+#[cfg(parallel_queries)]
+struct Workers(Vec<thread::JoinHandle<()>>);
+
+#[cfg(parallel_queries)]
+impl Workers {
+    fn join(&mut self) {
+        let mut panic = None;
+        for worker in self.0.drain(..) {
+            if let Err(e) = worker.join() {
+                panic = Some(e);
+            }
+        }
+        if let Some(e) = panic {
+            panic::resume_unwind(e);
+        }
+    }
+}
+
+#[cfg(parallel_queries)]
+impl Drop for Workers {
+    fn drop(&mut self) {
+        // We're already panicking if there are threads left to join.
+        for worker in self.0.drain(..) {
+            drop(worker.join());
+        }
+    }
+}
+
+#[cfg(parallel_queries)]
+struct RegistryState {
+    /// The number of threads of the pool which are running, i.e. neither
+    /// waiting nor done.
+    running: usize,
+    /// The number of threads waiting for a query.
+    blocked: usize,
+}
+
+/// The state of a pool which is shared by its threads.
+#[cfg(parallel_queries)]
+struct Registry<'a> {
+    state: Mutex<RegistryState>,
+    deadlock_handler: &'a (Fn() + Sync),
+}
+
+#[cfg(parallel_queries)]
+thread_local! {
+    /// The registry of the pool running the current thread, if any.
+    static CURRENT_REGISTRY: Cell<Option<*const Registry<'static>>> = Cell::new(None)
+}
+
+#[cfg(parallel_queries)]
+impl<'a> Registry<'a> {
+    fn enter<F: FnOnce()>(&self, f: F) {
+        let registry = self as *const Registry<'a> as *const Registry<'static>;
+        CURRENT_REGISTRY.with(|current| {
+            let prev = current.replace(Some(registry));
+            f();
+            current.set(prev);
+        })
+    }
+
+    fn add_thread(&self) {
+        self.state.lock().unwrap().running += 1;
+    }
+
+    fn exit(&self) {
+        let deadlock = {
+            let mut state = self.state.lock().unwrap();
+            state.running -= 1;
+            state.running == 0 && state.blocked > 0
+        };
+        if deadlock {
+            (self.deadlock_handler)();
+        }
+    }
+
+    fn block(&self) {
+        let deadlock = {
+            let mut state = self.state.lock().unwrap();
+            state.running -= 1;
+            state.blocked += 1;
+            state.running == 0
+        };
+        if deadlock {
+            (self.deadlock_handler)();
+        }
+    }
+
+    fn unblock(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.running += 1;
+        state.blocked -= 1;
+    }
+}
+
+/// Marks the current thread as waiting for a query being executed by
+/// another thread, which calls the deadlock handler if all the threads of its
+/// pool are now waiting.
+#[cfg(parallel_queries)]
+pub fn mark_blocked() {
+    CURRENT_REGISTRY.with(|current| {
+        if let Some(registry) = current.get() {
+            unsafe { (*registry).block() }
+        }
+    })
+}
+
+/// Marks the current thread as running again after `mark_blocked`.
+#[cfg(parallel_queries)]
+pub fn mark_unblocked() {
+    CURRENT_REGISTRY.with(|current| {
+        if let Some(registry) = current.get() {
+            unsafe { (*registry).unblock() }
+        }
+    })
+}
+
+#[cfg(all(test, parallel_queries))]
+mod tests {
+    use jobserver::Client;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+    use super::ThreadPool;
+
+    #[test]
+    fn for_each_runs_on_several_threads() {
+        let pool = ThreadPool::with_jobserver(4, Client::new(3).unwrap());
+        let threads = Mutex::new(HashSet::new());
+        pool.for_each((0..64).collect(), || panic!("unexpected deadlock"), |_: u32| {
+            threads.lock().unwrap().insert(thread::current().id());
+            thread::sleep(Duration::from_millis(5));
+        });
+        assert!(threads.into_inner().unwrap().len() > 1);
+    }
+}
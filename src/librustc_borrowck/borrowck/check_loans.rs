@@ -429,6 +429,7 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
             RegionKind::ReVar(..) |
             RegionKind::ReSkolemized(..) |
             RegionKind::ReClosureBound(..) |
+            RegionKind::ReCanonical(..) |
             RegionKind::ReErased => span_bug!(borrow_span,
                                               "unexpected region in borrowck {:?}",
                                               loan_region),
@@ -144,7 +144,8 @@ fn gather_move<'a, 'tcx>(bccx: &BorrowckCtxt<'a, 'tcx>,
     if let Some(illegal_move_origin) = potentially_illegal_move {
         debug!("illegal_move_origin={:?}", illegal_move_origin);
         let error = MoveError::with_move_info(illegal_move_origin,
-                                              move_info.span_path_opt);
+                                              move_info.span_path_opt,
+                                              move_info.cmt.clone());
         move_error_collector.add_error(error);
         return;
     }
@@ -13,11 +13,14 @@ use rustc::middle::mem_categorization as mc;
 use rustc::middle::mem_categorization::Categorization;
 use rustc::middle::mem_categorization::NoteClosureEnv;
 use rustc::middle::mem_categorization::InteriorOffsetKind as Kind;
+use rustc::hir;
+use rustc::hir::map as hir_map;
 use rustc::ty;
+use rustc::ty::adjustment::Adjust;
 use rustc_mir::util::borrowck_errors::{BorrowckErrors, Origin};
 use syntax::ast;
 use syntax_pos;
-use errors::DiagnosticBuilder;
+use errors::{Applicability, DiagnosticBuilder};
 use borrowck::gather_loans::gather_moves::PatternSource;
 
 pub struct MoveErrorCollector<'tcx> {
@@ -42,16 +45,20 @@ impl<'tcx> MoveErrorCollector<'tcx> {
 
 pub struct MoveError<'tcx> {
     move_from: mc::cmt<'tcx>,
-    move_to: Option<MovePlace<'tcx>>
+    move_to: Option<MovePlace<'tcx>>,
+    /// The place that is moved, which `move_from` is a prefix of.
+    moved: mc::cmt<'tcx>,
 }
 
 impl<'tcx> MoveError<'tcx> {
     pub fn with_move_info(move_from: mc::cmt<'tcx>,
-                          move_to: Option<MovePlace<'tcx>>)
+                          move_to: Option<MovePlace<'tcx>>,
+                          moved: mc::cmt<'tcx>)
                           -> MoveError<'tcx> {
         MoveError {
             move_from,
             move_to,
+            moved,
         }
     }
 }
@@ -65,7 +72,8 @@ pub struct MovePlace<'tcx> {
 
 pub struct GroupedMoveErrors<'tcx> {
     move_from: mc::cmt<'tcx>,
-    move_to_places: Vec<MovePlace<'tcx>>
+    move_to_places: Vec<MovePlace<'tcx>>,
+    moved: mc::cmt<'tcx>,
 }
 
 fn report_move_errors<'a, 'tcx>(bccx: &BorrowckCtxt<'a, 'tcx>, errors: &Vec<MoveError<'tcx>>) {
@@ -80,11 +88,14 @@ fn report_move_errors<'a, 'tcx>(bccx: &BorrowckCtxt<'a, 'tcx>, errors: &Vec<Move
                 let initializer =
                     e.init.as_ref().expect("should have an initializer to get an error");
                 if let Ok(snippet) = bccx.tcx.sess.codemap().span_to_snippet(initializer.span) {
-                    err.span_suggestion(initializer.span,
-                                        "consider using a reference instead",
-                                        format!("&{}", snippet));
+                    err.span_suggestion_with_applicability(
+                        initializer.span,
+                        "consider using a reference instead",
+                        format!("&{}", snippet),
+                        Applicability::MaybeIncorrect);
                 }
             }
+            None => suggest_borrowing_receiver(bccx, &mut err, &error.move_from, &error.moved),
             _ => {
                 for move_to in &error.move_to_places {
 
@@ -129,7 +140,8 @@ fn group_errors_with_same_origin<'tcx>(errors: &Vec<MoveError<'tcx>>)
         debug!("found a new move from location");
         grouped_errors.push(GroupedMoveErrors {
             move_from: error.move_from.clone(),
-            move_to_places: move_to
+            move_to_places: move_to,
+            moved: error.moved.clone(),
         })
     }
 }
@@ -169,6 +181,59 @@ fn report_cannot_move_out_of<'a, 'tcx>(bccx: &'a BorrowckCtxt<'a, 'tcx>,
     }
 }
 
+/// Suggests borrowing when the move out of borrowed content is done by a
+/// method call that takes its receiver by value, as in `self.opt.unwrap()`
+/// with `self: &Self`, or `b.into_iter()` with `b: &Box<Vec<T>>`.
+fn suggest_borrowing_receiver<'a, 'tcx>(bccx: &BorrowckCtxt<'a, 'tcx>,
+                                        err: &mut DiagnosticBuilder,
+                                        move_from: &mc::cmt<'tcx>,
+                                        moved: &mc::cmt<'tcx>) {
+    let tcx = bccx.tcx;
+    let receiver = match tcx.hir.find(tcx.hir.get_parent_node(moved.id)) {
+        Some(hir_map::NodeExpr(&hir::Expr { node: hir::ExprMethodCall(_, _, ref args), .. }))
+            if args[0].id == moved.id => &args[0],
+        _ => return,
+    };
+    let snippet = match tcx.sess.codemap().span_to_snippet(receiver.span) {
+        Ok(snippet) => snippet,
+        Err(_) => return,
+    };
+
+    match moved.ty.sty {
+        ty::TyAdt(def, _) if Some(def.did) == tcx.lang_items().option_type() => {
+            let is_mut = match move_from.cat {
+                Categorization::Deref(_, mc::BorrowedPtr(ty::MutBorrow, _)) |
+                Categorization::Deref(_, mc::Implicit(ty::MutBorrow, _)) => true,
+                _ => false,
+            };
+            err.span_suggestion_with_applicability(
+                receiver.span,
+                "consider borrowing the `Option`'s content",
+                format!("{}.{}()", snippet, if is_mut { "as_mut" } else { "as_ref" }),
+                Applicability::MaybeIncorrect);
+        }
+        _ => {
+            // Moving out of a `Box` that the receiver was auto-dereferenced
+            // through; call the method on a reborrow of the boxed value instead.
+            if let Categorization::Deref(_, mc::Unique) = moved.cat {
+                let derefs = bccx.tables.expr_adjustments(receiver).iter()
+                    .filter(|adjustment| match adjustment.kind {
+                        Adjust::Deref(None) => true,
+                        _ => false,
+                    })
+                    .count();
+                if derefs > 0 {
+                    err.span_suggestion_with_applicability(
+                        receiver.span,
+                        "consider calling the method on a reborrow instead",
+                        format!("(&{}{})", "*".repeat(derefs), snippet),
+                        Applicability::MaybeIncorrect);
+                }
+            }
+        }
+    }
+}
+
 fn note_move_destination(mut err: DiagnosticBuilder,
                          move_to_span: syntax_pos::Span,
                          pat_name: ast::Name,
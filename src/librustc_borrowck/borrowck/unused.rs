@@ -15,6 +15,7 @@ use rustc::ty;
 use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use std::slice;
 use syntax::ptr::P;
+use errors::Applicability;
 
 use borrowck::BorrowckCtxt;
 
@@ -85,7 +86,10 @@ impl<'a, 'tcx> UnusedMutCx<'a, 'tcx> {
                                       ids[0].0,
                                       ids[0].2,
                                       "variable does not need to be mutable")
-                .span_suggestion_short(mut_span, "remove this `mut`", "".to_owned())
+                .span_suggestion_short_with_applicability(mut_span,
+                                                          "remove this `mut`",
+                                                          "".to_owned(),
+                                                          Applicability::MachineApplicable)
                 .emit();
         }
     }
@@ -13,6 +13,7 @@ use std::collections::btree_map::Entry;
 use std::marker::PhantomData;
 use std::iter::FromIterator;
 use indexed_vec::{Idx, IndexVec};
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
 
 type Word = u128;
 const WORD_BITS: usize = 128;
@@ -266,7 +267,7 @@ impl BitMatrix {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct SparseBitMatrix<R, C>
 where
     R: Idx,
@@ -325,6 +326,11 @@ impl<R: Idx, C: Idx> SparseBitMatrix<R, C> {
     pub fn iter<'a>(&'a self, row: R) -> impl Iterator<Item = C> + 'a {
         self.vector[row].iter()
     }
+
+    /// Iterates through the rows of the matrix.
+    pub fn rows(&self) -> impl Iterator<Item = R> {
+        self.vector.indices()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -333,6 +339,21 @@ pub struct SparseBitSet<I: Idx> {
     _marker: PhantomData<I>,
 }
 
+impl<I: Idx> Encodable for SparseBitSet<I> {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        self.chunk_bits.encode(e)
+    }
+}
+
+impl<I: Idx> Decodable for SparseBitSet<I> {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        Ok(SparseBitSet {
+            chunk_bits: Decodable::decode(d)?,
+            _marker: PhantomData,
+        })
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct SparseChunk<I> {
     key: u32,
@@ -621,3 +642,28 @@ fn matrix_iter() {
     }
     assert!(iter.next().is_none());
 }
+
+#[test]
+fn sparse_matrix_encode_decode() {
+    use rustc_serialize::opaque;
+    use std::io::Cursor;
+
+    let mut matrix: SparseBitMatrix<usize, usize> = SparseBitMatrix::new(3, 300);
+    matrix.add(0, 1);
+    matrix.add(0, 200);
+    matrix.add(2, 63);
+    matrix.add(2, 64);
+
+    let mut cursor = Cursor::new(Vec::new());
+    matrix.encode(&mut opaque::Encoder::new(&mut cursor)).unwrap();
+    let data = cursor.into_inner();
+    let decoded: SparseBitMatrix<usize, usize> =
+        Decodable::decode(&mut opaque::Decoder::new(&data, 0)).unwrap();
+
+    for row in 0..3 {
+        assert_eq!(
+            matrix.iter(row).collect::<Vec<_>>(),
+            decoded.iter(row).collect::<Vec<_>>()
+        );
+    }
+}
@@ -26,6 +26,12 @@
 //!
 //! `MTLock` is a mutex which disappears if cfg!(parallel_queries) is false.
 //!
+//! `Once` is a value which can be set once and then only be read.
+//!
+//! `OneThread` wraps a value which may only be used on the thread which
+//! created it. It lets types which are not thread safe live in structures
+//! which must be `Sync`, as long as only the main thread touches them.
+//!
 //! `rustc_global!` gives us a way to declare variables which are intended to be
 //! global for the current rustc session. This currently maps to thread-locals,
 //! since rustdoc uses the rustc libraries in multiple threads.
@@ -38,6 +44,8 @@ use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
 use owning_ref::{Erased, OwningRef};
 
 cfg_if! {
@@ -363,6 +371,13 @@ impl<T> Lock<T> {
     }
 }
 
+impl<T: Default> Default for Lock<T> {
+    #[inline]
+    fn default() -> Self {
+        Lock::new(T::default())
+    }
+}
+
 // FIXME: Probably a bad idea
 impl<T: Clone> Clone for Lock<T> {
     #[inline]
@@ -443,6 +458,13 @@ impl<T> RwLock<T> {
     }
 }
 
+impl<T: Default> Default for RwLock<T> {
+    #[inline]
+    fn default() -> Self {
+        RwLock::new(T::default())
+    }
+}
+
 // FIXME: Probably a bad idea
 impl<T: Clone> Clone for RwLock<T> {
     #[inline]
@@ -450,3 +472,110 @@ impl<T: Clone> Clone for RwLock<T> {
         RwLock::new(self.borrow().clone())
     }
 }
+
+/// A type whose inner value can be written once and then stays read-only.
+// This contains a `PhantomData<T>` since this type conceptually owns a `T`
+// outside the lock once it is initialized. This makes `Once<T>` `Sync` only
+// if `T` is.
+pub struct Once<T>(Lock<Option<T>>, PhantomData<T>);
+
+impl<T> Once<T> {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Once(Lock::new(None), PhantomData)
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> Option<T> {
+        self.0.into_inner()
+    }
+
+    /// Sets the value, or returns `value` back if it was already set.
+    #[inline]
+    pub fn try_set(&self, value: T) -> Option<T> {
+        let mut lock = self.0.lock();
+        if lock.is_some() {
+            return Some(value);
+        }
+        *lock = Some(value);
+        None
+    }
+
+    #[inline]
+    pub fn set(&self, value: T) {
+        assert!(self.try_set(value).is_none(), "value was already set");
+    }
+
+    #[inline]
+    pub fn try_get(&self) -> Option<&T> {
+        let lock = &*self.0.lock();
+        if let Some(ref inner) = *lock {
+            // This is safe since the value is never mutated or moved once set
+            Some(unsafe { &*(inner as *const T) })
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self) -> &T {
+        self.try_get().expect("value was not set")
+    }
+}
+
+impl<T: Debug> Debug for Once<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Once").field(&self.try_get()).finish()
+    }
+}
+
+/// A type which only allows its inner value to be used on the thread which
+/// created it. It panics if it is used from another thread.
+#[derive(Debug)]
+pub struct OneThread<T> {
+    #[cfg(parallel_queries)]
+    thread: ::std::thread::ThreadId,
+    inner: T,
+}
+
+unsafe impl<T> ::std::marker::Sync for OneThread<T> {}
+unsafe impl<T> ::std::marker::Send for OneThread<T> {}
+
+impl<T> OneThread<T> {
+    #[inline(always)]
+    fn check(&self) {
+        #[cfg(parallel_queries)]
+        assert_eq!(::std::thread::current().id(), self.thread);
+    }
+
+    #[inline(always)]
+    pub fn new(inner: T) -> Self {
+        OneThread {
+            #[cfg(parallel_queries)]
+            thread: ::std::thread::current().id(),
+            inner,
+        }
+    }
+
+    #[inline(always)]
+    pub fn into_inner(value: Self) -> T {
+        value.check();
+        value.inner
+    }
+}
+
+impl<T> Deref for OneThread<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.check();
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for OneThread<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.check();
+        &mut self.inner
+    }
+}
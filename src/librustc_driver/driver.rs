@@ -11,27 +11,29 @@
 use rustc::dep_graph::DepGraph;
 use rustc::hir::{self, map as hir_map};
 use rustc::hir::lowering::lower_crate;
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
 use rustc::ich::Fingerprint;
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_mir as mir;
 use rustc::session::{Session, CompileResult, CrateDisambiguator};
 use rustc::session::CompileIncomplete;
-use rustc::session::config::{self, Input, OutputFilenames, OutputType};
+use rustc::session::config::{self, BorrowckMode, Input, OutputFilenames, OutputType};
 use rustc::session::search_paths::PathKind;
 use rustc::lint;
 use rustc::middle::{self, stability, reachable, resolve_lifetime};
 use rustc::middle::cstore::CrateStore;
 use rustc::middle::privacy::AccessLevels;
+use rustc::mir::BorrowCheckResult;
 use rustc::ty::{self, TyCtxt, Resolutions, AllArenas};
 use rustc::traits;
-use rustc::util::common::{ErrorReported, time, install_panic_hook};
+use rustc::util::common::{ErrorReported, time, install_panic_hook, print_time_passes_entry};
 use rustc_allocator as allocator;
 use rustc_borrowck as borrowck;
 use rustc_incremental;
 use rustc_resolve::{MakeGlobMap, Resolver, ResolverArenas};
 use rustc_metadata::creader::CrateLoader;
 use rustc_metadata::cstore::{self, CStore};
-use rustc_trans_utils::trans_crate::TransCrate;
+use rustc_trans_utils::codegen_backend::CodegenBackend;
 use rustc_typeck as typeck;
 use rustc_privacy;
 use rustc_plugin::registry::Registry;
@@ -63,7 +65,7 @@ use pretty::ReplaceBodyWithLoop;
 
 use profile;
 
-pub fn compile_input(trans: Box<TransCrate>,
+pub fn compile_input(trans: Box<CodegenBackend>,
                      sess: &Session,
                      cstore: &CStore,
                      input_path: &Option<PathBuf>,
@@ -264,6 +266,13 @@ pub fn compile_input(trans: Box<TransCrate>,
                 }
             }
 
+            if tcx.sess.opts.output_types.contains_key(&OutputType::MirJson) {
+                if let Err(e) = mir::transform::dump_mir::emit_mir_json(tcx, &outputs) {
+                    sess.err(&format!("could not emit MIR: {}", e));
+                    sess.abort_if_errors();
+                }
+            }
+
             Ok((outputs.clone(), ongoing_trans, tcx.dep_graph.clone()))
         })??
     };
@@ -278,6 +287,10 @@ pub fn compile_input(trans: Box<TransCrate>,
         sess.print_perf_stats();
     }
 
+    if sess.opts.debugging_opts.self_profile {
+        sess.save_self_profile(&outputs.out_filestem);
+    }
+
     controller_entry_point!(
         compilation_done,
         sess,
@@ -330,6 +343,13 @@ pub struct CompileController<'a> {
     /// Same as `provide`, but only for non-local crates,
     /// applied after `default_provide_extern`.
     pub provide_extern: Box<Fn(&mut ty::maps::Providers) + 'a>,
+    /// Called with the result of the MIR borrow checker for each body,
+    /// once all of them have been checked, so that tools can build on
+    /// what it found. The results are empty for bodies that the MIR
+    /// borrow checker doesn't check, e.g. without `-Zborrowck=mir`.
+    pub after_borrowck: Option<Box<for<'b, 'tcx> Fn(TyCtxt<'b, 'tcx, 'tcx>,
+                                                    DefId,
+                                                    &BorrowCheckResult<'tcx>) + 'a>>,
 }
 
 impl<'a> CompileController<'a> {
@@ -345,6 +365,7 @@ impl<'a> CompileController<'a> {
             continue_parse_after_error: false,
             provide: box |_| {},
             provide_extern: box |_| {},
+            after_borrowck: None,
         }
     }
 }
@@ -714,8 +735,8 @@ pub fn phase_2_configure_and_expand_inner<'a, F>(sess: &'a Session,
     });
 
     let whitelisted_legacy_custom_derives = registry.take_whitelisted_custom_derives();
-    let Registry { syntax_exts, early_lint_passes, late_lint_passes, lint_groups,
-                   llvm_passes, attributes, .. } = registry;
+    let Registry { syntax_exts, early_lint_passes, late_lint_passes, mir_lint_passes,
+                   lint_groups, llvm_passes, attributes, .. } = registry;
 
     sess.track_errors(|| {
         let mut ls = sess.lint_store.borrow_mut();
@@ -725,6 +746,9 @@ pub fn phase_2_configure_and_expand_inner<'a, F>(sess: &'a Session,
         for pass in late_lint_passes {
             ls.register_late_pass(Some(sess), true, pass);
         }
+        for pass in mir_lint_passes {
+            ls.register_mir_pass(Some(sess), true, pass);
+        }
 
         for (name, to) in lint_groups {
             ls.register_group(Some(sess), true, name, to);
@@ -781,7 +805,7 @@ pub fn phase_2_configure_and_expand_inner<'a, F>(sess: &'a Session,
         }
         let features = sess.features_untracked();
         let cfg = syntax::ext::expand::ExpansionConfig {
-            features: Some(&features),
+            features: Some(features),
             recursion_limit: sess.recursion_limit.get(),
             trace_mac: sess.opts.debugging_opts.trace_macros,
             should_test: sess.opts.test,
@@ -954,7 +978,7 @@ pub fn default_provide_extern(providers: &mut ty::maps::Providers) {
 /// Run the resolution, typechecking, region checking and other
 /// miscellaneous analysis passes on the crate. Return various
 /// structures carrying the results of the analysis.
-pub fn phase_3_run_analysis_passes<'tcx, F, R>(trans: &TransCrate,
+pub fn phase_3_run_analysis_passes<'tcx, F, R>(trans: &CodegenBackend,
                                                control: &CompileController,
                                                sess: &'tcx Session,
                                                cstore: &'tcx CrateStore,
@@ -1060,9 +1084,61 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(trans: &TransCrate,
              "borrow checking",
              || borrowck::check_crate(tcx));
 
-        time(sess,
-             "MIR borrow checking",
-             || for def_id in tcx.body_owners() { tcx.mir_borrowck(def_id); });
+        // When the inputs of a body are unchanged since the previous
+        // incremental session, `ensure` reuses the cached result (and
+        // replays its errors) rather than running the borrow checker.
+        time(sess, "MIR borrow checking", || {
+            tcx.par_body_owners(|def_id| {
+                ty::maps::queries::mir_borrowck::ensure(tcx, def_id);
+            });
+            // Printed from within the pass, so that the phases are nested
+            // under it.
+            if tcx.use_mir() {
+                for (phase, duration) in sess.mir_borrowck_times.phases() {
+                    print_time_passes_entry(sess.time_passes(), phase, duration);
+                }
+            }
+        });
+        // Both borrow checkers have run, so the errors held back in favour of
+        // the other one's (see `-Z borrowck-tool-errors`) can be emitted.
+        sess.diagnostic().flush_held_diagnostics();
+        if let Some(ref after_borrowck) = control.after_borrowck {
+            for def_id in tcx.body_owners() {
+                after_borrowck(tcx, def_id, &tcx.mir_borrowck(def_id));
+            }
+        }
+        if sess.opts.debugging_opts.nll_stats {
+            sess.nll_stats.print();
+        }
+        if sess.opts.incremental.is_some() && sess.opts.debugging_opts.incremental_info &&
+            tcx.use_mir()
+        {
+            // `mir_borrowck` counts the bodies it actually checks, which
+            // are all body owners (closures included) unless the result
+            // was reused.
+            let bodies = tcx.body_owners().count() as u64;
+            let bodies_checked = sess.mir_borrowck_times.bodies_checked.get();
+            println!("[incremental] MIR borrowck: {} of {} bodies reused",
+                     bodies - bodies_checked,
+                     bodies);
+        }
+
+        if tcx.borrowck_mode() == BorrowckMode::Compare {
+            let json = match sess.opts.debugging_opts.borrowck_compare_report {
+                None => None,
+                Some(ref format) if format == "text" => Some(false),
+                Some(ref format) if format == "json" => Some(true),
+                Some(ref format) => {
+                    sess.err(&format!("unknown borrowck comparison report format `{}`",
+                                      format));
+                    None
+                }
+            };
+            if let Some(json) = json {
+                sess.borrowck_comparison.borrow().print_report(
+                    sess.codemap(), &tcx.crate_name(LOCAL_CRATE).as_str(), json);
+            }
+        }
 
         time(sess,
              "MIR effect checking",
@@ -1087,13 +1163,19 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(trans: &TransCrate,
 
         time(sess, "lint checking", || lint::check_crate(tcx));
 
+        time(sess, "MIR lint checking", || lint::check_mir_crate(tcx));
+
+        if sess.opts.debugging_opts.interpret_main {
+            time(sess, "interpreting the program", || mir::interpret::eval_main(tcx));
+        }
+
         return Ok(f(tcx, analysis, rx, tcx.sess.compile_status()));
     })
 }
 
 /// Run the translation phase to LLVM, after which the AST and analysis can
 /// be discarded.
-pub fn phase_4_translate_to_llvm<'a, 'tcx>(trans: &TransCrate,
+pub fn phase_4_translate_to_llvm<'a, 'tcx>(trans: &CodegenBackend,
                                            tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                            rx: mpsc::Receiver<Box<Any + Send>>)
                                            -> Box<Any> {
@@ -74,8 +74,8 @@ use rustc::middle::cstore::CrateStore;
 use rustc_metadata::locator;
 use rustc_metadata::cstore::CStore;
 use rustc_metadata::dynamic_lib::DynamicLibrary;
-use rustc::util::common::{time, ErrorReported};
-use rustc_trans_utils::trans_crate::TransCrate;
+use rustc::util::common::{time, ErrorReported, BUG_REPORT_URL, STACK_SIZE};
+use rustc_trans_utils::codegen_backend::CodegenBackend;
 
 use serialize::json::ToJson;
 
@@ -115,14 +115,14 @@ pub mod target_features {
     use syntax::ast;
     use syntax::symbol::Symbol;
     use rustc::session::Session;
-    use rustc_trans_utils::trans_crate::TransCrate;
+    use rustc_trans_utils::codegen_backend::CodegenBackend;
 
     /// Add `target_feature = "..."` cfgs for a variety of platform
     /// specific features (SSE, NEON etc.).
     ///
     /// This is performed by checking whether a whitelisted set of
     /// features is available on the target machine, by querying LLVM.
-    pub fn add_configuration(cfg: &mut ast::CrateConfig, sess: &Session, trans: &TransCrate) {
+    pub fn add_configuration(cfg: &mut ast::CrateConfig, sess: &Session, trans: &CodegenBackend) {
         let tf = Symbol::intern("target_feature");
 
         for feat in trans.target_features(sess) {
@@ -135,9 +135,6 @@ pub mod target_features {
     }
 }
 
-const BUG_REPORT_URL: &'static str = "https://github.com/rust-lang/rust/blob/master/CONTRIBUTING.\
-                                      md#bug-reports";
-
 const ICE_REPORT_COMPILER_FLAGS: &'static [&'static str] = &[
     "Z",
     "C",
@@ -181,7 +178,7 @@ pub fn run<F>(run_compiler: F) -> isize
                                                                None,
                                                                true,
                                                                false);
-                    let handler = errors::Handler::with_emitter(true, false, Box::new(emitter));
+                    let handler = errors::Handler::with_emitter(true, None, Box::new(emitter));
                     handler.emit(&MultiSpan::new(),
                                  "aborting due to previous error(s)",
                                  errors::Level::Fatal);
@@ -193,7 +190,7 @@ pub fn run<F>(run_compiler: F) -> isize
     0
 }
 
-fn load_backend_from_dylib(path: &Path) -> fn() -> Box<TransCrate> {
+fn load_backend_from_dylib(path: &Path) -> fn() -> Box<CodegenBackend> {
     // Note that we're specifically using `open_global_now` here rather than
     // `open`, namely we want the behavior on Unix of RTLD_GLOBAL and RTLD_NOW,
     // where NOW means "bind everything right now" because we don't want
@@ -226,21 +223,21 @@ fn load_backend_from_dylib(path: &Path) -> fn() -> Box<TransCrate> {
     }
 }
 
-pub fn get_trans(sess: &Session) -> Box<TransCrate> {
+pub fn get_codegen_backend(sess: &Session) -> Box<CodegenBackend> {
     static INIT: Once = ONCE_INIT;
-    static mut LOAD: fn() -> Box<TransCrate> = || unreachable!();
+    static mut LOAD: fn() -> Box<CodegenBackend> = || unreachable!();
 
     INIT.call_once(|| {
         let trans_name = sess.opts.debugging_opts.codegen_backend.as_ref()
             .unwrap_or(&sess.target.target.options.codegen_backend);
         let backend = match &trans_name[..] {
             "metadata_only" => {
-                rustc_trans_utils::trans_crate::MetadataOnlyTransCrate::new
+                rustc_trans_utils::codegen_backend::MetadataOnlyCodegenBackend::new
             }
             filename if filename.contains(".") => {
                 load_backend_from_dylib(filename.as_ref())
             }
-            trans_name => get_trans_sysroot(trans_name),
+            trans_name => get_codegen_sysroot(trans_name),
         };
 
         unsafe {
@@ -252,10 +249,10 @@ pub fn get_trans(sess: &Session) -> Box<TransCrate> {
     backend
 }
 
-fn get_trans_sysroot(backend_name: &str) -> fn() -> Box<TransCrate> {
+fn get_codegen_sysroot(backend_name: &str) -> fn() -> Box<CodegenBackend> {
     // For now we only allow this function to be called once as it'll dlopen a
     // few things, which seems to work best if we only do that once. In
-    // general this assertion never trips due to the once guard in `get_trans`,
+    // general this assertion never trips due to the once guard in `get_codegen_backend`,
     // but there's a few manual calls to this function in this file we protect
     // against.
     static LOADED: AtomicBool = ATOMIC_BOOL_INIT;
@@ -268,7 +265,7 @@ fn get_trans_sysroot(backend_name: &str) -> fn() -> Box<TransCrate> {
     // let's just return a dummy creation function which won't be used in
     // general anyway.
     if cfg!(test) {
-        return rustc_trans_utils::trans_crate::MetadataOnlyTransCrate::new
+        return rustc_trans_utils::codegen_backend::MetadataOnlyCodegenBackend::new
     }
 
     let target = session::config::host_triple();
@@ -495,7 +492,7 @@ pub fn run_compiler<'a>(args: &[String],
         return (Err(CompileIncomplete::Stopped), Some(sess));
     }
 
-    let trans = get_trans(&sess);
+    let trans = get_codegen_backend(&sess);
 
     rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
 
@@ -617,7 +614,7 @@ pub trait CompilerCalls<'a> {
     // be called just before actual compilation starts (and before build_controller
     // is called), after all arguments etc. have been completely handled.
     fn late_callback(&mut self,
-                     _: &TransCrate,
+                     _: &CodegenBackend,
                      _: &getopts::Matches,
                      _: &Session,
                      _: &CrateStore,
@@ -799,7 +796,7 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
                 }
                 rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
                 let mut cfg = config::build_configuration(&sess, cfg.clone());
-                let trans = get_trans(&sess);
+                let trans = get_codegen_backend(&sess);
                 target_features::add_configuration(&mut cfg, &sess, &*trans);
                 sess.parse_sess.config = cfg;
                 let should_stop = RustcDefaultCalls::print_crate_info(
@@ -821,7 +818,7 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
     }
 
     fn late_callback(&mut self,
-                     trans: &TransCrate,
+                     trans: &CodegenBackend,
                      matches: &getopts::Matches,
                      sess: &Session,
                      cstore: &CrateStore,
@@ -958,7 +955,7 @@ impl RustcDefaultCalls {
     }
 
 
-    fn print_crate_info(trans: &TransCrate,
+    fn print_crate_info(trans: &CodegenBackend,
                         sess: &Session,
                         input: Option<&Input>,
                         odir: &Option<PathBuf>,
@@ -1101,7 +1098,7 @@ pub fn version(binary: &str, matches: &getopts::Matches) {
         println!("commit-date: {}", unw(commit_date_str()));
         println!("host: {}", config::host_triple());
         println!("release: {}", unw(release_str()));
-        get_trans_sysroot("llvm")().print_version();
+        get_codegen_sysroot("llvm")().print_version();
     }
 }
 
@@ -1398,7 +1395,7 @@ pub fn handle_options(args: &[String]) -> Option<getopts::Matches> {
     }
 
     if cg_flags.contains(&"passes=list".to_string()) {
-        get_trans_sysroot("llvm")().print_passes();
+        get_codegen_sysroot("llvm")().print_passes();
         return None;
     }
 
@@ -1430,9 +1427,6 @@ pub fn in_rustc_thread<F, R>(f: F) -> Result<R, Box<Any + Send>>
     where F: FnOnce() -> R + Send + 'static,
           R: Send + 'static,
 {
-    // Temporarily have stack size set to 16MB to deal with nom-using crates failing
-    const STACK_SIZE: usize = 16 * 1024 * 1024; // 16MB
-
     let mut cfg = thread::Builder::new().name("rustc".to_string());
 
     // FIXME: Hacks on hacks. If the env is trying to override the stack size
@@ -1517,7 +1511,7 @@ pub fn monitor<F: FnOnce() + Send + 'static>(f: F) {
                                                                 None,
                                                                 false,
                                                                 false));
-            let handler = errors::Handler::with_emitter(true, false, emitter);
+            let handler = errors::Handler::with_emitter(true, None, emitter);
 
             // a .span_bug or .bug call has already printed what
             // it wants to print.
@@ -1563,7 +1557,7 @@ pub fn diagnostics_registry() -> errors::registry::Registry {
     all_errors.extend_from_slice(&rustc_resolve::DIAGNOSTICS);
     all_errors.extend_from_slice(&rustc_privacy::DIAGNOSTICS);
     // FIXME: need to figure out a way to get these back in here
-    // all_errors.extend_from_slice(get_trans(sess).diagnostics());
+    // all_errors.extend_from_slice(get_codegen_backend(sess).diagnostics());
     all_errors.extend_from_slice(&rustc_trans_utils::DIAGNOSTICS);
     all_errors.extend_from_slice(&rustc_metadata::DIAGNOSTICS);
     all_errors.extend_from_slice(&rustc_passes::DIAGNOSTICS);
@@ -228,7 +228,7 @@ impl PpSourceMode {
             }
             PpmTyped => {
                 let control = &driver::CompileController::basic();
-                let trans = ::get_trans(sess);
+                let trans = ::get_codegen_backend(sess);
                 abort_on_err(driver::phase_3_run_analysis_passes(&*trans,
                                                                  control,
                                                                  sess,
@@ -1082,7 +1082,7 @@ fn print_with_analysis<'tcx, 'a: 'tcx>(sess: &'a Session,
     let mut out = Vec::new();
 
     let control = &driver::CompileController::basic();
-    let trans = ::get_trans(sess);
+    let trans = ::get_codegen_backend(sess);
     abort_on_err(driver::phase_3_run_analysis_passes(&*trans,
                                                      control,
                                                      sess,
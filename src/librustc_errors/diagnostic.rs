@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use Applicability;
 use CodeSuggestion;
 use SubstitutionPart;
 use Substitution;
@@ -25,6 +26,9 @@ pub struct Diagnostic {
     pub span: MultiSpan,
     pub children: Vec<SubDiagnostic>,
     pub suggestions: Vec<CodeSuggestion>,
+    /// The checker that reported this diagnostic when several of them check
+    /// the same code, e.g. `ast` or `mir` under `-Z borrowck=compare`.
+    pub origin: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
@@ -95,6 +99,7 @@ impl Diagnostic {
             span: MultiSpan::new(),
             children: vec![],
             suggestions: vec![],
+            origin: None,
         }
     }
 
@@ -213,18 +218,8 @@ impl Diagnostic {
     ///
     /// See `CodeSuggestion` for more information.
     pub fn span_suggestion_short(&mut self, sp: Span, msg: &str, suggestion: String) -> &mut Self {
-        self.suggestions.push(CodeSuggestion {
-            substitutions: vec![Substitution {
-                parts: vec![SubstitutionPart {
-                    snippet: suggestion,
-                    span: sp,
-                }],
-            }],
-            msg: msg.to_owned(),
-            show_code_when_inline: false,
-            approximate: false,
-        });
-        self
+        self.span_suggestion_short_with_applicability(sp, msg, suggestion,
+                                                      Applicability::Unspecified)
     }
 
     /// Prints out a message with a suggested edit of the code.
@@ -244,6 +239,29 @@ impl Diagnostic {
     ///
     /// See `CodeSuggestion` for more information.
     pub fn span_suggestion(&mut self, sp: Span, msg: &str, suggestion: String) -> &mut Self {
+        self.span_suggestion_with_applicability(sp, msg, suggestion, Applicability::Unspecified)
+    }
+
+    /// Prints out a message with multiple suggested edits of the code.
+    pub fn span_suggestions(&mut self, sp: Span, msg: &str, suggestions: Vec<String>) -> &mut Self {
+        self.span_suggestions_with_applicability(sp, msg, suggestions, Applicability::Unspecified)
+    }
+
+    /// Prints out a message with several alternative suggestions, each of
+    /// which edits the code at several places at once (e.g. adding `mut` to a
+    /// binding and borrowing it with `&mut`).
+    pub fn span_suggestions_multipart(&mut self, msg: &str,
+                                      suggestions: Vec<Vec<(Span, String)>>) -> &mut Self {
+        self.span_suggestions_multipart_with_applicability(msg, suggestions,
+                                                           Applicability::Unspecified)
+    }
+
+    /// Like `span_suggestion_short`, but tells tools how safe it is to apply
+    /// the suggestion without asking the user.
+    pub fn span_suggestion_short_with_applicability(&mut self, sp: Span, msg: &str,
+                                                    suggestion: String,
+                                                    applicability: Applicability)
+                                                    -> &mut Self {
         self.suggestions.push(CodeSuggestion {
             substitutions: vec![Substitution {
                 parts: vec![SubstitutionPart {
@@ -252,62 +270,94 @@ impl Diagnostic {
                 }],
             }],
             msg: msg.to_owned(),
-            show_code_when_inline: true,
-            approximate: false,
+            show_code_when_inline: false,
+            applicability,
         });
         self
     }
 
-    /// Prints out a message with multiple suggested edits of the code.
-    pub fn span_suggestions(&mut self, sp: Span, msg: &str, suggestions: Vec<String>) -> &mut Self {
+    /// Like `span_suggestion`, but tells tools how safe it is to apply the
+    /// suggestion without asking the user.
+    pub fn span_suggestion_with_applicability(&mut self, sp: Span, msg: &str,
+                                              suggestion: String,
+                                              applicability: Applicability)
+                                              -> &mut Self {
         self.suggestions.push(CodeSuggestion {
-            substitutions: suggestions.into_iter().map(|snippet| Substitution {
+            substitutions: vec![Substitution {
                 parts: vec![SubstitutionPart {
-                    snippet,
+                    snippet: suggestion,
                     span: sp,
                 }],
-            }).collect(),
+            }],
             msg: msg.to_owned(),
             show_code_when_inline: true,
-            approximate: false,
+            applicability,
         });
         self
     }
 
-    /// This is a suggestion that may contain mistakes or fillers and should
-    /// be read and understood by a human.
-    pub fn span_approximate_suggestion(&mut self, sp: Span, msg: &str,
-                                       suggestion: String) -> &mut Self {
+    /// Like `span_suggestions`, but tells tools how safe it is to apply the
+    /// suggestions without asking the user.
+    pub fn span_suggestions_with_applicability(&mut self, sp: Span, msg: &str,
+                                               suggestions: Vec<String>,
+                                               applicability: Applicability)
+                                               -> &mut Self {
         self.suggestions.push(CodeSuggestion {
-            substitutions: vec![Substitution {
+            substitutions: suggestions.into_iter().map(|snippet| Substitution {
                 parts: vec![SubstitutionPart {
-                    snippet: suggestion,
+                    snippet,
                     span: sp,
                 }],
-            }],
+            }).collect(),
             msg: msg.to_owned(),
             show_code_when_inline: true,
-            approximate: true,
+            applicability,
         });
         self
     }
 
-    pub fn span_approximate_suggestions(&mut self, sp: Span, msg: &str,
-                                        suggestions: Vec<String>) -> &mut Self {
+    /// Like `span_suggestions_multipart`, but tells tools how safe it is to
+    /// apply the suggestions without asking the user.
+    pub fn span_suggestions_multipart_with_applicability(&mut self, msg: &str,
+                                                         suggestions: Vec<Vec<(Span, String)>>,
+                                                         applicability: Applicability)
+                                                         -> &mut Self {
         self.suggestions.push(CodeSuggestion {
-            substitutions: suggestions.into_iter().map(|snippet| Substitution {
-                parts: vec![SubstitutionPart {
+            substitutions: suggestions.into_iter().map(|parts| Substitution {
+                parts: parts.into_iter().map(|(span, snippet)| SubstitutionPart {
                     snippet,
-                    span: sp,
-                }],
+                    span,
+                }).collect(),
             }).collect(),
             msg: msg.to_owned(),
             show_code_when_inline: true,
-            approximate: true,
+            applicability,
         });
         self
     }
 
+    /// This is a suggestion that may contain mistakes or fillers and should
+    /// be read and understood by a human.
+    pub fn span_approximate_suggestion(&mut self, sp: Span, msg: &str,
+                                       suggestion: String) -> &mut Self {
+        self.span_suggestion_with_applicability(sp, msg, suggestion,
+                                                Applicability::MaybeIncorrect)
+    }
+
+    pub fn span_approximate_suggestions(&mut self, sp: Span, msg: &str,
+                                        suggestions: Vec<String>) -> &mut Self {
+        self.span_suggestions_with_applicability(sp, msg, suggestions,
+                                                 Applicability::MaybeIncorrect)
+    }
+
+    /// Marks this diagnostic as reported by the checker `origin`, so that the
+    /// handler can drop it if the authoritative checker reports the same
+    /// problem (see `HandlerFlags::authoritative_origin`).
+    pub fn set_origin(&mut self, origin: &str) -> &mut Self {
+        self.origin = Some(origin.to_owned());
+        self
+    }
+
     pub fn set_span<S: Into<MultiSpan>>(&mut self, sp: S) -> &mut Self {
         self.span = sp.into();
         self
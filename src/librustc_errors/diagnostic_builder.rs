@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use Applicability;
 use Diagnostic;
 use DiagnosticId;
 use DiagnosticStyledString;
@@ -15,6 +16,7 @@ use DiagnosticStyledString;
 use Level;
 use Handler;
 use std::fmt::{self, Debug};
+use std::mem;
 use std::ops::{Deref, DerefMut};
 use std::thread::panicking;
 use syntax_pos::{MultiSpan, Span};
@@ -87,6 +89,20 @@ impl<'a> DiagnosticBuilder<'a> {
         self.cancel();
     }
 
+    /// Buffer the diagnostic instead of emitting it, so that the caller can
+    /// emit it later, e.g. once all the diagnostics of a pass can be sorted.
+    pub fn buffer(mut self, buffered_diagnostics: &mut Vec<Diagnostic>) {
+        if self.cancelled() {
+            return;
+        }
+
+        // Leave a cancelled diagnostic behind, so that dropping `self`
+        // doesn't complain about it not having been emitted.
+        let diagnostic = mem::replace(&mut self.diagnostic,
+                                      Diagnostic::new(Level::Cancelled, ""));
+        buffered_diagnostics.push(diagnostic);
+    }
+
     pub fn is_error(&self) -> bool {
         match self.level {
             Level::Bug |
@@ -186,16 +202,45 @@ impl<'a> DiagnosticBuilder<'a> {
                                      msg: &str,
                                      suggestions: Vec<String>)
                                      -> &mut Self);
+    forward!(pub fn span_suggestion_short_with_applicability(&mut self,
+                                                             sp: Span,
+                                                             msg: &str,
+                                                             suggestion: String,
+                                                             applicability: Applicability)
+                                                             -> &mut Self);
+    forward!(pub fn span_suggestion_with_applicability(&mut self,
+                                                       sp: Span,
+                                                       msg: &str,
+                                                       suggestion: String,
+                                                       applicability: Applicability)
+                                                       -> &mut Self);
+    forward!(pub fn span_suggestions_with_applicability(&mut self,
+                                                        sp: Span,
+                                                        msg: &str,
+                                                        suggestions: Vec<String>,
+                                                        applicability: Applicability)
+                                                        -> &mut Self);
     forward!(pub fn span_approximate_suggestion(&mut self,
                                                 sp: Span,
                                                 msg: &str,
                                                 suggestion: String)
                                                 -> &mut Self);
+    forward!(pub fn span_suggestions_multipart(&mut self,
+                                               msg: &str,
+                                               suggestions: Vec<Vec<(Span, String)>>)
+                                               -> &mut Self);
+    forward!(pub fn span_suggestions_multipart_with_applicability(
+        &mut self,
+        msg: &str,
+        suggestions: Vec<Vec<(Span, String)>>,
+        applicability: Applicability
+    ) -> &mut Self);
     forward!(pub fn span_approximate_suggestions(&mut self,
                                                  sp: Span,
                                                  msg: &str,
                                                  suggestions: Vec<String>)
                                                  -> &mut Self);
+    forward!(pub fn set_origin(&mut self, origin: &str) -> &mut Self);
     forward!(pub fn set_span<S: Into<MultiSpan>>(&mut self, sp: S) -> &mut Self);
     forward!(pub fn code(&mut self, s: DiagnosticId) -> &mut Self);
 
@@ -1006,7 +1006,12 @@ impl EmitterWriter {
             }
             buffer.append(0, ": ", Style::HeaderMsg);
             for &(ref text, _) in msg.iter() {
-                buffer.append(0, text, Style::HeaderMsg);
+                if self.short_message {
+                    // Keep every diagnostic on a single line, so the output stays easy to grep.
+                    buffer.append(0, &text.replace("\n", " "), Style::HeaderMsg);
+                } else {
+                    buffer.append(0, text, Style::HeaderMsg);
+                }
             }
         }
 
@@ -1037,8 +1042,10 @@ impl EmitterWriter {
 
         // Print out the annotate source lines that correspond with the error
         for annotated_file in annotated_files {
-            // we can't annotate anything if the source is unavailable.
-            if !cm.ensure_filemap_source_present(annotated_file.file.clone()) {
+            // we can't annotate anything if the source is unavailable, but the short
+            // format only needs the location.
+            if !self.short_message &&
+               !cm.ensure_filemap_source_present(annotated_file.file.clone()) {
                 continue;
             }
 
@@ -1063,7 +1070,7 @@ impl EmitterWriter {
                     }
                 } else {
                     buffer.prepend(0,
-                                   &format!("{}:{}:{} - ",
+                                   &format!("{}:{}:{}: ",
                                             loc.file.name,
                                             cm.doctest_offset_line(loc.line),
                                             loc.col.0 + 1),
@@ -37,7 +37,7 @@ use self::Level::*;
 use emitter::{Emitter, EmitterWriter};
 
 use rustc_data_structures::sync::Lrc;
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::stable_hasher::StableHasher;
 
 use std::borrow::Cow;
@@ -86,12 +86,30 @@ pub struct CodeSuggestion {
     pub substitutions: Vec<Substitution>,
     pub msg: String,
     pub show_code_when_inline: bool,
-    /// Whether or not the suggestion is approximate
-    ///
-    /// Sometimes we may show suggestions with placeholders,
-    /// which are useful for users but not useful for
-    /// tools like rustfix
-    pub approximate: bool,
+    /// Whether or not the suggestion can be applied by tools like rustfix
+    /// without a human looking at it first
+    pub applicability: Applicability,
+}
+
+/// Indicates the confidence in the correctness of a suggestion.
+///
+/// All suggestions are marked with an `Applicability`. Tools use the applicability of a
+/// suggestion to determine whether it should be automatically applied or if the user should
+/// be consulted before applying the suggestion.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended. This suggestion should be
+    /// automatically applied.
+    MachineApplicable,
+    /// The suggestion may be what the user intended, but it is uncertain. The suggestion
+    /// should result in valid Rust code if it is applied.
+    MaybeIncorrect,
+    /// The suggestion contains placeholders like `(...)` or `{ /* fields */ }`. The
+    /// suggestion cannot be applied automatically because it will not result in valid
+    /// Rust code. The user will need to fill in the placeholders.
+    HasPlaceholders,
+    /// The applicability of the suggestion is unknown.
+    Unspecified,
 }
 
 #[derive(Clone, Debug, PartialEq, Hash, RustcEncodable, RustcDecodable)]
@@ -277,19 +295,51 @@ pub struct Handler {
     // this handler. These hashes is used to avoid emitting the same error
     // twice.
     emitted_diagnostics: RefCell<FxHashSet<u128>>,
+
+    // This map counts the errors of each kind (see `similar_diagnostic_hash`)
+    // that have been emitted, so that long runs of them can be collapsed into
+    // a single note.
+    similar_diagnostics: RefCell<FxHashMap<u128, SimilarDiagnostics>>,
+
+    // With `HandlerFlags::authoritative_origin` set, this set contains the
+    // `origin_key` of every diagnostic of the authoritative origin, and the
+    // diagnostics of the other origins are held back in `held_diagnostics`
+    // until `flush_held_diagnostics` is called.
+    authoritative_diagnostics: RefCell<FxHashSet<u128>>,
+    held_diagnostics: RefCell<Vec<Diagnostic>>,
+}
+
+/// The number of errors of the same kind that are shown before the rest of
+/// them are collapsed into a note.
+const MAX_SIMILAR_DIAGNOSTICS: usize = 10;
+
+struct SimilarDiagnostics {
+    /// Order in which the kinds were first seen, to keep the notes stable.
+    index: usize,
+    shown: usize,
+    omitted: usize,
+    /// Primary span of the first error of this kind that was left out.
+    first_omitted_span: MultiSpan,
 }
 
 #[derive(Default)]
 pub struct HandlerFlags {
     pub can_emit_warnings: bool,
-    pub treat_err_as_bug: bool,
+    /// If `Some(n)`, the `n`th error is turned into a compiler bug.
+    pub treat_err_as_bug: Option<usize>,
     pub external_macro_backtrace: bool,
+    /// Collapse errors of the same kind beyond `MAX_SIMILAR_DIAGNOSTICS`.
+    pub deduplicate_diagnostics: bool,
+    /// When several checkers report the same problems (see
+    /// `Diagnostic::origin`), only the diagnostics of this one are emitted
+    /// for the problems it reports.
+    pub authoritative_origin: Option<String>,
 }
 
 impl Handler {
     pub fn with_tty_emitter(color_config: ColorConfig,
                             can_emit_warnings: bool,
-                            treat_err_as_bug: bool,
+                            treat_err_as_bug: Option<usize>,
                             cm: Option<Lrc<CodeMapper>>)
                             -> Handler {
         Handler::with_tty_emitter_and_flags(
@@ -311,7 +361,7 @@ impl Handler {
     }
 
     pub fn with_emitter(can_emit_warnings: bool,
-                        treat_err_as_bug: bool,
+                        treat_err_as_bug: Option<usize>,
                         e: Box<Emitter>)
                         -> Handler {
         Handler::with_emitter_and_flags(
@@ -333,6 +383,9 @@ impl Handler {
             tracked_diagnostics: RefCell::new(None),
             tracked_diagnostic_codes: RefCell::new(FxHashSet()),
             emitted_diagnostics: RefCell::new(FxHashSet()),
+            similar_diagnostics: RefCell::new(FxHashMap()),
+            authoritative_diagnostics: RefCell::new(FxHashSet()),
+            held_diagnostics: RefCell::new(Vec::new()),
         }
     }
 
@@ -347,6 +400,7 @@ impl Handler {
     /// the overall count of emitted error diagnostics.
     pub fn reset_err_count(&self) {
         self.emitted_diagnostics.replace(FxHashSet());
+        self.similar_diagnostics.replace(FxHashMap());
         self.err_count.store(0, SeqCst);
     }
 
@@ -442,9 +496,21 @@ impl Handler {
         err.cancel();
     }
 
+    /// Whether the next error to be reported should be turned into a
+    /// compiler bug because of `-Z treat-err-as-bug`.
+    fn treat_next_err_as_bug(&self) -> bool {
+        self.flags.treat_err_as_bug.map_or(false, |n| self.err_count() + 1 >= n)
+    }
+
     fn panic_if_treat_err_as_bug(&self) {
-        if self.flags.treat_err_as_bug {
-            panic!("encountered error with `-Z treat_err_as_bug");
+        if !self.treat_next_err_as_bug() {
+            return;
+        }
+        match self.flags.treat_err_as_bug {
+            Some(1) => panic!("encountered error with `-Z treat_err_as_bug"),
+            Some(n) => panic!("encountered error number {} with `-Z treat-err-as-bug={}`",
+                              self.err_count() + 1, n),
+            None => {}
         }
     }
 
@@ -485,7 +551,7 @@ impl Handler {
         panic!(ExplicitBug);
     }
     pub fn delay_span_bug<S: Into<MultiSpan>>(&self, sp: S, msg: &str) {
-        if self.flags.treat_err_as_bug {
+        if self.treat_next_err_as_bug() {
             self.span_bug(sp, msg);
         }
         let mut diagnostic = Diagnostic::new(Level::Bug, msg);
@@ -510,7 +576,7 @@ impl Handler {
         self.span_bug(sp, &format!("unimplemented {}", msg));
     }
     pub fn fatal(&self, msg: &str) -> FatalError {
-        if self.flags.treat_err_as_bug {
+        if self.treat_next_err_as_bug() {
             self.bug(msg);
         }
         let mut db = DiagnosticBuilder::new(self, Fatal, msg);
@@ -518,7 +584,7 @@ impl Handler {
         FatalError
     }
     pub fn err(&self, msg: &str) {
-        if self.flags.treat_err_as_bug {
+        if self.treat_next_err_as_bug() {
             self.bug(msg);
         }
         let mut db = DiagnosticBuilder::new(self, Error, msg);
@@ -554,6 +620,7 @@ impl Handler {
         self.err_count() > 0
     }
     pub fn abort_if_errors(&self) {
+        self.flush_held_diagnostics();
         let s;
         match self.err_count() {
             0 => {
@@ -615,6 +682,12 @@ impl Handler {
     fn emit_db(&self, db: &DiagnosticBuilder) {
         let diagnostic = &**db;
 
+        // Compilation stops after a fatal error (including the final "aborting
+        // due to previous errors"), so report what was left out before it.
+        if diagnostic.level == Level::Fatal {
+            self.print_omitted_diagnostics();
+        }
+
         if let Some(ref mut list) = *self.tracked_diagnostics.borrow_mut() {
             list.push(diagnostic.clone());
         }
@@ -623,6 +696,10 @@ impl Handler {
             self.tracked_diagnostic_codes.borrow_mut().insert(code.clone());
         }
 
+        if self.hold_by_origin(diagnostic) {
+            return;
+        }
+
         let diagnostic_hash = {
             use std::hash::Hash;
             let mut hasher = StableHasher::new();
@@ -633,14 +710,142 @@ impl Handler {
         // Only emit the diagnostic if we haven't already emitted an equivalent
         // one:
         if self.emitted_diagnostics.borrow_mut().insert(diagnostic_hash) {
-            self.emitter.borrow_mut().emit(db);
+            if !self.omit_similar_diagnostic(diagnostic) {
+                self.emitter.borrow_mut().emit(db);
+            }
             if db.is_error() {
                 self.bump_err_count();
             }
         }
     }
+
+    /// Returns whether `diagnostic` comes from a checker other than the
+    /// authoritative one, in which case it is held back until
+    /// `flush_held_diagnostics` is called. The diagnostics of the
+    /// authoritative checker are recorded so that the held back ones reporting
+    /// the same problem can be dropped.
+    fn hold_by_origin(&self, diagnostic: &Diagnostic) -> bool {
+        let (authoritative, origin) = match (&self.flags.authoritative_origin,
+                                             &diagnostic.origin) {
+            (&Some(ref authoritative), &Some(ref origin)) => (authoritative, origin),
+            _ => return false,
+        };
+        if origin == authoritative {
+            self.authoritative_diagnostics.borrow_mut().insert(origin_key(diagnostic));
+            false
+        } else {
+            self.held_diagnostics.borrow_mut().push(diagnostic.clone());
+            true
+        }
+    }
+
+    /// Emits the diagnostics held back by `hold_by_origin`, except for the
+    /// ones that report the same problem as a diagnostic of the authoritative
+    /// checker.
+    pub fn flush_held_diagnostics(&self) {
+        let held = mem::replace(&mut *self.held_diagnostics.borrow_mut(), Vec::new());
+        for mut diagnostic in held {
+            if self.authoritative_diagnostics.borrow().contains(&origin_key(&diagnostic)) {
+                continue;
+            }
+            // Emit it as a diagnostic of no particular origin so that it is
+            // not held back again.
+            diagnostic.origin = None;
+            DiagnosticBuilder::new_diagnostic(self, diagnostic).emit();
+        }
+    }
+
+    /// Records an error that is about to be emitted, and returns whether it
+    /// should be left out because too many errors of the same kind have
+    /// already been shown.
+    fn omit_similar_diagnostic(&self, diagnostic: &Diagnostic) -> bool {
+        if !self.flags.deduplicate_diagnostics || diagnostic.level != Level::Error {
+            return false;
+        }
+
+        let mut similar_diagnostics = self.similar_diagnostics.borrow_mut();
+        let index = similar_diagnostics.len();
+        let similar = similar_diagnostics.entry(similar_diagnostic_hash(diagnostic))
+            .or_insert_with(|| SimilarDiagnostics {
+                index,
+                shown: 0,
+                omitted: 0,
+                first_omitted_span: MultiSpan::new(),
+            });
+        if similar.shown < MAX_SIMILAR_DIAGNOSTICS {
+            similar.shown += 1;
+            false
+        } else {
+            if similar.omitted == 0 {
+                similar.first_omitted_span =
+                    MultiSpan::from_spans(diagnostic.span.primary_spans().to_vec());
+            }
+            similar.omitted += 1;
+            true
+        }
+    }
+
+    /// Emits a note for every kind of error that had some of its errors left
+    /// out by `omit_similar_diagnostic`.
+    fn print_omitted_diagnostics(&self) {
+        let similar_diagnostics = mem::replace(&mut *self.similar_diagnostics.borrow_mut(),
+                                               FxHashMap());
+        let mut omitted = similar_diagnostics.into_iter()
+            .map(|(_, similar)| similar)
+            .filter(|similar| similar.omitted > 0)
+            .collect::<Vec<_>>();
+        omitted.sort_by_key(|similar| similar.index);
+
+        for similar in omitted {
+            let msg = if similar.omitted == 1 {
+                "1 more error of the same kind omitted".to_string()
+            } else {
+                format!("{} more errors of the same kind omitted", similar.omitted)
+            };
+            let mut db = DiagnosticBuilder::new(self, Level::Note, &msg);
+            db.set_span(similar.first_omitted_span);
+            db.note("run with `-Z deduplicate-diagnostics=no` to see all of them");
+            db.emit();
+        }
+    }
+}
+
+/// Identifies the problem a diagnostic reports by its level, code and primary
+/// span. The message is left out, as each checker words it differently.
+fn origin_key(diagnostic: &Diagnostic) -> u128 {
+    use std::hash::Hash;
+    let mut hasher = StableHasher::new();
+    diagnostic.level.hash(&mut hasher);
+    diagnostic.code.hash(&mut hasher);
+    diagnostic.span.primary_span().hash(&mut hasher);
+    hasher.finish()
 }
 
+/// Hashes everything about a diagnostic except for where it points to, so that
+/// errors reporting the same problem in different places hash the same.
+fn similar_diagnostic_hash(diagnostic: &Diagnostic) -> u128 {
+    use std::hash::Hash;
+    let mut hasher = StableHasher::new();
+    diagnostic.level.hash(&mut hasher);
+    diagnostic.code.hash(&mut hasher);
+    diagnostic.message.hash(&mut hasher);
+    for span_label in diagnostic.span.span_labels() {
+        span_label.label.hash(&mut hasher);
+    }
+    for child in &diagnostic.children {
+        child.level.hash(&mut hasher);
+        child.message.hash(&mut hasher);
+    }
+    for suggestion in &diagnostic.suggestions {
+        suggestion.msg.hash(&mut hasher);
+        for substitution in &suggestion.substitutions {
+            for part in &substitution.parts {
+                part.snippet.hash(&mut hasher);
+            }
+        }
+    }
+    hasher.finish()
+}
 
 #[derive(Copy, PartialEq, Clone, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub enum Level {
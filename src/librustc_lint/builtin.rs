@@ -43,6 +43,7 @@ use std::collections::HashSet;
 
 use syntax::ast;
 use syntax::attr;
+use syntax::errors::Applicability;
 use syntax::feature_gate::{AttributeGate, AttributeType, Stability, deprecated_attributes};
 use syntax_pos::{BytePos, Span, SyntaxContext};
 use syntax::symbol::keywords;
@@ -79,7 +80,11 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for WhileTrue {
                         let msg = "denote infinite loops with `loop { ... }`";
                         let condition_span = cx.tcx.sess.codemap().def_span(e.span);
                         let mut err = cx.struct_span_lint(WHILE_TRUE, condition_span, msg);
-                        err.span_suggestion_short(condition_span, "use `loop`", "loop".to_owned());
+                        err.span_suggestion_short_with_applicability(
+                            condition_span,
+                            "use `loop`",
+                            "loop".to_owned(),
+                            Applicability::MachineApplicable);
                         err.emit();
                     }
                 }
@@ -177,9 +182,11 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NonShorthandFieldPatterns {
                                      &format!("the `{}:` in this pattern is redundant",
                                               ident.node));
                         let subspan = cx.tcx.sess.codemap().span_through_char(fieldpat.span, ':');
-                        err.span_suggestion_short(subspan,
-                                                  "remove this",
-                                                  format!("{}", ident.node));
+                        err.span_suggestion_short_with_applicability(
+                            subspan,
+                            "remove this",
+                            format!("{}", ident.node),
+                            Applicability::MachineApplicable);
                         err.emit();
                     }
                 }
@@ -639,6 +646,97 @@ impl EarlyLintPass for AnonymousParameters {
     }
 }
 
+declare_lint! {
+    pub KEYWORD_IDENTS,
+    Allow,
+    "detects identifiers which are keywords in the 2018 epoch"
+}
+
+/// Checks for identifiers which will be reserved as keywords in the 2018
+/// epoch.
+#[derive(Clone)]
+pub struct KeywordIdents;
+
+impl LintPass for KeywordIdents {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(KEYWORD_IDENTS)
+    }
+}
+
+impl EarlyLintPass for KeywordIdents {
+    fn check_ident(&mut self, cx: &EarlyContext, span: Span, ident: ast::Ident) {
+        match &*ident.name.as_str() {
+            // These are already reported by the parser in the 2018 epoch.
+            "async" | "await" if cx.sess.rust_2018() => return,
+            "async" | "await" | "dyn" | "try" => {}
+            _ => return,
+        }
+        // Raw identifiers, e.g. `r#async`, are already compatible.
+        match cx.sess.codemap().span_to_snippet(span) {
+            Ok(ref snippet) if snippet.starts_with("r#") => return,
+            _ => {}
+        }
+        let msg = format!("`{}` is a keyword in the 2018 epoch", ident);
+        let mut err = cx.struct_span_lint(KEYWORD_IDENTS, span, &msg);
+        // Raw identifiers are still unstable, so only suggest them to crates
+        // which can use them.
+        if cx.sess.features_untracked().raw_identifiers {
+            err.span_suggestion_with_applicability(
+                span,
+                "you can use a raw identifier to stay compatible",
+                format!("r#{}", ident),
+                Applicability::MachineApplicable,
+            );
+        } else {
+            err.note("it has to be renamed before switching to the 2018 epoch");
+        }
+        err.emit();
+    }
+}
+
+declare_lint! {
+    pub ELLIPSIS_INCLUSIVE_RANGE_PATTERNS,
+    Allow,
+    "`...` range patterns are deprecated"
+}
+
+/// Checks for `a...b` range patterns, which should be written `a..=b`.
+#[derive(Clone)]
+pub struct EllipsisInclusiveRangePatterns;
+
+impl LintPass for EllipsisInclusiveRangePatterns {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(ELLIPSIS_INCLUSIVE_RANGE_PATTERNS)
+    }
+}
+
+impl EarlyLintPass for EllipsisInclusiveRangePatterns {
+    fn check_pat(&mut self, cx: &EarlyContext, pat: &ast::Pat) {
+        // The suggestion can only be used if `..=` can be.
+        if !cx.sess.features_untracked().dotdoteq_in_patterns {
+            return;
+        }
+        if let ast::PatKind::Range(ref lo, ref hi,
+                                   ast::RangeEnd::Included(ast::RangeSyntax::DotDotDot)) =
+                pat.node {
+            let codemap = cx.sess.codemap();
+            let sugg = match (codemap.span_to_snippet(lo.span),
+                              codemap.span_to_snippet(hi.span)) {
+                (Ok(lo), Ok(hi)) => format!("{}..={}", lo, hi),
+                _ => return,
+            };
+            let mut err = cx.struct_span_lint(ELLIPSIS_INCLUSIVE_RANGE_PATTERNS,
+                                              pat.span,
+                                              "`...` range patterns are deprecated");
+            err.span_suggestion_with_applicability(pat.span,
+                                                   "use `..=` for an inclusive range",
+                                                   sugg,
+                                                   Applicability::MachineApplicable);
+            err.emit();
+        }
+    }
+}
+
 /// Checks for use of attributes which have been deprecated.
 #[derive(Clone)]
 pub struct DeprecatedAttr {
@@ -673,7 +771,11 @@ impl EarlyLintPass for DeprecatedAttr {
                     let msg = format!("use of deprecated attribute `{}`: {}. See {}",
                                       name, reason, link);
                     let mut err = cx.struct_span_lint(DEPRECATED, attr.span, &msg);
-                    err.span_suggestion_short(attr.span, "remove this attribute", "".to_owned());
+                    err.span_suggestion_short_with_applicability(
+                        attr.span,
+                        "remove this attribute",
+                        "".to_owned(),
+                        Applicability::MachineApplicable);
                     err.emit();
                 }
                 return;
@@ -1351,6 +1453,7 @@ impl EarlyLintPass for IgnoredGenericBounds {
             let spans : Vec<_> = match param {
                 &ast::GenericParam::Lifetime(ref l) => l.bounds.iter().map(|b| b.span).collect(),
                 &ast::GenericParam::Type(ref ty) => ty.bounds.iter().map(|b| b.span()).collect(),
+                &ast::GenericParam::Const(_) => Vec::new(),
             };
             if !spans.is_empty() {
                 cx.span_lint(
@@ -43,7 +43,7 @@ extern crate rustc_mir;
 extern crate syntax_pos;
 
 use rustc::lint;
-use rustc::lint::builtin::BARE_TRAIT_OBJECT;
+use rustc::lint::builtin::BARE_TRAIT_OBJECTS;
 use rustc::session;
 use rustc::util;
 
@@ -98,6 +98,14 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
             )
     }
 
+    macro_rules! add_mir_builtin_with_new {
+        ($sess:ident, $($name:ident),*,) => (
+            {$(
+                store.register_mir_pass($sess, false, box $name::new());
+                )*}
+            )
+    }
+
     macro_rules! add_lint_group {
         ($sess:ident, $name:expr, $($lint:ident),*) => (
             store.register_group($sess, false, $name, vec![$(LintId::of($lint)),*]);
@@ -108,6 +116,8 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                        UnusedParens,
                        UnusedImportBraces,
                        AnonymousParameters,
+                       KeywordIdents,
+                       EllipsisInclusiveRangePatterns,
                        UnusedDocComment,
                        IgnoredGenericBounds,
                        );
@@ -147,6 +157,10 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                           MissingDebugImplementations,
                           );
 
+    add_mir_builtin_with_new!(sess,
+                              LargeAssignments,
+                              );
+
     add_lint_group!(sess,
                     "bad_style",
                     NON_CAMEL_CASE_TYPES,
@@ -181,9 +195,18 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
 
     add_lint_group!(sess,
                     "rust_2018_idioms",
-                    BARE_TRAIT_OBJECT,
+                    BARE_TRAIT_OBJECTS,
                     UNREACHABLE_PUB);
 
+    // The lints which point out code that has to be changed before switching
+    // to the 2018 epoch, with a suggestion when it can be fixed automatically.
+    add_lint_group!(sess,
+                    "rust_2018_compatibility",
+                    KEYWORD_IDENTS,
+                    ELLIPSIS_INCLUSIVE_RANGE_PATTERNS,
+                    BARE_TRAIT_OBJECTS,
+                    TYVAR_BEHIND_RAW_POINTER);
+
     // Guidelines for creating a future incompatibility lint:
     //
     // - Create a lint defaulting to warn as normal, with ideally the same error
@@ -283,11 +306,17 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
             id: LintId::of(TYVAR_BEHIND_RAW_POINTER),
             reference: "issue #46906 <https://github.com/rust-lang/rust/issues/46906>",
             epoch: Some(Epoch::Epoch2018),
+        },
+        FutureIncompatibleInfo {
+            id: LintId::of(KEYWORD_IDENTS),
+            reference: "RFC 2052 <https://github.com/rust-lang/rfcs/pull/2052>",
+            epoch: Some(Epoch::Epoch2018),
         }
         ]);
 
     // Register renamed and removed lints
     store.register_renamed("unknown_features", "unused_features");
+    store.register_renamed("bare_trait_object", "bare_trait_objects");
     store.register_removed("unsigned_negation", "replaced by negate_unsigned feature gate");
     store.register_removed("negate_unsigned", "cast a signed value instead");
     store.register_removed("raw_pointer_derive", "using derive with raw pointers is ok");
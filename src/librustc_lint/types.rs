@@ -11,12 +11,14 @@
 #![allow(non_snake_case)]
 
 use rustc::hir::map as hir_map;
+use rustc::mir;
 use rustc::ty::subst::Substs;
+use rustc::session::Session;
 use rustc::ty::{self, AdtKind, Ty, TyCtxt};
 use rustc::ty::layout::{self, LayoutOf};
 use util::nodemap::FxHashSet;
-use lint::{LateContext, LintContext, LintArray};
-use lint::{LintPass, LateLintPass};
+use lint::{LateContext, MirContext, LintContext, LintArray};
+use lint::{LintPass, LateLintPass, MirLintPass};
 
 use std::cmp;
 use std::{i8, i16, i32, i64, u8, u16, u32, u64, f32, f64};
@@ -46,6 +48,12 @@ declare_lint! {
     "detects enums with widely varying variant sizes"
 }
 
+declare_lint! {
+    LARGE_ASSIGNMENTS,
+    Warn,
+    "detects moves of values larger than allowed by -Z move-size-limit"
+}
+
 #[derive(Copy, Clone)]
 pub struct TypeLimits {
     /// Id of the last visited negated expression
@@ -857,3 +865,62 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for VariantSizeDifferences {
         }
     }
 }
+
+pub struct LargeAssignments {
+    /// Spans already reported in the current body. A single move in the
+    /// source is often lowered to several moves through temporaries, which
+    /// all share its span.
+    reported: FxHashSet<Span>,
+}
+
+impl LargeAssignments {
+    pub fn new() -> LargeAssignments {
+        LargeAssignments { reported: FxHashSet() }
+    }
+}
+
+impl LintPass for LargeAssignments {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(LARGE_ASSIGNMENTS)
+    }
+}
+
+impl<'a, 'tcx> MirLintPass<'a, 'tcx> for LargeAssignments {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.move_size_limit.is_some()
+    }
+
+    fn check_mir(&mut self, _: &MirContext<'a, 'tcx>, _: &'a mir::Mir<'tcx>) {
+        self.reported.clear();
+    }
+
+    fn check_statement(&mut self,
+                       cx: &MirContext<'a, 'tcx>,
+                       statement: &'a mir::Statement<'tcx>,
+                       _: mir::Location) {
+        let limit = match cx.tcx.sess.opts.debugging_opts.move_size_limit {
+            Some(limit) => limit as u64,
+            None => return,
+        };
+        if let mir::StatementKind::Assign(_, mir::Rvalue::Use(mir::Operand::Move(ref place))) =
+            statement.kind
+        {
+            let ty = place.ty(cx.mir, cx.tcx).to_ty(cx.tcx);
+            // The size of a value whose type depends on type parameters is
+            // only known after monomorphization.
+            let size = match cx.layout_of(ty) {
+                Ok(layout) => layout.size.bytes(),
+                Err(_) => return,
+            };
+            let span = statement.source_info.span;
+            if size > limit && self.reported.insert(span) {
+                let msg = format!("moving {} bytes", size);
+                let mut err = cx.struct_span_lint(LARGE_ASSIGNMENTS, span, &msg);
+                err.span_label(span, "value moved from here");
+                err.note(&format!("the current maximum size is {} bytes, which can be \
+                                   changed with `-Z move-size-limit`", limit));
+                err.emit();
+            }
+        }
+    }
+}
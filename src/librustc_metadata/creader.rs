@@ -799,7 +799,8 @@ impl<'a> CrateLoader<'a> {
     }
 
     fn inject_profiler_runtime(&mut self) {
-        if self.sess.opts.debugging_opts.profile {
+        if self.sess.opts.debugging_opts.profile ||
+           self.sess.opts.debugging_opts.instrument_coverage {
             info!("loading profiler");
 
             let symbol = Symbol::intern("profiler_builtins");
@@ -227,6 +227,10 @@ impl CrateMetadata {
         attr::contains_name(&attrs, "no_builtins")
     }
 
+    pub fn is_mir_only_rlib(&self) -> bool {
+        self.root.mir_only_rlib
+    }
+
      pub fn has_copy_closures(&self, sess: &Session) -> bool {
         let attrs = self.get_item_attrs(CRATE_DEF_INDEX, sess);
         attr::contains_feature_attr(&attrs, "copy_closures")
@@ -20,6 +20,7 @@ use rustc::middle::cstore::{CrateStore, DepKind,
                             LoadedMacro, EncodedMetadata, NativeLibraryKind};
 use rustc::middle::exported_symbols::ExportedSymbol;
 use rustc::middle::stability::DeprecationEntry;
+use rustc::mir::BorrowCheckResult;
 use rustc::hir::def;
 use rustc::session::{CrateDisambiguator, Session};
 use rustc::ty::{self, TyCtxt};
@@ -104,6 +105,17 @@ provide! { <'tcx> tcx, def_id, other, cdata,
     generics_of => {
         tcx.alloc_generics(cdata.get_generics(def_id.index, tcx.sess))
     }
+    object_lifetime_defaults => {
+        // Like for local items, leave out the `Self` parameter of traits,
+        // which paths to them don't mention.
+        let generics = tcx.generics_of(def_id);
+        let has_own_self = generics.has_self && generics.parent.is_none();
+        Lrc::new(generics.types
+            .iter()
+            .skip(has_own_self as usize)
+            .map(|def| def.object_lifetime_default)
+            .collect())
+    }
     predicates_of => { cdata.get_predicates(def_id.index, tcx) }
     super_predicates_of => { cdata.get_super_predicates(def_id.index, tcx) }
     trait_def => {
@@ -141,6 +153,16 @@ provide! { <'tcx> tcx, def_id, other, cdata,
     mir_const_qualif => {
         (cdata.mir_const_qualif(def_id.index), Lrc::new(IdxSetBuf::new_empty(0)))
     }
+    mir_borrowck => {
+        // Only the region requirements of closures are needed outside of
+        // the crate which defines them: the mutable uses of locals and
+        // upvars only matter when checking their creator.
+        BorrowCheckResult {
+            closure_requirements: cdata.closure_region_requirements(def_id.index, tcx),
+            used_mut_locals: vec![],
+            used_mut_upvars: vec![],
+        }
+    }
     typeck_tables_of => { cdata.item_body_tables(def_id.index, tcx) }
     fn_sig => { cdata.fn_sig(def_id.index, tcx) }
     inherent_impls => { Lrc::new(cdata.get_inherent_implementations_for_type(def_id.index)) }
@@ -177,6 +199,7 @@ provide! { <'tcx> tcx, def_id, other, cdata,
     panic_strategy => { cdata.panic_strategy() }
     extern_crate => { Lrc::new(cdata.extern_crate.get()) }
     is_no_builtins => { cdata.is_no_builtins(tcx.sess) }
+    is_mir_only_rlib => { cdata.is_mir_only_rlib() }
     impl_defaultness => { cdata.get_impl_defaultness(def_id.index) }
     reachable_non_generics => {
         let reachable_non_generics = tcx
@@ -232,6 +255,9 @@ provide! { <'tcx> tcx, def_id, other, cdata,
         cdata.each_child_of_item(def_id.index, |child| result.push(child), tcx.sess);
         Lrc::new(result)
     }
+    coverage_info => {
+        Lrc::new(cdata.get_coverage_info(def_id.index).unwrap_or_default())
+    }
     defined_lang_items => { Lrc::new(cdata.get_lang_items()) }
     missing_lang_items => { Lrc::new(cdata.get_missing_lang_items()) }
 
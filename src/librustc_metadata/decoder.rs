@@ -454,6 +454,7 @@ impl<'tcx> EntryKind<'tcx> {
             EntryKind::Mod(_) => Def::Mod(did),
             EntryKind::Variant(_) => Def::Variant(did),
             EntryKind::Trait(_) => Def::Trait(did),
+            EntryKind::TraitAlias(_) => Def::TraitAlias(did),
             EntryKind::Enum(..) => Def::Enum(did),
             EntryKind::MacroDef(_) => Def::Macro(did, MacroKind::Bang),
             EntryKind::GlobalAsm => Def::GlobalAsm(did),
@@ -522,16 +523,24 @@ impl<'a, 'tcx> CrateMetadata {
     }
 
     pub fn get_trait_def(&self, item_id: DefIndex, sess: &Session) -> ty::TraitDef {
-        let data = match self.entry(item_id).kind {
-            EntryKind::Trait(data) => data.decode((self, sess)),
+        match self.entry(item_id).kind {
+            EntryKind::Trait(data) => {
+                let data = data.decode((self, sess));
+                ty::TraitDef::new(self.local_def_id(item_id),
+                                  data.unsafety,
+                                  data.paren_sugar,
+                                  data.has_auto_impl,
+                                  self.def_path_table.def_path_hash(item_id))
+            }
+            EntryKind::TraitAlias(_) => {
+                ty::TraitDef::new(self.local_def_id(item_id),
+                                  hir::Unsafety::Normal,
+                                  false,
+                                  false,
+                                  self.def_path_table.def_path_hash(item_id))
+            }
             _ => bug!(),
-        };
-
-        ty::TraitDef::new(self.local_def_id(item_id),
-                          data.unsafety,
-                          data.paren_sugar,
-                          data.has_auto_impl,
-                          self.def_path_table.def_path_hash(item_id))
+        }
     }
 
     fn get_variant(&self, item: &Entry, index: DefIndex) -> ty::VariantDef {
@@ -603,6 +612,9 @@ impl<'a, 'tcx> CrateMetadata {
                                 -> ty::GenericPredicates<'tcx> {
         match self.entry(item_id).kind {
             EntryKind::Trait(data) => data.decode(self).super_predicates.decode((self, tcx)),
+            EntryKind::TraitAlias(data) => {
+                data.decode(self).super_predicates.decode((self, tcx))
+            }
             _ => bug!(),
         }
     }
@@ -680,6 +692,16 @@ impl<'a, 'tcx> CrateMetadata {
             .collect()
     }
 
+    /// The coverage counters of the given function, if it was instrumented
+    /// with `-Z instrument-coverage`.
+    pub fn get_coverage_info(&self, id: DefIndex) -> Option<mir::CoverageInfo> {
+        self.root
+            .coverage_info
+            .decode(self)
+            .find(|&(def_index, _)| def_index == id)
+            .map(|(_, info)| info)
+    }
+
     /// Iterates over each child of the given item.
     pub fn each_child_of_item<F>(&self, id: DefIndex, mut callback: F, sess: &Session)
         where F: FnMut(def::Export)
@@ -1116,6 +1138,23 @@ impl<'a, 'tcx> CrateMetadata {
         sig.decode((self, tcx))
     }
 
+    /// The requirements that the MIR borrow checker found the creator of a
+    /// closure must prove, if the crate was checked with it.
+    pub fn closure_region_requirements(&self,
+                                       id: DefIndex,
+                                       tcx: TyCtxt<'a, 'tcx, 'tcx>)
+                                       -> Option<mir::ClosureRegionRequirements<'tcx>> {
+        match self.entry(id).kind {
+            EntryKind::Closure(data) => {
+                data.decode(self).region_requirements.map(|req| req.decode((self, tcx)))
+            }
+            // The initializers of consts and statics, like the bodies of
+            // functions, prove their own requirements; only closures (which
+            // may be nested in any of these) hand theirs to their creator.
+            _ => None,
+        }
+    }
+
     #[inline]
     pub fn def_key(&self, index: DefIndex) -> DefKey {
         self.def_path_table.def_key(index)
@@ -63,6 +63,9 @@ pub struct EncodeContext<'a, 'tcx: 'a> {
 
     // This is used to speed up Span encoding.
     filemap_cache: Lrc<FileMap>,
+
+    // The number of bytes used by the MIR of all the items, for -Z meta-stats.
+    mir_bytes: usize,
 }
 
 macro_rules! encoder_methods {
@@ -439,6 +442,11 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
             &exported_symbols);
         let exported_symbols_bytes = self.position() - i;
 
+        // Encode the coverage counters of instrumented functions.
+        i = self.position();
+        let coverage_info = self.tracked(IsolatedEncoder::encode_coverage_info, ());
+        let coverage_info_bytes = self.position() - i;
+
         // Encode and index the items.
         i = self.position();
         let items = self.encode_info_for_items();
@@ -462,6 +470,7 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
             panic_strategy: tcx.sess.panic_strategy(),
             has_global_allocator: has_global_allocator,
             has_default_lib_allocator: has_default_lib_allocator,
+            mir_only_rlib: tcx.sess.mir_only_rlib(),
             plugin_registrar_fn: tcx.sess
                 .plugin_registrar_fn
                 .get()
@@ -482,6 +491,7 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
             def_path_table,
             impls,
             exported_symbols,
+            coverage_info,
             index,
         });
 
@@ -502,8 +512,10 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
             println!("         codemap bytes: {}", codemap_bytes);
             println!("            impl bytes: {}", impl_bytes);
             println!("    exp. symbols bytes: {}", exported_symbols_bytes);
+            println!("        coverage bytes: {}", coverage_info_bytes);
             println!("  def-path table bytes: {}", def_path_table_bytes);
             println!("            item bytes: {}", item_bytes);
+            println!("             mir bytes: {}", self.mir_bytes);
             println!("           index bytes: {}", index_bytes);
             println!("            zero bytes: {}", zero_bytes);
             println!("           total bytes: {}", total_bytes);
@@ -872,7 +884,7 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
             let needs_inline = types > 0 || tcx.trans_fn_attrs(def_id).requests_inline();
             let is_const_fn = sig.constness == hir::Constness::Const;
             let ast = if is_const_fn { Some(body) } else { None };
-            let always_encode_mir = self.tcx.sess.opts.debugging_opts.always_encode_mir;
+            let always_encode_mir = self.tcx.sess.always_encode_mir();
             (ast, needs_inline || is_const_fn || always_encode_mir)
         } else {
             (None, false)
@@ -924,7 +936,10 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
         debug!("EntryBuilder::encode_mir({:?})", def_id);
         if self.tcx.mir_keys(LOCAL_CRATE).contains(&def_id) {
             let mir = self.tcx.optimized_mir(def_id);
-            Some(self.lazy(&mir))
+            let start = self.ecx.position();
+            let lazy = self.lazy(&mir);
+            self.ecx.mir_bytes += self.ecx.position() - start;
+            Some(lazy)
         } else {
             None
         }
@@ -1059,8 +1074,14 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
 
                 EntryKind::Trait(self.lazy(&data))
             }
+            hir::ItemTraitAlias(..) => {
+                let data = TraitAliasData {
+                    super_predicates: self.lazy(&tcx.super_predicates_of(def_id)),
+                };
+
+                EntryKind::TraitAlias(self.lazy(&data))
+            }
             hir::ItemExternCrate(_) |
-            hir::ItemTraitAlias(..) |
             hir::ItemUse(..) => bug!("cannot encode info for item {:?}", item),
         };
 
@@ -1130,7 +1151,8 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                 hir::ItemStruct(..) |
                 hir::ItemUnion(..) |
                 hir::ItemImpl(..) |
-                hir::ItemTrait(..) => Some(self.encode_generics(def_id)),
+                hir::ItemTrait(..) |
+                hir::ItemTraitAlias(..) => Some(self.encode_generics(def_id)),
                 _ => None,
             },
             predicates: match item.node {
@@ -1142,7 +1164,8 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                 hir::ItemStruct(..) |
                 hir::ItemUnion(..) |
                 hir::ItemImpl(..) |
-                hir::ItemTrait(..) => Some(self.encode_predicates(def_id)),
+                hir::ItemTrait(..) |
+                hir::ItemTraitAlias(..) => Some(self.encode_predicates(def_id)),
                 _ => None,
             },
 
@@ -1161,7 +1184,7 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
                 hir::ItemFn(_, _, constness, _, ref generics, _) => {
                     let has_tps = generics.ty_params().next().is_some();
                     let needs_inline = has_tps || tcx.trans_fn_attrs(def_id).requests_inline();
-                    let always_encode_mir = self.tcx.sess.opts.debugging_opts.always_encode_mir;
+                    let always_encode_mir = self.tcx.sess.always_encode_mir();
                     if needs_inline || constness == hir::Constness::Const || always_encode_mir {
                         self.encode_optimized_mir(def_id)
                     } else {
@@ -1269,7 +1292,20 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
 
             ty::TyClosure(def_id, substs) => {
                 let sig = substs.closure_sig(def_id, self.tcx);
-                let data = ClosureData { sig: self.lazy(&sig) };
+
+                // The MIR borrowck has already run on every body when
+                // it is enabled, so this only reads its cached result.
+                // Otherwise, there is nothing to record: don't run it
+                // just for the metadata.
+                let region_requirements = if self.tcx.use_mir() {
+                    self.tcx.mir_borrowck(def_id).closure_requirements
+                } else {
+                    None
+                };
+                let data = ClosureData {
+                    sig: self.lazy(&sig),
+                    region_requirements: region_requirements.map(|req| self.lazy(&req)),
+                };
                 EntryKind::Closure(self.lazy(&data))
             }
 
@@ -1386,6 +1422,26 @@ impl<'a, 'b: 'a, 'tcx: 'b> IsolatedEncoder<'a, 'b, 'tcx> {
         self.lazy_seq_ref(&tcx.lang_items().missing)
     }
 
+    /// Encodes the coverage counters of every function instrumented by
+    /// `-Z instrument-coverage`.
+    fn encode_coverage_info(&mut self, _: ()) -> LazySeq<(DefIndex, mir::CoverageInfo)> {
+        let tcx = self.tcx;
+        if !tcx.sess.opts.debugging_opts.instrument_coverage {
+            return LazySeq::empty();
+        }
+        let infos: Vec<_> = tcx.body_owners().filter_map(|def_id| {
+            let node_id = tcx.hir.as_local_node_id(def_id).unwrap();
+            match tcx.hir.body_owner_kind(node_id) {
+                hir::BodyOwnerKind::Fn => {
+                    Some((def_id.index, (*tcx.coverage_info(def_id)).clone()))
+                }
+                hir::BodyOwnerKind::Const |
+                hir::BodyOwnerKind::Static(_) => None,
+            }
+        }).collect();
+        self.lazy_seq(infos)
+    }
+
     /// Encodes an index, mapping each trait to its (local) implementations.
     fn encode_impls(&mut self, _: ()) -> LazySeq<TraitImpls> {
         debug!("IsolatedEncoder::encode_impls()");
@@ -1737,6 +1793,7 @@ pub fn encode_metadata<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             predicate_shorthands: Default::default(),
             filemap_cache: tcx.sess.codemap().files()[0].clone(),
             interpret_alloc_shorthands: Default::default(),
+            mir_bytes: 0,
         };
 
         // Encode the rustc version string in a predictable location.
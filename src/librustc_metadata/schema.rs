@@ -192,6 +192,7 @@ pub struct CrateRoot {
     pub panic_strategy: PanicStrategy,
     pub has_global_allocator: bool,
     pub has_default_lib_allocator: bool,
+    pub mir_only_rlib: bool,
     pub plugin_registrar_fn: Option<DefIndex>,
     pub macro_derive_registrar: Option<DefIndex>,
 
@@ -204,6 +205,7 @@ pub struct CrateRoot {
     pub def_path_table: Lazy<hir::map::definitions::DefPathTable>,
     pub impls: LazySeq<TraitImpls>,
     pub exported_symbols: LazySeq<(ExportedSymbol, SymbolExportLevel)>,
+    pub coverage_info: LazySeq<(DefIndex, mir::CoverageInfo)>,
 
     pub index: LazySeq<index::Index>,
 }
@@ -304,6 +306,7 @@ pub enum EntryKind<'tcx> {
     Closure(Lazy<ClosureData<'tcx>>),
     Generator(Lazy<GeneratorData<'tcx>>),
     Trait(Lazy<TraitData<'tcx>>),
+    TraitAlias(Lazy<TraitAliasData<'tcx>>),
     Impl(Lazy<ImplData<'tcx>>),
     Method(Lazy<MethodData<'tcx>>),
     AssociatedType(AssociatedContainer),
@@ -360,6 +363,9 @@ impl<'a, 'gcx> HashStable<StableHashingContext<'a>> for EntryKind<'gcx> {
             EntryKind::Trait(ref trait_data) => {
                 trait_data.hash_stable(hcx, hasher);
             }
+            EntryKind::TraitAlias(ref trait_alias_data) => {
+                trait_alias_data.hash_stable(hcx, hasher);
+            }
             EntryKind::Impl(ref impl_data) => {
                 impl_data.hash_stable(hcx, hasher);
             }
@@ -437,6 +443,15 @@ impl_stable_hash_for!(struct TraitData<'tcx> {
     super_predicates
 });
 
+#[derive(RustcEncodable, RustcDecodable)]
+pub struct TraitAliasData<'tcx> {
+    pub super_predicates: Lazy<ty::GenericPredicates<'tcx>>,
+}
+
+impl_stable_hash_for!(struct TraitAliasData<'tcx> {
+    super_predicates
+});
+
 #[derive(RustcEncodable, RustcDecodable)]
 pub struct ImplData<'tcx> {
     pub polarity: hir::ImplPolarity,
@@ -513,8 +528,11 @@ impl_stable_hash_for!(struct MethodData<'tcx> { fn_data, container, has_self });
 #[derive(RustcEncodable, RustcDecodable)]
 pub struct ClosureData<'tcx> {
     pub sig: Lazy<ty::PolyFnSig<'tcx>>,
+    /// The requirements found by the MIR borrow checker, which the creator
+    /// of the closure must prove; only present when they were computed.
+    pub region_requirements: Option<Lazy<mir::ClosureRegionRequirements<'tcx>>>,
 }
-impl_stable_hash_for!(struct ClosureData<'tcx> { sig });
+impl_stable_hash_for!(struct ClosureData<'tcx> { sig, region_requirements });
 
 #[derive(RustcEncodable, RustcDecodable)]
 pub struct GeneratorData<'tcx> {
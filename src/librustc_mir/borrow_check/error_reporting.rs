@@ -10,15 +10,19 @@
 
 use syntax_pos::Span;
 use rustc::middle::region::ScopeTree;
-use rustc::mir::{BorrowKind, Field, Local, LocalKind, Location, Operand};
-use rustc::mir::{Place, ProjectionElem, Rvalue, Statement, StatementKind};
+use rustc::hir;
+use rustc::mir::{AggregateKind, BorrowKind, Field, Local, LocalKind, Location, Mutability};
+use rustc::mir::Operand;
+use rustc::mir::{Place, Projection, ProjectionElem, Rvalue, Statement, StatementKind};
 use rustc::ty::{self, RegionKind};
 use rustc_data_structures::indexed_vec::Idx;
 use rustc_data_structures::sync::Lrc;
+use rustc_errors::{Applicability, DiagnosticBuilder};
 
+use super::borrow_set::BorrowData;
 use super::{Context, MirBorrowckCtxt};
 use super::{InitializationRequiringAction, PrefixSet};
-use dataflow::{Borrows, BorrowData, FlowAtLocation, MovingOutStatements};
+use dataflow::{Borrows, FlowAtLocation, MovingOutStatements};
 use dataflow::move_paths::MovePathIndex;
 use util::borrowck_errors::{BorrowckErrors, Origin};
 
@@ -41,15 +45,14 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 Some(name) => format!("`{}`", name),
                 None => "value".to_owned(),
             };
-            self.tcx
-                .cannot_act_on_uninitialized_variable(
-                    span,
-                    desired_action.as_noun(),
-                    &self.describe_place(place).unwrap_or("_".to_owned()),
-                    Origin::Mir,
-                )
-                .span_label(span, format!("use of possibly uninitialized {}", item_msg))
-                .emit();
+            let mut err = self.tcx.cannot_act_on_uninitialized_variable(
+                span,
+                desired_action.as_noun(),
+                &self.describe_place(place).unwrap_or("_".to_owned()),
+                Origin::Mir,
+            );
+            err.span_label(span, format!("use of possibly uninitialized {}", item_msg));
+            err.buffer(&mut self.errors_buffer);
         } else {
             let msg = ""; //FIXME: add "partially " or "collaterally "
 
@@ -114,7 +117,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 }
             }
 
-            err.emit();
+            err.buffer(&mut self.errors_buffer);
         }
     }
 
@@ -144,7 +147,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         );
         err.span_label(span, format!("move out of {} occurs here", value_msg));
         self.explain_why_borrow_contains_point(context, borrow, &mut err);
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 
     pub(super) fn report_use_while_mutably_borrowed(
@@ -165,7 +168,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         self.explain_why_borrow_contains_point(context, borrow, &mut err);
 
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 
     /// Finds the span of arguments of a closure (within `maybe_closure_span`) and its usage of
@@ -362,9 +365,22 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             );
         }
 
-        self.explain_why_borrow_contains_point(context, issued_borrow, &mut err);
+        let explained = self.explain_why_borrow_contains_point(context, issued_borrow, &mut err);
 
-        err.emit();
+        // Without an explicit later use to point at, point at where the
+        // region of the first borrow ends instead.
+        if !explained && end_issued_loan_span.is_none() {
+            if let Some(end_span) = self.find_borrow_region_end(issued_borrow, context.loc) {
+                let msg = match issued_borrow.kind {
+                    BorrowKind::Shared => "immutable borrow ends here",
+                    BorrowKind::Mut { .. } => "mutable borrow ends here",
+                    BorrowKind::Unique => "borrow from closure ends here",
+                };
+                err.span_label(end_span, msg);
+            }
+        }
+
+        err.buffer(&mut self.errors_buffer);
     }
 
     pub(super) fn report_borrowed_value_does_not_live_long_enough(
@@ -399,6 +415,13 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         self.access_place_error_reported
             .insert((root_place.clone(), borrow_span));
 
+        if let Some(name) = self.describe_place(&borrow.borrowed_place) {
+            if let Some((closure_span, capture_span)) = self.find_static_closure_capture(borrow) {
+                self.report_escaping_closure_capture(&name, closure_span, capture_span);
+                return;
+            }
+        }
+
         match (borrow.region, &self.describe_place(&borrow.borrowed_place)) {
             (RegionKind::ReScope(_), Some(name)) => {
                 self.report_scoped_local_value_does_not_live_long_enough(
@@ -457,12 +480,101 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             (RegionKind::ReLateBound(_, _), _)
             | (RegionKind::ReSkolemized(_, _), _)
             | (RegionKind::ReClosureBound(_), _)
+            | (RegionKind::ReCanonical(_), _)
             | (RegionKind::ReErased, _) => {
                 span_bug!(drop_span, "region does not make sense in this context");
             }
         }
     }
 
+    /// If `borrow` is captured by a closure that is required to
+    /// outlive `'static` (e.g., because it is passed to
+    /// `thread::spawn`), returns the span of the closure's header and
+    /// of the captured variable's use within the closure.
+    fn find_static_closure_capture(&self, borrow: &BorrowData<'tcx>) -> Option<(Span, Span)> {
+        let regioncx = self.nonlexical_regioncx.as_ref()?;
+        if !regioncx.region_outlives_static(borrow.region) {
+            return None;
+        }
+
+        let temp = match borrow.assigned_place {
+            Place::Local(temp) => temp,
+            _ => return None,
+        };
+
+        // The captured reference is moved into the closure right after
+        // the borrow is created, in the same block.
+        let location = borrow.reserve_location;
+        let block = &self.mir[location.block];
+        for stmt in &block.statements[location.statement_index + 1..] {
+            let (kind, operands) = match stmt.kind {
+                StatementKind::Assign(_, Rvalue::Aggregate(ref kind, ref operands)) => {
+                    (kind, operands)
+                }
+                _ => continue,
+            };
+            let closure_def_id = match **kind {
+                AggregateKind::Closure(def_id, _) => def_id,
+                _ => continue,
+            };
+            let upvar_index = operands.iter().position(|operand| match *operand {
+                Operand::Copy(Place::Local(l)) | Operand::Move(Place::Local(l)) => l == temp,
+                _ => false,
+            });
+            if let Some(upvar_index) = upvar_index {
+                let node_id = self.tcx.hir.as_local_node_id(closure_def_id)?;
+                let closure_span = match self.tcx.hir.expect_expr(node_id).node {
+                    hir::ExprClosure(_, _, _, fn_decl_span, _) => fn_decl_span,
+                    _ => stmt.source_info.span,
+                };
+                let capture_span = self.tcx.with_freevars(node_id, |freevars| {
+                    freevars.get(upvar_index).map(|freevar| freevar.span)
+                })?;
+                return Some((closure_span, capture_span));
+            }
+        }
+
+        None
+    }
+
+    fn report_escaping_closure_capture(
+        &mut self,
+        name: &String,
+        closure_span: Span,
+        capture_span: Span,
+    ) {
+        let tcx = self.tcx;
+        let path = format!("`{}`", name);
+        let (suggestion, applicability) = match tcx.sess.codemap().span_to_snippet(closure_span) {
+            Ok(string) => (format!("move {}", string), Applicability::MachineApplicable),
+            Err(_) => (format!("move |<args>| <body>"), Applicability::HasPlaceholders),
+        };
+
+        let mut err = tcx.cannot_capture_in_long_lived_closure(
+            closure_span,
+            &path,
+            capture_span,
+            Origin::Mir,
+        );
+        err.span_suggestion_with_applicability(
+            closure_span,
+            &format!(
+                "to force the closure to take ownership of {} \
+                 (and any other referenced variables), \
+                 use the `move` keyword",
+                path
+            ),
+            suggestion,
+            applicability,
+        );
+        err.note(&format!(
+            "if {} is still needed after the closure is created, \
+             consider moving an owned copy of it into the closure instead",
+            path
+        ));
+        err.buffer(&mut self.errors_buffer);
+    }
+
     fn report_scoped_local_value_does_not_live_long_enough(
         &mut self,
         context: Context,
@@ -486,7 +598,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             err.span_label(end, "borrowed value needs to live until here");
         }
         self.explain_why_borrow_contains_point(context, borrow, &mut err);
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 
     fn report_scoped_temporary_value_does_not_live_long_enough(
@@ -512,7 +624,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             err.span_label(end, "temporary value needs to live until here");
         }
         self.explain_why_borrow_contains_point(context, borrow, &mut err);
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 
     fn report_unscoped_local_value_does_not_live_long_enough(
@@ -550,7 +662,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
 
         self.explain_why_borrow_contains_point(context, borrow, &mut err);
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 
     fn report_unscoped_temporary_value_does_not_live_long_enough(
@@ -587,7 +699,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
 
         self.explain_why_borrow_contains_point(context, borrow, &mut err);
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 
     pub(super) fn report_illegal_mutation_of_borrowed(
@@ -606,7 +718,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         self.explain_why_borrow_contains_point(context, loan, &mut err);
 
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
     }
 
     pub(super) fn report_illegal_reassignment(
@@ -648,7 +760,91 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             }
         }
         err.span_label(span, msg);
-        err.emit();
+        err.buffer(&mut self.errors_buffer);
+    }
+
+    /// Suggests how to make `place_err`, the immutable place an error was
+    /// reported for, mutable: by adding `mut` to its binding or, if it is
+    /// reached through a shared borrow of another binding, by borrowing that
+    /// one mutably (and making it `mut` as well if needed).
+    pub(super) fn suggest_mutability(&self, err: &mut DiagnosticBuilder, place_err: &Place<'tcx>) {
+        match *place_err {
+            Place::Local(local) => {
+                if let Some(part) = self.mut_binding_suggestion(local) {
+                    err.span_suggestions_multipart_with_applicability(
+                        "consider making this binding mutable",
+                        vec![vec![part]],
+                        Applicability::MachineApplicable,
+                    );
+                }
+            }
+            Place::Projection(box Projection {
+                base: Place::Local(local),
+                elem: ProjectionElem::Deref,
+            }) => {
+                let decl = &self.mir.local_decls[local];
+                if !decl.is_user_variable {
+                    return;
+                }
+                match decl.ty.sty {
+                    ty::TyRef(_, ty::TypeAndMut { mutbl: hir::MutImmutable, .. }) => {}
+                    _ => return,
+                }
+
+                // Find the `&borrowed` the reference was initialized with.
+                let assignments: Vec<_> = self.mir.basic_blocks()
+                    .iter()
+                    .flat_map(|block| block.statements.iter())
+                    .filter_map(|statement| match statement.kind {
+                        StatementKind::Assign(
+                            Place::Local(assigned),
+                            Rvalue::Ref(_, BorrowKind::Shared, Place::Local(borrowed)),
+                        ) if assigned == local => Some((statement.source_info.span, borrowed)),
+                        _ => None,
+                    })
+                    .collect();
+                if assignments.len() != 1 {
+                    return;
+                }
+                let (borrow_span, borrowed) = assignments[0];
+                let snippet = match self.tcx.sess.codemap().span_to_snippet(borrow_span) {
+                    Ok(snippet) => snippet,
+                    Err(_) => return,
+                };
+                if !snippet.starts_with('&') || snippet.starts_with("&mut") {
+                    return;
+                }
+
+                let mut parts = vec![(borrow_span, format!("&mut {}", snippet[1..].trim_left()))];
+                if self.mir.local_decls[borrowed].mutability == Mutability::Not {
+                    match self.mut_binding_suggestion(borrowed) {
+                        Some(part) => parts.push(part),
+                        None => return,
+                    }
+                }
+                err.span_suggestions_multipart_with_applicability(
+                    "consider borrowing mutably",
+                    vec![parts],
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// The edit turning the plain binding `x` of `local` into `mut x`.
+    fn mut_binding_suggestion(&self, local: Local) -> Option<(Span, String)> {
+        let decl = &self.mir.local_decls[local];
+        let name = match decl.name {
+            Some(name) if decl.is_user_variable => name,
+            _ => return None,
+        };
+        let span = decl.source_info.span;
+        // Leave out bindings like `ref x` or `(x, y)` arguments.
+        match self.tcx.sess.codemap().span_to_snippet(span) {
+            Ok(ref snippet) if *snippet == *name.as_str() => Some((span, format!("mut {}", name))),
+            _ => None,
+        }
     }
 }
 
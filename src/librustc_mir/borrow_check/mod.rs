@@ -15,17 +15,25 @@ use rustc::hir;
 use rustc::hir::def_id::DefId;
 use rustc::hir::map::definitions::DefPathData;
 use rustc::infer::InferCtxt;
+use rustc::session::NllStats;
 use rustc::ty::{self, ParamEnv, TyCtxt};
 use rustc::ty::maps::Providers;
-use rustc::mir::{AssertMessage, BasicBlock, BorrowKind, Location, Place};
+use rustc::util::common::{catch_panic_quietly, print_held_panic, take_held_panic};
+use rustc::util::common::{IceContext, BUG_REPORT_URL};
+use rustc::mir::{AggregateKind, AssertMessage, BasicBlock, BorrowKind, Location, Place};
 use rustc::mir::{Mir, Mutability, Operand, Projection, ProjectionElem, Rvalue};
 use rustc::mir::{Field, Statement, StatementKind, Terminator, TerminatorKind};
-use rustc::mir::ClosureRegionRequirements;
+use rustc::mir::{BorrowCheckResult, Local};
 
 use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::{Diagnostic, DiagnosticBuilder, FatalErrorMarker, Level};
 use rustc_data_structures::indexed_set::IdxSetBuf;
 use rustc_data_structures::indexed_vec::Idx;
+use rustc_data_structures::sync::Lock;
 
+use std::cell::Cell;
+use std::panic;
+use std::time::{Duration, Instant};
 use std::rc::Rc;
 
 use syntax::ast;
@@ -37,7 +45,7 @@ use dataflow::MoveDataParamEnv;
 use dataflow::{DataflowResultsConsumer};
 use dataflow::{MaybeInitializedPlaces, MaybeUninitializedPlaces};
 use dataflow::{EverInitializedPlaces, MovingOutStatements};
-use dataflow::{BorrowData, Borrows, ReserveOrActivateIndex};
+use dataflow::{Borrows, ReserveOrActivateIndex};
 use dataflow::indexes::BorrowIndex;
 use dataflow::move_paths::{IllegalMoveOriginKind, MoveError};
 use dataflow::move_paths::{HasMoveData, LookupResult, MoveData, MovePathIndex};
@@ -45,10 +53,12 @@ use util::borrowck_errors::{BorrowckErrors, Origin};
 
 use std::iter;
 
+use self::borrow_set::{BorrowData, BorrowSet};
 use self::flows::Flows;
 use self::prefixes::PrefixSet;
 use self::MutateMode::{JustWrite, WriteAndRead};
 
+pub(crate) mod borrow_set;
 mod error_reporting;
 mod flows;
 mod prefixes;
@@ -65,35 +75,141 @@ pub fn provide(providers: &mut Providers) {
 fn mir_borrowck<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     def_id: DefId,
-) -> Option<ClosureRegionRequirements<'tcx>> {
+) -> BorrowCheckResult<'tcx> {
     let input_mir = tcx.mir_validated(def_id);
     debug!("run query mir_borrowck: {}", tcx.item_path_str(def_id));
 
     if !tcx.has_attr(def_id, "rustc_mir_borrowck") && !tcx.use_mir() {
-        return None;
+        return BorrowCheckResult {
+            closure_requirements: None,
+            used_mut_locals: vec![],
+            used_mut_upvars: vec![],
+        };
+    }
+
+    // Closures are body owners too, and are counted here once, whether
+    // their creator or the driver runs this query first.
+    let bodies_checked = &tcx.sess.mir_borrowck_times.bodies_checked;
+    bodies_checked.set(bodies_checked.get() + 1);
+
+    // With `-Ztreat-err-as-bug`, a panic is as good as an error, so
+    // don't recover from it.
+    if tcx.sess.opts.debugging_opts.treat_err_as_bug.is_some() {
+        return tcx.infer_ctxt().enter(|infcx| {
+            let input_mir: &Mir = &input_mir.borrow();
+            do_mir_borrowck(&infcx, input_mir, def_id)
+        });
     }
 
-    let opt_closure_req = tcx.infer_ctxt().enter(|infcx| {
-        let input_mir: &Mir = &input_mir.borrow();
-        do_mir_borrowck(&infcx, input_mir, def_id)
+    // If the borrow checker panics on this body, e.g. because of an
+    // internal assertion failing, report an error for the body rather
+    // than bringing down the compiler, so that the rest of the crate
+    // is still checked.
+    //
+    // This only works for panics in the borrow checker itself, e.g. not
+    // for those in the queries it runs: unwinding out of a query poisons
+    // it, and asking for it again, e.g. while checking the next body, would
+    // raise a fatal error rather than compute it. So these panics are not
+    // recovered from. Closures are checked by this query too, and recover
+    // on their own, so a panic in a closure doesn't poison anything.
+    let poisoned_queries = ty::maps::poisoned_query_count();
+    let result = catch_panic_quietly(|| {
+        tcx.infer_ctxt().enter(|infcx| {
+            let input_mir: &Mir = &input_mir.borrow();
+            do_mir_borrowck(&infcx, input_mir, def_id)
+        })
     });
     debug!("mir_borrowck done");
 
-    opt_closure_req
+    match result {
+        Ok(result) => result,
+        Err(payload) => {
+            if payload.is::<FatalErrorMarker>() ||
+                ty::maps::poisoned_query_count() != poisoned_queries
+            {
+                print_held_panic();
+                panic::resume_unwind(payload);
+            }
+
+            // Report the panic the way the driver reports any other, but
+            // as an error for this body only.
+            let mut err = DiagnosticBuilder::new(
+                tcx.sess.diagnostic(),
+                Level::Bug,
+                &format!("unexpected panic while borrow checking `{}`", tcx.item_path_str(def_id)),
+            );
+            err.set_span(tcx.def_span(def_id));
+            if let Some(message) = take_held_panic() {
+                err.note(&message);
+            }
+            err.note("the compiler unexpectedly panicked. this is a bug.");
+            err.note(&format!("we would appreciate a bug report: {}", BUG_REPORT_URL));
+            err.emit();
+            BorrowCheckResult {
+                closure_requirements: None,
+                used_mut_locals: vec![],
+                used_mut_upvars: vec![],
+            }
+        }
+    }
+}
+
+thread_local! {
+    // The time spent borrow checking whole bodies on this thread; see
+    // `record_phase_time`.
+    static BODIES_TIME: Cell<Duration> = Cell::new(Duration::from_secs(0));
+}
+
+/// Like `record_time`, but leaves out the time spent borrow checking
+/// other bodies during the phase. The NLL type check borrow checks the
+/// closures of a body when it needs their region requirements, and the
+/// phases of those are already counted on their own.
+fn record_phase_time<T, F>(accu: &Lock<Duration>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let nested_start = BODIES_TIME.with(|time| time.get());
+    let start = Instant::now();
+    let rv = f();
+    let nested = BODIES_TIME.with(|time| time.get()) - nested_start;
+    let duration = start.elapsed() - nested;
+    let mut accu = accu.lock();
+    *accu = *accu + duration;
+    rv
 }
 
 fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     infcx: &InferCtxt<'a, 'gcx, 'tcx>,
     input_mir: &Mir<'gcx>,
     def_id: DefId,
-) -> Option<ClosureRegionRequirements<'gcx>> {
+) -> BorrowCheckResult<'gcx> {
     let tcx = infcx.tcx;
+    let _ice_context = IceContext::enter(
+        format!("borrow checking `{}` ({:?})", tcx.item_path_str(def_id), def_id),
+    );
     let attributes = tcx.get_attrs(def_id);
     let param_env = tcx.param_env(def_id);
     let id = tcx.hir
         .as_local_node_id(def_id)
         .expect("do_mir_borrowck: non-local DefId");
 
+    let body_start = Instant::now();
+
+    if tcx.has_attr(def_id, "rustc_borrowck_panic") {
+        // Only panic once the closures have been checked, in nested
+        // queries, to test that this doesn't poison them.
+        for data in input_mir.basic_blocks() {
+            for statement in &data.statements {
+                if let StatementKind::Assign(_, Rvalue::Aggregate(ref kind, _)) = statement.kind {
+                    if let AggregateKind::Closure(closure_def_id, _) = **kind {
+                        ty::maps::queries::mir_borrowck::ensure(tcx, closure_def_id);
+                    }
+                }
+            }
+        }
+        panic!("`#[rustc_borrowck_panic]` on `{}`", tcx.item_path_str(def_id));
+    }
+
     // Make our own copy of the MIR. This copy will be modified (in place) to
     // contain non-lexical lifetimes. It will have a lifetime tied
     // to the inference context.
@@ -104,10 +220,16 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
         let mir = &mut mir;
 
         // Replace all regions with fresh inference variables.
-        Some(nll::replace_regions_in_mir(infcx, def_id, param_env, mir))
+        Some(record_phase_time(&tcx.sess.mir_borrowck_times.renumber, || {
+            nll::replace_regions_in_mir(infcx, def_id, param_env, mir)
+        }))
     };
     let mir = &mir;
 
+    // The errors are buffered, and emitted sorted by span once the whole
+    // body has been checked.
+    let mut errors_buffer = vec![];
+
     let move_data: MoveData<'tcx> = match MoveData::gather_moves(mir, tcx) {
         Ok(move_data) => move_data,
         Err((move_data, move_errors)) => {
@@ -135,7 +257,7 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
                         tcx.cannot_move_out_of_interior_noncopy(span, ty, is_index, origin)
                     }
                 };
-                err.emit();
+                err.buffer(&mut errors_buffer);
             }
             move_data
         }
@@ -189,7 +311,10 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     ));
 
     // If we are in non-lexical mode, compute the non-lexical lifetimes.
+    let nll_stats = &tcx.sess.nll_stats;
+    let err_count_before = tcx.sess.err_count();
     let (opt_regioncx, opt_closure_req) = if let Some(free_regions) = free_regions {
+        NllStats::add(&nll_stats.bodies_checked, 1);
         let (regioncx, opt_closure_req) = nll::compute_regions(
             infcx,
             def_id,
@@ -198,6 +323,7 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
             param_env,
             &mut flow_inits,
             &mdpe.move_data,
+            &mut errors_buffer,
         );
         (Some(Rc::new(regioncx)), opt_closure_req)
     } else {
@@ -206,13 +332,18 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     };
     let flow_inits = flow_inits; // remove mut
 
+    let borrow_set = Rc::new(BorrowSet::build(tcx, mir, opt_regioncx.as_ref().map(|r| &**r)));
+    if opt_regioncx.is_some() {
+        nll::dump_borrow_set(infcx, def_id, &borrow_set);
+    }
+
     let flow_borrows = FlowAtLocation::new(do_dataflow(
         tcx,
         mir,
         id,
         &attributes,
         &dead_unwinds,
-        Borrows::new(tcx, mir, opt_regioncx.clone(), def_id, body_id),
+        Borrows::new(tcx, mir, opt_regioncx.clone(), def_id, body_id, &borrow_set),
         |rs, i| {
             DebugFormatted::new(&(i.kind(), rs.location(i.borrow_index())))
         }
@@ -241,6 +372,10 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
         reservation_error_reported: FxHashSet(),
         nonlexical_regioncx: opt_regioncx,
         nonlexical_cause_info: None,
+        borrow_set,
+        errors_buffer,
+        used_mut: FxHashSet(),
+        used_mut_upvars: FxHashSet(),
     };
 
     let mut state = Flows::new(
@@ -253,7 +388,36 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
 
     mbcx.analyze_results(&mut state); // entry point for DataflowResultsConsumer
 
-    opt_closure_req
+    let mut errors_buffer = mbcx.errors_buffer;
+    record_phase_time(&tcx.sess.mir_borrowck_times.error_reporting, || {
+        errors_buffer.sort_by_key(|diag| diag.span.primary_span());
+        for diag in errors_buffer {
+            DiagnosticBuilder::new_diagnostic(tcx.sess.diagnostic(), diag).emit();
+        }
+    });
+
+    if tcx.nll() {
+        if tcx.sess.err_count() > err_count_before {
+            NllStats::add(&nll_stats.bodies_with_errors, 1);
+        }
+        if let Some(closure_req) = &opt_closure_req {
+            let num_requirements = closure_req.outlives_requirements.len() as u64;
+            NllStats::add(&nll_stats.closure_requirements, num_requirements);
+        }
+    }
+
+    let mut used_mut_locals: Vec<_> = mbcx.used_mut.into_iter().collect();
+    used_mut_locals.sort();
+    let mut used_mut_upvars: Vec<_> = mbcx.used_mut_upvars.into_iter().collect();
+    used_mut_upvars.sort();
+
+    BODIES_TIME.with(|time| time.set(time.get() + body_start.elapsed()));
+
+    BorrowCheckResult {
+        closure_requirements: opt_closure_req,
+        used_mut_locals,
+        used_mut_upvars,
+    }
 }
 
 #[allow(dead_code)]
@@ -290,6 +454,16 @@ pub struct MirBorrowckCtxt<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
     /// find out which CFG points are contained in each borrow region.
     nonlexical_regioncx: Option<Rc<RegionInferenceContext<'tcx>>>,
     nonlexical_cause_info: Option<RegionCausalInfo>,
+    /// The set of borrows extracted from the MIR
+    borrow_set: Rc<BorrowSet<'tcx>>,
+    /// The errors reported so far, emitted at the end of the analysis.
+    errors_buffer: Vec<Diagnostic>,
+    /// The user variables which were reassigned, mutated through a
+    /// projection or mutably borrowed.
+    used_mut: FxHashSet<Local>,
+    /// The by-value upvars of a closure which were reassigned, mutated
+    /// through a projection or mutably borrowed.
+    used_mut_upvars: FxHashSet<Field>,
 }
 
 // Check that:
@@ -392,9 +566,12 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
                 // ignored when consuming results (update to
                 // flow_state already handled).
             }
-            StatementKind::Nop | StatementKind::Validate(..) | StatementKind::StorageLive(..) => {
-                // `Nop`, `Validate`, and `StorageLive` are irrelevant
-                // to borrow check.
+            StatementKind::Nop |
+            StatementKind::Validate(..) |
+            StatementKind::StorageLive(..) |
+            StatementKind::Coverage { .. } => {
+                // `Nop`, `Validate`, `StorageLive` and `Coverage` are
+                // irrelevant to borrow check.
             }
 
             StatementKind::StorageDead(local) => {
@@ -513,9 +690,22 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
                             flow_state,
                         );
                     }
+                    AssertMessage::MisalignedPointerDereference { ref required, ref found } => {
+                        self.consume_operand(
+                            ContextKind::Assert.new(loc),
+                            (required, span),
+                            flow_state,
+                        );
+                        self.consume_operand(
+                            ContextKind::Assert.new(loc),
+                            (found, span),
+                            flow_state,
+                        );
+                    }
                     AssertMessage::Math(_ /*const_math_err*/) => {}
                     AssertMessage::GeneratorResumedAfterReturn => {}
                     AssertMessage::GeneratorResumedAfterPanic => {}
+                    AssertMessage::NullPointerDereference => {}
                 }
             }
 
@@ -528,11 +718,10 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
 
                 if self.movable_generator {
                     // Look for any active borrows to locals
-                    let domain = flow_state.borrows.operator();
-                    let data = domain.borrows();
+                    let borrow_set = self.borrow_set.clone();
                     flow_state.borrows.with_elems_outgoing(|borrows| {
                         for i in borrows {
-                            let borrow = &data[i.borrow_index()];
+                            let borrow = &borrow_set.borrows[i.borrow_index()];
                             self.check_for_local_borrow(borrow, span);
                         }
                     });
@@ -544,11 +733,10 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
                 // Often, the storage will already have been killed by an explicit
                 // StorageDead, but we don't always emit those (notably on unwind paths),
                 // so this "extra check" serves as a kind of backup.
-                let domain = flow_state.borrows.operator();
-                let data = domain.borrows();
+                let borrow_set = self.borrow_set.clone();
                 flow_state.borrows.with_elems_outgoing(|borrows| {
                     for i in borrows {
-                        let borrow = &data[i.borrow_index()];
+                        let borrow = &borrow_set.borrows[i.borrow_index()];
                         let context = ContextKind::StorageDead.new(loc);
                         self.check_for_invalidation_at_exit(context, borrow, span, flow_state);
                     }
@@ -992,6 +1180,87 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             // check for reassignments to immutable local variables
             self.check_if_reassignment_to_immutable_state(context, place_span, flow_state);
         }
+
+        self.record_mutation(place_span.0, flow_state);
+    }
+
+    /// Records the user variable written by a mutation of `place`, unless
+    /// it is the initialization of the variable, which doesn't require it
+    /// to be `mut`.
+    fn record_mutation(&mut self, place: &Place<'tcx>, flow_state: &Flows<'cx, 'gcx, 'tcx>) {
+        if let Some(field) = self.by_value_upvar_base(place) {
+            self.used_mut_upvars.insert(field);
+            return;
+        }
+        let local = match self.user_variable_base(place) {
+            Some(local) => local,
+            None => return,
+        };
+        if let Place::Local(_) = *place {
+            let reassigned = flow_state.ever_inits.elems_incoming().any(|i| {
+                let init = self.move_data.inits[i];
+                let init_place = &self.move_data.move_paths[init.path].place;
+                self.places_conflict(&init_place, place, Deep)
+            });
+            if !reassigned {
+                return;
+            }
+        }
+        self.used_mut.insert(local);
+    }
+
+    /// The user variable `place` is part of, if it isn't behind a
+    /// reference or raw pointer.
+    fn user_variable_base(&self, place: &Place<'tcx>) -> Option<Local> {
+        match *place {
+            Place::Local(local) if self.mir.local_decls[local].is_user_variable => Some(local),
+            Place::Local(_) | Place::Static(_) => None,
+            Place::Projection(ref proj) => match proj.elem {
+                ProjectionElem::Deref => None,
+                _ => self.user_variable_base(&proj.base),
+            },
+        }
+    }
+
+    /// The by-value upvar `place` is part of, if it isn't behind a
+    /// reference or raw pointer. Mutating such an upvar requires the
+    /// captured variable of the creator to be `mut`.
+    fn by_value_upvar_base(&self, place: &Place<'tcx>) -> Option<Field> {
+        if let Some(field) = self.is_upvar_field_projection(place) {
+            if self.mir.upvar_decls[field.index()].by_ref {
+                return None;
+            }
+            return Some(field);
+        }
+        match *place {
+            Place::Local(_) | Place::Static(_) => None,
+            Place::Projection(ref proj) => match proj.elem {
+                ProjectionElem::Deref => None,
+                _ => self.by_value_upvar_base(&proj.base),
+            },
+        }
+    }
+
+    /// Records the user variables and by-value upvars of this body that are
+    /// captured by value by a closure which mutates them, using the borrowck
+    /// result of the closure.
+    fn record_closure_captures(&mut self, closure_def_id: DefId, operands: &[Operand<'tcx>]) {
+        if !closure_def_id.is_local() {
+            return;
+        }
+        let used_mut_upvars = self.tcx.mir_borrowck(closure_def_id).used_mut_upvars;
+        for field in used_mut_upvars {
+            match operands[field.index()] {
+                Operand::Move(ref place) | Operand::Copy(ref place) => {
+                    if let Some(field) = self.by_value_upvar_base(place) {
+                        self.used_mut_upvars.insert(field);
+                    } else if let Some(local) = self.user_variable_base(place) {
+                        self.used_mut.insert(local);
+                    }
+                }
+                Operand::Constant(..) => {}
+            }
+        }
     }
 
     fn consume_rvalue(
@@ -1073,9 +1342,14 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 // `NullOp::Box`?
             }
 
-            Rvalue::Aggregate(ref _aggregate_kind, ref operands) => for operand in operands {
-                self.consume_operand(context, (operand, span), flow_state);
-            },
+            Rvalue::Aggregate(ref aggregate_kind, ref operands) => {
+                for operand in operands {
+                    self.consume_operand(context, (operand, span), flow_state);
+                }
+                if let AggregateKind::Closure(def_id, _) = **aggregate_kind {
+                    self.record_closure_captures(def_id, operands);
+                }
+            }
         }
     }
 
@@ -1219,13 +1493,12 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         debug!("check_for_local_borrow({:?})", borrow);
 
         if borrow_of_local_data(&borrow.borrowed_place) {
-            self.tcx
-                .cannot_borrow_across_generator_yield(
-                    self.retrieve_borrow_span(borrow),
-                    yield_span,
-                    Origin::Mir,
-                )
-                .emit();
+            let err = self.tcx.cannot_borrow_across_generator_yield(
+                self.retrieve_borrow_span(borrow),
+                yield_span,
+                Origin::Mir,
+            );
+            err.buffer(&mut self.errors_buffer);
         }
     }
 
@@ -1242,12 +1515,11 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         // Two-phase borrow support: For each activation that is newly
         // generated at this statement, check if it interferes with
         // another borrow.
-        let domain = flow_state.borrows.operator();
-        let data = domain.borrows();
+        let borrow_set = self.borrow_set.clone();
         flow_state.borrows.each_gen_bit(|gen| {
             if gen.is_activation() {
                 let borrow_index = gen.borrow_index();
-                let borrow = &data[borrow_index];
+                let borrow = &borrow_set.borrows[borrow_index];
                 // currently the flow analysis registers
                 // activations for both mutable and immutable
                 // borrows. So make sure we are talking about a
@@ -1506,7 +1778,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
     ///
     /// Returns true if an error is reported, false otherwise.
     fn check_access_permissions(
-        &self,
+        &mut self,
         (place, span): (&Place<'tcx>, Span),
         kind: ReadOrWrite,
         is_local_mutation_allowed: LocalMutationIsAllowed,
@@ -1543,8 +1815,13 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                         err.note(&format!("Value not mutable causing this error: `{}`", name));
                     }
                 }
+                self.suggest_mutability(&mut err, place_err);
 
-                err.emit();
+                err.buffer(&mut self.errors_buffer);
+            } else if let Some(field) = self.by_value_upvar_base(place) {
+                self.used_mut_upvars.insert(field);
+            } else if let Some(local) = self.user_variable_base(place) {
+                self.used_mut.insert(local);
             },
             Reservation(WriteKind::Mutate) | Write(WriteKind::Mutate) => {
                 if let Err(place_err) = self.is_mutable(place, is_local_mutation_allowed) {
@@ -1563,8 +1840,9 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                             err.note(&format!("Value not mutable causing this error: `{}`", name));
                         }
                     }
+                    self.suggest_mutability(&mut err, place_err);
 
-                    err.emit();
+                    err.buffer(&mut self.errors_buffer);
                 }
             }
             Reservation(WriteKind::Move)
@@ -2125,13 +2403,13 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         // FIXME: analogous code in check_loans first maps `place` to
         // its base_path.
 
-        let data = flow_state.borrows.operator().borrows();
+        let borrow_set = self.borrow_set.clone();
 
         // check for loan restricting path P being used. Accounts for
         // borrows of P, P.a.b, etc.
         let mut elems_incoming = flow_state.borrows.elems_incoming();
         while let Some(i) = elems_incoming.next() {
-            let borrowed = &data[i.borrow_index()];
+            let borrowed = &borrow_set.borrows[i.borrow_index()];
 
             if self.places_conflict(&borrowed.borrowed_place, place, access) {
                 debug!(
@@ -10,11 +10,12 @@
 
 use borrow_check::{Context, MirBorrowckCtxt};
 use borrow_check::nll::region_infer::{Cause, RegionInferenceContext};
-use dataflow::BorrowData;
-use rustc::mir::{Local, Location, Mir};
+use borrow_check::borrow_set::BorrowData;
+use rustc::mir::{Local, Location, Mir, TerminatorKind};
 use rustc::mir::visit::{MirVisitable, PlaceContext, Visitor};
 use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::DiagnosticBuilder;
+use syntax_pos::Span;
 use util::liveness::{self, DefUse, LivenessMode};
 
 impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
@@ -23,12 +24,14 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
     /// [described in the NLL RFC][d].
     ///
     /// [d]: https://rust-lang.github.io/rfcs/2094-nll.html#leveraging-intuition-framing-errors-in-terms-of-points
+    ///
+    /// Returns `false` if no explanation could be found.
     pub(in borrow_check) fn explain_why_borrow_contains_point(
         &mut self,
         context: Context,
         borrow: &BorrowData<'tcx>,
         err: &mut DiagnosticBuilder<'_>,
-    ) {
+    ) -> bool {
         if let Some(regioncx) = &self.nonlexical_regioncx {
             let mir = self.mir;
 
@@ -46,6 +49,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                                     mir.source_info(p).span,
                                     format!("borrow later used here"),
                                 );
+                                return true;
                             }
 
                             None => {
@@ -69,6 +73,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                                         local_name
                                     ),
                                 );
+                                return true;
                             }
 
                             None => {
@@ -88,6 +93,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                                 region,
                                 "...",
                             );
+                            return true;
                         }
                     }
 
@@ -95,6 +101,75 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 }
             }
         }
+
+        false
+    }
+
+    /// Returns the span of the point at which the region of `borrow` ends,
+    /// searching forward from `start_point`. If the region can be left at
+    /// more than one point, the one that comes last in the source is used.
+    pub(in borrow_check) fn find_borrow_region_end(
+        &self,
+        borrow: &BorrowData<'tcx>,
+        start_point: Location,
+    ) -> Option<Span> {
+        let regioncx = self.nonlexical_regioncx.as_ref()?;
+        let mir = self.mir;
+
+        let mut stack = vec![start_point];
+        let mut visited = FxHashSet();
+        let mut end_span: Option<Span> = None;
+        while let Some(p) = stack.pop() {
+            if !visited.insert(p) {
+                continue;
+            }
+
+            let block_data = &mir[p.block];
+            let successors = if p.statement_index < block_data.statements.len() {
+                vec![Location {
+                    statement_index: p.statement_index + 1,
+                    ..p
+                }]
+            } else {
+                block_data
+                    .terminator()
+                    .successors()
+                    .iter()
+                    .filter(|&&basic_block| !mir[basic_block].is_cleanup)
+                    .map(|&basic_block| Location {
+                        statement_index: 0,
+                        block: basic_block,
+                    })
+                    .collect()
+            };
+
+            let mut leaves_region = successors.is_empty();
+            for successor in successors {
+                if regioncx.region_contains_point(borrow.region, successor) {
+                    stack.push(successor);
+                } else {
+                    leaves_region = true;
+                }
+            }
+
+            if leaves_region {
+                // The span of the `return` is empty; point at the end of
+                // the body instead, as the AST borrowck does.
+                let span = match block_data.terminator().kind {
+                    TerminatorKind::Return
+                        if p.statement_index == block_data.statements.len() =>
+                    {
+                        self.tcx.sess.codemap().end_point(mir.span)
+                    }
+                    _ => mir.source_info(p).span,
+                };
+                if end_span.map_or(true, |end_span| end_span.hi() < span.hi()) {
+                    end_span = Some(span);
+                }
+            }
+        }
+
+        end_span
     }
 }
 
@@ -8,14 +8,18 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use borrow_check::borrow_set::BorrowSet;
 use rustc::hir::def_id::DefId;
 use rustc::mir::{ClosureRegionRequirements, ClosureOutlivesSubject, Mir};
 use rustc::infer::InferCtxt;
 use rustc::ty::{self, RegionKind, RegionVid};
+use rustc::util::common::IceContext;
+use borrow_check::record_phase_time;
 use rustc::util::nodemap::FxHashMap;
+use rustc_errors::Diagnostic;
 use std::collections::BTreeSet;
 use std::fmt::Debug;
-use std::io;
+use std::io::{self, Write};
 use transform::MirSource;
 use util::liveness::{LivenessResults, LocalSet};
 use dataflow::FlowAtLocation;
@@ -73,22 +77,32 @@ pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
     param_env: ty::ParamEnv<'gcx>,
     flow_inits: &mut FlowAtLocation<MaybeInitializedPlaces<'cx, 'gcx, 'tcx>>,
     move_data: &MoveData<'tcx>,
+    errors_buffer: &mut Vec<Diagnostic>,
 ) -> (
     RegionInferenceContext<'tcx>,
     Option<ClosureRegionRequirements<'gcx>>,
 ) {
+    let times = &infcx.tcx.sess.mir_borrowck_times;
+
     // Run the MIR type-checker.
-    let liveness = &LivenessResults::compute(mir);
-    let constraint_sets = &type_check::type_check(
-        infcx,
-        param_env,
-        mir,
-        def_id,
-        &universal_regions,
-        &liveness,
-        flow_inits,
-        move_data,
-    );
+    let liveness = &record_phase_time(&times.liveness, || {
+        let _ice_context = IceContext::enter(String::from("computing liveness"));
+        LivenessResults::compute(mir)
+    });
+    let constraint_sets = &record_phase_time(&times.type_check, || {
+        let _ice_context = IceContext::enter(String::from("type checking MIR"));
+        type_check::type_check(
+            infcx,
+            param_env,
+            mir,
+            def_id,
+            &universal_regions,
+            &liveness,
+            flow_inits,
+            move_data,
+            errors_buffer,
+        )
+    });
 
     // Create the region inference context, taking ownership of the region inference
     // data that was contained in `infcx`.
@@ -196,7 +210,13 @@ fn dump_mir_results<'a, 'gcx, 'tcx>(
                 )?;
             }
 
-            PassWhere::AfterLocation(_) | PassWhere::AfterCFG => {}
+            // After the CFG, map the region variables it mentions back
+            // to where they came from.
+            PassWhere::AfterCFG => {
+                regioncx.dump_mir_footer(out)?;
+            }
+
+            PassWhere::AfterLocation(_) => {}
         }
         Ok(())
     });
@@ -209,6 +229,37 @@ fn dump_mir_results<'a, 'gcx, 'tcx>(
     };
 }
 
+/// Dumps the borrows of the MIR, as found once the regions are inferred, to
+/// the `nll-borrows` file of `-Z dump-mir=nll`: one line per borrow, with
+/// its region, its location, and where it is activated.
+pub(in borrow_check) fn dump_borrow_set<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    mir_def_id: DefId,
+    borrow_set: &BorrowSet<'tcx>,
+) {
+    let source = MirSource::item(mir_def_id);
+    if !mir_util::dump_enabled(infcx.tcx, "nll", source) {
+        return;
+    }
+
+    let _: io::Result<()> = do catch {
+        let mut file =
+            pretty::create_dump_file(infcx.tcx, "borrows", None, "nll", &0, source)?;
+        let activations: FxHashMap<_, _> = borrow_set.activation_map
+            .iter()
+            .map(|(&location, &index)| (index, location))
+            .collect();
+        for (index, borrow) in borrow_set.borrows.iter_enumerated() {
+            writeln!(file, "{:?}: {} at {:?}, activated at {:?}",
+                     index,
+                     borrow,
+                     borrow.reserve_location,
+                     activations.get(&index).unwrap_or(&borrow.reserve_location))?;
+        }
+        Ok(())
+    };
+}
+
 fn dump_annotation<'a, 'gcx, 'tcx>(
     infcx: &InferCtxt<'a, 'gcx, 'tcx>,
     mir: &Mir<'tcx>,
@@ -227,7 +278,13 @@ fn dump_annotation<'a, 'gcx, 'tcx>(
     // for verifying that the compiler is behaving as expected.  These
     // warnings focus on the closure region requirements -- for
     // viewing the intraprocedural state, the -Zdump-mir output is
-    // better.
+    // better. With `#[rustc_regions(origins)]`, we also note where
+    // each region variable came from.
+    let annotate_origins = tcx.get_attrs(base_def_id)
+        .iter()
+        .filter(|attr| attr.check_name("rustc_regions"))
+        .filter_map(|attr| attr.meta_item_list())
+        .any(|list| list.iter().any(|item| item.check_name("origins")));
 
     if let Some(closure_region_requirements) = closure_region_requirements {
         let mut err = tcx.sess
@@ -235,6 +292,9 @@ fn dump_annotation<'a, 'gcx, 'tcx>(
             .span_note_diag(mir.span, "External requirements");
 
         regioncx.annotate(&mut err);
+        if annotate_origins {
+            regioncx.annotate_region_origins(&mut err);
+        }
 
         err.note(&format!(
             "number of external vids: {}",
@@ -254,6 +314,9 @@ fn dump_annotation<'a, 'gcx, 'tcx>(
             .diagnostic()
             .span_note_diag(mir.span, "No external requirements");
         regioncx.annotate(&mut err);
+        if annotate_origins {
+            regioncx.annotate_region_origins(&mut err);
+        }
         err.emit();
     }
 }
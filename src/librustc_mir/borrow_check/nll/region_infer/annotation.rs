@@ -18,6 +18,7 @@
 use borrow_check::nll::region_infer::RegionInferenceContext;
 use borrow_check::nll::universal_regions::DefiningTy;
 use rustc_errors::DiagnosticBuilder;
+use std::io;
 
 impl<'gcx, 'tcx> RegionInferenceContext<'tcx> {
     /// Write out our state into the `.mir` files.
@@ -54,4 +55,13 @@ impl<'gcx, 'tcx> RegionInferenceContext<'tcx> {
             }
         }
     }
+
+    /// Add a note with the origin of each region variable, for
+    /// `#[rustc_regions(origins)]`.
+    pub(crate) fn annotate_region_origins(&self, err: &mut DiagnosticBuilder<'_>) {
+        let _: io::Result<()> = self.for_each_region_origin(&mut |msg| {
+            err.note(&format!("region origin: {}", msg));
+            Ok(())
+        });
+    }
 }
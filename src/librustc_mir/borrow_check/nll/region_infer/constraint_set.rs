@@ -0,0 +1,49 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rustc::util::nodemap::FxHashMap;
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use std::ops::Deref;
+
+use super::Constraint;
+
+newtype_index!(ConstraintIndex { DEBUG_FORMAT = "ConstraintIndex({})" });
+
+/// The outlives constraints accumulated during type checking. Each
+/// distinct constraint is stored once, and is referred to elsewhere
+/// by its `ConstraintIndex`.
+#[derive(Default)]
+pub(super) struct ConstraintSet {
+    constraints: IndexVec<ConstraintIndex, Constraint>,
+
+    /// Maps each constraint back to its index, so that duplicates can
+    /// be detected on insertion.
+    indices: FxHashMap<Constraint, ConstraintIndex>,
+}
+
+impl ConstraintSet {
+    /// Adds `constraint` to the set, returning its index. If an
+    /// identical constraint was already added, returns the existing
+    /// index instead.
+    pub(super) fn push(&mut self, constraint: Constraint) -> ConstraintIndex {
+        let constraints = &mut self.constraints;
+        *self.indices
+            .entry(constraint)
+            .or_insert_with(|| constraints.push(constraint))
+    }
+}
+
+impl Deref for ConstraintSet {
+    type Target = IndexVec<ConstraintIndex, Constraint>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.constraints
+    }
+}
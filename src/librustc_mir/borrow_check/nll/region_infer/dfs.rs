@@ -18,7 +18,7 @@ use borrow_check::nll::region_infer::values::{RegionElementIndex, RegionValueEle
 use syntax::codemap::Span;
 use rustc::mir::{Location, Mir};
 use rustc::ty::RegionVid;
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 
 impl<'tcx> RegionInferenceContext<'tcx> {
     /// Function used to satisfy or test a `R1: R2 @ P`
@@ -43,8 +43,8 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         let mut stack = vec![];
         let mut visited = FxHashSet();
 
-        stack.push(op.start_point());
-        while let Some(p) = stack.pop() {
+        stack.push((op.start_point(), None));
+        while let Some((p, predecessor)) = stack.pop() {
             let point_index = self.elements.index(p);
 
             if !op.source_region_contains(point_index) {
@@ -57,6 +57,8 @@ impl<'tcx> RegionInferenceContext<'tcx> {
                 continue;
             }
 
+            op.visit_point(p, predecessor);
+
             let new = op.add_to_target_region(point_index)?;
             changed |= new;
 
@@ -65,17 +67,19 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             let start_stack_len = stack.len();
 
             if p.statement_index < block_data.statements.len() {
-                stack.push(Location {
+                let successor = Location {
                     statement_index: p.statement_index + 1,
                     ..p
-                });
+                };
+                stack.push((successor, Some(p)));
             } else {
                 stack.extend(block_data.terminator().successors().iter().map(
                     |&basic_block| {
-                        Location {
+                        let successor = Location {
                             statement_index: 0,
                             block: basic_block,
-                        }
+                        };
+                        (successor, Some(p))
                     },
                 ));
             }
@@ -104,6 +108,11 @@ pub(super) trait DfsOp {
     /// Returns true if the source region contains the given point.
     fn source_region_contains(&mut self, point_index: RegionElementIndex) -> bool;
 
+    /// Invoked when the walk first reaches `point`, coming from
+    /// `predecessor` (or `None` for the start point), before it is
+    /// added to the target region.
+    fn visit_point(&mut self, _point: Location, _predecessor: Option<Location>) {}
+
     /// Adds the given point to the target region, returning true if
     /// something has changed. Returns `Err` if we should abort the
     /// walk early.
@@ -170,6 +179,10 @@ impl<'v> DfsOp for CopyFromSourceToTarget<'v> {
 /// condition. Similarly, if we reach the end of the graph and find
 /// that R1 contains some universal region that R2 does not contain,
 /// we abort the walk early.
+///
+/// If `predecessors` is `Some`, the op remembers how the walk reached
+/// each point, so that a failure can report the CFG path from `P` to
+/// the point that is missing from R1.
 pub(super) struct TestTargetOutlivesSource<'v, 'tcx: 'v> {
     pub source_region: RegionVid,
     pub target_region: RegionVid,
@@ -177,11 +190,37 @@ pub(super) struct TestTargetOutlivesSource<'v, 'tcx: 'v> {
     pub universal_regions: &'v UniversalRegions<'tcx>,
     pub inferred_values: &'v RegionValues,
     pub constraint_point: Location,
+    pub predecessors: Option<FxHashMap<Location, Location>>,
+}
+
+/// Why a `R1: R2 @ P` test failed.
+pub(super) struct OutlivesFailure {
+    /// The element of R2 that was not found within R1.
+    pub elem: RegionElementIndex,
+
+    /// The CFG path from `P` to the point which is missing from R1, if
+    /// paths were being recorded and `elem` is a point.
+    pub path: Option<Vec<Location>>,
+}
+
+impl<'v, 'tcx> TestTargetOutlivesSource<'v, 'tcx> {
+    /// The path along which the walk reached `point`, starting with
+    /// the constraint point, if paths are being recorded.
+    fn path_to(&self, point: Location) -> Option<Vec<Location>> {
+        let predecessors = self.predecessors.as_ref()?;
+        let mut path = vec![point];
+        let mut current = point;
+        while let Some(&predecessor) = predecessors.get(&current) {
+            path.push(predecessor);
+            current = predecessor;
+        }
+        path.reverse();
+        Some(path)
+    }
 }
 
 impl<'v, 'tcx> DfsOp for TestTargetOutlivesSource<'v, 'tcx> {
-    /// The element that was not found within R2.
-    type Early = RegionElementIndex;
+    type Early = OutlivesFailure;
 
     fn start_point(&self) -> Location {
         self.constraint_point
@@ -192,14 +231,23 @@ impl<'v, 'tcx> DfsOp for TestTargetOutlivesSource<'v, 'tcx> {
             .contains(self.source_region, point_index)
     }
 
+    fn visit_point(&mut self, point: Location, predecessor: Option<Location>) {
+        if let (Some(predecessors), Some(predecessor)) = (&mut self.predecessors, predecessor) {
+            predecessors.insert(point, predecessor);
+        }
+    }
+
     fn add_to_target_region(
         &mut self,
         point_index: RegionElementIndex,
-    ) -> Result<bool, RegionElementIndex> {
+    ) -> Result<bool, OutlivesFailure> {
         if !self.inferred_values
             .contains(self.target_region, point_index)
         {
-            return Err(point_index);
+            let path = self.elements
+                .to_location(point_index)
+                .and_then(|point| self.path_to(point));
+            return Err(OutlivesFailure { elem: point_index, path });
         }
 
         Ok(false)
@@ -207,7 +255,7 @@ impl<'v, 'tcx> DfsOp for TestTargetOutlivesSource<'v, 'tcx> {
 
     fn add_universal_regions_outlived_by_source_to_target(
         &mut self,
-    ) -> Result<bool, RegionElementIndex> {
+    ) -> Result<bool, OutlivesFailure> {
         // For all `ur_in_source` in `source_region`.
         for ur_in_source in self.inferred_values
             .universal_regions_outlived_by(self.source_region)
@@ -236,7 +284,10 @@ impl<'v, 'tcx> DfsOp for TestTargetOutlivesSource<'v, 'tcx> {
             }
 
             // Otherwise, not known to be true.
-            return Err(self.elements.index(ur_in_source));
+            return Err(OutlivesFailure {
+                elem: self.elements.index(ur_in_source),
+                path: None,
+            });
         }
 
         Ok(false)
@@ -13,6 +13,7 @@
 //! state of region inference. This code handles emitting the region
 //! context internal state.
 
+use rustc::ty::{self, RegionKind};
 use std::io::{self, Write};
 use super::{Constraint, RegionInferenceContext};
 
@@ -30,13 +31,15 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             if self.definitions[region].is_universal {
                 let classification = self.universal_regions.region_classification(region).unwrap();
                 let outlived_by = self.universal_regions.regions_outlived_by(region);
+                let name = self.definitions[region].external_name.map(source_name);
                 writeln!(
                     out,
-                    "| {r:rw$} | {c:cw$} | {ob}",
+                    "| {r:rw$} | {c:cw$} | {n:rw$} | {ob}",
                     r = format!("{:?}", region),
                     rw = REGION_WIDTH,
                     c = format!("{:?}", classification),
                     cw = 8, // "External" at most
+                    n = name.unwrap_or(String::new()),
                     ob = format!("{:?}", outlived_by)
                 )?;
             }
@@ -61,6 +64,34 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         Ok(())
     }
 
+    /// Write out the footer of the `.mir` files, which maps each
+    /// region variable appearing in the MIR back to its origin.
+    pub(crate) fn dump_mir_footer(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "")?;
+        writeln!(out, "| Region Origins")?;
+        self.for_each_region_origin(&mut |msg| writeln!(out, "| {}", msg))
+    }
+
+    /// Invokes the `with_msg` callback with the origin of each region
+    /// variable, including where in the source it was created if it
+    /// wasn't created by NLL itself. This is shared by the `.mir` files
+    /// and `#[rustc_regions(origins)]`.
+    pub(crate) fn for_each_region_origin(
+        &self,
+        with_msg: &mut dyn FnMut(&str) -> io::Result<()>,
+    ) -> io::Result<()> {
+        for region in self.regions() {
+            with_msg(&format!(
+                "{r:rw$} | {o:?}",
+                r = format!("{:?}", region),
+                rw = REGION_WIDTH,
+                o = self.definitions[region].origin
+            ))?;
+        }
+
+        Ok(())
+    }
+
     /// Debugging aid: Invokes the `with_msg` callback repeatedly with
     /// our internal region constraints.  These are dumped into the
     /// -Zdump-mir file so that we can figure out why the region
@@ -84,6 +115,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
                 sub,
                 point,
                 span,
+                requirement_span: _,
             } = constraint;
             with_msg(&format!(
                 "{:?}: {:?} @ {:?} due to {:?}",
@@ -98,3 +130,16 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     }
 }
 
+/// The name of the universal region `r` in the source, without any of
+/// the details that `-Zverbose` would add to it.
+fn source_name(r: ty::Region) -> String {
+    match *r {
+        RegionKind::ReStatic => "'static".to_string(),
+        RegionKind::ReEarlyBound(data) => data.name.to_string(),
+        RegionKind::ReFree(ty::FreeRegion {
+            bound_region: ty::BrNamed(_, name),
+            ..
+        }) => name.to_string(),
+        _ => "'_".to_string(),
+    }
+}
@@ -11,7 +11,10 @@
 use std::collections::HashMap;
 
 use super::universal_regions::UniversalRegions;
+use rustc::hir;
 use rustc::hir::def_id::DefId;
+use rustc::hir::map::blocks::FnLikeNode;
+use rustc::ich::StableHashingContext;
 use rustc::infer::InferCtxt;
 use rustc::infer::NLLRegionVariableOrigin;
 use rustc::infer::RegionObligation;
@@ -19,21 +22,31 @@ use rustc::infer::RegionVariableOrigin;
 use rustc::infer::SubregionOrigin;
 use rustc::infer::error_reporting::nice_region_error::NiceRegionError;
 use rustc::infer::region_constraints::{GenericKind, VarOrigins};
+use rustc::middle::resolve_lifetime::static_object_lifetime_defaults;
 use rustc::mir::{ClosureOutlivesRequirement, ClosureOutlivesSubject, ClosureRegionRequirements,
-                 Local, Location, Mir};
+                 Local, Location, Mir, Operand, TerminatorKind, RETURN_PLACE};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::session::NllStats;
 use rustc::traits::ObligationCause;
-use rustc::ty::{self, RegionVid, Ty, TypeFoldable};
-use rustc::util::common::ErrorReported;
+use rustc::ty::{self, RegionVid, Ty, TyCtxt, TypeFoldable};
+use rustc::util::common::{ErrorReported, IceContext};
+use borrow_check::record_phase_time;
 use rustc_data_structures::bitvec::BitVector;
-use rustc_data_structures::indexed_vec::IndexVec;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher, StableHasherResult};
+use rustc_errors::DiagnosticBuilder;
 use std::fmt;
+use std::mem;
 use std::rc::Rc;
 use syntax::ast;
-use syntax_pos::Span;
+use syntax_pos::{MultiSpan, Span};
 
 mod annotation;
+mod constraint_set;
+use self::constraint_set::{ConstraintIndex, ConstraintSet};
 mod dfs;
-use self::dfs::{CopyFromSourceToTarget, TestTargetOutlivesSource};
+use self::dfs::{CopyFromSourceToTarget, OutlivesFailure, TestTargetOutlivesSource};
 mod dump_mir;
 mod graphviz;
 mod values;
@@ -62,7 +75,7 @@ pub struct RegionInferenceContext<'tcx> {
     inferred_values: Option<RegionValues>,
 
     /// The constraints we have accumulated and used during solving.
-    constraints: Vec<Constraint>,
+    constraints: ConstraintSet,
 
     /// Type constraints that we check after solving.
     type_tests: Vec<TypeTest<'tcx>>,
@@ -95,7 +108,7 @@ struct RegionDefinition<'tcx> {
 /// NB: The variants in `Cause` are intentionally ordered. Lower
 /// values are preferred when it comes to error messages. Do not
 /// reorder willy nilly.
-#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub(crate) enum Cause {
     /// point inserted because Local was live at the given Location
     LiveVar(Local, Location),
@@ -128,7 +141,7 @@ pub(crate) struct RegionCausalInfo {
     inferred_values: RegionValues,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, RustcEncodable, RustcDecodable)]
 pub struct Constraint {
     // NB. The ordering here is not significant for correctness, but
     // it is for convenience. Before we dump the constraints in the
@@ -143,6 +156,11 @@ pub struct Constraint {
     /// At this location.
     point: Location,
 
+    /// If this constraint was introduced by the requirements of a
+    /// closure created at `point`, where in the closure body the
+    /// requirement arose.
+    requirement_span: Option<Span>,
+
     /// Where did this constraint arise?
     span: Span,
 }
@@ -179,7 +197,7 @@ pub struct Constraint {
 /// For more information about this translation, see
 /// `InferCtxt::process_registered_region_obligations` and
 /// `InferCtxt::type_must_outlive` in `rustc::infer::outlives`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct TypeTest<'tcx> {
     /// The type `T` that must outlive the region.
     pub generic_kind: GenericKind<'tcx>,
@@ -193,6 +211,11 @@ pub struct TypeTest<'tcx> {
     /// Where did this constraint arise?
     pub span: Span,
 
+    /// If this type test was introduced by the requirements of a closure
+    /// created at `point`, where in the closure body the requirement
+    /// arose.
+    pub requirement_span: Option<Span>,
+
     /// A test which, if met by the region `'x`, proves that this type
     /// constraint is satisfied.
     pub test: RegionTest,
@@ -203,7 +226,7 @@ pub struct TypeTest<'tcx> {
 /// region values that get inferred for each variable; they only
 /// examine the results *after* inference.  This means they can
 /// conveniently include disjuction ("a or b must be true").
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub enum RegionTest {
     /// The subject region `'x` must by outlived by *some* region in
     /// the given set of regions.
@@ -233,6 +256,42 @@ pub enum RegionTest {
     All(Vec<RegionTest>),
 }
 
+impl_stable_hash_for!(struct Constraint {
+    sup,
+    sub,
+    point,
+    requirement_span,
+    span
+});
+
+impl_stable_hash_for!(struct TypeTest<'tcx> {
+    generic_kind,
+    lower_bound,
+    point,
+    span,
+    requirement_span,
+    test
+});
+
+impl<'a> HashStable<StableHashingContext<'a>> for RegionTest {
+    fn hash_stable<W: StableHasherResult>(
+        &self,
+        hcx: &mut StableHashingContext<'a>,
+        hasher: &mut StableHasher<W>,
+    ) {
+        mem::discriminant(self).hash_stable(hcx, hasher);
+        match *self {
+            RegionTest::IsOutlivedByAnyRegionIn(ref regions) |
+            RegionTest::IsOutlivedByAllRegionsIn(ref regions) => {
+                regions.hash_stable(hcx, hasher);
+            }
+            RegionTest::Any(ref tests) | RegionTest::All(ref tests) => {
+                tests.hash_stable(hcx, hasher);
+            }
+        }
+    }
+}
+
 impl<'tcx> RegionInferenceContext<'tcx> {
     /// Creates a new region inference context with a total of
     /// `num_region_variables` valid inference variables; the first N
@@ -259,7 +318,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             elements: elements.clone(),
             liveness_constraints: RegionValues::new(elements, num_region_variables),
             inferred_values: None,
-            constraints: Vec::new(),
+            constraints: ConstraintSet::default(),
             type_tests: Vec::new(),
             universal_regions,
         };
@@ -346,6 +405,19 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         inferred_values.contains(r.to_region_vid(), p)
     }
 
+    /// Returns true if the region `r` is required to outlive `'static`.
+    ///
+    /// Panics if called before `solve()` executes,
+    pub fn region_outlives_static<R>(&self, r: R) -> bool
+    where
+        R: ToRegionVid,
+    {
+        let inferred_values = self.inferred_values
+            .as_ref()
+            .expect("region values not yet inferred");
+        inferred_values.contains(r.to_region_vid(), self.universal_regions.fr_static)
+    }
+
     /// Returns access to the value of `r` for debugging purposes.
     pub(super) fn region_value_str(&self, r: RegionVid) -> String {
         let inferred_values = self.inferred_values
@@ -376,6 +448,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     pub(super) fn add_outlives(
         &mut self,
         span: Span,
+        requirement_span: Option<Span>,
         sup: RegionVid,
         sub: RegionVid,
         point: Location,
@@ -384,6 +457,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         assert!(self.inferred_values.is_none(), "values already inferred");
         self.constraints.push(Constraint {
             span,
+            requirement_span,
             sup,
             sub,
             point,
@@ -406,7 +480,11 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     ) -> Option<ClosureRegionRequirements<'gcx>> {
         assert!(self.inferred_values.is_none(), "values already inferred");
 
-        self.propagate_constraints(mir);
+        let times = &infcx.tcx.sess.mir_borrowck_times;
+        record_phase_time(&times.constraint_propagation, || {
+            let _ice_context = IceContext::enter(String::from("propagating region constraints"));
+            self.propagate_constraints(mir)
+        });
 
         // If this is a closure, we can propagate unsatisfied
         // `outlives_requirements` to our creator, so create a vector
@@ -419,9 +497,15 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             None
         };
 
-        self.check_type_tests(infcx, mir, mir_def_id, outlives_requirements.as_mut());
+        record_phase_time(&times.type_tests, || {
+            let _ice_context = IceContext::enter(String::from("checking type tests"));
+            self.check_type_tests(infcx, mir, mir_def_id, outlives_requirements.as_mut())
+        });
 
-        self.check_universal_regions(infcx, mir_def_id, outlives_requirements.as_mut());
+        record_phase_time(&times.universal_regions, || {
+            let _ice_context = IceContext::enter(String::from("checking universal regions"));
+            self.check_universal_regions(infcx, mir, mir_def_id, outlives_requirements.as_mut())
+        });
 
         let outlives_requirements = outlives_requirements.unwrap_or(vec![]);
 
@@ -467,14 +551,14 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         let dependency_map = self.build_dependency_map();
 
         // Constraints that may need to be repropagated (initially all):
-        let mut dirty_list: Vec<_> = (0..self.constraints.len()).collect();
+        let mut dirty_list: Vec<_> = self.constraints.indices().collect();
 
         // Set to 0 for each constraint that is on the dirty list:
         let mut clean_bit_vec = BitVector::new(dirty_list.len());
 
         debug!("propagate_constraints: --------------------");
         while let Some(constraint_idx) = dirty_list.pop() {
-            clean_bit_vec.insert(constraint_idx);
+            clean_bit_vec.insert(constraint_idx.index());
 
             let constraint = &self.constraints[constraint_idx];
             debug!("propagate_constraints: constraint={:?}", constraint);
@@ -497,7 +581,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
                 debug!("propagate_constraints:   sup={:?}", constraint.sup);
 
                 for &dep_idx in dependency_map.get(&constraint.sup).unwrap_or(&vec![]) {
-                    if clean_bit_vec.remove(dep_idx) {
+                    if clean_bit_vec.remove(dep_idx.index()) {
                         dirty_list.push(dep_idx);
                     }
                 }
@@ -513,10 +597,10 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     /// indices of constraints that need to be re-evaluated when X changes.
     /// These are constraints like Y: X @ P -- so if X changed, we may
     /// need to grow Y.
-    fn build_dependency_map(&self) -> HashMap<RegionVid, Vec<usize>> {
+    fn build_dependency_map(&self) -> HashMap<RegionVid, Vec<ConstraintIndex>> {
         let mut map = HashMap::new();
 
-        for (idx, constraint) in self.constraints.iter().enumerate() {
+        for (idx, constraint) in self.constraints.iter_enumerated() {
             map.entry(constraint.sub).or_insert(Vec::new()).push(idx);
         }
 
@@ -545,21 +629,31 @@ impl<'tcx> RegionInferenceContext<'tcx> {
 
             if let Some(propagated_outlives_requirements) = &mut propagated_outlives_requirements {
                 if self.try_promote_type_test(infcx, type_test, propagated_outlives_requirements) {
+                    NllStats::add(&tcx.sess.nll_stats.type_tests_promoted, 1);
                     continue;
                 }
             }
 
+            NllStats::add(&tcx.sess.nll_stats.type_tests_reported, 1);
+
             // Oh the humanity. Obviously we will do better than this error eventually.
             let lower_bound_region = self.to_error_region(type_test.lower_bound);
             if let Some(lower_bound_region) = lower_bound_region {
                 let region_scope_tree = &tcx.region_scope_tree(mir_def_id);
-                infcx.report_generic_bound_failure(
+                let mut err = infcx.construct_generic_bound_failure(
                     region_scope_tree,
+                    Some(mir_def_id),
                     type_test.span,
                     None,
                     type_test.generic_kind,
                     lower_bound_region,
                 );
+                if let Some(requirement_span) = type_test.requirement_span {
+                    err.span_label(requirement_span, "requirement introduced here");
+                }
+                self.note_type_test_failure_path(mir, type_test, &mut err);
+                self.note_outlives_failure_attribute(tcx, mir, type_test.point, &mut err);
+                err.emit();
             } else {
                 // FIXME. We should handle this case better. It
                 // indicates that we have e.g. some region variable
@@ -570,12 +664,115 @@ impl<'tcx> RegionInferenceContext<'tcx> {
                 // to report it; we could probably handle it by
                 // iterating over the universal regions and reporting
                 // an error that multiple bounds are required.
-                tcx.sess.span_err(
+                let mut err = struct_span_err!(
+                    tcx.sess,
                     type_test.span,
-                    &format!("`{}` does not live long enough", type_test.generic_kind,),
+                    E0695,
+                    "`{}` does not live long enough",
+                    type_test.generic_kind
                 );
+                self.note_type_test_failure_path(mir, type_test, &mut err);
+                self.note_outlives_failure_attribute(tcx, mir, type_test.point, &mut err);
+                err.emit();
+            }
+        }
+    }
+
+    /// If the failing outlives requirement comes from the bounds of a
+    /// function called at `point`, adds the note that function asks for with
+    /// `#[rustc_on_outlives_failure = "..."]`. This lets libraries explain
+    /// bounds like the `F: 'static` of spawn-like functions, whether they
+    /// fail as a type test or, once the type has been broken down into its
+    /// regions, as a region error.
+    fn note_outlives_failure_attribute(
+        &self,
+        tcx: ty::TyCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
+        point: Location,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let block = &mir[point.block];
+        if point.statement_index != block.statements.len() {
+            return;
+        }
+        let callee = match block.terminator().kind {
+            TerminatorKind::Call { func: Operand::Constant(ref func), .. } => match func.ty.sty {
+                ty::TyFnDef(def_id, _) => def_id,
+                _ => return,
+            },
+            _ => return,
+        };
+        let note = tcx.get_attrs(callee)
+            .iter()
+            .find(|attr| attr.check_name("rustc_on_outlives_failure"))
+            .and_then(|attr| attr.value_str());
+        if let Some(note) = note {
+            err.note(&note.as_str());
+        }
+    }
+
+    /// If the failing type test is missing a point of the CFG, rather
+    /// than a universal region, points out the path along which the
+    /// value must be valid to reach that point from where the test is
+    /// required. This is most helpful when the path goes around a loop.
+    fn note_type_test_failure_path(
+        &self,
+        mir: &Mir<'tcx>,
+        type_test: &TypeTest<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let path = match self.region_test_failure_path(
+            mir,
+            type_test.point,
+            type_test.lower_bound,
+            &type_test.test,
+        ) {
+            Some(path) => path,
+            None => return,
+        };
+
+        let mut spans: Vec<Span> = vec![];
+        for &location in &path {
+            let span = mir.source_info(location).span;
+            if !spans.contains(&span) {
+                spans.push(span);
             }
         }
+        if spans.len() > 1 {
+            err.span_note(
+                MultiSpan::from_spans(spans),
+                "value must be valid along this path",
+            );
+        }
+    }
+
+    /// Like `eval_region_test`, but for a test that is known to fail,
+    /// finds the CFG path to the point that makes one of the failing
+    /// `eval_outlives` checks fail, if there is one.
+    fn region_test_failure_path(
+        &self,
+        mir: &Mir<'tcx>,
+        point: Location,
+        lower_bound: RegionVid,
+        test: &RegionTest,
+    ) -> Option<Vec<Location>> {
+        match test {
+            RegionTest::IsOutlivedByAllRegionsIn(regions)
+            | RegionTest::IsOutlivedByAnyRegionIn(regions) => regions
+                .iter()
+                .filter_map(|&r| {
+                    self.eval_outlives_recording_path(mir, r, lower_bound, point, true)
+                        .err()
+                })
+                .filter_map(|failure| failure.path)
+                .next(),
+
+            RegionTest::Any(tests) | RegionTest::All(tests) => tests
+                .iter()
+                .filter(|test| !self.eval_region_test(mir, point, lower_bound, test))
+                .filter_map(|test| self.region_test_failure_path(mir, point, lower_bound, test))
+                .next(),
+        }
     }
 
     /// Converts a region inference variable into a `ty::Region` that
@@ -586,7 +783,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     /// one (e.g., this is just some random part of the CFG).
     pub fn to_error_region(&self, r: RegionVid) -> Option<ty::Region<'tcx>> {
         if self.universal_regions.is_universal_region(r) {
-            return self.definitions[r].external_name;
+            return self.universal_region_name(r);
         } else {
             let inferred_values = self.inferred_values
                 .as_ref()
@@ -600,6 +797,30 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         }
     }
 
+    /// The name of the universal region `fr` in errors. Universal regions
+    /// without a name of their own, e.g. those a closure gets from its
+    /// creator, are named after a named universal region which they are
+    /// known to be equal to, if there is one, rather than by their index.
+    /// If they are equal to several regions with different names, none of
+    /// those names is used.
+    fn universal_region_name(&self, fr: RegionVid) -> Option<ty::Region<'tcx>> {
+        self.definitions[fr].external_name.or_else(|| {
+            let mut names = self.universal_regions
+                .universal_regions()
+                .filter(|&other| {
+                    other != fr && self.universal_regions.outlives(fr, other) &&
+                        self.universal_regions.outlives(other, fr)
+                })
+                .filter_map(|other| self.definitions[other].external_name);
+            let name = names.next()?;
+            if names.all(|other_name| other_name == name) {
+                Some(name)
+            } else {
+                None
+            }
+        })
+    }
+
     fn try_promote_type_test<'gcx>(
         &self,
         infcx: &InferCtxt<'_, 'gcx, 'tcx>,
@@ -613,10 +834,28 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             lower_bound,
             point: _,
             span,
-            test: _,
+            requirement_span: _,
+            test,
         } = type_test;
 
-        let generic_ty = generic_kind.to_ty(tcx);
+        // A projection out of an opaque type with no other trait
+        // parameters, like `<impl Trait as Foo>::Bar: 'r`, holds if
+        // the opaque type itself outlives `'r`. When the projection has
+        // no declared bound that our creator could use instead, we
+        // require that, since our creator can check it in the scope that
+        // defines the opaque type.
+        let generic_ty = match *generic_kind {
+            GenericKind::Projection(ref projection_ty)
+                if projection_ty.substs.len() == 1 && !self.has_usable_declared_bound(test) =>
+            {
+                let self_ty = projection_ty.substs.type_at(0);
+                match self_ty.sty {
+                    ty::TyAnon(..) => self_ty,
+                    _ => generic_kind.to_ty(tcx),
+                }
+            }
+            _ => generic_kind.to_ty(tcx),
+        };
         let subject = match self.try_promote_type_test_subject(infcx, generic_ty) {
             Some(s) => s,
             None => return false,
@@ -638,6 +877,20 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         true
     }
 
+    /// True if `test`, the test of a projection type test, can be met
+    /// through a bound declared on the projection (by a where-clause or
+    /// by the trait) whose region is also known to our creator.
+    fn has_usable_declared_bound(&self, test: &RegionTest) -> bool {
+        match test {
+            RegionTest::IsOutlivedByAnyRegionIn(regions) => regions.iter().any(|&r| {
+                self.universal_regions.is_universal_region(r)
+                    && !self.universal_regions.is_local_free_region(r)
+            }),
+            RegionTest::Any(tests) => tests.iter().any(|t| self.has_usable_declared_bound(t)),
+            RegionTest::IsOutlivedByAllRegionsIn(_) | RegionTest::All(_) => false,
+        }
+    }
+
     /// When we promote a type test `T: 'r`, we have to convert the
     /// type `T` into something we can store in a query result (so
     /// something allocated for `'gcx`). This is problematic if `ty`
@@ -837,6 +1090,22 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             sup_region, sub_region, point
         );
 
+        self.eval_outlives_recording_path(mir, sup_region, sub_region, point, false)
+            .is_ok()
+    }
+
+    /// Evaluates whether `sup_region: sub_region @ point`, returning
+    /// the element that is missing from `sup_region` if not. If
+    /// `record_path` is true, the failure also carries the CFG path
+    /// from `point` to that element, when it is a point.
+    fn eval_outlives_recording_path(
+        &self,
+        mir: &Mir<'tcx>,
+        sup_region: RegionVid,
+        sub_region: RegionVid,
+        point: Location,
+        record_path: bool,
+    ) -> Result<(), OutlivesFailure> {
         // Roughly speaking, do a DFS of all region elements reachable
         // from `point` contained in `sub_region`. If any of those are
         // *not* present in `sup_region`, the DFS will abort early and
@@ -850,20 +1119,21 @@ impl<'tcx> RegionInferenceContext<'tcx> {
                 elements: &self.elements,
                 universal_regions: &self.universal_regions,
                 inferred_values: self.inferred_values.as_ref().unwrap(),
+                predecessors: if record_path { Some(FxHashMap()) } else { None },
             },
         ) {
             Ok(_) => {
                 debug!("eval_outlives: true");
-                true
+                Ok(())
             }
 
-            Err(elem) => {
+            Err(failure) => {
                 debug!(
                     "eval_outlives: false because `{:?}` is not present in `{:?}`",
-                    self.elements.to_element(elem),
+                    self.elements.to_element(failure.elem),
                     sup_region
                 );
-                false
+                Err(failure)
             }
         }
     }
@@ -888,6 +1158,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     fn check_universal_regions<'gcx>(
         &self,
         infcx: &InferCtxt<'_, 'gcx, 'tcx>,
+        mir: &Mir<'tcx>,
         mir_def_id: DefId,
         mut propagated_outlives_requirements: Option<&mut Vec<ClosureOutlivesRequirement<'gcx>>>,
     ) {
@@ -903,6 +1174,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         for (fr, _) in universal_definitions {
             self.check_universal_region(
                 infcx,
+                mir,
                 mir_def_id,
                 fr,
                 &mut propagated_outlives_requirements,
@@ -921,6 +1193,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     fn check_universal_region<'gcx>(
         &self,
         infcx: &InferCtxt<'_, 'gcx, 'tcx>,
+        mir: &Mir<'tcx>,
         mir_def_id: DefId,
         longer_fr: RegionVid,
         propagated_outlives_requirements: &mut Option<&mut Vec<ClosureOutlivesRequirement<'gcx>>>,
@@ -942,7 +1215,8 @@ impl<'tcx> RegionInferenceContext<'tcx> {
                 longer_fr, shorter_fr,
             );
 
-            let blame_span = self.blame_span(longer_fr, shorter_fr);
+            let blame_constraint = &self.constraints[self.blame_constraint(longer_fr, shorter_fr)];
+            let blame_span = blame_constraint.span;
 
             if let Some(propagated_outlives_requirements) = propagated_outlives_requirements {
                 // Shrink `fr` until we find a non-local region (if we do).
@@ -977,7 +1251,16 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             // Note: in this case, we use the unapproximated regions
             // to report the error. This gives better error messages
             // in some cases.
-            self.report_error(infcx, mir_def_id, longer_fr, shorter_fr, blame_span);
+            self.report_error(
+                infcx,
+                mir,
+                mir_def_id,
+                longer_fr,
+                shorter_fr,
+                blame_constraint.point,
+                blame_span,
+                blame_constraint.requirement_span,
+            );
         }
     }
 
@@ -989,13 +1272,19 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     /// ```
     ///
     /// Here we would be invoked with `fr = 'a` and `outlived_fr = `'b`.
+    ///
+    /// The requirement arises at `blame_point`, or, if it comes from a
+    /// closure created there, at `requirement_span` in the closure's body.
     fn report_error(
         &self,
         infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
         mir_def_id: DefId,
         fr: RegionVid,
         outlived_fr: RegionVid,
+        blame_point: Location,
         blame_span: Span,
+        requirement_span: Option<Span>,
     ) {
         // Obviously uncool error reporting.
 
@@ -1010,27 +1299,99 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             }
         }
 
+        let verbose = infcx.tcx.sess.verbose();
+        // Two different regions can have the same name, e.g. two anonymous
+        // regions are both called `'_`, and a region without a name of its own
+        // may be named after one equal to the other region. They are numbered
+        // instead, and the types of the signature they appear in are labelled
+        // below.
+        let numbered = match (fr_name, outlived_fr_name) {
+            (Some(fr_name), Some(outlived_fr_name)) => {
+                fr != outlived_fr
+                    && free_region_name(fr_name, verbose)
+                        == free_region_name(outlived_fr_name, verbose)
+            }
+            _ => false,
+        };
+        let region_name = |r: ty::Region<'tcx>, number: usize| {
+            if numbered {
+                format!("'{}", number)
+            } else {
+                free_region_name(r, verbose)
+            }
+        };
+
         let fr_string = match fr_name {
-            Some(r) => format!("free region `{}`", r),
+            Some(r) => format!("free region `{}`", region_name(r, 1)),
             None => format!("free region `{:?}`", fr),
         };
 
         let outlived_fr_string = match outlived_fr_name {
-            Some(r) => format!("free region `{}`", r),
+            Some(r) => format!("free region `{}`", region_name(r, 2)),
             None => format!("free region `{:?}`", outlived_fr),
         };
 
-        let mut diag = infcx.tcx.sess.struct_span_err(
-            blame_span,
-            &format!("{} does not outlive {}", fr_string, outlived_fr_string,),
-        );
+        // A region of the closure's own signature that has to outlive a region
+        // from the closure's environment means that borrowed data escapes the
+        // closure body.
+        let escapes_closure = infcx.tcx.is_closure(mir_def_id)
+            && self.universal_regions.is_local_free_region(fr)
+            && !self.universal_regions.is_local_free_region(outlived_fr);
+
+        let mut diag = if escapes_closure {
+            let mut diag = struct_span_err!(
+                infcx.tcx.sess,
+                blame_span,
+                E0696,
+                "borrowed data escapes outside of closure"
+            );
+            diag.span_label(blame_span, "borrowed data escapes the closure body here");
+            diag.note(&format!("{} does not outlive {}", fr_string, outlived_fr_string));
+            diag
+        } else {
+            struct_span_err!(
+                infcx.tcx.sess,
+                blame_span,
+                E0694,
+                "{} does not outlive {}",
+                fr_string,
+                outlived_fr_string
+            )
+        };
+
+        if let Some(requirement_span) = requirement_span {
+            diag.span_label(requirement_span, "requirement introduced here");
+        }
+
+        if let (true, Some(fr), Some(outlived_fr)) = (numbered, fr_name, outlived_fr_name) {
+            let gcx = infcx.tcx.global_tcx();
+            for &(r, number) in &[(fr, 1), (outlived_fr, 2)] {
+                if let Some(span) = signature_type_mentioning(gcx, mir_def_id, r) {
+                    diag.span_label(span, format!("lifetime `'{}` appears in this type", number));
+                }
+            }
+        }
 
+        // If the `'static` requirement comes from the default lifetime
+        // bound of a trait object in the signature, point that out.
+        if outlived_fr == self.universal_regions.fr_static {
+            let gcx = infcx.tcx.global_tcx();
+            for span in static_object_defaults_used_at(gcx, mir, mir_def_id, blame_point) {
+                diag.span_note(
+                    span,
+                    "the lifetime bound of this trait object defaults to `'static`",
+                );
+            }
+        }
+
+        self.note_outlives_failure_attribute(infcx.tcx, mir, blame_point, &mut diag);
+        infcx.tcx.sess.teach_explanation(&mut diag);
         diag.emit();
     }
 
-    /// Tries to finds a good span to blame for the fact that `fr1`
+    /// Tries to finds a good constraint to blame for the fact that `fr1`
     /// contains `fr2`.
-    fn blame_span(&self, fr1: RegionVid, fr2: RegionVid) -> Span {
+    fn blame_constraint(&self, fr1: RegionVid, fr2: RegionVid) -> ConstraintIndex {
         // Find everything that influenced final value of `fr`.
         let influenced_fr1 = self.dependencies(fr1);
 
@@ -1043,17 +1404,18 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         // of dependencies, which doesn't account for the locations of
         // contraints at all. But it will do for now.
         let relevant_constraint = self.constraints
-                .iter()
-                .filter_map(|constraint| {
+                .iter_enumerated()
+                .filter_map(|(idx, constraint)| {
                     if constraint.sub != fr2 {
                         None
                     } else {
                         influenced_fr1[constraint.sup]
-                            .map(|distance| (distance, constraint.span))
+                            .map(|distance| (distance, constraint.span, idx))
                     }
                 })
-                .min() // constraining fr1 with fewer hops *ought* to be more obvious
-                .map(|(_dist, span)| span);
+                // constraining fr1 with fewer hops *ought* to be more obvious
+                .min_by_key(|&(distance, span, _idx)| (distance, span))
+                .map(|(_dist, _span, idx)| idx);
 
         relevant_constraint.unwrap_or_else(|| {
             bug!(
@@ -1078,7 +1440,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
 
         while changed {
             changed = false;
-            for constraint in &self.constraints {
+            for constraint in self.constraints.iter() {
                 if let Some(n) = result_set[constraint.sup] {
                     let m = n + 1;
                     if result_set[constraint.sub]
@@ -1106,6 +1468,108 @@ impl RegionCausalInfo {
     }
 }
 
+/// Whether `r` is an anonymous free region, e.g. from an elided lifetime.
+fn is_anonymous_region(r: ty::Region) -> bool {
+    match *r {
+        ty::ReFree(ty::FreeRegion { bound_region: ty::BrAnon(_), .. }) |
+        ty::ReFree(ty::FreeRegion { bound_region: ty::BrFresh(_), .. }) => true,
+        _ => false,
+    }
+}
+
+/// The name of a free region in errors. Anonymous regions are called `'_`,
+/// which is also how users can write them.
+fn free_region_name(r: ty::Region, verbose: bool) -> String {
+    if is_anonymous_region(r) && !verbose {
+        "'_".to_string()
+    } else {
+        r.to_string()
+    }
+}
+
+/// Returns the span of the first type in the signature of `mir_def_id`, from
+/// its arguments to its return type, which mentions the free region `r`.
+fn signature_type_mentioning<'gcx>(
+    tcx: TyCtxt<'_, 'gcx, 'gcx>,
+    mir_def_id: DefId,
+    r: &ty::RegionKind,
+) -> Option<Span> {
+    let node_id = tcx.hir.as_local_node_id(mir_def_id)?;
+    let decl = FnLikeNode::from_node(tcx.hir.get(node_id))?.decl();
+    let tables = tcx.typeck_tables_of(mir_def_id);
+    let sig = *tables.liberated_fn_sigs().get(tcx.hir.node_to_hir_id(node_id))?;
+
+    let mentions_r = |ty: Ty<'gcx>| {
+        let mut found = false;
+        tcx.for_each_free_region(&ty, |ty_r| found |= *ty_r == *r);
+        found
+    };
+    let output_span = match decl.output {
+        hir::Return(ref ty) if mentions_r(sig.output()) => Some(ty.span),
+        _ => None,
+    };
+    decl.inputs
+        .iter()
+        .zip(sig.inputs())
+        .find(|&(_, &ty)| mentions_r(ty))
+        .map(|(input, _)| input.span)
+        .or(output_span)
+}
+
+/// Returns the spans of the trait objects whose lifetime bound defaults to
+/// `'static` in the parts of the signature of `mir_def_id` that the MIR uses
+/// at `point`: the return type, if it is assigned there, and the types of
+/// the arguments it mentions.
+fn static_object_defaults_used_at<'gcx, 'tcx>(
+    tcx: TyCtxt<'_, 'gcx, 'gcx>,
+    mir: &Mir<'tcx>,
+    mir_def_id: DefId,
+    point: Location,
+) -> Vec<Span> {
+    struct LocalsMentioned(Vec<Local>);
+
+    impl<'tcx> Visitor<'tcx> for LocalsMentioned {
+        fn visit_local(&mut self, local: &Local, _: PlaceContext<'tcx>, _: Location) {
+            if !self.0.contains(local) {
+                self.0.push(*local);
+            }
+        }
+    }
+
+    let node_id = tcx.hir.as_local_node_id(mir_def_id).unwrap();
+    let decl = match FnLikeNode::from_node(tcx.hir.get(node_id)) {
+        Some(fn_like) => fn_like.decl(),
+        None => return vec![],
+    };
+
+    let mut locals = LocalsMentioned(vec![]);
+    let data = &mir[point.block];
+    if point.statement_index < data.statements.len() {
+        locals.visit_statement(point.block, &data.statements[point.statement_index], point);
+    } else {
+        locals.visit_terminator(point.block, data.terminator(), point);
+    }
+
+    // The arguments come last, after the environment of closures.
+    let first_input = mir.arg_count + 1 - decl.inputs.len();
+    locals.0
+        .into_iter()
+        .filter_map(|local| {
+            if local == RETURN_PLACE {
+                match decl.output {
+                    hir::Return(ref ty) => Some(&**ty),
+                    hir::DefaultReturn(_) => None,
+                }
+            } else if local.index() >= first_input && local.index() <= mir.arg_count {
+                Some(&*decl.inputs[local.index() - first_input])
+            } else {
+                None
+            }
+        })
+        .flat_map(|ty| static_object_lifetime_defaults(tcx, ty))
+        .collect()
+}
+
 impl<'tcx> RegionDefinition<'tcx> {
     fn new(origin: RegionVariableOrigin) -> Self {
         // Create a new region definition. Note that, for free
@@ -1277,3 +1741,64 @@ impl Cause {
         }
     }
 }
+
+/// Encodes `value` with the opaque encoder and decodes it again.
+#[cfg(test)]
+fn encode_round_trip<T>(value: &T) -> T
+where
+    T: ::rustc_serialize::Encodable + ::rustc_serialize::Decodable,
+{
+    use rustc_serialize::opaque;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(Vec::new());
+    value.encode(&mut opaque::Encoder::new(&mut cursor)).unwrap();
+    let data = cursor.into_inner();
+    ::rustc_serialize::Decodable::decode(&mut opaque::Decoder::new(&data, 0)).unwrap()
+}
+
+#[test]
+fn constraint_encode_decode() {
+    use rustc::mir::BasicBlock;
+    use syntax_pos::{BytePos, NO_EXPANSION};
+
+    let constraint = Constraint {
+        sup: RegionVid::new(3),
+        sub: RegionVid::new(1),
+        point: Location {
+            block: BasicBlock::new(2),
+            statement_index: 4,
+        },
+        requirement_span: Some(Span::new(BytePos(10), BytePos(20), NO_EXPANSION)),
+        span: Span::new(BytePos(1), BytePos(5), NO_EXPANSION),
+    };
+    assert!(encode_round_trip(&constraint) == constraint);
+}
+
+#[test]
+fn type_test_encode_decode() {
+    use rustc::mir::BasicBlock;
+    use syntax::symbol::Symbol;
+    use syntax_pos::{BytePos, NO_EXPANSION};
+
+    let type_test = TypeTest {
+        generic_kind: GenericKind::Param(ty::ParamTy::new(1, Symbol::intern("T"))),
+        lower_bound: RegionVid::new(4),
+        point: Location {
+            block: BasicBlock::new(1),
+            statement_index: 0,
+        },
+        span: Span::new(BytePos(7), BytePos(9), NO_EXPANSION),
+        requirement_span: None,
+        test: RegionTest::Any(vec![
+            RegionTest::IsOutlivedByAnyRegionIn(vec![RegionVid::new(0), RegionVid::new(2)]),
+            RegionTest::All(vec![
+                RegionTest::IsOutlivedByAllRegionsIn(vec![RegionVid::new(1)]),
+            ]),
+        ]),
+    };
+    assert_eq!(
+        format!("{:?}", encode_round_trip(&type_test)),
+        format!("{:?}", type_test)
+    );
+}
@@ -9,10 +9,12 @@
 // except according to those terms.
 
 use std::rc::Rc;
+use rustc::ich::StableHashingContext;
 use rustc_data_structures::bitvec::SparseBitMatrix;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::indexed_vec::Idx;
 use rustc_data_structures::indexed_vec::IndexVec;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher, StableHasherResult};
 use rustc::mir::{BasicBlock, Location, Mir};
 use rustc::ty::RegionVid;
 use syntax::codemap::Span;
@@ -21,8 +23,10 @@ use super::{Cause, CauseExt, TrackCauses};
 
 /// Maps between the various kinds of elements of a region value to
 /// the internal indices that w use.
+#[derive(RustcEncodable, RustcDecodable)]
 pub(super) struct RegionValueElements {
-    /// For each basic block, how many points are contained within?
+    /// For each basic block, how many points come before it? This is
+    /// the index (among the points) of the first statement of the block.
     statements_before_block: IndexVec<BasicBlock, usize>,
     num_points: usize,
     num_universal_regions: usize,
@@ -81,42 +85,41 @@ impl RegionValueElements {
     pub(super) fn to_element(&self, i: RegionElementIndex) -> RegionElement {
         debug!("to_element(i={:?})", i);
 
-        if let Some(r) = self.to_universal_region(i) {
-            RegionElement::UniversalRegion(r)
-        } else {
-            let point_index = i.index() - self.num_universal_regions;
-
-            // Find the basic block. We have a vector with the
-            // starting index of the statement in each block. Imagine
-            // we have statement #22, and we have a vector like:
-            //
-            // [0, 10, 20]
-            //
-            // In that case, this represents point_index 2 of
-            // basic block BB2. We know this because BB0 accounts for
-            // 0..10, BB1 accounts for 11..20, and BB2 accounts for
-            // 20...
-            //
-            // To compute this, we could do a binary search, but
-            // because I am lazy we instead iterate through to find
-            // the last point where the "first index" (0, 10, or 20)
-            // was less than the statement index (22). In our case, this will
-            // be (BB2, 20).
-            //
-            // Nit: we could do a binary search here but I'm too lazy.
-            let (block, &first_index) = self.statements_before_block
-                .iter_enumerated()
-                .filter(|(_, first_index)| **first_index <= point_index)
-                .last()
-                .unwrap();
-
-            RegionElement::Location(Location {
-                block,
-                statement_index: point_index - first_index,
-            })
+        match self.to_universal_region(i) {
+            Some(r) => RegionElement::UniversalRegion(r),
+            None => RegionElement::Location(self.to_location(i).unwrap()),
         }
     }
 
+    /// Converts a particular `RegionElementIndex` to the point in the
+    /// CFG it represents, if that is what it represents. Returns
+    /// `None` otherwise.
+    pub(super) fn to_location(&self, i: RegionElementIndex) -> Option<Location> {
+        let point_index = i.index().checked_sub(self.num_universal_regions)?;
+
+        // Find the basic block. We have a vector with the starting
+        // index of the statement in each block, which is strictly
+        // increasing since every block has at least a terminator.
+        // Imagine we have statement #22, and we have a vector like:
+        //
+        // [0, 10, 20]
+        //
+        // In that case, this represents point_index 2 of basic block
+        // BB2. We know this because BB0 accounts for 0..10, BB1
+        // accounts for 10..20, and BB2 accounts for 20... A binary
+        // search either finds the block starting exactly at the point,
+        // or the position of the first block starting after it.
+        let block = match self.statements_before_block.binary_search(&point_index) {
+            Ok(block) => block,
+            Err(next_block) => BasicBlock::new(next_block.index() - 1),
+        };
+
+        Some(Location {
+            block,
+            statement_index: point_index - self.statements_before_block[block],
+        })
+    }
+
     /// Converts a particular `RegionElementIndex` to a universal
     /// region, if that is what it represents. Returns `None`
     /// otherwise.
@@ -184,6 +187,7 @@ impl ToElementIndex for RegionElementIndex {
 /// compact `SparseBitMatrix` representation, with one row per region
 /// variable. The columns consist of either universal regions or
 /// points in the CFG.
+#[derive(RustcEncodable, RustcDecodable)]
 pub(super) struct RegionValues {
     elements: Rc<RegionValueElements>,
     matrix: SparseBitMatrix<RegionVid, RegionElementIndex>,
@@ -197,6 +201,32 @@ pub(super) struct RegionValues {
 
 type CauseMap = FxHashMap<(RegionVid, RegionElementIndex), Rc<Cause>>;
 
+/// The causes are only tracked to explain errors, and are left out of
+/// the hash: two region values are the same if they contain the same
+/// elements.
+impl<'a> HashStable<StableHashingContext<'a>> for RegionValues {
+    fn hash_stable<W: StableHasherResult>(
+        &self,
+        hcx: &mut StableHashingContext<'a>,
+        hasher: &mut StableHasher<W>,
+    ) {
+        let RegionValueElements {
+            ref statements_before_block,
+            num_points,
+            num_universal_regions,
+        } = *self.elements;
+        statements_before_block.hash_stable(hcx, hasher);
+        num_points.hash_stable(hcx, hasher);
+        num_universal_regions.hash_stable(hcx, hasher);
+
+        self.matrix.rows().count().hash_stable(hcx, hasher);
+        for r in self.matrix.rows() {
+            let elements: Vec<usize> = self.matrix.iter(r).map(|i| i.index()).collect();
+            elements.hash_stable(hcx, hasher);
+        }
+    }
+}
+
 impl RegionValues {
     /// Creates a new set of "region values" that tracks causal information.
     /// Each of the regions in num_region_variables will be initialized with an
@@ -331,7 +361,7 @@ impl RegionValues {
 
     /// Iterate over the value of the region `r`, yielding up element
     /// indices. You may prefer `universal_regions_outlived_by` or
-    /// `elements_contained_in`.
+    /// `locations_outlived_by`.
     pub(super) fn element_indices_contained_in<'a>(
         &'a self,
         r: RegionVid,
@@ -350,13 +380,14 @@ impl RegionValues {
             .map(move |v| v.unwrap())
     }
 
-    /// Returns all the elements contained in a given region's value.
-    pub(super) fn elements_contained_in<'a>(
+    /// Returns just the points of the CFG that are contained in a given
+    /// region's value, ordered by basic block and then by statement.
+    pub(super) fn locations_outlived_by<'a>(
         &'a self,
         r: RegionVid,
-    ) -> impl Iterator<Item = RegionElement> + 'a {
+    ) -> impl Iterator<Item = Location> + 'a {
         self.element_indices_contained_in(r)
-            .map(move |r| self.elements.to_element(r))
+            .filter_map(move |i| self.elements.to_location(i))
     }
 
     /// Returns a "pretty" string value of the region. Meant for debugging.
@@ -376,35 +407,27 @@ impl RegionValues {
             sep = ", ";
         };
 
-        for element in self.elements_contained_in(r) {
-            match element {
-                RegionElement::Location(l) => {
-                    if let Some((location1, location2)) = open_location {
-                        if location2.block == l.block
-                            && location2.statement_index == l.statement_index - 1
-                        {
-                            open_location = Some((location1, l));
-                            continue;
-                        }
-
-                        push_sep(&mut result);
-                        Self::push_location_range(&mut result, location1, location2);
-                    }
-
-                    open_location = Some((l, l));
-                }
-
-                RegionElement::UniversalRegion(fr) => {
-                    if let Some((location1, location2)) = open_location {
-                        push_sep(&mut result);
-                        Self::push_location_range(&mut result, location1, location2);
-                        open_location = None;
-                    }
+        // Universal regions come first in the value, followed by the
+        // points in CFG order.
+        for fr in self.universal_regions_outlived_by(r) {
+            push_sep(&mut result);
+            result.push_str(&format!("{:?}", fr));
+        }
 
-                    push_sep(&mut result);
-                    result.push_str(&format!("{:?}", fr));
+        for l in self.locations_outlived_by(r) {
+            if let Some((location1, location2)) = open_location {
+                if location2.block == l.block
+                    && location2.statement_index == l.statement_index - 1
+                {
+                    open_location = Some((location1, l));
+                    continue;
                 }
+
+                push_sep(&mut result);
+                Self::push_location_range(&mut result, location1, location2);
             }
+
+            open_location = Some((l, l));
         }
 
         if let Some((location1, location2)) = open_location {
@@ -443,3 +466,44 @@ impl RegionValues {
         }
     }
 }
+
+#[test]
+fn region_values_encode_decode() {
+    use super::encode_round_trip;
+
+    // Two universal regions, and a CFG with a block of two statements
+    // followed by a block of one.
+    let elements = Rc::new(RegionValueElements {
+        statements_before_block: vec![0, 3].into_iter().collect(),
+        num_points: 5,
+        num_universal_regions: 2,
+    });
+    let mut values = RegionValues::new(&elements, 4);
+    let point = Location {
+        block: BasicBlock::new(1),
+        statement_index: 1,
+    };
+    values.add(RegionVid::new(1), RegionVid::new(1), &Cause::UniversalRegion(RegionVid::new(1)));
+    values.add(RegionVid::new(2), point, &Cause::LiveOther(point));
+    values.add(RegionVid::new(3), RegionVid::new(0), &Cause::UniversalRegion(RegionVid::new(0)));
+
+    let decoded = encode_round_trip(&values);
+    assert_eq!(
+        decoded.elements.statements_before_block,
+        values.elements.statements_before_block
+    );
+    assert_eq!(decoded.elements.num_points, values.elements.num_points);
+    assert_eq!(
+        decoded.elements.num_universal_regions,
+        values.elements.num_universal_regions
+    );
+    for r in 0..4 {
+        let r = RegionVid::new(r);
+        assert_eq!(
+            decoded.matrix.iter(r).collect::<Vec<_>>(),
+            values.matrix.iter(r).collect::<Vec<_>>()
+        );
+    }
+    assert!(decoded.causes == values.causes);
+    assert_eq!(decoded.cause(RegionVid::new(2), point), Some(Rc::new(Cause::LiveOther(point))));
+}
@@ -8,7 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use rustc::mir::Mir;
+use rustc::mir::{AggregateKind, Location, Mir, Rvalue, Statement, StatementKind};
+use rustc::infer::SubregionOrigin;
 use rustc::infer::region_constraints::Constraint;
 use rustc::infer::region_constraints::RegionConstraintData;
 use rustc::infer::region_constraints::{Verify, VerifyBound};
@@ -67,7 +68,20 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
 
             let span = self.mir.source_info(locations.from_location).span;
 
-            for constraint in constraints.keys() {
+            // The constraints added where a closure is created come from the
+            // requirements that it propagated to us, and their origins point
+            // to where in the closure body those arose.
+            let creates_closure = self.creates_closure(locations.at_location);
+            let requirement_span = |origin: &SubregionOrigin<'tcx>| {
+                let origin_span = origin.span();
+                if creates_closure && origin_span != span {
+                    Some(origin_span)
+                } else {
+                    None
+                }
+            };
+
+            for (constraint, origin) in constraints {
                 debug!("generate: constraint: {:?}", constraint);
                 let (a_vid, b_vid) = match constraint {
                     Constraint::VarSubVar(a_vid, b_vid) => (*a_vid, *b_vid),
@@ -83,12 +97,18 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
                 // reverse direction, because `regioncx` talks about
                 // "outlives" (`>=`) whereas the region constraints
                 // talk about `<=`.
-                self.regioncx
-                    .add_outlives(span, b_vid, a_vid, locations.at_location);
+                self.regioncx.add_outlives(
+                    span,
+                    requirement_span(origin),
+                    b_vid,
+                    a_vid,
+                    locations.at_location,
+                );
             }
 
             for verify in verifys {
-                let type_test = self.verify_to_type_test(verify, span, locations);
+                let mut type_test = self.verify_to_type_test(verify, span, locations);
+                type_test.requirement_span = requirement_span(&verify.origin);
                 self.regioncx.add_type_test(type_test);
             }
 
@@ -118,10 +138,26 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
             lower_bound,
             point,
             span,
+            requirement_span: None,
             test,
         }
     }
 
+    /// Whether the statement at `location` creates a closure.
+    fn creates_closure(&self, location: Location) -> bool {
+        let block = &self.mir[location.block];
+        match block.statements.get(location.statement_index) {
+            Some(Statement {
+                kind: StatementKind::Assign(_, Rvalue::Aggregate(ref kind, _)),
+                ..
+            }) => match **kind {
+                AggregateKind::Closure(..) => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
     fn verify_bound_to_region_test(&self, verify_bound: &VerifyBound<'tcx>) -> RegionTest {
         match verify_bound {
             VerifyBound::AnyRegion(regions) => RegionTest::IsOutlivedByAnyRegionIn(
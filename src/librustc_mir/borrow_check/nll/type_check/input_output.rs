@@ -15,7 +15,9 @@
 //! `impl Trait` instances. In contrast, the input/output types found in
 //! the MIR (specifically, in the special local variables for the
 //! `RETURN_PLACE` the MIR arguments) are always fully normalize (and
-//! contain revealed `impl Trait` values).
+//! contain revealed `impl Trait` values). The same processing applies
+//! to assignments that define an `impl Trait` in the type of a `let`
+//! binding, which are checked here too.
 
 use borrow_check::nll::renumber;
 use borrow_check::nll::universal_regions::UniversalRegions;
@@ -28,6 +30,7 @@ use rustc::mir::visit::TyContext;
 use rustc::traits::PredicateObligations;
 
 use rustc_data_structures::indexed_vec::Idx;
+use std::mem;
 
 use super::{AtLocation, TypeChecker};
 
@@ -45,7 +48,6 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             unnormalized_input_tys,
             ..
         } = universal_regions;
-        let infcx = self.infcx;
 
         let start_position = Location {
             block: START_BLOCK,
@@ -70,7 +72,8 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         }
 
         // Return types are a bit more complex. They may contain existential `impl Trait`
-        // types.
+        // types. The "return type" of a `const` or `static` is its declared type, so this
+        // also covers `impl Trait` in those (with `#![feature(impl_trait_in_bindings)]`).
         debug!(
             "equate_inputs_and_outputs: unnormalized_output_ty={:?}",
             unnormalized_output_ty
@@ -81,51 +84,93 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             output_ty
         );
         let mir_output_ty = mir.local_decls[RETURN_PLACE].ty;
-        let anon_type_map = self.fully_perform_op(start_position.at_self(), |cx| {
+        self.equate_anon_types_and_type(
+            start_position,
+            mir_def_id,
+            universal_regions,
+            output_ty,
+            mir_output_ty,
+        );
+
+        // `impl Trait` in the type of a `let` binding is defined by the
+        // assignment of the initializer. The `impl Trait` belongs to the
+        // enclosing item, so closures define it on behalf of their creator.
+        let anon_parent_def_id = tcx.closure_base_def_id(mir_def_id);
+        let anon_assignments = mem::replace(&mut self.anon_assignments, vec![]);
+        for (location, declared_ty, concrete_ty) in anon_assignments {
+            self.equate_anon_types_and_type(
+                location,
+                anon_parent_def_id,
+                universal_regions,
+                declared_ty,
+                concrete_ty,
+            );
+        }
+    }
+
+    /// Equates `declared_ty`, in which the existential `impl Trait`
+    /// types defined by `anon_parent_def_id` are replaced with their
+    /// hidden types, with `mir_ty`. Then, requires the hidden types to
+    /// be the types recorded by typeck for the `impl Trait`s, and adds
+    /// the region constraints that they impose, i.e., that any region
+    /// in a hidden type is one of the regions the `impl Trait` may
+    /// name.
+    fn equate_anon_types_and_type(
+        &mut self,
+        location: Location,
+        anon_parent_def_id: DefId,
+        universal_regions: &UniversalRegions<'tcx>,
+        declared_ty: Ty<'tcx>,
+        mir_ty: Ty<'tcx>,
+    ) {
+        let tcx = self.infcx.tcx;
+        let infcx = self.infcx;
+
+        let anon_type_map = self.fully_perform_op(location.at_self(), |cx| {
             let mut obligations = ObligationAccumulator::default();
 
-            let (output_ty, anon_type_map) = obligations.add(infcx.instantiate_anon_types(
-                mir_def_id,
+            let (declared_ty, anon_type_map) = obligations.add(infcx.instantiate_anon_types(
+                anon_parent_def_id,
                 cx.body_id,
                 cx.param_env,
-                &output_ty,
+                &declared_ty,
             ));
             debug!(
-                "equate_inputs_and_outputs: instantiated output_ty={:?}",
-                output_ty
+                "equate_anon_types_and_type: instantiated declared_ty={:?}",
+                declared_ty
             );
             debug!(
-                "equate_inputs_and_outputs: anon_type_map={:#?}",
+                "equate_anon_types_and_type: anon_type_map={:#?}",
                 anon_type_map
             );
 
             debug!(
-                "equate_inputs_and_outputs: mir_output_ty={:?}",
-                mir_output_ty
+                "equate_anon_types_and_type: mir_ty={:?}",
+                mir_ty
             );
             obligations.add(infcx
                 .at(&cx.misc(cx.last_span), cx.param_env)
-                .eq(output_ty, mir_output_ty)?);
+                .eq(declared_ty, mir_ty)?);
 
             for (&anon_def_id, anon_decl) in &anon_type_map {
                 let anon_defn_ty = tcx.type_of(anon_def_id);
                 let anon_defn_ty = anon_defn_ty.subst(tcx, anon_decl.substs);
                 let anon_defn_ty = renumber::renumber_regions(
                     cx.infcx,
-                    TyContext::Location(start_position),
+                    TyContext::Location(location),
                     &anon_defn_ty,
                 );
                 debug!(
-                    "equate_inputs_and_outputs: concrete_ty={:?}",
+                    "equate_anon_types_and_type: concrete_ty={:?}",
                     anon_decl.concrete_ty
                 );
-                debug!("equate_inputs_and_outputs: anon_defn_ty={:?}", anon_defn_ty);
+                debug!("equate_anon_types_and_type: anon_defn_ty={:?}", anon_defn_ty);
                 obligations.add(infcx
                     .at(&cx.misc(cx.last_span), cx.param_env)
                     .eq(anon_decl.concrete_ty, anon_defn_ty)?);
             }
 
-            debug!("equate_inputs_and_outputs: equated");
+            debug!("equate_anon_types_and_type: equated");
 
             Ok(InferOk {
                 value: Some(anon_type_map),
@@ -134,10 +179,10 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         }).unwrap_or_else(|terr| {
                 span_mirbug!(
                     self,
-                    start_position,
-                    "equate_inputs_and_outputs: `{:?}=={:?}` failed with `{:?}`",
-                    output_ty,
-                    mir_output_ty,
+                    location,
+                    "equate_anon_types_and_type: `{:?}=={:?}` failed with `{:?}`",
+                    declared_ty,
+                    mir_ty,
                     terr
                 );
                 None
@@ -148,7 +193,7 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         // prove that `T: Iterator` where `T` is the type we
         // instantiated it with).
         if let Some(anon_type_map) = anon_type_map {
-            self.fully_perform_op(start_position.at_self(), |_cx| {
+            self.fully_perform_op(location.at_self(), |_cx| {
                 infcx.constrain_anon_types(&anon_type_map, universal_regions);
                 Ok(InferOk {
                     value: (),
@@ -14,13 +14,11 @@ use dataflow::MaybeInitializedPlaces;
 use dataflow::move_paths::{HasMoveData, MoveData};
 use rustc::mir::{BasicBlock, Location, Mir};
 use rustc::mir::Local;
-use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
-use rustc::traits;
-use rustc::infer::InferOk;
-use rustc::util::common::ErrorReported;
+use rustc::ty::{Ty, TyCtxt, TypeFoldable};
+use rustc::ty::subst::Kind;
+use rustc::infer::region_constraints::RegionConstraintData;
 use borrow_check::nll::type_check::AtLocation;
-use rustc_data_structures::fx::FxHashSet;
-use syntax::codemap::DUMMY_SP;
+use rustc_data_structures::fx::FxHashMap;
 use util::liveness::LivenessResults;
 
 use super::TypeChecker;
@@ -48,6 +46,7 @@ pub(super) fn generate<'gcx, 'tcx>(
         liveness,
         flow_inits,
         move_data,
+        drop_data: FxHashMap(),
     };
 
     for bb in mir.basic_blocks().indices() {
@@ -68,6 +67,12 @@ where
     liveness: &'gen LivenessResults,
     flow_inits: &'gen mut FlowAtLocation<MaybeInitializedPlaces<'flow, 'gcx, 'tcx>>,
     move_data: &'gen MoveData<'tcx>,
+    drop_data: FxHashMap<Ty<'tcx>, DropData<'tcx>>,
+}
+
+struct DropData<'tcx> {
+    dropped_kinds: Vec<Kind<'tcx>>,
+    region_constraint_data: Option<RegionConstraintData<'tcx>>,
 }
 
 impl<'gen, 'typeck, 'flow, 'gcx, 'tcx> TypeLivenessGenerator<'gen, 'typeck, 'flow, 'gcx, 'tcx> {
@@ -84,7 +89,7 @@ impl<'gen, 'typeck, 'flow, 'gcx, 'tcx> TypeLivenessGenerator<'gen, 'typeck, 'flo
                 for live_local in live_locals.iter() {
                     let live_local_ty = self.mir.local_decls[live_local].ty;
                     let cause = Cause::LiveVar(live_local, location);
-                    self.push_type_live_constraint(live_local_ty, location, cause);
+                    Self::push_type_live_constraint(&mut self.cx, live_local_ty, location, cause);
                 }
             });
 
@@ -151,8 +156,12 @@ impl<'gen, 'typeck, 'flow, 'gcx, 'tcx> TypeLivenessGenerator<'gen, 'typeck, 'flo
     /// `location` -- i.e., it may be used later. This means that all
     /// regions appearing in the type `live_ty` must be live at
     /// `location`.
-    fn push_type_live_constraint<T>(&mut self, value: T, location: Location, cause: Cause)
-    where
+    fn push_type_live_constraint<T>(
+        cx: &mut TypeChecker<'_, 'gcx, 'tcx>,
+        value: T,
+        location: Location,
+        cause: Cause,
+    ) where
         T: TypeFoldable<'tcx>,
     {
         debug!(
@@ -161,9 +170,9 @@ impl<'gen, 'typeck, 'flow, 'gcx, 'tcx> TypeLivenessGenerator<'gen, 'typeck, 'flo
             location
         );
 
-        self.tcx.for_each_free_region(&value, |live_region| {
-            self.cx
-                .constraints
+        let tcx = cx.tcx();
+        tcx.for_each_free_region(&value, |live_region| {
+            cx.constraints
                 .liveness_set
                 .push((live_region, location, cause.clone()));
         });
@@ -187,86 +196,39 @@ impl<'gen, 'typeck, 'flow, 'gcx, 'tcx> TypeLivenessGenerator<'gen, 'typeck, 'flo
             location
         );
 
-        // If we end visiting the same type twice (usually due to a cycle involving
-        // associated types), we need to ensure that its region types match up with the type
-        // we added to the 'known' map the first time around. For this reason, we need
-        // our infcx to hold onto its calculated region constraints after each call
-        // to dtorck_constraint_for_ty. Otherwise, normalizing the corresponding associated
-        // type will end up instantiating the type with a new set of inference variables
-        // Since this new type will never be in 'known', we end up looping forever.
-        //
-        // For this reason, we avoid calling TypeChecker.normalize, instead doing all normalization
-        // ourselves in one large 'fully_perform_op' callback.
-        let (type_constraints, kind_constraints) = self.cx.fully_perform_op(location.at_self(),
-            |cx| {
-
-            let tcx = cx.infcx.tcx;
-            let mut selcx = traits::SelectionContext::new(cx.infcx);
-            let cause = cx.misc(cx.last_span);
-
-            let mut types = vec![(dropped_ty, 0)];
-            let mut final_obligations = Vec::new();
-            let mut type_constraints = Vec::new();
-            let mut kind_constraints = Vec::new();
-
-            let mut known = FxHashSet();
-
-            while let Some((ty, depth)) = types.pop() {
-                let span = DUMMY_SP; // FIXME
-                let result = match tcx.dtorck_constraint_for_ty(span, dropped_ty, depth, ty) {
-                    Ok(result) => result,
-                    Err(ErrorReported) => {
-                        continue;
-                    }
-                };
-
-                let ty::DtorckConstraint {
-                    outlives,
-                    dtorck_types,
-                } = result;
-
-                // All things in the `outlives` array may be touched by
-                // the destructor and must be live at this point.
-                for outlive in outlives {
-                    let cause = Cause::DropVar(dropped_local, location);
-                    kind_constraints.push((outlive, location, cause));
-                }
+        // Make sure the drop data is computed for `dropped_ty`.
+        let cx = &mut *self.cx;
+        let drop_data = self.drop_data
+            .entry(dropped_ty)
+            .or_insert_with(|| Self::compute_drop_data(cx, dropped_ty));
 
-                // However, there may also be some types that
-                // `dtorck_constraint_for_ty` could not resolve (e.g.,
-                // associated types and parameters). We need to normalize
-                // associated types here and possibly recursively process.
-                for ty in dtorck_types {
-                    let traits::Normalized { value: ty, obligations } =
-                        traits::normalize(&mut selcx, cx.param_env, cause.clone(), &ty);
-
-                    final_obligations.extend(obligations);
-
-                    let ty = cx.infcx.resolve_type_and_region_vars_if_possible(&ty);
-                    match ty.sty {
-                        ty::TyParam(..) | ty::TyProjection(..) | ty::TyAnon(..) => {
-                            let cause = Cause::DropVar(dropped_local, location);
-                            type_constraints.push((ty, location, cause));
-                        }
-
-                        _ => if known.insert(ty) {
-                            types.push((ty, depth + 1));
-                        },
-                    }
-                }
-            }
-
-            Ok(InferOk {
-                value: (type_constraints, kind_constraints), obligations: final_obligations
-            })
-        }).unwrap();
+        if let Some(ref data) = drop_data.region_constraint_data {
+            cx.push_region_constraints(location.at_self(), data.clone());
+        }
 
-        for (ty, location, cause) in type_constraints {
-            self.push_type_live_constraint(ty, location, cause);
+        // All things in the `outlives` array may be touched by
+        // the destructor and must be live at this point.
+        let cause = Cause::DropVar(dropped_local, location);
+        for &kind in &drop_data.dropped_kinds {
+            Self::push_type_live_constraint(cx, kind, location, cause.clone());
         }
+    }
 
-        for (kind, location, cause) in kind_constraints {
-            self.push_type_live_constraint(kind, location, cause);
+    fn compute_drop_data(
+        cx: &mut TypeChecker<'_, 'gcx, 'tcx>,
+        dropped_ty: Ty<'tcx>,
+    ) -> DropData<'tcx> {
+        debug!("compute_drop_data(dropped_ty={:?})", dropped_ty);
+
+        let (dropped_kinds, region_constraint_data) =
+            cx.fully_perform_op_and_get_region_constraint_data(|cx| {
+                let cause = cx.misc(cx.last_span);
+                Ok(cx.infcx.at(&cause, cx.param_env).dropck_outlives(dropped_ty))
+            }).unwrap();
+
+        DropData {
+            dropped_kinds,
+            region_constraint_data,
         }
     }
 }
@@ -21,9 +21,10 @@ use rustc::hir::def_id::DefId;
 use rustc::infer::{InferCtxt, InferOk, InferResult, LateBoundRegionConversionTime, UnitResult};
 use rustc::infer::region_constraints::{GenericKind, RegionConstraintData};
 use rustc::traits::{self, FulfillmentContext};
+use rustc::traits::query::NoSolution;
 use rustc::ty::error::TypeError;
-use rustc::ty::fold::TypeFoldable;
-use rustc::ty::{self, ToPolyTraitRef, Ty, TyCtxt, TypeVariants};
+use rustc::ty::fold::{TypeFoldable, TypeFolder};
+use rustc::ty::{self, CanonicalVar, ToPolyTraitRef, Ty, TyCtxt, TypeVariants};
 use rustc::mir::*;
 use rustc::mir::tcx::PlaceTy;
 use rustc::mir::visit::{PlaceContext, Visitor};
@@ -33,7 +34,8 @@ use syntax_pos::{Span, DUMMY_SP};
 use transform::{MirPass, MirSource};
 use util::liveness::LivenessResults;
 
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::{Diagnostic, DiagnosticBuilder};
 use rustc_data_structures::indexed_vec::Idx;
 
 macro_rules! span_mirbug {
@@ -62,6 +64,7 @@ macro_rules! span_mirbug_and_err {
 
 mod liveness;
 mod input_output;
+mod relate_tys;
 
 /// Type checks the given `mir` in the context of the inference
 /// context `infcx`. Returns any region constraints that have yet to
@@ -92,6 +95,7 @@ mod input_output;
 ///   constraints for the regions in the types of variables
 /// - `flow_inits` -- results of a maybe-init dataflow analysis
 /// - `move_data` -- move-data constructed when performing the maybe-init dataflow analysis
+/// - `errors_buffer` -- errors are buffered here rather than emitted directly
 pub(crate) fn type_check<'gcx, 'tcx>(
     infcx: &InferCtxt<'_, 'gcx, 'tcx>,
     param_env: ty::ParamEnv<'gcx>,
@@ -101,6 +105,7 @@ pub(crate) fn type_check<'gcx, 'tcx>(
     liveness: &LivenessResults,
     flow_inits: &mut FlowAtLocation<MaybeInitializedPlaces<'_, 'gcx, 'tcx>>,
     move_data: &MoveData<'tcx>,
+    errors_buffer: &mut Vec<Diagnostic>,
 ) -> MirTypeckRegionConstraints<'tcx> {
     let body_id = infcx.tcx.hir.as_local_node_id(mir_def_id).unwrap();
     let implicit_region_bound = infcx.tcx.mk_region(ty::ReVar(universal_regions.fr_fn_body));
@@ -111,6 +116,7 @@ pub(crate) fn type_check<'gcx, 'tcx>(
         mir,
         &universal_regions.region_bound_pairs,
         Some(implicit_region_bound),
+        errors_buffer,
         &mut |cx| {
             liveness::generate(cx, mir, liveness, flow_inits, move_data);
 
@@ -126,6 +132,7 @@ fn type_check_internal<'gcx, 'tcx>(
     mir: &Mir<'tcx>,
     region_bound_pairs: &[(ty::Region<'tcx>, GenericKind<'tcx>)],
     implicit_region_bound: Option<ty::Region<'tcx>>,
+    errors_buffer: &mut Vec<Diagnostic>,
     extra: &mut dyn FnMut(&mut TypeChecker<'_, 'gcx, 'tcx>),
 ) -> MirTypeckRegionConstraints<'tcx> {
     let mut checker = TypeChecker::new(
@@ -148,6 +155,7 @@ fn type_check_internal<'gcx, 'tcx>(
 
     extra(&mut checker);
 
+    errors_buffer.append(&mut checker.errors_buffer);
     checker.constraints
 }
 
@@ -584,6 +592,27 @@ struct TypeChecker<'a, 'gcx: 'a + 'tcx, 'tcx: 'a> {
     implicit_region_bound: Option<ty::Region<'tcx>>,
     reported_errors: FxHashSet<(Ty<'tcx>, Span)>,
     constraints: MirTypeckRegionConstraints<'tcx>,
+
+    /// Projection types that we have already normalized, and what
+    /// they normalized to. Only results that are independent of the
+    /// location where the normalization took place (i.e., that did
+    /// not generate any region constraints) are cached here.
+    ///
+    /// Every location has its own region variables, so both the
+    /// projections and their results have their region variables
+    /// replaced by canonical ones, see `normalization_cache_key`.
+    normalization_cache: FxHashMap<Ty<'tcx>, Ty<'tcx>>,
+
+    /// Errors that are not MIR bugs, such as failures to normalize,
+    /// are buffered here and handed back to the caller.
+    errors_buffer: Vec<Diagnostic>,
+
+    /// Assignments of a value to a place whose type contains an
+    /// existential `impl Trait` (e.g., `let x: impl Debug = 22;`),
+    /// with the type of the place and of the value. These define
+    /// the `impl Trait` and are checked along with the return type
+    /// in `equate_inputs_and_outputs`.
+    anon_assignments: Vec<(Location, Ty<'tcx>, Ty<'tcx>)>,
 }
 
 /// A collection of region constraints that must be satisfied for the
@@ -647,6 +676,9 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             implicit_region_bound,
             reported_errors: FxHashSet(),
             constraints: MirTypeckRegionConstraints::default(),
+            normalization_cache: FxHashMap(),
+            errors_buffer: vec![],
+            anon_assignments: vec![],
         }
     }
 
@@ -661,12 +693,62 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
     ) -> Result<R, TypeError<'tcx>>
     where
         OP: FnOnce(&mut Self) -> InferResult<'tcx, R>,
+    {
+        self.fully_perform_op_or_else(locations, op, |this, e| {
+            span_mirbug!(this, "", "errors selecting obligation: {:?}", e);
+        })
+    }
+
+    /// Like `fully_perform_op`, but if some of the obligations that
+    /// `op` produces cannot be fulfilled, invokes `on_errors` with
+    /// them instead of reporting a MIR bug.
+    fn fully_perform_op_or_else<OP, R, E>(
+        &mut self,
+        locations: Locations,
+        op: OP,
+        on_errors: E,
+    ) -> Result<R, TypeError<'tcx>>
+    where
+        OP: FnOnce(&mut Self) -> InferResult<'tcx, R>,
+        E: FnOnce(&mut Self, Vec<traits::FulfillmentError<'tcx>>),
+    {
+        let (value, data) = self.perform_op_and_take_region_constraints(op, on_errors)?;
+        if let Some(data) = data {
+            self.push_region_constraints(locations, data);
+        }
+        Ok(value)
+    }
+
+    /// Like `fully_perform_op`, but rather than recording the region
+    /// constraints that `op` generates at some location, hands them
+    /// back to the caller, which can then record them at as many
+    /// locations as it likes with `push_region_constraints`.
+    fn fully_perform_op_and_get_region_constraint_data<OP, R>(
+        &mut self,
+        op: OP,
+    ) -> Result<(R, Option<RegionConstraintData<'tcx>>), TypeError<'tcx>>
+    where
+        OP: FnOnce(&mut Self) -> InferResult<'tcx, R>,
+    {
+        self.perform_op_and_take_region_constraints(op, |this, e| {
+            span_mirbug!(this, "", "errors selecting obligation: {:?}", e);
+        })
+    }
+
+    fn perform_op_and_take_region_constraints<OP, R, E>(
+        &mut self,
+        op: OP,
+        on_errors: E,
+    ) -> Result<(R, Option<RegionConstraintData<'tcx>>), TypeError<'tcx>>
+    where
+        OP: FnOnce(&mut Self) -> InferResult<'tcx, R>,
+        E: FnOnce(&mut Self, Vec<traits::FulfillmentError<'tcx>>),
     {
         let mut fulfill_cx = FulfillmentContext::new();
         let InferOk { value, obligations } = self.infcx.commit_if_ok(|_| op(self))?;
         fulfill_cx.register_predicate_obligations(self.infcx, obligations);
         if let Err(e) = fulfill_cx.select_all_or_error(self.infcx) {
-            span_mirbug!(self, "", "errors selecting obligation: {:?}", e);
+            on_errors(self, e);
         }
 
         self.infcx.process_registered_region_obligations(
@@ -677,15 +759,20 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         );
 
         let data = self.infcx.take_and_reset_region_constraints();
-        if !data.is_empty() {
-            debug!("fully_perform_op: constraints generated at {:?} are {:#?}",
-                   locations, data);
-            self.constraints
-                .outlives_sets
-                .push(OutlivesSet { locations, data });
+        if data.is_empty() {
+            Ok((value, None))
+        } else {
+            Ok((value, Some(data)))
         }
+    }
 
-        Ok(value)
+    /// Records region constraints that must hold at `locations`.
+    fn push_region_constraints(&mut self, locations: Locations, data: RegionConstraintData<'tcx>) {
+        debug!("push_region_constraints: constraints generated at {:?} are {:#?}",
+               locations, data);
+        self.constraints
+            .outlives_sets
+            .push(OutlivesSet { locations, data });
     }
 
     fn sub_types(
@@ -694,6 +781,10 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         sup: Ty<'tcx>,
         locations: Locations,
     ) -> UnitResult<'tcx> {
+        if self.relate_types(sub, ty::Covariant, sup, locations) {
+            return Ok(());
+        }
+
         self.fully_perform_op(locations, |this| {
             this.infcx
                 .at(&this.misc(this.last_span), this.param_env)
@@ -702,6 +793,10 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
     }
 
     fn eq_types(&mut self, a: Ty<'tcx>, b: Ty<'tcx>, locations: Locations) -> UnitResult<'tcx> {
+        if self.relate_types(a, ty::Invariant, b, locations) {
+            return Ok(());
+        }
+
         self.fully_perform_op(locations, |this| {
             this.infcx
                 .at(&this.misc(this.last_span), this.param_env)
@@ -709,6 +804,36 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         })
     }
 
+    /// Relates `a` and `b` with the variance `v`, directly recording
+    /// only the outlives constraints that the variance of each
+    /// position requires. Returns false if the types could not be
+    /// related this way (e.g., because they are not structurally the
+    /// same, or need higher-ranked subtyping), in which case the
+    /// caller should relate them through the inference context
+    /// instead.
+    fn relate_types(
+        &mut self,
+        a: Ty<'tcx>,
+        v: ty::Variance,
+        b: Ty<'tcx>,
+        locations: Locations,
+    ) -> bool {
+        let cause = self.misc(self.last_span);
+        match relate_tys::relate_types(self.infcx, &cause, a, v, b) {
+            Ok(data) => {
+                if !data.is_empty() {
+                    debug!("relate_types: constraints generated at {:?} are {:#?}",
+                           locations, data);
+                    self.constraints
+                        .outlives_sets
+                        .push(OutlivesSet { locations, data });
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
     fn tcx(&self) -> TyCtxt<'a, 'gcx, 'tcx> {
         self.infcx.tcx
     }
@@ -723,14 +848,24 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
                 if let Err(terr) =
                     self.sub_types(rv_ty, place_ty, location.at_successor_within_block())
                 {
-                    span_mirbug!(
-                        self,
-                        stmt,
-                        "bad assignment ({:?} = {:?}): {:?}",
-                        place_ty,
-                        rv_ty,
-                        terr
-                    );
+                    let has_anon_types = place_ty.walk().any(|ty| match ty.sty {
+                        ty::TyAnon(..) => true,
+                        _ => false,
+                    });
+                    if has_anon_types {
+                        // The value may be the hidden type of an `impl Trait`
+                        // that is defined here; see `anon_assignments`.
+                        self.anon_assignments.push((location, place_ty, rv_ty));
+                    } else {
+                        span_mirbug!(
+                            self,
+                            stmt,
+                            "bad assignment ({:?} = {:?}): {:?}",
+                            place_ty,
+                            rv_ty,
+                            terr
+                        );
+                    }
                 }
                 self.check_rvalue(mir, rv, location);
             }
@@ -764,6 +899,7 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             | StatementKind::InlineAsm { .. }
             | StatementKind::EndRegion(_)
             | StatementKind::Validate(..)
+            | StatementKind::Coverage { .. }
             | StatementKind::Nop => {}
         }
     }
@@ -1465,7 +1601,8 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             // these extra requirements are basically like where
             // clauses on the struct.
             AggregateKind::Closure(def_id, substs) => {
-                if let Some(closure_region_requirements) = tcx.mir_borrowck(*def_id) {
+                let closure_requirements = tcx.mir_borrowck(*def_id).closure_requirements;
+                if let Some(closure_region_requirements) = closure_requirements {
                     closure_region_requirements.apply_requirements(
                         self.infcx,
                         self.body_id,
@@ -1549,13 +1686,141 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         T: fmt::Debug + TypeFoldable<'tcx>,
     {
         debug!("normalize(value={:?}, location={:?})", value, location);
-        self.fully_perform_op(location.at_self(), |this| {
-            let mut selcx = traits::SelectionContext::new(this.infcx);
-            let cause = this.misc(this.last_span);
-            let traits::Normalized { value, obligations } =
-                traits::normalize(&mut selcx, this.param_env, cause, value);
-            Ok(InferOk { value, obligations })
-        }).unwrap()
+        if !value.has_projections() {
+            return value.clone();
+        }
+
+        value.fold_with(&mut NormalizeFolder { cx: self, location })
+    }
+
+    /// Normalizes a single projection type, reusing an earlier result
+    /// if we have one. If the projection cannot be normalized, an
+    /// error is buffered and the projection is returned unchanged.
+    fn normalize_projection_ty(&mut self, ty: Ty<'tcx>, location: Location) -> Ty<'tcx> {
+        let tcx = self.tcx();
+        let (key, regions) = self.normalization_cache_key(ty);
+        if let Some(&normalized_key) = self.normalization_cache.get(&key) {
+            let normalized_ty = tcx.fold_regions(&normalized_key, &mut false, |r, _| match *r {
+                ty::ReCanonical(var) => regions[var.index()],
+                _ => r,
+            });
+            debug!("normalize_projection_ty: {:?} cached as {:?}", ty, normalized_ty);
+            return normalized_ty;
+        }
+
+        let outlives_sets_before = self.constraints.outlives_sets.len();
+        let mut failed = false;
+        let normalized_ty = self.fully_perform_op_or_else(
+            location.at_self(),
+            |this| {
+                let cause = this.misc(this.last_span);
+                let traits::Normalized { value, obligations } =
+                    match this.infcx.at(&cause, this.param_env).normalize(&ty) {
+                        Ok(normalized) => normalized,
+
+                        // The canonical query found no solution; fall
+                        // back to the old normalizer so that the
+                        // failing obligations get reported below.
+                        Err(NoSolution) => {
+                            let mut selcx = traits::SelectionContext::new(this.infcx);
+                            traits::normalize(&mut selcx, this.param_env, cause, &ty)
+                        }
+                    };
+                Ok(InferOk { value, obligations })
+            },
+            |this, errors| {
+                debug!("normalize_projection_ty: {:?} failed with {:?}", ty, errors);
+                failed = true;
+                let span = this.last_span;
+                if this.reported_errors.insert((ty, span)) {
+                    let mut err = struct_span_err!(this.tcx().sess, span, E0697,
+                                                   "could not normalize `{}`", ty);
+                    err.buffer(&mut this.errors_buffer);
+                }
+            },
+        );
+
+        match normalized_ty {
+            Ok(normalized_ty) if !failed => {
+                let normalized_ty = self.infcx.resolve_type_vars_if_possible(&normalized_ty);
+                if self.constraints.outlives_sets.len() == outlives_sets_before {
+                    // The result can only be reused if all of its region
+                    // variables come from the projection.
+                    let mut reusable = true;
+                    let normalized_key = tcx.fold_regions(&normalized_ty, &mut false, |r, _| {
+                        match *r {
+                            ty::ReVar(_) => match regions.iter().position(|&v| v == r) {
+                                Some(index) => {
+                                    tcx.mk_region(ty::ReCanonical(CanonicalVar::new(index)))
+                                }
+                                None => {
+                                    reusable = false;
+                                    r
+                                }
+                            },
+                            _ => r,
+                        }
+                    });
+                    if reusable {
+                        self.normalization_cache.insert(key, normalized_key);
+                    }
+                }
+                normalized_ty
+            }
+            _ => ty,
+        }
+    }
+
+    /// Returns `ty` with each of its region variables replaced by a
+    /// canonical region, numbered in the order in which they first appear,
+    /// along with those region variables. Projections which only differ
+    /// in their region variables share the same key.
+    fn normalization_cache_key(&self, ty: Ty<'tcx>) -> (Ty<'tcx>, Vec<ty::Region<'tcx>>) {
+        let tcx = self.tcx();
+        let mut regions = vec![];
+        let key = tcx.fold_regions(&ty, &mut false, |r, _| match *r {
+            ty::ReVar(_) => {
+                let index = match regions.iter().position(|&v| v == r) {
+                    Some(index) => index,
+                    None => {
+                        regions.push(r);
+                        regions.len() - 1
+                    }
+                };
+                tcx.mk_region(ty::ReCanonical(CanonicalVar::new(index)))
+            }
+            _ => r,
+        });
+        (key, regions)
+    }
+}
+
+/// Normalizes each projection type in a value through
+/// `TypeChecker::normalize_projection_ty`, so that the results can be
+/// shared across locations.
+struct NormalizeFolder<'a, 'b: 'a, 'gcx: 'b + 'tcx, 'tcx: 'b> {
+    cx: &'a mut TypeChecker<'b, 'gcx, 'tcx>,
+    location: Location,
+}
+
+impl<'a, 'b, 'gcx, 'tcx> TypeFolder<'gcx, 'tcx> for NormalizeFolder<'a, 'b, 'gcx, 'tcx> {
+    fn tcx<'c>(&'c self) -> TyCtxt<'c, 'gcx, 'tcx> {
+        self.cx.infcx.tcx
+    }
+
+    fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        if !ty.has_projections() {
+            return ty;
+        }
+
+        match ty.sty {
+            // Projections that mention bound regions are left alone,
+            // as `traits::normalize` would do.
+            ty::TyProjection(_) if !ty.has_escaping_regions() => {
+                self.cx.normalize_projection_ty(ty, self.location)
+            }
+            _ => ty.super_fold_with(self),
+        }
     }
 }
 
@@ -1580,10 +1845,23 @@ impl MirPass for TypeckMir {
         }
         let param_env = tcx.param_env(def_id);
         tcx.infer_ctxt().enter(|infcx| {
-            let _ = type_check_internal(&infcx, id, param_env, mir, &[], None, &mut |_| ());
+            let mut errors_buffer = vec![];
+            let _ = type_check_internal(
+                &infcx,
+                id,
+                param_env,
+                mir,
+                &[],
+                None,
+                &mut errors_buffer,
+                &mut |_| (),
+            );
 
             // For verification purposes, we just ignore the resulting
             // region constraint sets. Not our problem. =)
+            for diag in errors_buffer {
+                DiagnosticBuilder::new_diagnostic(tcx.sess.diagnostic(), diag).emit();
+            }
         });
     }
 }
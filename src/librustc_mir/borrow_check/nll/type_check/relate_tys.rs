@@ -0,0 +1,190 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Relates two types in the MIR type checker, taking the variance of
+//! each position into account, and records the outlives constraints
+//! that result. Only the constraints that the variance requires are
+//! produced: a covariant position yields one region constraint, an
+//! invariant position two, and a bivariant position none.
+//!
+//! This handles the types that show up after renumbering, where every
+//! region is a region variable. Types that still contain type
+//! inference variables, or that require higher-ranked subtyping, are
+//! rejected with an error, and the caller falls back to relating them
+//! through the inference context.
+
+use rustc::infer::{InferCtxt, SubregionOrigin, TypeTrace};
+use rustc::infer::region_constraints::{Constraint, RegionConstraintData};
+use rustc::traits::ObligationCause;
+use rustc::ty::error::TypeError;
+use rustc::ty::relate::{self, Relate, RelateResult, TypeRelation};
+use rustc::ty::{self, Ty, TyCtxt};
+
+/// Relates `a` and `b` such that `a` is related to `b` with the
+/// variance `v` (so `ty::Covariant` means `a <: b`), returning the
+/// region constraints that must hold.
+pub(super) fn relate_types<'cx, 'gcx, 'tcx>(
+    infcx: &'cx InferCtxt<'cx, 'gcx, 'tcx>,
+    cause: &ObligationCause<'tcx>,
+    a: Ty<'tcx>,
+    v: ty::Variance,
+    b: Ty<'tcx>,
+) -> RelateResult<'tcx, RegionConstraintData<'tcx>> {
+    debug!("relate_types(a={:?}, v={:?}, b={:?})", a, v, b);
+    let mut type_relating = TypeRelating {
+        infcx,
+        ambient_variance: v,
+        origin: SubregionOrigin::Subtype(TypeTrace::types(cause, true, a, b)),
+        data: RegionConstraintData::default(),
+    };
+    type_relating.relate(&a, &b)?;
+    Ok(type_relating.data)
+}
+
+struct TypeRelating<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
+    infcx: &'cx InferCtxt<'cx, 'gcx, 'tcx>,
+
+    /// How we are relating `a` and `b`, taking into account the
+    /// positions we have passed through so far:
+    ///
+    /// - covariant means `a <: b`
+    /// - contravariant means `b <: a`
+    /// - invariant means `a == b`
+    /// - bivariant means that the two are not related at all
+    ambient_variance: ty::Variance,
+
+    /// The origin recorded for every constraint we generate.
+    origin: SubregionOrigin<'tcx>,
+
+    /// The region constraints accumulated so far.
+    data: RegionConstraintData<'tcx>,
+}
+
+impl<'cx, 'gcx, 'tcx> TypeRelating<'cx, 'gcx, 'tcx> {
+    /// Records that `sub <= sup`.
+    fn push_subregion(&mut self, sub: ty::Region<'tcx>, sup: ty::Region<'tcx>) {
+        let constraint = match (sub, sup) {
+            // All regions are subregions of `'static`, and of themselves.
+            (_, &ty::ReStatic) => return,
+            _ if sub == sup => return,
+            (&ty::ReVar(sub_id), &ty::ReVar(sup_id)) => Constraint::VarSubVar(sub_id, sup_id),
+            (_, &ty::ReVar(sup_id)) => Constraint::RegSubVar(sub, sup_id),
+            (&ty::ReVar(sub_id), _) => Constraint::VarSubReg(sub_id, sup),
+            _ => Constraint::RegSubReg(sub, sup),
+        };
+
+        debug!("push_subregion: {:?}", constraint);
+        let origin = &self.origin;
+        self.data
+            .constraints
+            .entry(constraint)
+            .or_insert_with(|| origin.clone());
+    }
+}
+
+impl<'cx, 'gcx, 'tcx> TypeRelation<'cx, 'gcx, 'tcx> for TypeRelating<'cx, 'gcx, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'cx, 'gcx, 'tcx> {
+        self.infcx.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "nll::relate_tys"
+    }
+
+    fn a_is_expected(&self) -> bool {
+        true
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        variance: ty::Variance,
+        a: &T,
+        b: &T,
+    ) -> RelateResult<'tcx, T> {
+        let old_ambient_variance = self.ambient_variance;
+        self.ambient_variance = self.ambient_variance.xform(variance);
+
+        debug!(
+            "relate_with_variance: ambient_variance = {:?}",
+            self.ambient_variance
+        );
+
+        let r = self.relate(a, b)?;
+
+        self.ambient_variance = old_ambient_variance;
+
+        Ok(r)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        // Identical types impose no constraints, whatever the variance.
+        if a == b || self.ambient_variance == ty::Bivariant {
+            return Ok(a);
+        }
+
+        match (&a.sty, &b.sty) {
+            (&ty::TyInfer(_), _) | (_, &ty::TyInfer(_)) => {
+                // Leave these to the inference context.
+                Err(TypeError::Sorts(relate::expected_found(self, &a, &b)))
+            }
+
+            _ => relate::super_relate_tys(self, a, b),
+        }
+    }
+
+    fn regions(
+        &mut self,
+        a: ty::Region<'tcx>,
+        b: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        debug!(
+            "regions(a={:?}, b={:?}, ambient_variance={:?})",
+            a, b, self.ambient_variance
+        );
+
+        match (a, b) {
+            (&ty::ReLateBound(..), _) | (_, &ty::ReLateBound(..)) => {
+                // Bound regions only relate to themselves here; anything
+                // else needs higher-ranked subtyping.
+                return if a == b {
+                    Ok(a)
+                } else {
+                    Err(TypeError::RegionsDoesNotOutlive(a, b))
+                };
+            }
+            _ => {}
+        }
+
+        match self.ambient_variance {
+            ty::Covariant => self.push_subregion(a, b),
+            ty::Contravariant => self.push_subregion(b, a),
+            ty::Invariant => {
+                self.push_subregion(a, b);
+                self.push_subregion(b, a);
+            }
+            ty::Bivariant => {}
+        }
+
+        Ok(a)
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: &ty::Binder<T>,
+        b: &ty::Binder<T>,
+    ) -> RelateResult<'tcx, ty::Binder<T>>
+    where
+        T: Relate<'tcx>,
+    {
+        // The bound regions on either side must line up exactly (see
+        // `regions`), so we can relate the contents directly.
+        Ok(ty::Binder(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
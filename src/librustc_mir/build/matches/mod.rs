@@ -185,21 +185,39 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
             next_candidate_pre_binding_block: block
         };
 
-        // Simplify the candidate. Since the pattern is irrefutable, this should
-        // always convert all match-pairs into bindings.
+        // Simplify the candidate. Since the pattern is irrefutable, this
+        // converts all match-pairs into bindings, except for or-patterns.
         unpack!(block = self.simplify_candidate(block, &mut candidate));
 
-        if !candidate.match_pairs.is_empty() {
-            span_bug!(candidate.match_pairs[0].pattern.span,
-                      "match pairs {:?} remaining after simplifying \
-                       irrefutable pattern",
-                      candidate.match_pairs);
+        if candidate.match_pairs.is_empty() {
+            // now apply the bindings, which will also declare the variables
+            self.bind_matched_candidate(block, candidate.bindings);
+            return block.unit();
         }
 
-        // now apply the bindings, which will also declare the variables
-        self.bind_matched_candidate(block, candidate.bindings);
+        // An irrefutable or-pattern still has to find out which of its
+        // alternatives matched, so run the main match algorithm with a
+        // single "arm" that continues after the pattern.
+        let source_info = self.source_info(irrefutable_pat.span);
+        let mut arm_blocks = ArmBlocks {
+            blocks: vec![self.cfg.start_new_block()],
+        };
+        candidate.pre_binding_block = self.cfg.start_new_block();
+        candidate.next_candidate_pre_binding_block = self.cfg.start_new_block();
+        self.cfg.terminate(candidate.next_candidate_pre_binding_block,
+                           source_info, TerminatorKind::Unreachable);
+
+        let mut otherwise = self.match_candidates(irrefutable_pat.span,
+                                                  &mut arm_blocks,
+                                                  vec![candidate],
+                                                  block);
+        otherwise.sort();
+        otherwise.dedup();
+        for block in otherwise {
+            self.cfg.terminate(block, source_info, TerminatorKind::Unreachable);
+        }
 
-        block.unit()
+        arm_blocks.blocks[0].unit()
     }
 
     /// Declares the bindings of the given pattern and returns the visibility scope
@@ -283,6 +301,11 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                     self.visit_bindings(&subpattern.pattern, f);
                 }
             }
+            PatternKind::Or { ref pats } => {
+                // all alternatives bind the same variables, and the bindings
+                // of later alternatives refer to those of the first one
+                self.visit_bindings(&pats[0], f);
+            }
         }
     }
 }
@@ -480,6 +503,16 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
             return vec![block];
         }
 
+        // An or-pattern is never tested directly. Instead, the candidate is
+        // replaced by one candidate per alternative, in order, which are
+        // then matched like any other candidates.
+        if let Some(expanded) = self.expand_or_candidate(&unmatched_candidates[0]) {
+            let candidates = expanded.into_iter()
+                                     .chain(unmatched_candidates.drain(1..))
+                                     .collect();
+            return self.match_candidates(span, arm_blocks, candidates, block);
+        }
+
         // Test candidates where possible.
         let (otherwise, tested_candidates) =
             self.test_candidates(span, arm_blocks, &unmatched_candidates, block);
@@ -499,6 +532,47 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
         self.match_candidates(span, arm_blocks, untested_candidates, join_block)
     }
 
+    /// If the first match-pair of `candidate` is an or-pattern, returns one
+    /// candidate per alternative. The new candidates are chained by false
+    /// edges between the pre-binding blocks of `candidate` and of its
+    /// successor.
+    fn expand_or_candidate<'pat>(&mut self,
+                                 candidate: &Candidate<'pat, 'tcx>)
+                                 -> Option<Vec<Candidate<'pat, 'tcx>>>
+    {
+        let pattern: &'pat Pattern<'tcx> = candidate.match_pairs[0].pattern;
+        let pats = match *pattern.kind {
+            PatternKind::Or { ref pats } => pats,
+            _ => return None,
+        };
+        debug!("expand_or_candidate: expanding {:?}", pattern);
+
+        let pre_binding_blocks: Vec<_> =
+            pats.iter().map(|_| self.cfg.start_new_block()).collect();
+        let source_info = self.source_info(candidate.span);
+        self.cfg.terminate(candidate.pre_binding_block, source_info,
+                           TerminatorKind::Goto { target: pre_binding_blocks[0] });
+        let next_blocks = pre_binding_blocks[1..].iter()
+            .cloned()
+            .chain(Some(candidate.next_candidate_pre_binding_block));
+
+        let place = &candidate.match_pairs[0].place;
+        Some(pats.iter().zip(pre_binding_blocks.iter().zip(next_blocks)).map(
+            |(pat, (&pre_binding_block, next_candidate_pre_binding_block))| {
+                let mut match_pairs = vec![MatchPair::new(place.clone(), pat)];
+                match_pairs.extend(candidate.match_pairs[1..].iter().cloned());
+                Candidate {
+                    span: candidate.span,
+                    match_pairs,
+                    bindings: candidate.bindings.clone(),
+                    guard: candidate.guard.clone(),
+                    arm_index: candidate.arm_index,
+                    pre_binding_block,
+                    next_candidate_pre_binding_block,
+                }
+            }).collect())
+    }
+
     fn join_otherwise_blocks(&mut self,
                              span: Span,
                              mut otherwise: Vec<BasicBlock>)
@@ -747,6 +821,9 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                                        vec![candidate.next_candidate_pre_binding_block],
                                });
 
+        let binding_locals: Vec<_> = candidate.bindings.iter()
+            .map(|binding| self.var_indices[&binding.var_id])
+            .collect();
         self.bind_matched_candidate(block, candidate.bindings);
 
         if let Some(guard) = candidate.guard {
@@ -756,7 +833,23 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
             let source_info = self.source_info(guard.span);
             let cond = unpack!(block = self.as_local_operand(block, guard));
 
+            // If the guard fails, the bindings (and the temporary holding
+            // the guard's value) are dead: another candidate of the same
+            // arm, or the next iteration of an enclosing loop, will mark
+            // them live again.
             let false_edge_block = self.cfg.start_new_block();
+            if let Operand::Move(Place::Local(temp)) = cond {
+                self.cfg.push(false_edge_block, Statement {
+                    source_info,
+                    kind: StatementKind::StorageDead(temp)
+                });
+            }
+            for &local in binding_locals.iter().rev() {
+                self.cfg.push(false_edge_block, Statement {
+                    source_info,
+                    kind: StatementKind::StorageDead(local)
+                });
+            }
             self.cfg.terminate(block, source_info,
                                TerminatorKind::if_(self.hir.tcx(), cond, arm_block,
                                    false_edge_block));
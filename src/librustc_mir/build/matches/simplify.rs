@@ -96,6 +96,11 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                 Err(match_pair)
             }
 
+            PatternKind::Or { .. } => {
+                // expanded into one candidate per alternative by `match_candidates`
+                Err(match_pair)
+            }
+
             PatternKind::Slice { ref prefix, ref slice, ref suffix } => {
                 if prefix.is_empty() && slice.is_some() && suffix.is_empty() {
                     // irrefutable
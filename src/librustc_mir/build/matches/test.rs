@@ -104,6 +104,12 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
             PatternKind::Deref { .. } => {
                 self.error_simplifyable(match_pair)
             }
+
+            PatternKind::Or { .. } => {
+                span_bug!(match_pair.pattern.span,
+                          "or-pattern {:?} should have been expanded into candidates",
+                          match_pair.pattern)
+            }
         }
     }
 
@@ -141,7 +147,8 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
             PatternKind::Wild |
             PatternKind::Binding { .. } |
             PatternKind::Leaf { .. } |
-            PatternKind::Deref { .. } => {
+            PatternKind::Deref { .. } |
+            PatternKind::Or { .. } => {
                 // don't know how to add these patterns to a switch
                 false
             }
@@ -601,6 +608,10 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                 }
             }
 
+            // An or-pattern is only ever tested through its alternatives,
+            // once its candidate has been expanded.
+            (_, &PatternKind::Or { .. }) => false,
+
             (&TestKind::Eq { .. }, _) |
             (&TestKind::Range { .. }, _) |
             (&TestKind::Len { .. }, _) => {
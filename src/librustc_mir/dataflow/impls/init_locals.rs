@@ -0,0 +1,164 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub use super::*;
+
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use dataflow::BitDenotation;
+
+/// This calculates which locals may hold a value: a local holds a value from
+/// the moment any part of it is assigned to until it is moved out of as a
+/// whole, dropped or its storage is marked dead. The arguments hold a value
+/// on function entry.
+///
+/// Moves out of a local take effect just before the statement or terminator
+/// doing them, so that the state they see is the one in which the locals
+/// they write are going to be assigned to.
+#[derive(Copy, Clone)]
+pub struct MaybeInitializedLocals<'a, 'tcx: 'a> {
+    mir: &'a Mir<'tcx>,
+}
+
+impl<'a, 'tcx: 'a> MaybeInitializedLocals<'a, 'tcx> {
+    pub fn new(mir: &'a Mir<'tcx>)
+               -> Self {
+        MaybeInitializedLocals { mir: mir }
+    }
+
+    pub fn mir(&self) -> &Mir<'tcx> {
+        self.mir
+    }
+}
+
+impl<'a, 'tcx> BitDenotation for MaybeInitializedLocals<'a, 'tcx> {
+    type Idx = Local;
+    fn name() -> &'static str { "maybe_init_locals" }
+    fn bits_per_block(&self) -> usize {
+        self.mir.local_decls.len()
+    }
+
+    fn start_block_effect(&self, sets: &mut IdxSet<Local>) {
+        for arg in self.mir.args_iter() {
+            sets.add(&arg);
+        }
+    }
+
+    fn before_statement_effect(&self,
+                               sets: &mut BlockSets<Local>,
+                               loc: Location) {
+        MovedLocalsVisitor {
+            sets,
+        }.visit_statement(loc.block, &self.mir[loc.block].statements[loc.statement_index], loc);
+    }
+
+    fn statement_effect(&self,
+                        sets: &mut BlockSets<Local>,
+                        loc: Location) {
+        match self.mir[loc.block].statements[loc.statement_index].kind {
+            StatementKind::Assign(ref place, _) |
+            StatementKind::SetDiscriminant { ref place, .. } => assign(sets, place),
+            StatementKind::InlineAsm { ref outputs, .. } => {
+                for place in outputs {
+                    assign(sets, place);
+                }
+            }
+            StatementKind::StorageDead(local) => sets.kill(&local),
+            _ => {}
+        }
+    }
+
+    fn before_terminator_effect(&self,
+                                sets: &mut BlockSets<Local>,
+                                loc: Location) {
+        MovedLocalsVisitor {
+            sets,
+        }.visit_terminator(loc.block, self.mir[loc.block].terminator(), loc);
+    }
+
+    fn terminator_effect(&self,
+                         sets: &mut BlockSets<Local>,
+                         loc: Location) {
+        // The destination of a call is only assigned on the return edge,
+        // see `propagate_call_return`.
+        match self.mir[loc.block].terminator().kind {
+            TerminatorKind::Drop { location: Place::Local(local), .. } => sets.kill(&local),
+            TerminatorKind::DropAndReplace { ref location, .. } => assign(sets, location),
+            _ => {}
+        }
+    }
+
+    fn propagate_call_return(&self,
+                             in_out: &mut IdxSet<Local>,
+                             _call_bb: mir::BasicBlock,
+                             _dest_bb: mir::BasicBlock,
+                             dest_place: &mir::Place) {
+        if let Some(local) = find_local(dest_place) {
+            in_out.add(&local);
+        }
+    }
+}
+
+impl<'a, 'tcx> BitwiseOperator for MaybeInitializedLocals<'a, 'tcx> {
+    #[inline]
+    fn join(&self, pred1: usize, pred2: usize) -> usize {
+        pred1 | pred2 // "maybe" means we union effects of both preds
+    }
+}
+
+impl<'a, 'tcx> InitialFlow for MaybeInitializedLocals<'a, 'tcx> {
+    #[inline]
+    fn bottom_value() -> bool {
+        false // bottom = uninitialized
+    }
+}
+
+fn find_local<'tcx>(place: &Place<'tcx>) -> Option<Local> {
+    match *place {
+        Place::Local(l) => Some(l),
+        Place::Static(..) => None,
+        Place::Projection(ref proj) => {
+            match proj.elem {
+                ProjectionElem::Deref => None,
+                _ => find_local(&proj.base)
+            }
+        }
+    }
+}
+
+fn assign<'tcx>(sets: &mut BlockSets<Local>, place: &Place<'tcx>) {
+    if let Some(local) = find_local(place) {
+        sets.gen(&local);
+    }
+}
+
+struct MovedLocalsVisitor<'b, 'c: 'b> {
+    sets: &'b mut BlockSets<'c, Local>,
+}
+
+impl<'tcx, 'b, 'c> Visitor<'tcx> for MovedLocalsVisitor<'b, 'c> {
+    fn visit_operand(&mut self,
+                     operand: &Operand<'tcx>,
+                     location: Location) {
+        if let Operand::Move(Place::Local(local)) = *operand {
+            self.sets.kill(&local);
+        }
+
+        self.super_operand(operand, location)
+    }
+
+    fn visit_place(&mut self,
+                   _place: &Place<'tcx>,
+                   _context: PlaceContext<'tcx>,
+                   _location: Location) {
+        // Only whole locals which are moved out of are of interest, and
+        // those are found by `visit_operand`.
+    }
+}
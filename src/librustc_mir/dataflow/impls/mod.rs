@@ -37,6 +37,14 @@ mod borrowed_locals;
 
 pub use self::borrowed_locals::*;
 
+mod unassigned_temps;
+
+pub use self::unassigned_temps::*;
+
+mod init_locals;
+
+pub use self::init_locals::*;
+
 #[allow(dead_code)]
 pub(super) mod borrows;
 
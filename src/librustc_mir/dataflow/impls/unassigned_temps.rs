@@ -0,0 +1,166 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub use super::*;
+
+use rustc::mir::*;
+use rustc::mir::visit::Visitor;
+use dataflow::BitDenotation;
+
+/// This calculates which temporaries may not have been assigned since the
+/// function was entered or since their storage was last marked live or dead.
+/// MIR building only ever reads a temporary after assigning to it, and the
+/// MIR validator uses this analysis to check that passes keep it that way.
+///
+/// Assigning to any part of a temporary, or borrowing any part of it, counts
+/// as assigning to all of it.
+#[derive(Copy, Clone)]
+pub struct MaybeUnassignedTemps<'a, 'tcx: 'a> {
+    mir: &'a Mir<'tcx>,
+}
+
+impl<'a, 'tcx: 'a> MaybeUnassignedTemps<'a, 'tcx> {
+    pub fn new(mir: &'a Mir<'tcx>)
+               -> Self {
+        MaybeUnassignedTemps { mir: mir }
+    }
+
+    pub fn mir(&self) -> &Mir<'tcx> {
+        self.mir
+    }
+}
+
+impl<'a, 'tcx> BitDenotation for MaybeUnassignedTemps<'a, 'tcx> {
+    type Idx = Local;
+    fn name() -> &'static str { "maybe_unassigned_temps" }
+    fn bits_per_block(&self) -> usize {
+        self.mir.local_decls.len()
+    }
+
+    fn start_block_effect(&self, sets: &mut IdxSet<Local>) {
+        // No temporary has been assigned on function entry
+        for local in self.mir.temps_iter() {
+            sets.add(&local);
+        }
+    }
+
+    fn statement_effect(&self,
+                        sets: &mut BlockSets<Local>,
+                        loc: Location) {
+        AssignedTempsVisitor {
+            mir: self.mir,
+            sets,
+        }.visit_statement(loc.block, &self.mir[loc.block].statements[loc.statement_index], loc);
+    }
+
+    fn terminator_effect(&self,
+                         sets: &mut BlockSets<Local>,
+                         loc: Location) {
+        // The destination of a call is only assigned on the return edge,
+        // see `propagate_call_return`.
+        if let TerminatorKind::DropAndReplace { ref location, .. } =
+            self.mir[loc.block].terminator().kind
+        {
+            if let Some(local) = find_local(location) {
+                if self.mir.local_kind(local) == LocalKind::Temp {
+                    sets.kill(&local);
+                }
+            }
+        }
+    }
+
+    fn propagate_call_return(&self,
+                             in_out: &mut IdxSet<Local>,
+                             _call_bb: mir::BasicBlock,
+                             _dest_bb: mir::BasicBlock,
+                             dest_place: &mir::Place) {
+        if let Some(local) = find_local(dest_place) {
+            in_out.remove(&local);
+        }
+    }
+}
+
+impl<'a, 'tcx> BitwiseOperator for MaybeUnassignedTemps<'a, 'tcx> {
+    #[inline]
+    fn join(&self, pred1: usize, pred2: usize) -> usize {
+        pred1 | pred2 // "maybe" means we union effects of both preds
+    }
+}
+
+impl<'a, 'tcx> InitialFlow for MaybeUnassignedTemps<'a, 'tcx> {
+    #[inline]
+    fn bottom_value() -> bool {
+        false // bottom = assigned
+    }
+}
+
+struct AssignedTempsVisitor<'a, 'tcx: 'a, 'b, 'c: 'b> {
+    mir: &'a Mir<'tcx>,
+    sets: &'b mut BlockSets<'c, Local>,
+}
+
+fn find_local<'tcx>(place: &Place<'tcx>) -> Option<Local> {
+    match *place {
+        Place::Local(l) => Some(l),
+        Place::Static(..) => None,
+        Place::Projection(ref proj) => {
+            match proj.elem {
+                ProjectionElem::Deref => None,
+                _ => find_local(&proj.base)
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx, 'b, 'c> AssignedTempsVisitor<'a, 'tcx, 'b, 'c> {
+    fn assign(&mut self, place: &Place<'tcx>) {
+        if let Some(local) = find_local(place) {
+            if self.mir.local_kind(local) == LocalKind::Temp {
+                self.sets.kill(&local);
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx, 'b, 'c> Visitor<'tcx> for AssignedTempsVisitor<'a, 'tcx, 'b, 'c> {
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       location: Location) {
+        match statement.kind {
+            StatementKind::Assign(ref place, _) |
+            StatementKind::SetDiscriminant { ref place, .. } => self.assign(place),
+            StatementKind::InlineAsm { ref outputs, .. } => {
+                for place in outputs {
+                    self.assign(place);
+                }
+            }
+            StatementKind::StorageLive(local) |
+            StatementKind::StorageDead(local) => {
+                if self.mir.local_kind(local) == LocalKind::Temp {
+                    self.sets.gen(&local);
+                }
+            }
+            _ => {}
+        }
+
+        self.super_statement(block, statement, location)
+    }
+
+    fn visit_rvalue(&mut self,
+                    rvalue: &Rvalue<'tcx>,
+                    location: Location) {
+        if let Rvalue::Ref(_, _, ref place) = *rvalue {
+            self.assign(place);
+        }
+
+        self.super_rvalue(rvalue, location)
+    }
+}
@@ -2321,6 +2321,102 @@ b.resume();
 ```
 "##,
 
+E0694: r##"
+A lifetime in a function signature was required to outlive another lifetime,
+but nothing in the signature says that it does. This error is reported by the
+MIR borrow checker.
+
+Erroneous code example:
+
+```compile_fail,E0694
+#![feature(nll)]
+
+fn foo<'a, 'b>(x: &'a u32) -> &'b u32 {
+    x // error: free region `'a` does not outlive free region `'b`
+}
+```
+
+Here `foo` promises to return a reference that is valid for `'b`, but the
+reference it returns is only known to be valid for `'a`. The caller picks both
+lifetimes, so `'a` could end long before `'b` does.
+
+To fix this, either return a reference with the right lifetime, or declare that
+`'a` outlives `'b`:
+
+```
+#![feature(nll)]
+
+fn foo<'a: 'b, 'b>(x: &'a u32) -> &'b u32 {
+    x // ok!
+}
+```
+"##,
+
+E0695: r##"
+A value of a generic type was required to outlive a region, but the MIR borrow
+checker could not find a single lifetime that it is known to outlive.
+
+This happens when a reference to a value of a generic type has to be valid for
+a lifetime which is made up of several unrelated lifetimes of the signature,
+none of which the type is known to outlive. To fix it, add a bound requiring
+that the type outlives the lifetimes in question, for example `T: 'a + 'b`.
+"##,
+
+E0696: r##"
+A closure stored borrowed data in a place that lives outside of the closure,
+but the data is only known to be valid while the closure runs. This error is
+reported by the MIR borrow checker.
+
+Erroneous code example:
+
+```compile_fail,E0696
+#![feature(nll)]
+
+use std::cell::Cell;
+
+fn foo<'a, F>(_cell: Cell<&'a u32>, _f: F)
+where
+    F: for<'x> FnOnce(Cell<&'a u32>, Cell<&'x u32>),
+{
+}
+
+fn main() {
+    let a = 0;
+    let cell = Cell::new(&a);
+    foo(cell, |cell_a, cell_x| {
+        cell_a.set(cell_x.get()); // error: borrowed data escapes outside of closure
+    })
+}
+```
+
+Here the closure can be called with a `cell_x` of any lifetime `'x`, but it
+stores the reference inside `cell_x` into `cell_a`, which lives on after the
+closure returns. The reference could then outlive the data it points to.
+
+To fix this, make sure the borrowed data lives as long as the place it is
+stored in, for example by tying both to the same lifetime:
+
+```
+#![feature(nll)]
+
+use std::cell::Cell;
+
+fn foo<'a, F>(_cell: Cell<&'a u32>, _f: F)
+where
+    F: FnOnce(Cell<&'a u32>, Cell<&'a u32>),
+{
+}
+
+fn main() {
+    let a = 0;
+    let cell = Cell::new(&a);
+    foo(cell, |cell_a, cell_x| {
+        cell_a.set(cell_x.get()); // ok!
+    })
+}
+```
+"##,
+
 }
 
 register_diagnostics! {
@@ -2334,4 +2430,5 @@ register_diagnostics! {
     E0594, // cannot assign to {}
     E0598, // lifetime of {} is too short to guarantee its contents can be...
     E0625, // thread-local statics cannot be accessed at compile-time
+    E0697, // could not normalize an associated type while checking MIR
 }
@@ -28,6 +28,7 @@ use rustc::mir::Field;
 use rustc::mir::interpret::{Value, PrimVal};
 use rustc::util::common::ErrorReported;
 
+use syntax::ast::{IntTy, UintTy};
 use syntax_pos::{Span, DUMMY_SP};
 
 use arena::TypedArena;
@@ -84,7 +85,19 @@ impl<'a, 'tcx> Matrix<'a, 'tcx> {
         Matrix(vec![])
     }
 
+    /// Pushes a row, expanding an or-pattern in its first column into one
+    /// row per alternative so that the first column never holds an `Or`.
     pub fn push(&mut self, row: Vec<&'a Pattern<'tcx>>) {
+        if let Some(&first) = row.first() {
+            if let PatternKind::Or { ref pats } = *first.kind {
+                for alternative in pats {
+                    let mut new_row = vec![alternative];
+                    new_row.extend_from_slice(&row[1..]);
+                    self.push(new_row);
+                }
+                return;
+            }
+        }
         self.0.push(row)
     }
 }
@@ -136,7 +149,11 @@ impl<'a, 'tcx> fmt::Debug for Matrix<'a, 'tcx> {
 impl<'a, 'tcx> FromIterator<Vec<&'a Pattern<'tcx>>> for Matrix<'a, 'tcx> {
     fn from_iter<T: IntoIterator<Item=Vec<&'a Pattern<'tcx>>>>(iter: T) -> Self
     {
-        Matrix(iter.into_iter().collect())
+        let mut matrix = Matrix::empty();
+        for row in iter {
+            matrix.push(row);
+        }
+        matrix
     }
 }
 
@@ -218,8 +235,15 @@ impl<'a, 'tcx> MatchCheckCtxt<'a, 'tcx> {
         }).clone()
     }
 
+    /// Whether the uninhabitedness of types should be taken into account when
+    /// checking patterns, e.g. so that `Ok(x)` is irrefutable for `Result<T, !>`.
+    fn check_uninhabited(&self) -> bool {
+        let features = self.tcx.features();
+        features.exhaustive_patterns || features.never_type
+    }
+
     fn is_uninhabited(&self, ty: Ty<'tcx>) -> bool {
-        if self.tcx.features().never_type {
+        if self.check_uninhabited() {
             self.tcx.is_ty_uninhabited_from(self.module, ty)
         } else {
             false
@@ -245,7 +269,7 @@ impl<'a, 'tcx> MatchCheckCtxt<'a, 'tcx> {
                               substs: &'tcx ty::subst::Substs<'tcx>)
                               -> bool
     {
-        if self.tcx.features().never_type {
+        if self.check_uninhabited() {
             self.tcx.is_enum_variant_uninhabited_from(self.module, variant, substs)
         } else {
             false
@@ -404,6 +428,7 @@ impl<'tcx> Witness<'tcx> {
                 _ => {
                     match *ctor {
                         ConstantValue(value) => PatternKind::Constant { value },
+                        ConstantRange(lo, hi, end) => PatternKind::Range { lo, hi, end },
                         _ => PatternKind::Wild,
                     }
                 }
@@ -427,7 +452,9 @@ impl<'tcx> Witness<'tcx> {
 ///
 /// This intentionally does not list ConstantValue specializations for
 /// non-booleans, because we currently assume that there is always a
-/// "non-standard constant" that matches. See issue #12483.
+/// "non-standard constant" that matches. See issue #12483. The exception
+/// are integers under `exhaustive_integer_patterns`, whose values are all
+/// covered by a single range spanning the whole type.
 ///
 /// We make sure to omit constructors that are statically impossible. eg for
 /// Option<!> we do not include Some(_) in the returned list of constructors.
@@ -468,6 +495,15 @@ fn all_constructors<'a, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
                 .map(|v| Variant(v.did))
                 .collect()
         }
+        // The range of `usize` and `isize` depends on the target, so matching
+        // on them must not be considered exhaustive just because the arms
+        // cover the range of the current target.
+        ty::TyInt(_) | ty::TyUint(_)
+            if cx.tcx.features().exhaustive_integer_patterns && !is_pointer_sized(pcx.ty) => {
+            let (bits, _) = int_size_and_signedness(cx.tcx, pcx.ty).unwrap();
+            let max = !0u128 >> (128 - bits);
+            vec![IntRange { lo: 0, hi: max, ty: pcx.ty }.into_ctor(cx.tcx)]
+        }
         _ => {
             if cx.is_uninhabited(pcx.ty) {
                 vec![]
@@ -478,6 +514,219 @@ fn all_constructors<'a, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
     }
 }
 
+fn is_pointer_sized(ty: Ty) -> bool {
+    match ty.sty {
+        ty::TyInt(IntTy::Isize) | ty::TyUint(UintTy::Usize) => true,
+        _ => false,
+    }
+}
+
+/// Returns the size in bits and the signedness of an integral type, or `None`
+/// for any other type.
+fn int_size_and_signedness<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, ty: Ty<'tcx>)
+                                     -> Option<(u32, bool)>
+{
+    match ty.sty {
+        ty::TyInt(ity) => {
+            let ity = if ity == IntTy::Isize { tcx.sess.target.isize_ty } else { ity };
+            Some((ity.bit_width().unwrap() as u32, true))
+        }
+        ty::TyUint(uty) => {
+            let uty = if uty == UintTy::Usize { tcx.sess.target.usize_ty } else { uty };
+            Some((uty.bit_width().unwrap() as u32, false))
+        }
+        _ => None,
+    }
+}
+
+/// An inclusive range of integer values, used for precise integer
+/// exhaustiveness checking. The bounds are offset so that the minimum value
+/// of the type is always encoded as `0`, which lets signed and unsigned
+/// ranges be compared and split in the same way.
+#[derive(Clone, Debug)]
+struct IntRange<'tcx> {
+    lo: u128,
+    hi: u128,
+    ty: Ty<'tcx>,
+}
+
+impl<'tcx> IntRange<'tcx> {
+    fn from_ctor<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                     ctor: &Constructor<'tcx>)
+                     -> Option<IntRange<'tcx>> {
+        match *ctor {
+            ConstantValue(value) => {
+                let value = Self::encode(tcx, value)?;
+                Some(IntRange { lo: value, hi: value, ty: value_ty(ctor) })
+            }
+            ConstantRange(lo, hi, end) => {
+                let (lo, hi) = (Self::encode(tcx, lo)?, Self::encode(tcx, hi)?);
+                let hi = match end {
+                    RangeEnd::Included => hi,
+                    // `lo..hi` with `lo >= hi` is rejected when lowering the pattern.
+                    RangeEnd::Excluded => hi.checked_sub(1)?,
+                };
+                Some(IntRange { lo, hi, ty: value_ty(ctor) })
+            }
+            _ => None,
+        }
+    }
+
+    fn from_pat<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                    pat: &Pattern<'tcx>)
+                    -> Option<IntRange<'tcx>> {
+        match *pat.kind {
+            PatternKind::Constant { value } => Self::from_ctor(tcx, &ConstantValue(value)),
+            PatternKind::Range { lo, hi, end } => {
+                Self::from_ctor(tcx, &ConstantRange(lo, hi, end))
+            }
+            _ => None,
+        }
+    }
+
+    fn encode<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>, value: &ty::Const<'tcx>) -> Option<u128> {
+        let (bits, signed) = int_size_and_signedness(tcx, value.ty)?;
+        let raw = value.val.to_raw_bits()? & (!0u128 >> (128 - bits));
+        if signed {
+            Some(raw ^ (1u128 << (bits - 1)))
+        } else {
+            Some(raw)
+        }
+    }
+
+    fn decode<'a>(&self, tcx: TyCtxt<'a, 'tcx, 'tcx>, encoded: u128) -> &'tcx ty::Const<'tcx> {
+        let (bits, signed) = int_size_and_signedness(tcx, self.ty).unwrap();
+        let raw = if signed {
+            // Signed constants are stored sign-extended to 128 bits.
+            let shift = 128 - bits;
+            ((((encoded ^ (1u128 << (bits - 1))) << shift) as i128) >> shift) as u128
+        } else {
+            encoded
+        };
+        tcx.mk_const(ty::Const {
+            val: ConstVal::Value(Value::ByVal(PrimVal::Bytes(raw))),
+            ty: self.ty,
+        })
+    }
+
+    fn into_ctor<'a>(self, tcx: TyCtxt<'a, 'tcx, 'tcx>) -> Constructor<'tcx> {
+        if self.lo == self.hi {
+            ConstantValue(self.decode(tcx, self.lo))
+        } else {
+            ConstantRange(self.decode(tcx, self.lo), self.decode(tcx, self.hi), RangeEnd::Included)
+        }
+    }
+
+    fn intersection(&self, other: &IntRange<'tcx>) -> Option<IntRange<'tcx>> {
+        let lo = cmp::max(self.lo, other.lo);
+        let hi = cmp::min(self.hi, other.hi);
+        if lo <= hi {
+            Some(IntRange { lo, hi, ty: self.ty })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the (at most two) parts of `self` that are not covered by `other`.
+    fn subtract(&self, other: &IntRange<'tcx>) -> Vec<IntRange<'tcx>> {
+        if self.intersection(other).is_none() {
+            return vec![self.clone()];
+        }
+        let mut remaining = vec![];
+        if other.lo > self.lo {
+            remaining.push(IntRange { lo: self.lo, hi: other.lo - 1, ty: self.ty });
+        }
+        if other.hi < self.hi {
+            remaining.push(IntRange { lo: other.hi + 1, hi: self.hi, ty: self.ty });
+        }
+        remaining
+    }
+}
+
+fn value_ty<'tcx>(ctor: &Constructor<'tcx>) -> Ty<'tcx> {
+    match *ctor {
+        ConstantValue(value) | ConstantRange(value, ..) => value.ty,
+        _ => bug!("constructor {:?} has no value", ctor),
+    }
+}
+
+/// Computes the constructors of `all_ctors` that aren't covered by any of
+/// `used_ctors`. Integer ranges can be partially covered by several used
+/// constructors, so only their uncovered parts are returned.
+fn compute_missing_ctors<'a, 'tcx: 'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                       all_ctors: &[Constructor<'tcx>],
+                                       used_ctors: &[Constructor<'tcx>])
+                                       -> Vec<Constructor<'tcx>>
+{
+    let mut missing_ctors = vec![];
+    for ctor in all_ctors {
+        match IntRange::from_ctor(tcx, ctor) {
+            Some(range) => {
+                let mut remaining = vec![range];
+                for used_range in used_ctors.iter().filter_map(|c| IntRange::from_ctor(tcx, c)) {
+                    remaining = remaining.iter().flat_map(|r| r.subtract(&used_range)).collect();
+                    if remaining.is_empty() {
+                        break;
+                    }
+                }
+                missing_ctors.extend(remaining.into_iter().map(|r| r.into_ctor(tcx)));
+            }
+            None => {
+                if !used_ctors.contains(ctor) {
+                    missing_ctors.push(ctor.clone());
+                }
+            }
+        }
+    }
+    missing_ctors
+}
+
+/// Splits the integer range constructors in `ctors` at the boundaries of the
+/// ranges matched by the first column of `matrix`, so that each resulting
+/// range is either entirely inside or entirely outside every row's range.
+/// Specializing the matrix by such a range then gives an exact answer, where
+/// the whole range would only match rows that cover all of it.
+fn split_grouped_constructors<'p, 'a: 'p, 'tcx: 'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                                    ctors: Vec<Constructor<'tcx>>,
+                                                    &Matrix(ref m): &Matrix<'p, 'tcx>)
+                                                    -> Vec<Constructor<'tcx>>
+{
+    if !tcx.features().exhaustive_integer_patterns {
+        return ctors;
+    }
+
+    let mut split_ctors = Vec::with_capacity(ctors.len());
+    for ctor in ctors {
+        let range = match IntRange::from_ctor(tcx, &ctor) {
+            Some(range) => range,
+            None => {
+                split_ctors.push(ctor);
+                continue;
+            }
+        };
+
+        // The start of each subrange of `range`.
+        let mut borders = vec![range.lo];
+        for row in m {
+            let overlap = IntRange::from_pat(tcx, row[0]).and_then(|r| r.intersection(&range));
+            if let Some(overlap) = overlap {
+                borders.push(overlap.lo);
+                if overlap.hi < range.hi {
+                    borders.push(overlap.hi + 1);
+                }
+            }
+        }
+        borders.sort();
+        borders.dedup();
+
+        for (i, &lo) in borders.iter().enumerate() {
+            let hi = borders.get(i + 1).map_or(range.hi, |&next| next - 1);
+            split_ctors.push(IntRange { lo, hi, ty: range.ty }.into_ctor(tcx));
+        }
+    }
+    split_ctors
+}
+
 fn max_slice_length<'p, 'a: 'p, 'tcx: 'a, I>(
     cx: &mut MatchCheckCtxt<'a, 'tcx>,
     patterns: I) -> u64
@@ -634,6 +883,30 @@ pub fn is_useful<'p, 'a: 'p, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
 
     assert!(rows.iter().all(|r| r.len() == v.len()));
 
+    // An or-pattern is useful if any of its alternatives is. The matrix
+    // never has an or-pattern in its first column, see `Matrix::push`.
+    if let PatternKind::Or { ref pats } = *v[0].kind {
+        let mut witnesses = vec![];
+        let mut useful = false;
+        for alternative in pats {
+            let mut new_v = vec![alternative];
+            new_v.extend_from_slice(&v[1..]);
+            match is_useful(cx, matrix, &new_v, witness) {
+                UsefulWithWitness(pats) => {
+                    useful = true;
+                    witnesses.extend(pats);
+                }
+                Useful => useful = true,
+                NotUseful => {}
+            }
+        }
+        return match (useful, witness) {
+            (false, _) => NotUseful,
+            (true, ConstructWitness) => UsefulWithWitness(witnesses),
+            (true, LeaveOutWitness) => Useful,
+        };
+    }
+
     let pcx = PatternContext {
         // TyErr is used to represent the type of wildcard patterns matching
         // against inaccessible (private) fields of structs, so that we won't
@@ -663,7 +936,7 @@ pub fn is_useful<'p, 'a: 'p, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
 
     if let Some(constructors) = pat_constructors(cx, v[0], pcx) {
         debug!("is_useful - expanding constructors: {:#?}", constructors);
-        constructors.into_iter().map(|c|
+        split_grouped_constructors(cx.tcx, constructors, matrix).into_iter().map(|c|
             is_useful_specialized(cx, matrix, v, c.clone(), pcx.ty, witness)
         ).find(|result| result.is_useful()).unwrap_or(NotUseful)
     } else {
@@ -675,9 +948,7 @@ pub fn is_useful<'p, 'a: 'p, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
         debug!("used_ctors = {:#?}", used_ctors);
         let all_ctors = all_constructors(cx, pcx);
         debug!("all_ctors = {:#?}", all_ctors);
-        let missing_ctors: Vec<Constructor> = all_ctors.iter().filter(|c| {
-            !used_ctors.contains(*c)
-        }).cloned().collect();
+        let missing_ctors = compute_missing_ctors(cx.tcx, &all_ctors, &used_ctors);
 
         // `missing_ctors` is the set of constructors from the same type as the
         // first column of `matrix` that are matched only by wildcard patterns
@@ -710,7 +981,7 @@ pub fn is_useful<'p, 'a: 'p, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
         let is_non_exhaustive = is_privately_empty || is_declared_nonexhaustive;
 
         if missing_ctors.is_empty() && !is_non_exhaustive {
-            all_ctors.into_iter().map(|c| {
+            split_grouped_constructors(cx.tcx, all_ctors, matrix).into_iter().map(|c| {
                 is_useful_specialized(cx, matrix, v, c.clone(), pcx.ty, witness)
             }).find(|result| result.is_useful()).unwrap_or(NotUseful)
         } else {
@@ -812,9 +1083,9 @@ fn is_useful_specialized<'p, 'a:'p, 'tcx: 'a>(
         }
     }).collect();
     let wild_patterns: Vec<_> = wild_patterns_owned.iter().collect();
-    let matrix = Matrix(m.iter().flat_map(|r| {
+    let matrix: Matrix = m.iter().flat_map(|r| {
         specialize(cx, &r, &ctor, &wild_patterns)
-    }).collect());
+    }).collect();
     match specialize(cx, v, &ctor, &wild_patterns) {
         Some(v) => match is_useful(cx, &matrix, &v, witness) {
             UsefulWithWitness(witnesses) => UsefulWithWitness(
@@ -866,6 +1137,8 @@ fn pat_constructors<'tcx>(_cx: &mut MatchCheckCtxt,
                 Some(vec![Slice(pat_len)])
             }
         }
+        PatternKind::Or { .. } =>
+            span_bug!(pat.span, "or-pattern {:?} was not expanded", pat),
     }
 }
 
@@ -1161,6 +1434,8 @@ fn specialize<'p, 'a: 'p, 'tcx: 'a>(
                     "unexpected ctor {:?} for slice pat", constructor)
             }
         }
+
+        PatternKind::Or { .. } => span_bug!(pat.span, "or-pattern {:?} was not expanded", pat),
     };
     debug!("specialize({:#?}, {:#?}) = {:#?}", r[0], wild_patterns, head);
 
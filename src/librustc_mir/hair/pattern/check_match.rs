@@ -480,11 +480,18 @@ fn check_exhaustive<'a, 'tcx>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
                 1 => format!("pattern {} not covered", joined_patterns),
                 _ => format!("patterns {} not covered", joined_patterns)
             };
-            create_e0004(cx.tcx.sess, sp,
-                            format!("non-exhaustive patterns: {} not covered",
-                                    joined_patterns))
-                .span_label(sp, label_text)
-                .emit();
+            let mut err = create_e0004(cx.tcx.sess, sp,
+                                       format!("non-exhaustive patterns: {} not covered",
+                                               joined_patterns));
+            err.span_label(sp, label_text);
+            if let ty::TyAdt(adt_def, _) = scrut_ty.sty {
+                if adt_def.is_enum() && adt_def.is_non_exhaustive() && !adt_def.did.is_local() {
+                    err.note(&format!("`{}` is marked as non-exhaustive, so a wildcard arm is \
+                                       required to match it outside of its defining crate",
+                                      cx.tcx.item_path_str(adt_def.did)));
+                }
+            }
+            err.emit();
         }
         NotUseful => {
             // This is good, wildcard pattern isn't reachable
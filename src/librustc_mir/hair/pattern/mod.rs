@@ -120,12 +120,19 @@ pub enum PatternKind<'tcx> {
         slice: Option<Pattern<'tcx>>,
         suffix: Vec<Pattern<'tcx>>,
     },
+
+    /// A | B | C, nested inside another pattern. The alternatives bind the
+    /// same variables.
+    Or {
+        pats: Vec<Pattern<'tcx>>,
+    },
 }
 
 fn print_const_val(value: &ty::Const, f: &mut fmt::Formatter) -> fmt::Result {
     match value.val {
         ConstVal::Value(v) => print_miri_value(v, value.ty, f),
-        ConstVal::Unevaluated(..) => bug!("{:?} not printable in a pattern", value)
+        ConstVal::Unevaluated(..) |
+        ConstVal::Param(_) => bug!("{:?} not printable in a pattern", value)
     }
 }
 
@@ -280,6 +287,18 @@ impl<'tcx> fmt::Display for Pattern<'tcx> {
                 }
                 write!(f, "]")
             }
+            PatternKind::Or { ref pats } => {
+                let mut first = true;
+                for p in pats {
+                    if first {
+                        first = false;
+                    } else {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{}", p)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -557,6 +576,10 @@ impl<'a, 'tcx> PatternContext<'a, 'tcx> {
 
                 self.lower_variant_or_leaf(def, pat.span, ty, subpatterns)
             }
+
+            PatKind::Or(ref pats) => {
+                PatternKind::Or { pats: self.lower_patterns(pats) }
+            }
         };
 
         Pattern {
@@ -869,7 +892,7 @@ impl<'a, 'tcx> PatternContext<'a, 'tcx> {
                             subpatterns,
                         }
                     },
-                    ConstVal::Unevaluated(..) =>
+                    ConstVal::Unevaluated(..) | ConstVal::Param(_) =>
                         span_bug!(span, "{:#?} is not a valid enum constant", cv),
                 }
             },
@@ -1063,6 +1086,7 @@ impl<'tcx> PatternFoldable<'tcx> for PatternKind<'tcx> {
                 slice: slice.fold_with(folder),
                 suffix: suffix.fold_with(folder)
             },
+            PatternKind::Or { ref pats } => PatternKind::Or { pats: pats.fold_with(folder) },
         }
     }
 }
@@ -218,22 +218,23 @@ impl<'mir, 'tcx> super::Machine<'mir, 'tcx> for CompileTimeEvaluator {
         debug!("eval_fn_call: {:?}", instance);
         if !ecx.tcx.is_const_fn(instance.def_id()) {
             let def_id = instance.def_id();
-            let (op, oflo) = if let Some(op) = ecx.tcx.is_binop_lang_item(def_id) {
-                op
-            } else {
+            if let Some((op, oflo)) = ecx.tcx.is_binop_lang_item(def_id) {
+                let (dest, bb) = destination.expect("128 lowerings can't diverge");
+                let dest_ty = sig.output();
+                if oflo {
+                    ecx.intrinsic_with_overflow(op, args[0], args[1], dest, dest_ty)?;
+                } else {
+                    ecx.intrinsic_overflowing(op, args[0], args[1], dest, dest_ty)?;
+                }
+                ecx.goto_block(bb);
+                return Ok(true);
+            }
+            // With `-Z unleash-the-miri`, any function with MIR can be called.
+            if !ecx.tcx.sess.opts.debugging_opts.unleash_the_miri {
                 return Err(
                     ConstEvalError::NotConst(format!("calling non-const fn `{}`", instance)).into(),
                 );
-            };
-            let (dest, bb) = destination.expect("128 lowerings can't diverge");
-            let dest_ty = sig.output();
-            if oflo {
-                ecx.intrinsic_with_overflow(op, args[0], args[1], dest, dest_ty)?;
-            } else {
-                ecx.intrinsic_overflowing(op, args[0], args[1], dest, dest_ty)?;
             }
-            ecx.goto_block(bb);
-            return Ok(true);
         }
         let mir = match ecx.load_mir(instance.def) {
             Ok(mir) => mir,
@@ -119,7 +119,7 @@ impl<'tcx> ValTy<'tcx> {
     pub fn from(val: &ty::Const<'tcx>) -> Option<Self> {
         match val.val {
             ConstVal::Value(value) => Some(ValTy { value, ty: val.ty }),
-            ConstVal::Unevaluated { .. } => None,
+            ConstVal::Unevaluated { .. } | ConstVal::Param(_) => None,
         }
     }
 }
@@ -193,8 +193,8 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
             param_env,
             memory: Memory::new(tcx, memory_data),
             stack: Vec::new(),
-            stack_limit: tcx.sess.const_eval_stack_frame_limit.get(),
-            steps_remaining: tcx.sess.const_eval_step_limit.get(),
+            stack_limit: M::stack_frame_limit(tcx.sess),
+            steps_remaining: M::step_limit(tcx.sess),
         }
     }
 
@@ -242,6 +242,7 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
                 }, ty)
             }
             ConstVal::Value(val) => Ok(val),
+            ConstVal::Param(param) => bug!("const parameter `{}` in miri", param),
         }
     }
 
@@ -1670,6 +1671,11 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
             for FrameInfo { span, location } in frames {
                 err.span_note(span, &format!("inside call to `{}`", location));
             }
+            if let EvalErrorKind::ExecutionTimeLimitReached = e.kind {
+                err.note(&format!("evaluation was stopped after {} steps, which can be \
+                                   changed with `#![const_eval_limit=\"N\"]`",
+                                  M::step_limit(self.tcx.sess)));
+            }
             err.emit();
         } else {
             self.tcx.sess.err(&e.to_string());
@@ -6,6 +6,7 @@ use rustc::mir::interpret::{AllocId, EvalResult, PrimVal, MemoryPointer, AccessK
 use super::{EvalContext, Place, ValTy, Memory};
 
 use rustc::mir;
+use rustc::session::Session;
 use rustc::ty::{self, Ty};
 use syntax::codemap::Span;
 use syntax::ast::Mutability;
@@ -19,6 +20,16 @@ pub trait Machine<'mir, 'tcx>: Sized {
     /// Additional memory kinds a machine wishes to distinguish from the builtin ones
     type MemoryKinds: ::std::fmt::Debug + PartialEq + Copy + Clone;
 
+    /// The maximum number of stack frames the evaluated code may push
+    fn stack_frame_limit(sess: &Session) -> usize {
+        sess.const_eval_stack_frame_limit.get()
+    }
+
+    /// The number of steps the evaluation may take before being aborted
+    fn step_limit(sess: &Session) -> usize {
+        sess.const_eval_step_limit.get()
+    }
+
     /// Entry point to all function calls.
     ///
     /// Returns Ok(true) when the function was handled completely
@@ -7,6 +7,7 @@ mod place;
 mod machine;
 mod memory;
 mod operator;
+mod program;
 mod step;
 mod terminator;
 mod traits;
@@ -30,4 +31,6 @@ pub use self::const_eval::{
 
 pub use self::machine::Machine;
 
+pub use self::program::{eval_main, ProgramEvaluator, ProgramMemoryKind};
+
 pub use self::memory::{write_target_uint, write_target_int, read_target_uint};
@@ -0,0 +1,417 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Interpretation of whole programs, starting at their `main` function, with
+//! `-Z interpret-main`. This reuses the const evaluator, but lets the
+//! interpreted code call any function with MIR and use the heap, so that
+//! tests can be checked for undefined behavior at compile time.
+//!
+//! Anything the interpreter can't emulate, like calling foreign functions,
+//! is reported as an unsupported operation rather than silently ignored.
+
+use rustc::mir;
+use rustc::session::Session;
+use rustc::traits;
+use rustc::ty::{self, TyCtxt, Ty, Instance};
+use rustc::ty::layout::{self, Align, LayoutOf};
+
+use syntax::ast::Mutability;
+use syntax::codemap::Span;
+
+use rustc::mir::interpret::{EvalResult, EvalError, EvalErrorKind, GlobalId, PrimVal, Pointer,
+                            AllocId};
+use super::{Place, EvalContext, StackPopCleanup, ValTy, Memory, MemoryKind};
+
+use std::fmt;
+
+/// The machine used to interpret whole programs.
+pub struct ProgramEvaluator;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ProgramMemoryKind {
+    /// Memory allocated by `__rust_alloc` and friends, or by `box`
+    Heap,
+}
+
+/// Operations the interpreted program performed, which can't be emulated.
+#[derive(Clone, Debug)]
+struct Unsupported(String);
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unsupported operation: {}", self.0)
+    }
+}
+
+impl<'tcx> Into<EvalError<'tcx>> for Unsupported {
+    fn into(self) -> EvalError<'tcx> {
+        EvalErrorKind::MachineError(self.to_string()).into()
+    }
+}
+
+fn unsupported<'tcx, T>(msg: String) -> EvalResult<'tcx, T> {
+    Err(Unsupported(msg).into())
+}
+
+/// Interprets the entry function of the local crate, if any, and reports the
+/// first error the interpreted program runs into.
+pub fn eval_main<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    let (main_id, span) = match *tcx.sess.entry_fn.borrow() {
+        Some(entry) => entry,
+        None => return,
+    };
+    let main_def_id = tcx.hir.local_def_id(main_id);
+    let instance = Instance::mono(tcx, main_def_id);
+    let param_env = ty::ParamEnv::empty(traits::Reveal::All);
+    let mut ecx = EvalContext::new(tcx.at(span), param_env, ProgramEvaluator, ());
+
+    let res = (|| {
+        let mir = ecx.load_mir(instance.def)?;
+        if !mir.return_ty().is_nil() {
+            return unsupported("`main` with a return type other than `()`".to_string());
+        }
+        ecx.push_stack_frame(
+            instance,
+            span,
+            mir,
+            Place::undef(),
+            StackPopCleanup::None,
+        )?;
+        while ecx.step()? {}
+        Ok(())
+    })();
+
+    match res {
+        Ok(()) => {
+            let leaks = ecx.memory.leak_report();
+            if leaks != 0 {
+                tcx.sess.span_warn(span, &format!("the interpreted program leaked {} heap \
+                                                   allocation(s)", leaks));
+            }
+        }
+        Err(err) => report(&ecx, err),
+    }
+}
+
+fn report<'a, 'mir, 'tcx>(ecx: &EvalContext<'a, 'mir, 'tcx, ProgramEvaluator>,
+                          err: EvalError<'tcx>) {
+    match err.kind {
+        EvalErrorKind::Layout(_) |
+        EvalErrorKind::TypeckError => return,
+        _ => {}
+    }
+    let span = match ecx.stack().last() {
+        Some(frame) => {
+            let block = &frame.mir.basic_blocks()[frame.block];
+            if frame.stmt < block.statements.len() {
+                block.statements[frame.stmt].source_info.span
+            } else {
+                block.terminator().source_info.span
+            }
+        }
+        None => ecx.tcx.span,
+    };
+    let mut diag = ecx.tcx.sess.struct_span_err(span, "error while interpreting the program");
+    diag.span_label(span, err.to_string());
+    let (frames, _) = ecx.generate_stacktrace(None);
+    for frame in frames {
+        diag.span_note(frame.span, &format!("inside call to `{}`", frame.location));
+    }
+    diag.emit();
+}
+
+fn heap_align<'tcx>(align: u64) -> EvalResult<'tcx, Align> {
+    if !align.is_power_of_two() {
+        return err!(HeapAllocNonPowerOfTwoAlignment(align));
+    }
+    Ok(Align::from_bytes(align, align).unwrap())
+}
+
+impl<'mir, 'tcx> super::Machine<'mir, 'tcx> for ProgramEvaluator {
+    type MemoryData = ();
+    type MemoryKinds = ProgramMemoryKind;
+
+    fn stack_frame_limit(sess: &Session) -> usize {
+        sess.opts.debugging_opts.interpret_main_stack_limit
+            .unwrap_or_else(|| sess.const_eval_stack_frame_limit.get())
+    }
+
+    fn step_limit(sess: &Session) -> usize {
+        sess.opts.debugging_opts.interpret_main_step_limit
+            .unwrap_or_else(|| sess.const_eval_step_limit.get())
+    }
+
+    fn eval_fn_call<'a>(
+        ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        instance: ty::Instance<'tcx>,
+        destination: Option<(Place, mir::BasicBlock)>,
+        args: &[ValTy<'tcx>],
+        span: Span,
+        sig: ty::FnSig<'tcx>,
+    ) -> EvalResult<'tcx, bool> {
+        debug!("eval_fn_call: {:?}", instance);
+        let def_id = instance.def_id();
+        if let Some((op, oflo)) = ecx.tcx.is_binop_lang_item(def_id) {
+            let (dest, bb) = destination.expect("128 lowerings can't diverge");
+            let dest_ty = sig.output();
+            if oflo {
+                ecx.intrinsic_with_overflow(op, args[0], args[1], dest, dest_ty)?;
+            } else {
+                ecx.intrinsic_overflowing(op, args[0], args[1], dest, dest_ty)?;
+            }
+            ecx.goto_block(bb);
+            return Ok(true);
+        }
+
+        let lang_items = ecx.tcx.lang_items();
+        if Some(def_id) == lang_items.panic_fn() ||
+           Some(def_id) == lang_items.panic_bounds_check_fn() ||
+           Some(def_id) == lang_items.panic_fmt() {
+            return err!(Panic);
+        }
+        match &ecx.tcx.item_path_str(def_id)[..] {
+            "std::panicking::begin_panic" |
+            "std::panicking::begin_panic_fmt" => return err!(Panic),
+            _ => {}
+        }
+
+        let mir = match ecx.load_mir(instance.def) {
+            Ok(mir) => mir,
+            Err(err) => {
+                if let EvalErrorKind::NoMirFor(ref path) = err.kind {
+                    call_foreign_fn(ecx, path, destination, args, sig)?;
+                    return Ok(true);
+                }
+                return Err(err);
+            }
+        };
+        let (return_place, return_to_block) = match destination {
+            Some((place, block)) => (place, StackPopCleanup::Goto(block)),
+            None => (Place::undef(), StackPopCleanup::None),
+        };
+
+        ecx.push_stack_frame(
+            instance,
+            span,
+            mir,
+            return_place,
+            return_to_block,
+        )?;
+
+        Ok(false)
+    }
+
+    fn call_intrinsic<'a>(
+        ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        instance: ty::Instance<'tcx>,
+        args: &[ValTy<'tcx>],
+        dest: Place,
+        dest_layout: layout::TyLayout<'tcx>,
+        target: mir::BasicBlock,
+    ) -> EvalResult<'tcx> {
+        let substs = instance.substs;
+
+        let intrinsic_name = &ecx.tcx.item_name(instance.def_id())[..];
+        match intrinsic_name {
+            "min_align_of" => {
+                let elem_ty = substs.type_at(0);
+                let elem_align = ecx.layout_of(elem_ty)?.align.abi();
+                let align_val = PrimVal::from_u128(elem_align as u128);
+                ecx.write_primval(dest, align_val, dest_layout.ty)?;
+            }
+
+            "size_of" => {
+                let ty = substs.type_at(0);
+                let size = ecx.layout_of(ty)?.size.bytes() as u128;
+                ecx.write_primval(dest, PrimVal::from_u128(size), dest_layout.ty)?;
+            }
+
+            "size_of_val" | "min_align_of_val" => {
+                let ty = substs.type_at(0);
+                let (size, align) = ecx.size_and_align_of_dst(ty, args[0].value)?;
+                let val = if intrinsic_name == "size_of_val" {
+                    size.bytes()
+                } else {
+                    align.abi()
+                };
+                ecx.write_primval(dest, PrimVal::from_u128(val as u128), dest_layout.ty)?;
+            }
+
+            "type_id" => {
+                let ty = substs.type_at(0);
+                let type_id = ecx.tcx.type_id_hash(ty) as u128;
+                ecx.write_primval(dest, PrimVal::from_u128(type_id), dest_layout.ty)?;
+            }
+
+            "needs_drop" => {
+                let ty = substs.type_at(0);
+                let needs_drop = ty.needs_drop(*ecx.tcx, ecx.param_env);
+                ecx.write_primval(dest, PrimVal::from_bool(needs_drop), dest_layout.ty)?;
+            }
+
+            "assume" => {
+                if !ecx.value_to_primval(args[0])?.to_bool()? {
+                    return err!(AssumptionNotHeld);
+                }
+            }
+
+            "likely" | "unlikely" => {
+                ecx.write_value(args[0], dest)?;
+            }
+
+            "forget" => {}
+
+            "abort" => return err!(Panic),
+
+            "offset" => {
+                let pointee_size = ecx.layout_of(substs.type_at(0))?.size.bytes() as i64;
+                let count = ecx.value_to_primval(args[1])?.to_i64()?;
+                let ptr = Pointer::from(ecx.value_to_primval(args[0])?);
+                let result = ptr.signed_offset(count * pointee_size, &ecx)?;
+                if let PrimVal::Ptr(ptr) = result.into_inner_primval() {
+                    ecx.memory.check_bounds(ptr, false)?;
+                }
+                ecx.write_ptr(dest, result, dest_layout.ty)?;
+            }
+
+            name => return unsupported(format!("calling intrinsic `{}`", name)),
+        }
+
+        ecx.goto_block(target);
+
+        // Since we pushed no stack frame, the main loop will act
+        // as if the call just completed and it's returning to the
+        // current frame.
+        Ok(())
+    }
+
+    fn try_ptr_op<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        bin_op: mir::BinOp,
+        left: PrimVal,
+        _left_ty: Ty<'tcx>,
+        right: PrimVal,
+        _right_ty: Ty<'tcx>,
+    ) -> EvalResult<'tcx, Option<(PrimVal, bool)>> {
+        match (left, right) {
+            (PrimVal::Bytes(_), PrimVal::Bytes(_)) => Ok(None),
+            (PrimVal::Ptr(left), PrimVal::Ptr(right)) if bin_op == mir::BinOp::Eq => {
+                Ok(Some((PrimVal::from_bool(left == right), false)))
+            }
+            (PrimVal::Ptr(left), PrimVal::Ptr(right)) if bin_op == mir::BinOp::Ne => {
+                Ok(Some((PrimVal::from_bool(left != right), false)))
+            }
+            _ => unsupported(format!("pointer arithmetic or comparison with `{:?}`", bin_op)),
+        }
+    }
+
+    fn mark_static_initialized<'a>(
+        _mem: &mut Memory<'a, 'mir, 'tcx, Self>,
+        _id: AllocId,
+        _mutability: Mutability,
+    ) -> EvalResult<'tcx, bool> {
+        Ok(false)
+    }
+
+    fn init_static<'a>(
+        ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        cid: GlobalId<'tcx>,
+    ) -> EvalResult<'tcx, AllocId> {
+        // statics are computed by the const evaluator, and are immutable
+        // for the interpreted program
+        ecx.const_eval(cid)?;
+        Ok(ecx
+            .tcx
+            .interpret_interner
+            .get_cached(cid.instance.def_id())
+            .expect("uncached static"))
+    }
+
+    fn box_alloc<'a>(
+        ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        ty: Ty<'tcx>,
+        dest: Place,
+    ) -> EvalResult<'tcx> {
+        let layout = ecx.layout_of(ty)?;
+        let box_ty = ecx.tcx.mk_box(ty);
+        // Like `exchange_malloc`, don't allocate zero-sized boxes.
+        let ptr = if layout.size.bytes() == 0 {
+            PrimVal::Bytes(layout.align.abi() as u128)
+        } else {
+            let kind = MemoryKind::Machine(ProgramMemoryKind::Heap);
+            PrimVal::Ptr(ecx.memory.allocate(layout.size.bytes(), layout.align, Some(kind))?)
+        };
+        ecx.write_primval(dest, ptr, box_ty)
+    }
+
+    fn global_item_with_linkage<'a>(
+        _ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        _instance: ty::Instance<'tcx>,
+        _mutability: Mutability,
+    ) -> EvalResult<'tcx> {
+        unsupported("statics with `linkage` attribute".to_string())
+    }
+}
+
+/// Emulates the functions without MIR the interpreted program calls. Only the
+/// allocator shims are supported.
+fn call_foreign_fn<'a, 'mir, 'tcx>(
+    ecx: &mut EvalContext<'a, 'mir, 'tcx, ProgramEvaluator>,
+    path: &str,
+    destination: Option<(Place, mir::BasicBlock)>,
+    args: &[ValTy<'tcx>],
+    sig: ty::FnSig<'tcx>,
+) -> EvalResult<'tcx> {
+    let (dest, block) = match destination {
+        Some(destination) => destination,
+        None => return unsupported(format!("calling foreign function `{}`", path)),
+    };
+    let heap = MemoryKind::Machine(ProgramMemoryKind::Heap);
+    let name = path.rsplit("::").next().unwrap_or(path);
+    match name {
+        "__rust_alloc" | "__rust_alloc_zeroed" => {
+            let size = ecx.value_to_primval(args[0])?.to_u64()?;
+            let align = heap_align(ecx.value_to_primval(args[1])?.to_u64()?)?;
+            if size == 0 {
+                return err!(HeapAllocZeroBytes);
+            }
+            let ptr = ecx.memory.allocate(size, align, Some(heap))?;
+            if name == "__rust_alloc_zeroed" {
+                ecx.memory.write_repeat(ptr.into(), 0, size)?;
+            }
+            ecx.write_primval(dest, PrimVal::Ptr(ptr), sig.output())?;
+        }
+        "__rust_dealloc" => {
+            let ptr = ecx.value_to_primval(args[0])?.to_ptr()?;
+            let size = ecx.value_to_primval(args[1])?.to_u64()?;
+            let align = heap_align(ecx.value_to_primval(args[2])?.to_u64()?)?;
+            if size == 0 {
+                return err!(HeapAllocZeroBytes);
+            }
+            ecx.memory.deallocate(ptr, Some((size, align)), heap)?;
+        }
+        "__rust_realloc" => {
+            let ptr = ecx.value_to_primval(args[0])?.to_ptr()?;
+            let old_size = ecx.value_to_primval(args[1])?.to_u64()?;
+            let old_align = heap_align(ecx.value_to_primval(args[2])?.to_u64()?)?;
+            let new_size = ecx.value_to_primval(args[3])?.to_u64()?;
+            let new_align = heap_align(ecx.value_to_primval(args[4])?.to_u64()?)?;
+            if old_size == 0 || new_size == 0 {
+                return err!(HeapAllocZeroBytes);
+            }
+            let new_ptr = ecx.memory.reallocate(ptr, old_size, old_align,
+                                                new_size, new_align, heap)?;
+            ecx.write_primval(dest, PrimVal::Ptr(new_ptr), sig.output())?;
+        }
+        _ => return unsupported(format!("calling foreign function `{}`", path)),
+    }
+    ecx.goto_block(block);
+    Ok(())
+}
@@ -93,6 +93,9 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             // size of MIR constantly.
             Nop => {}
 
+            // Coverage counters only exist at runtime.
+            Coverage { .. } => {}
+
             InlineAsm { .. } => return err!(InlineAsm),
         }
 
@@ -152,6 +152,16 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                         Math(ref err) => {
                             err!(Math(terminator.source_info.span, err.clone()))
                         }
+                        MisalignedPointerDereference { ref required, ref found } => {
+                            let required = self.eval_operand_to_primval(required)
+                                .expect("can't eval required alignment")
+                                .to_u64()?;
+                            let found = self.eval_operand_to_primval(found)
+                                .expect("can't eval address")
+                                .to_u64()?;
+                            err!(AlignmentCheckFailed { required, has: found })
+                        }
+                        NullPointerDereference => err!(InvalidNullPointerUsage),
                         GeneratorResumedAfterReturn |
                         GeneratorResumedAfterPanic => unimplemented!(),
                     };
@@ -8,12 +8,15 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use rustc::hir::def_id::DefId;
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
+use rustc::lint::builtin::EXCESSIVE_MONOMORPHIZATION;
 use rustc::middle::lang_items::DropInPlaceFnLangItem;
 use rustc::traits;
 use rustc::ty::adjustment::CustomCoerceUnsized;
 use rustc::ty::subst::Kind;
 use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use syntax::ast::CRATE_NODE_ID;
 
 pub use rustc::ty::Instance;
 pub use self::item::{MonoItem, MonoItemExt};
@@ -70,6 +73,83 @@ pub fn assert_symbols_are_distinct<'a, 'tcx, I>(tcx: TyCtxt<'a, 'tcx, 'tcx>, tra
     }
 }
 
+/// Groups the instances of generic functions among `mono_items` by the
+/// function they instantiate, along with the estimated size of each instance.
+/// The functions with the most instances come first.
+fn generic_instances<'a, 'tcx, I>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mono_items: I)
+                                  -> Vec<(DefId, Vec<(Instance<'tcx>, usize)>)>
+    where I: Iterator<Item=&'a MonoItem<'tcx>>
+{
+    let mut instances = FxHashMap();
+    for mono_item in mono_items {
+        if let MonoItem::Fn(instance) = *mono_item {
+            if let ty::InstanceDef::Item(def_id) = instance.def {
+                if instance.substs.types().next().is_some() {
+                    instances.entry(def_id)
+                             .or_insert(Vec::new())
+                             .push((instance, mono_item.size_estimate(&tcx)));
+                }
+            }
+        }
+    }
+
+    let mut instances: Vec<_> = instances.into_iter().map(|(def_id, mut instances)| {
+        instances.sort_by_key(|&(instance, _)| instance.to_string());
+        (tcx.item_path_str(def_id), def_id, instances)
+    }).collect();
+    instances.sort_by(|&(ref path1, _, ref instances1), &(ref path2, _, ref instances2)| {
+        instances2.len().cmp(&instances1.len()).then_with(|| path1.cmp(path2))
+    });
+    instances.into_iter().map(|(_, def_id, instances)| (def_id, instances)).collect()
+}
+
+/// Prints, for each generic function, the number of its instances and their
+/// total estimated size, followed by each instance and its estimated size.
+/// This is the output of `-Z print-trans-items=verbose`, meant to help finding
+/// the generic functions responsible for the size of a binary.
+///
+/// The instances are requested by the crate being compiled, which is
+/// printed as `crate`, while `defined-in` is the crate of the function.
+pub fn print_generic_instances<'a, 'tcx, I>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mono_items: I)
+    where I: Iterator<Item=&'a MonoItem<'tcx>>
+{
+    let local_crate = tcx.crate_name(LOCAL_CRATE);
+    for (def_id, instances) in generic_instances(tcx, mono_items) {
+        let total_size: usize = instances.iter().map(|&(_, size)| size).sum();
+        println!("GENERIC_FN {} @@ crate:{} defined-in:{} instances:{} size:{}",
+                 tcx.item_path_str(def_id),
+                 local_crate,
+                 tcx.crate_name(def_id.krate),
+                 instances.len(),
+                 total_size);
+        for (instance, size) in instances {
+            println!("GENERIC_FN_INSTANCE {} @@ size:{}", instance, size);
+        }
+    }
+}
+
+/// Warns about the generic functions which have more instances than allowed
+/// by `-Z mono-instance-limit`.
+pub fn check_instance_counts<'a, 'tcx, I>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                         mono_items: I,
+                                         limit: usize)
+    where I: Iterator<Item=&'a MonoItem<'tcx>>
+{
+    for (def_id, instances) in generic_instances(tcx, mono_items) {
+        if instances.len() <= limit {
+            // The functions with the most instances come first.
+            break;
+        }
+        let node_id = tcx.hir.as_local_node_id(def_id).unwrap_or(CRATE_NODE_ID);
+        let msg = format!("generic function `{}` is instantiated {} times, \
+                           more than the limit of {}",
+                          tcx.item_path_str(def_id),
+                          instances.len(),
+                          limit);
+        tcx.lint_node(EXCESSIVE_MONOMORPHIZATION, node_id, tcx.def_span(def_id), &msg);
+    }
+}
+
 fn fn_once_adapter_instance<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     closure_did: DefId,
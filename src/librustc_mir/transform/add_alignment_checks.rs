@@ -0,0 +1,316 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This pass implements `-Z mir-emit-alignment-checks`: before every
+//! statement or terminator which dereferences a raw pointer, or passes one
+//! to a memory-accessing intrinsic (`copy`, `copy_nonoverlapping`,
+//! `write_bytes`, `volatile_load` and `volatile_store`), it inserts
+//! assertions that the pointer is not null and is aligned for its pointee:
+//!
+//! ```text
+//! _addr = _ptr as usize;
+//! _null = Eq(_addr, const 0usize);
+//! assert(!move _null, "null pointer dereference") -> bb1;
+//!
+//! bb1:
+//! _mask = BitAnd(_addr, const ALIGN - 1);
+//! _aligned = Eq(move _mask, const 0usize);
+//! assert(move _aligned, "misaligned pointer dereference", const ALIGN, _addr) -> bb2;
+//! ```
+//!
+//! Pointers to unsized types are not checked, and pointers to types whose
+//! layout depends on generic parameters are only checked for null.
+//!
+//! The assertions unwind to the cleanup block of the terminator of their
+//! block, or of the block it jumps to, so that the locals in scope are
+//! dropped. As this pass runs before drop elaboration, locals which are not
+//! yet initialized at the assertion get drop flags like at any other edge
+//! into that cleanup block. When there is no such cleanup block, the
+//! assertions leak the locals, which is always sound.
+
+use rustc::hir;
+use rustc::middle::const_val::ConstVal;
+use rustc::mir::*;
+use rustc::mir::interpret::{Value, PrimVal};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::session::Session;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::layout::LayoutOf;
+use syntax::abi::Abi;
+use syntax_pos::Span;
+use transform::{MirPass, MirSource};
+
+pub struct AddAlignmentChecks;
+
+/// A raw pointer which must be checked before it is used.
+struct PointerCheck<'tcx> {
+    pointer: Operand<'tcx>,
+    /// The alignment of the pointee, if known.
+    align: Option<u64>,
+}
+
+impl MirPass for AddAlignmentChecks {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_emit_alignment_checks
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        // Constants, statics and promoted constants are only evaluated at
+        // compile-time, where miri already catches these errors and the
+        // integer address of a pointer isn't known. `const fn`s are checked,
+        // as they are called at runtime too, and can't dereference raw
+        // pointers at compile-time.
+        if source.promoted.is_some() {
+            return
+        }
+        let node_id = tcx.hir.as_local_node_id(source.def_id).unwrap();
+        match tcx.hir.body_owner_kind(node_id) {
+            hir::BodyOwnerKind::Fn => {}
+            hir::BodyOwnerKind::Const |
+            hir::BodyOwnerKind::Static(_) => return,
+        }
+
+        let param_env = tcx.param_env(source.def_id);
+        let mut checks = vec![];
+        {
+            let mir: &Mir<'tcx> = mir;
+            for (block, data) in mir.basic_blocks().iter_enumerated() {
+                // Panicking during unwinding aborts, so there is nothing to
+                // gain by checking cleanup blocks.
+                if data.is_cleanup {
+                    continue
+                }
+                for (statement_index, statement) in data.statements.iter().enumerate() {
+                    let location = Location { block, statement_index };
+                    let mut finder = PointerFinder { tcx, mir, pointers: vec![] };
+                    match statement.kind {
+                        StatementKind::Assign(..) |
+                        StatementKind::SetDiscriminant { .. } => {
+                            finder.visit_statement(block, statement, location);
+                        }
+                        _ => continue,
+                    }
+                    checks.push((location, finder.pointers));
+                }
+
+                let location = Location { block, statement_index: data.statements.len() };
+                let mut finder = PointerFinder { tcx, mir, pointers: vec![] };
+                finder.visit_terminator(block, data.terminator(), location);
+                finder.intrinsic_pointers(data.terminator());
+                checks.push((location, finder.pointers));
+            }
+        }
+
+        // Insert the checks from the last location to the first one, so that
+        // splitting a block doesn't move the locations still to be handled.
+        for (location, pointers) in checks.into_iter().rev() {
+            let span = mir.source_info(location).span;
+            let pointers: Vec<_> = pointers.into_iter().filter_map(|(pointer, pointee)| {
+                let pointee = tcx.erase_regions(&pointee);
+                if !pointee.is_sized(tcx.at(span), param_env) {
+                    return None;
+                }
+                let align = tcx.layout_of(param_env.and(pointee))
+                    .ok()
+                    .map(|layout| layout.align.abi());
+                Some(PointerCheck { pointer, align })
+            }).collect();
+            if !pointers.is_empty() {
+                insert_checks(tcx, mir, location, pointers);
+            }
+        }
+    }
+}
+
+struct PointerFinder<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    /// The raw pointers used, along with their pointee types, in the order
+    /// in which they have to be checked.
+    pointers: Vec<(Operand<'tcx>, Ty<'tcx>)>,
+}
+
+impl<'a, 'tcx> PointerFinder<'a, 'tcx> {
+    /// Collects the pointers passed to the memory-accessing intrinsics.
+    fn intrinsic_pointers(&mut self, terminator: &Terminator<'tcx>) {
+        let (func, args) = match terminator.kind {
+            TerminatorKind::Call { ref func, ref args, .. } => (func, args),
+            _ => return,
+        };
+        let (def_id, substs) = match func.ty(self.mir, self.tcx).sty {
+            ty::TyFnDef(def_id, substs) => (def_id, substs),
+            _ => return,
+        };
+        if self.tcx.fn_sig(def_id).abi() != Abi::RustIntrinsic {
+            return
+        }
+        let pointer_args: &[usize] = match &*self.tcx.item_name(def_id) {
+            "copy" | "copy_nonoverlapping" => &[0, 1],
+            "write_bytes" | "volatile_load" | "volatile_store" => &[0],
+            _ => &[],
+        };
+        for &index in pointer_args {
+            let pointer = match args[index] {
+                Operand::Copy(ref place) |
+                Operand::Move(ref place) => Operand::Copy(place.clone()),
+                Operand::Constant(ref constant) => Operand::Constant(constant.clone()),
+            };
+            self.pointers.push((pointer, substs.type_at(0)));
+        }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for PointerFinder<'a, 'tcx> {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        // Visit the base first, as it has to be checked before it is used
+        // to compute this place.
+        self.super_place(place, context, location);
+
+        if let Place::Projection(ref proj) = *place {
+            if let ProjectionElem::Deref = proj.elem {
+                let pointer_ty = proj.base.ty(self.mir, self.tcx).to_ty(self.tcx);
+                if let ty::TyRawPtr(ty::TypeAndMut { ty: pointee, .. }) = pointer_ty.sty {
+                    self.pointers.push((Operand::Copy(proj.base.clone()), pointee));
+                }
+            }
+        }
+    }
+}
+
+/// Splits the block of `location` right before it, and checks every pointer
+/// of `pointers` in between.
+fn insert_checks<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           mir: &mut Mir<'tcx>,
+                           location: Location,
+                           pointers: Vec<PointerCheck<'tcx>>) {
+    let source_info = *mir.source_info(location);
+    let span = source_info.span;
+    let usize_ty = tcx.types.usize;
+    let bool_ty = tcx.types.bool;
+    let cleanup = find_cleanup(mir, location.block);
+
+    let rest = {
+        let data = &mut mir[location.block];
+        let statements = data.statements.split_off(location.statement_index);
+        BasicBlockData {
+            statements,
+            terminator: data.terminator.take(),
+            is_cleanup: false,
+        }
+    };
+    let rest = mir.basic_blocks_mut().push(rest);
+
+    let mut block = location.block;
+    for check in pointers {
+        let addr = Place::Local(mir.local_decls.push(LocalDecl::new_temp(usize_ty, span)));
+        let is_null = Place::Local(mir.local_decls.push(LocalDecl::new_temp(bool_ty, span)));
+        mir[block].statements.push(Statement {
+            source_info,
+            kind: StatementKind::Assign(addr.clone(),
+                                        Rvalue::Cast(CastKind::Misc, check.pointer, usize_ty)),
+        });
+        mir[block].statements.push(Statement {
+            source_info,
+            kind: StatementKind::Assign(is_null.clone(),
+                                        Rvalue::BinaryOp(BinOp::Eq,
+                                                         Operand::Copy(addr.clone()),
+                                                         constant_usize(tcx, span, 0))),
+        });
+        let next = mir.basic_blocks_mut().push(BasicBlockData::new(None));
+        mir[block].terminator = Some(Terminator {
+            source_info,
+            kind: TerminatorKind::Assert {
+                cond: Operand::Move(is_null),
+                expected: false,
+                msg: AssertMessage::NullPointerDereference,
+                target: next,
+                cleanup,
+            },
+        });
+        block = next;
+
+        let align = match check.align {
+            Some(align) if align > 1 => align,
+            _ => continue,
+        };
+        let mask = Place::Local(mir.local_decls.push(LocalDecl::new_temp(usize_ty, span)));
+        let is_aligned = Place::Local(mir.local_decls.push(LocalDecl::new_temp(bool_ty, span)));
+        mir[block].statements.push(Statement {
+            source_info,
+            kind: StatementKind::Assign(mask.clone(),
+                                        Rvalue::BinaryOp(BinOp::BitAnd,
+                                                         Operand::Copy(addr.clone()),
+                                                         constant_usize(tcx, span, align - 1))),
+        });
+        mir[block].statements.push(Statement {
+            source_info,
+            kind: StatementKind::Assign(is_aligned.clone(),
+                                        Rvalue::BinaryOp(BinOp::Eq,
+                                                         Operand::Move(mask),
+                                                         constant_usize(tcx, span, 0))),
+        });
+        let next = mir.basic_blocks_mut().push(BasicBlockData::new(None));
+        mir[block].terminator = Some(Terminator {
+            source_info,
+            kind: TerminatorKind::Assert {
+                cond: Operand::Move(is_aligned),
+                expected: true,
+                msg: AssertMessage::MisalignedPointerDereference {
+                    required: constant_usize(tcx, span, align),
+                    found: Operand::Copy(addr),
+                },
+                target: next,
+                cleanup,
+            },
+        });
+        block = next;
+    }
+
+    mir[block].terminator = Some(Terminator {
+        source_info,
+        kind: TerminatorKind::Goto { target: rest },
+    });
+}
+
+/// Finds the cleanup block which a panic in `block` should unwind to: the
+/// one of its terminator, or, if it just jumps to another block, of that
+/// block.
+fn find_cleanup(mir: &Mir, mut block: BasicBlock) -> Option<BasicBlock> {
+    for _ in 0..mir.basic_blocks().len() {
+        match mir[block].terminator().kind {
+            TerminatorKind::Call { cleanup, .. } |
+            TerminatorKind::Assert { cleanup, .. } => return cleanup,
+            TerminatorKind::Drop { unwind, .. } |
+            TerminatorKind::DropAndReplace { unwind, .. } => return unwind,
+            TerminatorKind::Goto { target } => block = target,
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn constant_usize<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, span: Span, val: u64) -> Operand<'tcx> {
+    Operand::Constant(box Constant {
+        span,
+        ty: tcx.types.usize,
+        literal: Literal::Value {
+            value: tcx.mk_const(ty::Const {
+                val: ConstVal::Value(Value::ByVal(PrimVal::Bytes(val.into()))),
+                ty: tcx.types.usize
+            })
+        }
+    })
+}
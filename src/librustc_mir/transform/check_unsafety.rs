@@ -19,9 +19,9 @@ use rustc::hir::def_id::DefId;
 use rustc::lint::builtin::{SAFE_EXTERN_STATICS, SAFE_PACKED_BORROWS, UNUSED_UNSAFE};
 use rustc::mir::*;
 use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc_errors::Applicability;
 
 use syntax::ast;
-use syntax::symbol::Symbol;
 
 use util;
 
@@ -34,6 +34,7 @@ pub struct UnsafetyChecker<'a, 'tcx: 'a> {
     param_env: ty::ParamEnv<'tcx>,
     used_unsafe: FxHashSet<ast::NodeId>,
     inherited_blocks: Vec<(ast::NodeId, bool)>,
+    used_operations: FxHashSet<UnsafetyViolationDetails>,
 }
 
 impl<'a, 'gcx, 'tcx> UnsafetyChecker<'a, 'tcx> {
@@ -53,6 +54,7 @@ impl<'a, 'gcx, 'tcx> UnsafetyChecker<'a, 'tcx> {
             param_env,
             used_unsafe: FxHashSet(),
             inherited_blocks: vec![],
+            used_operations: FxHashSet(),
         }
     }
 }
@@ -85,7 +87,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                 let func_ty = func.ty(self.mir, self.tcx);
                 let sig = func_ty.fn_sig(self.tcx);
                 if let hir::Unsafety::Unsafe = sig.unsafety() {
-                    self.require_unsafe("call to unsafe function")
+                    self.require_unsafe(UnsafetyViolationDetails::CallToUnsafeFunction)
                 }
             }
         }
@@ -105,12 +107,13 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
             StatementKind::StorageDead(..) |
             StatementKind::EndRegion(..) |
             StatementKind::Validate(..) |
+            StatementKind::Coverage { .. } |
             StatementKind::Nop => {
                 // safe (at least as emitted during MIR construction)
             }
 
             StatementKind::InlineAsm { .. } => {
-                self.require_unsafe("use of inline assembly")
+                self.require_unsafe(UnsafetyViolationDetails::UseOfInlineAssembly)
             },
         }
         self.super_statement(block, statement, location);
@@ -127,17 +130,20 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                 &AggregateKind::Adt(..) => {}
                 &AggregateKind::Closure(def_id, _) => {
                     let UnsafetyCheckResult {
-                        violations, unsafe_blocks
+                        violations, unsafe_blocks, used_operations
                     } = self.tcx.unsafety_check_result(def_id);
                     self.register_violations(&violations, &unsafe_blocks);
+                    self.used_operations.extend(used_operations.iter().cloned());
                 }
                 &AggregateKind::Generator(def_id, _, interior) => {
                     let UnsafetyCheckResult {
-                        violations, unsafe_blocks
+                        violations, unsafe_blocks, used_operations
                     } = self.tcx.unsafety_check_result(def_id);
                     self.register_violations(&violations, &unsafe_blocks);
+                    self.used_operations.extend(used_operations.iter().cloned());
                     if !interior.movable {
-                        self.require_unsafe("construction of immovable generator")
+                        self.require_unsafe(
+                            UnsafetyViolationDetails::ImmovableGeneratorConstruction)
                     }
                 }
             }
@@ -156,7 +162,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                     self.visibility_scope_info[source_info.scope].lint_root;
                 self.register_violations(&[UnsafetyViolation {
                     source_info,
-                    description: Symbol::intern("borrow of packed field").as_str(),
+                    details: UnsafetyViolationDetails::BorrowOfPackedField,
                     kind: UnsafetyViolationKind::BorrowPacked(lint_root)
                 }], &[]);
             }
@@ -178,7 +184,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                 let base_ty = base.ty(self.mir, self.tcx).to_ty(self.tcx);
                 match base_ty.sty {
                     ty::TyRawPtr(..) => {
-                        self.require_unsafe("dereference of raw pointer")
+                        self.require_unsafe(UnsafetyViolationDetails::DerefOfRawPointer)
                     }
                     ty::TyAdt(adt, _) => {
                         if adt.is_union() {
@@ -196,12 +202,13 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                                 if elem_ty.moves_by_default(self.tcx, self.param_env,
                                                             self.source_info.span) {
                                     self.require_unsafe(
-                                        "assignment to non-`Copy` union field")
+                                        UnsafetyViolationDetails::AssignToNonCopyUnionField)
                                 } else {
                                     // write to non-move union, safe
                                 }
                             } else {
-                                self.require_unsafe("access to union field")
+                                self.require_unsafe(
+                                    UnsafetyViolationDetails::AccessToUnionField)
                             }
                         }
                     }
@@ -214,14 +221,14 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
             }
             &Place::Static(box Static { def_id, ty: _ }) => {
                 if self.tcx.is_static(def_id) == Some(hir::Mutability::MutMutable) {
-                    self.require_unsafe("use of mutable static");
+                    self.require_unsafe(UnsafetyViolationDetails::UseOfMutableStatic);
                 } else if self.tcx.is_foreign_item(def_id) {
                     let source_info = self.source_info;
                     let lint_root =
                         self.visibility_scope_info[source_info.scope].lint_root;
                     self.register_violations(&[UnsafetyViolation {
                         source_info,
-                        description: Symbol::intern("use of extern static").as_str(),
+                        details: UnsafetyViolationDetails::UseOfExternStatic,
                         kind: UnsafetyViolationKind::ExternStatic(lint_root)
                     }], &[]);
                 }
@@ -233,12 +240,12 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
 
 impl<'a, 'tcx> UnsafetyChecker<'a, 'tcx> {
     fn require_unsafe(&mut self,
-                      description: &'static str)
+                      details: UnsafetyViolationDetails)
     {
         let source_info = self.source_info;
         self.register_violations(&[UnsafetyViolation {
             source_info,
-            description: Symbol::intern(description).as_str(),
+            details,
             kind: UnsafetyViolationKind::General,
         }], &[]);
     }
@@ -246,6 +253,7 @@ impl<'a, 'tcx> UnsafetyChecker<'a, 'tcx> {
     fn register_violations(&mut self,
                            violations: &[UnsafetyViolation],
                            unsafe_blocks: &[(ast::NodeId, bool)]) {
+        self.used_operations.extend(violations.iter().map(|violation| violation.details));
         let within_unsafe = match self.visibility_scope_info[self.source_info.scope].safety {
             Safety::Safe => {
                 for violation in violations {
@@ -258,7 +266,10 @@ impl<'a, 'tcx> UnsafetyChecker<'a, 'tcx> {
             }
             Safety::BuiltinUnsafe | Safety::FnUnsafe => true,
             Safety::ExplicitUnsafe(node_id) => {
-                if !violations.is_empty() {
+                // An `unsafe` block in a closure is redundant when the closure
+                // is itself within an `unsafe` block, so if it is used, the
+                // enclosing block is what is really used.
+                if !violations.is_empty() || unsafe_blocks.iter().any(|&(_, used)| used) {
                     self.used_unsafe.insert(node_id);
                 }
                 true
@@ -339,7 +350,8 @@ fn unsafety_check_result<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId)
             debug!("unsafety_violations: {:?} - remote, skipping", def_id);
             return UnsafetyCheckResult {
                 violations: Lrc::new([]),
-                unsafe_blocks: Lrc::new([])
+                unsafe_blocks: Lrc::new([]),
+                used_operations: Lrc::new([]),
             }
         }
     };
@@ -350,9 +362,12 @@ fn unsafety_check_result<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId)
     checker.visit_mir(mir);
 
     check_unused_unsafe(tcx, def_id, &checker.used_unsafe, &mut checker.inherited_blocks);
+    let mut used_operations: Vec<_> = checker.used_operations.into_iter().collect();
+    used_operations.sort();
     UnsafetyCheckResult {
         violations: checker.violations.into(),
-        unsafe_blocks: checker.inherited_blocks.into()
+        unsafe_blocks: checker.inherited_blocks.into(),
+        used_operations: used_operations.into(),
     }
 }
 
@@ -405,7 +420,8 @@ fn report_unused_unsafe(tcx: TyCtxt, used_unsafe: &FxHashSet<ast::NodeId>, id: a
     let span = tcx.sess.codemap().def_span(tcx.hir.span(id));
     let msg = "unnecessary `unsafe` block";
     let mut db = tcx.struct_span_lint_node(UNUSED_UNSAFE, id, span, msg);
-    db.span_label(span, msg);
+    db.span_suggestion_short_with_applicability(span, "remove this `unsafe`", String::new(),
+                                                Applicability::MachineApplicable);
     if let Some((kind, id)) = is_enclosed(tcx, used_unsafe, id) {
         db.span_label(tcx.sess.codemap().def_span(tcx.hir.span(id)),
                       format!("because it's nested under this `unsafe` {}", kind));
@@ -439,37 +455,54 @@ pub fn check_unsafety<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) {
 
     let UnsafetyCheckResult {
         violations,
-        unsafe_blocks
+        unsafe_blocks,
+        used_operations
     } = tcx.unsafety_check_result(def_id);
 
+    // For unit testing: list the unsafe operations used by functions with
+    // a special `rustc_unsafe_operations` attribute.
+    if tcx.has_attr(def_id, "rustc_unsafe_operations") {
+        let used_operations: Vec<_> = used_operations.iter()
+            .map(|operation| operation.description())
+            .collect();
+        tcx.sess.span_err(tcx.def_span(def_id),
+                          &format!("unsafe operations: [{}]", used_operations.join(", ")));
+    }
+
     for &UnsafetyViolation {
-        source_info, description, kind
+        source_info, details, kind
     } in violations.iter() {
+        let description = details.description();
         // Report an error.
         match kind {
             UnsafetyViolationKind::General => {
                 struct_span_err!(
                     tcx.sess, source_info.span, E0133,
                     "{} requires unsafe function or block", description)
-                    .span_label(source_info.span, &description[..])
+                    .span_label(source_info.span, description)
+                    .note(details.explanation())
                     .emit();
             }
             UnsafetyViolationKind::ExternStatic(lint_node_id) => {
-                tcx.lint_node(SAFE_EXTERN_STATICS,
-                              lint_node_id,
-                              source_info.span,
-                              &format!("{} requires unsafe function or \
-                                        block (error E0133)", &description[..]));
+                tcx.struct_span_lint_node(SAFE_EXTERN_STATICS,
+                                          lint_node_id,
+                                          source_info.span,
+                                          &format!("{} requires unsafe function or \
+                                                    block (error E0133)", description))
+                    .note(details.explanation())
+                    .emit();
             }
             UnsafetyViolationKind::BorrowPacked(lint_node_id) => {
                 if let Some(impl_def_id) = builtin_derive_def_id(tcx, def_id) {
                     tcx.unsafe_derive_on_repr_packed(impl_def_id);
                 } else {
-                    tcx.lint_node(SAFE_PACKED_BORROWS,
-                                  lint_node_id,
-                                  source_info.span,
-                                  &format!("{} requires unsafe function or \
-                                            block (error E0133)", &description[..]));
+                    tcx.struct_span_lint_node(SAFE_PACKED_BORROWS,
+                                              lint_node_id,
+                                              source_info.span,
+                                              &format!("{} requires unsafe function or \
+                                                        block (error E0133)", description))
+                        .note(details.explanation())
+                        .emit();
                 }
             }
         }
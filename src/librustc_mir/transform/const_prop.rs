@@ -145,6 +145,7 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                     };
                     self.const_eval(cid, c.span)
                 },
+                ConstVal::Param(_) => None,
             },
             // evaluate the promoted and replace the constant with the evaluated result
             Literal::Promoted { index } => {
@@ -481,7 +482,9 @@ impl<'b, 'a, 'tcx> Visitor<'tcx> for ConstPropagator<'b, 'a, 'tcx> {
                     let msg = match msg {
                         // Need proper const propagator for these
                         GeneratorResumedAfterReturn |
-                        GeneratorResumedAfterPanic => return,
+                        GeneratorResumedAfterPanic |
+                        MisalignedPointerDereference { .. } |
+                        NullPointerDereference => return,
                         Math(ref err) => err.description().to_owned(),
                         BoundsCheck { ref len, ref index } => {
                             let len = self.eval_operand(len).expect("len must be const");
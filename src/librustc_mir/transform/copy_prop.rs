@@ -32,6 +32,7 @@
 use rustc::hir;
 use rustc::mir::{Constant, Local, LocalKind, Location, Place, Mir, Operand, Rvalue, StatementKind};
 use rustc::mir::visit::MutVisitor;
+use rustc::session::Session;
 use rustc::ty::TyCtxt;
 use transform::{MirPass, MirSource};
 use util::def_use::DefUseAnalysis;
@@ -39,6 +40,12 @@ use util::def_use::DefUseAnalysis;
 pub struct CopyPropagation;
 
 impl MirPass for CopyPropagation {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        // We only run when the MIR optimization level is > 1.
+        // This avoids a slow pass, and messing up debug info.
+        sess.opts.debugging_opts.mir_opt_level > 1
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource,
@@ -61,12 +68,6 @@ impl MirPass for CopyPropagation {
             }
         }
 
-        // We only run when the MIR optimization level is > 1.
-        // This avoids a slow pass, and messing up debug info.
-        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
-            return;
-        }
-
         let mut def_use_analysis = DefUseAnalysis::new(mir);
         loop {
             def_use_analysis.analyze(mir);
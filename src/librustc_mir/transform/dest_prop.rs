@@ -0,0 +1,307 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Destination propagation: for an assignment `dest = move src` between two
+//! locals, replace `src` with `dest` everywhere, so that the value is built
+//! directly in its final place and the copy disappears. Most notably, this
+//! implements the "named return value optimization": a value built in a
+//! temporary and then moved to the return place `_0` is built in `_0`.
+//!
+//! Two locals can only be merged if they don't *conflict*, i.e.:
+//!
+//! - neither of them is written while the other may hold a value, according
+//!   to the `MaybeInitializedLocals` dataflow analysis;
+//! - no statement or terminator writes one of them while also reading or
+//!   writing the other one (e.g. `_1 = f(move _2)`), as trans may compute
+//!   the result in place, except for copies between the two;
+//! - they are not both arguments, which are all written on entry.
+//!
+//! Locals which are ever borrowed are never merged, because the dataflow
+//! analysis doesn't know about the uses made through references. Neither
+//! are user variables when debuginfo is enabled, as they would lose their
+//! own storage.
+
+use dataflow::{do_dataflow, DebugFormatted, FlowAtLocation, FlowsAtLocation};
+use dataflow::MaybeInitializedLocals;
+use rustc::mir::*;
+use rustc::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc::session::config::NoDebugInfo;
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bitvec::{BitMatrix, BitVector};
+use rustc_data_structures::indexed_set::IdxSetBuf;
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use transform::{MirPass, MirSource};
+
+pub struct DestinationPropagation;
+
+impl MirPass for DestinationPropagation {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level >= 2
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let candidates = find_candidates(tcx, mir);
+        if candidates.is_empty() {
+            return
+        }
+        let mut conflicts = compute_conflicts(tcx, source, mir);
+
+        let mut replacements: IndexVec<Local, Local> = mir.local_decls.indices().collect();
+        let mut merged = BitVector::new(mir.local_decls.len());
+        for (dest, src) in candidates {
+            let dest = find(&replacements, dest);
+            let src = find(&replacements, src);
+            if dest == src || conflicts.contains(dest.index(), src.index()) {
+                continue
+            }
+            debug!("DestinationPropagation: merging {:?} into {:?} in {:?}",
+                   src, dest, source.def_id);
+
+            // `dest` now holds `src`'s values as well, so it conflicts with
+            // everything `src` conflicts with.
+            replacements[src] = dest;
+            conflicts.merge(src.index(), dest.index());
+            for other in conflicts.iter(src.index()).collect::<Vec<_>>() {
+                conflicts.add(other, dest.index());
+            }
+            merged.insert(src.index());
+            merged.insert(dest.index());
+        }
+
+        if merged.iter().next().is_none() {
+            return
+        }
+        let replacements = replacements.iter().map(|&local| find(&replacements, local)).collect();
+        Replacer { replacements, merged }.visit_mir(mir);
+    }
+}
+
+fn find(replacements: &IndexVec<Local, Local>, mut local: Local) -> Local {
+    while replacements[local] != local {
+        local = replacements[local];
+    }
+    local
+}
+
+/// Returns the `(dest, src)` pairs of the copies `dest = move src` which
+/// could be removed by replacing `src` with `dest`.
+fn find_candidates<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                             mir: &Mir<'tcx>)
+                             -> Vec<(Local, Local)> {
+    let mut borrowed = BorrowedLocals(BitVector::new(mir.local_decls.len()));
+    borrowed.visit_mir(mir);
+    let borrowed = borrowed.0;
+    let keep_user_variables = tcx.sess.opts.debuginfo != NoDebugInfo;
+
+    let mut candidates = vec![];
+    for data in mir.basic_blocks() {
+        for statement in &data.statements {
+            let (dest, src) = match copy_between_locals(statement) {
+                Some(pair) => pair,
+                None => continue,
+            };
+            // Arguments and the return place can't be renamed.
+            if src == dest || src == RETURN_PLACE || src.index() <= mir.arg_count {
+                continue
+            }
+            let (dest_decl, src_decl) = (&mir.local_decls[dest], &mir.local_decls[src]);
+            if dest_decl.ty != src_decl.ty ||
+                (keep_user_variables && src_decl.name.is_some()) ||
+                borrowed.contains(dest.index()) ||
+                borrowed.contains(src.index())
+            {
+                continue
+            }
+            candidates.push((dest, src));
+        }
+    }
+    candidates
+}
+
+/// Matches on `dest = src`, where `src` is either moved or copied.
+fn copy_between_locals(statement: &Statement) -> Option<(Local, Local)> {
+    match statement.kind {
+        StatementKind::Assign(Place::Local(dest), Rvalue::Use(ref operand)) => {
+            match *operand {
+                Operand::Copy(Place::Local(src)) |
+                Operand::Move(Place::Local(src)) => Some((dest, src)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Computes which pairs of locals conflict, as defined in the module docs.
+fn compute_conflicts<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                               source: MirSource,
+                               mir: &Mir<'tcx>)
+                               -> BitMatrix {
+    let locals = mir.local_decls.len();
+    let mut conflicts = BitMatrix::new(locals, locals);
+
+    for a in mir.args_iter() {
+        for b in mir.args_iter() {
+            add_conflict(&mut conflicts, a, b);
+        }
+    }
+
+    let node_id = tcx.hir.as_local_node_id(source.def_id).unwrap();
+    let dead_unwinds = IdxSetBuf::new_empty(mir.basic_blocks().len());
+    let analysis = MaybeInitializedLocals::new(mir);
+    let initialized =
+        do_dataflow(tcx, mir, node_id, &[], &dead_unwinds, analysis,
+                    |bd, p| DebugFormatted::new(&bd.mir().local_decls[p]));
+    let mut flow = FlowAtLocation::new(initialized);
+
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        flow.reset_to_entry_of(block);
+        for statement_index in 0..data.statements.len() + 1 {
+            let location = Location { block, statement_index };
+            let mut accesses = Accesses::default();
+            let copy = if statement_index < data.statements.len() {
+                let statement = &data.statements[statement_index];
+                accesses.visit_statement(block, statement, location);
+                flow.reconstruct_statement_effect(location);
+                copy_between_locals(statement)
+            } else {
+                accesses.visit_terminator(block, data.terminator(), location);
+                flow.reconstruct_terminator_effect(location);
+                None
+            };
+
+            // The moves out of locals are already applied to the flow state
+            // here, but not the writes. The source of a copy may still hold
+            // its value, which is the one its destination gets.
+            for &written in &accesses.written {
+                flow.each_state_bit(|initialized| {
+                    if copy != Some((written, initialized)) {
+                        add_conflict(&mut conflicts, written, initialized);
+                    }
+                });
+                if copy.is_none() {
+                    for &mentioned in &accesses.mentioned {
+                        add_conflict(&mut conflicts, written, mentioned);
+                    }
+                }
+            }
+
+            flow.apply_local_effect(location);
+        }
+    }
+
+    conflicts
+}
+
+fn add_conflict(conflicts: &mut BitMatrix, a: Local, b: Local) {
+    if a != b {
+        conflicts.add(a.index(), b.index());
+        conflicts.add(b.index(), a.index());
+    }
+}
+
+/// The locals accessed by a statement or terminator.
+#[derive(Default)]
+struct Accesses {
+    /// The locals written, even partially or by dropping them.
+    written: Vec<Local>,
+    /// All the locals read or written.
+    mentioned: Vec<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for Accesses {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        let writes = match context {
+            PlaceContext::Store |
+            PlaceContext::AsmOutput |
+            PlaceContext::Call |
+            PlaceContext::Drop => true,
+            _ => false,
+        };
+        if writes {
+            if let Some(local) = base_local(place) {
+                self.written.push(local);
+            }
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        // Storage markers are removed for the merged locals.
+        if !context.is_storage_marker() {
+            self.mentioned.push(local);
+        }
+    }
+}
+
+fn base_local(place: &Place) -> Option<Local> {
+    match *place {
+        Place::Local(local) => Some(local),
+        Place::Static(..) => None,
+        Place::Projection(ref proj) => base_local(&proj.base),
+    }
+}
+
+struct BorrowedLocals(BitVector);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Ref(_, _, ref place) = *rvalue {
+            if let Some(local) = base_local(place) {
+                self.0.insert(local.index());
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
+
+struct Replacer {
+    replacements: IndexVec<Local, Local>,
+    /// The locals which were merged with another one, whose storage markers
+    /// don't make sense anymore.
+    merged: BitVector,
+}
+
+impl<'tcx> MutVisitor<'tcx> for Replacer {
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext<'tcx>, _: Location) {
+        *local = self.replacements[*local];
+    }
+
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &mut Statement<'tcx>,
+                       location: Location) {
+        let remove = match statement.kind {
+            StatementKind::StorageLive(local) |
+            StatementKind::StorageDead(local) => self.merged.contains(local.index()),
+            _ => false,
+        };
+        if remove {
+            statement.make_nop();
+            return
+        }
+
+        self.super_statement(block, statement, location);
+
+        // Copies between merged locals are now self-assignments.
+        if let Some((dest, src)) = copy_between_locals(statement) {
+            if dest == src {
+                statement.make_nop();
+            }
+        }
+    }
+}
@@ -48,20 +48,24 @@ impl fmt::Display for Disambiguator {
 }
 
 
+/// Dumps `mir` before or after the `run_index`-th run of the pass
+/// `pass_name` on it, if requested by `-Z dump-mir`.
 pub fn on_mir_pass<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                              pass_num: &dyn fmt::Display,
                              pass_name: &str,
+                             run_index: usize,
                              source: MirSource,
                              mir: &Mir<'tcx>,
                              is_after: bool) {
-    if mir_util::dump_enabled(tcx, pass_name, source) {
-        mir_util::dump_mir(tcx,
-                           Some(pass_num),
-                           pass_name,
-                           &Disambiguator { is_after },
-                           source,
-                           mir,
-                           |_, _| Ok(()) );
+    if mir_util::dump_enabled_for_run(tcx, pass_name, Some(run_index), source) {
+        mir_util::dump_mir_for_run(tcx,
+                                   Some(pass_num),
+                                   pass_name,
+                                   Some(run_index),
+                                   &Disambiguator { is_after },
+                                   source,
+                                   mir,
+                                   |_, _| Ok(()) );
     }
 }
 
@@ -75,3 +79,14 @@ pub fn emit_mir<'a, 'tcx>(
     mir_util::write_mir_pretty(tcx, None, &mut f)?;
     Ok(())
 }
+
+pub fn emit_mir_json<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    outputs: &OutputFilenames)
+    -> io::Result<()>
+{
+    let path = outputs.path(OutputType::MirJson);
+    let mut f = File::create(&path)?;
+    mir_util::write_mir_json(tcx, &mut f)?;
+    Ok(())
+}
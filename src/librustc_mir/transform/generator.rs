@@ -35,6 +35,10 @@
 //!         mir_locals...,
 //!     }
 //!
+//! Saved locals whose storage is never live at the same time share a single field of the
+//! generator struct, even if their types differ, so a generator only needs as much space for
+//! its locals as the largest set of them which can be live at once.
+//!
 //! This pass computes the meaning of the state field and the MIR locals which are live
 //! across a suspension point. There are however two hardcoded generator states:
 //!     0 - Generator have not been resumed yet
@@ -68,6 +72,7 @@ use rustc::ty::{self, TyCtxt, AdtDef, Ty, GeneratorInterior};
 use rustc::ty::subst::Substs;
 use util::dump_mir;
 use util::liveness::{self, LivenessMode};
+use rustc_data_structures::bitvec::BitMatrix;
 use rustc_data_structures::indexed_vec::Idx;
 use rustc_data_structures::indexed_set::IdxSetBuf;
 use std::collections::HashMap;
@@ -77,7 +82,7 @@ use std::mem;
 use transform::{MirPass, MirSource};
 use transform::simplify;
 use transform::no_landing_pads::no_landing_pads;
-use dataflow::{do_dataflow, DebugFormatted, state_for_location};
+use dataflow::{do_dataflow, DataflowResults, DebugFormatted, state_for_location};
 use dataflow::{MaybeStorageLive, HaveBeenBorrowedLocals};
 use rustc::mir::interpret::{Value, PrimVal};
 
@@ -367,7 +372,8 @@ fn locals_live_across_suspend_points<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                                source: MirSource,
                                                movable: bool) ->
                                                (liveness::LocalSet,
-                                                HashMap<BasicBlock, liveness::LocalSet>) {
+                                                HashMap<BasicBlock, liveness::LocalSet>,
+                                                BitMatrix) {
     let dead_unwinds = IdxSetBuf::new_empty(mir.basic_blocks().len());
     let node_id = tcx.hir.as_local_node_id(source.def_id).unwrap();
 
@@ -458,7 +464,96 @@ fn locals_live_across_suspend_points<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     // The generator argument is ignored
     set.remove(&self_arg());
 
-    (set, storage_liveness_map)
+    let storage_conflicts = compute_storage_conflicts(mir, &set, &ignored.0, &storage_live);
+
+    (set, storage_liveness_map, storage_conflicts)
+}
+
+/// For every pair of locals which are saved across some suspension point, records whether
+/// their storage is ever live at the same time. Locals which do not conflict can be stored in
+/// the same field of the generator struct.
+///
+/// The result is a square matrix indexed by `Local`, which is symmetric and only has entries
+/// for locals in `saved_locals`.
+fn compute_storage_conflicts<'a, 'tcx>(mir: &'a Mir<'tcx>,
+                                       saved_locals: &liveness::LocalSet,
+                                       ignored: &liveness::LocalSet,
+                                       storage_live: &DataflowResults<MaybeStorageLive<'a, 'tcx>>)
+                                       -> BitMatrix {
+    let num_locals = mir.local_decls.len();
+    let mut conflicts = BitMatrix::new(num_locals, num_locals);
+
+    // Locals without storage markers are always live, so they conflict with every other
+    // saved local.
+    let mut always_live = ignored.to_owned();
+    always_live.intersect(saved_locals);
+
+    {
+        let mut record_conflicts = |live: &liveness::LocalSet| {
+            let mut live = live.to_owned();
+            live.intersect(saved_locals);
+            live.union(&always_live);
+            for a in live.iter() {
+                for b in live.iter() {
+                    conflicts.add(a.index(), b.index());
+                }
+            }
+        };
+
+        for (block, data) in mir.basic_blocks().iter_enumerated() {
+            // `MaybeStorageLive` only changes state at `StorageLive` and `StorageDead`
+            // statements, so we can replay the block here instead of asking the dataflow
+            // results about every single location.
+            let mut live = storage_live.sets().on_entry_set_for(block.index()).to_owned();
+            record_conflicts(&live);
+            for statement in &data.statements {
+                match statement.kind {
+                    StatementKind::StorageLive(l) => {
+                        live.add(&l);
+                        record_conflicts(&live);
+                    }
+                    StatementKind::StorageDead(l) => {
+                        live.remove(&l);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Assigns each saved local to a slot of the generator struct. Two locals may share a slot if
+/// their storage is never live at the same time, whatever their types. Returns the slot of
+/// each saved local, in the iteration order of `saved_locals`, along with the number of slots
+/// needed.
+fn assign_slots(saved_locals: &liveness::LocalSet,
+                storage_conflicts: &BitMatrix)
+                -> (Vec<(Local, usize)>, usize) {
+    let mut slots: Vec<Vec<Local>> = vec![];
+    let mut assignment = vec![];
+
+    for local in saved_locals.iter() {
+        let slot = slots.iter().position(|occupants| {
+            occupants.iter().all(|other| {
+                !storage_conflicts.contains(local.index(), other.index())
+            })
+        });
+        let slot = match slot {
+            Some(slot) => slot,
+            None => {
+                slots.push(vec![]);
+                slots.len() - 1
+            }
+        };
+        slots[slot].push(local);
+        assignment.push((local, slot));
+    }
+
+    debug!("assign_slots: {} saved locals in {} slots", assignment.len(), slots.len());
+
+    (assignment, slots.len())
 }
 
 fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
@@ -471,10 +566,8 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         HashMap<BasicBlock, liveness::LocalSet>)
 {
     // Use a liveness analysis to compute locals which are live across a suspension point
-    let (live_locals, storage_liveness) = locals_live_across_suspend_points(tcx,
-                                                                            mir,
-                                                                            source,
-                                                                            interior.movable);
+    let (live_locals, storage_liveness, storage_conflicts) =
+        locals_live_across_suspend_points(tcx, mir, source, interior.movable);
     // Erase regions from the types passed in from typeck so we can compare them with
     // MIR types
     let allowed_upvars = tcx.erase_regions(&upvars);
@@ -503,22 +596,33 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     let upvar_len = mir.upvar_decls.len();
     let dummy_local = LocalDecl::new_internal(tcx.mk_nil(), mir.span);
 
-    // Gather live locals and their indices replacing values in mir.local_decls with a dummy
-    // to avoid changing local indices
-    let live_decls = live_locals.iter().map(|local| {
-        let var = mem::replace(&mut mir.local_decls[local], dummy_local.clone());
-        (local, var)
-    });
+    // Decide which live locals can share storage in the generator struct.
+    let (assignment, num_slots) = assign_slots(&live_locals, &storage_conflicts);
 
     // Create a map from local indices to generator struct indices.
     // These are offset by (upvar_len + 1) because of fields which comes before locals.
-    // We also create a vector of the LocalDecls of these locals.
-    let (remap, vars) = live_decls.enumerate().map(|(idx, (local, var))| {
-        ((local, (var.ty, upvar_len + 1 + idx)), var)
-    }).unzip();
+    // We also create a vector of the LocalDecls of each slot, using the first local
+    // assigned to it, and collect the other types stored in it. The values in
+    // mir.local_decls are replaced with a dummy to avoid changing local indices.
+    let mut remap = HashMap::new();
+    let mut vars: Vec<Option<LocalDecl<'tcx>>> = vec![None; num_slots];
+    let mut shared_tys: Vec<Vec<Ty<'tcx>>> = vec![vec![]; num_slots];
+    for (local, slot) in assignment {
+        let var = mem::replace(&mut mir.local_decls[local], dummy_local.clone());
+        remap.insert(local, (var.ty, upvar_len + 1 + slot));
+        match vars[slot] {
+            None => vars[slot] = Some(var),
+            Some(ref first) => {
+                if first.ty != var.ty && !shared_tys[slot].contains(&var.ty) {
+                    shared_tys[slot].push(var.ty);
+                }
+            }
+        }
+    }
 
     let layout = GeneratorLayout {
-        fields: vars
+        fields: vars.into_iter().map(|var| var.unwrap()).collect(),
+        shared_tys,
     };
 
     (remap, layout, storage_liveness)
@@ -0,0 +1,141 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `-Z instrument-coverage`.
+//!
+//! The `InstrumentCoverage` pass inserts a `Coverage` statement at the start
+//! of every basic block of a function, each incrementing its own counter, and
+//! gives that statement the span of the source code covered by the block.
+//! Trans lowers these statements to LLVM's `llvm.instrprof.increment`, and
+//! the profiler runtime writes the counters out when the program exits.
+//!
+//! The `coverage_info` query then reads the counters and their spans back
+//! from the optimized MIR. Its result is encoded into the crate metadata, so
+//! that a coverage report can map every counter to the code it counts.
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc::ty::maps::Providers;
+use rustc::session::Session;
+use rustc_data_structures::sync::Lrc;
+use syntax_pos::Span;
+use transform::{MirPass, MirSource};
+
+pub(crate) fn provide(providers: &mut Providers) {
+    *providers = Providers {
+        coverage_info,
+        ..*providers
+    };
+}
+
+pub struct InstrumentCoverage;
+
+impl MirPass for InstrumentCoverage {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.instrument_coverage
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        // Promoted constants are always evaluated at compile-time, so there
+        // is nothing to count in them.
+        if source.promoted.is_some() {
+            return
+        }
+        let node_id = tcx.hir.as_local_node_id(source.def_id).unwrap();
+        if tcx.hir.maybe_body_owned_by(node_id).is_none() {
+            // Tuple struct and variant constructors have no code of their own.
+            return
+        }
+        match tcx.hir.body_owner_kind(node_id) {
+            hir::BodyOwnerKind::Fn => {}
+            hir::BodyOwnerKind::Const |
+            hir::BodyOwnerKind::Static(_) => return,
+        }
+
+        let body_span = mir.span;
+        let mut index = 0;
+        for data in mir.basic_blocks_mut() {
+            if data.is_cleanup {
+                continue
+            }
+            let region = match block_region(body_span, data) {
+                Some(region) => region,
+                // Blocks which don't correspond to any source code of this
+                // function, e.g. because they were expanded from a macro,
+                // can't be mapped back to anything.
+                None => continue,
+            };
+            let scope = data.terminator().source_info.scope;
+            data.statements.insert(0, Statement {
+                source_info: SourceInfo { span: region, scope },
+                kind: StatementKind::Coverage { index },
+            });
+            index += 1;
+        }
+        debug!("InstrumentCoverage: inserted {} counters into {:?}", index, source.def_id);
+    }
+}
+
+/// The smallest span of `body_span` covering every statement and the
+/// terminator of `data`, if any of them is within `body_span`.
+fn block_region(body_span: Span, data: &BasicBlockData) -> Option<Span> {
+    let spans = data.statements.iter()
+        .map(|statement| statement.source_info.span)
+        .chain(Some(data.terminator().source_info.span));
+    let mut region: Option<Span> = None;
+    for span in spans {
+        if span.lo() >= span.hi() || !body_span.contains(span) {
+            continue
+        }
+        region = Some(match region {
+            Some(region) => region.to(span),
+            None => span,
+        });
+    }
+    region
+}
+
+fn coverage_info<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> Lrc<CoverageInfo> {
+    let mir = tcx.optimized_mir(def_id);
+    let codemap = tcx.sess.codemap();
+
+    let mut counters = vec![];
+    for data in mir.basic_blocks() {
+        for statement in &data.statements {
+            if let StatementKind::Coverage { index } = statement.kind {
+                let span = statement.source_info.span;
+                let lo = codemap.lookup_char_pos(span.lo());
+                let hi = codemap.lookup_char_pos(span.hi());
+                counters.push((index, CodeRegion {
+                    file_name: lo.file.name.to_string(),
+                    start_line: lo.line as u32,
+                    start_col: lo.col.0 as u32 + 1,
+                    end_line: hi.line as u32,
+                    end_col: hi.col.0 as u32 + 1,
+                }));
+            }
+        }
+    }
+    counters.sort_by_key(|&(index, _)| index);
+    for (expected, &(index, _)) in counters.iter().enumerate() {
+        assert_eq!(index as usize, expected, "coverage counters of {:?} are not contiguous",
+                   def_id);
+    }
+
+    Lrc::new(CoverageInfo {
+        function_hash: tcx.def_path_hash(def_id).0.to_smaller_hash(),
+        regions: counters.into_iter().map(|(_, region)| region).collect(),
+    })
+}
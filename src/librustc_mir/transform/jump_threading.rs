@@ -0,0 +1,254 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Jump threading: replaces a `switchInt` whose operand is already known
+//! from the straight-line code leading to it with a `goto` to the matching
+//! target. This mostly cleans up after `?` and nested matches, which set
+//! the discriminant of an enum just before switching on it:
+//!
+//! ```text
+//! bb0: {
+//!     ((_2 as Ok).0: u32) = move _3;
+//!     discriminant(_2) = 0;
+//!     goto -> bb1;
+//! }
+//!
+//! bb1: {
+//!     _4 = discriminant(_2);
+//!     switchInt(move _4) -> [0isize: bb2, otherwise: bb3];
+//! }
+//! ```
+//!
+//! Here, `bb0` gets a copy of the statements of `bb1` and jumps straight to
+//! `bb2`. The `switchInt` itself is only rewritten when its own block
+//! determines the operand, as other predecessors may still reach it.
+//!
+//! The operand is known if it was last assigned an integer constant, or the
+//! discriminant of a place whose variant was last set by `SetDiscriminant` or
+//! an enum aggregate, without anything mentioning the local or the base of
+//! the place in between.
+
+use rustc::middle::const_val::ConstVal;
+use rustc::mir::*;
+use rustc::mir::interpret::{Value, PrimVal};
+use rustc::mir::tcx::PlaceTy;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::session::Session;
+use rustc::ty::{self, TyCtxt};
+use transform::{MirPass, MirSource};
+use transform::simplify;
+
+pub struct JumpThreading;
+
+/// A block whose terminator can be replaced with a `goto`.
+struct Thread {
+    /// The block to rewrite.
+    block: BasicBlock,
+    /// The block with the `switchInt`, if it's not `block` itself; its
+    /// statements have to be appended to `block`.
+    switch_block: Option<BasicBlock>,
+    target: BasicBlock,
+}
+
+impl MirPass for JumpThreading {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level >= 2
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let mut threads = vec![];
+        {
+            let mir: &Mir<'tcx> = mir;
+            let predecessors = mir.predecessors();
+            for (block, data) in mir.basic_blocks().iter_enumerated() {
+                let (local, switch_ty, values, targets) = match data.terminator().kind {
+                    TerminatorKind::SwitchInt {
+                        discr: Operand::Copy(Place::Local(local)),
+                        switch_ty,
+                        ref values,
+                        ref targets,
+                    } |
+                    TerminatorKind::SwitchInt {
+                        discr: Operand::Move(Place::Local(local)),
+                        switch_ty,
+                        ref values,
+                        ref targets,
+                    } => (local, switch_ty, values, targets),
+                    _ => continue,
+                };
+                let target_for = |value: u128| {
+                    match values.iter().position(|&v| v == value) {
+                        Some(index) => targets[index],
+                        None => *targets.last().unwrap(),
+                    }
+                };
+
+                let statements: Vec<_> = data.statements.iter().collect();
+                if let Some(value) = known_value(tcx, mir, &statements, local, switch_ty) {
+                    threads.push(Thread { block, switch_block: None, target: target_for(value) });
+                    continue
+                }
+
+                for &pred in &predecessors[block] {
+                    if pred == block {
+                        continue
+                    }
+                    match mir[pred].terminator().kind {
+                        TerminatorKind::Goto { target } if target == block => {}
+                        _ => continue,
+                    }
+                    let statements: Vec<_> = mir[pred].statements.iter()
+                        .chain(&data.statements)
+                        .collect();
+                    if let Some(value) = known_value(tcx, mir, &statements, local, switch_ty) {
+                        threads.push(Thread {
+                            block: pred,
+                            switch_block: Some(block),
+                            target: target_for(value),
+                        });
+                    }
+                }
+            }
+        }
+
+        if threads.is_empty() {
+            return
+        }
+
+        for thread in threads {
+            debug!("JumpThreading: {:?} (through {:?}) goes to {:?} in {:?}",
+                   thread.block, thread.switch_block, thread.target, source.def_id);
+            if let Some(switch_block) = thread.switch_block {
+                let statements = mir[switch_block].statements.clone();
+                mir[thread.block].statements.extend(statements);
+            }
+            mir[thread.block].terminator_mut().kind = TerminatorKind::Goto {
+                target: thread.target,
+            };
+        }
+        simplify::remove_dead_blocks(mir);
+    }
+}
+
+/// The value of `local` at the end of `statements`, if known.
+fn known_value<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                         mir: &Mir<'tcx>,
+                         statements: &[&Statement<'tcx>],
+                         local: Local,
+                         switch_ty: ty::Ty<'tcx>)
+                         -> Option<u128> {
+    for (index, statement) in statements.iter().enumerate().rev() {
+        match statement.kind {
+            StatementKind::Assign(Place::Local(dest), ref rvalue) if dest == local => {
+                return match *rvalue {
+                    Rvalue::Use(Operand::Constant(ref constant)) => {
+                        // The bits of signed constants may not be truncated
+                        // the way `switchInt` values are.
+                        match switch_ty.sty {
+                            ty::TyBool | ty::TyChar | ty::TyUint(_) => constant_bits(constant),
+                            _ => None,
+                        }
+                    }
+                    Rvalue::Discriminant(ref place) => {
+                        known_discriminant(tcx, mir, &statements[..index], place)
+                    }
+                    _ => None,
+                };
+            }
+            _ if mentions(statement, local) => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The discriminant of `place` at the end of `statements`, if known.
+fn known_discriminant<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                mir: &Mir<'tcx>,
+                                statements: &[&Statement<'tcx>],
+                                place: &Place<'tcx>)
+                                -> Option<u128> {
+    // Places behind a reference could be modified through other references.
+    let base = base_local_without_deref(place)?;
+    let adt_def = match place.ty(mir, tcx) {
+        PlaceTy::Ty { ty } => match ty.sty {
+            ty::TyAdt(adt_def, _) if adt_def.is_enum() => adt_def,
+            _ => return None,
+        },
+        PlaceTy::Downcast { .. } => return None,
+    };
+
+    for statement in statements.iter().rev() {
+        match statement.kind {
+            StatementKind::SetDiscriminant { place: ref dest, variant_index }
+                if dest == place => {
+                return Some(adt_def.discriminant_for_variant(tcx, variant_index).val);
+            }
+            StatementKind::Assign(ref dest, Rvalue::Aggregate(ref kind, _)) if dest == place => {
+                return match **kind {
+                    AggregateKind::Adt(_, variant_index, ..) => {
+                        Some(adt_def.discriminant_for_variant(tcx, variant_index).val)
+                    }
+                    _ => None,
+                };
+            }
+            _ if mentions(statement, base) => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn constant_bits(constant: &Constant) -> Option<u128> {
+    match constant.literal {
+        Literal::Value { value } => match value.val {
+            ConstVal::Value(Value::ByVal(PrimVal::Bytes(bits))) => Some(bits),
+            _ => None,
+        },
+        Literal::Promoted { .. } => None,
+    }
+}
+
+fn base_local_without_deref(place: &Place) -> Option<Local> {
+    match *place {
+        Place::Local(local) => Some(local),
+        Place::Static(..) => None,
+        Place::Projection(ref proj) => match proj.elem {
+            ProjectionElem::Deref => None,
+            _ => base_local_without_deref(&proj.base),
+        },
+    }
+}
+
+/// Whether `statement` mentions `local`, ignoring storage markers.
+fn mentions<'tcx>(statement: &Statement<'tcx>, local: Local) -> bool {
+    struct FindLocal {
+        local: Local,
+        found: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for FindLocal {
+        fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+            if local == self.local && !context.is_storage_marker() {
+                self.found = true;
+            }
+        }
+    }
+
+    let mut finder = FindLocal { local, found: false };
+    finder.visit_statement(START_BLOCK, statement, Location {
+        block: START_BLOCK,
+        statement_index: 0,
+    });
+    finder.found
+}
@@ -26,7 +26,7 @@ impl MirPass for Lower128Bit {
                           _src: MirSource,
                           mir: &mut Mir<'tcx>) {
         let debugging_override = tcx.sess.opts.debugging_opts.lower_128bit_ops;
-        let target_default = tcx.sess.host.options.i128_lowering;
+        let target_default = tcx.sess.target.target.options.i128_lowering;
         if !debugging_override.unwrap_or(target_default) {
             return
         }
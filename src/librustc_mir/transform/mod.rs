@@ -12,24 +12,28 @@ use borrow_check::nll::type_check;
 use build;
 use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc::mir::{Mir, Promoted};
+use rustc::session::Session;
 use rustc::ty::TyCtxt;
 use rustc::ty::maps::Providers;
 use rustc::ty::steal::Steal;
 use rustc::hir;
 use rustc::hir::intravisit::{self, Visitor, NestedVisitorMap};
-use rustc::util::nodemap::DefIdSet;
+use rustc::util::nodemap::{DefIdSet, FxHashMap};
 use rustc_data_structures::sync::Lrc;
 use std::borrow::Cow;
 use syntax::ast;
 use syntax_pos::Span;
 
 pub mod add_validation;
+pub mod add_alignment_checks;
 pub mod add_moves_for_packed_drops;
 pub mod clean_end_regions;
 pub mod check_unsafety;
 pub mod simplify_branches;
 pub mod simplify;
+pub mod simplify_try;
 pub mod erase_regions;
+pub mod reveal_all;
 pub mod no_landing_pads;
 pub mod rustc_peek;
 pub mod elaborate_drops;
@@ -37,19 +41,25 @@ pub mod add_call_guards;
 pub mod promote_consts;
 pub mod qualify_consts;
 pub mod remove_noop_landing_pads;
+pub mod remove_dead_borrows;
 pub mod dump_mir;
 pub mod deaggregator;
 pub mod instcombine;
 pub mod copy_prop;
+pub mod dest_prop;
 pub mod const_prop;
 pub mod generator;
 pub mod inline;
+pub mod jump_threading;
 pub mod lower_128bit;
 pub mod uniform_array_move_out;
+pub mod validate;
+pub mod instrument_coverage;
 
 pub(crate) fn provide(providers: &mut Providers) {
     self::qualify_consts::provide(providers);
     self::check_unsafety::provide(providers);
+    self::instrument_coverage::provide(providers);
     *providers = Providers {
         mir_keys,
         mir_built,
@@ -148,12 +158,71 @@ pub trait MirPass {
         default_name::<Self>()
     }
 
+    /// Whether this pass should run in the current session, given flags
+    /// such as `-Z mir-opt-level`. This can be overridden on the command
+    /// line with `-Z mir-enable-passes`.
+    fn is_enabled(&self, _sess: &Session) -> bool {
+        true
+    }
+
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource,
                           mir: &mut Mir<'tcx>);
 }
 
+/// Decides whether `pass` is run, taking `-Z mir-enable-passes` into
+/// account. A pass is named there either by its full name, or by the name
+/// of its type for passes which are run several times under different
+/// labels (`SimplifyCfg` covers `SimplifyCfg-initial`, etc.).
+pub fn is_pass_enabled(sess: &Session, pass: &dyn MirPass) -> bool {
+    let name = pass.name();
+    let overridden = sess.opts.debugging_opts.mir_enable_passes.iter().find(|&&(ref n, _)| {
+        name == n.as_str() || (name.starts_with(n.as_str()) && name[n.len()..].starts_with("-"))
+    });
+    match overridden {
+        Some(&(_, enabled)) => enabled,
+        None => pass.is_enabled(sess),
+    }
+}
+
+/// Runs a single pass on `mir`, surrounded by the `-Z dump-mir` and
+/// `-Z validate-mir` hooks.
+fn run_pass<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                      suite_index: usize,
+                      index: usize,
+                      source: MirSource,
+                      pass: &dyn MirPass,
+                      mir: &mut Mir<'tcx>) {
+    let name = pass.name();
+    if !is_pass_enabled(tcx.sess, pass) {
+        debug!("run_pass: skipping disabled pass `{}` on {:?}", name, source);
+        return;
+    }
+
+    // Count the runs of each pass on each body for the `name#N` filters of
+    // `-Z dump-mir`, but only when it is given.
+    let run_index = if tcx.sess.opts.debugging_opts.dump_mir.is_some() {
+        let mut pass_runs = tcx.sess.mir_pass_runs.borrow_mut();
+        let runs = pass_runs.entry(name.to_string())
+            .or_insert_with(FxHashMap)
+            .entry((source.def_id, source.promoted))
+            .or_insert(0);
+        *runs += 1;
+        *runs - 1
+    } else {
+        0
+    };
+    let pass_num = format!("{:03}-{:03}", suite_index, index);
+    dump_mir::on_mir_pass(tcx, &pass_num, &name, run_index, source, mir, false);
+    pass.run_pass(tcx, source, mir);
+    dump_mir::on_mir_pass(tcx, &pass_num, &name, run_index, source, mir, true);
+
+    if tcx.sess.opts.debugging_opts.validate_mir {
+        validate::Validator::new(tcx, source, mir, &name).validate();
+    }
+}
+
 pub macro run_passes($tcx:ident, $mir:ident, $def_id:ident, $suite_index:expr; $($pass:expr,)*) {{
     let suite_index: usize = $suite_index;
     let run_passes = |mir: &mut _, promoted| {
@@ -162,18 +231,11 @@ pub macro run_passes($tcx:ident, $mir:ident, $def_id:ident, $suite_index:expr; $
             promoted
         };
         let mut index = 0;
-        let mut run_pass = |pass: &dyn MirPass| {
-            let run_hooks = |mir: &_, index, is_after| {
-                dump_mir::on_mir_pass($tcx, &format_args!("{:03}-{:03}", suite_index, index),
-                                      &pass.name(), source, mir, is_after);
-            };
-            run_hooks(mir, index, false);
-            pass.run_pass($tcx, source, mir);
-            run_hooks(mir, index, true);
-
+        let mut run = |pass: &dyn MirPass| {
+            run_pass($tcx, suite_index, index, source, pass, mir);
             index += 1;
         };
-        $(run_pass(&$pass);)*
+        $(run(&$pass);)*
     };
 
     run_passes(&mut $mir, None);
@@ -235,6 +297,11 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
         remove_noop_landing_pads::RemoveNoopLandingPads,
         simplify::SimplifyCfg::new("early-opt"),
 
+        // Must come before drop elaboration, which handles the locals that are
+        // not initialized when the checks unwind, and before inlining, so that
+        // the checks of a function stay with its code.
+        add_alignment_checks::AddAlignmentChecks,
+
         // These next passes must be executed together
         add_call_guards::CriticalCallEdges,
         elaborate_drops::ElaborateDrops,
@@ -249,14 +316,19 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
 
         simplify::SimplifyCfg::new("elaborate-drops"),
 
+        // Only looks at the uses of the temporaries of borrows, so it does
+        // not depend on regions.
+        remove_dead_borrows::RemoveDeadBorrows,
+
         // No lifetime analysis based on borrowing can be done from here on out.
 
         // From here on out, regions are gone.
         erase_regions::EraseRegions,
+        // ... and so are projections which can be normalized.
+        reveal_all::RevealAll,
 
         lower_128bit::Lower128Bit,
 
-
         // Optimizations begin.
         uniform_array_move_out::RestoreSubsliceArrayMoveOut,
         inline::Inline,
@@ -270,11 +342,19 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
         simplify_branches::SimplifyBranches::new("after-const-prop"),
         deaggregator::Deaggregator,
         copy_prop::CopyPropagation,
+        jump_threading::JumpThreading,
+        simplify_try::SimplifyArmIdentity,
+        simplify_try::SimplifyBranchSame,
         remove_noop_landing_pads::RemoveNoopLandingPads,
         simplify::SimplifyCfg::new("final"),
+        simplify::DeduplicateBlocks,
+        dest_prop::DestinationPropagation,
         simplify::SimplifyLocals,
 
         add_call_guards::CriticalCallEdges,
+
+        // Runs last, so that every basic block trans sees gets a counter.
+        instrument_coverage::InstrumentCoverage,
         dump_mir::Marker("PreTrans"),
     ];
     tcx.alloc_mir(mir)
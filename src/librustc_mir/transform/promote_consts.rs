@@ -66,6 +66,21 @@ impl TempState {
 /// A "root candidate" for promotion, which will become the
 /// returned value in a promoted MIR, unless it's a subset
 /// of a larger candidate.
+///
+/// Which values may be promoted depends on the kind of candidate:
+///
+/// - A `Ref` in a `fn` or `const fn` body is promoted *implicitly*, i.e.
+///   the user never asked for it, so the value must be one whose evaluation
+///   can never fail or behave differently at compile-time. Besides the usual
+///   restrictions (no `Drop`, no interior mutability, no runtime values),
+///   this excludes calls to `const fn`s which are not `#[rustc_promotable]`
+///   and reads of union fields.
+/// - A `Ref` in a `const` or `static` initializer can refer to any constant
+///   value, as every temporary there lives for the whole program anyway.
+/// - An `Argument` is promoted *explicitly*, because the callee requires a
+///   constant, so any constant value is accepted, including `const fn` calls.
+///
+/// Inline assembly operands are never promoted.
 #[derive(Debug)]
 pub enum Candidate {
     /// Borrow of a constant temporary.
@@ -79,6 +94,18 @@ pub enum Candidate {
     Argument { bb: BasicBlock, index: usize },
 }
 
+impl Candidate {
+    /// Returns `true` if the user explicitly asked for this candidate to be
+    /// evaluated at compile-time, so that it does not have to follow the
+    /// stricter rules of implicit promotion.
+    pub fn forces_explicit_promotion(&self) -> bool {
+        match *self {
+            Candidate::Ref(_) => false,
+            Candidate::Argument { .. } => true,
+        }
+    }
+}
+
 struct TempCollector<'tcx> {
     temps: IndexVec<Local, TempState>,
     span: Span,
@@ -70,10 +70,17 @@ bitflags! {
         // promote_consts decided they weren't simple enough.
         const NOT_PROMOTABLE    = 1 << 6;
 
-        // Const items can only have MUTABLE_INTERIOR
-        // and NOT_PROMOTABLE without producing an error.
+        // Constant, but only promotable where a constant is
+        // explicitly required (see `promote_consts::Candidate`):
+        // calls to `const fn`s without `#[rustc_promotable]`
+        // and reads of union fields.
+        const EXPLICIT_ONLY     = 1 << 7;
+
+        // Const items can only have MUTABLE_INTERIOR, NOT_PROMOTABLE
+        // and EXPLICIT_ONLY without producing an error.
         const CONST_ERROR       = !Qualif::MUTABLE_INTERIOR.bits &
-                                  !Qualif::NOT_PROMOTABLE.bits;
+                                  !Qualif::NOT_PROMOTABLE.bits &
+                                  !Qualif::EXPLICIT_ONLY.bits;
     }
 }
 
@@ -164,12 +171,24 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
         }
     }
 
+    /// With `-Z unleash-the-miri`, the restrictions of const contexts aren't
+    /// enforced, and the const evaluator is left to deal with whatever it
+    /// gets. A warning is emitted instead of each error that would have been.
+    fn unleashed(&self) -> bool {
+        if self.tcx.sess.opts.debugging_opts.unleash_the_miri {
+            self.tcx.sess.span_warn(self.span, "skipping const checks");
+            true
+        } else {
+            false
+        }
+    }
+
     // FIXME(eddyb) we could split the errors into meaningful
     // categories, but enabling full miri would make that
     // slightly pointless (even with feature-gating).
     fn not_const(&mut self) {
         self.add(Qualif::NOT_CONST);
-        if self.mode != Mode::Fn {
+        if self.mode != Mode::Fn && !self.unleashed() {
             let mut err = struct_span_err!(
                 self.tcx.sess,
                 self.span,
@@ -230,14 +249,31 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
 
     /// Check if a Local with the current qualifications is promotable.
     fn can_promote(&mut self) -> bool {
+        let qualif = self.qualif - Qualif::EXPLICIT_ONLY;
         // References to statics are allowed, but only in other statics.
         if self.mode == Mode::Static || self.mode == Mode::StaticMut {
-            (self.qualif - Qualif::STATIC_REF).is_empty()
+            (qualif - Qualif::STATIC_REF).is_empty()
         } else {
-            self.qualif.is_empty()
+            qualif.is_empty()
         }
     }
 
+    /// Check if the current value can be promoted for `candidate`, following
+    /// the rules documented on `promote_consts::Candidate`.
+    fn can_promote_candidate(&mut self, candidate: &Candidate) -> bool {
+        if !self.can_promote() {
+            return false;
+        }
+        // In constants and statics, every temporary without a `StorageDead`
+        // is `'static` anyway, so only function bodies make a difference
+        // between implicit and explicit promotion. Crates which didn't opt
+        // into the restrictions still promote everything that is constant.
+        let implicit = (self.mode == Mode::Fn || self.mode == Mode::ConstFn) &&
+            !candidate.forces_explicit_promotion() &&
+            self.tcx.features().restricted_promotion;
+        !implicit || !self.qualif.contains(Qualif::EXPLICIT_ONLY)
+    }
+
     /// Check if a Place with the current qualifications could
     /// be consumed, by either an operand or a Deref projection.
     fn try_consume(&mut self) -> bool {
@@ -530,7 +566,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 
                             let base_ty = proj.base.ty(this.mir, this.tcx).to_ty(this.tcx);
                             if let ty::TyRawPtr(_) = base_ty.sty {
-                                if this.mode != Mode::Fn {
+                                if this.mode != Mode::Fn && !this.unleashed() {
                                     let mut err = struct_span_err!(
                                         this.tcx.sess,
                                         this.span,
@@ -562,6 +598,14 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                                           "cannot refer to the interior of another \
                                            static, use a constant instead");
                             }
+                            let base_ty = proj.base.ty(this.mir, this.tcx).to_ty(this.tcx);
+                            if let ty::TyAdt(def, _) = base_ty.sty {
+                                if def.is_union() {
+                                    // Which field of a union is initialized is
+                                    // only known at runtime.
+                                    this.add(Qualif::EXPLICIT_ONLY);
+                                }
+                            }
                             let ty = place.ty(this.mir, this.tcx).to_ty(this.tcx);
                             this.qualif.restrict(ty, this.tcx, this.param_env);
                         }
@@ -604,7 +648,8 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                         let (bits, _) = self.tcx.at(constant.span).mir_const_qualif(def_id);
 
                         let qualif = Qualif::from_bits(bits).expect("invalid mir_const_qualif");
-                        self.add(qualif);
+                        // The value of a constant is known, however it was computed.
+                        self.add(qualif - Qualif::EXPLICIT_ONLY);
 
                         // Just in case the type is more specific than
                         // the definition, e.g. impl associated const
@@ -736,7 +781,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 
                 // We might have a candidate for promotion.
                 let candidate = Candidate::Ref(location);
-                if self.can_promote() {
+                if self.can_promote_candidate(&candidate) {
                     // We can only promote direct borrows of temps.
                     if let Place::Local(local) = *place {
                         if self.mir.local_kind(local) == LocalKind::Temp {
@@ -754,7 +799,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                     (CastTy::Ptr(_), CastTy::Int(_)) |
                     (CastTy::FnPtr, CastTy::Int(_)) => {
                         self.add(Qualif::NOT_CONST);
-                        if self.mode != Mode::Fn {
+                        if self.mode != Mode::Fn && !self.unleashed() {
                             let mut err = struct_span_err!(
                                 self.tcx.sess,
                                 self.span,
@@ -805,7 +850,7 @@ This does not pose a problem by itself because they can't be accessed directly."
                             op == BinOp::Offset);
 
                     self.add(Qualif::NOT_CONST);
-                    if self.mode != Mode::Fn {
+                    if self.mode != Mode::Fn && !self.unleashed() {
                         struct_span_err!(
                             self.tcx.sess, self.span, E0395,
                             "raw pointers cannot be compared in {}s",
@@ -862,12 +907,14 @@ This does not pose a problem by itself because they can't be accessed directly."
             let fn_ty = func.ty(self.mir, self.tcx);
             let mut callee_def_id = None;
             let (mut is_shuffle, mut is_const_fn) = (false, None);
+            let mut is_intrinsic = false;
             if let ty::TyFnDef(def_id, _) = fn_ty.sty {
                 callee_def_id = Some(def_id);
                 match self.tcx.fn_sig(def_id).abi() {
                     Abi::RustIntrinsic |
                     Abi::PlatformIntrinsic => {
                         assert!(!self.tcx.is_const_fn(def_id));
+                        is_intrinsic = true;
                         match &self.tcx.item_name(def_id)[..] {
                             "size_of" | "min_align_of" | "type_id" => is_const_fn = Some(def_id),
 
@@ -897,7 +944,7 @@ This does not pose a problem by itself because they can't be accessed directly."
                     }
                     let candidate = Candidate::Argument { bb, index: i };
                     if is_shuffle && i == 2 {
-                        if this.can_promote() {
+                        if this.can_promote_candidate(&candidate) {
                             this.promotion_candidates.push(candidate);
                         } else {
                             span_err!(this.tcx.sess, this.span, E0526,
@@ -913,7 +960,7 @@ This does not pose a problem by itself because they can't be accessed directly."
                     if !constant_arguments.contains(&i) {
                         return
                     }
-                    if this.can_promote() {
+                    if this.can_promote_candidate(&candidate) {
                         this.promotion_candidates.push(candidate);
                     } else {
                         this.tcx.sess.span_err(this.span,
@@ -925,6 +972,12 @@ This does not pose a problem by itself because they can't be accessed directly."
 
             // Const fn calls.
             if let Some(def_id) = is_const_fn {
+                // Intrinsics and functions which promise to stay promotable
+                // are the only calls that can be promoted implicitly.
+                if !is_intrinsic && !self.tcx.has_attr(def_id, "rustc_promotable") {
+                    self.add(Qualif::EXPLICIT_ONLY);
+                }
+
                 // find corresponding rustc_const_unstable feature
                 if let Some(&attr::Stability {
                     rustc_const_unstable: Some(attr::RustcConstUnstable {
@@ -959,7 +1012,7 @@ This does not pose a problem by itself because they can't be accessed directly."
                 }
             } else {
                 self.qualif = Qualif::NOT_CONST;
-                if self.mode != Mode::Fn {
+                if self.mode != Mode::Fn && !self.unleashed() {
                     // FIXME(#24111) Remove this check when const fn stabilizes
                     let (msg, note) = if let UnstableFeatures::Disallow =
                             self.tcx.sess.opts.unstable_features {
@@ -989,10 +1042,11 @@ This does not pose a problem by itself because they can't be accessed directly."
                 if self.qualif.intersects(Qualif::CONST_ERROR) {
                     self.qualif = Qualif::NOT_CONST;
                 } else {
-                    // Be conservative about the returned value of a const fn.
+                    // Be conservative about the returned value of a const fn,
+                    // but remember whether the call can only be promoted explicitly.
                     let tcx = self.tcx;
                     let ty = dest.ty(self.mir, tcx).to_ty(tcx);
-                    self.qualif = Qualif::empty();
+                    self.qualif = self.qualif & Qualif::EXPLICIT_ONLY;
                     self.add_type(ty);
                 }
                 self.assign(dest, location);
@@ -1099,6 +1153,7 @@ This does not pose a problem by itself because they can't be accessed directly."
                 StatementKind::InlineAsm {..} |
                 StatementKind::EndRegion(_) |
                 StatementKind::Validate(..) |
+                StatementKind::Coverage { .. } |
                 StatementKind::Nop => {}
             }
         });
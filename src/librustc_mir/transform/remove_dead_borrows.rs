@@ -0,0 +1,93 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+//! Removes the borrows whose result is never used.
+//!
+//! Once borrowck has run, a borrow only matters through the uses of the
+//! reference it creates. Borrows like the one of `let _ = &x;` store their
+//! reference in a temporary which is never used. Such borrows are found by
+//! looking for the `Rvalue::Ref`s assigned to a temporary which has no other
+//! uses; this only depends on the uses of the temporaries, not on regions.
+//! The borrows are then replaced with `Nop`s, along with the storage
+//! statements of their temporaries. This makes the MIR smaller, and keeps
+//! LLVM from having to assume that the borrowed places escape.
+
+use rustc::mir::*;
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bitvec::BitVector;
+use rustc_data_structures::indexed_vec::Idx;
+use transform::{MirPass, MirSource};
+use util::def_use::DefUseAnalysis;
+
+pub struct RemoveDeadBorrows;
+
+impl MirPass for RemoveDeadBorrows {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level >= 2
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          _tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let dead_borrows = find_dead_borrows(mir);
+        if dead_borrows.is_empty() {
+            return
+        }
+        debug!("RemoveDeadBorrows: removing {:?} in {:?}", dead_borrows, source.def_id);
+
+        let mut dead_temps = BitVector::new(mir.local_decls.len());
+        for (location, temp) in dead_borrows {
+            mir.make_statement_nop(location);
+            dead_temps.insert(temp.index());
+        }
+        for block in mir.basic_blocks_mut() {
+            for statement in &mut block.statements {
+                match statement.kind {
+                    StatementKind::StorageLive(local) |
+                    StatementKind::StorageDead(local) if dead_temps.contains(local.index()) => {
+                        statement.make_nop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// The locations of the borrows of `mir` whose result is stored in a
+/// temporary that is never used, along with those temporaries.
+fn find_dead_borrows<'tcx>(mir: &Mir<'tcx>) -> Vec<(Location, Local)> {
+    let mut def_use_analysis = DefUseAnalysis::new(mir);
+    def_use_analysis.analyze(mir);
+
+    let mut dead_borrows = vec![];
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            let temp = match statement.kind {
+                StatementKind::Assign(Place::Local(local), Rvalue::Ref(..))
+                    if mir.local_kind(local) == LocalKind::Temp => local,
+                _ => continue,
+            };
+            let location = Location { block, statement_index };
+            // Besides its storage statements, the temporary must only appear
+            // in the borrow itself.
+            let unused = def_use_analysis.local_info(temp).defs_and_uses.iter().all(|place_use| {
+                place_use.context.is_storage_marker() || place_use.location == location
+            });
+            if unused {
+                dead_borrows.push((location, temp));
+            }
+        }
+    }
+    dead_borrows
+}
@@ -64,7 +64,8 @@ impl RemoveNoopLandingPads {
                 StatementKind::Assign(_, _) |
                 StatementKind::SetDiscriminant { .. } |
                 StatementKind::InlineAsm { .. } |
-                StatementKind::Validate { .. } => {
+                StatementKind::Validate { .. } |
+                StatementKind::Coverage { .. } => {
                     return false;
                 }
             }
@@ -0,0 +1,83 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This pass normalizes all the types occurring in the MIR in `Reveal::All`
+//! mode, right after `EraseRegions`, so that later passes and trans never see
+//! projections which could have been normalized.
+//! NOTE: Like `EraseRegions`, we do NOT touch validation statements.
+
+use rustc::hir::def_id::DefId;
+use rustc::ty::subst::Substs;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::mir::*;
+use rustc::mir::visit::{MutVisitor, TyContext};
+use transform::{MirPass, MirSource};
+
+struct RevealAllVisitor<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    in_validation_statement: bool,
+}
+
+impl<'a, 'tcx> RevealAllVisitor<'a, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> Self {
+        RevealAllVisitor {
+            tcx,
+            param_env: tcx.param_env(def_id).reveal_all(),
+            in_validation_statement: false,
+        }
+    }
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for RevealAllVisitor<'a, 'tcx> {
+    fn visit_ty(&mut self, ty: &mut Ty<'tcx>, _: TyContext) {
+        if !self.in_validation_statement {
+            *ty = self.tcx.normalize_associated_type_in_env(ty, self.param_env);
+        }
+        self.super_ty(ty);
+    }
+
+    fn visit_const(&mut self, constant: &mut &'tcx ty::Const<'tcx>, _: Location) {
+        *constant = self.tcx.normalize_associated_type_in_env(constant, self.param_env);
+    }
+
+    fn visit_substs(&mut self, substs: &mut &'tcx Substs<'tcx>, _: Location) {
+        *substs = self.tcx.normalize_associated_type_in_env(substs, self.param_env);
+    }
+
+    fn visit_closure_substs(&mut self,
+                            substs: &mut ty::ClosureSubsts<'tcx>,
+                            _: Location) {
+        *substs = self.tcx.normalize_associated_type_in_env(substs, self.param_env);
+    }
+
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &mut Statement<'tcx>,
+                       location: Location) {
+        self.in_validation_statement = match statement.kind {
+            StatementKind::Validate(..) => true,
+            _ => false,
+        };
+        self.super_statement(block, statement, location);
+        self.in_validation_statement = false;
+    }
+}
+
+pub struct RevealAll;
+
+impl MirPass for RevealAll {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        RevealAllVisitor::new(tcx, source.def_id).visit_mir(mir);
+    }
+}
@@ -13,6 +13,13 @@
 //! The `SimplifyCfg` pass gets rid of unnecessary blocks in the CFG, whereas the `SimplifyLocals`
 //! gets rid of all the unnecessary local variable declarations.
 //!
+//! The `DeduplicateBlocks` pass merges identical basic blocks: match lowering and drop
+//! elaboration generate many identical panic and cleanup blocks, and every jump to such a block
+//! is redirected to the first of its copies. It only runs after borrowck, whose errors point at
+//! individual blocks, and with `-Z mir-opt-level=1` or higher. Blocks that are not cleanup
+//! blocks are only merged with `-Z mir-opt-level=2` or higher, since that loses their source
+//! information.
+//!
 //! The `SimplifyLocals` pass is kinda expensive and therefore not very suitable to be run often.
 //! Most of the passes should not care or be impacted in meaningful ways due to extra locals
 //! either, so running the pass once, right before translation, should suffice.
@@ -38,12 +45,16 @@
 //! return.
 
 use rustc_data_structures::bitvec::BitVector;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use rustc::ich::{Fingerprint, StableHashingContext};
 use rustc::ty::TyCtxt;
 use rustc::mir::*;
 use rustc::mir::visit::{MutVisitor, Visitor, PlaceContext};
 use rustc::session::config::FullDebugInfo;
+use rustc::util::nodemap::FxHashMap;
 use std::borrow::Cow;
+use syntax_pos::DUMMY_SP;
 use transform::{MirPass, MirSource};
 
 pub struct SimplifyCfg { label: String }
@@ -277,6 +288,125 @@ pub fn remove_dead_blocks(mir: &mut Mir) {
     }
 }
 
+pub struct DeduplicateBlocks;
+
+impl MirPass for DeduplicateBlocks {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _src: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let mir_opt_level = tcx.sess.opts.debugging_opts.mir_opt_level;
+        if mir_opt_level < 1 {
+            return;
+        }
+
+        // Redirecting jumps to a duplicate can make further blocks identical,
+        // so keep going until nothing changes. Every round removes at least
+        // one block, so this terminates.
+        while deduplicate_blocks(tcx, mir, mir_opt_level >= 2) {
+            simplify_cfg(mir);
+        }
+    }
+}
+
+/// Redirects every jump to a basic block to the first block that is
+/// structurally identical to it, leaving the duplicates dead. Only cleanup
+/// blocks are considered unless `include_non_cleanup` is set. Returns whether
+/// any jump was redirected.
+pub fn deduplicate_blocks<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                    mir: &mut Mir<'tcx>,
+                                    include_non_cleanup: bool)
+                                    -> bool {
+    let mut replacements: IndexVec<BasicBlock, BasicBlock> =
+        mir.basic_blocks().indices().collect();
+    let mut changed = false;
+    {
+        let mut hcx = tcx.create_stable_hashing_context();
+        let mut canonical_blocks: FxHashMap<Fingerprint, BasicBlock> = FxHashMap();
+        for (bb, data) in mir.basic_blocks().iter_enumerated() {
+            if !data.is_cleanup && !include_non_cleanup {
+                continue
+            }
+            let canonical = *canonical_blocks.entry(block_fingerprint(&mut hcx, data))
+                                             .or_insert(bb);
+            if canonical != bb {
+                debug!("deduplicate_blocks: {:?} is a duplicate of {:?}", bb, canonical);
+                replacements[bb] = canonical;
+                changed = true;
+            }
+        }
+    }
+
+    if changed {
+        for block in mir.basic_blocks_mut() {
+            for target in block.terminator_mut().successors_mut() {
+                *target = replacements[*target];
+            }
+        }
+    }
+    changed
+}
+
+/// Hashes everything about a basic block that `blocks_equivalent` compares,
+/// so that blocks with the same fingerprint can be merged without comparing
+/// (or cloning) them pairwise.
+fn block_fingerprint<'a, 'tcx>(hcx: &mut StableHashingContext<'a>,
+                               data: &BasicBlockData<'tcx>)
+                               -> Fingerprint {
+    let mut hasher = StableHasher::new();
+    hcx.while_hashing_spans(false, |hcx| {
+        data.is_cleanup.hash_stable(hcx, &mut hasher);
+        for statement in &data.statements {
+            statement.kind.hash_stable(hcx, &mut hasher);
+        }
+        let terminator = data.terminator();
+        terminator.kind.hash_stable(hcx, &mut hasher);
+        if let TerminatorKind::Assert { .. } = terminator.kind {
+            // The span of a failed assertion ends up in its panic message.
+            hcx.while_hashing_spans(true, |hcx| {
+                terminator.source_info.span.hash_stable(hcx, &mut hasher);
+            });
+        }
+    });
+    hasher.finish()
+}
+
+/// Checks whether two basic blocks have exactly the same effect. Source
+/// information, including the spans of constants, is ignored, except where
+/// it is observable at runtime. A cleanup block is never equivalent to a
+/// block which isn't one.
+pub fn blocks_equivalent<'tcx>(a: &BasicBlockData<'tcx>, b: &BasicBlockData<'tcx>) -> bool {
+    if a.is_cleanup != b.is_cleanup || a.statements.len() != b.statements.len() {
+        return false
+    }
+    let (a, b) = (erase_constant_spans(a), erase_constant_spans(b));
+    a.statements.iter().zip(&b.statements).all(|(a, b)| a.kind == b.kind) &&
+        terminators_equivalent(a.terminator(), b.terminator())
+}
+
+/// Returns a copy of `data` in which the span of every constant is dummy,
+/// so that blocks can be compared without them.
+fn erase_constant_spans<'tcx>(data: &BasicBlockData<'tcx>) -> BasicBlockData<'tcx> {
+    struct ConstantSpanEraser;
+
+    impl<'tcx> MutVisitor<'tcx> for ConstantSpanEraser {
+        fn visit_constant(&mut self, constant: &mut Constant<'tcx>, _: Location) {
+            constant.span = DUMMY_SP;
+        }
+    }
+
+    let mut data = data.clone();
+    ConstantSpanEraser.visit_basic_block_data(START_BLOCK, &mut data);
+    data
+}
+
+fn terminators_equivalent(a: &Terminator, b: &Terminator) -> bool {
+    match a.kind {
+        // The span of a failed assertion ends up in its panic message.
+        TerminatorKind::Assert { .. } => a.source_info == b.source_info && a.kind == b.kind,
+        _ => a.kind == b.kind,
+    }
+}
 
 pub struct SimplifyLocals;
 
@@ -285,28 +415,73 @@ impl MirPass for SimplifyLocals {
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           _: MirSource,
                           mir: &mut Mir<'tcx>) {
-        let mut marker = DeclMarker { locals: BitVector::new(mir.local_decls.len()) };
-        marker.visit_mir(mir);
-        // Return pointer and arguments are always live
-        marker.locals.insert(RETURN_PLACE.index());
-        for arg in mir.args_iter() {
-            marker.locals.insert(arg.index());
-        }
+        // Removing the assignments to a local that is never read can leave
+        // the locals they read from unused in turn, so iterate until nothing
+        // more can be removed.
+        let used = loop {
+            let mut marker = DeclMarker { locals: BitVector::new(mir.local_decls.len()) };
+            marker.visit_mir(mir);
+            // Return pointer and arguments are always live
+            marker.locals.insert(RETURN_PLACE.index());
+            for arg in mir.args_iter() {
+                marker.locals.insert(arg.index());
+            }
 
-        // We may need to keep dead user variables live for debuginfo.
-        if tcx.sess.opts.debuginfo == FullDebugInfo {
-            for local in mir.vars_iter() {
-                marker.locals.insert(local.index());
+            // We may need to keep dead user variables live for debuginfo.
+            if tcx.sess.opts.debuginfo == FullDebugInfo {
+                for local in mir.vars_iter() {
+                    marker.locals.insert(local.index());
+                }
             }
-        }
 
-        let map = make_local_map(&mut mir.local_decls, marker.locals);
+            if !remove_unused_definitions(mir, &marker.locals) {
+                break marker.locals;
+            }
+        };
+
+        let map = make_local_map(&mut mir.local_decls, used);
         // Update references to all vars and tmps now
         LocalUpdater { map: map }.visit_mir(mir);
         mir.local_decls.shrink_to_fit();
     }
 }
 
+/// Removes the statements which only write to locals that are never read.
+/// Returns whether any such statement was found.
+fn remove_unused_definitions(mir: &mut Mir, used: &BitVector) -> bool {
+    let mut removed = false;
+    for data in mir.basic_blocks_mut() {
+        data.statements.retain(|stmt| {
+            let keep = match stmt.kind {
+                StatementKind::Assign(ref place, _) |
+                StatementKind::SetDiscriminant { ref place, .. } => {
+                    match written_local(place) {
+                        Some(local) => used.contains(local.index()),
+                        None => true,
+                    }
+                }
+                _ => true,
+            };
+            removed |= !keep;
+            keep
+        });
+    }
+    removed
+}
+
+/// The local whose contents are partially or completely overwritten when
+/// writing to `place`, if writing to `place` does not read any local.
+fn written_local(place: &Place) -> Option<Local> {
+    match *place {
+        Place::Local(local) => Some(local),
+        Place::Projection(ref proj) => match proj.elem {
+            ProjectionElem::Field(..) | ProjectionElem::Downcast(..) => written_local(&proj.base),
+            _ => None,
+        },
+        Place::Static(_) => None,
+    }
+}
+
 /// Construct the mapping while swapping out unused stuff out from the `vec`.
 fn make_local_map<'tcx, I: Idx, V>(vec: &mut IndexVec<I, V>, mask: BitVector) -> Vec<usize> {
     let mut map: Vec<usize> = ::std::iter::repeat(!0).take(vec.len()).collect();
@@ -322,11 +497,28 @@ fn make_local_map<'tcx, I: Idx, V>(vec: &mut IndexVec<I, V>, mask: BitVector) ->
     map
 }
 
+/// Collects the locals that are read somewhere. Storage markers are not
+/// reads, and neither are statements which only write (a field of) a local,
+/// since those are removed along with the local if it turns out to be unused.
 struct DeclMarker {
     pub locals: BitVector,
 }
 
 impl<'tcx> Visitor<'tcx> for DeclMarker {
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       location: Location) {
+        match statement.kind {
+            StatementKind::Assign(ref place, ref rvalue) if written_local(place).is_some() => {
+                self.visit_rvalue(rvalue, location);
+            }
+            StatementKind::SetDiscriminant { ref place, .. }
+                if written_local(place).is_some() => {}
+            _ => self.super_statement(block, statement, location),
+        }
+    }
+
     fn visit_local(&mut self, local: &Local, ctx: PlaceContext<'tcx>, _: Location) {
         // ignore these altogether, they get removed along with their otherwise unused decls.
         if ctx != PlaceContext::StorageLive && ctx != PlaceContext::StorageDead {
@@ -341,12 +533,14 @@ struct LocalUpdater {
 
 impl<'tcx> MutVisitor<'tcx> for LocalUpdater {
     fn visit_basic_block_data(&mut self, block: BasicBlock, data: &mut BasicBlockData<'tcx>) {
-        // Remove unnecessary StorageLive and StorageDead annotations.
+        // Remove unnecessary StorageLive and StorageDead annotations, as well
+        // as any leftover nops.
         data.statements.retain(|stmt| {
             match stmt.kind {
                 StatementKind::StorageLive(l) | StatementKind::StorageDead(l) => {
                     self.map[l.index()] != !0
                 }
+                StatementKind::Nop => false,
                 _ => true
             }
         });
@@ -0,0 +1,325 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Peephole passes that clean up matches which merely rebuild their
+//! scrutinee, as in:
+//!
+//! ```rust
+//! match x {
+//!     Ok(v) => Ok(v),
+//!     Err(e) => Err(e),
+//! }
+//! ```
+//!
+//! `SimplifyArmIdentity` turns every arm of such a match into a plain
+//! `_0 = move _1` (or `_0 = _1` if the scrutinee's type is `Copy`), after which
+//! `SimplifyBranchSame` notices that all the arms are identical and replaces
+//! the `switchInt` with a `goto`.
+//!
+//! Both passes run only at `-Z mir-opt-level=2` and above.
+
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::indexed_vec::IndexVec;
+use transform::{MirPass, MirSource};
+use transform::simplify;
+
+/// Simplifies arms of the form:
+///
+/// ```text
+/// _LOCAL_TMP = move ((_LOCAL_1 as Variant).FIELD: TY);
+/// ((_LOCAL_0 as Variant).FIELD: TY) = move _LOCAL_TMP;
+/// discriminant(_LOCAL_0) = VAR_IDX;
+/// ```
+///
+/// into:
+///
+/// ```text
+/// _LOCAL_0 = move _LOCAL_1
+/// ```
+///
+/// If the field is copied out of `_LOCAL_1` rather than moved and the type of
+/// `_LOCAL_1` is itself `Copy`, `_LOCAL_1` is copied as a whole instead.
+/// Otherwise it is moved: copying a `Result<u32, String>` is not valid MIR,
+/// even in an arm where it is known to hold an `Ok`.
+///
+/// Intermediate copies between temporaries (`_TMP2 = move _TMP1`), as well
+/// as storage markers, are allowed to appear between the statements above.
+/// The temporaries must not be used anywhere else; their storage markers are
+/// removed along with the statements, so that the rewritten arms of a match
+/// end up identical.
+pub struct SimplifyArmIdentity;
+
+impl MirPass for SimplifyArmIdentity {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level >= 2
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let param_env = tcx.param_env(source.def_id);
+        let use_counts = count_uses(mir);
+        let (basic_blocks, local_decls) = mir.basic_blocks_and_local_decls_mut();
+        for bb in basic_blocks {
+            let mut index = 0;
+            while index < bb.statements.len() {
+                let arm = match_identity_arm(&bb.statements[index..]);
+                match arm {
+                    Some(arm) => {
+                        if local_decls[arm.local_0].ty != local_decls[arm.local_1].ty ||
+                            arm.tmps.iter().any(|&tmp| use_counts[tmp] != 2)
+                        {
+                            index += 1;
+                            continue
+                        }
+
+                        debug!("SimplifyArmIdentity: rewriting {:?} in {:?}", arm, bb);
+                        let local_1 = Place::Local(arm.local_1);
+                        let span = bb.statements[index].source_info.span;
+                        let ty = local_decls[arm.local_1].ty;
+                        let operand = if arm.copy && !ty.moves_by_default(tcx, param_env, span) {
+                            Operand::Copy(local_1)
+                        } else {
+                            Operand::Move(local_1)
+                        };
+                        bb.statements[index].kind = StatementKind::Assign(
+                            Place::Local(arm.local_0),
+                            Rvalue::Use(operand),
+                        );
+                        for &i in &arm.to_nop {
+                            bb.statements[index + i].make_nop();
+                        }
+                        for stmt in &mut bb.statements {
+                            match stmt.kind {
+                                StatementKind::StorageLive(local) |
+                                StatementKind::StorageDead(local)
+                                    if arm.tmps.contains(&local) => stmt.make_nop(),
+                                _ => {}
+                            }
+                        }
+                        index += arm.len;
+                    }
+                    None => index += 1,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct IdentityArm {
+    /// The local being written to, `_LOCAL_0` above.
+    local_0: Local,
+    /// The local being read from, `_LOCAL_1` above.
+    local_1: Local,
+    /// Whether the field is copied out of `_LOCAL_1` rather than moved.
+    copy: bool,
+    /// The temporaries the field goes through, `_LOCAL_TMP` above.
+    tmps: Vec<Local>,
+    /// Indices, relative to the first statement, of the statements that
+    /// become dead once the first one is rewritten.
+    to_nop: Vec<usize>,
+    /// Number of statements covered by the pattern.
+    len: usize,
+}
+
+fn match_identity_arm(stmts: &[Statement]) -> Option<IdentityArm> {
+    let (mut tmp, local_1, variant, field, copy) = match_get_variant_field(stmts.first()?)?;
+    let mut tmps = vec![tmp];
+    let mut to_nop = vec![];
+
+    let mut i = 1;
+    let local_0 = loop {
+        let stmt = stmts.get(i)?;
+        match stmt.kind {
+            StatementKind::StorageLive(_) |
+            StatementKind::StorageDead(_) |
+            StatementKind::Nop => {}
+            StatementKind::Assign(Place::Local(dest),
+                                  Rvalue::Use(Operand::Move(Place::Local(src)))) |
+            StatementKind::Assign(Place::Local(dest),
+                                  Rvalue::Use(Operand::Copy(Place::Local(src))))
+                if src == tmp => {
+                tmp = dest;
+                tmps.push(tmp);
+                to_nop.push(i);
+            }
+            _ => {
+                let (src, local_0, set_variant, set_field) = match_set_variant_field(stmt)?;
+                if src != tmp || set_variant != variant || set_field != field {
+                    return None
+                }
+                to_nop.push(i);
+                break local_0;
+            }
+        }
+        i += 1;
+    };
+
+    i += 1;
+    loop {
+        let stmt = stmts.get(i)?;
+        match stmt.kind {
+            StatementKind::StorageLive(_) |
+            StatementKind::StorageDead(_) |
+            StatementKind::Nop => {}
+            StatementKind::SetDiscriminant { place: Place::Local(place), variant_index }
+                if place == local_0 && variant_index == variant => {
+                to_nop.push(i);
+                break;
+            }
+            _ => return None,
+        }
+        i += 1;
+    }
+
+    if local_0 == local_1 {
+        return None
+    }
+
+    Some(IdentityArm { local_0, local_1, copy, tmps, to_nop, len: i + 1 })
+}
+
+/// Counts how many times each local is used in `mir`, not counting storage
+/// markers.
+fn count_uses(mir: &Mir) -> IndexVec<Local, usize> {
+    struct UseCounter {
+        counts: IndexVec<Local, usize>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for UseCounter {
+        fn visit_local(&mut self, local: &Local, context: PlaceContext<'tcx>, _: Location) {
+            if !context.is_storage_marker() {
+                self.counts[*local] += 1;
+            }
+        }
+    }
+
+    let mut counter = UseCounter { counts: IndexVec::from_elem(0, &mir.local_decls) };
+    counter.visit_mir(mir);
+    counter.counts
+}
+
+/// Match on:
+///
+/// ```text
+/// _LOCAL_INTO = move ((_LOCAL_FROM as Variant).FIELD: TY);
+/// ```
+///
+/// or on the same statement with a copy instead of a move, in which case the
+/// last element of the result is `true`.
+fn match_get_variant_field(stmt: &Statement) -> Option<(Local, Local, usize, Field, bool)> {
+    match stmt.kind {
+        StatementKind::Assign(Place::Local(local_into), Rvalue::Use(ref operand)) => {
+            let (place, copy) = match *operand {
+                Operand::Move(ref place) => (place, false),
+                Operand::Copy(ref place) => (place, true),
+                Operand::Constant(_) => return None,
+            };
+            let (local_from, variant, field) = match_variant_field_place(place)?;
+            Some((local_into, local_from, variant, field, copy))
+        }
+        _ => None,
+    }
+}
+
+/// Match on:
+///
+/// ```text
+/// ((_LOCAL_FROM as Variant).FIELD: TY) = move _LOCAL_INTO;
+/// ```
+///
+/// or on the same statement with a copy instead of a move.
+fn match_set_variant_field(stmt: &Statement) -> Option<(Local, Local, usize, Field)> {
+    match stmt.kind {
+        StatementKind::Assign(ref place, Rvalue::Use(Operand::Move(Place::Local(local_into)))) |
+        StatementKind::Assign(ref place, Rvalue::Use(Operand::Copy(Place::Local(local_into)))) => {
+            let (local_from, variant, field) = match_variant_field_place(place)?;
+            Some((local_into, local_from, variant, field))
+        }
+        _ => None,
+    }
+}
+
+/// Match on `((_LOCAL as Variant).FIELD: TY)`, where `Variant` has exactly
+/// one field.
+fn match_variant_field_place(place: &Place) -> Option<(Local, usize, Field)> {
+    if let Place::Projection(ref field_proj) = *place {
+        if let ProjectionElem::Field(field, _) = field_proj.elem {
+            if let Place::Projection(ref downcast_proj) = field_proj.base {
+                if let (&Place::Local(local), &ProjectionElem::Downcast(adt_def, variant)) =
+                    (&downcast_proj.base, &downcast_proj.elem)
+                {
+                    if adt_def.variants[variant].fields.len() == 1 {
+                        return Some((local, variant, field));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Replaces a `switchInt` whose reachable targets are all structurally
+/// identical basic blocks with a `goto` to the first of them.
+pub struct SimplifyBranchSame;
+
+impl MirPass for SimplifyBranchSame {
+    fn is_enabled(&self, sess: &Session) -> bool {
+        sess.opts.debugging_opts.mir_opt_level >= 2
+    }
+
+    fn run_pass<'a, 'tcx>(&self,
+                          _tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let mut replacements = vec![];
+        {
+            let bbs = mir.basic_blocks();
+            for (bb, data) in bbs.iter_enumerated() {
+                let targets = match data.terminator().kind {
+                    TerminatorKind::SwitchInt { ref targets, .. } => targets,
+                    _ => continue,
+                };
+
+                let mut reachable = targets.iter().cloned().filter(|&target| {
+                    match bbs[target].terminator().kind {
+                        TerminatorKind::Unreachable => false,
+                        _ => true,
+                    }
+                });
+                let first = match reachable.next() {
+                    Some(first) => first,
+                    None => continue,
+                };
+                let same = reachable.all(|target| {
+                    simplify::blocks_equivalent(&bbs[first], &bbs[target])
+                });
+                if same {
+                    replacements.push((bb, first));
+                }
+            }
+        }
+
+        if replacements.is_empty() {
+            return
+        }
+
+        for (bb, target) in replacements {
+            debug!("SimplifyBranchSame: {:?} always goes to {:?}", bb, target);
+            mir[bb].terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+        simplify::remove_dead_blocks(mir);
+    }
+}
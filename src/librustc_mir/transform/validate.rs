@@ -0,0 +1,405 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A structural validator for MIR, run after every MIR pass when
+//! `-Z validate-mir` is given.
+//!
+//! The validator only checks invariants that every pass is expected to
+//! preserve, so that a broken invariant can be blamed on the pass that
+//! just ran:
+//!
+//! - all branch targets exist, and `switchInt` has one more target than it
+//!   has values;
+//! - cleanup blocks only branch to cleanup blocks and never unwind, return
+//!   or yield, while normal blocks only unwind into cleanup blocks and never
+//!   resume unwinding; the start block is not a cleanup block;
+//! - once `AddCallGuards` has run for translation, no call edge with a
+//!   cleanup block is critical;
+//! - storage markers never apply to the return place or arguments, nor
+//!   appear in cleanup blocks, and no local is marked live again while its
+//!   storage may still be live along some path;
+//! - the type of an assignment's destination agrees with the type of its
+//!   rvalue (modulo regions), and operands have the types their users expect;
+//! - temporaries are only read after they have been assigned to along every
+//!   path, since their storage was last marked live or dead.
+
+use rustc::mir::*;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::indexed_set::IdxSetBuf;
+use rustc_data_structures::indexed_vec::Idx;
+use rustc::util::nodemap::FxHashMap;
+use dataflow::{do_dataflow, DebugFormatted, MaybeStorageLive, MaybeUnassignedTemps};
+use dataflow::{FlowAtLocation, FlowsAtLocation};
+use transform::MirSource;
+
+pub struct Validator<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    source: MirSource,
+    /// The name of the pass which produced the MIR being validated.
+    when: &'a str,
+}
+
+impl<'a, 'tcx> Validator<'a, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+               source: MirSource,
+               mir: &'a Mir<'tcx>,
+               when: &'a str)
+               -> Self {
+        Validator { tcx, mir, source, when }
+    }
+
+    /// Validates the MIR, ICE-ing on the first broken invariant found.
+    pub fn validate(mut self) {
+        if self.mir.basic_blocks()[START_BLOCK].is_cleanup {
+            self.fail(START_BLOCK.start_location(), "the start block is a cleanup block");
+        }
+        for (bb, data) in self.mir.basic_blocks().iter_enumerated() {
+            self.check_storage_markers(bb, data);
+        }
+        let mir = self.mir;
+        self.visit_mir(mir);
+        // The dataflow analysis relies on the edges and locals checked above.
+        self.check_storage_liveness();
+        self.check_temp_assignments();
+        if self.when == "PreTrans" {
+            self.check_critical_call_edges();
+        }
+    }
+
+    fn fail(&self, location: Location, msg: &str) -> ! {
+        let span = self.mir.source_info(location).span;
+        span_bug!(span,
+                  "broken MIR in {:?} ({:?}) after `{}` at {:?}:\n{}",
+                  self.source.def_id,
+                  self.source.promoted,
+                  self.when,
+                  location,
+                  msg)
+    }
+
+    fn check_edge(&self, location: Location, target: BasicBlock, is_unwind: bool) {
+        let bbs = self.mir.basic_blocks();
+        if target.index() >= bbs.len() {
+            self.fail(location, &format!("encountered jump to invalid basic block {:?}", target));
+        }
+        let src = &bbs[location.block];
+        match (src.is_cleanup, bbs[target].is_cleanup, is_unwind) {
+            // Regular edges within the same kind of block are fine, and
+            // unwinding always goes from normal code into cleanup code.
+            (false, false, false) |
+            (false, true, true) |
+            (true, true, false) => {}
+            (true, _, true) => {
+                self.fail(location, &format!("unwind edge to {:?} out of a cleanup block",
+                                             target));
+            }
+            (false, true, false) => {
+                self.fail(location, &format!("normal edge from a non-cleanup block into \
+                                              cleanup block {:?}", target));
+            }
+            (_, false, true) => {
+                self.fail(location, &format!("unwind edge to non-cleanup block {:?}", target));
+            }
+            (true, false, false) => {
+                self.fail(location, &format!("cleanup block branches to non-cleanup \
+                                              block {:?}", target));
+            }
+        }
+    }
+
+    fn check_unwind(&self, location: Location, unwind: Option<BasicBlock>) {
+        if let Some(unwind) = unwind {
+            self.check_edge(location, unwind, true);
+        }
+    }
+
+    fn check_storage_markers(&self, bb: BasicBlock, data: &BasicBlockData<'tcx>) {
+        // Whether each local was last marked live (`true`) or dead (`false`)
+        // within this block.
+        let mut last_marker = FxHashMap();
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            let location = Location { block: bb, statement_index };
+            let (local, live) = match statement.kind {
+                StatementKind::StorageLive(local) => (local, true),
+                StatementKind::StorageDead(local) => (local, false),
+                _ => continue,
+            };
+            if local == RETURN_PLACE || local.index() <= self.mir.arg_count {
+                self.fail(location, &format!("storage marker for {:?}, which is the return \
+                                              place or an argument", local));
+            }
+            if data.is_cleanup {
+                self.fail(location, &format!("storage marker for {:?} in a cleanup block",
+                                             local));
+            }
+            if last_marker.insert(local, live) == Some(live) {
+                self.fail(location, &format!("{:?} marked {} twice in a row",
+                                             local,
+                                             if live { "live" } else { "dead" }));
+            }
+        }
+    }
+
+    /// Checks that no `StorageLive` is reachable from another `StorageLive`
+    /// of the same local without a `StorageDead` of it in between.
+    fn check_storage_liveness(&self) {
+        let node_id = self.tcx.hir.as_local_node_id(self.source.def_id).unwrap();
+        let dead_unwinds = IdxSetBuf::new_empty(self.mir.basic_blocks().len());
+        let analysis = MaybeStorageLive::new(self.mir);
+        let storage_live =
+            do_dataflow(self.tcx, self.mir, node_id, &[], &dead_unwinds, analysis,
+                        |bd, p| DebugFormatted::new(&bd.mir().local_decls[p]));
+
+        for (bb, data) in self.mir.basic_blocks().iter_enumerated() {
+            let mut live = storage_live.sets().on_entry_set_for(bb.index()).to_owned();
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                match statement.kind {
+                    StatementKind::StorageLive(local) => {
+                        if !live.add(&local) {
+                            let location = Location { block: bb, statement_index };
+                            self.fail(location, &format!("{:?} marked live while its storage \
+                                                          may already be live", local));
+                        }
+                    }
+                    StatementKind::StorageDead(local) => {
+                        live.remove(&local);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Checks that every read of a temporary happens after an assignment to
+    /// it along every path leading there.
+    fn check_temp_assignments(&self) {
+        let node_id = self.tcx.hir.as_local_node_id(self.source.def_id).unwrap();
+        let dead_unwinds = IdxSetBuf::new_empty(self.mir.basic_blocks().len());
+        let analysis = MaybeUnassignedTemps::new(self.mir);
+        let unassigned =
+            do_dataflow(self.tcx, self.mir, node_id, &[], &dead_unwinds, analysis,
+                        |bd, p| DebugFormatted::new(&bd.mir().local_decls[p]));
+        let mut flow = FlowAtLocation::new(unassigned);
+
+        for (bb, data) in self.mir.basic_blocks().iter_enumerated() {
+            flow.reset_to_entry_of(bb);
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let location = Location { block: bb, statement_index };
+                let mut reads = TempReads { mir: self.mir, reads: vec![] };
+                reads.visit_statement(bb, statement, location);
+                self.check_temp_reads(&flow, location, reads.reads);
+                flow.reconstruct_statement_effect(location);
+                flow.apply_local_effect(location);
+            }
+            let location = Location { block: bb, statement_index: data.statements.len() };
+            let mut reads = TempReads { mir: self.mir, reads: vec![] };
+            reads.visit_terminator(bb, data.terminator(), location);
+            self.check_temp_reads(&flow, location, reads.reads);
+        }
+    }
+
+    fn check_temp_reads(&self,
+                        flow: &FlowAtLocation<MaybeUnassignedTemps<'a, 'tcx>>,
+                        location: Location,
+                        reads: Vec<Local>) {
+        for local in reads {
+            if flow.contains(&local) {
+                self.fail(location, &format!("read of temporary {:?}, which may not have \
+                                              been assigned to", local));
+            }
+        }
+    }
+
+    /// Checks that the return edge of a call which can unwind does not lead
+    /// to a block with other predecessors, as translation needs to put code
+    /// on that edge. `AddCallGuards::CriticalCallEdges` splits such edges
+    /// right before the MIR is handed to translation.
+    fn check_critical_call_edges(&self) {
+        let predecessors = self.mir.predecessors();
+        for (bb, data) in self.mir.basic_blocks().iter_enumerated() {
+            if let TerminatorKind::Call {
+                destination: Some((_, target)), cleanup: Some(_), ..
+            } = data.terminator().kind {
+                if predecessors[target].len() > 1 {
+                    let location = Location { block: bb, statement_index: data.statements.len() };
+                    self.fail(location, &format!("critical call edge to {:?}, which has {} \
+                                                  predecessors", target,
+                                                 predecessors[target].len()));
+                }
+            }
+        }
+    }
+
+    /// Whether `a` and `b` can be considered the same type. Regions are
+    /// ignored, and types for which equality is too strict (because of
+    /// subtyping between higher-ranked types, or unnormalized projections)
+    /// are always accepted.
+    fn types_agree(&self, a: Ty<'tcx>, b: Ty<'tcx>) -> bool {
+        if a == b {
+            return true;
+        }
+        let (a, b) = (self.tcx.erase_regions(&a), self.tcx.erase_regions(&b));
+        if a == b {
+            return true;
+        }
+        let is_opaque = |ty: Ty<'tcx>| {
+            ty.has_projections() || ty.walk().any(|ty| match ty.sty {
+                ty::TyFnPtr(..) |
+                ty::TyDynamic(..) |
+                ty::TyAnon(..) |
+                ty::TyGeneratorWitness(..) => true,
+                _ => false,
+            })
+        };
+        is_opaque(a) || is_opaque(b)
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for Validator<'a, 'tcx> {
+    fn visit_local(&mut self, local: &Local, _context: PlaceContext<'tcx>, location: Location) {
+        if local.index() >= self.mir.local_decls.len() {
+            self.fail(location, &format!("use of undeclared local {:?}", local));
+        }
+    }
+
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       location: Location) {
+        if let StatementKind::Assign(ref place, ref rvalue) = statement.kind {
+            let place_ty = place.ty(self.mir, self.tcx).to_ty(self.tcx);
+            let rvalue_ty = rvalue.ty(self.mir, self.tcx);
+            if !self.types_agree(place_ty, rvalue_ty) {
+                self.fail(location, &format!("assignment of a value of type `{}` to a place \
+                                              of type `{}`", rvalue_ty, place_ty));
+            }
+        }
+
+        self.super_statement(block, statement, location);
+    }
+
+    fn visit_terminator_kind(&mut self,
+                             block: BasicBlock,
+                             kind: &TerminatorKind<'tcx>,
+                             location: Location) {
+        match *kind {
+            TerminatorKind::Goto { target } => {
+                self.check_edge(location, target, false);
+            }
+            TerminatorKind::SwitchInt { ref discr, switch_ty, ref values, ref targets } => {
+                let discr_ty = discr.ty(self.mir, self.tcx);
+                if !self.types_agree(discr_ty, switch_ty) {
+                    self.fail(location, &format!("switchInt on a `{}` declared to switch \
+                                                  on a `{}`", discr_ty, switch_ty));
+                }
+                if values.len() + 1 != targets.len() {
+                    self.fail(location, &format!("switchInt with {} values but {} targets",
+                                                 values.len(), targets.len()));
+                }
+                for &target in targets {
+                    self.check_edge(location, target, false);
+                }
+            }
+            TerminatorKind::Drop { target, unwind, .. } |
+            TerminatorKind::DropAndReplace { target, unwind, .. } => {
+                self.check_edge(location, target, false);
+                self.check_unwind(location, unwind);
+            }
+            TerminatorKind::Call { ref func, ref destination, cleanup, .. } => {
+                let func_ty = func.ty(self.mir, self.tcx);
+                match func_ty.sty {
+                    ty::TyFnPtr(..) | ty::TyFnDef(..) => {}
+                    _ => self.fail(location, &format!("call of a non-function type `{}`",
+                                                      func_ty)),
+                }
+                if let Some((_, target)) = *destination {
+                    self.check_edge(location, target, false);
+                }
+                self.check_unwind(location, cleanup);
+            }
+            TerminatorKind::Assert { ref cond, target, cleanup, .. } => {
+                let cond_ty = cond.ty(self.mir, self.tcx);
+                if cond_ty != self.tcx.types.bool {
+                    self.fail(location, &format!("assert on a `{}`, expected `bool`", cond_ty));
+                }
+                self.check_edge(location, target, false);
+                self.check_unwind(location, cleanup);
+            }
+            TerminatorKind::Yield { resume, drop, .. } => {
+                self.check_edge(location, resume, false);
+                if let Some(drop) = drop {
+                    self.check_edge(location, drop, false);
+                }
+            }
+            TerminatorKind::FalseEdges { real_target, ref imaginary_targets } => {
+                self.check_edge(location, real_target, false);
+                for &target in imaginary_targets {
+                    self.check_edge(location, target, false);
+                }
+            }
+            TerminatorKind::FalseUnwind { real_target, unwind } => {
+                self.check_edge(location, real_target, false);
+                self.check_unwind(location, unwind);
+            }
+            TerminatorKind::Resume |
+            TerminatorKind::Abort |
+            TerminatorKind::Return |
+            TerminatorKind::Unreachable |
+            TerminatorKind::GeneratorDrop => {}
+        }
+
+        let is_cleanup = self.mir.basic_blocks()[block].is_cleanup;
+        match *kind {
+            TerminatorKind::Return |
+            TerminatorKind::Yield { .. } |
+            TerminatorKind::GeneratorDrop if is_cleanup => {
+                self.fail(location, &format!("`{:?}` in a cleanup block", kind));
+            }
+            TerminatorKind::Resume |
+            TerminatorKind::Abort if !is_cleanup => {
+                self.fail(location, &format!("`{:?}` outside of a cleanup block", kind));
+            }
+            _ => {}
+        }
+
+        self.super_terminator_kind(block, kind, location);
+    }
+}
+
+/// Collects the temporaries read by a statement or terminator.
+struct TempReads<'a, 'tcx: 'a> {
+    mir: &'a Mir<'tcx>,
+    reads: Vec<Local>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for TempReads<'a, 'tcx> {
+    fn visit_operand(&mut self, operand: &Operand<'tcx>, location: Location) {
+        match *operand {
+            Operand::Copy(ref place) | Operand::Move(ref place) => {
+                // Reading any part of a place, or through a pointer stored
+                // in it, reads the local it is based on.
+                let mut base = place;
+                while let Place::Projection(ref proj) = *base {
+                    base = &proj.base;
+                }
+                if let Place::Local(local) = *base {
+                    if self.mir.local_kind(local) == LocalKind::Temp {
+                        self.reads.push(local);
+                    }
+                }
+            }
+            Operand::Constant(..) => {}
+        }
+
+        self.super_operand(operand, location);
+    }
+}
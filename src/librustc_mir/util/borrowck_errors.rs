@@ -9,8 +9,9 @@
 // except according to those terms.
 
 use rustc::ty::{self, TyCtxt};
+use rustc::session::BorrowckKind;
 use rustc::session::config::BorrowckMode;
-use rustc_errors::{DiagnosticBuilder, DiagnosticId};
+use rustc_errors::{Diagnostic, DiagnosticBuilder, DiagnosticId};
 use syntax_pos::{MultiSpan, Span};
 
 use std::fmt;
@@ -50,6 +51,30 @@ impl Origin {
             Origin::Mir => mode.use_mir(),
         }
     }
+
+    /// Marks an error as coming from this origin if both borrow checkers are
+    /// being compared, so that the diagnostics handler can drop the ones both
+    /// of them report (see `-Z borrowck-tool-errors`).
+    pub fn set_diagnostic_origin(self, tcx: TyCtxt, diag: &mut Diagnostic) {
+        if tcx.borrowck_mode() == BorrowckMode::Compare {
+            diag.set_origin(match self {
+                Origin::Ast => "ast",
+                Origin::Mir => "mir",
+            });
+        }
+    }
+
+    /// Records an error of this origin for the `-Z borrowck-compare-report`
+    /// summary, if both borrow checkers are being compared.
+    pub fn record_for_comparison(self, tcx: TyCtxt, diag: &Diagnostic) {
+        if tcx.borrowck_mode() == BorrowckMode::Compare {
+            let kind = match self {
+                Origin::Ast => BorrowckKind::Ast,
+                Origin::Mir => BorrowckKind::Mir,
+            };
+            tcx.sess.borrowck_comparison.borrow_mut().record(kind, diag);
+        }
+    }
 }
 
 pub trait BorrowckErrors {
@@ -517,6 +542,8 @@ impl<'b, 'gcx, 'tcx> BorrowckErrors for TyCtxt<'b, 'gcx, 'tcx> {
         if !o.should_emit_errors(self.borrowck_mode()) {
             self.sess.diagnostic().cancel(&mut diag);
         }
+        o.set_diagnostic_origin(*self, &mut diag);
+        o.record_for_comparison(*self, &diag);
         diag
     }
 }
@@ -639,31 +639,41 @@ impl<'l, 'b, 'tcx, D> DropCtxt<'l, 'b, 'tcx, D>
     fn open_drop_for_array(&mut self, ety: Ty<'tcx>, opt_size: Option<u64>) -> BasicBlock {
         debug!("open_drop_for_array({:?}, {:?})", ety, opt_size);
 
+        if let Some(size) = opt_size {
+            // Elements can only be moved out of an array through constant
+            // indices, which are `u32`s, so a larger array is always either
+            // fully initialized or fully moved out of.
+            if size <= (u32::MAX as u64) {
+                let size = size as u32;
+                let fields: Vec<(Place<'tcx>, Option<D::Path>)> = (0..size).map(|i| {
+                    (self.place.clone().elem(ProjectionElem::ConstantIndex{
+                        offset: i,
+                        min_length: size,
+                        from_end: false
+                    }),
+                     self.elaborator.array_subpath(self.path, i, size))
+                }).collect();
+
+                // Some elements may have been moved out, so drop each element
+                // according to its own drop flag.
+                if fields.iter().any(|(_,path)| path.is_some()) {
+                    let (succ, unwind) = self.drop_ladder_bottom();
+                    return self.drop_ladder(fields, succ, unwind).0
+                }
+            }
+
+            // The length of the array is known, so an index-based loop works
+            // for every element type, and it only ever accesses the array
+            // through `P[cur]`, which later analyses understand.
+            return self.drop_loop_pair(ety, false);
+        }
+
         // if size_of::<ety>() == 0 {
         //     index_based_loop
         // } else {
         //     ptr_based_loop
         // }
 
-        if let Some(size) = opt_size {
-            assert!(size <= (u32::MAX as u64),
-                    "move out check doesn't implemented for array bigger then u32");
-            let size = size as u32;
-            let fields: Vec<(Place<'tcx>, Option<D::Path>)> = (0..size).map(|i| {
-                (self.place.clone().elem(ProjectionElem::ConstantIndex{
-                    offset: i,
-                    min_length: size,
-                    from_end: false
-                }),
-                 self.elaborator.array_subpath(self.path, i, size))
-            }).collect();
-
-            if fields.iter().any(|(_,path)| path.is_some()) {
-                let (succ, unwind) = self.drop_ladder_bottom();
-                return self.drop_ladder(fields, succ, unwind).0
-            }
-        }
-
         let move_ = |place: &Place<'tcx>| Operand::Move(place.clone());
         let tcx = self.tcx();
         let size = &Place::Local(self.new_temp(tcx.types.usize));
@@ -17,7 +17,7 @@ use std::io::{self, Write};
 
 use rustc_data_structures::indexed_vec::Idx;
 
-use super::pretty::dump_mir_def_ids;
+use super::pretty::{dump_mir_def_ids, source_line};
 
 /// Write a graphviz DOT graph of a list of MIRs.
 pub fn write_mir_graphviz<'tcx, W>(tcx: TyCtxt<'_, '_, 'tcx>,
@@ -52,7 +52,7 @@ pub fn write_mir_fn_graphviz<'tcx, W>(tcx: TyCtxt<'_, '_, 'tcx>,
 
     // Nodes
     for (block, _) in mir.basic_blocks().iter_enumerated() {
-        write_node(block, mir, w)?;
+        write_node(tcx, block, mir, w)?;
     }
 
     // Edges
@@ -77,6 +77,21 @@ pub fn write_node_label<W: Write, INIT, FINI>(block: BasicBlock,
                                               fini: FINI) -> io::Result<()>
     where INIT: Fn(&mut W) -> io::Result<()>,
           FINI: Fn(&mut W) -> io::Result<()>
+{
+    write_block_label(block, mir, w, num_cols, &mut |_| None, init, fini)
+}
+
+/// Like `write_node_label`, but writes the result of `source_lines`, if any,
+/// as a comment before each statement and the terminator.
+fn write_block_label<W: Write, INIT, FINI>(block: BasicBlock,
+                                           mir: &Mir,
+                                           w: &mut W,
+                                           num_cols: u32,
+                                           source_lines: &mut dyn FnMut(Location) -> Option<String>,
+                                           init: INIT,
+                                           fini: FINI) -> io::Result<()>
+    where INIT: Fn(&mut W) -> io::Result<()>,
+          FINI: Fn(&mut W) -> io::Result<()>
 {
     let data = &mir[block];
 
@@ -93,7 +108,8 @@ pub fn write_node_label<W: Write, INIT, FINI>(block: BasicBlock,
     // List of statements in the middle.
     if !data.statements.is_empty() {
         write!(w, r#"<tr><td align="left" balign="left">"#)?;
-        for statement in &data.statements {
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            write_source_line(source_lines(Location { block, statement_index }), w)?;
             write!(w, "{}<br/>", escape(statement))?;
         }
         write!(w, "</td></tr>")?;
@@ -103,7 +119,10 @@ pub fn write_node_label<W: Write, INIT, FINI>(block: BasicBlock,
     // displayed as labels on the edges between blocks.
     let mut terminator_head = String::new();
     data.terminator().kind.fmt_head(&mut terminator_head).unwrap();
-    write!(w, r#"<tr><td align="left">{}</td></tr>"#, dot::escape_html(&terminator_head))?;
+    write!(w, r#"<tr><td align="left" balign="left">"#)?;
+    let statement_index = data.statements.len();
+    write_source_line(source_lines(Location { block, statement_index }), w)?;
+    write!(w, "{}</td></tr>", dot::escape_html(&terminator_head))?;
 
     fini(w)?;
 
@@ -111,11 +130,22 @@ pub fn write_node_label<W: Write, INIT, FINI>(block: BasicBlock,
     writeln!(w, "</table>")
 }
 
-/// Write a graphviz DOT node for the given basic block.
-fn write_node<W: Write>(block: BasicBlock, mir: &Mir, w: &mut W) -> io::Result<()> {
+/// Write a line of source code as a gray comment in a node label.
+fn write_source_line<W: Write>(line: Option<String>, w: &mut W) -> io::Result<()> {
+    if let Some(line) = line {
+        write!(w, r#"<font color="gray">// {}</font><br/>"#, dot::escape_html(&line))?;
+    }
+    Ok(())
+}
+
+/// Write a graphviz DOT node for the given basic block, with the lines of
+/// source code its statements come from.
+fn write_node<W: Write>(tcx: TyCtxt, block: BasicBlock, mir: &Mir, w: &mut W) -> io::Result<()> {
     // Start a new node with the label to follow, in one of DOT's pseudo-HTML tables.
     write!(w, r#"    {} [shape="none", label=<"#, node(block))?;
-    write_node_label(block, mir, w, 1, |_| Ok(()), |_| Ok(()))?;
+    let mut last_line = None;
+    let mut source_lines = |location| source_line(tcx, mir, location, &mut last_line);
+    write_block_label(block, mir, w, 1, &mut source_lines, |_| Ok(()), |_| Ok(()))?;
     // Close the node label and the node itself.
     writeln!(w, ">];")
 }
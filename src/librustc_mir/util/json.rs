@@ -0,0 +1,161 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `--emit=mir-json`, which writes the MIR of the crate as JSON,
+//! for consumption by external analysis tools. Unlike the output of
+//! `--emit=mir`, the structure of the output is versioned: tools should check
+//! `version`, which is bumped whenever the format changes incompatibly.
+//!
+//! Places, rvalues, operands and types are only given in their pretty-printed
+//! form, as strings.
+
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::indexed_vec::Idx;
+use rustc_serialize::json::{self, Json};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use syntax_pos::Span;
+use super::pretty::dump_mir_def_ids;
+
+/// The version of the format of the output.
+const MIR_JSON_VERSION: u64 = 1;
+
+/// Write the MIR of all the bodies of the local crate, including promoted
+/// constants, as a single JSON object.
+pub fn write_mir_json<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>,
+                                      w: &mut dyn Write)
+                                      -> io::Result<()> {
+    let mut bodies = Vec::new();
+    for def_id in dump_mir_def_ids(tcx, None) {
+        let mir = &tcx.optimized_mir(def_id);
+        let path = tcx.item_path_str(def_id);
+        bodies.push(body_to_json(tcx, &path, None, mir));
+        for (promoted, mir) in mir.promoted.iter_enumerated() {
+            bodies.push(body_to_json(tcx, &path, Some(promoted), mir));
+        }
+    }
+
+    let mut root = BTreeMap::new();
+    root.insert("version".to_string(), Json::U64(MIR_JSON_VERSION));
+    root.insert("crate".to_string(), Json::String(tcx.crate_name.to_string()));
+    root.insert("bodies".to_string(), Json::Array(bodies));
+    writeln!(w, "{}", json::as_pretty_json(&Json::Object(root)))
+}
+
+fn body_to_json<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>,
+                                path: &str,
+                                promoted: Option<Promoted>,
+                                mir: &Mir<'tcx>)
+                                -> Json {
+    let locals = mir.local_decls.iter_enumerated().map(|(local, decl)| {
+        let mut obj = BTreeMap::new();
+        obj.insert("index".to_string(), Json::U64(local.index() as u64));
+        obj.insert("name".to_string(), match decl.name {
+            Some(name) => Json::String(name.to_string()),
+            None => Json::Null,
+        });
+        obj.insert("type".to_string(), Json::String(decl.ty.to_string()));
+        obj.insert("mutable".to_string(), Json::Boolean(decl.mutability == Mutability::Mut));
+        obj.insert("user_variable".to_string(), Json::Boolean(decl.is_user_variable));
+        obj.insert("span".to_string(), span_to_json(tcx, decl.source_info.span));
+        Json::Object(obj)
+    }).collect();
+
+    let blocks = mir.basic_blocks().iter_enumerated().map(|(block, data)| {
+        let statements = data.statements.iter().enumerate().map(|(index, statement)| {
+            let mut obj = BTreeMap::new();
+            obj.insert("index".to_string(), Json::U64(index as u64));
+            obj.insert("kind".to_string(), Json::String(statement_kind_name(&statement.kind)
+                                                            .to_string()));
+            obj.insert("text".to_string(), Json::String(format!("{:?}", statement)));
+            obj.insert("span".to_string(), span_to_json(tcx, statement.source_info.span));
+            Json::Object(obj)
+        }).collect();
+
+        let terminator = data.terminator();
+        let mut term = BTreeMap::new();
+        term.insert("kind".to_string(), Json::String(terminator_kind_name(&terminator.kind)
+                                                         .to_string()));
+        term.insert("text".to_string(), Json::String(format!("{:?}", terminator.kind)));
+        term.insert("successors".to_string(), Json::Array(
+            terminator.successors().iter().map(|bb| Json::U64(bb.index() as u64)).collect()
+        ));
+        term.insert("span".to_string(), span_to_json(tcx, terminator.source_info.span));
+
+        let mut obj = BTreeMap::new();
+        obj.insert("index".to_string(), Json::U64(block.index() as u64));
+        obj.insert("cleanup".to_string(), Json::Boolean(data.is_cleanup));
+        obj.insert("statements".to_string(), Json::Array(statements));
+        obj.insert("terminator".to_string(), Json::Object(term));
+        Json::Object(obj)
+    }).collect();
+
+    let mut obj = BTreeMap::new();
+    obj.insert("path".to_string(), Json::String(path.to_string()));
+    obj.insert("promoted".to_string(), match promoted {
+        Some(promoted) => Json::U64(promoted.index() as u64),
+        None => Json::Null,
+    });
+    obj.insert("arg_count".to_string(), Json::U64(mir.arg_count as u64));
+    obj.insert("span".to_string(), span_to_json(tcx, mir.span));
+    obj.insert("locals".to_string(), Json::Array(locals));
+    obj.insert("blocks".to_string(), Json::Array(blocks));
+    Json::Object(obj)
+}
+
+/// Spans are given by their file, and the 1-based line and column of their
+/// start and end.
+fn span_to_json<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>, span: Span) -> Json {
+    let codemap = tcx.sess.codemap();
+    let lo = codemap.lookup_char_pos(span.lo());
+    let hi = codemap.lookup_char_pos(span.hi());
+
+    let mut obj = BTreeMap::new();
+    obj.insert("file".to_string(), Json::String(lo.file.name.to_string()));
+    obj.insert("line_lo".to_string(), Json::U64(lo.line as u64));
+    obj.insert("col_lo".to_string(), Json::U64(lo.col.0 as u64 + 1));
+    obj.insert("line_hi".to_string(), Json::U64(hi.line as u64));
+    obj.insert("col_hi".to_string(), Json::U64(hi.col.0 as u64 + 1));
+    Json::Object(obj)
+}
+
+fn statement_kind_name(kind: &StatementKind) -> &'static str {
+    match *kind {
+        StatementKind::Assign(..) => "Assign",
+        StatementKind::SetDiscriminant { .. } => "SetDiscriminant",
+        StatementKind::StorageLive(..) => "StorageLive",
+        StatementKind::StorageDead(..) => "StorageDead",
+        StatementKind::InlineAsm { .. } => "InlineAsm",
+        StatementKind::Validate(..) => "Validate",
+        StatementKind::EndRegion(..) => "EndRegion",
+        StatementKind::Coverage { .. } => "Coverage",
+        StatementKind::Nop => "Nop",
+    }
+}
+
+fn terminator_kind_name(kind: &TerminatorKind) -> &'static str {
+    match *kind {
+        TerminatorKind::Goto { .. } => "Goto",
+        TerminatorKind::SwitchInt { .. } => "SwitchInt",
+        TerminatorKind::Resume => "Resume",
+        TerminatorKind::Abort => "Abort",
+        TerminatorKind::Return => "Return",
+        TerminatorKind::Unreachable => "Unreachable",
+        TerminatorKind::Drop { .. } => "Drop",
+        TerminatorKind::DropAndReplace { .. } => "DropAndReplace",
+        TerminatorKind::Call { .. } => "Call",
+        TerminatorKind::Assert { .. } => "Assert",
+        TerminatorKind::Yield { .. } => "Yield",
+        TerminatorKind::GeneratorDrop => "GeneratorDrop",
+        TerminatorKind::FalseEdges { .. } => "FalseEdges",
+        TerminatorKind::FalseUnwind { .. } => "FalseUnwind",
+    }
+}
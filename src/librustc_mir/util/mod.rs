@@ -15,10 +15,14 @@ pub mod patch;
 
 mod alignment;
 mod graphviz;
+mod json;
 pub(crate) mod pretty;
+mod spanview;
 pub mod liveness;
 
 pub use self::alignment::is_disaligned;
-pub use self::pretty::{dump_enabled, dump_mir, write_mir_pretty, PassWhere};
+pub use self::pretty::{dump_enabled, dump_enabled_for_run, dump_mir, dump_mir_for_run};
+pub use self::pretty::{write_mir_pretty, PassWhere};
 pub use self::graphviz::{write_mir_graphviz};
+pub use self::json::write_mir_json;
 pub use self::graphviz::write_node_label as write_graphviz_node_label;
@@ -20,7 +20,9 @@ use std::fmt::Display;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use syntax_pos::{BytePos, DUMMY_SP};
 use super::graphviz::write_mir_fn_graphviz;
+use super::spanview::write_mir_fn_spanview;
 use transform::MirSource;
 
 const INDENT: &'static str = "    ";
@@ -70,6 +72,11 @@ pub enum PassWhere {
 ///   or `typeck` appears in the name.
 /// - `foo & nll | bar & typeck` == match if `foo` and `nll` both appear in the name
 ///   or `typeck` and `bar` both appear in the name.
+///
+/// A substring of the form `name#N` only matches the `N`th run (counting
+/// from 0) of a MIR pass on a MIR body, where `name` appears in the name of
+/// the pass or of the body. For example, `SimplifyCfg#1` matches the second
+/// time any `SimplifyCfg` pass is run.
 pub fn dump_mir<'a, 'gcx, 'tcx, F>(
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
     pass_num: Option<&dyn Display>,
@@ -81,7 +88,24 @@ pub fn dump_mir<'a, 'gcx, 'tcx, F>(
 ) where
     F: FnMut(PassWhere, &mut dyn Write) -> io::Result<()>,
 {
-    if !dump_enabled(tcx, pass_name, source) {
+    dump_mir_for_run(tcx, pass_num, pass_name, None, disambiguator, source, mir, extra_data)
+}
+
+/// Like `dump_mir`, but for the `run_index`th run of the pass `pass_name`,
+/// so that `name#N` filters can match.
+pub fn dump_mir_for_run<'a, 'gcx, 'tcx, F>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    pass_num: Option<&dyn Display>,
+    pass_name: &str,
+    run_index: Option<usize>,
+    disambiguator: &dyn Display,
+    source: MirSource,
+    mir: &Mir<'tcx>,
+    extra_data: F,
+) where
+    F: FnMut(PassWhere, &mut dyn Write) -> io::Result<()>,
+{
+    if !dump_enabled_for_run(tcx, pass_name, run_index, source) {
         return;
     }
 
@@ -105,6 +129,15 @@ pub fn dump_enabled<'a, 'gcx, 'tcx>(
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
     pass_name: &str,
     source: MirSource,
+) -> bool {
+    dump_enabled_for_run(tcx, pass_name, None, source)
+}
+
+pub fn dump_enabled_for_run<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    pass_name: &str,
+    run_index: Option<usize>,
+    source: MirSource,
 ) -> bool {
     let filters = match tcx.sess.opts.debugging_opts.dump_mir {
         None => return false,
@@ -116,6 +149,24 @@ pub fn dump_enabled<'a, 'gcx, 'tcx>(
     });
     filters.split("|").any(|or_filter| {
         or_filter.split("&").all(|and_filter| {
+            // `item=PATH` selects the item with exactly that path, along
+            // with the closures inside it.
+            if and_filter.starts_with("item=") {
+                let base_path = item_path::with_forced_impl_filename_line(|| {
+                    tcx.item_path_str(tcx.closure_base_def_id(source.def_id))
+                });
+                return base_path == &and_filter["item=".len()..];
+            }
+            // `path:TEXT` selects the items whose path contains `TEXT`,
+            // without also matching pass names.
+            if and_filter.starts_with("path:") {
+                return node_path.contains(&and_filter["path:".len()..]);
+            }
+            if let Some(hash) = and_filter.rfind('#') {
+                let (name, index) = (&and_filter[..hash], &and_filter[hash + 1..]);
+                return (pass_name.contains(name) || node_path.contains(name)) &&
+                    run_index.is_some() && index.parse::<usize>().ok() == run_index;
+            }
             and_filter == "all" || pass_name.contains(and_filter) || node_path.contains(and_filter)
         })
     })
@@ -161,6 +212,15 @@ fn dump_matched_mir_node<'a, 'gcx, 'tcx, F>(
             Ok(())
         };
     }
+
+    if tcx.sess.opts.debugging_opts.dump_mir_spanview {
+        let _: io::Result<()> = do catch {
+            let mut file =
+                create_dump_file(tcx, "html", pass_num, pass_name, disambiguator, source)?;
+            write_mir_fn_spanview(tcx, node_path, mir, &mut extra_data, &mut file)?;
+            Ok(())
+        };
+    }
 }
 
 /// Returns the path to the filename where we should dump a given MIR.
@@ -255,20 +315,72 @@ pub fn write_mir_pretty<'a, 'gcx, 'tcx>(
             writeln!(w, "")?;
         }
 
-        write_mir_fn(tcx, MirSource::item(def_id), mir, &mut |_, _| Ok(()), w)?;
+        let mut last_line = None;
+        write_mir_fn(tcx, MirSource::item(def_id), mir, &mut |pass_where, w| {
+            write_source_line(tcx, mir, pass_where, &mut last_line, w)
+        }, w)?;
 
-        for (i, mir) in mir.promoted.iter_enumerated() {
+        for (i, promoted) in mir.promoted.iter_enumerated() {
             writeln!(w, "")?;
             let src = MirSource {
                 def_id,
                 promoted: Some(i),
             };
-            write_mir_fn(tcx, src, mir, &mut |_, _| Ok(()), w)?;
+            let mut last_line = None;
+            write_mir_fn(tcx, src, promoted, &mut |pass_where, w| {
+                write_source_line(tcx, promoted, pass_where, &mut last_line, w)
+            }, w)?;
+        }
+    }
+    Ok(())
+}
+
+/// Before each statement or terminator, writes the line of source code it
+/// comes from as a comment, unless that line was the last one written in the
+/// current basic block.
+fn write_source_line(
+    tcx: TyCtxt,
+    mir: &Mir,
+    pass_where: PassWhere,
+    last_line: &mut Option<(BytePos, usize)>,
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    match pass_where {
+        PassWhere::BeforeBlock(_) => *last_line = None,
+        PassWhere::BeforeLocation(location) => {
+            if let Some(line) = source_line(tcx, mir, location, last_line) {
+                writeln!(w, "{0}{0}// {1}", INDENT, line)?;
+            }
         }
+        _ => {}
     }
     Ok(())
 }
 
+/// Returns `file:line: text` for the line of source code that `location`
+/// comes from, or `None` if it has no span or is on `last_line`, which is
+/// then updated.
+pub(crate) fn source_line(
+    tcx: TyCtxt,
+    mir: &Mir,
+    location: Location,
+    last_line: &mut Option<(BytePos, usize)>,
+) -> Option<String> {
+    let span = mir.source_info(location).span;
+    if span == DUMMY_SP {
+        return None;
+    }
+    let loc = tcx.sess.codemap().lookup_char_pos(span.lo());
+    let line = Some((loc.file.start_pos, loc.line));
+    if *last_line == line {
+        return None;
+    }
+    *last_line = line;
+    loc.file.get_line(loc.line - 1).map(|text| {
+        format!("{}:{}: {}", loc.file.name, loc.line, text.trim())
+    })
+}
+
 pub fn write_mir_fn<'a, 'gcx, 'tcx, F>(
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
     src: MirSource,
@@ -612,10 +724,14 @@ fn write_temp_decls(mir: &Mir, w: &mut dyn Write) -> io::Result<()> {
     Ok(())
 }
 
+/// Returns the bodies to dump, in source order so that the output doesn't
+/// depend on how `mir_keys` happens to be hashed.
 pub fn dump_mir_def_ids(tcx: TyCtxt, single: Option<DefId>) -> Vec<DefId> {
     if let Some(i) = single {
         vec![i]
     } else {
-        tcx.mir_keys(LOCAL_CRATE).iter().cloned().collect()
+        let mut def_ids: Vec<_> = tcx.mir_keys(LOCAL_CRATE).iter().cloned().collect();
+        def_ids.sort_by_key(|&def_id| (tcx.def_span(def_id), def_id));
+        def_ids
     }
 }
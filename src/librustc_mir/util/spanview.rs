@@ -0,0 +1,167 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `-Z dump-mir-spanview`, which renders the source code of a
+//! MIR body as HTML. Every piece of source text is highlighted with a color
+//! picked from the basic block of the innermost MIR statement covering it,
+//! and hovering over it shows that statement, along with any extra data the
+//! dumping pass prints before it (e.g. the NLL liveness results).
+
+use rustc::mir::*;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::indexed_vec::Idx;
+use std::io::{self, Write};
+use syntax_pos::{BytePos, Span};
+use super::pretty::PassWhere;
+
+const HEADER: &'static str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<style>
+    body { font-family: monospace; }
+    .code { white-space: pre-wrap; }
+    .code span[title]:hover { outline: 1px solid black; }
+</style>"#;
+
+/// A span of the MIR body's source code, and the MIR statements it covers.
+struct SpanViewable {
+    span: Span,
+    /// The block of the first statement with this span, which determines the
+    /// color of the highlighted text.
+    block: BasicBlock,
+    /// The text shown when hovering over the span: one line per statement,
+    /// followed by the extra data for that statement.
+    tooltip: String,
+}
+
+/// Write an HTML page showing the source code of `mir`, highlighted per MIR
+/// statement. `extra_data` is called with `PassWhere::BeforeLocation` for
+/// every statement, and what it writes is added to that statement's tooltip.
+pub fn write_mir_fn_spanview<'tcx, F, W>(tcx: TyCtxt<'_, '_, 'tcx>,
+                                         node_path: &str,
+                                         mir: &Mir<'tcx>,
+                                         extra_data: &mut F,
+                                         w: &mut W) -> io::Result<()>
+    where F: FnMut(PassWhere, &mut dyn Write) -> io::Result<()>,
+          W: Write
+{
+    writeln!(w, "{}", HEADER)?;
+    writeln!(w, "<title>{}</title>", escape_html(node_path))?;
+    writeln!(w, "</head>")?;
+    writeln!(w, "<body>")?;
+    writeln!(w, "<h1>{}</h1>", escape_html(node_path))?;
+
+    let body_span = mir.span;
+    let snippet = match tcx.sess.codemap().span_to_snippet(body_span) {
+        Ok(snippet) => snippet,
+        Err(_) => {
+            writeln!(w, "<p>The source code of this MIR body is not available.</p>")?;
+            return writeln!(w, "</body>\n</html>");
+        }
+    };
+    let viewables = collect_viewables(mir, body_span, extra_data)?;
+
+    write!(w, "<div class=\"code\">")?;
+    let mut current = None;
+    let mut start = 0;
+    for (offset, _) in snippet.char_indices() {
+        let innermost = innermost_viewable(&viewables, body_span.lo() + BytePos(offset as u32));
+        if innermost != current {
+            write_run(w, &viewables, current, &snippet[start..offset])?;
+            current = innermost;
+            start = offset;
+        }
+    }
+    write_run(w, &viewables, current, &snippet[start..])?;
+    writeln!(w, "</div>")?;
+    writeln!(w, "</body>")?;
+    writeln!(w, "</html>")
+}
+
+fn collect_viewables<'tcx, F>(mir: &Mir<'tcx>,
+                              body_span: Span,
+                              extra_data: &mut F) -> io::Result<Vec<SpanViewable>>
+    where F: FnMut(PassWhere, &mut dyn Write) -> io::Result<()>
+{
+    let mut viewables: Vec<SpanViewable> = vec![];
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        for statement_index in 0..data.statements.len() + 1 {
+            let location = Location { block, statement_index };
+            let span = mir.source_info(location).span;
+            // Skip statements which don't correspond to any source text of
+            // this body, such as those expanded from macros.
+            if span.lo() >= span.hi() || span.lo() < body_span.lo() ||
+                span.hi() > body_span.hi() {
+                continue
+            }
+
+            let mut text = match data.statements.get(statement_index) {
+                Some(statement) => format!("{:?}: {:?}", location, statement),
+                None => format!("{:?}: {:?}", location, data.terminator().kind),
+            };
+            let mut extra = vec![];
+            extra_data(PassWhere::BeforeLocation(location), &mut extra)?;
+            for line in String::from_utf8_lossy(&extra).lines() {
+                text.push_str("\n    ");
+                text.push_str(line.trim());
+            }
+
+            let existing = viewables.iter().position(|viewable| viewable.span == span);
+            match existing {
+                Some(index) => {
+                    let tooltip = &mut viewables[index].tooltip;
+                    tooltip.push('\n');
+                    tooltip.push_str(&text);
+                }
+                None => viewables.push(SpanViewable { span, block, tooltip: text }),
+            }
+        }
+    }
+    Ok(viewables)
+}
+
+/// The index of the smallest span in `viewables` which contains `pos`.
+fn innermost_viewable(viewables: &[SpanViewable], pos: BytePos) -> Option<usize> {
+    viewables.iter()
+        .enumerate()
+        .filter(|&(_, viewable)| viewable.span.lo() <= pos && pos < viewable.span.hi())
+        .min_by_key(|&(_, viewable)| viewable.span.hi().0 - viewable.span.lo().0)
+        .map(|(index, _)| index)
+}
+
+fn write_run<W: Write>(w: &mut W,
+                       viewables: &[SpanViewable],
+                       viewable: Option<usize>,
+                       text: &str) -> io::Result<()> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    match viewable {
+        Some(index) => {
+            let viewable = &viewables[index];
+            // Spread the colors of consecutive blocks around the color wheel.
+            let hue = (viewable.block.index() * 67) % 360;
+            write!(w,
+                   "<span style=\"background-color: hsl({}, 70%, 85%)\" title=\"{}\">{}</span>",
+                   hue,
+                   escape_html(&viewable.tooltip),
+                   escape_html(text))
+        }
+        None => write!(w, "{}", escape_html(text)),
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace("&", "&amp;")
+        .replace("<", "&lt;")
+        .replace(">", "&gt;")
+        .replace("\"", "&quot;")
+}
@@ -39,13 +39,14 @@ impl<'a> AstValidator<'a> {
     fn check_lifetime(&self, lifetime: &Lifetime) {
         let valid_names = [keywords::StaticLifetime.name(), keywords::Invalid.name()];
         if !valid_names.contains(&lifetime.ident.name) &&
-            token::Ident(lifetime.ident.without_first_quote()).is_reserved_ident() {
+            token::Ident(lifetime.ident.without_first_quote(), false).is_reserved_ident() {
             self.err_handler().span_err(lifetime.span, "lifetimes cannot use keyword names");
         }
     }
 
     fn check_label(&self, label: Ident, span: Span) {
-        if token::Ident(label.without_first_quote()).is_reserved_ident() || label.name == "'_" {
+        if token::Ident(label.without_first_quote(), false).is_reserved_ident() ||
+           label.name == "'_" {
             self.err_handler().span_err(span, &format!("invalid label name `{}`", label.name));
         }
     }
@@ -143,6 +144,7 @@ impl<'a> AstValidator<'a> {
             .filter_map(|param| match *param {
                 GenericParam::Lifetime(_) => None,
                 GenericParam::Type(ref t) => Some(t.span),
+                GenericParam::Const(ref c) => Some(c.span),
             }).collect();
         if !non_lifetime_param_spans.is_empty() {
             self.err_handler().span_err(non_lifetime_param_spans,
@@ -159,7 +161,7 @@ impl<'a> AstValidator<'a> {
                             "lifetime bounds cannot be used in this context");
                     }
                 }
-                GenericParam::Type(_) => {}
+                GenericParam::Type(_) | GenericParam::Const(_) => {}
             }
         }
     }
@@ -90,6 +90,7 @@ impl<'a, 'tcx> mir_visit::Visitor<'tcx> for StatCollector<'a, 'tcx> {
             StatementKind::StorageLive(..) => "StatementKind::StorageLive",
             StatementKind::StorageDead(..) => "StatementKind::StorageDead",
             StatementKind::InlineAsm { .. } => "StatementKind::InlineAsm",
+            StatementKind::Coverage { .. } => "StatementKind::Coverage",
             StatementKind::Nop => "StatementKind::Nop",
         }, &statement.kind);
         self.super_statement(block, statement, location);
@@ -140,6 +141,10 @@ impl<'a, 'tcx> mir_visit::Visitor<'tcx> for StatCollector<'a, 'tcx> {
             AssertMessage::GeneratorResumedAfterPanic => {
                 "AssertMessage::GeneratorResumedAfterPanic"
             }
+            AssertMessage::MisalignedPointerDereference { .. } => {
+                "AssertMessage::MisalignedPointerDereference"
+            }
+            AssertMessage::NullPointerDereference => "AssertMessage::NullPointerDereference",
         }, msg);
         self.super_assert_message(msg, location);
     }
@@ -123,6 +123,15 @@ impl<'a, 'gcx> CheckCrateVisitor<'a, 'gcx> {
     fn handle_const_fn_call(&mut self, def_id: DefId, ret_ty: Ty<'gcx>) {
         self.promotable &= self.type_has_only_promotable_values(ret_ty);
 
+        // In function bodies, only calls to functions which promise to stay
+        // promotable are promoted (see `promote_consts::Candidate` in MIR),
+        // if the crate opted into it.
+        if self.in_fn && self.tcx.features().restricted_promotion &&
+            !self.tcx.has_attr(def_id, "rustc_promotable")
+        {
+            self.promotable = false;
+        }
+
         self.promotable &= if let Some(fn_id) = self.tcx.hir.as_local_node_id(def_id) {
             FnLikeNode::from_node(self.tcx.hir.get(fn_id)).map_or(false, |fn_like| {
                 fn_like.constness() == hir::Constness::Const
@@ -401,9 +410,20 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>, e: &hir::Expr, node
             }
         }
 
+        hir::ExprField(ref base, _) => {
+            // Which field of a union is initialized is only known at runtime,
+            // so function bodies don't promote reads of union fields.
+            if v.in_fn && v.tcx.features().restricted_promotion {
+                if let ty::TyAdt(adt, _) = v.tables.expr_ty_adjusted(base).sty {
+                    if adt.is_union() {
+                        v.promotable = false;
+                    }
+                }
+            }
+        }
+
         hir::ExprBlock(_) |
         hir::ExprIndex(..) |
-        hir::ExprField(..) |
         hir::ExprTupField(..) |
         hir::ExprArray(_) |
         hir::ExprType(..) |
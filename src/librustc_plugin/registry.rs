@@ -10,7 +10,7 @@
 
 //! Used by plugin crates to tell `rustc` about the plugins they provide.
 
-use rustc::lint::{EarlyLintPassObject, LateLintPassObject, LintId, Lint};
+use rustc::lint::{EarlyLintPassObject, LateLintPassObject, MirLintPassObject, LintId, Lint};
 use rustc::session::Session;
 
 use syntax::ext::base::{SyntaxExtension, NamedSyntaxExtension, NormalTT, IdentTT};
@@ -51,6 +51,9 @@ pub struct Registry<'a> {
     #[doc(hidden)]
     pub late_lint_passes: Vec<LateLintPassObject>,
 
+    #[doc(hidden)]
+    pub mir_lint_passes: Vec<MirLintPassObject>,
+
     #[doc(hidden)]
     pub lint_groups: HashMap<&'static str, Vec<LintId>>,
 
@@ -73,6 +76,7 @@ impl<'a> Registry<'a> {
             syntax_exts: vec![],
             early_lint_passes: vec![],
             late_lint_passes: vec![],
+            mir_lint_passes: vec![],
             lint_groups: HashMap::new(),
             llvm_passes: vec![],
             attributes: vec![],
@@ -161,6 +165,12 @@ impl<'a> Registry<'a> {
     pub fn register_late_lint_pass(&mut self, lint_pass: LateLintPassObject) {
         self.late_lint_passes.push(lint_pass);
     }
+
+    /// Register a compiler lint pass that runs over MIR.
+    pub fn register_mir_lint_pass(&mut self, lint_pass: MirLintPassObject) {
+        self.mir_lint_passes.push(lint_pass);
+    }
+
     /// Register a lint group.
     pub fn register_lint_group(&mut self, name: &'static str, to: Vec<&'static Lint>) {
         self.lint_groups.insert(name, to.into_iter().map(|x| LintId::of(x)).collect());
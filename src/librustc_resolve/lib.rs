@@ -928,6 +928,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Resolver<'a> {
                     // Allow all following defaults to refer to this type parameter.
                     default_ban_rib.bindings.remove(&Ident::with_empty_ctxt(ty_param.ident.name));
                 }
+                GenericParam::Const(ref const_param) => self.visit_ty(&const_param.ty),
             }
         }
         for p in &generics.where_clause.predicates { self.visit_where_predicate(p); }
@@ -2580,6 +2581,7 @@ impl<'a> Resolver<'a> {
                      ident: &SpannedIdent,
                      pat_id: NodeId,
                      outer_pat_id: NodeId,
+                     in_or_alternative: bool,
                      pat_src: PatternSource,
                      bindings: &mut FxHashMap<Ident, NodeId>)
                      -> PathResolution {
@@ -2590,6 +2592,11 @@ impl<'a> Resolver<'a> {
         // passes make about or-patterns.)
         let mut def = Def::Local(pat_id);
         match bindings.get(&ident.node).cloned() {
+            Some(id) if id == outer_pat_id && in_or_alternative => {
+                // `Variant(A(a) | B(a))`, ok
+                // Reuse definition from the first `a`.
+                def = self.ribs[ValueNS].last_mut().unwrap().bindings[&ident.node];
+            }
             Some(id) if id == outer_pat_id => {
                 // `Variant(a, a)`, error
                 resolve_error(
@@ -2637,6 +2644,21 @@ impl<'a> Resolver<'a> {
                        // Maps idents to the node ID for the
                        // outermost pattern that binds them.
                        bindings: &mut FxHashMap<Ident, NodeId>) {
+        // Collect the bindings which appear in the second or later alternative of a
+        // nested or-pattern; these may repeat bindings of the earlier alternatives.
+        let mut or_alternative_ids = NodeSet();
+        pat.walk(&mut |pat| {
+            if let PatKind::Or(ref alternatives) = pat.node {
+                for alternative in &alternatives[1..] {
+                    alternative.walk(&mut |pat| {
+                        or_alternative_ids.insert(pat.id);
+                        true
+                    });
+                }
+            }
+            true
+        });
+
         // Visit all direct subpatterns of this pattern.
         let outer_pat_id = pat.id;
         pat.walk(&mut |pat| {
@@ -2685,7 +2707,9 @@ impl<'a> Resolver<'a> {
                             }
                         }
                     }).unwrap_or_else(|| {
-                        self.fresh_binding(ident, pat.id, outer_pat_id, pat_src, bindings)
+                        let in_or_alternative = or_alternative_ids.contains(&pat.id);
+                        self.fresh_binding(ident, pat.id, outer_pat_id, in_or_alternative,
+                                           pat_src, bindings)
                     });
 
                     self.record_def(pat.id, resolution);
@@ -2709,6 +2733,14 @@ impl<'a> Resolver<'a> {
         });
 
         visit::walk_pat(self, pat);
+
+        // This has to happen *after* we determine which pat_idents are variants
+        pat.walk(&mut |pat| {
+            if let PatKind::Or(ref alternatives) = pat.node {
+                self.check_consistent_bindings(alternatives);
+            }
+            true
+        });
     }
 
     // High-level and context dependent path resolution routine.
@@ -3201,7 +3233,7 @@ impl<'a> Resolver<'a> {
                     // `$crate::a::b`
                     module = Some(self.resolve_crate_root(ident.node.ctxt, true));
                     continue
-                } else if i == 1 && !token::Ident(ident.node).is_path_segment_keyword() {
+                } else if i == 1 && !token::Ident(ident.node, false).is_path_segment_keyword() {
                     let prev_name = path[0].node.name;
                     if prev_name == keywords::Extern.name() ||
                        prev_name == keywords::CrateRoot.name() &&
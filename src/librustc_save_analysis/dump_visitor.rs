@@ -24,9 +24,11 @@
 //! is used for recording the output in a format-agnostic way (see CsvDumper
 //! for an example).
 
+use rustc::hir;
 use rustc::hir::def::Def as HirDef;
 use rustc::hir::def_id::DefId;
 use rustc::hir::map::Node;
+use rustc::middle::resolve_lifetime as rl;
 use rustc::ty::{self, TyCtxt};
 use rustc_data_structures::fx::FxHashSet;
 
@@ -47,7 +49,7 @@ use syntax::codemap::{Spanned, DUMMY_SP, respan};
 use syntax_pos::*;
 
 use {escape, generated_code, lower_attributes, PathCollector, SaveContext};
-use json_dumper::{Access, DumpOutput, JsonDumper};
+use json_dumper::{Access, DumpOutput, JsonDumper, LifetimeData, LifetimeKind};
 use span_utils::SpanUtils;
 use sig;
 
@@ -446,41 +448,73 @@ impl<'l, 'tcx: 'l, 'll, O: DumpOutput + 'll> DumpVisitor<'l, 'tcx, 'll, O> {
         id: NodeId,
     ) {
         for param in &generics.params {
-            if let ast::GenericParam::Type(ref ty_param) = *param {
-                let param_ss = ty_param.span;
-                let name = escape(self.span.snippet(param_ss));
-                // Append $id to name to make sure each one is unique
-                let qualname = format!("{}::{}${}", prefix, name, id);
-                if !self.span.filter_generated(Some(param_ss), full_span) {
-                    let id = ::id_from_node_id(ty_param.id, &self.save_ctxt);
-                    let span = self.span_from_span(param_ss);
-
-                    self.dumper.dump_def(
-                        &Access {
-                            public: false,
-                            reachable: false,
-                        },
-                        Def {
-                            kind: DefKind::Type,
-                            id,
+            match *param {
+                ast::GenericParam::Lifetime(ref lifetime_def) => {
+                    let lifetime = &lifetime_def.lifetime;
+                    if !self.span.filter_generated(Some(lifetime.span), full_span) {
+                        let span = self.span_from_span(lifetime.span);
+                        self.dumper.dump_lifetime(LifetimeData {
+                            kind: LifetimeKind::Def,
                             span,
-                            name,
-                            qualname,
-                            value: String::new(),
-                            parent: None,
-                            children: vec![],
-                            decl_id: None,
-                            docs: String::new(),
-                            sig: None,
-                            attributes: vec![],
-                        },
-                    );
+                            name: lifetime.ident.name.to_string(),
+                            def_span: None,
+                        });
+                    }
+                }
+                ast::GenericParam::Type(ref ty_param) => {
+                    let param_ss = ty_param.span;
+                    let name = escape(self.span.snippet(param_ss));
+                    // Append $id to name to make sure each one is unique
+                    let qualname = format!("{}::{}${}", prefix, name, id);
+                    if !self.span.filter_generated(Some(param_ss), full_span) {
+                        let id = ::id_from_node_id(ty_param.id, &self.save_ctxt);
+                        let span = self.span_from_span(param_ss);
+
+                        self.dumper.dump_def(
+                            &Access {
+                                public: false,
+                                reachable: false,
+                            },
+                            Def {
+                                kind: DefKind::Type,
+                                id,
+                                span,
+                                name,
+                                qualname,
+                                value: String::new(),
+                                parent: None,
+                                children: vec![],
+                                decl_id: None,
+                                docs: String::new(),
+                                sig: None,
+                                attributes: vec![],
+                            },
+                        );
+                    }
                 }
             }
         }
         self.visit_generics(generics);
     }
 
+    /// Dumps a use of a lifetime at `span` which resolved to `region`. Uses
+    /// of named lifetimes point to their definition.
+    fn process_lifetime_ref(&mut self, span: Span, name: String, region: rl::Region) {
+        let def_span = match region {
+            rl::Region::EarlyBound(_, def_id, _) | rl::Region::LateBound(_, def_id, _) => {
+                self.tcx.hir.span_if_local(def_id).map(|sp| self.span_from_span(sp))
+            }
+            _ => None,
+        };
+        let span = self.span_from_span(span);
+        self.dumper.dump_lifetime(LifetimeData {
+            kind: LifetimeKind::Ref,
+            span,
+            name,
+            def_span,
+        });
+    }
+
     fn process_fn(
         &mut self,
         item: &'l ast::Item,
@@ -1581,6 +1615,24 @@ impl<'l, 'tcx: 'l, 'll, O: DumpOutput + 'll> Visitor<'l> for DumpVisitor<'l, 'tc
         }
     }
 
+    fn visit_lifetime(&mut self, lifetime: &'l ast::Lifetime) {
+        if generated_code(lifetime.span) {
+            return;
+        }
+
+        // The lifetimes of definitions are not resolved; they are dumped by
+        // `process_generic_params`.
+        let hir_id = self.tcx.hir.node_to_hir_id(lifetime.id);
+        if let Some(region) = self.tcx.named_region(hir_id) {
+            let name = if lifetime.ident.name == "'_" {
+                "elided".to_owned()
+            } else {
+                lifetime.ident.name.to_string()
+            };
+            self.process_lifetime_ref(lifetime.span, name, region);
+        }
+    }
+
     fn visit_ty(&mut self, t: &'l ast::Ty) {
         self.process_macro_use(t.span);
         match t.node {
@@ -1607,6 +1659,22 @@ impl<'l, 'tcx: 'l, 'll, O: DumpOutput + 'll> Visitor<'l> for DumpVisitor<'l, 'tc
                 self.visit_ty(element);
                 self.nest_tables(length.id, |v| v.visit_expr(length));
             }
+            ast::TyKind::Rptr(None, _) => {
+                // The elided lifetime of `&T` only exists in the HIR; it is
+                // dumped at the `&`.
+                if !generated_code(t.span) {
+                    if let Some(Node::NodeTy(hir_ty)) = self.tcx.hir.find(t.id) {
+                        if let hir::TyRptr(ref lifetime, _) = hir_ty.node {
+                            let hir_id = self.tcx.hir.node_to_hir_id(lifetime.id);
+                            if let Some(region) = self.tcx.named_region(hir_id) {
+                                let span = t.span.with_hi(t.span.lo() + BytePos(1));
+                                self.process_lifetime_ref(span, "elided".to_owned(), region);
+                            }
+                        }
+                    }
+                }
+                visit::walk_ty(self, t)
+            }
             _ => visit::walk_ty(self, t),
         }
     }
@@ -13,7 +13,7 @@ use std::io::Write;
 use rustc_serialize::json::as_json;
 
 use rls_data::{self, Analysis, CratePreludeData, Def, DefKind, Import, MacroRef, Ref, RefKind,
-               Relation, Impl};
+               Relation, Impl, SpanData};
 use rls_data::config::Config;
 use rls_span::{Column, Row};
 
@@ -23,18 +23,43 @@ pub struct Access {
     pub public: bool,
 }
 
+/// A lifetime definition, or a use of a lifetime. `rls_data` has no kind
+/// for lifetimes, so these are kept apart from the defs and refs of the
+/// analysis.
+#[derive(Debug, RustcEncodable)]
+pub struct LifetimeData {
+    pub kind: LifetimeKind,
+    pub span: SpanData,
+    /// The name of the lifetime, or `elided` if none was written.
+    pub name: String,
+    /// For a use, the definition of the named lifetime it resolves to.
+    pub def_span: Option<SpanData>,
+}
+
+#[derive(Debug, RustcEncodable)]
+pub enum LifetimeKind {
+    Def,
+    Ref,
+}
+
 pub struct JsonDumper<O: DumpOutput> {
     result: Analysis,
+    lifetimes: Vec<LifetimeData>,
     config: Config,
     output: O,
 }
 
 pub trait DumpOutput {
     fn dump(&mut self, result: &Analysis);
+
+    /// Outputs the lifetime data collected alongside `result`. Ignored
+    /// by default, since it does not fit in an `Analysis`.
+    fn dump_lifetimes(&mut self, _lifetimes: &Vec<LifetimeData>) {}
 }
 
 pub struct WriteOutput<'b, W: Write + 'b> {
     output: &'b mut W,
+    lifetimes_output: &'b mut W,
 }
 
 impl<'b, W: Write> DumpOutput for WriteOutput<'b, W> {
@@ -43,6 +68,12 @@ impl<'b, W: Write> DumpOutput for WriteOutput<'b, W> {
             error!("Error writing output");
         }
     }
+
+    fn dump_lifetimes(&mut self, lifetimes: &Vec<LifetimeData>) {
+        if let Err(_) = write!(self.lifetimes_output, "{}", as_json(lifetimes)) {
+            error!("Error writing lifetimes output");
+        }
+    }
 }
 
 pub struct CallbackOutput<'b> {
@@ -56,11 +87,19 @@ impl<'b> DumpOutput for CallbackOutput<'b> {
 }
 
 impl<'b, W: Write> JsonDumper<WriteOutput<'b, W>> {
-    pub fn new(writer: &'b mut W, config: Config) -> JsonDumper<WriteOutput<'b, W>> {
+    pub fn new(
+        writer: &'b mut W,
+        lifetimes_writer: &'b mut W,
+        config: Config,
+    ) -> JsonDumper<WriteOutput<'b, W>> {
         JsonDumper {
-            output: WriteOutput { output: writer },
+            output: WriteOutput {
+                output: writer,
+                lifetimes_output: lifetimes_writer,
+            },
             config: config.clone(),
             result: Analysis::new(config),
+            lifetimes: vec![],
         }
     }
 }
@@ -74,6 +113,7 @@ impl<'b> JsonDumper<CallbackOutput<'b>> {
             output: CallbackOutput { callback: callback },
             config: config.clone(),
             result: Analysis::new(config),
+            lifetimes: vec![],
         }
     }
 }
@@ -81,6 +121,7 @@ impl<'b> JsonDumper<CallbackOutput<'b>> {
 impl<O: DumpOutput> Drop for JsonDumper<O> {
     fn drop(&mut self) {
         self.output.dump(&self.result);
+        self.output.dump_lifetimes(&self.lifetimes);
     }
 }
 
@@ -111,6 +152,13 @@ impl<'b, O: DumpOutput + 'b> JsonDumper<O> {
         self.result.refs.push(data);
     }
 
+    pub fn dump_lifetime(&mut self, data: LifetimeData) {
+        if self.config.pub_only || self.config.reachable_only {
+            return;
+        }
+        self.lifetimes.push(data);
+    }
+
     pub fn dump_def(&mut self, access: &Access, mut data: Def) {
         if !access.public && self.config.pub_only
             || !access.reachable && self.config.reachable_only {
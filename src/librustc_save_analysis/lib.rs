@@ -935,6 +935,9 @@ fn make_signature(decl: &ast::FnDecl, generics: &ast::Generics) -> String {
             .map(|param| match *param {
                 ast::GenericParam::Lifetime(ref l) => l.lifetime.ident.name.to_string(),
                 ast::GenericParam::Type(ref t) => t.ident.to_string(),
+                ast::GenericParam::Const(ref c) => {
+                    format!("const {}: {}", c.ident, ty_to_string(&c.ty))
+                }
             })
             .collect::<Vec<_>>()
             .join(", "));
@@ -1027,7 +1030,8 @@ impl<'a> DumpHandler<'a> {
         }
     }
 
-    fn output_file(&self, ctx: &SaveContext) -> File {
+    /// Creates the files for the analysis and for the lifetime data.
+    fn output_files(&self, ctx: &SaveContext) -> (File, File) {
         let sess = &ctx.tcx.sess;
         let file_name = match ctx.config.output_file {
             Some(ref s) => PathBuf::from(s),
@@ -1061,11 +1065,11 @@ impl<'a> DumpHandler<'a> {
 
         info!("Writing output to {}", file_name.display());
 
-        let output_file = File::create(&file_name).unwrap_or_else(
+        let create = |file_name: &Path| File::create(file_name).unwrap_or_else(
             |e| sess.fatal(&format!("Could not open {}: {}", file_name.display(), e)),
         );
 
-        output_file
+        (create(&file_name), create(&file_name.with_extension("lifetimes.json")))
     }
 }
 
@@ -1076,8 +1080,8 @@ impl<'a> SaveHandler for DumpHandler<'a> {
         krate: &ast::Crate,
         cratename: &str,
     ) {
-        let output = &mut self.output_file(&save_ctxt);
-        let mut dumper = JsonDumper::new(output, save_ctxt.config.clone());
+        let (ref mut output, ref mut lifetimes_output) = self.output_files(&save_ctxt);
+        let mut dumper = JsonDumper::new(output, lifetimes_output, save_ctxt.config.clone());
         let mut visitor = DumpVisitor::new(save_ctxt, &mut dumper);
 
         visitor.dump_crate_info(cratename, krate);
@@ -21,7 +21,7 @@ use back::symbol_export;
 use rustc::hir::def_id::{LOCAL_CRATE, CrateNum};
 use rustc::middle::dependency_format::Linkage;
 use rustc::session::Session;
-use rustc::session::config::{self, CrateType, OptLevel, DebugInfoLevel};
+use rustc::session::config::{self, CrateType, OptLevel, DebugInfoLevel, LinkerPluginLto};
 use rustc::ty::TyCtxt;
 use rustc_back::{LinkerFlavor, LldFlavor};
 use serialize::{json, Encoder};
@@ -118,6 +118,7 @@ pub trait Linker {
     fn no_relro(&mut self);
     fn optimize(&mut self);
     fn debuginfo(&mut self);
+    fn linker_plugin_lto(&mut self);
     fn no_default_libraries(&mut self);
     fn build_dylib(&mut self, out_filename: &Path);
     fn build_static_executable(&mut self);
@@ -284,6 +285,35 @@ impl<'a> Linker for GccLinker<'a> {
         // Don't do anything special here for GNU-style linkers.
     }
 
+    fn linker_plugin_lto(&mut self) {
+        let plugin = match self.sess.opts.cg.linker_plugin_lto {
+            LinkerPluginLto::Disabled => return,
+            LinkerPluginLto::LinkerPluginAuto => None,
+            LinkerPluginLto::LinkerPlugin(ref path) => Some(path.clone()),
+        };
+
+        if let Some(plugin) = plugin {
+            let mut arg = OsString::from("-plugin=");
+            arg.push(plugin);
+            self.linker_arg(&arg);
+        }
+
+        // The plugin only knows about the numeric optimization levels.
+        let opt_level = match self.sess.opts.optimize {
+            OptLevel::No => "O0",
+            OptLevel::Less => "O1",
+            OptLevel::Default | OptLevel::Size | OptLevel::SizeMin => "O2",
+            OptLevel::Aggressive => "O3",
+        };
+        self.linker_arg(&format!("-plugin-opt={}", opt_level));
+
+        let target_cpu = match self.sess.opts.cg.target_cpu {
+            Some(ref s) => s.clone(),
+            None => self.sess.target.target.options.cpu.clone(),
+        };
+        self.linker_arg(&format!("-plugin-opt=mcpu={}", target_cpu));
+    }
+
     fn no_default_libraries(&mut self) {
         if !self.is_ld {
             self.cmd.arg("-nodefaultlibs");
@@ -509,6 +539,11 @@ impl<'a> Linker for MsvcLinker<'a> {
         // Needs more investigation of `/OPT` arguments
     }
 
+    fn linker_plugin_lto(&mut self) {
+        // `lld-link` performs LTO over bitcode objects by itself, and the
+        // Microsoft linker doesn't support it.
+    }
+
     fn debuginfo(&mut self) {
         // This will cause the Microsoft linker to generate a PDB file
         // from the CodeView line tables in the object files.
@@ -712,6 +747,10 @@ impl<'a> Linker for EmLinker<'a> {
         self.cmd.args(&["--memory-init-file", "0"]);
     }
 
+    fn linker_plugin_lto(&mut self) {
+        // Emscripten always links bitcode.
+    }
+
     fn debuginfo(&mut self) {
         // Preserve names or generate source maps depending on debug info
         self.cmd.arg(match self.sess.opts.debuginfo {
@@ -880,6 +919,10 @@ impl Linker for WasmLd {
     fn debuginfo(&mut self) {
     }
 
+    fn linker_plugin_lto(&mut self) {
+        // `wasm-ld` performs LTO over bitcode objects by itself.
+    }
+
     fn no_default_libraries(&mut self) {
     }
 
@@ -28,6 +28,7 @@ use llvm;
 use llvm::{ModuleRef, TargetMachineRef, PassManagerRef, DiagnosticInfoRef};
 use llvm::{SMDiagnosticRef, ContextRef};
 use {CrateTranslation, ModuleSource, ModuleTranslation, CompiledModule, ModuleKind};
+use ModuleReuse;
 use CrateInfo;
 use rustc::hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc::ty::TyCtxt;
@@ -46,6 +47,7 @@ use jobserver::{Client, Acquired};
 use rustc_demangle;
 
 use std::any::Any;
+use std::cell::RefCell;
 use std::ffi::{CString, CStr};
 use std::fs;
 use std::io::{self, Write};
@@ -298,7 +300,8 @@ impl ModuleConfig {
         self.no_builtins = no_builtins || sess.target.target.options.no_builtins;
         self.time_passes = sess.time_passes();
         self.inline_threshold = sess.opts.cg.inline_threshold;
-        self.obj_is_bitcode = sess.target.target.options.obj_is_bitcode;
+        self.obj_is_bitcode = sess.target.target.options.obj_is_bitcode ||
+                              sess.opts.cg.linker_plugin_lto.enabled();
 
         // Copy what clang does by turning on loop vectorization at O2 and
         // slp vectorization at O3. Otherwise configure other optimization aspects
@@ -370,7 +373,7 @@ pub struct CodegenContext {
 
 impl CodegenContext {
     pub fn create_diag_handler(&self) -> Handler {
-        Handler::with_emitter(true, false, Box::new(self.diag_emitter.clone()))
+        Handler::with_emitter(true, None, Box::new(self.diag_emitter.clone()))
     }
 
     pub(crate) fn config(&self, kind: ModuleKind) -> &ModuleConfig {
@@ -856,6 +859,10 @@ pub fn start_async_translation(tcx: TyCtxt,
         modules_config.passes.push("insert-gcov-profiling".to_owned())
     }
 
+    if sess.opts.debugging_opts.instrument_coverage {
+        modules_config.passes.push("instrprof".to_owned())
+    }
+
     modules_config.opt_level = Some(get_llvm_opt_level(sess.opts.optimize));
     modules_config.opt_size = Some(get_llvm_opt_size(sess.opts.optimize));
 
@@ -901,6 +908,7 @@ pub fn start_async_translation(tcx: TyCtxt,
                 allocator_config.emit_obj = true;
             },
             OutputType::Mir => {}
+            OutputType::MirJson => {}
             OutputType::DepInfo => {}
         }
     }
@@ -950,6 +958,7 @@ pub fn start_async_translation(tcx: TyCtxt,
         shared_emitter_main,
         future: coordinator_thread,
         output_filenames: tcx.output_filenames(LOCAL_CRATE),
+        module_reuse: RefCell::new(FxHashMap()),
     }
 }
 
@@ -1052,6 +1061,7 @@ fn produce_final_output_artifacts(sess: &Session,
                 copy_if_one_unit(OutputType::Object, true);
             }
             OutputType::Mir |
+            OutputType::MirJson |
             OutputType::Metadata |
             OutputType::Exe |
             OutputType::DepInfo => {}
@@ -1131,6 +1141,24 @@ pub(crate) fn dump_incremental_data(trans: &CrateTranslation) {
     println!("[incremental] Re-using {} out of {} modules",
               trans.modules.iter().filter(|m| m.pre_existing).count(),
               trans.modules.len());
+
+    // List every module, with why its cached object code could or couldn't
+    // be re-used and how large that object code is, to help finding out
+    // which changes invalidate the most of the cache.
+    let mut modules: Vec<_> = trans.modules.iter().collect();
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    for module in modules {
+        let reuse = match trans.module_reuse.get(&module.name) {
+            Some(reuse) => reuse.description(),
+            None if module.pre_existing => "re-used",
+            None => "re-translated",
+        };
+        let size = match trans.object_sizes.get(&module.name) {
+            Some(size) => format!("{} bytes", size),
+            None => String::from("no object file"),
+        };
+        println!("[incremental] Module `{}` {} ({})", module.name, reuse, size);
+    }
 }
 
 enum WorkItem {
@@ -2142,6 +2170,7 @@ pub struct OngoingCrateTranslation {
     shared_emitter_main: SharedEmitterMain,
     future: thread::JoinHandle<Result<CompiledModules, ()>>,
     output_filenames: Arc<OutputFilenames>,
+    module_reuse: RefCell<FxHashMap<String, ModuleReuse>>,
 }
 
 impl OngoingCrateTranslation {
@@ -2167,6 +2196,18 @@ impl OngoingCrateTranslation {
         copy_module_artifacts_into_incr_comp_cache(sess,
                                                    dep_graph,
                                                    &compiled_modules);
+
+        // The object files may not survive `produce_final_output_artifacts`.
+        let mut object_sizes = FxHashMap();
+        if sess.opts.debugging_opts.incremental_info {
+            for module in &compiled_modules.modules {
+                let size = module.object.as_ref().and_then(|path| fs::metadata(path).ok());
+                if let Some(size) = size {
+                    object_sizes.insert(module.name.clone(), size.len());
+                }
+            }
+        }
+
         produce_final_output_artifacts(sess,
                                        &compiled_modules,
                                        &self.output_filenames);
@@ -2184,6 +2225,8 @@ impl OngoingCrateTranslation {
             windows_subsystem: self.windows_subsystem,
             linker_info: self.linker_info,
             crate_info: self.crate_info,
+            module_reuse: self.module_reuse.into_inner(),
+            object_sizes,
 
             modules: compiled_modules.modules,
             allocator_module: compiled_modules.allocator_module,
@@ -2193,6 +2236,12 @@ impl OngoingCrateTranslation {
         trans
     }
 
+    /// Records whether the codegen unit `name` was re-used, for
+    /// `-Z incremental-info`.
+    pub(crate) fn record_module_reuse(&self, name: &str, reuse: ModuleReuse) {
+        self.module_reuse.borrow_mut().insert(name.to_string(), reuse);
+    }
+
     pub(crate) fn submit_pre_translated_module_to_llvm(&self,
                                                        tcx: TyCtxt,
                                                        mtrans: ModuleTranslation) {
@@ -27,6 +27,7 @@ use super::ModuleLlvm;
 use super::ModuleSource;
 use super::ModuleTranslation;
 use super::ModuleKind;
+use super::ModuleReuse;
 
 use abi;
 use back::link;
@@ -828,6 +829,7 @@ pub fn trans_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
 
         // First, if incremental compilation is enabled, we try to re-use the
         // codegen unit from the cache.
+        let mut reuse = ModuleReuse::Disabled;
         if tcx.dep_graph.is_fully_enabled() {
             let cgu_id = cgu.work_product_id();
 
@@ -860,15 +862,19 @@ pub fn trans_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                         llmod_id,
                     };
                     tcx.dep_graph.mark_loaded_from_cache(dep_node_index, true);
+                    ongoing_translation.record_module_reuse(cgu.name(), ModuleReuse::Reused);
                     write::submit_translated_module_to_llvm(tcx, module, 0);
                     // Continue to next cgu, this one is done.
                     continue
                 }
+                reuse = ModuleReuse::InputsChanged;
             } else {
                 // This can happen if files were  deleted from the cache
                 // directory for some reason. We just re-compile then.
+                reuse = ModuleReuse::NoPreviousWorkProduct;
             }
         }
+        ongoing_translation.record_module_reuse(cgu.name(), reuse);
 
         let _timing_guard = time_graph.as_ref().map(|time_graph| {
             time_graph.start(write::TRANS_WORKER_TIMELINE,
@@ -946,8 +952,10 @@ fn collect_and_partition_translation_items<'a, 'tcx>(
             let mode_string = mode_string.trim();
             if mode_string == "eager" {
                 MonoItemCollectionMode::Eager
+            } else if mode_string == "verbose" && tcx.sess.opts.cg.link_dead_code {
+                MonoItemCollectionMode::Eager
             } else {
-                if mode_string != "lazy" {
+                if mode_string != "lazy" && mode_string != "verbose" {
                     let message = format!("Unknown codegen-item collection mode '{}'. \
                                            Falling back to 'lazy' mode.",
                                            mode_string);
@@ -999,7 +1007,8 @@ fn collect_and_partition_translation_items<'a, 'tcx>(
         }
     }).collect();
 
-    if tcx.sess.opts.debugging_opts.print_trans_items.is_some() {
+    if let Some(ref print_trans_items) = tcx.sess.opts.debugging_opts.print_trans_items {
+        let print_trans_items = print_trans_items.to_lowercase();
         let mut item_to_cgus = FxHashMap();
 
         for cgu in &codegen_units {
@@ -1050,6 +1059,14 @@ fn collect_and_partition_translation_items<'a, 'tcx>(
         for item in item_keys {
             println!("TRANS_ITEM {}", item);
         }
+
+        if print_trans_items.trim() == "verbose" {
+            ::rustc_mir::monomorphize::print_generic_instances(tcx, items.iter());
+        }
+    }
+
+    if let Some(limit) = tcx.sess.opts.debugging_opts.mono_instance_limit {
+        ::rustc_mir::monomorphize::check_instance_counts(tcx, items.iter(), limit);
     }
 
     (Arc::new(translation_items), Arc::new(codegen_units))
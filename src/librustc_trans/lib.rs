@@ -83,7 +83,7 @@ use rustc::session::config::{OutputFilenames, OutputType, PrintRequest};
 use rustc::ty::{self, TyCtxt};
 use rustc::util::nodemap::{FxHashSet, FxHashMap};
 use rustc_mir::monomorphize;
-use rustc_trans_utils::trans_crate::TransCrate;
+use rustc_trans_utils::codegen_backend::CodegenBackend;
 
 mod diagnostics;
 
@@ -141,18 +141,18 @@ mod type_;
 mod type_of;
 mod value;
 
-pub struct LlvmTransCrate(());
+pub struct LlvmCodegenBackend(());
 
-impl !Send for LlvmTransCrate {} // Llvm is on a per-thread basis
-impl !Sync for LlvmTransCrate {}
+impl !Send for LlvmCodegenBackend {} // Llvm is on a per-thread basis
+impl !Sync for LlvmCodegenBackend {}
 
-impl LlvmTransCrate {
-    pub fn new() -> Box<TransCrate> {
-        box LlvmTransCrate(())
+impl LlvmCodegenBackend {
+    pub fn new() -> Box<CodegenBackend> {
+        box LlvmCodegenBackend(())
     }
 }
 
-impl TransCrate for LlvmTransCrate {
+impl CodegenBackend for LlvmCodegenBackend {
     fn init(&self, sess: &Session) {
         llvm_util::init(sess); // Make sure llvm is inited
     }
@@ -232,7 +232,7 @@ impl TransCrate for LlvmTransCrate {
     ) -> Result<(), CompileIncomplete>{
         use rustc::util::common::time;
         let trans = trans.downcast::<::back::write::OngoingCrateTranslation>()
-            .expect("Expected LlvmTransCrate's OngoingCrateTranslation, found Box<Any>")
+            .expect("Expected LlvmCodegenBackend's OngoingCrateTranslation, found Box<Any>")
             .join(sess, dep_graph);
         if sess.opts.debugging_opts.incremental_info {
             back::write::dump_incremental_data(&trans);
@@ -265,8 +265,8 @@ impl TransCrate for LlvmTransCrate {
 
 /// This is the entrypoint for a hot plugged rustc_trans
 #[no_mangle]
-pub fn __rustc_codegen_backend() -> Box<TransCrate> {
-    LlvmTransCrate::new()
+pub fn __rustc_codegen_backend() -> Box<CodegenBackend> {
+    LlvmCodegenBackend::new()
 }
 
 struct ModuleTranslation {
@@ -334,6 +334,32 @@ impl ModuleTranslation {
     }
 }
 
+/// Why the object code of a codegen unit was, or wasn't, re-used from the
+/// incremental compilation cache, as reported by `-Z incremental-info`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ModuleReuse {
+    /// None of the inputs of the codegen unit changed.
+    Reused,
+    /// Some input of the codegen unit changed.
+    InputsChanged,
+    /// The cache holds no object code for the codegen unit, e.g. because it
+    /// is new, or its files were deleted from the cache directory.
+    NoPreviousWorkProduct,
+    /// Incremental compilation isn't enabled.
+    Disabled,
+}
+
+impl ModuleReuse {
+    fn description(&self) -> &'static str {
+        match *self {
+            ModuleReuse::Reused => "re-used, its inputs are unchanged",
+            ModuleReuse::InputsChanged => "re-translated, its inputs changed",
+            ModuleReuse::NoPreviousWorkProduct => "re-translated, it isn't in the cache",
+            ModuleReuse::Disabled => "translated, incremental compilation is disabled",
+        }
+    }
+}
+
 #[derive(Debug)]
 struct CompiledModule {
     name: String,
@@ -383,6 +409,11 @@ struct CrateTranslation {
     windows_subsystem: Option<String>,
     linker_info: back::linker::LinkerInfo,
     crate_info: CrateInfo,
+    /// Whether each regular module was re-used, see `ModuleReuse`.
+    module_reuse: FxHashMap<String, ModuleReuse>,
+    /// The sizes of the object files of the regular modules, only recorded
+    /// with `-Z incremental-info`.
+    object_sizes: FxHashMap<String, u64>,
 }
 
 // Misc info we load from metadata to persist beyond the tcx
@@ -364,6 +364,21 @@ impl<'a, 'tcx> FunctionCx<'a, 'tcx> {
                         (lang_items::PanicBoundsCheckFnLangItem,
                          vec![file_line_col, index, len])
                     }
+                    mir::AssertMessage::MisalignedPointerDereference {
+                        ref required,
+                        ref found,
+                    } => {
+                        let required = self.trans_operand(&mut bx, required).immediate();
+                        let found = self.trans_operand(&mut bx, found).immediate();
+
+                        let file_line_col = C_struct(bx.cx, &[filename, line, col], false);
+                        let file_line_col = consts::addr_of(bx.cx,
+                                                            file_line_col,
+                                                            align,
+                                                            "panic_misaligned_loc");
+                        (lang_items::PanicMisalignedPointerDereferenceFnLangItem,
+                         vec![file_line_col, required, found])
+                    }
                     mir::AssertMessage::Math(ref err) => {
                         let msg_str = Symbol::intern(err.description()).as_str();
                         let msg_str = C_str_slice(bx.cx, msg_str);
@@ -378,11 +393,16 @@ impl<'a, 'tcx> FunctionCx<'a, 'tcx> {
                          vec![msg_file_line_col])
                     }
                     mir::AssertMessage::GeneratorResumedAfterReturn |
-                    mir::AssertMessage::GeneratorResumedAfterPanic => {
-                        let str = if let mir::AssertMessage::GeneratorResumedAfterReturn = *msg {
-                            "generator resumed after completion"
-                        } else {
-                            "generator resumed after panicking"
+                    mir::AssertMessage::GeneratorResumedAfterPanic |
+                    mir::AssertMessage::NullPointerDereference => {
+                        let str = match *msg {
+                            mir::AssertMessage::GeneratorResumedAfterReturn => {
+                                "generator resumed after completion"
+                            }
+                            mir::AssertMessage::GeneratorResumedAfterPanic => {
+                                "generator resumed after panicking"
+                            }
+                            _ => "null pointer dereference",
                         };
                         let msg_str = Symbol::intern(str).as_str();
                         let msg_str = C_str_slice(bx.cx, msg_str);
@@ -162,6 +162,7 @@ impl<'a, 'tcx> FunctionCx<'a, 'tcx> {
                 self.const_to_miri_value(bx, c)
             },
             ConstVal::Value(miri_val) => Ok(miri_val),
+            ConstVal::Param(param) => bug!("const parameter `{}` in trans", param),
         }
     }
 
@@ -12,9 +12,10 @@ use common::{C_i32, C_null};
 use libc::c_uint;
 use llvm::{self, ValueRef, BasicBlockRef};
 use llvm::debuginfo::DIScope;
-use rustc::ty::{self, TypeFoldable};
+use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
 use rustc::ty::layout::{LayoutOf, TyLayout};
 use rustc::mir::{self, Mir};
+use rustc::mir::visit::{TyContext, Visitor};
 use rustc::ty::subst::Substs;
 use rustc::infer::TransNormalize;
 use rustc::session::config::FullDebugInfo;
@@ -29,6 +30,7 @@ use type_::Type;
 use syntax_pos::{DUMMY_SP, NO_EXPANSION, BytePos, Span};
 use syntax::symbol::keywords;
 
+use std::fmt;
 use std::iter;
 
 use rustc_data_structures::bitvec::BitVector;
@@ -194,6 +196,61 @@ impl<'a, 'tcx> LocalRef<'tcx> {
 
 ///////////////////////////////////////////////////////////////////////////
 
+/// Checks that all the types in the MIR given to trans are free of regions
+/// and of projections that could still be normalized in `Reveal::All` mode.
+struct RevealedTypesChecker<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    instance: Instance<'tcx>,
+    span: Span,
+    in_validation_statement: bool,
+}
+
+impl<'a, 'tcx> RevealedTypesChecker<'a, 'tcx> {
+    fn check<T>(&self, value: &T, what: &str)
+        where T: TransNormalize<'tcx> + PartialEq + fmt::Debug
+    {
+        if value.has_erasable_regions() {
+            span_bug!(self.span, "trans_mir: {} `{:?}` of `{}` has regions",
+                      what, value, self.instance);
+        }
+        let normalized = self.tcx.normalize_associated_type_in_env(value, self.param_env);
+        if normalized != *value {
+            span_bug!(self.span, "trans_mir: {} `{:?}` of `{}` is not normalized, expected `{:?}`",
+                      what, value, self.instance, normalized);
+        }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for RevealedTypesChecker<'a, 'tcx> {
+    fn visit_ty(&mut self, ty: &Ty<'tcx>, _: TyContext) {
+        // Like `EraseRegions` and `RevealAll`, we do NOT look at validation statements.
+        if !self.in_validation_statement {
+            self.check(ty, "type");
+        }
+    }
+
+    fn visit_substs(&mut self, substs: &&'tcx Substs<'tcx>, _: mir::Location) {
+        self.check(substs, "substs");
+    }
+
+    fn visit_source_info(&mut self, source_info: &mir::SourceInfo) {
+        self.span = source_info.span;
+    }
+
+    fn visit_statement(&mut self,
+                       block: mir::BasicBlock,
+                       statement: &mir::Statement<'tcx>,
+                       location: mir::Location) {
+        self.in_validation_statement = match statement.kind {
+            mir::StatementKind::Validate(..) => true,
+            _ => false,
+        };
+        self.super_statement(block, statement, location);
+        self.in_validation_statement = false;
+    }
+}
+
 pub fn trans_mir<'a, 'tcx: 'a>(
     cx: &'a CodegenCx<'a, 'tcx>,
     llfn: ValueRef,
@@ -201,6 +258,18 @@ pub fn trans_mir<'a, 'tcx: 'a>(
     instance: Instance<'tcx>,
     sig: ty::FnSig<'tcx>,
 ) {
+    // The optimized MIR of items went through `EraseRegions` and `RevealAll`,
+    // shims are built by `make_shim` and keep their regions.
+    if let ty::InstanceDef::Item(def_id) = instance.def {
+        RevealedTypesChecker {
+            tcx: cx.tcx,
+            param_env: cx.tcx.param_env(def_id).reveal_all(),
+            instance,
+            span: mir.span,
+            in_validation_statement: false,
+        }.visit_mir(mir);
+    }
+
     let fn_ty = FnType::new(cx, sig, &[]);
     debug!("fn_ty: {:?}", fn_ty);
     let debug_context =
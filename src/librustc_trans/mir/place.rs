@@ -438,8 +438,21 @@ impl<'a, 'tcx> FunctionCx<'a, 'tcx> {
 
                 match projection.elem {
                     mir::ProjectionElem::Deref => bug!(),
-                    mir::ProjectionElem::Field(ref field, _) => {
-                        tr_base.project_field(bx, field.index())
+                    mir::ProjectionElem::Field(ref field, field_ty) => {
+                        let field_place = tr_base.project_field(bx, field.index());
+                        // A field of a generator may be shared by saved locals of
+                        // different types, so access it as the type of this one.
+                        let layout = match tr_base.layout.ty.sty {
+                            ty::TyGenerator(..) => cx.layout_of(self.monomorphize(&field_ty)),
+                            _ => field_place.layout,
+                        };
+                        if layout.ty != field_place.layout.ty {
+                            let llval = bx.pointercast(field_place.llval,
+                                                       layout.llvm_type(cx).ptr_to());
+                            PlaceRef::new_sized(llval, layout, tr_base.align.min(layout.align))
+                        } else {
+                            field_place
+                        }
                     }
                     mir::ProjectionElem::Index(index) => {
                         let index = &mir::Operand::Copy(mir::Place::Local(index));
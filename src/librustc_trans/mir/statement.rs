@@ -8,10 +8,15 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use llvm::{self, True};
 use rustc::mir;
 
 use asm;
 use builder::Builder;
+use common::{C_bytes, C_u32, C_u64, val_ty};
+use consts;
+use declare;
+use type_::Type;
 
 use super::FunctionCx;
 use super::LocalRef;
@@ -82,9 +87,45 @@ impl<'a, 'tcx> FunctionCx<'a, 'tcx> {
                 asm::trans_inline_asm(&bx, asm, outputs, input_vals);
                 bx
             }
+            mir::StatementKind::Coverage { index } => {
+                self.trans_coverage_counter(&bx, index);
+                bx
+            }
             mir::StatementKind::EndRegion(_) |
             mir::StatementKind::Validate(..) |
             mir::StatementKind::Nop => bx,
         }
     }
+
+    /// Increments the coverage counter `index` of the current function, with
+    /// the `llvm.instrprof.increment` intrinsic. LLVM's `instrprof` pass then
+    /// allocates the counters, which are written out by the profiler runtime.
+    fn trans_coverage_counter(&mut self, bx: &Builder<'a, 'tcx>, index: u32) {
+        let cx = self.cx;
+        let info = cx.tcx.coverage_info(self.instance.def_id());
+
+        // The intrinsic identifies the function through a global holding its
+        // name, which `instrprof` expects to be called `__profn_<name>`.
+        let fn_name = cx.tcx.symbol_name(self.instance).name;
+        let var_name = format!("__profn_{}", fn_name);
+        let name_var = match declare::get_declared_value(cx, &var_name) {
+            Some(name_var) => name_var,
+            None => {
+                let name = C_bytes(cx, fn_name.as_bytes());
+                let name_var = declare::declare_global(cx, &var_name, val_ty(name));
+                unsafe {
+                    llvm::LLVMSetInitializer(name_var, name);
+                    llvm::LLVMSetGlobalConstant(name_var, True);
+                    llvm::LLVMRustSetLinkage(name_var, llvm::Linkage::PrivateLinkage);
+                }
+                name_var
+            }
+        };
+
+        let llfn = cx.get_intrinsic("llvm.instrprof.increment");
+        bx.call(llfn, &[consts::ptrcast(name_var, Type::i8p(cx)),
+                        C_u64(cx, info.function_hash),
+                        C_u32(cx, info.num_counters()),
+                        C_u32(cx, index)], None);
+    }
 }
@@ -50,7 +50,15 @@ use link::{build_link_meta, out_filename};
 
 pub use rustc_data_structures::sync::MetadataRef;
 
-pub trait TransCrate {
+/// The interface between the compiler and a code generation backend, like
+/// the LLVM backend of `rustc_trans`.
+///
+/// The driver picks the backend with `-Z codegen-backend`, which is either
+/// `metadata_only`, the name of a backend shipped in the sysroot, or the path
+/// of a dylib exporting a `__rustc_codegen_backend` function returning a
+/// `Box<CodegenBackend>`. This makes it possible to develop other backends
+/// without forking the compiler.
+pub trait CodegenBackend {
     fn init(&self, _sess: &Session) {}
     fn print(&self, _req: PrintRequest, _sess: &Session) {}
     fn target_features(&self, _sess: &Session) -> Vec<Symbol> { vec![] }
@@ -81,19 +89,19 @@ pub trait TransCrate {
     ) -> Result<(), CompileIncomplete>;
 }
 
-pub struct DummyTransCrate;
+pub struct DummyCodegenBackend;
 
-impl TransCrate for DummyTransCrate {
+impl CodegenBackend for DummyCodegenBackend {
     fn metadata_loader(&self) -> Box<MetadataLoader> {
         box DummyMetadataLoader(())
     }
 
     fn provide(&self, _providers: &mut Providers) {
-        bug!("DummyTransCrate::provide");
+        bug!("DummyCodegenBackend::provide");
     }
 
     fn provide_extern(&self, _providers: &mut Providers) {
-        bug!("DummyTransCrate::provide_extern");
+        bug!("DummyCodegenBackend::provide_extern");
     }
 
     fn trans_crate<'a, 'tcx>(
@@ -101,7 +109,7 @@ impl TransCrate for DummyTransCrate {
         _tcx: TyCtxt<'a, 'tcx, 'tcx>,
         _rx: mpsc::Receiver<Box<Any + Send>>
     ) -> Box<Any> {
-        bug!("DummyTransCrate::trans_crate");
+        bug!("DummyCodegenBackend::trans_crate");
     }
 
     fn join_trans_and_link(
@@ -111,7 +119,7 @@ impl TransCrate for DummyTransCrate {
         _dep_graph: &DepGraph,
         _outputs: &OutputFilenames,
     ) -> Result<(), CompileIncomplete> {
-        bug!("DummyTransCrate::join_trans_and_link");
+        bug!("DummyCodegenBackend::join_trans_and_link");
     }
 }
 
@@ -167,20 +175,20 @@ impl MetadataLoader for NoLlvmMetadataLoader {
     }
 }
 
-pub struct MetadataOnlyTransCrate(());
+pub struct MetadataOnlyCodegenBackend(());
 pub struct OngoingCrateTranslation {
     metadata: EncodedMetadata,
     metadata_version: Vec<u8>,
     crate_name: Symbol,
 }
 
-impl MetadataOnlyTransCrate {
-    pub fn new() -> Box<TransCrate> {
-        box MetadataOnlyTransCrate(())
+impl MetadataOnlyCodegenBackend {
+    pub fn new() -> Box<CodegenBackend> {
+        box MetadataOnlyCodegenBackend(())
     }
 }
 
-impl TransCrate for MetadataOnlyTransCrate {
+impl CodegenBackend for MetadataOnlyCodegenBackend {
     fn init(&self, sess: &Session) {
         for cty in sess.opts.crate_types.iter() {
             match *cty {
@@ -264,7 +272,8 @@ impl TransCrate for MetadataOnlyTransCrate {
         outputs: &OutputFilenames,
     ) -> Result<(), CompileIncomplete> {
         let trans = trans.downcast::<OngoingCrateTranslation>()
-            .expect("Expected MetadataOnlyTransCrate's OngoingCrateTranslation, found Box<Any>");
+            .expect("Expected MetadataOnlyCodegenBackend's OngoingCrateTranslation, \
+                     found Box<Any>");
         for &crate_type in sess.opts.crate_types.iter() {
             if crate_type != CrateType::CrateTypeRlib && crate_type != CrateType::CrateTypeDylib {
                 continue;
@@ -46,7 +46,7 @@ pub extern crate rustc as __rustc;
 use rustc::ty::TyCtxt;
 
 pub mod link;
-pub mod trans_crate;
+pub mod codegen_backend;
 pub mod symbol_names;
 pub mod symbol_names_test;
 
@@ -65,6 +65,7 @@ pub trait AstConv<'gcx, 'tcx> {
     fn projected_ty_from_poly_trait_ref(&self,
                                         span: Span,
                                         item_def_id: DefId,
+                                        item_segment: &hir::PathSegment,
                                         poly_trait_ref: ty::PolyTraitRef<'tcx>)
                                         -> Ty<'tcx>;
 
@@ -184,6 +185,57 @@ impl<'o, 'gcx: 'tcx, 'tcx> AstConv<'gcx, 'tcx>+'o {
         substs
     }
 
+    /// Given the substitutions `parent_substs` of the trait that declares the
+    /// associated type `item_def_id`, returns the substitutions for a projection
+    /// of it through `item_segment`. For a generic associated type, these are
+    /// followed by the arguments for the associated type's own parameters.
+    pub fn create_substs_for_associated_item(&self,
+        span: Span,
+        item_def_id: DefId,
+        item_segment: &hir::PathSegment,
+        parent_substs: &'tcx Substs<'tcx>)
+        -> &'tcx Substs<'tcx>
+    {
+        let tcx = self.tcx();
+        let generics = tcx.generics_of(item_def_id);
+        if generics.regions.is_empty() && generics.types.is_empty() {
+            self.prohibit_type_params(slice::from_ref(item_segment));
+            return parent_substs;
+        }
+
+        debug!("create_substs_for_associated_item(item_def_id={:?}, parent_substs={:?})",
+               item_def_id, parent_substs);
+
+        item_segment.with_parameters(|parameters| {
+            if parameters.lifetimes.len() != generics.regions.len() {
+                report_lifetime_number_error(tcx, span,
+                                             parameters.lifetimes.len(),
+                                             generics.regions.len());
+            }
+            check_type_argument_count(tcx, span, parameters.types.len(), &generics.types);
+            parameters.bindings.first().map(|b| self.prohibit_projection(b.span));
+
+            let own_regions_start = generics.parent_count();
+            let own_types_start = own_regions_start + generics.regions.len();
+            parent_substs.extend_to(tcx, item_def_id, |def, _| {
+                let i = def.index as usize - own_regions_start;
+                if let Some(lifetime) = parameters.lifetimes.get(i) {
+                    self.ast_region_to_region(lifetime, Some(def))
+                } else {
+                    tcx.types.re_static
+                }
+            }, |def, _| {
+                let i = def.index as usize - own_types_start;
+                if let Some(ty) = parameters.types.get(i) {
+                    self.ast_ty_to_ty(ty)
+                } else {
+                    // We've already errored above about the mismatch.
+                    tcx.types.err
+                }
+            })
+        })
+    }
+
     /// Given the type/region arguments provided to some path (along with
     /// an implicit Self, if this is a trait reference) returns the complete
     /// set of substitutions. This may involve applying defaulted type parameters.
@@ -579,30 +631,57 @@ impl<'o, 'gcx: 'tcx, 'tcx> AstConv<'gcx, 'tcx>+'o {
             return tcx.types.err;
         }
 
+        // Trait aliases are expanded into the traits they stand for before
+        // the principal is chosen, so `dyn Alias` behaves like its expansion.
         let mut projection_bounds = vec![];
+        let mut trait_refs = vec![];
         let dummy_self = tcx.mk_ty(TRAIT_OBJECT_DUMMY_SELF);
-        let principal = self.instantiate_poly_trait_ref(&trait_bounds[0],
-                                                        dummy_self,
-                                                        &mut projection_bounds);
-
-        for trait_bound in trait_bounds[1..].iter() {
-            // Sanity check for non-principal trait bounds
-            self.instantiate_poly_trait_ref(trait_bound,
-                                            dummy_self,
-                                            &mut vec![]);
+        for trait_bound in trait_bounds {
+            let trait_ref = self.instantiate_poly_trait_ref(trait_bound,
+                                                            dummy_self,
+                                                            &mut projection_bounds);
+            self.expand_object_trait_ref(trait_ref,
+                                         trait_bound.trait_ref.path.span,
+                                         &mut trait_refs,
+                                         &mut projection_bounds);
         }
 
-        let (auto_traits, trait_bounds) = split_auto_traits(tcx, &trait_bounds[1..]);
+        if trait_refs.is_empty() {
+            return tcx.types.err;
+        }
 
-        if !trait_bounds.is_empty() {
-            let b = &trait_bounds[0];
-            let span = b.trait_ref.path.span;
-            struct_span_err!(self.tcx().sess, span, E0225,
-                "only auto traits can be used as additional traits in a trait object")
-                .span_label(span, "non-auto additional trait")
-                .emit();
+        // The principal trait is the first non-auto trait, wherever it is written
+        // (`dyn Send + Trait` is the same type as `dyn Trait + Send`). Objects made
+        // only of auto traits use the first of them instead.
+        let principal_index = trait_refs.iter().position(|&(trait_ref, _)| {
+            !tcx.trait_is_auto(trait_ref.def_id())
+        }).unwrap_or(0);
+        let principal = trait_refs[principal_index].0;
+
+        let (auto_traits, other_traits): (Vec<_>, Vec<_>) = trait_refs.iter().enumerate()
+            .filter(|&(i, &(trait_ref, _))| i != principal_index && trait_ref != principal)
+            .map(|(_, &bound)| bound)
+            .partition(|&(trait_ref, _)| tcx.trait_is_auto(trait_ref.def_id()));
+
+        if !other_traits.is_empty() {
+            let spans: Vec<_> = other_traits.iter().map(|&(_, span)| span).collect();
+            let mut err = struct_span_err!(self.tcx().sess, spans.clone(), E0225,
+                "only auto traits can be used as additional traits in a trait object");
+            for span in spans {
+                err.span_label(span, "non-auto additional trait");
+            }
+            err.emit();
         }
 
+        let mut auto_traits: Vec<_> = auto_traits.into_iter()
+            .map(|(trait_ref, _)| trait_ref.def_id())
+            .collect();
+
+        // Repeating an auto trait doesn't change the object type.
+        auto_traits.sort();
+        auto_traits.dedup();
+        auto_traits.retain(|&trait_did| trait_did != principal.def_id());
+
         // Erase the dummy_self (TRAIT_OBJECT_DUMMY_SELF) used above.
         let existential_principal = principal.map_bound(|trait_ref| {
             self.trait_ref_to_existential(trait_ref)
@@ -689,6 +768,51 @@ impl<'o, 'gcx: 'tcx, 'tcx> AstConv<'gcx, 'tcx>+'o {
         ty
     }
 
+    /// Pushes `trait_ref` onto `trait_refs`, or, if it names a trait alias, the
+    /// traits and projections the alias expands to. Aliases whose bounds can't
+    /// be written in an object type (e.g. `trait Foo = where u8: Copy;`) are
+    /// reported at `span`.
+    fn expand_object_trait_ref(&self,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+        span: Span,
+        trait_refs: &mut Vec<(ty::PolyTraitRef<'tcx>, Span)>,
+        projection_bounds: &mut Vec<ty::PolyProjectionPredicate<'tcx>>)
+    {
+        let tcx = self.tcx();
+        let alias_def_id = trait_ref.def_id();
+        if !tcx.is_trait_alias(alias_def_id) {
+            trait_refs.push((trait_ref, span));
+            return;
+        }
+
+        let dummy_self = trait_ref.self_ty();
+        let predicates = tcx.predicates_of(alias_def_id)
+            .instantiate(tcx, trait_ref.skip_binder().substs)
+            .predicates;
+        for predicate in predicates {
+            match predicate {
+                ty::Predicate::Trait(ref data) if data.skip_binder().self_ty() == dummy_self => {
+                    self.expand_object_trait_ref(data.to_poly_trait_ref(),
+                                                 span,
+                                                 trait_refs,
+                                                 projection_bounds);
+                }
+                ty::Predicate::Projection(ref data)
+                    if data.skip_binder().projection_ty.self_ty() == dummy_self => {
+                    projection_bounds.push(data.clone());
+                }
+                _ => {
+                    struct_span_err!(tcx.sess, span, E0645,
+                        "trait alias `{}` cannot be used as a trait object",
+                        tcx.item_path_str(alias_def_id))
+                        .span_label(span, "the alias has bounds that are not on `Self`")
+                        .emit();
+                    return;
+                }
+            }
+        }
+    }
+
     fn report_ambiguous_associated_type(&self,
                                         span: Span,
                                         type_str: &str,
@@ -807,8 +931,6 @@ impl<'o, 'gcx: 'tcx, 'tcx> AstConv<'gcx, 'tcx>+'o {
 
         debug!("associated_path_def_to_ty: {:?}::{}", ty, assoc_name);
 
-        self.prohibit_type_params(slice::from_ref(item_segment));
-
         // Find the type of the associated item, and the trait where the associated
         // item is declared.
         let bound = match (&ty.sty, ty_path_def) {
@@ -860,7 +982,7 @@ impl<'o, 'gcx: 'tcx, 'tcx> AstConv<'gcx, 'tcx>+'o {
         })
         .expect("missing associated type");
 
-        let ty = self.projected_ty_from_poly_trait_ref(span, item.def_id, bound);
+        let ty = self.projected_ty_from_poly_trait_ref(span, item.def_id, item_segment, bound);
         let ty = self.normalize_ty(span, ty);
 
         let def = Def::AssociatedTy(item.def_id);
@@ -884,8 +1006,6 @@ impl<'o, 'gcx: 'tcx, 'tcx> AstConv<'gcx, 'tcx>+'o {
         let tcx = self.tcx();
         let trait_def_id = tcx.parent_def_id(item_def_id).unwrap();
 
-        self.prohibit_type_params(slice::from_ref(item_segment));
-
         let self_ty = if let Some(ty) = opt_self_ty {
             ty
         } else {
@@ -906,7 +1026,11 @@ impl<'o, 'gcx: 'tcx, 'tcx> AstConv<'gcx, 'tcx>+'o {
 
         debug!("qpath_to_ty: trait_ref={:?}", trait_ref);
 
-        self.normalize_ty(span, tcx.mk_projection(item_def_id, trait_ref.substs))
+        let substs = self.create_substs_for_associated_item(span,
+                                                            item_def_id,
+                                                            item_segment,
+                                                            trait_ref.substs);
+        self.normalize_ty(span, tcx.mk_projection(item_def_id, substs))
     }
 
     pub fn prohibit_type_params(&self, segments: &[hir::PathSegment]) {
@@ -1273,33 +1397,6 @@ impl<'o, 'gcx: 'tcx, 'tcx> AstConv<'gcx, 'tcx>+'o {
     }
 }
 
-/// Divides a list of general trait bounds into two groups: builtin bounds (Sync/Send) and the
-/// remaining general trait bounds.
-fn split_auto_traits<'a, 'b, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>,
-                                         trait_bounds: &'b [hir::PolyTraitRef])
-    -> (Vec<DefId>, Vec<&'b hir::PolyTraitRef>)
-{
-    let (auto_traits, trait_bounds): (Vec<_>, _) = trait_bounds.iter().partition(|bound| {
-        // Checks whether `trait_did` is an auto trait and adds it to `auto_traits` if so.
-        match bound.trait_ref.path.def {
-            Def::Trait(trait_did) if tcx.trait_is_auto(trait_did) => {
-                true
-            }
-            _ => false
-        }
-    });
-
-    let auto_traits = auto_traits.into_iter().map(|tr| {
-        if let Def::Trait(trait_did) = tr.trait_ref.path.def {
-            trait_did
-        } else {
-            unreachable!()
-        }
-    }).collect::<Vec<_>>();
-
-    (auto_traits, trait_bounds)
-}
-
 fn check_type_argument_count(tcx: TyCtxt, span: Span, supplied: usize,
                              ty_param_defs: &[ty::TypeParameterDef]) {
     let accepted = ty_param_defs.len();
@@ -65,9 +65,11 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                     _ => true,
                 }
             }
+            // The alternatives of an or-pattern peel off references themselves.
             PatKind::Wild |
             PatKind::Binding(..) |
-            PatKind::Ref(..) => false,
+            PatKind::Ref(..) |
+            PatKind::Or(..) => false,
         };
         if is_non_ref_pat {
             debug!("pattern is non reference pattern");
@@ -473,6 +475,12 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                 }
                 expected_ty
             }
+            PatKind::Or(ref pats) => {
+                for pat in pats {
+                    self.check_pat_walk(&pat, expected, def_bm, is_arg);
+                }
+                expected
+            }
         };
 
         self.write_ty(pat.hir_id, ty);
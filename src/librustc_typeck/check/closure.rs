@@ -15,13 +15,14 @@ use super::{check_fn, Expectation, FnCtxt, GeneratorTypes};
 use astconv::AstConv;
 use rustc::hir::def_id::DefId;
 use rustc::infer::{InferOk, InferResult};
-use rustc::infer::LateBoundRegionConversionTime;
+use rustc::infer::{LateBoundRegionConversionTime, RegionVariableOrigin};
 use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc::traits::error_reporting::ArgKind;
 use rustc::ty::{self, ToPolyTraitRef, Ty};
 use rustc::ty::subst::Substs;
 use rustc::ty::TypeFoldable;
 use std::cmp;
+use std::collections::BTreeMap;
 use std::iter;
 use syntax::abi::Abi;
 use syntax::codemap::Span;
@@ -399,10 +400,15 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
     ///       - We could use some kind of subtyping relationship here,
     ///         I imagine, but equality is easier and works fine for
     ///         our purposes.
+    ///     - Check the closure body against `S'` rather than `E'`.
     ///
     /// The key intuition here is that the user's types must be valid
     /// from "the inside" of the closure, but the expectation
-    /// ultimately drives the overall signature.
+    /// ultimately drives the overall signature. Checking the body
+    /// against `S'` means that the regions the user wrote, or that
+    /// were elided, are region variables related to `E'` by the
+    /// equality above, instead of being the free regions of `E'`
+    /// themselves.
     ///
     /// # Examples
     ///
@@ -419,7 +425,8 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
     /// - E' is `&'!0 u32 -> &'!0 u32`
     /// - S' is `&'?0 u32 -> ?T`
     ///
-    /// S' can be unified with E' with `['?0 = '!0, ?T = &'!10 u32]`.
+    /// S' can be unified with E' with `['?0 = '!0, ?T = &'!10 u32]`,
+    /// and the body is then checked against `&'?0 u32 -> &'!0 u32`.
     ///
     /// # Arguments
     ///
@@ -479,12 +486,10 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
         // Along the way, it also writes out entries for types that the user
         // wrote into our tables, which are then later used by the privacy
         // check.
-        match self.check_supplied_sig_against_expectation(decl, &closure_sigs) {
+        match self.merge_supplied_sig_with_expectation(decl, body, closure_sigs) {
             Ok(infer_ok) => self.register_infer_ok_obligations(infer_ok),
-            Err(_) => return self.sig_of_closure_no_expectation(expr_def_id, decl, body),
+            Err(_) => self.sig_of_closure_no_expectation(expr_def_id, decl, body),
         }
-
-        closure_sigs
     }
 
     fn sig_of_closure_with_mismatched_number_of_arguments(
@@ -516,14 +521,17 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
         self.closure_sigs(expr_def_id, body, error_sig)
     }
 
-    /// Enforce the user's types against the expectation.  See
+    /// Enforce the user's types against the expectation, and return
+    /// the signatures of the closure with the liberated signature
+    /// replaced by the instantiated user's types. See
     /// `sig_of_closure_with_expectation` for details on the overall
     /// strategy.
-    fn check_supplied_sig_against_expectation(
+    fn merge_supplied_sig_with_expectation(
         &self,
         decl: &hir::FnDecl,
-        expected_sigs: &ClosureSignatures<'tcx>,
-    ) -> InferResult<'tcx, ()> {
+        body: &hir::Body,
+        mut expected_sigs: ClosureSignatures<'tcx>,
+    ) -> InferResult<'tcx, ClosureSignatures<'tcx>> {
         // Get the signature S that the user gave.
         //
         // (See comment on `sig_of_closure_with_expectation` for the
@@ -531,7 +539,7 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
         let supplied_sig = self.supplied_sig_of_closure(decl);
 
         debug!(
-            "check_supplied_sig_against_expectation: supplied_sig={:?}",
+            "merge_supplied_sig_with_expectation: supplied_sig={:?}",
             supplied_sig
         );
 
@@ -552,20 +560,39 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
         self.infcx.commit_if_ok(|_| {
             let mut all_obligations = vec![];
 
+            // Instantiate S to S', i.e., with fresh variables. This is
+            // done once for the whole signature, so that a region the
+            // user wrote in several places (or that elision copied from
+            // an argument to the return type) is one variable in S'.
+            // Each variable gets the span of the first argument type that
+            // mentions it, or else of the return type.
+            let mut region_spans = BTreeMap::new();
+            for (hir_ty, &supplied_ty) in decl.inputs.iter()
+                           .zip(*supplied_sig.inputs().skip_binder())
+            {
+                let regions = self.tcx.collect_referenced_late_bound_regions(
+                    &ty::Binder(supplied_ty),
+                );
+                for br in regions {
+                    region_spans.entry(br).or_insert(hir_ty.span);
+                }
+            }
+            let (supplied_sig, _) = self.tcx.replace_late_bound_regions(&supplied_sig, |br| {
+                let span = region_spans.get(&br).cloned().unwrap_or(decl.output.span());
+                self.next_region_var(RegionVariableOrigin::LateBoundRegion(
+                    span,
+                    br,
+                    LateBoundRegionConversionTime::FnCall,
+                ))
+            });
+
             // The liberated version of this signature should be be a subtype
             // of the liberated form of the expectation.
             for ((hir_ty, &supplied_ty), expected_ty) in decl.inputs.iter()
-                           .zip(*supplied_sig.inputs().skip_binder()) // binder moved to (*) below
+                           .zip(supplied_sig.inputs())
                            .zip(expected_sigs.liberated_sig.inputs())
             // `liberated_sig` is E'.
             {
-                // Instantiate (this part of..) S to S', i.e., with fresh variables.
-                let (supplied_ty, _) = self.infcx.replace_late_bound_regions_with_fresh_var(
-                    hir_ty.span,
-                    LateBoundRegionConversionTime::FnCall,
-                    &ty::Binder(supplied_ty),
-                ); // recreated from (*) above
-
                 // Check that E' = S'.
                 let cause = &self.misc(hir_ty.span);
                 let InferOk {
@@ -576,21 +603,27 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                 all_obligations.extend(obligations);
             }
 
-            let (supplied_output_ty, _) = self.infcx.replace_late_bound_regions_with_fresh_var(
-                decl.output.span(),
-                LateBoundRegionConversionTime::FnCall,
-                &supplied_sig.output(),
-            );
             let cause = &self.misc(decl.output.span());
             let InferOk {
                 value: (),
                 obligations,
             } = self.at(cause, self.param_env)
-                .eq(expected_sigs.liberated_sig.output(), supplied_output_ty)?;
+                .eq(expected_sigs.liberated_sig.output(), supplied_sig.output())?;
             all_obligations.extend(obligations);
 
+            // S' is now known to be equal to E', so check the body
+            // against S': its regions are the fresh variables created
+            // above rather than the free regions of E'.
+            let liberated_sig = self.resolve_type_vars_if_possible(&supplied_sig);
+            expected_sigs.liberated_sig = self.inh.normalize_associated_types_in(
+                body.value.span,
+                body.value.id,
+                self.param_env,
+                &liberated_sig,
+            );
+
             Ok(InferOk {
-                value: (),
+                value: expected_sigs,
                 obligations: all_obligations,
             })
         })
@@ -334,6 +334,7 @@ pub fn check_safety_of_destructor_if_necessary<'a, 'gcx, 'tcx>(
             match outlive.unpack() {
                 UnpackedKind::Lifetime(lt) => rcx.sub_regions(origin(), parent_scope, lt),
                 UnpackedKind::Type(ty) => rcx.type_must_outlive(origin(), ty, parent_scope),
+                UnpackedKind::Const(_) => {}
             }
         }
     }
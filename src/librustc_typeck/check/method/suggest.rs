@@ -29,7 +29,6 @@ use rustc::hir;
 use rustc::hir::print;
 use rustc::infer::type_variable::TypeVariableOrigin;
 
-use std::cell;
 use std::cmp::Ordering;
 
 use super::{MethodError, NoMatchData, CandidateSource};
@@ -627,7 +626,7 @@ impl Ord for TraitInfo {
 
 /// Retrieve all traits in this crate and any dependent crates.
 pub fn all_traits<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> AllTraits<'a> {
-    if tcx.all_traits.borrow().is_none() {
+    if tcx.all_traits.try_get().is_none() {
         use rustc::hir::itemlikevisit;
 
         let mut traits = vec![];
@@ -691,19 +690,19 @@ pub fn all_traits<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> AllTraits<'a>
             handle_external_def(tcx, &mut traits, &mut external_mods, Def::Mod(def_id));
         }
 
-        *tcx.all_traits.borrow_mut() = Some(traits);
+        // Another thread may have computed the list in the meantime, in
+        // which case ours is simply dropped.
+        tcx.all_traits.try_set(traits);
     }
 
-    let borrow = tcx.all_traits.borrow();
-    assert!(borrow.is_some());
     AllTraits {
-        borrow,
+        traits: tcx.all_traits.get(),
         idx: 0,
     }
 }
 
 pub struct AllTraits<'a> {
-    borrow: cell::Ref<'a, Option<AllTraitsVec>>,
+    traits: &'a AllTraitsVec,
     idx: usize,
 }
 
@@ -711,9 +710,8 @@ impl<'a> Iterator for AllTraits<'a> {
     type Item = TraitInfo;
 
     fn next(&mut self) -> Option<TraitInfo> {
-        let AllTraits { ref borrow, ref mut idx } = *self;
-        // ugh.
-        borrow.as_ref().unwrap().get(*idx).map(|info| {
+        let AllTraits { traits, ref mut idx } = *self;
+        traits.get(*idx).map(|info| {
             *idx += 1;
             TraitInfo::new(*info)
         })
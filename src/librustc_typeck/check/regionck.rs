@@ -123,6 +123,10 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
             // regionck assumes typeck succeeded
             rcx.visit_body(body);
             rcx.visit_region_obligations(id);
+            rcx.constrain_anon_types(
+                &self.anon_types.borrow(),
+                rcx.outlives_environment.free_region_map(),
+            );
         }
         rcx.resolve_regions_and_report_errors_unless_nll();
 
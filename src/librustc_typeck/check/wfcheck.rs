@@ -591,17 +591,36 @@ impl<'a, 'gcx> CheckTypeWellFormedVisitor<'a, 'gcx> {
                                          None,
                                          &mut constrained_parameters);
 
+        // Const parameters come after the lifetime and type parameters
+        // and are invariant, so only the latter can be reported below.
+        let params: Vec<_> = ast_generics.params.iter()
+            .filter(|param| !param.is_const_param())
+            .collect();
+
         for (index, _) in variances.iter().enumerate() {
             if constrained_parameters.contains(&Parameter(index as u32)) {
                 continue;
             }
 
-            let (span, name) = match ast_generics.params[index] {
+            let (span, name) = match *params[index] {
                 hir::GenericParam::Lifetime(ref ld) => (ld.lifetime.span, ld.lifetime.name.name()),
                 hir::GenericParam::Type(ref tp) => (tp.span, tp.name),
+                hir::GenericParam::Const(_) => bug!("const parameter with a variance"),
             };
             self.report_bivariance(span, name);
         }
+
+        // Nothing can refer to a const parameter yet, so every one of them is unused.
+        for const_param in ast_generics.const_params() {
+            self.report_unused_const_param(const_param.span, const_param.name);
+        }
+    }
+
+    fn report_unused_const_param(&self, span: Span, param_name: ast::Name) {
+        struct_span_err!(self.tcx.sess, span, E0392, "parameter `{}` is never used", param_name)
+            .span_label(span, "unused const parameter")
+            .help(&format!("consider removing `{}`", param_name))
+            .emit();
     }
 
     fn report_bivariance(&self,
@@ -0,0 +1,186 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Checks that impls specializing a `default impl` are "always
+//! applicable", i.e. that whether they apply cannot depend on lifetimes.
+//! Lifetimes are erased by the time trans picks the most specialized
+//! impl, so an impl that only applies for some lifetimes would be
+//! selected even where it doesn't hold.
+//!
+//! Matching the trait ref of the specializing impl against the one of
+//! the impl it specializes gives an instantiation of the parameters of
+//! the latter. For the specializing impl to be always applicable:
+//!
+//! - that instantiation may not mention `'static`,
+//! - it may not mention any parameter of the specializing impl more
+//!   than once, as that would require two lifetimes (or two types that
+//!   may contain lifetimes) to be equal, and
+//! - the specializing impl may not add lifetime constraints, i.e.
+//!   outlives predicates that the impl it specializes doesn't have.
+
+use constrained_type_params as ctp;
+use rustc::hir::def_id::DefId;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::relate::{self, Relate, RelateResult, TypeRelation};
+use rustc::ty::subst::{Kind, Substs};
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+
+use syntax_pos::Span;
+
+pub fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    for &impl_id in tcx.hir.krate().trait_impls.values().flat_map(|impls| impls) {
+        check_impl(tcx, tcx.hir.local_def_id(impl_id));
+    }
+}
+
+fn check_impl<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, impl_def_id: DefId) {
+    let trait_ref = tcx.impl_trait_ref(impl_def_id).unwrap();
+    if trait_ref.references_error() {
+        return;
+    }
+
+    let parent_def_id = tcx.specialization_graph_of(trait_ref.def_id).parent(impl_def_id);
+    if parent_def_id == trait_ref.def_id || !tcx.impl_is_default(parent_def_id) {
+        return;
+    }
+
+    let parent_trait_ref = tcx.impl_trait_ref(parent_def_id).unwrap();
+    let mut matcher = ParentSubsts { tcx, substs: FxHashMap() };
+    if matcher.relate(&parent_trait_ref, &trait_ref).is_err() {
+        // The impls are related through where-clauses rather than
+        // structurally; there is nothing more we can check here.
+        debug!("always_applicable: {:?} does not match {:?}", trait_ref, parent_trait_ref);
+        return;
+    }
+    // The instantiation of the parameters of the parent impl, in order.
+    let mut parent_args: Vec<_> = matcher.substs.into_iter().collect();
+    parent_args.sort_by_key(|&(index, _)| index);
+    let parent_args: Vec<Kind<'tcx>> = parent_args.into_iter().map(|(_, arg)| arg).collect();
+
+    debug!("always_applicable: {:?} specializes {:?} with {:?}",
+           impl_def_id, parent_def_id, parent_args);
+
+    let span = tcx.def_span(impl_def_id);
+    let generics = tcx.generics_of(impl_def_id);
+
+    let mut mentions_static = false;
+    tcx.for_each_free_region(&parent_args, |r| {
+        if let ty::ReStatic = *r {
+            mentions_static = true;
+        }
+    });
+    if mentions_static {
+        struct_span_err!(tcx.sess, span, E0693,
+                         "cannot specialize on `'static` lifetime")
+            .emit();
+    }
+
+    let mut seen = FxHashSet();
+    let mut reported = FxHashSet();
+    for param in ctp::parameters_for(&parent_args, true) {
+        if !seen.insert(param.clone()) && reported.insert(param.clone()) {
+            struct_span_err!(tcx.sess, span, E0693,
+                             "specializing impl repeats parameter `{}`",
+                             parameter_name(generics, param))
+                .emit();
+        }
+    }
+
+    if parent_args.len() == tcx.generics_of(parent_def_id).count() {
+        let parent_substs = tcx.mk_substs(parent_args.into_iter());
+        check_outlives_predicates(tcx, span, impl_def_id, parent_def_id, parent_substs);
+    }
+}
+
+/// Reports the outlives predicates of the specializing impl that don't
+/// also hold for the impl it specializes.
+fn check_outlives_predicates<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                       span: Span,
+                                       impl_def_id: DefId,
+                                       parent_def_id: DefId,
+                                       parent_substs: &'tcx Substs<'tcx>)
+{
+    let parent_predicates: FxHashSet<_> =
+        tcx.predicates_of(parent_def_id).instantiate(tcx, parent_substs).predicates
+            .into_iter()
+            .collect();
+
+    for predicate in &tcx.predicates_of(impl_def_id).predicates {
+        match *predicate {
+            ty::Predicate::RegionOutlives(..) |
+            ty::Predicate::TypeOutlives(..) if !parent_predicates.contains(predicate) => {
+                struct_span_err!(tcx.sess, span, E0693,
+                                 "cannot specialize on predicate `{}`",
+                                 predicate)
+                    .emit();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn parameter_name(generics: &ty::Generics, param: ctp::Parameter) -> String {
+    let ctp::Parameter(index) = param;
+    generics.regions.iter()
+        .find(|def| def.index == index)
+        .map(|def| def.name.to_string())
+        .or_else(|| {
+            generics.types.iter()
+                .find(|def| def.index == index)
+                .map(|def| def.name.to_string())
+        })
+        .unwrap_or_else(|| bug!("no parameter with index {}", index))
+}
+
+/// Matches the trait ref of an impl against the one of an impl that
+/// specializes it, recording the instantiation of the parameters of the
+/// former.
+struct ParentSubsts<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    substs: FxHashMap<u32, Kind<'tcx>>,
+}
+
+impl<'a, 'tcx> TypeRelation<'a, 'tcx, 'tcx> for ParentSubsts<'a, 'tcx> {
+    fn tag(&self) -> &'static str { "ParentSubsts" }
+    fn tcx(&self) -> TyCtxt<'a, 'tcx, 'tcx> { self.tcx }
+    fn a_is_expected(&self) -> bool { true }
+
+    fn relate_with_variance<T: Relate<'tcx>>(&mut self,
+                                             _: ty::Variance,
+                                             a: &T,
+                                             b: &T)
+                                             -> RelateResult<'tcx, T>
+    {
+        self.relate(a, b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        if let ty::TyParam(param) = a.sty {
+            self.substs.insert(param.idx, Kind::from(b));
+            return Ok(a);
+        }
+        relate::super_relate_tys(self, a, b)
+    }
+
+    fn regions(&mut self, a: ty::Region<'tcx>, b: ty::Region<'tcx>)
+               -> RelateResult<'tcx, ty::Region<'tcx>> {
+        if let ty::ReEarlyBound(data) = *a {
+            self.substs.insert(data.index, Kind::from(b));
+        }
+        Ok(a)
+    }
+
+    fn binders<T>(&mut self, a: &ty::Binder<T>, b: &ty::Binder<T>)
+                  -> RelateResult<'tcx, ty::Binder<T>>
+        where T: Relate<'tcx>
+    {
+        Ok(ty::Binder(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
@@ -22,6 +22,7 @@ use rustc::ty::maps::Providers;
 
 use syntax::ast;
 
+mod always_applicable;
 mod builtin;
 mod inherent_impls;
 mod inherent_impls_overlap;
@@ -137,6 +138,13 @@ pub fn check_coherence<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
     ty::maps::queries::crate_inherent_impls_overlap_check::ensure(tcx, LOCAL_CRATE);
 }
 
+/// Checks that impls specializing a `default impl` are always applicable.
+/// This relies on the specialization graphs, so it must only run once
+/// coherence checking succeeded.
+pub fn check_always_applicable<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    always_applicable::check(tcx);
+}
+
 /// Overlap: No two impls for the same trait are implemented for the
 /// same type. Likewise, no two inherent impls for a given type
 /// constructor provide a method with the same name.
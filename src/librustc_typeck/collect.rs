@@ -29,10 +29,11 @@ use lint;
 use constrained_type_params as ctp;
 use middle::lang_items::SizedTraitLangItem;
 use middle::resolve_lifetime as rl;
+use rustc::middle::const_val::ConstVal;
 use rustc::mir::mono::Linkage;
-use rustc::ty::subst::Substs;
+use rustc::ty::subst::{Subst, Substs};
 use rustc::ty::{ToPredicate, ReprOptions};
-use rustc::ty::{self, AdtKind, ToPolyTraitRef, Ty, TyCtxt};
+use rustc::ty::{self, AdtKind, ToPolyTraitRef, Ty, TyCtxt, TypeFoldable};
 use rustc::ty::maps::Providers;
 use rustc::ty::util::IntTypeExt;
 use rustc::util::nodemap::{FxHashSet, FxHashMap};
@@ -42,6 +43,7 @@ use syntax::{abi, ast};
 use syntax::ast::MetaItemKind;
 use syntax::attr::{InlineAttr, list_contains_name, mark_used};
 use syntax::codemap::Spanned;
+use syntax::feature_gate::{GateIssue, emit_feature_err};
 use syntax::symbol::{Symbol, keywords};
 use syntax_pos::{Span, DUMMY_SP};
 
@@ -115,6 +117,10 @@ impl<'a, 'tcx> Visitor<'tcx> for CollectItemTypesVisitor<'a, 'tcx> {
                 self.tcx.type_of(def_id);
             }
         }
+        for param in generics.const_params() {
+            let def_id = self.tcx.hir.local_def_id(param.id);
+            self.tcx.type_of(def_id);
+        }
         intravisit::walk_generics(self, generics);
     }
 
@@ -128,10 +134,14 @@ impl<'a, 'tcx> Visitor<'tcx> for CollectItemTypesVisitor<'a, 'tcx> {
     }
 
     fn visit_ty(&mut self, ty: &'tcx hir::Ty) {
-        if let hir::TyImplTraitExistential(..) = ty.node {
-            let def_id = self.tcx.hir.local_def_id(ty.id);
-            self.tcx.generics_of(def_id);
-            self.tcx.predicates_of(def_id);
+        match ty.node {
+            hir::TyImplTraitExistential(..) => {
+                let def_id = self.tcx.hir.local_def_id(ty.id);
+                self.tcx.generics_of(def_id);
+                self.tcx.predicates_of(def_id);
+            }
+            hir::TyArray(_, length) => check_array_length_params(self.tcx, length),
+            _ => {}
         }
         intravisit::walk_ty(self, ty);
     }
@@ -147,6 +157,34 @@ impl<'a, 'tcx> Visitor<'tcx> for CollectItemTypesVisitor<'a, 'tcx> {
     }
 }
 
+/// Without `#![feature(lazy_normalization_consts)]`, the lengths of array
+/// types are evaluated eagerly, and so cannot refer to the type parameters
+/// in scope.
+fn check_array_length_params<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, length: hir::BodyId) {
+    struct TyParamVisitor<'a, 'tcx: 'a> {
+        tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    }
+
+    impl<'a, 'tcx> Visitor<'tcx> for TyParamVisitor<'a, 'tcx> {
+        fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+            NestedVisitorMap::None
+        }
+
+        fn visit_path(&mut self, path: &'tcx hir::Path, _: ast::NodeId) {
+            if let Def::TyParam(_) = path.def {
+                emit_feature_err(&self.tcx.sess.parse_sess, "lazy_normalization_consts",
+                                 path.span, GateIssue::Language,
+                                 "type parameters in array lengths are unstable");
+            }
+            intravisit::walk_path(self, path);
+        }
+    }
+
+    if !tcx.features().lazy_normalization_consts {
+        TyParamVisitor { tcx }.visit_body(tcx.hir.body(length));
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////
 // Utility types and common code for the above passes.
 
@@ -196,11 +234,17 @@ impl<'a, 'tcx> AstConv<'tcx, 'tcx> for ItemCtxt<'a, 'tcx> {
     fn projected_ty_from_poly_trait_ref(&self,
                                         span: Span,
                                         item_def_id: DefId,
+                                        item_segment: &hir::PathSegment,
                                         poly_trait_ref: ty::PolyTraitRef<'tcx>)
                                         -> Ty<'tcx>
     {
         if let Some(trait_ref) = poly_trait_ref.no_late_bound_regions() {
-            self.tcx().mk_projection(item_def_id, trait_ref.substs)
+            let substs = AstConv::create_substs_for_associated_item(self,
+                                                                    span,
+                                                                    item_def_id,
+                                                                    item_segment,
+                                                                    trait_ref.substs);
+            self.tcx().mk_projection(item_def_id, substs)
         } else {
             // no late-bound regions, we can just ignore the binder
             span_err!(self.tcx().sess, span, E0212,
@@ -389,16 +433,12 @@ fn convert_item<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, item_id: ast::NodeId) {
             tcx.impl_trait_ref(def_id);
             tcx.predicates_of(def_id);
         },
-        hir::ItemTrait(..) => {
+        hir::ItemTrait(..) | hir::ItemTraitAlias(..) => {
             tcx.generics_of(def_id);
             tcx.trait_def(def_id);
             tcx.at(it.span).super_predicates_of(def_id);
             tcx.predicates_of(def_id);
         },
-        hir::ItemTraitAlias(..) => {
-            span_err!(tcx.sess, it.span, E0645,
-                      "trait aliases are not yet implemented (see issue #41517)");
-        },
         hir::ItemStruct(ref struct_def, _) |
         hir::ItemUnion(ref struct_def, _) => {
             tcx.generics_of(def_id);
@@ -771,6 +811,31 @@ fn has_late_bound_regions<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     }
 }
 
+/// Returns the node id of the item, trait item or impl item containing `node_id`.
+fn enclosing_item(tcx: TyCtxt, node_id: ast::NodeId) -> ast::NodeId {
+    let mut parent_id = node_id;
+    loop {
+        match tcx.hir.get(parent_id) {
+            hir_map::NodeItem(_) |
+            hir_map::NodeImplItem(_) |
+            hir_map::NodeTraitItem(_) => return parent_id,
+            _ => {
+                parent_id = tcx.hir.get_parent_node(parent_id);
+            }
+        }
+    }
+}
+
+/// Whether `node_id` is the length of an array type.
+fn is_array_length(tcx: TyCtxt, node_id: ast::NodeId) -> bool {
+    match tcx.hir.get(tcx.hir.get_parent_node(node_id)) {
+        hir_map::NodeTy(&hir::Ty { node: hir::TyArray(_, length), .. }) => {
+            tcx.hir.body(length).value.id == node_id
+        }
+        _ => false,
+    }
+}
+
 fn generics_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                          def_id: DefId)
                          -> &'tcx ty::Generics {
@@ -792,17 +857,14 @@ fn generics_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         NodeExpr(&hir::Expr { node: hir::ExprClosure(..), .. }) => {
             Some(tcx.closure_base_def_id(def_id))
         }
+        NodeExpr(_) if tcx.features().lazy_normalization_consts &&
+                       is_array_length(tcx, node_id) => {
+            // Array lengths can refer to the generic parameters in scope;
+            // they are normalized lazily, once those parameters are known.
+            Some(tcx.hir.local_def_id(enclosing_item(tcx, node_id)))
+        }
         NodeTy(&hir::Ty { node: hir::TyImplTraitExistential(..), .. }) => {
-            let mut parent_id = node_id;
-            loop {
-                match tcx.hir.get(parent_id) {
-                    NodeItem(_) | NodeImplItem(_) | NodeTraitItem(_) => break,
-                    _ => {
-                        parent_id = tcx.hir.get_parent_node(parent_id);
-                    }
-                }
-            }
-            Some(tcx.hir.local_def_id(parent_id))
+            Some(tcx.hir.local_def_id(enclosing_item(tcx, node_id)))
         }
         _ => None
     };
@@ -872,13 +934,14 @@ fn generics_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     let has_self = opt_self.is_some();
     let mut parent_has_self = false;
     let mut own_start = has_self as u32;
-    let (parent_regions, parent_types) = parent_def_id.map_or((0, 0), |def_id| {
+    let (parent_regions, parent_types, parent_consts) = parent_def_id.map_or((0, 0, 0), |def_id| {
         let generics = tcx.generics_of(def_id);
         assert_eq!(has_self, false);
         parent_has_self = generics.has_self;
         own_start = generics.count() as u32;
         (generics.parent_regions + generics.regions.len() as u32,
-            generics.parent_types + generics.types.len() as u32)
+            generics.parent_types + generics.types.len() as u32,
+            generics.parent_consts + generics.consts.len() as u32)
     });
 
     let early_lifetimes = early_bound_lifetimes_from_generics(tcx, ast_generics);
@@ -891,8 +954,7 @@ fn generics_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         }
     }).collect::<Vec<_>>();
 
-    let hir_id = tcx.hir.node_to_hir_id(node_id);
-    let object_lifetime_defaults = tcx.object_lifetime_defaults(hir_id);
+    let object_lifetime_defaults = tcx.object_lifetime_defaults(def_id);
 
     // Now create the real type parameters.
     let type_start = own_start + regions.len() as u32;
@@ -918,7 +980,7 @@ fn generics_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             def_id: tcx.hir.local_def_id(p.id),
             has_default: p.default.is_some(),
             object_lifetime_default:
-                object_lifetime_defaults.as_ref().map_or(rl::Set1::Empty, |o| o[i]),
+                object_lifetime_defaults.get(i).cloned().unwrap_or(rl::Set1::Empty),
             pure_wrt_drop: p.pure_wrt_drop,
             synthetic: p.synthetic,
         }
@@ -969,12 +1031,24 @@ fn generics_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                    .map(|param| (param.def_id, param.index))
                                    .collect();
 
+    // Const parameters come after all the type parameters, `Self` included.
+    let const_start = type_start + (types.len() - has_self as usize) as u32;
+    let consts = ast_generics.const_params().enumerate().map(|(i, p)| {
+        ty::ConstParameterDef {
+            name: p.name,
+            def_id: tcx.hir.local_def_id(p.id),
+            index: const_start + i as u32,
+        }
+    }).collect();
+
     tcx.alloc_generics(ty::Generics {
         parent: parent_def_id,
         parent_regions,
         parent_types,
+        parent_consts,
         regions,
         types,
+        consts,
         type_param_to_index,
         has_self: has_self || parent_has_self,
         has_late_bound_regions: has_late_bound_regions(tcx, node),
@@ -1123,6 +1197,10 @@ fn type_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             icx.to_ty(ty)
         }
 
+        NodeConstParam(param) => {
+            icx.to_ty(&param.ty)
+        }
+
         NodeTy(&hir::Ty { node: TyImplTraitExistential(..), .. }) => {
             let owner = tcx.hir.get_parent_did(node_id);
             let hir_id = tcx.hir.node_to_hir_id(node_id);
@@ -1314,6 +1392,7 @@ fn explicit_predicates_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
 
     let mut is_trait = None;
     let mut is_default_impl_trait = None;
+    let mut is_trait_alias = false;
 
     let icx = ItemCtxt::new(tcx, def_id);
     let no_generics = hir::Generics::empty();
@@ -1343,6 +1422,11 @@ fn explicit_predicates_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                     generics
                 }
 
+                ItemTraitAlias(ref generics, _) => {
+                    is_trait_alias = true;
+                    generics
+                }
+
                 _ => &no_generics,
             }
         }
@@ -1401,6 +1485,13 @@ fn explicit_predicates_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         predicates.push(trait_ref.to_poly_trait_ref().to_predicate());
     }
 
+    // A trait alias has no `Self: Alias` predicate of its own: using
+    // the alias means proving the bounds it expands to, along with the
+    // where-clauses on its other parameters added below.
+    if is_trait_alias {
+        predicates.extend(tcx.super_predicates_of(def_id).predicates);
+    }
+
     // In default impls, we can assume that the self type implements
     // the trait. So in:
     //
@@ -1508,6 +1599,12 @@ fn explicit_predicates_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                 }
             };
 
+            // The bounds of a generic associated type may refer to its own
+            // parameters, so they are predicates of the associated type itself.
+            if !trait_item.generics.params.is_empty() {
+                return vec![].into_iter();
+            }
+
             let assoc_ty = tcx.mk_projection(
                 tcx.hir.local_def_id(trait_item.id),
                 self_trait_ref.substs,
@@ -1523,6 +1620,30 @@ fn explicit_predicates_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         }))
     }
 
+    // Add the bounds of a generic associated type, e.g. the `Deref<Target = T>`
+    // in `type Pointer<T>: Deref<Target = T>;`, in terms of its own parameters.
+    if let NodeTraitItem(&TraitItem { node: TraitItemKind::Type(ref bounds, _), .. }) = node {
+        if !ast_generics.params.is_empty() {
+            let assoc_ty = tcx.mk_projection(def_id, Substs::identity_for_item(tcx, def_id));
+
+            let bounds = compute_bounds(&icx,
+                                        assoc_ty,
+                                        bounds,
+                                        SizedByDefault::Yes,
+                                        tcx.hir.span(node_id));
+
+            predicates.extend(bounds.predicates(tcx, assoc_ty));
+        }
+    }
+
+    // Lazily normalized constants which depend on generic parameters are
+    // assumed to be evaluatable within the item whose signature they are
+    // in; it's up to the users of the item to prove them.
+    if tcx.features().lazy_normalization_consts {
+        let const_evaluatable = const_evaluatable_predicates(tcx, def_id, node, &predicates);
+        predicates.extend(const_evaluatable);
+    }
+
     // Subtle: before we store the predicates into the tcx, we
     // sort them so that predicates like `T: Foo<Item=U>` come
     // before uses of `U`.  This avoids false ambiguity errors
@@ -1543,6 +1664,72 @@ fn explicit_predicates_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     }
 }
 
+/// Returns the `ConstEvaluatable` predicates for the generic constants in
+/// the signature of `def_id`, i.e. its where-clauses, the types of its
+/// fields or arguments, or its impl header. Unless `def_id` is itself a
+/// type, this includes those predicates of the types in its signature.
+fn const_evaluatable_predicates<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                          def_id: DefId,
+                                          node: hir_map::Node<'tcx>,
+                                          predicates: &[ty::Predicate<'tcx>])
+                                          -> Vec<ty::Predicate<'tcx>> {
+    use rustc::hir::map::*;
+
+    let mut tys: Vec<_> = predicates.iter().flat_map(|predicate| predicate.walk_tys()).collect();
+    let mut is_type = false;
+    match node {
+        NodeItem(&hir::Item { node: hir::ItemFn(..), .. }) |
+        NodeTraitItem(&hir::TraitItem { node: hir::TraitItemKind::Method(..), .. }) |
+        NodeImplItem(&hir::ImplItem { node: hir::ImplItemKind::Method(..), .. }) => {
+            let sig = tcx.fn_sig(def_id);
+            tys.extend(sig.skip_binder().inputs_and_output.iter().cloned());
+        }
+        NodeItem(&hir::Item { node: hir::ItemImpl(..), .. }) => {
+            tys.push(tcx.type_of(def_id));
+            if let Some(trait_ref) = tcx.impl_trait_ref(def_id) {
+                tys.extend(trait_ref.substs.types());
+            }
+        }
+        NodeItem(&hir::Item { node: hir::ItemStruct(..), .. }) |
+        NodeItem(&hir::Item { node: hir::ItemEnum(..), .. }) |
+        NodeItem(&hir::Item { node: hir::ItemUnion(..), .. }) => {
+            // Other types are not looked into, as that would be a cycle
+            // for recursive types.
+            is_type = true;
+            for variant in &tcx.adt_def(def_id).variants {
+                tys.extend(variant.fields.iter().map(|field| tcx.type_of(field.did)));
+            }
+        }
+        _ => {}
+    }
+
+    let mut const_evaluatable = vec![];
+    for ty in tys.iter().flat_map(|ty| ty.walk()) {
+        match ty.sty {
+            ty::TyArray(_, &ty::Const { val: ConstVal::Unevaluated(def_id, substs), .. }) => {
+                if substs.needs_subst() {
+                    const_evaluatable.push(ty::Predicate::ConstEvaluatable(def_id, substs));
+                }
+            }
+            ty::TyAdt(adt_def, substs) if !is_type => {
+                let adt_predicates = tcx.predicates_of(adt_def.did);
+                const_evaluatable.extend(adt_predicates.predicates.iter().filter(|predicate| {
+                    match **predicate {
+                        ty::Predicate::ConstEvaluatable(..) => true,
+                        _ => false,
+                    }
+                }).map(|predicate| predicate.subst(tcx, substs)));
+            }
+            _ => {}
+        }
+    }
+    let mut seen: FxHashSet<_> = predicates.iter().cloned().collect();
+    const_evaluatable.retain(|predicate| {
+        !predicate.has_escaping_regions() && seen.insert(predicate.clone())
+    });
+    const_evaluatable
+}
+
 pub enum SizedByDefault { Yes, No, }
 
 /// Translate the AST's notion of ty param bounds (which are an enum consisting of a newtyped Ty or
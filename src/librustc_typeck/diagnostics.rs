@@ -4606,7 +4606,6 @@ This error indicates that there is a mismatch between generic parameters and
 impl Trait parameters in a trait declaration versus its impl.
 
 ```compile_fail,E0643
-#![feature(universal_impl_trait)]
 trait Foo {
     fn foo(&self, _: &impl Iterator);
 }
@@ -4863,6 +4862,7 @@ register_diagnostics! {
     E0632, // cannot provide explicit type parameters when `impl Trait` is used in
            // argument position.
     E0641, // cannot cast to/from a pointer with an unknown kind
-    E0645, // trait aliases not finished
+    E0645, // trait alias cannot be used as a trait object
+    E0693, // specializing impl is not always applicable
     E0907, // type inside generator must be known in this context
 }
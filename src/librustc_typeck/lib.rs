@@ -338,6 +338,11 @@ pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>)
           coherence::check_coherence(tcx));
     })?;
 
+    tcx.sess.track_errors(|| {
+        time(tcx.sess, "specialization checking", ||
+             coherence::check_always_applicable(tcx));
+    })?;
+
     tcx.sess.track_errors(|| {
         time(tcx.sess, "variance testing", ||
              variance::test::test_variance(tcx));
@@ -298,8 +298,9 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
             }
 
             ty::TyProjection(ref data) => {
-                let tcx = self.tcx();
-                self.add_constraints_from_trait_ref(current, data.trait_ref(tcx), variance);
+                // This covers the trait reference as well as the arguments
+                // of a generic associated type, all of which are invariant.
+                self.add_constraints_from_invariant_substs(current, data.substs, variance);
             }
 
             ty::TyAnon(_, substs) => {
@@ -388,6 +389,8 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
                 UnpackedKind::Type(ty) => {
                     self.add_constraints_from_ty(current, ty, variance_i)
                 }
+                // Const parameters are always invariant, see `create_map`.
+                UnpackedKind::Const(_) => {}
             }
         }
     }
@@ -425,6 +428,7 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
 
             ty::ReFree(..) |
             ty::ReClosureBound(..) |
+            ty::ReCanonical(..) |
             ty::ReScope(..) |
             ty::ReVar(..) |
             ty::ReSkolemized(..) |
@@ -109,6 +109,15 @@ impl<'a, 'tcx> SolveContext<'a, 'tcx> {
                 }
             }
 
+            // Constants are never subtypes of each other: make const parameters invariant.
+            let mut const_generics = Some(generics);
+            while let Some(generics) = const_generics {
+                for param in &generics.consts {
+                    variances[param.index as usize] = ty::Invariant;
+                }
+                const_generics = generics.parent.map(|parent| tcx.generics_of(parent));
+            }
+
             (def_id, Lrc::new(variances))
         }).collect()
     }
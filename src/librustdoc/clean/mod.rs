@@ -1237,6 +1237,21 @@ impl Clean<TyParam> for hir::TyParam {
     }
 }
 
+#[derive(Clone, RustcEncodable, RustcDecodable, PartialEq, Eq, Debug, Hash)]
+pub struct ConstParam {
+    pub name: String,
+    pub ty: Type,
+}
+
+impl Clean<ConstParam> for hir::ConstParam {
+    fn clean(&self, cx: &DocContext) -> ConstParam {
+        ConstParam {
+            name: self.name.clean(cx),
+            ty: self.ty.clean(cx),
+        }
+    }
+}
+
 impl<'tcx> Clean<TyParam> for ty::TypeParameterDef {
     fn clean(&self, cx: &DocContext) -> TyParam {
         cx.renderinfo.borrow_mut().external_typarams.insert(self.def_id, self.name.clean(cx));
@@ -1490,6 +1505,7 @@ impl Clean<Option<Lifetime>> for ty::RegionKind {
             ty::ReSkolemized(..) |
             ty::ReEmpty |
             ty::ReClosureBound(_) |
+            ty::ReCanonical(_) |
             ty::ReErased => None
         }
     }
@@ -1613,6 +1629,7 @@ impl<'tcx> Clean<Type> for ty::ProjectionTy<'tcx> {
 pub enum GenericParam {
     Lifetime(Lifetime),
     Type(TyParam),
+    Const(ConstParam),
 }
 
 impl Clean<GenericParam> for hir::GenericParam {
@@ -1620,6 +1637,7 @@ impl Clean<GenericParam> for hir::GenericParam {
         match *self {
             hir::GenericParam::Lifetime(ref l) => GenericParam::Lifetime(l.clean(cx)),
             hir::GenericParam::Type(ref t) => GenericParam::Type(t.clean(cx)),
+            hir::GenericParam::Const(ref c) => GenericParam::Const(c.clean(cx)),
         }
     }
 }
@@ -3619,6 +3637,9 @@ fn name_from_pat(p: &hir::Pat) -> String {
             let end = end.iter().map(|p| name_from_pat(&**p));
             format!("[{}]", begin.chain(mid).chain(end).collect::<Vec<_>>().join(", "))
         },
+        PatKind::Or(ref pats) => {
+            pats.iter().map(|p| name_from_pat(&**p)).collect::<Vec<String>>().join(" | ")
+        }
     }
 }
 
@@ -3641,6 +3662,7 @@ fn print_const(cx: &DocContext, n: &ty::Const) -> String {
             }
             s
         },
+        ConstVal::Param(param) => param.name.to_string(),
     }
 }
 
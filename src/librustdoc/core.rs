@@ -152,13 +152,13 @@ pub fn run_core(search_paths: SearchPaths,
     let codemap = Lrc::new(codemap::CodeMap::new(sessopts.file_path_mapping()));
     let diagnostic_handler = errors::Handler::with_tty_emitter(ColorConfig::Auto,
                                                                true,
-                                                               false,
+                                                               None,
                                                                Some(codemap.clone()));
 
     let mut sess = session::build_session_(
         sessopts, cpath, diagnostic_handler, codemap,
     );
-    let trans = rustc_driver::get_trans(&sess);
+    let trans = rustc_driver::get_codegen_backend(&sess);
     let cstore = Rc::new(CStore::new(trans.metadata_loader()));
     rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
 
@@ -142,6 +142,13 @@ impl fmt::Display for clean::GenericParam {
 
                 Ok(())
             }
+            clean::GenericParam::Const(ref cp) => {
+                if f.alternate() {
+                    write!(f, "const {}: {:#}", cp.name, cp.ty)
+                } else {
+                    write!(f, "const {}:&nbsp;{}", cp.name, cp.ty)
+                }
+            }
         }
     }
 }
@@ -323,8 +323,10 @@ impl<'a> Classifier<'a> {
             }
 
             // Keywords are also included in the identifier set.
-            token::Ident(ident) => {
+            token::Ident(ident, is_raw) => {
                 match &*ident.name.as_str() {
+                    _ if is_raw => Class::Ident,
+
                     "ref" | "mut" => Class::RefKeyWord,
 
                     "self" |"Self" => Class::Self_,
@@ -113,7 +113,7 @@ impl Path {
     pub fn default_to_global(mut self) -> Path {
         if !self.is_global() {
             let ident = self.segments[0].identifier;
-            if !::parse::token::Ident(ident).is_path_segment_keyword() ||
+            if !::parse::token::Ident(ident, false).is_path_segment_keyword() ||
                ident.name == keywords::Crate.name() {
                 self.segments.insert(0, PathSegment::crate_root(self.span));
             }
@@ -323,10 +323,21 @@ pub struct TyParam {
     pub span: Span,
 }
 
+/// A const generic parameter, e.g. `const N: usize`.
+#[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
+pub struct ConstParam {
+    pub attrs: ThinVec<Attribute>,
+    pub ident: Ident,
+    pub id: NodeId,
+    pub ty: P<Ty>,
+    pub span: Span,
+}
+
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub enum GenericParam {
     Lifetime(LifetimeDef),
     Type(TyParam),
+    Const(ConstParam),
 }
 
 impl GenericParam {
@@ -343,6 +354,13 @@ impl GenericParam {
             _ => false,
         }
     }
+
+    pub fn is_const_param(&self) -> bool {
+        match *self {
+            GenericParam::Const(_) => true,
+            _ => false,
+        }
+    }
 }
 
 /// Represents lifetime, type and const parameters attached to a declaration of
@@ -578,7 +596,7 @@ impl Pat {
             PatKind::Struct(_, ref fields, _) => {
                 fields.iter().all(|field| field.node.pat.walk(it))
             }
-            PatKind::TupleStruct(_, ref s, _) | PatKind::Tuple(ref s, _) => {
+            PatKind::TupleStruct(_, ref s, _) | PatKind::Tuple(ref s, _) | PatKind::Or(ref s) => {
                 s.iter().all(|p| p.walk(it))
             }
             PatKind::Box(ref s) | PatKind::Ref(ref s, _) | PatKind::Paren(ref s) => {
@@ -677,6 +695,9 @@ pub enum PatKind {
     Slice(Vec<P<Pat>>, Option<P<Pat>>, Vec<P<Pat>>),
     /// Parentheses in patters used for grouping, i.e. `(PAT)`.
     Paren(P<Pat>),
+    /// An or-pattern nested inside another pattern, e.g. `Some(0 | 1)`.
+    /// Top-level alternatives of a match arm are kept in `Arm::pats` instead.
+    Or(Vec<P<Pat>>),
     /// A macro pattern; pre-expansion
     Mac(Mac),
 }
@@ -283,9 +283,6 @@ declare_features! (
     // Allows `impl Trait` in function return types.
     (active, conservative_impl_trait, "1.12.0", Some(34511), None),
 
-    // Allows `impl Trait` in function arguments.
-    (active, universal_impl_trait, "1.23.0", Some(34511), None),
-
     // The `!` type
     (active, never_type, "1.13.0", Some(35121), None),
 
@@ -399,9 +396,6 @@ declare_features! (
     (active, clone_closures, "1.22.0", Some(44490), None),
     (active, copy_closures, "1.22.0", Some(44490), None),
 
-    // allow `'_` placeholder lifetimes
-    (active, underscore_lifetimes, "1.22.0", Some(44524), None),
-
     // allow `..=` in patterns (RFC 1192)
     (active, dotdoteq_in_patterns, "1.22.0", Some(28237), None),
 
@@ -455,6 +449,34 @@ declare_features! (
 
     // Parentheses in patterns
     (active, pattern_parentheses, "1.26.0", None, None),
+
+    // Allows `#![const_eval_limit="N"]`
+    (active, const_eval_limit, "1.26.0", None, None),
+
+    // Raw identifiers, e.g. `r#match`, allowing keywords to be used as identifiers
+    (active, raw_identifiers, "1.26.0", None, Some(Epoch::Epoch2018)),
+
+    // `impl Trait` in the types of `let` bindings, `const`s and `static`s
+    (active, impl_trait_in_bindings, "1.26.0", Some(34511), None),
+
+    // Const generic parameters, e.g. `struct Foo<const N: usize>`
+    (active, const_generics, "1.26.0", Some(44580), None),
+
+    // Lazily normalized constants in types, e.g. `[u8; T::SIZE]`
+    (active, lazy_normalization_consts, "1.26.0", Some(43408), None),
+
+    // Integer matches are exhaustive if their arms cover every value, e.g. `0...255u8`
+    (active, exhaustive_integer_patterns, "1.26.0", Some(50907), None),
+
+    // Patterns take the uninhabitedness of types into account, e.g. `let Ok(x) = Ok::<_, !>(1);`
+    (active, exhaustive_patterns, "1.26.0", Some(51085), None),
+
+    // Or-patterns nested inside other patterns, e.g. `Some(0 | 1)`
+    (active, or_patterns, "1.26.0", Some(54883), None),
+
+    // Calls to `const fn`s which aren't `#[rustc_promotable]` and reads of union fields
+    // are only promoted where a constant is required, e.g. not in `&foo()` in a `fn`
+    (active, restricted_promotion, "1.26.0", None, None),
 );
 
 declare_features! (
@@ -554,6 +576,10 @@ declare_features! (
     (accepted, match_beginning_vert, "1.25.0", Some(44101), None),
     // Nested groups in `use` (RFC 2128)
     (accepted, use_nested_groups, "1.25.0", Some(44494), None),
+    // allow `'_` placeholder lifetimes
+    (accepted, underscore_lifetimes, "1.26.0", Some(44524), None),
+    // Allows `impl Trait` in function arguments.
+    (accepted, universal_impl_trait, "1.26.0", Some(34511), None),
 );
 
 // If you change this, please modify src/doc/unstable-book as well. You must
@@ -711,6 +737,11 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                              "the `#[rustc_on_unimplemented]` attribute \
                                               is an experimental feature",
                                              cfg_fn!(on_unimplemented))),
+    ("rustc_on_outlives_failure", Normal, Gated(Stability::Unstable,
+                                                "rustc_attrs",
+                                                "the `#[rustc_on_outlives_failure]` attribute \
+                                                 is an experimental feature",
+                                                cfg_fn!(rustc_attrs))),
     ("rustc_const_unstable", Normal, Gated(Stability::Unstable,
                                              "rustc_const_unstable",
                                              "the `#[rustc_const_unstable]` attribute \
@@ -749,12 +780,24 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                       is just used for rustc unit tests \
                                       and will never be stable",
                                      cfg_fn!(rustc_attrs))),
+    ("rustc_unsafe_operations", Normal, Gated(Stability::Unstable,
+                                              "rustc_attrs",
+                                              "the `#[rustc_unsafe_operations]` attribute \
+                                               is just used for rustc unit tests \
+                                               and will never be stable",
+                                              cfg_fn!(rustc_attrs))),
     ("rustc_regions", Normal, Gated(Stability::Unstable,
                                     "rustc_attrs",
                                     "the `#[rustc_regions]` attribute \
                                      is just used for rustc unit tests \
                                      and will never be stable",
                                     cfg_fn!(rustc_attrs))),
+    ("rustc_borrowck_panic", Normal, Gated(Stability::Unstable,
+                                           "rustc_attrs",
+                                           "the `#[rustc_borrowck_panic]` attribute \
+                                            is just used for rustc unit tests \
+                                            and will never be stable",
+                                           cfg_fn!(rustc_attrs))),
     ("rustc_error", Whitelisted, Gated(Stability::Unstable,
                                        "rustc_attrs",
                                        "the `#[rustc_error]` attribute \
@@ -994,6 +1037,10 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                  "rustc_attrs",
                                  "never will be stable",
                                  cfg_fn!(rustc_attrs))),
+    ("rustc_promotable", Whitelisted, Gated(Stability::Unstable,
+                                 "rustc_attrs",
+                                 "never will be stable",
+                                 cfg_fn!(rustc_attrs))),
 
     // Crate level attributes
     ("crate_name", CrateLevel, Ungated),
@@ -1005,6 +1052,11 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
     ("no_builtins", CrateLevel, Ungated),
     ("recursion_limit", CrateLevel, Ungated),
     ("type_length_limit", CrateLevel, Ungated),
+    ("const_eval_limit", CrateLevel, Gated(Stability::Unstable,
+                                           "const_eval_limit",
+                                           "the `#[const_eval_limit]` attribute is \
+                                            experimental",
+                                           cfg_fn!(const_eval_limit))),
 ];
 
 // cfg(...)'s that are feature gated
@@ -1377,6 +1429,11 @@ impl<'a> PostExpansionVisitor<'a> {
                 }
             }
         }
+
+        for &span in &*self.context.parse_sess.raw_identifier_spans.borrow() {
+            gate_feature_post!(&self, raw_identifiers, span,
+                               "raw identifiers are experimental and subject to change");
+        }
     }
 }
 
@@ -1493,7 +1550,7 @@ impl<'a> Visitor<'a> for PostExpansionVisitor<'a> {
             ast::ItemKind::TraitAlias(..) => {
                 gate_feature_post!(&self, trait_alias,
                                    i.span,
-                                   "trait aliases are not yet fully implemented");
+                                   "trait aliases are experimental");
             }
 
             ast::ItemKind::Impl(_, polarity, defaultness, _, _, _, ref impl_items) => {
@@ -1670,6 +1727,10 @@ impl<'a> Visitor<'a> for PostExpansionVisitor<'a> {
                 gate_feature_post!(&self, pattern_parentheses, pattern.span,
                                    "parentheses in patterns are unstable");
             }
+            PatKind::Or(..) => {
+                gate_feature_post!(&self, or_patterns, pattern.span,
+                                   "or-patterns syntax is experimental");
+            }
             _ => {}
         }
         visit::walk_pat(self, pattern)
@@ -1777,6 +1838,11 @@ impl<'a> Visitor<'a> for PostExpansionVisitor<'a> {
                 (&ld.attrs, "attributes on lifetime bindings are experimental"),
             ast::GenericParam::Type(ref t) =>
                 (&t.attrs, "attributes on type parameter bindings are experimental"),
+            ast::GenericParam::Const(ref c) => {
+                gate_feature_post!(&self, const_generics, c.span,
+                                   "const generics are unstable");
+                (&c.attrs, "attributes on const parameter bindings are experimental")
+            }
         };
 
         if !attrs.is_empty() {
@@ -1785,14 +1851,6 @@ impl<'a> Visitor<'a> for PostExpansionVisitor<'a> {
 
         visit::walk_generic_param(self, param)
     }
-
-    fn visit_lifetime(&mut self, lt: &'a ast::Lifetime) {
-        if lt.ident.name == "'_" {
-            gate_feature_post!(&self, underscore_lifetimes, lt.span,
-                               "underscore lifetimes are unstable");
-        }
-        visit::walk_lifetime(self, lt)
-    }
 }
 
 pub fn get_features(span_handler: &Handler, krate_attrs: &[ast::Attribute],
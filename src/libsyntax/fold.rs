@@ -241,6 +241,10 @@ pub trait Folder : Sized {
         noop_fold_ty_param(tp, self)
     }
 
+    fn fold_const_param(&mut self, cp: ConstParam) -> ConstParam {
+        noop_fold_const_param(cp, self)
+    }
+
     fn fold_generic_param(&mut self, param: GenericParam) -> GenericParam {
         noop_fold_generic_param(param, self)
     }
@@ -577,7 +581,7 @@ pub fn noop_fold_tts<T: Folder>(tts: TokenStream, fld: &mut T) -> TokenStream {
 // apply ident folder if it's an ident, apply other folds to interpolated nodes
 pub fn noop_fold_token<T: Folder>(t: token::Token, fld: &mut T) -> token::Token {
     match t {
-        token::Ident(id) => token::Ident(fld.fold_ident(id)),
+        token::Ident(id, is_raw) => token::Ident(fld.fold_ident(id), is_raw),
         token::Lifetime(id) => token::Lifetime(fld.fold_ident(id)),
         token::Interpolated(nt) => {
             let nt = match Lrc::try_unwrap(nt) {
@@ -629,7 +633,8 @@ pub fn noop_fold_interpolated<T: Folder>(nt: token::Nonterminal, fld: &mut T)
         token::NtPat(pat) => token::NtPat(fld.fold_pat(pat)),
         token::NtExpr(expr) => token::NtExpr(fld.fold_expr(expr)),
         token::NtTy(ty) => token::NtTy(fld.fold_ty(ty)),
-        token::NtIdent(id) => token::NtIdent(Spanned::<Ident>{node: fld.fold_ident(id.node), ..id}),
+        token::NtIdent(id, is_raw) =>
+            token::NtIdent(Spanned::<Ident>{node: fld.fold_ident(id.node), ..id}, is_raw),
         token::NtMeta(meta) => token::NtMeta(fld.fold_meta_item(meta)),
         token::NtPath(path) => token::NtPath(fld.fold_path(path)),
         token::NtTT(tt) => token::NtTT(fld.fold_tt(tt)),
@@ -685,10 +690,26 @@ pub fn noop_fold_ty_param<T: Folder>(tp: TyParam, fld: &mut T) -> TyParam {
     }
 }
 
+pub fn noop_fold_const_param<T: Folder>(cp: ConstParam, fld: &mut T) -> ConstParam {
+    let ConstParam {attrs, id, ident, ty, span} = cp;
+    let attrs: Vec<_> = attrs.into();
+    ConstParam {
+        attrs: attrs.into_iter()
+            .flat_map(|x| fld.fold_attribute(x).into_iter())
+            .collect::<Vec<_>>()
+            .into(),
+        id: fld.new_id(id),
+        ident: fld.fold_ident(ident),
+        ty: fld.fold_ty(ty),
+        span: fld.new_span(span),
+    }
+}
+
 pub fn noop_fold_generic_param<T: Folder>(param: GenericParam, fld: &mut T) -> GenericParam {
     match param {
         GenericParam::Lifetime(l) => GenericParam::Lifetime(fld.fold_lifetime_def(l)),
         GenericParam::Type(t) => GenericParam::Type(fld.fold_ty_param(t)),
+        GenericParam::Const(c) => GenericParam::Const(fld.fold_const_param(c)),
     }
 }
 
@@ -1149,6 +1170,7 @@ pub fn noop_fold_pat<T: Folder>(p: P<Pat>, folder: &mut T) -> P<Pat> {
                        after.move_map(|x| folder.fold_pat(x)))
             }
             PatKind::Paren(inner) => PatKind::Paren(folder.fold_pat(inner)),
+            PatKind::Or(pats) => PatKind::Or(pats.move_map(|x| folder.fold_pat(x))),
             PatKind::Mac(mac) => PatKind::Mac(folder.fold_mac(mac))
         },
         span: folder.new_span(span)
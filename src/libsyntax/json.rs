@@ -22,7 +22,8 @@
 use codemap::{CodeMap, FilePathMapping};
 use syntax_pos::{self, MacroBacktrace, Span, SpanLabel, MultiSpan};
 use errors::registry::Registry;
-use errors::{DiagnosticBuilder, SubDiagnostic, CodeSuggestion, CodeMapper};
+use errors::{DiagnosticBuilder, SubDiagnostic, CodeSuggestion, CodeMapper, Substitution};
+use errors::Applicability;
 use errors::DiagnosticId;
 use errors::emitter::{Emitter, EmitterWriter};
 
@@ -38,8 +39,9 @@ pub struct JsonEmitter {
     registry: Option<Registry>,
     cm: Lrc<CodeMapper + 'static>,
     pretty: bool,
-    /// Whether "approximate suggestions" are enabled in the config
-    approximate_suggestions: bool,
+    /// Whether the applicability of suggestions should be included in the output,
+    /// both as `suggestion_applicability` and as the older `suggestion_approximate`
+    suggestion_applicability: bool,
     ui_testing: bool,
 }
 
@@ -47,13 +49,13 @@ impl JsonEmitter {
     pub fn stderr(registry: Option<Registry>,
                   code_map: Lrc<CodeMap>,
                   pretty: bool,
-                  approximate_suggestions: bool) -> JsonEmitter {
+                  suggestion_applicability: bool) -> JsonEmitter {
         JsonEmitter {
             dst: Box::new(io::stderr()),
             registry,
             cm: code_map,
             pretty,
-            approximate_suggestions,
+            suggestion_applicability,
             ui_testing: false,
         }
     }
@@ -68,13 +70,13 @@ impl JsonEmitter {
                registry: Option<Registry>,
                code_map: Lrc<CodeMap>,
                pretty: bool,
-               approximate_suggestions: bool) -> JsonEmitter {
+               suggestion_applicability: bool) -> JsonEmitter {
         JsonEmitter {
             dst,
             registry,
             cm: code_map,
             pretty,
-            approximate_suggestions,
+            suggestion_applicability,
             ui_testing: false,
         }
     }
@@ -136,7 +138,12 @@ struct DiagnosticSpan {
     /// If we are suggesting a replacement, this will contain text
     /// that should be sliced in atop this span.
     suggested_replacement: Option<String>,
-    /// If the suggestion is approximate
+    /// How confident we are that the suggestion can be applied without
+    /// a human checking it.
+    #[rustc_serialize_exclude_null]
+    suggestion_applicability: Option<Applicability>,
+    /// Whether the suggestion may need a human to look at it. Kept for tools
+    /// that predate `suggestion_applicability`.
     #[rustc_serialize_exclude_null]
     suggestion_approximate: Option<bool>,
     /// Macro invocations that created the code at this span, if any.
@@ -179,15 +186,28 @@ impl Diagnostic {
     fn from_diagnostic_builder(db: &DiagnosticBuilder,
                                je: &JsonEmitter)
                                -> Diagnostic {
-        let sugg = db.suggestions.iter().map(|sugg| {
-            Diagnostic {
-                message: sugg.msg.clone(),
-                code: None,
-                level: "help",
-                spans: DiagnosticSpan::from_suggestion(sugg, je),
-                children: vec![],
-                rendered: None,
-            }
+        let sugg = db.suggestions.iter().flat_map(|sugg| {
+            // The alternatives of a multipart suggestion each get their own
+            // child, so that tools know which edits have to be applied
+            // together.
+            let multipart = sugg.substitutions.iter().any(|s| s.parts.len() > 1);
+            let alternatives: Vec<&[Substitution]> = if multipart {
+                sugg.substitutions.chunks(1).collect()
+            } else {
+                vec![&sugg.substitutions[..]]
+            };
+            alternatives.into_iter().map(move |substitutions| {
+                Diagnostic {
+                    message: sugg.msg.clone(),
+                    code: None,
+                    level: "help",
+                    spans: DiagnosticSpan::from_suggestion(sugg,
+                                                           substitutions,
+                                                           je),
+                    children: vec![],
+                    rendered: None,
+                }
+            })
         });
 
         // generate regular command line output and store it in the json
@@ -239,7 +259,7 @@ impl Diagnostic {
 
 impl DiagnosticSpan {
     fn from_span_label(span: SpanLabel,
-                       suggestion: Option<(&String, bool)>,
+                       suggestion: Option<(&String, Applicability)>,
                        je: &JsonEmitter)
                        -> DiagnosticSpan {
         Self::from_span_etc(span.span,
@@ -252,7 +272,7 @@ impl DiagnosticSpan {
     fn from_span_etc(span: Span,
                      is_primary: bool,
                      label: Option<String>,
-                     suggestion: Option<(&String, bool)>,
+                     suggestion: Option<(&String, Applicability)>,
                      je: &JsonEmitter)
                      -> DiagnosticSpan {
         // obtain the full backtrace from the `macro_backtrace`
@@ -272,7 +292,7 @@ impl DiagnosticSpan {
     fn from_span_full(span: Span,
                       is_primary: bool,
                       label: Option<String>,
-                      suggestion: Option<(&String, bool)>,
+                      suggestion: Option<(&String, Applicability)>,
                       mut backtrace: vec::IntoIter<MacroBacktrace>,
                       je: &JsonEmitter)
                       -> DiagnosticSpan {
@@ -301,10 +321,10 @@ impl DiagnosticSpan {
             })
         });
 
-        let suggestion_approximate = if je.approximate_suggestions {
-             suggestion.map(|x| x.1)
+        let (suggestion_applicability, suggestion_approximate) = if je.suggestion_applicability {
+            (suggestion.map(|x| x.1), suggestion.map(|x| x.1 != Applicability::MachineApplicable))
         } else {
-            None
+            (None, None)
         };
 
         DiagnosticSpan {
@@ -318,6 +338,7 @@ impl DiagnosticSpan {
             is_primary,
             text: DiagnosticSpanLine::from_span(span, je),
             suggested_replacement: suggestion.map(|x| x.0.clone()),
+            suggestion_applicability,
             suggestion_approximate,
             expansion: backtrace_step,
             label,
@@ -331,24 +352,25 @@ impl DiagnosticSpan {
            .collect()
     }
 
-    fn from_suggestion(suggestion: &CodeSuggestion, je: &JsonEmitter)
+    fn from_suggestion(suggestion: &CodeSuggestion,
+                       substitutions: &[Substitution],
+                       je: &JsonEmitter)
                        -> Vec<DiagnosticSpan> {
-        suggestion.substitutions
-                      .iter()
-                      .flat_map(|substitution| {
-                          substitution.parts.iter().map(move |suggestion_inner| {
-                              let span_label = SpanLabel {
-                                  span: suggestion_inner.span,
-                                  is_primary: true,
-                                  label: None,
-                              };
-                              DiagnosticSpan::from_span_label(span_label,
-                                                              Some((&suggestion_inner.snippet,
-                                                                   suggestion.approximate)),
-                                                              je)
-                          })
-                      })
-                      .collect()
+        substitutions.iter()
+            .flat_map(|substitution| {
+                substitution.parts.iter().map(move |suggestion_inner| {
+                    let span_label = SpanLabel {
+                        span: suggestion_inner.span,
+                        is_primary: true,
+                        label: None,
+                    };
+                    DiagnosticSpan::from_span_label(span_label,
+                                                    Some((&suggestion_inner.snippet,
+                                                          suggestion.applicability)),
+                                                    je)
+                })
+            })
+            .collect()
     }
 }
 
@@ -15,7 +15,7 @@ use ast::Unsafety;
 use ast::{Mod, Arg, Arm, Attribute, BindingMode, TraitItemKind};
 use ast::Block;
 use ast::{BlockCheckMode, CaptureBy, Movability};
-use ast::{Constness, Crate};
+use ast::{ConstParam, Constness, Crate};
 use ast::Defaultness;
 use ast::EnumDef;
 use ast::{Expr, ExprKind, RangeLimits};
@@ -42,6 +42,7 @@ use ast::{BinOpKind, UnOp};
 use ast::{RangeEnd, RangeSyntax};
 use {ast, attr};
 use codemap::{self, CodeMap, Spanned, respan};
+use epoch::Epoch;
 use syntax_pos::{self, Span, MultiSpan, BytePos, FileName, DUMMY_SP};
 use errors::{self, DiagnosticBuilder};
 use parse::{self, classify, token};
@@ -358,7 +359,7 @@ impl TokenCursor {
 
         let body = TokenTree::Delimited(sp, Delimited {
             delim: token::Bracket,
-            tts: [TokenTree::Token(sp, token::Ident(ast::Ident::from_str("doc"))),
+            tts: [TokenTree::Token(sp, token::Ident(ast::Ident::from_str("doc"), false)),
                   TokenTree::Token(sp, token::Eq),
                   TokenTree::Token(sp, token::Literal(
                       token::StrRaw(Symbol::intern(&stripped), num_of_hashes), None))]
@@ -784,7 +785,7 @@ impl<'a> Parser<'a> {
 
     fn parse_ident_common(&mut self, recover: bool) -> PResult<'a, ast::Ident> {
         match self.token {
-            token::Ident(i) => {
+            token::Ident(i, is_raw) => {
                 if self.token.is_reserved_ident() {
                     let mut err = self.expected_ident_found();
                     if recover {
@@ -792,6 +793,8 @@ impl<'a> Parser<'a> {
                     } else {
                         return Err(err);
                     }
+                } else if !is_raw {
+                    self.check_epoch_keyword(i);
                 }
                 self.bump();
                 Ok(i)
@@ -810,6 +813,21 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Reports the use as an identifier of a keyword which is only reserved
+    /// starting with the 2018 epoch, e.g. `async`.
+    fn check_epoch_keyword(&self, ident: ast::Ident) {
+        if self.sess.epoch < Epoch::Epoch2018 ||
+           (ident.name != keywords::Async.name() && ident.name != keywords::Await.name()) {
+            return
+        }
+        let msg = format!("`{}` is a keyword in the 2018 epoch", ident);
+        let mut err = self.diagnostic().struct_span_err(self.span, &msg);
+        err.span_suggestion(self.span,
+                            "you can use a raw identifier to stay compatible",
+                            format!("r#{}", ident));
+        err.emit();
+    }
+
     /// Check if the next token is `tok`, and return `true` if so.
     ///
     /// This method will automatically add `tok` to `expected_tokens` if `tok` is not
@@ -1929,7 +1947,7 @@ impl<'a> Parser<'a> {
 
     pub fn parse_path_segment_ident(&mut self) -> PResult<'a, ast::Ident> {
         match self.token {
-            token::Ident(sid) if self.token.is_path_segment_keyword() => {
+            token::Ident(sid, _) if self.token.is_path_segment_keyword() => {
                 self.bump();
                 Ok(sid)
             }
@@ -2744,11 +2762,14 @@ impl<'a> Parser<'a> {
     }
 
     pub fn process_potential_macro_variable(&mut self) {
-        let ident = match self.token {
+        let (ident, is_raw) = match self.token {
             token::Dollar if self.span.ctxt() != syntax_pos::hygiene::SyntaxContext::empty() &&
                              self.look_ahead(1, |t| t.is_ident()) => {
                 self.bump();
-                let name = match self.token { token::Ident(ident) => ident, _ => unreachable!() };
+                let name = match self.token {
+                    token::Ident(ident, _) => ident,
+                    _ => unreachable!()
+                };
                 let mut err = self.fatal(&format!("unknown macro variable `{}`", name));
                 err.span_label(self.span, "unknown macro variable");
                 err.emit();
@@ -2757,13 +2778,13 @@ impl<'a> Parser<'a> {
             token::Interpolated(ref nt) => {
                 self.meta_var_span = Some(self.span);
                 match nt.0 {
-                    token::NtIdent(ident) => ident,
+                    token::NtIdent(ident, is_raw) => (ident, is_raw),
                     _ => return,
                 }
             }
             _ => return,
         };
-        self.token = token::Ident(ident.node);
+        self.token = token::Ident(ident.node, is_raw);
         self.span = ident.span;
     }
 
@@ -3320,6 +3341,7 @@ impl<'a> Parser<'a> {
         // Parse: `for <src_pat> in <src_expr> <src_loop_block>`
 
         let pat = self.parse_top_level_pat()?;
+        let pat = self.parse_or_pat_rest(pat)?;
         if !self.eat_keyword(keywords::In) {
             let in_span = self.prev_span.between(self.span);
             let mut err = self.sess.span_diagnostic
@@ -3547,6 +3569,31 @@ impl<'a> Parser<'a> {
         };
     }
 
+    /// Parse a subpattern which may be an or-pattern, e.g. the `0 | 1` in `Some(0 | 1)`.
+    fn parse_pat_with_or(&mut self) -> PResult<'a, P<Pat>> {
+        let pat = self.parse_pat()?;
+        self.parse_or_pat_rest(pat)
+    }
+
+    /// Parse the remaining alternatives of an or-pattern whose first alternative is `pat`,
+    /// if any.
+    fn parse_or_pat_rest(&mut self, pat: P<Pat>) -> PResult<'a, P<Pat>> {
+        let lo = pat.span;
+        if !self.check(&token::BinOp(token::Or)) {
+            return Ok(pat);
+        }
+
+        let mut pats = vec![pat];
+        while self.eat(&token::BinOp(token::Or)) {
+            pats.push(self.parse_pat()?);
+        }
+        Ok(P(Pat {
+            id: ast::DUMMY_NODE_ID,
+            node: PatKind::Or(pats),
+            span: lo.to(self.prev_span),
+        }))
+    }
+
     // Parses a parenthesized list of patterns like
     // `()`, `(p)`, `(p,)`, `(p, q)`, or `(p, .., q)`. Returns:
     // - a vector of the patterns that were parsed
@@ -3574,7 +3621,7 @@ impl<'a> Parser<'a> {
                                   "`..` can only be used once per tuple or tuple struct pattern");
                 }
             } else if !self.check(&token::CloseDelim(token::Paren)) {
-                fields.push(self.parse_pat()?);
+                fields.push(self.parse_pat_with_or()?);
             } else {
                 break
             }
@@ -3628,9 +3675,15 @@ impl<'a> Parser<'a> {
                     }
                     continue
                 }
+
+                if self.is_subslice_binding() {
+                    slice = Some(self.parse_subslice_binding()?);
+                    before_slice = false;
+                    continue
+                }
             }
 
-            let subpat = self.parse_pat()?;
+            let subpat = self.parse_pat_with_or()?;
             if before_slice && self.eat(&token::DotDot) {
                 slice = Some(subpat);
                 before_slice = false;
@@ -3644,6 +3697,50 @@ impl<'a> Parser<'a> {
         Ok((before, slice, after))
     }
 
+    /// Is the parser looking at a subslice binding, e.g. `rest @ ..` or
+    /// `ref mut rest @ ..`, in a slice pattern?
+    fn is_subslice_binding(&self) -> bool {
+        let mut dist = 0;
+        if self.token.is_keyword(keywords::Ref) {
+            dist += 1;
+            if self.look_ahead(1, |t| t.is_keyword(keywords::Mut)) {
+                dist += 1;
+            }
+        } else if self.token.is_keyword(keywords::Mut) {
+            dist += 1;
+        }
+
+        self.look_ahead(dist, |t| t.is_ident()) &&
+            self.look_ahead(dist + 1, |t| *t == token::At) &&
+            self.look_ahead(dist + 2, |t| *t == token::DotDot) &&
+            self.look_ahead(dist + 3, |t| {
+                *t == token::Comma || *t == token::CloseDelim(token::Bracket)
+            })
+    }
+
+    /// Parse a subslice binding such as `rest @ ..`. It binds the same
+    /// subslice as `rest..` does.
+    fn parse_subslice_binding(&mut self) -> PResult<'a, P<Pat>> {
+        let lo = self.span;
+        let binding_mode = if self.eat_keyword(keywords::Ref) {
+            BindingMode::ByRef(self.parse_mutability())
+        } else if self.eat_keyword(keywords::Mut) {
+            BindingMode::ByValue(Mutability::Mutable)
+        } else {
+            BindingMode::ByValue(Mutability::Immutable)
+        };
+        let ident_span = self.span;
+        let ident = self.parse_ident()?;
+        let ident = codemap::Spanned { span: ident_span, node: ident };
+        self.expect(&token::At)?;
+        self.expect(&token::DotDot)?;
+        Ok(P(Pat {
+            id: ast::DUMMY_NODE_ID,
+            node: PatKind::Ident(binding_mode, ident, None),
+            span: lo.to(self.prev_span),
+        }))
+    }
+
     /// Parse the fields of a struct-like pattern
     fn parse_pat_fields(&mut self) -> PResult<'a, (Vec<codemap::Spanned<ast::FieldPat>>, bool)> {
         let mut fields = Vec::new();
@@ -3688,7 +3785,7 @@ impl<'a> Parser<'a> {
                 // Parsing a pattern of the form "fieldname: pat"
                 let fieldname = self.parse_field_name()?;
                 self.bump();
-                let pat = self.parse_pat()?;
+                let pat = self.parse_pat_with_or()?;
                 hi = pat.span;
                 (pat, fieldname, false)
             } else {
@@ -4005,6 +4102,7 @@ impl<'a> Parser<'a> {
     fn parse_local(&mut self, attrs: ThinVec<Attribute>) -> PResult<'a, P<Local>> {
         let lo = self.prev_span;
         let pat = self.parse_top_level_pat()?;
+        let pat = self.parse_or_pat_rest(pat)?;
 
         let (err, ty) = if self.eat(&token::Colon) {
             // Save the state of the parser before parsing type normally, in case there is a `:`
@@ -4226,7 +4324,7 @@ impl<'a> Parser<'a> {
                      -> PResult<'a, Option<P<Item>>> {
         let token_lo = self.span;
         let (ident, def) = match self.token {
-            token::Ident(ident) if ident.name == keywords::Macro.name() => {
+            token::Ident(ident, false) if ident.name == keywords::Macro.name() => {
                 self.bump();
                 let ident = self.parse_ident()?;
                 let tokens = if self.check(&token::OpenDelim(token::Brace)) {
@@ -4254,7 +4352,7 @@ impl<'a> Parser<'a> {
 
                 (ident, ast::MacroDef { tokens: tokens.into(), legacy: false })
             }
-            token::Ident(ident) if ident.name == "macro_rules" &&
+            token::Ident(ident, _) if ident.name == "macro_rules" &&
                                    self.look_ahead(1, |t| *t == token::Not) => {
                 let prev_span = self.prev_span;
                 self.complain_if_pub_macro(&vis.node, prev_span);
@@ -4733,6 +4831,23 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Parses a const generic parameter, e.g. `const N: usize`.
+    fn parse_const_param(&mut self, preceding_attrs: Vec<Attribute>) -> PResult<'a, ConstParam> {
+        let lo = self.span;
+        self.expect_keyword(keywords::Const)?;
+        let ident = self.parse_ident()?;
+        self.expect(&token::Colon)?;
+        let ty = self.parse_ty()?;
+
+        Ok(ConstParam {
+            attrs: preceding_attrs.into(),
+            ident,
+            id: ast::DUMMY_NODE_ID,
+            ty,
+            span: lo.to(self.prev_span),
+        })
+    }
+
     /// Parses the following grammar:
     ///     TraitItemAssocTy = Ident ["<"...">"] [":" [TyParamBounds]] ["where" ...] ["=" Ty]
     fn parse_trait_item_assoc_ty(&mut self, preceding_attrs: Vec<Attribute>)
@@ -4766,7 +4881,7 @@ impl<'a> Parser<'a> {
         }))
     }
 
-    /// Parses (possibly empty) list of lifetime and type parameters, possibly including
+    /// Parses (possibly empty) list of lifetime, type and const parameters, possibly including
     /// trailing comma and erroneous trailing attributes.
     pub fn parse_generic_params(&mut self) -> PResult<'a, Vec<ast::GenericParam>> {
         let mut params = Vec::new();
@@ -4790,6 +4905,10 @@ impl<'a> Parser<'a> {
                     self.span_err(self.prev_span,
                         "lifetime parameters must be declared prior to type parameters");
                 }
+            } else if self.check_keyword(keywords::Const) {
+                // Parse const parameter.
+                params.push(ast::GenericParam::Const(self.parse_const_param(attrs)?));
+                seen_ty_param = true;
             } else if self.check_ident() {
                 // Parse type parameter.
                 params.push(ast::GenericParam::Type(self.parse_ty_param(attrs)?));
@@ -5059,7 +5178,11 @@ impl<'a> Parser<'a> {
     fn parse_self_arg(&mut self) -> PResult<'a, Option<Arg>> {
         let expect_ident = |this: &mut Self| match this.token {
             // Preserve hygienic context.
-            token::Ident(ident) => { let sp = this.span; this.bump(); codemap::respan(sp, ident) }
+            token::Ident(ident, _) => {
+                let sp = this.span;
+                this.bump();
+                codemap::respan(sp, ident)
+            }
             _ => unreachable!()
         };
         let isolated_self = |this: &mut Self, n| {
@@ -5356,7 +5479,7 @@ impl<'a> Parser<'a> {
             VisibilityKind::Inherited => Ok(()),
             _ => {
                 let is_macro_rules: bool = match self.token {
-                    token::Ident(sid) => sid.name == Symbol::intern("macro_rules"),
+                    token::Ident(sid, _) => sid.name == Symbol::intern("macro_rules"),
                     _ => false,
                 };
                 if is_macro_rules {
@@ -5499,6 +5622,7 @@ impl<'a> Parser<'a> {
         //     `<` (LIFETIME|IDENT) `,` - first generic parameter in a list
         //     `<` (LIFETIME|IDENT) `:` - generic parameter with bounds
         //     `<` (LIFETIME|IDENT) `=` - generic parameter with a default
+        //     `<` `const` - const generic parameter
         // The only truly ambiguous case is
         //     `<` IDENT `>` `::` IDENT ...
         // we disambiguate it in favor of generics (`impl<T> ::absolute::Path<T> { ... }`)
@@ -5506,6 +5630,7 @@ impl<'a> Parser<'a> {
         // (`impl <Type>::AssocTy { ... }`) aren't even allowed by type checker at the moment.
         self.token == token::Lt &&
             (self.look_ahead(1, |t| t == &token::Pound || t == &token::Gt) ||
+             self.look_ahead(1, |t| t.is_keyword(keywords::Const)) ||
              self.look_ahead(1, |t| t.is_lifetime() || t.is_ident()) &&
                 self.look_ahead(2, |t| t == &token::Gt || t == &token::Comma ||
                                        t == &token::Colon || t == &token::Eq))
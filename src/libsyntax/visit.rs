@@ -424,6 +424,9 @@ pub fn walk_pat<'a, V: Visitor<'a>>(visitor: &mut V, pattern: &'a Pat) {
         PatKind::Tuple(ref tuple_elements, _) => {
             walk_list!(visitor, visit_pat, tuple_elements);
         }
+        PatKind::Or(ref alternatives) => {
+            walk_list!(visitor, visit_pat, alternatives);
+        }
         PatKind::Box(ref subpattern) |
         PatKind::Ref(ref subpattern, _) |
         PatKind::Paren(ref subpattern) => {
@@ -492,6 +495,11 @@ pub fn walk_generic_param<'a, V: Visitor<'a>>(visitor: &mut V, param: &'a Generi
             walk_list!(visitor, visit_ty, &t.default);
             walk_list!(visitor, visit_attribute, &*t.attrs);
         }
+        GenericParam::Const(ref c) => {
+            visitor.visit_ident(c.span, c.ident);
+            visitor.visit_ty(&c.ty);
+            walk_list!(visitor, visit_attribute, &*c.attrs);
+        }
     }
 }
 
@@ -546,6 +546,7 @@ impl<'a> TraitDef<'a> {
         params.extend(generics.params.iter().map(|param| {
             match *param {
                 ref l @ GenericParam::Lifetime(_) => l.clone(),
+                ref c @ GenericParam::Const(_) => c.clone(),
                 GenericParam::Type(ref ty_param) => {
                     // I don't think this can be moved out of the loop, since
                     // a TyParamBound requires an ast id
@@ -322,12 +322,14 @@ declare_keywords! {
     (54, Yield,          "yield")
 
     // Weak keywords, have special meaning only in specific contexts.
-    (55, Auto,           "auto")
-    (56, Catch,          "catch")
-    (57, Default,        "default")
-    (58, Dyn,            "dyn")
-    (59, StaticLifetime, "'static")
-    (60, Union,          "union")
+    (55, Async,          "async") // reserved in the 2018 epoch
+    (56, Auto,           "auto")
+    (57, Await,          "await") // reserved in the 2018 epoch
+    (58, Catch,          "catch")
+    (59, Default,        "default")
+    (60, Dyn,            "dyn")
+    (61, StaticLifetime, "'static")
+    (62, Union,          "union")
 }
 
 // If an interner exists in TLS, return it. Otherwise, prepare a fresh one.
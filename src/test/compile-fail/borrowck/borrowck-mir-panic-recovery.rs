@@ -0,0 +1,45 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that a panic in the MIR borrow checker is reported as an error
+// for the function it was checking, and that the functions after it
+// are still checked. The closure in `panics_after_closure` is checked
+// before the panic, in a nested query, which must not be poisoned: the
+// driver asks for its result again, and would otherwise stop with a
+// fatal error before checking `later`.
+
+// compile-flags:-Zborrowck=mir
+
+#![feature(rustc_attrs)]
+#![allow(warnings)]
+
+#[rustc_borrowck_panic]
+fn panics() {} //~ ERROR unexpected panic while borrow checking `panics`
+
+#[rustc_borrowck_panic]
+fn panics_after_closure() { //~ ERROR unexpected panic while borrow checking `panics_after_closure`
+    let c = || {
+        let mut x = 22;
+        let y = &mut x;
+        let z = &mut x; //~ ERROR cannot borrow `x` as mutable more than once at a time
+        *y += 1;
+    };
+    c();
+}
+
+fn later() {
+    let mut x = 22;
+    let y = &mut x;
+    let z = &mut x; //~ ERROR cannot borrow `x` as mutable more than once at a time
+    *y += 1;
+}
+
+fn main() {}
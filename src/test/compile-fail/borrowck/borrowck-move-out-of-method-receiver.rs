@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that moving out of borrowed content through the receiver of a
+// by-value method suggests borrowing instead.
+
+struct Foo {
+    opt: Option<String>,
+}
+
+impl Foo {
+    fn len(&self) -> usize {
+        self.opt.unwrap().len()
+        //~^ ERROR cannot move out of borrowed content
+        //~| HELP consider borrowing the `Option`'s content
+    }
+
+    fn push(&mut self) {
+        self.opt.unwrap().push('!')
+        //~^ ERROR cannot move out of borrowed content
+        //~| HELP consider borrowing the `Option`'s content
+    }
+}
+
+fn count(b: &Box<Vec<String>>) -> usize {
+    b.into_iter().count()
+    //~^ ERROR cannot move out of borrowed content
+    //~| HELP consider calling the method on a reborrow instead
+}
+
+fn main() {}
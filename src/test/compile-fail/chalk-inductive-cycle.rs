@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z chalk
+
+// Check that a goal which can only be proven through itself is left to
+// the regular selection, which reports the overflow.
+
+trait Foo {}
+
+struct Bar;
+
+impl Foo for Bar where Bar: Foo {}
+
+fn is_foo<T: Foo>() {}
+
+fn main() {
+    is_foo::<Bar>(); //~ ERROR overflow evaluating the requirement `Bar: Foo`
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z chalk
+
+trait Animal {}
+
+trait Loud {}
+
+struct Dog;
+
+struct Rock;
+
+impl Animal for Dog {}
+
+impl<T> Loud for T where T: Animal {}
+
+fn is_loud<T: Loud>() {}
+
+fn main() {
+    is_loud::<Dog>();
+    is_loud::<Rock>(); //~ ERROR the trait bound `Rock: Loud` is not satisfied
+}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the body of a closure whose signature is deduced from an
+// expected `Fn(&'a T)` bound is still checked against the regions of
+// that signature by lexical region checking, when the user annotates
+// the arguments and return type.
+
+fn with_ref<'a, T, F: Fn(&'a T) -> &'a T>(x: &'a T, f: F) -> &'a T {
+    f(x)
+}
+
+fn annotated_local<'a>(v: &'a Vec<u32>) -> &'a Vec<u32> {
+    with_ref(v, |_x: &'a Vec<u32>| -> &'a Vec<u32> {
+        let local = vec![];
+        &local //~ ERROR `local` does not live long enough
+    })
+}
+
+fn main() {}
@@ -25,7 +25,7 @@ trait Trait { type Item; }
 struct A<T>
     where T : Trait,
           T : Add<T::Item>
-    //~^ ERROR cyclic dependency detected
+    //~^ ERROR cycle detected
     //~| ERROR associated type `Item` not found for `T`
 {
     data: T
@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mono-instance-limit=2
+
+#![deny(excessive_monomorphization)]
+
+fn id<T>(x: T) -> T {
+//~^ ERROR generic function `id` is instantiated 3 times, more than the limit of 2
+    x
+}
+
+fn pair<T: Clone>(x: T) -> (T, T) {
+    (x.clone(), x)
+}
+
+fn main() {
+    id(1u8);
+    id(1u16);
+    id(1u32);
+
+    pair(1u8);
+    pair(1u16);
+}
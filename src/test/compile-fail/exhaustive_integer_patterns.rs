@@ -0,0 +1,91 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(exhaustive_integer_patterns)]
+#![feature(i128_type)]
+#![deny(unreachable_patterns)]
+
+fn main() {
+    let x: u8 = 0;
+
+    match x {
+        0...31 => {}
+        32...127 => {}
+        128 => {}
+        129...255 => {}
+    }
+
+    match x { //~ ERROR non-exhaustive patterns: `128...255` not covered
+        0...127 => {}
+    }
+
+    match x { //~ ERROR non-exhaustive patterns: `0` and `255` not covered
+        1...254 => {}
+    }
+
+    match x {
+        0...100 => {}
+        50...200 => {}
+        100...255 => {}
+        20...30 => {} //~ ERROR unreachable pattern
+    }
+
+    let y: i8 = 0;
+
+    match y {
+        -128...-1 => {}
+        0 => {}
+        1...127 => {}
+    }
+
+    match y { //~ ERROR non-exhaustive patterns: `0` not covered
+        -128...-1 => {}
+        1...127 => {}
+    }
+
+    match (x, y) { //~ ERROR non-exhaustive patterns: `(0, 0)` not covered
+        (1...255, _) => {}
+        (0, -128...-1) => {}
+        (0, 1...127) => {}
+    }
+
+    let z: u128 = 0;
+
+    match z {
+        0 => {}
+        1...340282366920938463463374607431768211454 => {}
+        340282366920938463463374607431768211455 => {}
+    }
+
+    let w: i64 = 0;
+
+    match w { //~ ERROR non-exhaustive patterns: `-9223372036854775808` not covered
+        -9223372036854775807...9223372036854775807 => {}
+    }
+
+    // The range of `usize` and `isize` depends on the target, so these are
+    // not exhaustive on any of them.
+    let u: usize = 0;
+
+    match u { //~ ERROR non-exhaustive patterns: `_` not covered
+        0...std::usize::MAX => {}
+    }
+
+    let i: isize = 0;
+
+    match i { //~ ERROR non-exhaustive patterns: `_` not covered
+        std::isize::MIN...std::isize::MAX => {}
+    }
+
+    match u {
+        0...std::usize::MAX => {}
+        _ => {}
+    }
+}
@@ -1,4 +1,4 @@
-// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
 // file at the top-level directory of this distribution and at
 // http://rust-lang.org/COPYRIGHT.
 //
@@ -8,13 +8,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-struct Foo<'a>(&'a u8);
+enum Void {}
 
-fn foo(x: &u8) -> Foo<'_> { //~ ERROR underscore lifetimes are unstable
-    Foo(x)
+fn foo() -> Result<u32, Void> {
+    Ok(12)
 }
 
 fn main() {
-    let x = 5;
-    let _ = foo(&x);
+    let Ok(_x) = foo(); //~ ERROR refutable pattern in local binding
 }
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that the type of a `let` binding with an `impl Trait` in it
+// stays opaque: only the initializer sees the hidden type.
+
+#![feature(impl_trait_in_bindings)]
+
+use std::fmt::Debug;
+
+fn main() {
+    let x: impl Debug + Copy = 22u32;
+    let y: u32 = x;
+    //~^ ERROR mismatched types
+
+    let v: impl Debug = vec![1, 2, 3];
+    v.push(4);
+    //~^ ERROR no method named `push` found
+}
@@ -13,7 +13,7 @@ pub trait Subscriber {
 }
 
 pub trait Processor: Subscriber<Input = Self::Input> {
-    //~^ ERROR cyclic dependency detected [E0391]
+    //~^ ERROR cycle detected [E0391]
     type Input;
 }
 
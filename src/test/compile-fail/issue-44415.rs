@@ -15,7 +15,7 @@ use std::intrinsics;
 
 struct Foo {
     bytes: [u8; unsafe { intrinsics::size_of::<Foo>() }],
-    //~^ ERROR cyclic dependency detected
+    //~^ ERROR cycle detected
     x: usize,
 }
 
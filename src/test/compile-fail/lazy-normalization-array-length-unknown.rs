@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a lazily normalized array length which depends on generic
+// parameters is not assumed to be evaluatable outside of the signatures
+// it is in.
+
+#![feature(lazy_normalization_consts)]
+
+use std::mem;
+
+trait Foo {
+    const SIZE: usize;
+}
+
+struct Buffer<T: Foo> {
+    bytes: [u8; T::SIZE],
+}
+
+fn size_of_buffer<T: Foo>() -> usize {
+    mem::size_of::<Buffer<T>>() //~ ERROR type annotations required: cannot resolve
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// revisions: ast mir
+//[mir] compile-flags: -Z borrowck=mir -Z nll
+
+#![feature(match_default_bindings)]
+
+fn main() {
+    let mut opt = Some(String::new());
+    match &mut opt {
+        Some(s) => {
+            // `s` is an implicit `ref mut` binding, so `opt` stays borrowed
+            // until its last use below.
+            opt = None; //[ast]~ ERROR cannot assign to `opt` because it is borrowed [E0506]
+            //[mir]~^ ERROR cannot assign to `opt` because it is borrowed [E0506]
+            s.push('a');
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that NLL requires the regions in the hidden type of an
+// `impl Trait` in a `let` binding to outlive the bounds of the
+// `impl Trait`, as it does for `impl Trait` in return position.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+#![feature(impl_trait_in_bindings)]
+
+use std::fmt::Debug;
+
+fn escapes<'a>(x: &'a u32) {
+    let y: impl Debug + 'static = x;
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR does not outlive
+}
+
+fn ok<'a>(x: &'a u32) {
+    let y: impl Debug + 'a = x;
+}
+
+fn main() {}
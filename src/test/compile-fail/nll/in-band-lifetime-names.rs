@@ -0,0 +1,22 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that NLL names in-band lifetimes in region errors.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(in_band_lifetimes)]
+#![allow(warnings)]
+
+fn foo(x: &'a u32) -> &'static u32 { x }
+//~^ ERROR free region `'a` does not outlive free region `'static`
+
+fn main() {}
@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the MIR type check, which normalizes `<u32 as Tr<'_>>::Out`
+// once for both calls, still uses the regions of each call.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+trait Tr<'x> {
+    type Out;
+}
+
+impl<'x> Tr<'x> for u32 {
+    type Out = &'x u32;
+}
+
+fn id_out<'x>(v: <u32 as Tr<'x>>::Out) -> <u32 as Tr<'x>>::Out {
+    v
+}
+
+fn both<'a, 'b>(x: &'a u32, y: &'b u32) -> &'a u32 {
+    id_out(x);
+    id_out(y)
+    //~^ ERROR free region `'b` does not outlive free region `'a`
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that NLL points out a trait object whose lifetime bound
+// defaults to `'static` when that causes a region error, but not the
+// ones that have nothing to do with the error.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+trait SomeTrait {}
+
+fn load<'a>(r: Box<SomeTrait + 'a>) -> Box<SomeTrait> { r }
+//~^ ERROR free region `'a` does not outlive free region `'static`
+//~| NOTE the lifetime bound of this trait object defaults to `'static`
+
+fn load_first<'a>(r: Box<SomeTrait + 'a>, _unused: Box<SomeTrait>) -> Box<SomeTrait> {
+    r //~ ERROR free region `'a` does not outlive free region `'static`
+}
+//~^^^ NOTE the lifetime bound of this trait object defaults to `'static`
+
+fn main() {}
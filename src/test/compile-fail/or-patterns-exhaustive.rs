@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(or_patterns, pattern_parentheses)]
+#![deny(unreachable_patterns)]
+
+fn exhaustive(x: (bool, bool)) {
+    match x {
+    //~^ ERROR non-exhaustive patterns: `(true, false)` not covered
+        (true | false, true) | (false, false) => {}
+    }
+    match x {
+        (true | false, true | false) => {}
+    }
+}
+
+fn unreachable(x: Option<u8>) {
+    match x {
+        Some(0 | 1) => {}
+        Some(1 | 2) => {}
+        Some(0 | 1 | 2) => {}
+        //~^ ERROR unreachable pattern
+        _ => {}
+    }
+}
+
+fn main() {
+    let (Some(0) | None) = Some(1u8);
+    //~^ ERROR refutable pattern in local binding
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(or_patterns, pattern_parentheses)]
+
+enum E {
+    A(u8),
+    B(u8),
+    C,
+}
+
+fn bindings(e: Option<E>) {
+    match e {
+        Some(E::A(x) | E::C) => {}
+        //~^ ERROR variable `x` is not bound in all patterns
+        Some(E::A(ref y) | E::B(y)) => {}
+        //~^ ERROR variable `y` is bound in inconsistent ways within the same match arm
+        _ => {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Calls to `const fn`s and reads of union fields are not implicitly
+// promoted to `'static` inside of function bodies.
+
+// gate-test-restricted_promotion
+
+#![feature(const_fn, restricted_promotion)]
+
+const fn foo() -> u32 {
+    42
+}
+
+union Foo {
+    a: u32,
+    b: f32,
+}
+
+const FOO: Foo = Foo { a: 1 };
+
+fn main() {
+    let x: &'static u32 = &foo(); //~ ERROR does not live long enough
+    let y: &'static u32 = unsafe { &FOO.a }; //~ ERROR does not live long enough
+}
@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test the unsafe operations recorded for each function, including those
+// in its closures and those within unsafe blocks.
+
+#![feature(rustc_attrs)]
+
+static mut COUNTER: u32 = 0;
+
+unsafe fn bump() {}
+
+#[rustc_unsafe_operations]
+fn none() {} //~ ERROR unsafe operations: []
+
+#[rustc_unsafe_operations]
+fn deref(p: *const u32) -> u32 { //~ ERROR unsafe operations: [dereference of raw pointer]
+    unsafe { *p }
+}
+
+#[rustc_unsafe_operations]
+fn in_closure(p: *const u32) -> u32 {
+//~^ ERROR [call to unsafe function, dereference of raw pointer, use of mutable static]
+    let get = || unsafe { bump(); *p };
+    unsafe { COUNTER += get(); COUNTER }
+}
+
+fn main() {}
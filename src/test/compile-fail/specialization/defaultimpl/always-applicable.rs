@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that impls specializing a `default impl` must be "always applicable",
+// i.e. they may not constrain lifetimes.
+
+#![feature(specialization)]
+
+trait Foo {}
+
+default impl<T> Foo for T {}
+
+impl Foo for &'static str {}
+//~^ ERROR cannot specialize on `'static` lifetime
+
+trait Bar {}
+
+default impl<A, B> Bar for (A, B) {}
+
+impl<T> Bar for (T, T) {}
+//~^ ERROR specializing impl repeats parameter `T`
+
+impl<'a> Bar for (&'a u8, &'a u16) {}
+//~^ ERROR specializing impl repeats parameter `'a`
+
+trait Baz {}
+
+default impl<T> Baz for Vec<T> {}
+
+impl<'a, T: 'a> Baz for Vec<&'a T> {}
+//~^ ERROR cannot specialize on predicate
+
+fn main() {}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(trait_alias)]
+
+use std::fmt::{Debug, Display};
+
+trait DebugDisplay = Debug + Display;
+trait Outlives = Debug + 'static;
+trait NoSelf = where u8: Copy;
+
+fn main() {
+    let _: Box<DebugDisplay>;
+    //~^ ERROR only auto traits can be used as additional traits in a trait object
+    let _: Box<Outlives>;
+    //~^ ERROR trait alias `Outlives` cannot be used as a trait object
+    let _: Box<NoSelf>;
+    //~^ ERROR trait alias `NoSelf` cannot be used as a trait object
+}
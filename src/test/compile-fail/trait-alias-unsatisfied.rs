@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(trait_alias)]
+
+trait CloneDefault = Clone + Default;
+
+#[derive(Default)]
+struct NoClone;
+
+fn pair<T: CloneDefault>() -> (T, T) {
+    let one = T::default();
+    (one.clone(), one)
+}
+
+fn main() {
+    pair::<NoClone>();
+    //~^ ERROR the trait bound `NoClone: std::clone::Clone` is not satisfied
+}
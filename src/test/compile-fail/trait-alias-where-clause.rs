@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that using a trait alias requires the where-clauses on its
+// parameters, and not only its bounds on `Self`.
+
+#![feature(trait_alias)]
+
+use std::rc::Rc;
+
+trait CloneSendWith<T> = Clone where T: Send;
+
+fn send_u8<S: CloneSendWith<u8>>() {}
+
+fn send_rc<S: CloneSendWith<Rc<u8>>>() {}
+//~^ ERROR `std::rc::Rc<u8>` cannot be sent between threads safely
+
+fn main() {
+    send_u8::<u8>();
+}
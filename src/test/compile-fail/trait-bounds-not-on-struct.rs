@@ -9,7 +9,7 @@
 // except according to those terms.
 
 #![feature(dyn_trait)]
-#![allow(bare_trait_object)]
+#![allow(bare_trait_objects)]
 
 struct Foo;
 
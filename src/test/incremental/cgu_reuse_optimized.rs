@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the object code of unchanged codegen units is re-used in
+// optimized builds, where it's the output of the LLVM optimizations.
+
+// revisions:cfail1 cfail2
+// compile-flags: -Z query-dep-graph -O
+// must-compile-successfully
+
+#![feature(rustc_attrs)]
+#![feature(stmt_expr_attributes)]
+#![crate_type = "rlib"]
+
+#![rustc_partition_translated(module="cgu_reuse_optimized-changed", cfg="cfail2")]
+#![rustc_partition_reused(module="cgu_reuse_optimized-unchanged", cfg="cfail2")]
+
+pub mod changed {
+    fn private_fn(x: u32) -> u32 {
+        #[cfg(cfail1)]
+        return x + 1;
+
+        #[cfg(cfail2)]
+        return x + 2;
+    }
+
+    pub fn public_fn(x: u32) -> u32 {
+        private_fn(x) * 2
+    }
+}
+
+pub mod unchanged {
+    pub fn public_fn(x: u32) -> u32 {
+        x.wrapping_mul(3)
+    }
+}
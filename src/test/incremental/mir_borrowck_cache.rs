@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the results of the MIR borrow checker are reused for bodies
+// which didn't change, and recomputed for the ones which did.
+
+// revisions: rpass1 rpass2
+// compile-flags: -Z query-dep-graph -Z borrowck=mir
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() {
+    changed();
+    unchanged();
+}
+
+#[cfg(rpass1)]
+fn changed() -> u32 {
+    let mut x = 1;
+    x += 1;
+    x
+}
+
+#[cfg(rpass2)]
+#[rustc_dirty(label="MirBorrowCheck", cfg="rpass2")]
+fn changed() -> u32 {
+    let mut x = 2;
+    let y = &mut x;
+    *y += 1;
+    x
+}
+
+#[rustc_clean(label="MirBorrowCheck", cfg="rpass2")]
+fn unchanged() -> u32 {
+    let mut v = vec![1, 2, 3];
+    v.push(4);
+    v.len() as u32
+}
@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-opt-level=2
+
+fn pick(x: u8) -> u8 {
+    match x {
+        0 => 7,
+        1 => 8,
+        _ => 7,
+    }
+}
+
+fn main() {
+    pick(0);
+}
+
+// END RUST SOURCE
+// START rustc.pick.SimplifyCfg-initial.after.mir
+// ...
+//     _0 = const 7u8;
+// ...
+//     _0 = const 7u8;
+// ...
+// END rustc.pick.SimplifyCfg-initial.after.mir
+// START rustc.pick.DeduplicateBlocks.after.mir
+// bb0: {
+//     switchInt(_1) -> [0u8: bb1, 1u8: bb2, otherwise: bb1];
+// }
+// bb1: {
+//     _0 = const 7u8;
+// ...
+// END rustc.pick.DeduplicateBlocks.after.mir
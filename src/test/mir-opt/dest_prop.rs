@@ -0,0 +1,49 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-opt-level=2
+
+#[inline(never)]
+fn fill(byte: u8) -> [u8; 1024] {
+    [byte; 1024]
+}
+
+fn make(byte: u8) -> [u8; 1024] {
+    let buf = fill(byte);
+    buf
+}
+
+fn main() {
+    make(0);
+}
+
+// END RUST SOURCE
+// START rustc.make.DestinationPropagation.before.mir
+//  bb0: {
+//      ...
+//      _2 = const fill(move _3) -> bb1;
+//  }
+//  bb1: {
+//      ...
+//      _0 = _2;
+//      ...
+//      return;
+//  }
+// END rustc.make.DestinationPropagation.before.mir
+// START rustc.make.DestinationPropagation.after.mir
+//  bb0: {
+//      ...
+//      _0 = const fill(move _3) -> bb1;
+//  }
+//  bb1: {
+//      ...
+//      return;
+//  }
+// END rustc.make.DestinationPropagation.after.mir
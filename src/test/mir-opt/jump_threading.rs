@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-opt-level=2
+
+fn unwrap_known(x: u32) -> u32 {
+    let o = Some(x);
+    match o {
+        Some(v) => v,
+        None => 0,
+    }
+}
+
+fn main() {
+    unwrap_known(0);
+}
+
+// END RUST SOURCE
+// START rustc.unwrap_known.JumpThreading.before.mir
+//  bb0: {
+//      ...
+//      discriminant(_2) = 1;
+//      ...
+//      _4 = discriminant(_2);
+//      switchInt(move _4) -> [0isize: bb2, 1isize: bb3, otherwise: bb1];
+//  }
+// END rustc.unwrap_known.JumpThreading.before.mir
+// START rustc.unwrap_known.JumpThreading.after.mir
+//  bb0: {
+//      ...
+//      discriminant(_2) = 1;
+//      ...
+//      _4 = discriminant(_2);
+//      goto -> bb1;
+//  }
+// END rustc.unwrap_known.JumpThreading.after.mir
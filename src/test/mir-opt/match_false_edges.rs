@@ -38,6 +38,20 @@ pub fn full_tested_match2() {
     };
 }
 
+// no_mangle to make sure this gets instantiated even in an executable.
+#[no_mangle]
+pub fn guard_in_loop() {
+    // A failed guard ends the storage of `x`, which the next iteration marks
+    // live again.
+    loop {
+        let _ = match Some(42) {
+            Some(x) if guard() => (1, x),
+            Some(y) => (2, y),
+            None => (3, 3),
+        };
+    }
+}
+
 fn main() {
     let _ = match Some(1) {
         Some(_w) if guard() => 1,
@@ -96,6 +110,8 @@ fn main() {
 //      switchInt(move _7) -> [false: bb11, otherwise: bb2];
 //  }
 //  bb11: { // to pre_binding2
+//      StorageDead(_7);
+//      StorageDead(_4);
 //      falseEdges -> [real: bb5, imaginary: bb5];
 //  }
 //  bb12: { // bindingNoLandingPads.before.mir2 and arm2
@@ -160,6 +176,8 @@ fn main() {
 //      switchInt(move _7) -> [false: bb11, otherwise: bb2];
 //  }
 //  bb11: { // to pre_binding2
+//      StorageDead(_7);
+//      StorageDead(_4);
 //      falseEdges -> [real: bb6, imaginary: bb5];
 //  }
 //  bb12: { // binding2 and arm2
@@ -222,6 +240,8 @@ fn main() {
 //    switchInt(move _9) -> [false: bb11, otherwise: bb2];
 // }
 // bb11: { // to pre_binding2
+//     StorageDead(_9);
+//     StorageDead(_4);
 //     falseEdges -> [real: bb5, imaginary: bb5];
 // }
 // bb12: { // binding2 & arm2
@@ -243,6 +263,8 @@ fn main() {
 //     switchInt(move _11) -> [false: bb15, otherwise: bb3];
 // }
 // bb15: { // to pre_binding4
+//     StorageDead(_11);
+//     StorageDead(_6);
 //     falseEdges -> [real: bb7, imaginary: bb7];
 // }
 // bb16: { // binding4 & arm4
@@ -256,3 +278,13 @@ fn main() {
 //     return;
 // }
 // END rustc.main.QualifyAndPromoteConstants.before.mir
+// START rustc.guard_in_loop.QualifyAndPromoteConstants.after.mir
+// ...
+//      StorageLive(_5);
+//      _5 = ((_3 as Some).0: i32);
+//      StorageLive(_8);
+// ...
+//      StorageDead(_8);
+//      StorageDead(_5);
+// ...
+// END rustc.guard_in_loop.QualifyAndPromoteConstants.after.mir
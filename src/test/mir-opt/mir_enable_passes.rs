@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `-Z mir-enable-passes` can disable a pass by its base name,
+// which disables every labelled instance of it.
+
+// compile-flags: -Z mir-enable-passes=-SimplifyBranches
+
+fn main() {
+    if false {
+        println!("hello world!");
+    }
+}
+
+// END RUST SOURCE
+// START rustc.main.SimplifyCfg-early-opt.before.mir
+// bb0: {
+//     switchInt(const false) -> [false: bb3, otherwise: bb2];
+// }
+// END rustc.main.SimplifyCfg-early-opt.before.mir
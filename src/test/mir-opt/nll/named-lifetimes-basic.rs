@@ -27,11 +27,11 @@ fn main() {
 // END RUST SOURCE
 // START rustc.use_x.nll.0.mir
 // | Free Region Mapping
-// | '_#0r    | Global   | ['_#2r, '_#1r, '_#0r, '_#4r, '_#3r]
-// | '_#1r    | External | ['_#1r, '_#4r]
-// | '_#2r    | External | ['_#2r, '_#1r, '_#4r]
-// | '_#3r    | Local    | ['_#4r, '_#3r]
-// | '_#4r    | Local    | ['_#4r]
+// | '_#0r    | Global   | 'static  | ['_#2r, '_#1r, '_#0r, '_#4r, '_#3r]
+// | '_#1r    | External | 'a       | ['_#1r, '_#4r]
+// | '_#2r    | External | 'b       | ['_#2r, '_#1r, '_#4r]
+// | '_#3r    | Local    | 'c       | ['_#4r, '_#3r]
+// | '_#4r    | Local    |          | ['_#4r]
 // |
 // | Inferred Region Values
 // | '_#0r    | {'_#0r, bb0[0..=1]}
@@ -47,3 +47,11 @@ fn main() {
 // ...
 // fn use_x(_1: &'_#5r mut i32, _2: &'_#6r u32, _3: &'_#7r u32, _4: &'_#8r u32) -> bool {
 // END rustc.use_x.nll.0.mir
+// START rustc.use_x.nll.0.mir
+// | Region Origins
+// | '_#0r    | NLL(FreeRegion)
+// | '_#1r    | NLL(FreeRegion)
+// | '_#2r    | NLL(FreeRegion)
+// | '_#3r    | NLL(FreeRegion)
+// | '_#4r    | NLL(FreeRegion)
+// END rustc.use_x.nll.0.mir
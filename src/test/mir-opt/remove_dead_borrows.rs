@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-flags: -Z mir-opt-level=2
+
+fn main() {
+    let x = 0;
+    let _ = &x;
+}
+
+// END RUST SOURCE
+// START rustc.main.RemoveDeadBorrows.before.mir
+//  bb0: {
+//      StorageLive(_1);
+//      _1 = const 0i32;
+//      StorageLive(_2);
+//      _2 = &_1;
+//      ...
+//      return;
+//  }
+// END rustc.main.RemoveDeadBorrows.before.mir
+// START rustc.main.RemoveDeadBorrows.after.mir
+//  bb0: {
+//      StorageLive(_1);
+//      _1 = const 0i32;
+//      nop;
+//      nop;
+//      ...
+//      return;
+//  }
+// END rustc.main.RemoveDeadBorrows.after.mir
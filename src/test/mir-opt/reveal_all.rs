@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `RevealAll` replaces `impl Trait` types by the types they hide.
+
+#![feature(conservative_impl_trait)]
+
+fn foo() -> impl Copy {
+    0u8
+}
+
+fn main() {
+    let _x = foo();
+}
+
+// END RUST SOURCE
+// START rustc.main.RevealAll.before.mir
+// fn main() -> () {
+//     ...
+//     let _1: impl std::marker::Copy;
+//     ...
+// }
+// END rustc.main.RevealAll.before.mir
+// START rustc.main.RevealAll.after.mir
+// fn main() -> () {
+//     ...
+//     let _1: u8;
+//     ...
+// }
+// END rustc.main.RevealAll.after.mir
@@ -0,0 +1,69 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-opt-level=2
+
+fn id(r: Result<u32, i32>) -> Result<u32, i32> {
+    match r {
+        Ok(x) => Ok(x),
+        Err(e) => Err(e),
+    }
+}
+
+fn id_move(r: Result<Box<u32>, String>) -> Result<Box<u32>, String> {
+    match r {
+        Ok(x) => Ok(x),
+        Err(e) => Err(e),
+    }
+}
+
+fn id_mixed(r: Result<u32, String>) -> Result<u32, String> {
+    match r {
+        Ok(x) => Ok(x),
+        Err(e) => Err(e),
+    }
+}
+
+fn main() {
+    id(Ok(4));
+    id_move(Ok(Box::new(4)));
+    id_mixed(Ok(4));
+}
+
+// END RUST SOURCE
+// START rustc.id.SimplifyArmIdentity.after.mir
+// ...
+//     _0 = _1;
+// ...
+//     _0 = _1;
+// ...
+// END rustc.id.SimplifyArmIdentity.after.mir
+// START rustc.id.SimplifyBranchSame.after.mir
+// bb0: {
+// ...
+//     _0 = _1;
+// ...
+//     return;
+// }
+// END rustc.id.SimplifyBranchSame.after.mir
+// START rustc.id_move.SimplifyArmIdentity.after.mir
+// ...
+//     _0 = move _1;
+// ...
+//     _0 = move _1;
+// ...
+// END rustc.id_move.SimplifyArmIdentity.after.mir
+// START rustc.id_mixed.SimplifyArmIdentity.after.mir
+// ...
+//     _0 = move _1;
+// ...
+//     _0 = move _1;
+// ...
+// END rustc.id_mixed.SimplifyArmIdentity.after.mir
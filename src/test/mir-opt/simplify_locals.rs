@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn main() {
+    let x = (1, 2);
+    let _y = x.0;
+}
+
+// END RUST SOURCE
+// START rustc.main.SimplifyLocals.before.mir
+// let mut _0: ();
+// let _1: (i32, i32);
+// ...
+// END rustc.main.SimplifyLocals.before.mir
+// START rustc.main.SimplifyLocals.after.mir
+// let mut _0: ();
+// ...
+// bb0: {
+//     _0 = ();
+//     return;
+// }
+// END rustc.main.SimplifyLocals.after.mir
@@ -0,0 +1,18 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-emit-alignment-checks
+// error-pattern:misaligned pointer dereference: address must be a multiple of 0x4
+
+fn main() {
+    let x = [0u32; 2];
+    let ptr = (&x as *const [u32; 2] as *const u8).wrapping_offset(1) as *const u32;
+    let _y = unsafe { *ptr };
+}
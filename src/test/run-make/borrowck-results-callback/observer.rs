@@ -0,0 +1,89 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// A minimal tool that observes the results of the MIR borrow checker
+// through the `after_borrowck` callback of the compile controller.
+
+#![feature(box_syntax, rustc_private)]
+
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_errors;
+extern crate rustc_lint;
+extern crate rustc_metadata;
+extern crate rustc_trans_utils;
+extern crate syntax;
+
+use rustc::session::build_session;
+use rustc::session::config::{basic_options, BorrowckMode, Input, OutputType, OutputTypes};
+use rustc_driver::Compilation;
+use rustc_driver::driver::{compile_input, CompileController};
+use rustc_errors::registry::Registry;
+use rustc_metadata::cstore::CStore;
+use syntax::codemap::FileName;
+
+use std::path::PathBuf;
+use std::rc::Rc;
+
+fn main() {
+    let src = r#"
+    fn foo() {
+        let mut x = 22;
+        x += 1;
+    }
+
+    fn main() {
+        foo();
+    }
+    "#;
+
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 {
+        panic!("expected rustc path");
+    }
+
+    let tmpdir = PathBuf::from(&args[1]);
+
+    let mut sysroot = PathBuf::from(&args[2]);
+    sysroot.pop();
+    sysroot.pop();
+
+    let mut opts = basic_options();
+    opts.output_types = OutputTypes::new(&[(OutputType::Metadata, None)]);
+    opts.maybe_sysroot = Some(sysroot);
+    opts.borrowck_mode = BorrowckMode::Mir;
+
+    let descriptions = Registry::new(&rustc::DIAGNOSTICS);
+    let sess = build_session(opts, None, descriptions);
+    let trans = rustc_driver::get_codegen_backend(&sess);
+    let cstore = Rc::new(CStore::new(trans.metadata_loader()));
+    rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+
+    let mut control = CompileController::basic();
+    control.after_analysis.stop = Compilation::Stop;
+    control.after_borrowck = Some(box |tcx, def_id, result| {
+        println!("{}: used mut {:?}", tcx.item_path_str(def_id), result.used_mut_locals);
+    });
+
+    let input = Input::Str { name: FileName::Anon, input: src.to_string() };
+    let _ = compile_input(
+        trans,
+        &sess,
+        &cstore,
+        &None,
+        &input,
+        &None,
+        &Some(tmpdir.join("out")),
+        None,
+        &control
+    );
+}
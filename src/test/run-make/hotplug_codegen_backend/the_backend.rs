@@ -23,11 +23,11 @@ use rustc::ty::TyCtxt;
 use rustc::ty::maps::Providers;
 use rustc::middle::cstore::MetadataLoader;
 use rustc::dep_graph::DepGraph;
-use rustc_trans_utils::trans_crate::{TransCrate, MetadataOnlyTransCrate};
+use rustc_trans_utils::codegen_backend::{CodegenBackend, MetadataOnlyCodegenBackend};
 
-struct TheBackend(Box<TransCrate>);
+struct TheBackend(Box<CodegenBackend>);
 
-impl TransCrate for TheBackend {
+impl CodegenBackend for TheBackend {
     fn metadata_loader(&self) -> Box<MetadataLoader> {
         self.0.metadata_loader()
     }
@@ -77,6 +77,6 @@ impl TransCrate for TheBackend {
 
 /// This is the entrypoint for a hot plugged rustc_trans
 #[no_mangle]
-pub fn __rustc_codegen_backend() -> Box<TransCrate> {
-    Box::new(TheBackend(MetadataOnlyTransCrate::new()))
+pub fn __rustc_codegen_backend() -> Box<CodegenBackend> {
+    Box::new(TheBackend(MetadataOnlyCodegenBackend::new()))
 }
@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+pub fn unchanged() -> u32 {
+    let mut v = vec![1, 2, 3];
+    let mut push = |x| v.push(x);
+    push(4);
+    v.len() as u32
+}
+
+#[cfg(first)]
+pub fn changed() -> u32 {
+    let mut x = 1;
+    x += 1;
+    x
+}
+
+#[cfg(second)]
+pub fn changed() -> u32 {
+    let mut x = 2;
+    let y = &mut x;
+    *y += 1;
+    x
+}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub mod unchanged {
+    pub fn unchanged(x: u32) -> u32 {
+        x.wrapping_mul(3)
+    }
+}
+
+pub mod changed {
+    #[cfg(first)]
+    pub fn changed(x: u32) -> u32 {
+        x + 1
+    }
+
+    #[cfg(second)]
+    pub fn changed(x: u32) -> u32 {
+        x + 2
+    }
+}
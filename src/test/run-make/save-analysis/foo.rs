@@ -465,3 +465,11 @@ trait Foo {
 
 #[doc(include="extra-docs.md")]
 struct StructWithDocs;
+
+fn longest<'a, 'b: 'a>(x: &'a str, y: &'b str) -> &'a str {
+    if x.len() > y.len() { x } else { y }
+}
+
+fn first_word(s: &str) -> &str {
+    s.split(' ').next().unwrap_or(s)
+}
@@ -0,0 +1,41 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+import sys
+
+PHASES = [
+    "MIR renumbering",
+    "NLL liveness",
+    "NLL type check",
+    "NLL constraint propagation",
+    "NLL type tests",
+    "NLL universal region checks",
+    "MIR borrowck error reporting",
+]
+
+# Each line is `<indentation>time: <secs>[; rss: <n>MB]\t<pass>`
+passes = []
+for line in sys.stdin:
+    if "\t" not in line:
+        continue
+    timing, name = line.rstrip("\n").split("\t", 1)
+    passes.append((len(timing) - len(timing.lstrip(" ")), name))
+
+names = [name for (_, name) in passes]
+assert names.count("MIR borrow checking") == 1, names
+parent = names.index("MIR borrow checking")
+assert parent >= len(PHASES), names
+
+# The phases come right before their parent, one level deeper.
+(parent_indentation, _) = passes[parent]
+assert passes[parent - len(PHASES):parent] == \
+    [(parent_indentation + 2, phase) for phase in PHASES], passes
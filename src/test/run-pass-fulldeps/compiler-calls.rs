@@ -26,7 +26,7 @@ use rustc::middle::cstore::CrateStore;
 use rustc::session::Session;
 use rustc::session::config::{self, Input};
 use rustc_driver::{driver, CompilerCalls, Compilation};
-use rustc_trans_utils::trans_crate::TransCrate;
+use rustc_trans_utils::codegen_backend::CodegenBackend;
 use syntax::ast;
 
 use std::path::PathBuf;
@@ -48,7 +48,7 @@ impl<'a> CompilerCalls<'a> for TestCalls {
     }
 
     fn late_callback(&mut self,
-                     _: &TransCrate,
+                     _: &CodegenBackend,
                      _: &getopts::Matches,
                      _: &Session,
                      _: &CrateStore,
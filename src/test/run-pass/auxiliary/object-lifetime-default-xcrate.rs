@@ -0,0 +1,24 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The type parameters of a trait and of a struct whose object lifetime
+// default is `'a`.
+
+pub trait Wrap<'a, T: ?Sized + 'a> {
+    fn wrap(&self, t: &'a T) -> &'a T {
+        t
+    }
+}
+
+pub struct Unit;
+
+impl<'a, T: ?Sized + 'a> Wrap<'a, T> for Unit {}
+
+pub struct Ref<'a, T: ?Sized + 'a>(pub &'a T);
@@ -0,0 +1,54 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z chalk
+
+// Check that the Chalk-style solver proves goals through impls,
+// generic impls with where-clauses and where-clauses in scope.
+
+trait Animal {
+    fn name(&self) -> String;
+}
+
+trait Loud {
+    fn shout(&self) -> String;
+}
+
+struct Dog;
+
+struct Pair<T>(T, T);
+
+impl Animal for Dog {
+    fn name(&self) -> String {
+        "dog".to_string()
+    }
+}
+
+impl<T: Animal> Animal for Pair<T> {
+    fn name(&self) -> String {
+        format!("{} and {}", self.0.name(), self.1.name())
+    }
+}
+
+impl<T> Loud for T where T: Animal {
+    fn shout(&self) -> String {
+        self.name().to_uppercase()
+    }
+}
+
+fn shout_twice<T: Loud>(t: &T) -> String {
+    format!("{} {}", t.shout(), t.shout())
+}
+
+fn main() {
+    assert_eq!(Dog.shout(), "DOG");
+    assert_eq!(Pair(Dog, Dog).shout(), "DOG AND DOG");
+    assert_eq!(shout_twice(&Pair(Pair(Dog, Dog), Dog)), "DOG AND DOG AND DOG DOG AND DOG AND DOG");
+}
@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-pretty `dyn ::foo` parses differently in the current epoch
+
+#![feature(dyn_trait)]
+
+use std::fmt::Display;
+
+// Auto traits and the lifetime bound can come in any order, and
+// repeated auto traits don't change the object type.
+
+fn reorder<'a>(x: Box<dyn Send + Display + 'a>) -> Box<dyn Display + Send + 'a> {
+    x
+}
+
+fn dedup<'a>(x: Box<dyn Display + Send + Sync + Send + 'a>) -> Box<dyn Sync + Display + Send + 'a> {
+    x
+}
+
+fn lifetime_first<'a>(x: &'a (dyn 'a + Sync + Display)) -> &'a (dyn Display + Sync + 'a) {
+    x
+}
+
+fn main() {
+    let x: Box<dyn Send + Display> = Box::new(33u8);
+    assert_eq!(format!("{}", reorder(x)), "33");
+
+    let y: Box<dyn Send + Sync + Display> = Box::new(34u8);
+    assert_eq!(format!("{}", dedup(y)), "34");
+
+    assert_eq!(format!("{}", lifetime_first(&35u8)), "35");
+}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(exhaustive_patterns)]
+
+enum Void {}
+
+fn unwrap<T>(result: Result<T, Void>) -> T {
+    let Ok(value) = result;
+    value
+}
+
+fn main() {
+    assert_eq!(unwrap(Ok::<_, Void>(12)), 12);
+
+    let x: Option<Void> = None;
+    match x {
+        None => {}
+    }
+}
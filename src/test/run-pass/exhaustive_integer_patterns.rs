@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(exhaustive_integer_patterns)]
+
+fn classify(x: u8) -> &'static str {
+    match x {
+        0 => "zero",
+        1...9 => "digit",
+        10...99 => "two digits",
+        100...255 => "three digits",
+    }
+}
+
+fn sign(x: i16) -> i8 {
+    match x {
+        -32768...-1 => -1,
+        0 => 0,
+        1...32767 => 1,
+    }
+}
+
+fn main() {
+    assert_eq!(classify(0), "zero");
+    assert_eq!(classify(7), "digit");
+    assert_eq!(classify(42), "two digits");
+    assert_eq!(classify(255), "three digits");
+    assert_eq!(sign(-300), -1);
+    assert_eq!(sign(0), 0);
+    assert_eq!(sign(i16::max_value()), 1);
+}
@@ -0,0 +1,77 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Locals whose storage is never live at the same time should share a
+// single slot in the generator, whatever their types.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+
+fn main() {
+    let mut gen = || {
+        {
+            let a = [1u8; 1024];
+            yield;
+            assert_eq!(a[0], 1);
+        }
+        {
+            let b = [2u8; 1024];
+            yield;
+            assert_eq!(b[1023], 2);
+        }
+    };
+
+    assert!(std::mem::size_of_val(&gen) < 2048);
+
+    match gen.resume() {
+        GeneratorState::Yielded(()) => {}
+        s => panic!("bad state: {:?}", s),
+    }
+    match gen.resume() {
+        GeneratorState::Yielded(()) => {}
+        s => panic!("bad state: {:?}", s),
+    }
+    match gen.resume() {
+        GeneratorState::Complete(()) => {}
+        s => panic!("bad state: {:?}", s),
+    }
+
+    let mut gen = || {
+        {
+            let a = [1u8; 1025];
+            yield;
+            assert_eq!(a[1024], 1);
+        }
+        {
+            let b = [2u64; 128];
+            yield;
+            assert_eq!(b[127], 2);
+        }
+        {
+            let c = vec![String::from("c"); 16];
+            yield;
+            assert_eq!(c[15], "c");
+        }
+    };
+
+    assert!(std::mem::size_of_val(&gen) < 2048);
+
+    for _ in 0..3 {
+        match gen.resume() {
+            GeneratorState::Yielded(()) => {}
+            s => panic!("bad state: {:?}", s),
+        }
+    }
+    match gen.resume() {
+        GeneratorState::Complete(()) => {}
+        s => panic!("bad state: {:?}", s),
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// ignore-emscripten i128 doesn't work
+
+// Checks that 128-bit arithmetic agrees between const evaluation and runtime,
+// both natively and when the operators are lowered to compiler-builtins calls.
+
+// revisions: native lowered
+//[lowered] compile-flags: -Z lower_128bit_ops=yes
+
+#![feature(i128_type, test)]
+
+extern crate test;
+use test::black_box as b;
+
+const U_MAX: u128 = !0;
+const U_A: u128 = 0x1234_5678_9abc_def0_1234_5678_9abc_def0;
+const U_B: u128 = 0xfedc_ba98_7654_3210;
+const U_MUL: u128 = U_A * 3;
+const U_DIV: u128 = U_MAX / U_B;
+const U_REM: u128 = U_MAX % U_B;
+const U_SHL: u128 = U_B << 64;
+const U_SHR: u128 = U_A >> 120;
+
+const I_MIN: i128 = 1 << 127;
+const I_MAX: i128 = !I_MIN;
+const I_NEG: i128 = -(U_B as i128);
+const I_MUL: i128 = I_NEG * 7;
+const I_DIV: i128 = I_MIN / I_NEG;
+const I_REM: i128 = I_MIN % I_NEG;
+const I_SHR: i128 = I_MIN >> 127;
+
+fn main() {
+    // const results
+    assert_eq!(U_MUL, 0x369d_0369_d036_9cd0_369d_0369_d036_9cd0);
+    assert_eq!(U_DIV, 0x1_0124_9249_2492_4924);
+    assert_eq!(U_REM, 0x7f59_8f32_8cc2_65bf);
+    assert_eq!(U_SHL, 0xfedc_ba98_7654_3210_0000_0000_0000_0000);
+    assert_eq!(U_SHR, 0x12);
+    assert_eq!(I_MAX, 0x7fff_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+    assert_eq!(I_SHR, -1);
+    assert_eq!([0u8; U_SHR as usize].len(), 18);
+
+    // const and runtime evaluation agree
+    assert_eq!(U_MUL, b(U_A) * b(3));
+    assert_eq!(U_DIV, b(U_MAX) / b(U_B));
+    assert_eq!(U_REM, b(U_MAX) % b(U_B));
+    assert_eq!(U_SHL, b(U_B) << b(64));
+    assert_eq!(U_SHR, b(U_A) >> b(120));
+    assert_eq!(I_MUL, b(I_NEG) * b(7));
+    assert_eq!(I_DIV, b(I_MIN) / b(I_NEG));
+    assert_eq!(I_REM, b(I_MIN) % b(I_NEG));
+    assert_eq!(I_SHR, b(I_MIN) >> b(127));
+
+    // checked arithmetic
+    assert_eq!(b(U_MAX).checked_add(1), None);
+    assert_eq!(b(U_A).checked_add(U_B), Some(U_A + U_B));
+    assert_eq!(b(0u128).checked_sub(1), None);
+    assert_eq!(b(U_A).checked_mul(16), None);
+    assert_eq!(b(U_A).checked_mul(3), Some(U_MUL));
+    assert_eq!(b(U_A).checked_div(0), None);
+    assert_eq!(b(U_A).checked_rem(0), None);
+    assert_eq!(b(1u128).checked_shl(128), None);
+    assert_eq!(b(1u128).checked_shl(127), Some(1 << 127));
+    assert_eq!(b(I_MAX).checked_add(1), None);
+    assert_eq!(b(I_MIN).checked_sub(1), None);
+    assert_eq!(b(I_MIN).checked_mul(-1), None);
+    assert_eq!(b(I_MIN).checked_div(-1), None);
+    assert_eq!(b(I_MIN).checked_rem(-1), None);
+    assert_eq!(b(I_MIN).checked_neg(), None);
+    assert_eq!(b(I_NEG).checked_mul(7), Some(I_MUL));
+    assert_eq!(b(-1i128).checked_shr(128), None);
+
+    // wrapping arithmetic
+    assert_eq!(b(U_MAX).wrapping_add(1), 0);
+    assert_eq!(b(0u128).wrapping_sub(1), U_MAX);
+    assert_eq!(b(U_A).wrapping_mul(16), 0x2345_6789_abcd_ef01_2345_6789_abcd_ef00);
+    assert_eq!(b(1u128).wrapping_shl(129), 2);
+    assert_eq!(b(U_MAX).wrapping_shr(255), 1);
+    assert_eq!(b(I_MAX).wrapping_add(1), I_MIN);
+    assert_eq!(b(I_MIN).wrapping_sub(1), I_MAX);
+    assert_eq!(b(I_MIN).wrapping_mul(-1), I_MIN);
+    assert_eq!(b(I_MIN).wrapping_div(-1), I_MIN);
+    assert_eq!(b(I_MIN).wrapping_rem(-1), 0);
+    assert_eq!(b(I_MIN).wrapping_neg(), I_MIN);
+    assert_eq!(b(I_MIN).wrapping_shr(255), -1);
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(impl_trait_in_bindings)]
+
+use std::fmt::Debug;
+
+const FOO: impl Debug + Clone + PartialEq<i32> = 42;
+static BAR: impl Debug + Sync = "bar";
+
+fn main() {
+    assert_eq!(FOO, 42);
+    assert_eq!(format!("{:?}", BAR), "\"bar\"");
+
+    let foo: impl Debug + Clone = FOO;
+    assert_eq!(format!("{:?}", foo.clone()), "42");
+
+    let add_one: impl Fn(i32) -> i32 = |x| x + 1;
+    assert_eq!(add_one(1), 2);
+
+    let iter: impl Iterator<Item = u32> = vec![1, 2, 3].into_iter();
+    assert_eq!(iter.sum::<u32>(), 6);
+}
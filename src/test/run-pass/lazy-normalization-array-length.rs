@@ -0,0 +1,50 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that array lengths can depend on the generic parameters in scope
+// when they are normalized lazily, and that such lengths can be used in
+// the items whose signature they are in.
+
+#![feature(lazy_normalization_consts)]
+
+use std::mem;
+
+trait Foo {
+    const SIZE: usize;
+}
+
+impl Foo for u8 {
+    const SIZE: usize = 4;
+}
+
+impl Foo for u16 {
+    const SIZE: usize = 8;
+}
+
+struct Buffer<T: Foo> {
+    bytes: [u8; T::SIZE],
+}
+
+impl<T: Foo> Buffer<T> {
+    fn len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+fn size_of_buffer<T: Foo>(_: &Buffer<T>) -> usize {
+    mem::size_of::<Buffer<T>>()
+}
+
+fn main() {
+    let buffer = Buffer::<u8> { bytes: [1; 4] };
+    assert_eq!(buffer.len(), 4);
+    assert_eq!(size_of_buffer(&buffer), 4);
+    assert_eq!(size_of_buffer(&Buffer::<u16> { bytes: [2; 8] }), 8);
+}
@@ -0,0 +1,49 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-emit-alignment-checks
+// ignore-wasm32-bare compiled with panic=abort by default
+
+// The checks inserted by `-Z mir-emit-alignment-checks` unwind like any
+// other panic, dropping the locals in scope.
+
+use std::cell::Cell;
+use std::panic;
+
+struct Guard<'a>(&'a Cell<u32>);
+
+impl<'a> Drop for Guard<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn read_after_guard(drops: &Cell<u32>, ptr: *const u32) -> u32 {
+    let _guard = Guard(drops);
+    let value = unsafe { *ptr };
+    let _later = Guard(drops);
+    value
+}
+
+fn main() {
+    let drops = Cell::new(0);
+    let x = [0u32; 2];
+    let misaligned = (&x as *const [u32; 2] as *const u8).wrapping_offset(1) as *const u32;
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        read_after_guard(&drops, misaligned)
+    }));
+    assert!(result.is_err());
+    // Only the guard which was initialized is dropped.
+    assert_eq!(drops.get(), 1);
+
+    assert_eq!(read_after_guard(&drops, &x[1]), 0);
+    assert_eq!(drops.get(), 3);
+}
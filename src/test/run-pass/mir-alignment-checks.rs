@@ -0,0 +1,45 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-emit-alignment-checks
+
+// Valid uses of raw pointers pass the checks inserted by
+// `-Z mir-emit-alignment-checks`.
+
+use std::ptr;
+
+#[derive(Clone, Copy)]
+struct Pair {
+    a: u64,
+    b: u16,
+}
+
+fn read_generic<T: Copy>(ptr: *const T) -> T {
+    unsafe { *ptr }
+}
+
+fn main() {
+    let mut pair = Pair { a: 1, b: 2 };
+    let ptr = &mut pair as *mut Pair;
+    unsafe {
+        (*ptr).a += 10;
+        (*ptr).b = ptr::read(&(*ptr).b) + 1;
+        ptr::write(&mut (*ptr).a, 20);
+    }
+    assert_eq!(read_generic(ptr).a, 20);
+    assert_eq!(read_generic(&pair.b as *const u16), 3);
+
+    let bytes = [1u8, 2, 3, 4];
+    let unaligned = unsafe { *(&bytes[1] as *const u8) };
+    assert_eq!(unaligned, 2);
+
+    let slice: *const [u8] = &bytes[..];
+    assert_eq!(unsafe { (*slice).len() }, 4);
+}
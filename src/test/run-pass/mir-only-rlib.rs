@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:mir-only-rlib.rs
+// compile-flags: -Z mir-opt-level=3
+
+// Test that the non-generic functions of an rlib built with
+// -Z mir-only-rlibs, which has no code for them, are translated from
+// their MIR in the crates using them, and can be inlined there.
+
+extern crate mir_only_rlib;
+
+use mir_only_rlib::{add, Counter};
+
+fn main() {
+    assert_eq!(add(1, 2), 3);
+
+    let mut counter = Counter::new();
+    counter.incr();
+    assert_eq!(counter.incr(), 2);
+}
@@ -0,0 +1,66 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that fixed-size arrays are dropped element by element, in order,
+// both when some elements have been moved out and when an element's
+// destructor panics.
+
+// ignore-emscripten no threads support
+
+#![feature(slice_patterns)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+// The ids of the dropped values, one per hex digit.
+static LOG: AtomicUsize = AtomicUsize::new(0);
+
+struct D(u8, bool);
+
+impl Drop for D {
+    fn drop(&mut self) {
+        let old = LOG.load(Ordering::SeqCst);
+        LOG.store(old << 4 | self.0 as usize, Ordering::SeqCst);
+        if self.1 {
+            panic!("panic while dropping {}", self.0);
+        }
+    }
+}
+
+fn take_log() -> usize {
+    LOG.swap(0, Ordering::SeqCst)
+}
+
+fn partial_move(cond: bool) {
+    let arr = [D(1, false), D(2, false), D(3, false), D(4, false)];
+    if cond {
+        let [_, b, _, d] = arr;
+        drop(d);
+        drop(b);
+    }
+}
+
+fn main() {
+    {
+        let _arr = [D(1, false), D(2, false), D(3, false)];
+    }
+    assert_eq!(take_log(), 0x123);
+
+    partial_move(true);
+    assert_eq!(take_log(), 0x4213);
+    partial_move(false);
+    assert_eq!(take_log(), 0x1234);
+
+    let result = thread::spawn(|| {
+        let _arr = [D(1, false), D(2, true), D(3, false)];
+    }).join();
+    assert!(result.is_err());
+    assert_eq!(take_log(), 0x123);
+}
@@ -0,0 +1,68 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the MIR produced by every pass passes validation.
+
+// compile-flags: -Z validate-mir -Z mir-opt-level=3
+
+#![feature(generators, generator_trait)]
+
+use std::ops::Generator;
+
+struct Noisy(u32);
+
+impl Drop for Noisy {
+    fn drop(&mut self) {}
+}
+
+fn pick(o: Option<Noisy>, fallback: u32) -> u32 {
+    match o {
+        Some(Noisy(n)) if n > 10 => n,
+        Some(_) => fallback,
+        None => 0,
+    }
+}
+
+fn sum(xs: &[u32]) -> u32 {
+    let mut total = 0;
+    for &x in xs {
+        total += x;
+    }
+    total
+}
+
+// A failed guard has to end the storage of its bindings before the next
+// candidate of the same arm, or the next loop iteration, binds them again.
+fn small_sides(pairs: &[(u32, u32)]) -> u32 {
+    let mut total = 0;
+    for &pair in pairs {
+        match pair {
+            (n, 0) | (0, n) if n < 10 => total += n,
+            _ => {}
+        }
+    }
+    total
+}
+
+fn main() {
+    let mut gen = || {
+        let n = Noisy(3);
+        yield n.0;
+        yield sum(&[1, 2, 3]);
+    };
+    gen.resume();
+    gen.resume();
+
+    assert_eq!(pick(Some(Noisy(11)), 5), 11);
+    assert_eq!(pick(Some(Noisy(1)), 5), 5);
+    assert_eq!(pick(None, 5), 0);
+    assert_eq!(sum(&[4, 5]), 9);
+    assert_eq!(small_sides(&[(3, 0), (0, 4), (20, 0), (0, 0), (5, 5)]), 7);
+}
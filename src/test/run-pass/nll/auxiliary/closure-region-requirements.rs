@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![crate_type = "rlib"]
+
+// The closure in `collect` stores its argument in a vector it borrows
+// from its creator, so it has region requirements that are encoded in
+// the metadata of this crate.
+
+pub fn collect<'a>(items: &[&'a u32]) -> Vec<&'a u32> {
+    let mut out = vec![];
+    {
+        let mut push = |item| out.push(item);
+        for &item in items {
+            push(item);
+        }
+    }
+    out
+}
+
+// Same, but translated in the crate using it.
+pub fn collect_generic<'a, T>(items: &[&'a T]) -> Vec<&'a T> {
+    let mut out = vec![];
+    {
+        let mut push = |item| out.push(item);
+        for &item in items {
+            push(item);
+        }
+    }
+    out
+}
+
+// Closures in the initializers of consts and statics are encoded like
+// any other.
+pub const PUSH: fn(&mut Vec<&'static u32>, &'static u32) = |out, item| out.push(item);
+
+pub static FIRST: fn(&[&'static u32]) -> Option<&'static u32> = |items| items.first().cloned();
@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that a crate whose closures have region requirements can be
+// checked with the MIR borrowck, have those requirements encoded in its
+// metadata, and be used from another crate.
+
+// aux-build:closure-region-requirements.rs
+// compile-flags:-Znll -Zborrowck=mir
+
+extern crate closure_region_requirements;
+
+use closure_region_requirements::{collect, collect_generic, PUSH, FIRST};
+
+static THREE: u32 = 3;
+
+fn main() {
+    let (a, b) = (1, 2);
+    let items = collect(&[&a, &b]);
+    assert_eq!(items, [&1, &2]);
+    let items = collect_generic(&[&a, &b]);
+    assert_eq!(items, [&1, &2]);
+
+    let mut statics = vec![];
+    PUSH(&mut statics, &THREE);
+    assert_eq!(FIRST(&statics), Some(&3));
+}
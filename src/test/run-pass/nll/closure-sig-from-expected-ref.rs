@@ -0,0 +1,53 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that closures whose argument types are deduced from an expected
+// `Fn(&'a T)` bound can be borrow checked with NLL, both when `'a` is a
+// region of the creator and when it is bound by the expected signature.
+// In the closure body, the regions of the deduced signature are NLL
+// region variables that the creator's regions can be related to. The
+// same must hold when the user annotates the arguments and return type,
+// either with a region of the creator or with elided regions that are
+// linked by elision.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn with_ref<'a, T, F: Fn(&'a T) -> &'a T>(x: &'a T, f: F) -> &'a T {
+    f(x)
+}
+
+fn with_any_ref<T, F: for<'b> Fn(&'b T) -> &'b T>(x: &T, f: F) -> &T {
+    f(x)
+}
+
+fn first<'a>(v: &'a Vec<u32>) -> &'a u32 {
+    with_ref(v, |v| v).first().unwrap()
+}
+
+fn annotated<'a>(v: &'a Vec<u32>) -> &'a Vec<u32> {
+    with_ref(v, |x: &'a Vec<u32>| -> &'a Vec<u32> { x })
+}
+
+fn annotated_any(v: &Vec<u32>) -> &Vec<u32> {
+    with_any_ref(v, |x: &Vec<u32>| -> &Vec<u32> { x })
+}
+
+fn main() {
+    let v = vec![1, 2, 3];
+    let a = first(&v);
+    let b = with_any_ref(&v, |v| v);
+    let c = with_ref(&v[1], |x| x);
+    assert_eq!(*a, 1);
+    assert_eq!(b.len(), 3);
+    assert_eq!(*c, 2);
+    assert_eq!(annotated(&v).len(), 3);
+    assert_eq!(annotated_any(&v)[2], 3);
+}
@@ -0,0 +1,57 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(nll)]
+#![feature(match_default_bindings)]
+
+struct Pair {
+    a: u32,
+    b: Vec<u32>,
+}
+
+fn bump(pair: &mut Pair) {
+    // `a` and `b` are implicitly `ref mut` bindings into `*pair`.
+    let Pair { a, b } = pair;
+    *a += 1;
+    b.push(*a);
+    // The implicit borrows end at their last use, so `pair` is usable again.
+    pair.a += 1;
+}
+
+fn first_even(v: &Vec<Option<u32>>) -> Option<&u32> {
+    for x in v {
+        if let Some(n) = x {
+            if n % 2 == 0 {
+                return Some(n);
+            }
+        }
+    }
+    None
+}
+
+fn main() {
+    let mut pair = Pair { a: 1, b: vec![] };
+    bump(&mut pair);
+    assert_eq!(pair.a, 3);
+    assert_eq!(pair.b, [2]);
+
+    assert_eq!(first_even(&vec![None, Some(3), Some(4)]), Some(&4));
+
+    let mut opt = Some(5);
+    if let Some(x) = &mut opt {
+        *x += 1;
+    }
+    match &opt {
+        Some(x) => assert_eq!(*x, 6),
+        None => panic!(),
+    }
+    opt = None;
+    assert_eq!(opt, None);
+}
@@ -0,0 +1,54 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the MIR type checker relates user-defined types, references
+// and fn pointers according to their variance, rather than requiring the
+// regions in them to be equal.
+
+#![feature(nll)]
+
+struct Wrapper<'a> {
+    value: &'a u32,
+}
+
+struct Nested<'a> {
+    inner: Wrapper<'a>,
+    source: fn() -> &'a u32,
+}
+
+fn shorten_wrapper<'a, 'b: 'a>(w: Wrapper<'b>) -> Wrapper<'a> {
+    w
+}
+
+fn shorten_nested<'a, 'b: 'a>(n: Nested<'b>) -> Nested<'a> {
+    n
+}
+
+fn lengthen_callback<'a, 'b: 'a>(f: fn(&'a u32)) -> fn(&'b u32) {
+    f
+}
+
+fn ignore(_: &u32) {}
+
+fn forty_four() -> &'static u32 {
+    &44
+}
+
+fn main() {
+    let x = 22;
+    let w = shorten_wrapper(Wrapper { value: &x });
+    assert_eq!(*w.value, 22);
+
+    let n = shorten_nested(Nested { inner: w, source: forty_four });
+    assert_eq!(*n.inner.value + *(n.source)(), 66);
+
+    let f = lengthen_callback(ignore);
+    f(&x);
+}
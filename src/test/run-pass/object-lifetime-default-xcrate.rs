@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:object-lifetime-default-xcrate.rs
+
+// Test that the object lifetime defaults of the type parameters of a
+// foreign trait and struct are applied to the right parameters. The trait
+// has an implicit `Self` parameter, which paths to it don't mention.
+
+extern crate object_lifetime_default_xcrate;
+
+use object_lifetime_default_xcrate::{Ref, Unit, Wrap};
+use std::fmt::Display;
+
+// The `Display` argument defaults to `Display + 'a` here, not `'static`.
+fn show<'a>(w: &Wrap<'a, Display>, d: &'a (Display + 'a)) -> String {
+    w.wrap(d).to_string()
+}
+
+fn show_ref<'a>(r: Ref<'a, Display>) -> String {
+    r.0.to_string()
+}
+
+fn main() {
+    let x = 22;
+    let local = &x;
+    assert_eq!(show(&Unit, &local), "22");
+    assert_eq!(show_ref(Ref(&local)), "22");
+}
@@ -0,0 +1,128 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(or_patterns, pattern_parentheses, slice_patterns)]
+
+#[derive(Clone, Copy)]
+enum E {
+    A(u8),
+    B(u8),
+    C,
+}
+
+fn classify(x: Option<u8>) -> u8 {
+    match x {
+        Some(0 | 1) => 0,
+        Some(2 | 3 | 4) => 1,
+        Some(_) => 2,
+        None => 3,
+    }
+}
+
+fn inner(e: Option<E>) -> u8 {
+    match e {
+        Some(E::A(x) | E::B(x)) => x,
+        Some(E::C) | None => 0,
+    }
+}
+
+fn pairs(p: (bool, bool)) -> u8 {
+    match p {
+        (true, true | false) => 1,
+        (false, true | false) => 2,
+    }
+}
+
+fn slices(s: &[u8]) -> u8 {
+    match s {
+        &[0 | 1, x] => x,
+        _ => 0,
+    }
+}
+
+struct S {
+    a: u8,
+    b: Option<u8>,
+}
+
+fn fields(s: S) -> u8 {
+    match s {
+        S { a: 0 | 1, b: Some(b) } => b,
+        S { a, .. } => a,
+    }
+}
+
+fn param((E::A(x) | E::B(x)): E) -> u8 {
+    x
+}
+
+fn main() {
+    let (Ok(x) | Err(x)) = Ok::<u8, u8>(4);
+    assert_eq!(x, 4);
+    let (Ok(x) | Err(x)) = Err::<u8, u8>(5);
+    assert_eq!(x, 5);
+
+    assert_eq!(param(E::A(1)), 1);
+    assert_eq!(param(E::B(2)), 2);
+
+    let closure = |(Ok(x) | Err(x)): Result<u8, u8>| x;
+    assert_eq!(closure(Err(6)), 6);
+
+    let mut total = 0;
+    for (Ok(x) | Err(x)) in vec![Ok::<u8, u8>(1), Err(2)] {
+        total += x;
+    }
+    assert_eq!(total, 3);
+
+    let mut total = 0;
+    for (E::A(x) | E::B(x), _) in vec![(E::A(1), 0), (E::B(2), 0)] {
+        total += x;
+    }
+    assert_eq!(total, 3);
+
+    assert_eq!(classify(Some(0)), 0);
+    assert_eq!(classify(Some(1)), 0);
+    assert_eq!(classify(Some(3)), 1);
+    assert_eq!(classify(Some(5)), 2);
+    assert_eq!(classify(None), 3);
+
+    assert_eq!(inner(Some(E::A(7))), 7);
+    assert_eq!(inner(Some(E::B(8))), 8);
+    assert_eq!(inner(Some(E::C)), 0);
+    assert_eq!(inner(None), 0);
+
+    assert_eq!(pairs((true, false)), 1);
+    assert_eq!(pairs((false, true)), 2);
+
+    assert_eq!(slices(&[1, 9]), 9);
+    assert_eq!(slices(&[2, 9]), 0);
+
+    assert_eq!(fields(S { a: 1, b: Some(5) }), 5);
+    assert_eq!(fields(S { a: 1, b: None }), 1);
+    assert_eq!(fields(S { a: 2, b: Some(5) }), 2);
+
+    if let Some(E::A(1 | 2)) = Some(E::A(2)) {
+    } else {
+        panic!();
+    }
+
+    let mut v = vec![Some(3), Some(4), None];
+    let mut sum = 0;
+    while let Some(Some(x @ (3 | 4))) = v.pop() {
+        sum += x;
+    }
+    assert_eq!(sum, 0);
+    v.push(Some(4));
+    while let Some(Some(x @ (3 | 4))) = v.pop() {
+        sum += x;
+    }
+    assert_eq!(sum, 11);
+}
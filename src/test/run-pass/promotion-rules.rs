@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Values which can't fail to evaluate are still implicitly promoted, and
+// explicit promotion still accepts arbitrary `const fn` calls.
+
+#![feature(attr_literals, rustc_attrs, const_fn, restricted_promotion)]
+
+use std::mem;
+
+const FIVE: u32 = 5;
+
+const fn double(x: u32) -> u32 {
+    x * 2
+}
+
+// In constants, every temporary lives forever anyway.
+const TEN: &'static u32 = &double(FIVE);
+
+#[rustc_args_required_const(0)]
+fn required(x: u32) -> u32 {
+    x
+}
+
+fn main() {
+    let a: &'static u32 = &FIVE;
+    let b: &'static usize = &mem::size_of::<u32>();
+    let c: &'static u8 = &u8::max_value();
+    let d: &'static *const u8 = &std::ptr::null();
+    assert_eq!(*a, 5);
+    assert_eq!(*b, 4);
+    assert_eq!(*c, 255);
+    assert!(d.is_null());
+    assert_eq!(*TEN, 10);
+    assert_eq!(required(double(21)), 42);
+}
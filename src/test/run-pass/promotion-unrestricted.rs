@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Without `#![feature(restricted_promotion)]`, calls to any `const fn` and
+// reads of union fields are still implicitly promoted.
+
+#![feature(const_fn)]
+
+const fn foo() -> u32 {
+    42
+}
+
+union Foo {
+    a: u32,
+    b: f32,
+}
+
+const FOO: Foo = Foo { a: 1 };
+
+fn main() {
+    let x: &'static u32 = &foo();
+    let y: &'static u32 = unsafe { &FOO.a };
+    assert_eq!(*x, 42);
+    assert_eq!(*y, 1);
+}
@@ -0,0 +1,42 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z query-threads=4
+
+// Type checking and borrow checking of the bodies are spread over several
+// threads, which all need the signatures of the other functions.
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn quadruple(x: u32) -> u32 {
+    double(double(x))
+}
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fn bump(&mut self) -> u32 {
+        self.count = quadruple(self.count + 1);
+        self.count
+    }
+}
+
+const SIXTEEN: u32 = 4 * 4;
+
+fn main() {
+    let mut counter = Counter { count: 0 };
+    assert_eq!(counter.bump(), 4);
+    assert_eq!(counter.bump(), 20);
+    assert_eq!(quadruple(SIXTEEN), 64);
+}
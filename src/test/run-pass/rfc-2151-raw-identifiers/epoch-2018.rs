@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-flags: -Zepoch=2018 -Zunstable-options
+
+// Raw identifiers don't need a feature gate in the 2018 epoch.
+
+fn r#async() -> u32 {
+    1
+}
+
+pub fn main() {
+    let r#try = r#async();
+    assert_eq!(1, r#try);
+}
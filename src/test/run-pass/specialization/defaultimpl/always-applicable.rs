@@ -0,0 +1,48 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests impls specializing a `default impl` that are "always applicable",
+// i.e. that apply regardless of lifetimes.
+
+#![feature(specialization)]
+
+trait Foo {
+    fn foo(&self) -> &'static str;
+}
+
+default impl<T> Foo for T {
+    fn foo(&self) -> &'static str {
+        "generic"
+    }
+}
+
+impl<'a> Foo for &'a str {
+    fn foo(&self) -> &'static str {
+        "str"
+    }
+}
+
+impl<T: Clone> Foo for Vec<T> {
+    fn foo(&self) -> &'static str {
+        "Vec"
+    }
+}
+
+impl<'a, 'b> Foo for (&'a u8, &'b u8) {
+    fn foo(&self) -> &'static str {
+        "pair"
+    }
+}
+
+fn main() {
+    assert_eq!("hello".foo(), "str");
+    assert_eq!(vec![1, 2].foo(), "Vec");
+    assert_eq!((&0u8, &1u8).foo(), "pair");
+}
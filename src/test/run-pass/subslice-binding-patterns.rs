@@ -0,0 +1,51 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(slice_patterns)]
+#![feature(advanced_slice_patterns)]
+
+fn sum(v: &[u32]) -> u32 {
+    match v {
+        &[] => 0,
+        &[first, ref rest @ ..] => first + sum(rest),
+    }
+}
+
+fn ends(v: &[u8]) -> Option<(u8, u8)> {
+    match *v {
+        [] => None,
+        [x] => Some((x, x)),
+        [first, ref _middle @ .., last] => Some((first, last)),
+    }
+}
+
+fn bump_all(v: &mut [i32]) {
+    // The first element and the rest of the slice are disjoint places, so
+    // both can be borrowed mutably at once.
+    if let [ref mut first, ref mut rest @ ..] = *v {
+        *first += 1;
+        bump_all(rest);
+    }
+}
+
+fn main() {
+    assert_eq!(sum(&[1, 2, 3, 4]), 10);
+    assert_eq!(ends(&[]), None);
+    assert_eq!(ends(&[7]), Some((7, 7)));
+    assert_eq!(ends(&[1, 2, 3]), Some((1, 3)));
+
+    let mut v = [1, 2, 3];
+    bump_all(&mut v);
+    assert_eq!(v, [2, 3, 4]);
+
+    let [a, b @ ..] = [1u8, 2, 3];
+    assert_eq!(a, 1);
+    assert_eq!(b, [2, 3]);
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:trait_alias.rs
+
+#![feature(trait_alias)]
+
+extern crate trait_alias;
+
+use trait_alias::{CloneDefault, DebugSend};
+
+fn twice<T: CloneDefault>(t: &T) -> (T, T) {
+    (t.clone(), t.clone())
+}
+
+fn main() {
+    let (a, b): (u8, u8) = trait_alias::pair();
+    assert_eq!((a, b), (0, 0));
+    assert_eq!(twice(&3u32), (3, 3));
+
+    let object: Box<DebugSend> = Box::new(5i32);
+    assert_eq!(format!("{:?}", object), "5");
+}
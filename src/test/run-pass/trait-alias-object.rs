@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(trait_alias)]
+
+use std::fmt::{Debug, Display};
+
+trait DebugSend = Debug + Send;
+trait DebugSync = Debug + Sync + Send;
+trait IterOf<T> = Iterator<Item=T>;
+
+fn describe(value: &DebugSend) -> String {
+    format!("{:?}", value)
+}
+
+fn total(iter: Box<IterOf<u32>>) -> u32 {
+    iter.sum()
+}
+
+fn show<T: Display>(t: T) -> String {
+    t.to_string()
+}
+
+fn main() {
+    assert_eq!(describe(&"hi"), "\"hi\"");
+
+    // Repeating a trait that an alias already contains doesn't change the object type.
+    let object: Box<DebugSync + Send> = Box::new(1u8);
+    let object: Box<DebugSend + Sync> = object;
+    assert_eq!(format!("{:?}", object), "1");
+
+    assert_eq!(total(Box::new(vec![1, 2, 3].into_iter())), 6);
+    assert_eq!(show(7), "7");
+}
@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-flags: -Z borrowck=mir
+
+// Assigning through a shared borrow of an immutable binding suggests both
+// making the binding `mut` and borrowing it mutably, as a single suggestion.
+
+fn main() {
+    let x = 0;
+    let r = &x;
+    *r = 1; //~ ERROR cannot assign to immutable item `*r`
+}
@@ -0,0 +1,24 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a closure which is `FnOnce` only because it moves a captured
+// variable suggests cloning the variable, if it is `Clone`.
+
+struct NotClone;
+
+fn call<F: Fn()>(f: F) { f() }
+
+fn main() {
+    let s = String::new();
+    call(move || drop(s)); //~ ERROR expected a closure that implements the `Fn` trait
+
+    let n = NotClone;
+    call(move || drop(n)); //~ ERROR expected a closure that implements the `Fn` trait
+}
@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// must-compile-successfully
+// compile-flags: -Z unleash-the-miri
+
+// Check that `-Z unleash-the-miri` turns the error for a call to a
+// non-const fn in a constant into a warning.
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+const X: u32 = double(21);
+
+fn main() {
+    assert_eq!(X, 42);
+}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_generics)]
+
+fn foo<const N: usize>() {}
+
+struct Foo<const N: usize>;
+//~^ ERROR parameter `N` is never used
+
+enum Bar<'a, T, const N: usize> {
+//~^ ERROR parameter `N` is never used
+    A(&'a T),
+}
+
+struct Qux;
+
+impl<const N: usize> Qux {
+    fn bar<const M: bool>(&self) {}
+}
+
+fn main() {}
@@ -18,7 +18,7 @@ trait B: C {
 }
 
 trait C: B { }
-    //~^ ERROR cyclic dependency detected
+    //~^ ERROR cycle detected
     //~| cyclic reference
 
 fn main() { }
@@ -1,4 +1,4 @@
-// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
 // file at the top-level directory of this distribution and at
 // http://rust-lang.org/COPYRIGHT.
 //
@@ -8,9 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// gate-test-universal_impl_trait
+fn foo<const X: ()>() {} //~ ERROR const generics are unstable
 
-fn foo(x: impl std::fmt::Debug) { print!("{:?}", x); }
-//~^ ERROR `impl Trait` in argument position is experimental
+struct Foo<const X: usize>; //~ ERROR const generics are unstable
 
 fn main() {}
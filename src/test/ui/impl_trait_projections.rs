@@ -7,7 +7,7 @@
 // <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
-#![feature(dyn_trait, conservative_impl_trait, universal_impl_trait)]
+#![feature(dyn_trait, conservative_impl_trait)]
 
 use std::fmt::Debug;
 use std::option;
@@ -12,7 +12,7 @@ trait t1 : t2 {
 }
 
 trait t2 : t1 {
-//~^ ERROR cyclic dependency detected
+//~^ ERROR cycle detected
 //~| cyclic reference
 }
 
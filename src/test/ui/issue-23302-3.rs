@@ -10,6 +10,6 @@
 
 const A: i32 = B;
 
-const B: i32 = A; //~ ERROR cyclic dependency detected
+const B: i32 = A; //~ ERROR cycle detected
 
 fn main() { }
@@ -0,0 +1,20 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(raw_identifiers)]
+#![deny(keyword_idents)]
+#![allow(unused)]
+
+fn main() {
+    let await = 1; //~ ERROR `await` is a keyword in the 2018 epoch
+    //~^ WARN this was previously accepted
+    let r#async = r#await;
+}
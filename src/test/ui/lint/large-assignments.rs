@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z move-size-limit=1000
+
+#![deny(large_assignments)]
+#![allow(dead_code, unused_variables)]
+
+struct Large([u8; 2000]);
+struct Small([u8; 10]);
+
+fn consume(_: Large) {}
+
+fn moves(large: Large, small: Small) {
+    let moved = large; //~ ERROR moving 2000 bytes
+    consume(moved); //~ ERROR moving 2000 bytes
+    let also_moved = small;
+}
+
+#[allow(large_assignments)]
+fn allowed(large: Large) {
+    let moved = large;
+}
+
+fn generic<T>(value: T) {
+    let moved = value;
+}
+
+fn main() {}
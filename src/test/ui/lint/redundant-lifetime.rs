@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![deny(redundant_lifetime)]
+#![allow(dead_code)]
+
+fn one_argument<'a>(x: &'a u32) -> u32 { *x } //~ ERROR can be elided
+
+fn only_input<'a>(x: &'a u32) -> &'a u32 { x } //~ ERROR can be elided
+
+struct S;
+
+impl S {
+    fn self_lifetime<'a, T>(&'a self, _: &T) -> &'a S { self } //~ ERROR can be elided
+}
+
+// Elision would not pick `'a` here.
+fn two_arguments<'a>(x: &'a u32, y: &'a u32) -> u32 { *x + *y }
+
+fn ambiguous<'a>(x: &'a u32, _: &u32) -> &'a u32 { x }
+
+fn main() {}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// must-compile-successfully
+
+// `rust-2018-compatibility.rs` with its machine-applicable suggestions
+// applied, which must not trigger the lints again.
+
+#![feature(dotdoteq_in_patterns, raw_identifiers)]
+#![deny(rust_2018_compatibility)]
+#![allow(unused)]
+
+fn main() {
+    let r#async = 5;
+    match 3 {
+        0..=5 => {}
+        _ => {}
+    }
+}
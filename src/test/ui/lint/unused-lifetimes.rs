@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![deny(unused_lifetimes)]
+#![allow(dead_code)]
+
+fn only<'a>() {} //~ ERROR never used
+
+fn last<'a, 'b>(x: &'a u32) -> &'a u32 { x } //~ ERROR never used
+
+fn first<'a, T>(_: T) {} //~ ERROR never used
+
+fn used_in_body<'a>() {
+    let _: Option<&'a u32> = None;
+}
+
+struct S;
+
+impl<'a> S { //~ ERROR never used
+    fn method<'b>(&self) {} //~ ERROR never used
+}
+
+struct Wrapper<'a>(&'a u32);
+
+impl<'a> Wrapper<'a> {}
+
+trait Trait {
+    fn method<'a>(&self);
+}
+
+// The lifetimes of trait methods are not linted in their impls.
+impl Trait for S {
+    fn method<'a>(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that two different anonymous regions in an error are told apart when
+// `-Z verbose` isn't given, instead of both being called `'_`.
+
+#![feature(nll)]
+
+fn main() {
+    let _push = |x: &u32, y: &mut Vec<&u32>| y.push(x);
+    //~^ ERROR free region `'1` does not outlive free region `'2`
+}
@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-flags:-Znll -Zborrowck=mir
+
+// Check that a conflicting borrow error points at where the region of
+// the first borrow ends when no later use explains why it is live.
+// Here, the shared reborrow of `*x` has to outlive a region of the
+// closure's creator, which has no name in the closure, so it lasts until
+// the end of the closure body.
+
+fn with<'a, F>(x: &'a mut u32, v: &mut Vec<&'a u32>, f: F)
+where
+    F: FnOnce(&'a mut u32, &mut Vec<&'a u32>),
+{
+    f(x, v)
+}
+
+fn main() {
+    let mut x = 0;
+    let mut v = vec![];
+    with(&mut x, &mut v, |x, v| {
+        v.push(&*x);
+        let m = &mut *x;
+        //~^ ERROR cannot borrow `*x` as mutable because it is also borrowed as immutable
+        *m += 1;
+    });
+}
@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a region of the closure signature which has no name of its
+// own, but is known to be equal to `'static`, is called `'static` in errors.
+
+#![feature(nll)]
+
+use std::cell::Cell;
+
+struct Forever<'x: 'static>(Cell<&'x u32>);
+
+fn with_forever<F>(_f: F)
+where
+    F: FnOnce(&Forever<'static>, &u32),
+{
+}
+
+fn main() {
+    with_forever(|forever, x| {
+        forever.0.set(x);
+        //~^ ERROR borrowed data escapes outside of closure
+    });
+}
@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a `move` closure which mutates a captured `mut` binding
+// neither reports an error nor lets `unused_mut` fire for the binding,
+// including when the mutation happens in a nested closure.
+
+// must-compile-successfully
+// compile-flags: -Zborrowck=mir
+
+#![deny(unused_mut)]
+
+fn direct() -> u32 {
+    let mut x = 0;
+    let mut incr = move || {
+        x += 1;
+        x
+    };
+    incr()
+}
+
+fn nested() -> u32 {
+    let mut x = 0;
+    let outer = move || {
+        let mut inner = move || {
+            x += 1;
+            x
+        };
+        inner()
+    };
+    outer()
+}
+
+fn main() {
+    assert_eq!(direct(), 1);
+    assert_eq!(nested(), 1);
+}
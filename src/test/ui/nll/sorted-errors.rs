@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(nll)]
+
+// Errors from the MIR borrow checker are reported in the order of their
+// spans, even though moves out of borrowed content are found before the
+// borrows are checked.
+
+fn main() {
+    let mut x = 1;
+    let r = &x;
+    x = 2; //~ ERROR cannot assign to `x` because it is borrowed
+    drop(r);
+    drop(x);
+
+    let v = &vec![String::new()];
+    let _s = v[0]; //~ ERROR cannot move out of borrowed content
+}
@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that the lifetime bound suggested for an `impl Trait` argument is
+// added to the `impl Trait` itself.
+
+#![feature(nll)]
+
+#![allow(warnings)]
+
+use std::cell::Cell;
+
+fn region_static<'a>(cell: Cell<&'a usize>, t: impl Sized) {
+    outlives(cell, t)
+    //~^ ERROR the parameter type `impl Sized` may not live long enough
+}
+
+fn outlives<'a, T>(x: Cell<&'a usize>, y: T)
+where
+    T: 'a,
+{
+}
+
+fn main() {}
@@ -0,0 +1,55 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a closure which has to prove that a projection out of an
+// `impl Trait` type outlives some region, where the trait declares a
+// bound for the projection, passes the projection itself on to its
+// creator, which can then use that declared bound.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+#![allow(warnings)]
+#![feature(conservative_impl_trait)]
+#![feature(dyn_trait)]
+
+use std::fmt::Debug;
+
+trait Anything<'b> {
+    type AssocType: Debug + 'b;
+
+    fn get(&self) -> Self::AssocType;
+}
+
+impl<'b> Anything<'b> for &'b u32 {
+    type AssocType = &'b u32;
+
+    fn get(&self) -> &'b u32 {
+        *self
+    }
+}
+
+fn opaque<'b>(x: &'b u32) -> impl Anything<'b> {
+    x
+}
+
+fn with_signature<'a, T, F>(x: Box<T>, op: F) -> Box<dyn Debug + 'a>
+    where F: FnOnce(Box<T>) -> Box<dyn Debug + 'a>
+{
+    op(x)
+}
+
+fn declared_bound<'a, 'b: 'a>(x: &'b u32) -> Box<dyn Debug + 'a> {
+    // `<impl Anything<'b> as Anything<'b>>::AssocType: 'a` follows
+    // from the bound declared in the trait.
+    with_signature(Box::new(opaque(x).get()), |y| y)
+}
+
+fn main() {}
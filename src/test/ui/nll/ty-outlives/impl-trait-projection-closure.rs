@@ -0,0 +1,61 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a closure which has to prove that a projection out of an
+// `impl Trait` type outlives some region asks its creator to prove
+// that the `impl Trait` type itself outlives that region.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+#![feature(conservative_impl_trait)]
+#![feature(dyn_trait)]
+
+use std::fmt::Debug;
+
+trait Anything {
+    type AssocType: Debug;
+
+    fn get(&self) -> Self::AssocType;
+}
+
+impl Anything for u32 {
+    type AssocType = u32;
+
+    fn get(&self) -> u32 {
+        *self
+    }
+}
+
+fn opaque<T>(_t: T) -> impl Anything {
+    22u32
+}
+
+fn with_signature<'a, T, F>(x: Box<T>, op: F) -> Box<dyn Debug + 'a>
+    where F: FnOnce(Box<T>) -> Box<dyn Debug + 'a>
+{
+    op(x)
+}
+
+fn no_region<'a, T>(t: T) -> Box<dyn Debug + 'a> {
+    // The closure has to prove that `<impl Anything as
+    // Anything>::AssocType` outlives the region in its return type.
+    // It can't know that, so it requires that `impl Anything` does,
+    // which holds only if `T: 'a`.
+    with_signature(Box::new(opaque(t).get()), |y| y)
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR the parameter type `T` may not live long enough
+}
+
+fn correct_region<'a, T: 'a>(t: T) -> Box<dyn Debug + 'a> {
+    with_signature(Box::new(opaque(t).get()), |y| y)
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the note of `#[rustc_on_outlives_failure]` is also added when the
+// bound is on a closure type, whose captured references then fail to outlive
+// the required region.
+
+#![feature(nll)]
+#![feature(rustc_attrs)]
+
+#![allow(warnings)]
+
+#[rustc_on_outlives_failure = "the closure passed to `spawn` must not borrow from its caller"]
+fn spawn<F>(f: F)
+where
+    F: FnOnce() + 'static,
+{
+    f()
+}
+
+fn borrows_argument<'a>(x: &'a usize) {
+    spawn(move || drop(x))
+    //~^ ERROR free region `'a` does not outlive free region `'static`
+}
+
+fn main() {}
@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that when a type test fails because a region is missing a point
+// inside a loop, the error points out the path from the requirement to
+// that point.
+
+#![feature(nll)]
+
+#![allow(warnings)]
+
+use std::cell::Cell;
+
+trait Tr<'x> {
+    type Out;
+}
+
+fn out<'x, T: Tr<'x>>(_: &'x T) -> <T as Tr<'x>>::Out {
+    unimplemented!()
+}
+
+fn outlives<'a, T>(x: &Cell<&'a usize>, y: T)
+where
+    T: 'a,
+{
+}
+
+fn in_loop<'a, T>(cell: Cell<&'a usize>, t: T)
+where
+    T: 'a,
+    for<'x> T: Tr<'x>,
+{
+    loop {
+        let o = out(&t);
+        outlives(&cell, o);
+        //~^ ERROR the associated type `<T as Tr<'_>>::Out` may not live long enough
+    }
+}
+
+fn main() {}
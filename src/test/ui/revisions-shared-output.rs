@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Revisions of a UI test share `revisions-shared-output.stderr` unless they
+// have their own `revisions-shared-output.<revision>.stderr`.
+
+// revisions: shared own
+
+fn main() {
+    let _ = fake; //~ ERROR cannot find value `fake`
+    #[cfg(own)]
+    let _ = other_fake; //[own]~ ERROR cannot find value `other_fake`
+}
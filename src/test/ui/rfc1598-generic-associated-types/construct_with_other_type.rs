@@ -8,10 +8,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-#![feature(generic_associated_types)]
+// must-compile-successfully
 
-//FIXME(#44265): "lifetime parameters are not allowed on this type" errors will be addressed in a
-//follow-up PR
+#![feature(generic_associated_types)]
 
 trait Foo {
     type Bar<'a, 'b>;
@@ -23,7 +22,6 @@ trait Baz {
 
 impl<T> Baz for T where T: Foo {
     type Quux<'a> = <T as Foo>::Bar<'a, 'static>;
-    //~^ ERROR lifetime parameters are not allowed on this type [E0110]
 }
 
 fn main() {}
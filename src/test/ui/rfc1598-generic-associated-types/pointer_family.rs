@@ -8,9 +8,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-#![feature(generic_associated_types)]
+// must-compile-successfully
 
-//FIXME(#44265): "type parameter not allowed" errors will be addressed in a follow-up PR
+#![feature(generic_associated_types)]
 
 use std::rc::Rc;
 use std::sync::Arc;
@@ -19,7 +19,6 @@ use std::ops::Deref;
 trait PointerFamily {
     type Pointer<T>: Deref<Target = T>;
     fn new<T>(value: T) -> Self::Pointer<T>;
-    //~^ ERROR type parameters are not allowed on this type [E0109]
 }
 
 struct ArcFamily;
@@ -27,7 +26,6 @@ struct ArcFamily;
 impl PointerFamily for ArcFamily {
     type Pointer<T> = Arc<T>;
     fn new<T>(value: T) -> Self::Pointer<T> {
-    //~^ ERROR type parameters are not allowed on this type [E0109]
         Arc::new(value)
     }
 }
@@ -37,14 +35,12 @@ struct RcFamily;
 impl PointerFamily for RcFamily {
     type Pointer<T> = Rc<T>;
     fn new<T>(value: T) -> Self::Pointer<T> {
-    //~^ ERROR type parameters are not allowed on this type [E0109]
         Rc::new(value)
     }
 }
 
 struct Foo<P: PointerFamily> {
     bar: P::Pointer<String>,
-    //~^ ERROR type parameters are not allowed on this type [E0109]
 }
 
 fn main() {}
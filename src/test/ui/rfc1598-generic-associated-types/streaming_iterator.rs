@@ -8,10 +8,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-#![feature(generic_associated_types)]
+// must-compile-successfully
 
-//FIXME(#44265): "lifetime parameter not allowed on this type" errors will be addressed in a
-// follow-up PR
+#![feature(generic_associated_types)]
 
 use std::fmt::Display;
 
@@ -19,13 +18,11 @@ trait StreamingIterator {
     type Item<'a>;
     // Applying the lifetime parameter `'a` to `Self::Item` inside the trait.
     fn next<'a>(&'a self) -> Option<Self::Item<'a>>;
-    //~^ ERROR lifetime parameters are not allowed on this type [E0110]
 }
 
 struct Foo<T: StreamingIterator> {
     // Applying a concrete lifetime to the constructor outside the trait.
     bar: <T as StreamingIterator>::Item<'static>,
-    //~^ ERROR lifetime parameters are not allowed on this type [E0110]
 }
 
 // Users can bound parameters by the type constructed by that trait's associated type constructor
@@ -33,6 +30,5 @@ struct Foo<T: StreamingIterator> {
 //FIXME(sunjay): This next line should parse and be valid
 //fn foo<T: for<'a> StreamingIterator<Item<'a>=&'a [i32]>>(iter: T) { /* ... */ }
 fn foo<T>(iter: T) where T: StreamingIterator, for<'a> T::Item<'a>: Display { /* ... */ }
-//~^ ERROR lifetime parameters are not allowed on this type [E0110]
 
 fn main() {}
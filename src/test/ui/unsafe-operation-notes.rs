@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that each kind of unsafe operation explains why it requires unsafe.
+
+union U {
+    a: u32,
+}
+
+static mut COUNTER: u32 = 0;
+
+fn main() {
+    let u = U { a: 1 };
+    let _a = u.a; //~ ERROR E0133
+    let _c = COUNTER; //~ ERROR E0133
+}
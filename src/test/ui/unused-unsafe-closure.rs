@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that an `unsafe` block is used when a closure within it performs
+// unsafe operations, even if the closure has its own `unsafe` block.
+
+#![deny(unused_unsafe)]
+
+unsafe fn f() {}
+
+fn main() {
+    unsafe {
+        let g = || unsafe { f() }; //~ ERROR unnecessary `unsafe`
+        g();
+    }
+}
@@ -32,17 +32,25 @@ BUILD_DIR="$1"
 shift
 
 while [[ "$1" != "" ]]; do
-    STDERR_NAME="${1/%.rs/.stderr}"
-    STDOUT_NAME="${1/%.rs/.stdout}"
+    # Revisions and `--compare-mode=nll` runs save their output as e.g.
+    # `foo.rev.stderr` or `foo.rev.nll.stderr`, next to the plain `foo.stderr`.
+    TEST_STEM="${1%.rs}"
+    for OUT_PATH in $BUILD_DIR/$TEST_STEM.stdout $BUILD_DIR/$TEST_STEM.stderr \
+                    $BUILD_DIR/$TEST_STEM.*.stdout $BUILD_DIR/$TEST_STEM.*.stderr; do
+        OUT_NAME="${OUT_PATH#$BUILD_DIR/}"
+        # Only a revision and/or `nll` may come between the test name and the
+        # extension; anything else is the output of another test.
+        INFIX="${OUT_NAME#$TEST_STEM}"
+        INFIX="${INFIX%.*}"
+        INFIX="${INFIX%.nll}"
+        if [[ "$INFIX" == *.*.* ]]; then
+            continue
+        fi
+        if [ -f $BUILD_DIR/$OUT_NAME ] && \
+               ! (diff $BUILD_DIR/$OUT_NAME $MYDIR/$OUT_NAME >& /dev/null); then
+            echo updating $MYDIR/$OUT_NAME
+            cp $BUILD_DIR/$OUT_NAME $MYDIR/$OUT_NAME
+        fi
+    done
     shift
-    if [ -f $BUILD_DIR/$STDOUT_NAME ] && \
-           ! (diff $BUILD_DIR/$STDOUT_NAME $MYDIR/$STDOUT_NAME >& /dev/null); then
-        echo updating $MYDIR/$STDOUT_NAME
-        cp $BUILD_DIR/$STDOUT_NAME $MYDIR/$STDOUT_NAME
-    fi
-    if [ -f $BUILD_DIR/$STDERR_NAME ] && \
-           ! (diff $BUILD_DIR/$STDERR_NAME $MYDIR/$STDERR_NAME >& /dev/null); then
-        echo updating $MYDIR/$STDERR_NAME
-        cp $BUILD_DIR/$STDERR_NAME $MYDIR/$STDERR_NAME
-    fi
 done
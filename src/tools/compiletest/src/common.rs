@@ -95,6 +95,33 @@ impl fmt::Display for Mode {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CompareMode {
+    Nll,
+}
+
+impl CompareMode {
+    pub fn to_str(&self) -> &'static str {
+        match *self {
+            CompareMode::Nll => "nll",
+        }
+    }
+
+    /// The extra flags to pass to the compiler when running in this mode.
+    pub fn rustc_flags(&self) -> &'static [&'static str] {
+        match *self {
+            CompareMode::Nll => &["-Zborrowck=mir", "-Ztwo-phase-borrows"],
+        }
+    }
+
+    pub fn parse(s: String) -> CompareMode {
+        match s.as_str() {
+            "nll" => CompareMode::Nll,
+            x => panic!("unknown --compare-mode option: {}", x),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Config {
     /// The library paths required for running the compiler
@@ -137,6 +164,10 @@ pub struct Config {
     /// The test mode, compile-fail, run-fail, run-pass
     pub mode: Mode,
 
+    /// An optional mode in which to re-run the UI tests, comparing their output
+    /// with e.g. `foo.nll.stderr` if it exists, and with `foo.stderr` otherwise
+    pub compare_mode: Option<CompareMode>,
+
     /// Run ignored tests
     pub run_ignored: bool,
 
@@ -229,14 +260,24 @@ pub struct TestPaths {
     pub relative_dir: PathBuf, // e.g., foo/bar
 }
 
-/// Used by `ui` tests to generate things like `foo.stderr` from `foo.rs`.
-pub fn expected_output_path(testpaths: &TestPaths, revision: Option<&str>, kind: &str) -> PathBuf {
+/// Used by `ui` tests to generate things like `foo.stderr` from `foo.rs`, or
+/// `foo.nll.stderr` under `--compare-mode=nll`.
+pub fn expected_output_path(testpaths: &TestPaths,
+                            revision: Option<&str>,
+                            compare_mode: &Option<CompareMode>,
+                            kind: &str) -> PathBuf {
     assert!(UI_EXTENSIONS.contains(&kind));
-    let extension = match revision {
-        Some(r) => format!("{}.{}", r, kind),
-        None => kind.to_string(),
-    };
-    testpaths.file.with_extension(extension)
+    let mut parts = Vec::new();
+
+    if let Some(x) = revision {
+        parts.push(x);
+    }
+    if let Some(ref x) = *compare_mode {
+        parts.push(x.to_str());
+    }
+    parts.push(kind);
+
+    testpaths.file.with_extension(parts.join("."))
 }
 
 pub const UI_EXTENSIONS: &[&str] = &[UI_STDERR, UI_STDOUT];
@@ -35,7 +35,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use filetime::FileTime;
 use getopts::Options;
-use common::{Config, TestPaths};
+use common::{CompareMode, Config, TestPaths};
 use common::{DebugInfoGdb, DebugInfoLldb, Mode, Pretty};
 use common::{expected_output_path, UI_EXTENSIONS};
 use test::ColorConfig;
@@ -221,6 +221,12 @@ pub fn parse_config(args: Vec<String>) -> Config {
         )
         .reqopt("", "llvm-cxxflags", "C++ flags for LLVM", "FLAGS")
         .optopt("", "nodejs", "the name of nodejs", "PATH")
+        .optopt(
+            "",
+            "compare-mode",
+            "mode describing what file the actual ui output will be compared to",
+            "COMPARE MODE",
+        )
         .optopt(
             "",
             "remote-test-client",
@@ -294,6 +300,7 @@ pub fn parse_config(args: Vec<String>) -> Config {
             .unwrap()
             .parse()
             .expect("invalid mode"),
+        compare_mode: matches.opt_str("compare-mode").map(CompareMode::parse),
         run_ignored: matches.opt_present("ignored"),
         filter: matches.free.first().cloned(),
         filter_exact: matches.opt_present("exact"),
@@ -632,9 +639,10 @@ pub fn make_test(config: &Config, testpaths: &TestPaths) -> test::TestDescAndFn
 
 fn stamp(config: &Config, testpaths: &TestPaths) -> PathBuf {
     let stamp_name = format!(
-        "{}-{}.stamp",
+        "{}-{}{}.stamp",
         testpaths.file.file_name().unwrap().to_str().unwrap(),
-        config.stage_id
+        config.stage_id,
+        config.compare_mode.map_or(String::new(), |mode| format!("-{}", mode.to_str()))
     );
     config
         .build_base
@@ -686,14 +694,21 @@ fn up_to_date(config: &Config, testpaths: &TestPaths, props: &EarlyProps) -> boo
     }
 
     // UI test files.
+    let mut compare_modes = vec![None];
+    if config.compare_mode.is_some() {
+        compare_modes.push(config.compare_mode);
+    }
     for extension in UI_EXTENSIONS {
-        for revision in &props.revisions {
-            let path = &expected_output_path(testpaths, Some(revision), extension);
-            inputs.push(mtime(path));
-        }
+        for compare_mode in &compare_modes {
+            for revision in &props.revisions {
+                let path = &expected_output_path(testpaths, Some(revision), compare_mode,
+                                                 extension);
+                inputs.push(mtime(path));
+            }
 
-        if props.revisions.is_empty() {
-            let path = &expected_output_path(testpaths, None, extension);
+            // Revisions fall back to the revision-less output, see
+            // `TestCx::expected_output_path`.
+            let path = &expected_output_path(testpaths, None, compare_mode, extension);
             inputs.push(mtime(path));
         }
     }
@@ -714,7 +729,11 @@ pub fn make_test_name(config: &Config, testpaths: &TestPaths) -> test::TestName
     let path = PathBuf::from(config.src_base.file_name().unwrap())
         .join(&testpaths.relative_dir)
         .join(&testpaths.file.file_name().unwrap());
-    test::DynTestName(format!("[{}] {}", config.mode, path.display()))
+    let mode_suffix = match config.compare_mode {
+        Some(ref mode) => format!(" ({})", mode.to_str()),
+        None => String::new(),
+    };
+    test::DynTestName(format!("[{}{}] {}", config.mode, mode_suffix, path.display()))
 }
 
 pub fn make_test_closure(config: &Config, testpaths: &TestPaths) -> test::TestFn {
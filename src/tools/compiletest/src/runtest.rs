@@ -1693,6 +1693,10 @@ impl<'test> TestCx<'test> {
 
         rustc.args(&self.props.compile_flags);
 
+        if let Some(ref compare_mode) = self.config.compare_mode {
+            rustc.args(compare_mode.rustc_flags());
+        }
+
         rustc
     }
 
@@ -1842,8 +1846,16 @@ impl<'test> TestCx<'test> {
         let dir = self.config.build_base.join(&self.testpaths.relative_dir);
 
         // Note: The directory `dir` is created during `collect_tests_from_dir`
-        dir.join(&self.output_testname(&self.testpaths.file))
-            .with_extension(&self.config.stage_id)
+        let testname = self.output_testname(&self.testpaths.file);
+        match self.config.compare_mode {
+            // `foo.nll.stage1-<target>`, so that e.g. the actual stderr is saved
+            // to `foo.nll.stderr`
+            Some(ref mode) => {
+                dir.join(format!("{}.{}.{}", testname.display(), mode.to_str(),
+                                 self.config.stage_id))
+            }
+            None => dir.join(&testname).with_extension(&self.config.stage_id),
+        }
     }
 
     fn maybe_dump_to_stdout(&self, out: &str, err: &str) {
@@ -2514,10 +2526,14 @@ impl<'test> TestCx<'test> {
         let expected_stdout_path = self.expected_output_path(UI_STDOUT);
         let expected_stdout = self.load_expected_output(&expected_stdout_path);
 
-        let normalized_stdout =
-            self.normalize_output(&proc_res.stdout, &self.props.normalize_stdout);
+        let src_dir = self.config.find_rust_src_root().map(|root| root.join("src"));
+
+        let normalized_stdout = self.normalize_output(&proc_res.stdout,
+                                                      src_dir.as_ref().map(|dir| &**dir),
+                                                      &self.props.normalize_stdout);
 
         let normalized_stderr = self.normalize_output(&proc_res.stderr,
+                                                      src_dir.as_ref().map(|dir| &**dir),
                                                       &self.props.normalize_stderr);
 
         let mut errors = 0;
@@ -2550,7 +2566,9 @@ impl<'test> TestCx<'test> {
                 self.fatal_proc_rec("test run failed!", &proc_res);
             }
         }
-        if !explicit {
+        // The `//~` annotations describe the default mode; differences under a
+        // compare mode are only tracked by its expected output files.
+        if !explicit && self.config.compare_mode.is_none() {
             let proc_res = self.compile_test(&["--error-format", "json"]);
             if !expected_errors.is_empty() || !proc_res.status.success() {
                 // "// error-pattern" comments
@@ -2760,21 +2778,29 @@ impl<'test> TestCx<'test> {
         mir_dump_dir
     }
 
-    fn normalize_output(&self, output: &str, custom_rules: &[(String, String)]) -> String {
+    fn normalize_output(&self,
+                        output: &str,
+                        src_dir: Option<&Path>,
+                        custom_rules: &[(String, String)])
+                        -> String {
         let parent_dir = self.testpaths.file.parent().unwrap();
         let cflags = self.props.compile_flags.join(" ");
         let json = cflags.contains("--error-format json")
             || cflags.contains("--error-format pretty-json")
             || cflags.contains("--error-format=json")
             || cflags.contains("--error-format=pretty-json");
-        let parent_dir_str = if json {
-            parent_dir.display().to_string().replace("\\", "\\\\")
-        } else {
-            parent_dir.display().to_string()
-        };
+        let parent_dir_str = path_for_output(parent_dir, json);
 
         let mut normalized = output.replace(&parent_dir_str, "$DIR");
 
+        // Paths into the rest of the source tree (e.g. libcore) and into the
+        // build directory vary between checkouts and hosts.
+        normalized = normalized.replace(&path_for_output(&self.config.build_base, json),
+                                        "$TEST_BUILD_DIR");
+        if let Some(src_dir) = src_dir {
+            normalized = normalized.replace(&path_for_output(src_dir, json), "$SRC_DIR");
+        }
+
         if json {
             // escaped newlines in json strings should be readable
             // in the stderr files. There's no point int being correct,
@@ -2787,6 +2813,11 @@ impl<'test> TestCx<'test> {
               .replace("\\", "/") // normalize for paths on windows
               .replace("\r\n", "\n") // normalize for linebreaks on windows
               .replace("\t", "\\t"); // makes tabs visible
+
+        normalized = SYMBOL_HASH_RE.with(|re| {
+            re.replace_all(&normalized, "::h$$HASH").into_owned()
+        });
+
         for rule in custom_rules {
             let re = Regex::new(&rule.0).expect("bad regex in custom normalization rule");
             normalized = re.replace_all(&normalized, &rule.1[..]).into_owned();
@@ -2794,8 +2825,24 @@ impl<'test> TestCx<'test> {
         normalized
     }
 
+    /// Finds the most specific expected output for the current revision and
+    /// compare mode. A missing `foo.rev.nll.stderr` falls back to
+    /// `foo.rev.stderr`, then `foo.nll.stderr` and finally `foo.stderr`, so
+    /// revisions that produce identical output can share one file.
     fn expected_output_path(&self, kind: &str) -> PathBuf {
-        expected_output_path(&self.testpaths, self.revision, kind)
+        let compare_mode = &self.config.compare_mode;
+        let candidates = [
+            (self.revision, compare_mode),
+            (self.revision, &None),
+            (None, compare_mode),
+        ];
+        for &(revision, mode) in &candidates {
+            let path = expected_output_path(&self.testpaths, revision, mode, kind);
+            if path.exists() {
+                return path;
+            }
+        }
+        expected_output_path(&self.testpaths, None, &None, kind)
     }
 
     fn load_expected_output(&self, path: &Path) -> String {
@@ -2845,7 +2892,14 @@ impl<'test> TestCx<'test> {
             }
         }
 
-        let output_file = self.output_base_name().with_extension(kind);
+        // Name the actual output like the most specific expected file, so that
+        // revisions and compare modes don't overwrite each other's output.
+        let expected_name = expected_output_path(&self.testpaths,
+                                                 self.revision,
+                                                 &self.config.compare_mode,
+                                                 kind);
+        let output_file = self.output_base_name()
+            .with_file_name(expected_name.file_name().unwrap());
         match File::create(&output_file).and_then(|mut f| f.write_all(actual.as_bytes())) {
             Ok(()) => {}
             Err(e) => self.fatal(&format!(
@@ -2862,6 +2916,22 @@ impl<'test> TestCx<'test> {
     }
 }
 
+thread_local! {
+    // Symbol hashes change whenever the compiler or the crate metadata does.
+    static SYMBOL_HASH_RE: Regex = Regex::new(r"::h[0-9a-f]{16}").unwrap();
+}
+
+/// Renders `path` the way it appears in compiler output, escaping backslashes
+/// when the output is JSON.
+fn path_for_output(path: &Path, json: bool) -> String {
+    let path = path.display().to_string();
+    if json {
+        path.replace("\\", "\\\\")
+    } else {
+        path
+    }
+}
+
 struct ProcArgs {
     prog: String,
     args: Vec<String>,